@@ -129,6 +129,7 @@ mod forms;
 mod polygon;
 mod popup;
 mod state;
+mod terrain;
 mod windowing;
 
 pub(crate) use drawing::*;
@@ -136,6 +137,7 @@ pub(crate) use filesystem::*;
 pub(crate) use forms::*;
 pub(crate) use popup::*;
 pub(crate) use state::*;
+pub(crate) use terrain::*;
 pub(crate) use windowing::*;
 
 pub struct RengineNativeEditor {
@@ -200,6 +202,14 @@ pub struct RengineNativeEditor {
     /// because a half-drawn shape belongs to the gesture, not to the document —
     /// switching tabs mid-draw should abandon it, not carry it over.
     polygon_draft: Option<Vec<[f32; 2]>>,
+    /// The armed terrain brush, if any; see `terrain.rs`.
+    terrain_brush: Option<TerrainBrush>,
+    /// Every terrain node's layer in the active tab, tiled by the engine's
+    /// `Autotiler`. Rebuilt only when `terrain_layers_key` goes stale.
+    terrain_layers: Vec<TerrainLayer>,
+    /// `(SceneTab::tab_id, edit_revision, loaded_sheet_count)`, as for
+    /// `ui_preview_key`.
+    terrain_layers_key: Option<(u64, u64, usize)>,
     /// The same fonts as real ids, for measuring authored line heights.
     preview_fonts: BTreeMap<String, FontId>,
     /// Which manifest `preview_bindings` came from, so a scene opened from a
@@ -227,6 +237,9 @@ impl Game for RengineNativeEditor {
         let mut editor = Self {
             preview_bindings,
             polygon_draft: None,
+            terrain_brush: None,
+            terrain_layers: Vec::new(),
+            terrain_layers_key: None,
             preview_fonts,
             preview_manifest_path: project_file.clone(),
             workspace_root,
@@ -334,6 +347,7 @@ impl Game for RengineNativeEditor {
                 self.cancel_polygon();
             }
         }
+        self.update_terrain_brush(engine, &layout);
         if !self.ui_has_focus() && engine.input().is_key_pressed(KeyCode::KeyF) {
             self.frame_active_scene_view();
         }
@@ -45,6 +45,7 @@ impl RengineNativeEditor {
         // `&mut self`, and `draw_viewport` below only gets `&self` once the
         // canvas borrow is live.
         self.rebuild_ui_preview_if_needed(engine);
+        self.rebuild_terrain_layers_if_needed(engine);
 
         let canvas = frame.canvas(0);
 
@@ -1007,9 +1008,12 @@ impl RengineNativeEditor {
             // fill and labels for it, but keep the selection outline — that's
             // the one thing the preview draw can't show.
             let ui_authored = self.ui_preview.is_some() && is_ui_authored_node(node);
+            let terrain = self.terrain_layer(node.id);
 
             if !ui_authored {
-                if let Some(texture) = sprite_texture {
+                if let Some(layer) = terrain {
+                    draw_terrain_layer(canvas, layer, rect, zoom);
+                } else if let Some(texture) = sprite_texture {
                     canvas.rect(
                         rect.x,
                         rect.y,
@@ -1037,7 +1041,7 @@ impl RengineNativeEditor {
 
             if ui_authored {
                 // Preview already drew this node; nothing more to paint.
-            } else if sprite_texture.is_none() {
+            } else if sprite_texture.is_none() && terrain.is_none() {
                 canvas.text_aligned(
                     rect.x + rect.w * 0.5,
                     text_baseline_in_rect(
@@ -1086,6 +1090,8 @@ impl RengineNativeEditor {
             }
         }
 
+        self.draw_terrain_brush(engine, canvas, viewport);
+
         if self.active_scene_tab().scene.nodes.is_empty() {
             canvas.text_aligned(
                 viewport.center().x,
@@ -1617,7 +1623,7 @@ fn draw_resize_handle(canvas: &mut Canvas, rect: PanelRect) {
     }
 }
 
-fn draw_outline(canvas: &mut Canvas, rect: PanelRect, color: Color) {
+pub(crate) fn draw_outline(canvas: &mut Canvas, rect: PanelRect, color: Color) {
     canvas.line(rect.x, rect.y, rect.x + rect.w, rect.y, 1.0, color);
     canvas.line(
         rect.x + rect.w,
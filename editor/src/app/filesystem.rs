@@ -496,7 +496,7 @@ impl RengineNativeEditor {
             .scene
             .nodes
             .iter()
-            .filter(|node| node.kind == SceneNodeKind::Sprite)
+            .filter(|node| node.kind == SceneNodeKind::Sprite || is_terrain_node(node))
         {
             let stored_path = node.asset_alias.trim();
             if stored_path.is_empty() {
@@ -24,6 +24,7 @@ impl RengineNativeEditor {
     /// Arm the tool. Clicks in the viewport now place points instead of
     /// selecting nodes.
     pub(crate) fn begin_polygon(&mut self) {
+        self.terrain_brush = None;
         self.polygon_draft = Some(Vec::new());
         self.push_log(
             "Polygon: click to place points, Enter to finish, Esc to cancel".to_string(),
@@ -47,6 +47,10 @@ pub(crate) enum PopupMenuAction {
     /// to put something into a scene, and because a letter shortcut is dead
     /// whenever the inspector holds focus — which is most of the time.
     BeginPolygon,
+    /// Arm the terrain brush on the selected node. On the Add menu beside the
+    /// polygon tool; right-clicking a terrain node selects it, so the menu
+    /// opened there paints that node.
+    BeginTerrainBrush,
     AddNode {
         kind: SceneNodeKind,
         parent: Option<u64>,
@@ -132,7 +136,10 @@ impl RengineNativeEditor {
                     parent: *parent,
                     position: *position,
                 })
-                .chain(std::iter::once(PopupMenuAction::BeginPolygon))
+                .chain([
+                    PopupMenuAction::BeginPolygon,
+                    PopupMenuAction::BeginTerrainBrush,
+                ])
                 .collect(),
             PopupMenuKind::ChangeNodeKind { node_id } => NODE_KIND_OPTIONS
                 .into_iter()
@@ -181,6 +188,7 @@ impl RengineNativeEditor {
             }
             PopupMenuAction::ThemeSet { theme } => format!("{} Theme", theme.label()),
             PopupMenuAction::BeginPolygon => "Draw Polygon".to_string(),
+            PopupMenuAction::BeginTerrainBrush => "Paint Terrain".to_string(),
             PopupMenuAction::AddNode {
                 kind,
                 parent,
@@ -207,6 +215,7 @@ impl RengineNativeEditor {
             // Ticked while the tool is collecting points, so the menu shows
             // that the viewport is in a mode.
             PopupMenuAction::BeginPolygon => self.polygon_tool_active(),
+            PopupMenuAction::BeginTerrainBrush => self.terrain_brush_active(),
             PopupMenuAction::ViewTogglePanel { panel } => self.panel_state(*panel).open,
             PopupMenuAction::ChangeNodeKind { node_id, kind } => self
                 .active_scene_tab()
@@ -259,6 +268,7 @@ impl RengineNativeEditor {
                 }
             }
             PopupMenuAction::BeginPolygon => self.begin_polygon(),
+            PopupMenuAction::BeginTerrainBrush => self.begin_terrain_brush(),
             PopupMenuAction::AddNode {
                 kind,
                 parent,
@@ -343,9 +353,9 @@ pub(crate) fn popup_menu_item_count(kind: &PopupMenuKind) -> usize {
         PopupMenuKind::FileMenu => 8,
         PopupMenuKind::ViewMenu => 5,
         PopupMenuKind::ThemeMenu => EditorTheme::all().len(),
-        PopupMenuKind::AddNode { .. } | PopupMenuKind::ChangeNodeKind { .. } => {
-            NODE_KIND_OPTIONS.len()
-        }
+        // The node kinds, then the polygon and terrain tools.
+        PopupMenuKind::AddNode { .. } => NODE_KIND_OPTIONS.len() + 2,
+        PopupMenuKind::ChangeNodeKind { .. } => NODE_KIND_OPTIONS.len(),
         PopupMenuKind::ProjectEntry { path } => {
             if path.is_file() && is_scene_path(path) {
                 3
@@ -0,0 +1,544 @@
+//! The terrain brush: paint autotiled terrain onto a node in the viewport.
+//!
+//! A node becomes a terrain layer by carrying an `autotile` property — the
+//! path of a rules file, the same JSON a manifest sprite sheet names with its
+//! own `autotile` field — with its `asset_alias` pointing at the sheet image
+//! and `autotile_tile` giving the sheet's square tile size. The node's box is
+//! the grid: one cell per tile, bottom-left first, y-up like `TileMap`.
+//!
+//! Painting goes through the engine's own `Autotiler::set_terrain` rather than
+//! a copy of its rules, and the result is stored in `autotile_terrain` as
+//! `Autotiler::terrain_text`. A game rebuilds the layer from the node with
+//! `Autotiler::paint_text` and `apply`, so it gets exactly the tiles the
+//! viewport showed.
+
+use std::sync::Arc;
+
+use super::*;
+
+pub(crate) const AUTOTILE_RULES_PROP: &str = "autotile";
+pub(crate) const AUTOTILE_TILE_PROP: &str = "autotile_tile";
+pub(crate) const AUTOTILE_TERRAIN_PROP: &str = "autotile_terrain";
+
+/// Tile size when a node names rules but not `autotile_tile`.
+const DEFAULT_TILE_SIZE: u32 = 16;
+
+/// One terrain node's grid, painted and tiled, ready to draw.
+pub(crate) struct TerrainLayer {
+    node_id: u64,
+    tiler: Autotiler,
+    map: TileMap,
+    /// The sheet, once its texture has loaded. Until then cells draw as flat
+    /// per-terrain colours, so painting works before the image shows up.
+    texture: Option<TextureId>,
+}
+
+/// The armed brush. Held on the editor rather than the tab for the same
+/// reason as the polygon draft: it belongs to the gesture, not the document.
+pub(crate) struct TerrainBrush {
+    node_id: u64,
+    /// Index into the layer's rules of the terrain being painted.
+    terrain: usize,
+    /// The drag in progress, from press to release.
+    stroke: Option<TerrainStroke>,
+}
+
+struct TerrainStroke {
+    /// The cell last painted, so holding still does not repaint it.
+    last_cell: Option<(usize, usize)>,
+    /// The scene before the stroke, pushed to undo once it changes anything,
+    /// so one drag is one undo step and a click on a cell that already holds
+    /// the terrain leaves no empty entry behind.
+    undo: Option<SceneHistoryEntry>,
+}
+
+impl RengineNativeEditor {
+    /// Whether viewport clicks are currently painting terrain.
+    pub(crate) fn terrain_brush_active(&self) -> bool {
+        self.terrain_brush.is_some()
+    }
+
+    /// Arm the brush on the selected node, if it is a terrain layer.
+    pub(crate) fn begin_terrain_brush(&mut self) {
+        let Some(node_id) = self.active_scene_tab().selected_node else {
+            self.push_log("Terrain: select a node with an `autotile` property first".to_string());
+            return;
+        };
+        let Some(layer) = self.terrain_layer(node_id) else {
+            self.push_log(format!(
+                "Terrain: the selected node needs an `{AUTOTILE_RULES_PROP}` property naming \
+                 a rules file that loads"
+            ));
+            return;
+        };
+        let name = terrain_name(layer, 0);
+        self.polygon_draft = None;
+        self.terrain_brush = Some(TerrainBrush {
+            node_id,
+            terrain: 0,
+            stroke: None,
+        });
+        self.push_log(format!(
+            "Terrain: drag to paint {name}, Shift to erase, [ and ] to switch terrain, Esc to finish"
+        ));
+    }
+
+    pub(crate) fn end_terrain_brush(&mut self) {
+        if self.terrain_brush.take().is_some() {
+            self.push_log("Terrain brush put away".to_string());
+        }
+    }
+
+    /// Start a stroke. Called by the viewport press handler while armed.
+    pub(crate) fn press_terrain_brush(&mut self, engine: &Engine, scene_point: [f32; 2]) {
+        let history_entry = SceneHistoryEntry::capture(self.active_scene_tab());
+        if let Some(brush) = self.terrain_brush.as_mut() {
+            brush.stroke = Some(TerrainStroke {
+                last_cell: None,
+                undo: Some(history_entry),
+            });
+        }
+        self.paint_terrain_at(scene_point, erase_down(engine));
+    }
+
+    /// The brush's keys and the rest of a drag, once per frame.
+    pub(crate) fn update_terrain_brush(&mut self, engine: &Engine, layout: &ShellLayout) {
+        let Some(brush) = self.terrain_brush.as_ref() else {
+            return;
+        };
+        let node_id = brush.node_id;
+        let stroking = brush.stroke.is_some();
+        let Some(count) = self
+            .terrain_layer(node_id)
+            .map(|layer| layer.tiler.rules().terrains.len())
+        else {
+            // The node went away — deleted, or its tab closed.
+            self.end_terrain_brush();
+            return;
+        };
+
+        let input = engine.input();
+        if input.is_key_pressed(KeyCode::Escape) || input.is_key_pressed(KeyCode::Enter) {
+            self.end_terrain_brush();
+            return;
+        }
+        let step = if input.is_key_pressed(KeyCode::BracketRight) {
+            1
+        } else if input.is_key_pressed(KeyCode::BracketLeft) {
+            count.saturating_sub(1)
+        } else {
+            0
+        };
+        if step != 0 && count > 0 {
+            let terrain = self
+                .terrain_brush
+                .as_ref()
+                .map_or(0, |brush| (brush.terrain + step) % count);
+            if let Some(brush) = self.terrain_brush.as_mut() {
+                brush.terrain = terrain;
+            }
+            let name = self
+                .terrain_layer(node_id)
+                .map(|layer| terrain_name(layer, terrain))
+                .unwrap_or_default();
+            self.push_log(format!("Terrain: painting {name}"));
+        }
+
+        if !stroking {
+            return;
+        }
+        if !input.is_mouse_down(0) {
+            if let Some(brush) = self.terrain_brush.as_mut() {
+                brush.stroke = None;
+            }
+            return;
+        }
+        let mouse = engine.mouse_screen_pos();
+        if layout.viewport.contains(mouse) {
+            let scene_point = screen_to_scene_zoomed(
+                mouse,
+                layout.viewport,
+                self.active_scene_tab().viewport_pan,
+                self.viewport_zoom(layout.viewport),
+            );
+            self.paint_terrain_at(scene_point, erase_down(engine));
+        }
+    }
+
+    /// Paint (or erase) the brush's terrain into the cell under `scene_point`
+    /// and store the layer back on its node.
+    fn paint_terrain_at(&mut self, scene_point: [f32; 2], erase: bool) {
+        let Some(brush) = self.terrain_brush.as_ref() else {
+            return;
+        };
+        let (node_id, terrain) = (brush.node_id, brush.terrain);
+        let last_cell = brush.stroke.as_ref().and_then(|stroke| stroke.last_cell);
+        let Some(node) = self.active_scene_tab().scene.node(node_id) else {
+            return;
+        };
+        let Some(cell) = terrain_cell_at(node, scene_point) else {
+            return;
+        };
+        if last_cell == Some(cell) {
+            return;
+        }
+        if let Some(stroke) = self
+            .terrain_brush
+            .as_mut()
+            .and_then(|brush| brush.stroke.as_mut())
+        {
+            stroke.last_cell = Some(cell);
+        }
+
+        let Some(layer) = self
+            .terrain_layers
+            .iter_mut()
+            .find(|layer| layer.node_id == node_id)
+        else {
+            return;
+        };
+        let name = terrain_name(layer, terrain);
+        let paint = (!erase).then_some(name.as_str());
+        if layer.tiler.terrain_at(cell.0, cell.1) == paint {
+            return;
+        }
+        layer
+            .tiler
+            .set_terrain(&mut layer.map, cell.0, cell.1, paint);
+        let text = layer.tiler.terrain_text();
+
+        let undo = self
+            .terrain_brush
+            .as_mut()
+            .and_then(|brush| brush.stroke.as_mut())
+            .and_then(|stroke| stroke.undo.take());
+        let tab = self.active_scene_tab_mut();
+        if let Some(node) = tab.scene.node_mut(node_id) {
+            node.properties
+                .insert(AUTOTILE_TERRAIN_PROP.to_string(), text);
+        }
+        tab.mark_dirty();
+        if let Some(entry) = undo {
+            tab.push_undo_entry(entry);
+        }
+        // The layer already holds this edit, so move its cache key along with
+        // the revision rather than reparsing the rules file for every cell a
+        // drag crosses.
+        let revision = tab.edit_revision;
+        if let Some(key) = self.terrain_layers_key.as_mut() {
+            key.1 = revision;
+        }
+    }
+
+    pub(crate) fn terrain_layer(&self, node_id: u64) -> Option<&TerrainLayer> {
+        self.terrain_layers
+            .iter()
+            .find(|layer| layer.node_id == node_id)
+    }
+
+    /// Rebuild every terrain node's layer when the scene changes or a sheet
+    /// texture finishes loading. Same keying as the ui preview, with the count
+    /// of loaded sheets standing in for its resolved-texture count.
+    pub(crate) fn rebuild_terrain_layers_if_needed(&mut self, engine: &Engine) {
+        let sheets_loaded = self
+            .active_scene_tab()
+            .scene
+            .nodes
+            .iter()
+            .filter(|node| is_terrain_node(node) && self.terrain_sheet(engine, node).is_some())
+            .count();
+        let key = (
+            self.active_scene_tab().tab_id,
+            self.active_scene_tab().edit_revision,
+            sheets_loaded,
+        );
+        if self.terrain_layers_key == Some(key) {
+            return;
+        }
+        self.terrain_layers_key = Some(key);
+
+        let mut layers = Vec::new();
+        let mut problems = Vec::new();
+        for node in self
+            .active_scene_tab()
+            .scene
+            .nodes
+            .iter()
+            .filter(|node| is_terrain_node(node))
+        {
+            match self.build_terrain_layer(engine, node) {
+                Ok(layer) => layers.push(layer),
+                Err(error) => problems.push(format!("Terrain: {}: {error}", node.name)),
+            }
+        }
+        self.terrain_layers = layers;
+        for problem in problems {
+            self.push_log(problem);
+        }
+    }
+
+    fn build_terrain_layer(
+        &self,
+        engine: &Engine,
+        node: &SceneNode,
+    ) -> Result<TerrainLayer, String> {
+        let rules_path = self.resolve_stored_path(node.properties[AUTOTILE_RULES_PROP].trim());
+        let text = fs::read_to_string(&rules_path)
+            .map_err(|error| format!("{}: {error}", self.display_path(&rules_path)))?;
+        let rules = AutotileRules::parse(&rules_path, &text).map_err(|error| error.to_string())?;
+        let tile = terrain_tile_size(node);
+        let (cols, rows) = terrain_grid_size(node);
+        let loaded = self.terrain_sheet(engine, node);
+        let sheet = match &loaded {
+            Some(asset) => SpriteSheet::new(asset.id, asset.width, asset.height, tile, tile),
+            None => SpriteSheet::new(engine.white_texture(), tile, tile, tile, tile),
+        };
+        let mut map = TileMap::new(cols, rows, tile as f32);
+        let mut tiler =
+            Autotiler::new(Arc::new(rules), &sheet, cols, rows, |def| map.add_tile(def));
+        if let Some(painted) = node.properties.get(AUTOTILE_TERRAIN_PROP) {
+            tiler.paint_text(painted);
+        }
+        tiler.apply(&mut map);
+        Ok(TerrainLayer {
+            node_id: node.id,
+            tiler,
+            map,
+            texture: loaded.map(|asset| asset.id),
+        })
+    }
+
+    /// The node's sheet image, if it has loaded. Requested alongside sprite
+    /// previews in `request_sprite_previews`.
+    fn terrain_sheet(&self, engine: &Engine, node: &SceneNode) -> Option<TextureAsset> {
+        let alias = node.asset_alias.trim();
+        if alias.is_empty() {
+            return None;
+        }
+        engine.loaded_texture(self.resolve_stored_path(alias))
+    }
+
+    /// While armed: the cell under the cursor and what the brush will do to it.
+    pub(crate) fn draw_terrain_brush(
+        &self,
+        engine: &Engine,
+        canvas: &mut Canvas,
+        viewport: PanelRect,
+    ) {
+        let Some(brush) = self.terrain_brush.as_ref() else {
+            return;
+        };
+        let Some(layer) = self.terrain_layer(brush.node_id) else {
+            return;
+        };
+        let Some(node) = self.active_scene_tab().scene.node(brush.node_id) else {
+            return;
+        };
+        let erase = erase_down(engine);
+        let label = if erase {
+            "Terrain: erase".to_string()
+        } else {
+            format!("Terrain: {}", terrain_name(layer, brush.terrain))
+        };
+        canvas.text(
+            viewport.x + 10.0,
+            viewport.top() - 44.0,
+            &label,
+            12.0,
+            Color::from_rgba8(232, 168, 72, 235),
+        );
+
+        let mouse = engine.mouse_screen_pos();
+        if !viewport.contains(mouse) {
+            return;
+        }
+        let pan = self.active_scene_tab().viewport_pan;
+        let zoom = self.viewport_zoom(viewport);
+        let Some((col, row)) =
+            terrain_cell_at(node, screen_to_scene_zoomed(mouse, viewport, pan, zoom))
+        else {
+            return;
+        };
+        let tile = terrain_tile_size(node) as f32;
+        let origin = terrain_origin(node);
+        let corner = scene_to_screen_zoomed(
+            [origin[0] + col as f32 * tile, origin[1] + row as f32 * tile],
+            viewport,
+            pan,
+            zoom,
+        );
+        draw_outline(
+            canvas,
+            PanelRect::new(corner.x, corner.y, tile * zoom, tile * zoom),
+            if erase {
+                Color::from_rgba8(224, 96, 96, 235)
+            } else {
+                Color::from_rgba8(232, 168, 72, 235)
+            },
+        );
+    }
+}
+
+/// The layer's tiles over the node's box, `rect` in screen space.
+pub(crate) fn draw_terrain_layer(
+    canvas: &mut Canvas,
+    layer: &TerrainLayer,
+    rect: PanelRect,
+    zoom: f32,
+) {
+    canvas.rect(
+        rect.x,
+        rect.y,
+        rect.w,
+        rect.h,
+        Color::from_rgba8(20, 26, 32, 160),
+    );
+    let size = layer.map.tile_size * zoom;
+    for row in 0..layer.map.height {
+        for col in 0..layer.map.width {
+            let (x, y) = (rect.x + col as f32 * size, rect.y + row as f32 * size);
+            match (layer.texture, layer.map.get(col, row)) {
+                (Some(texture), Some(tile)) => {
+                    if let Some(def) = layer.map.tile(tile) {
+                        canvas.image_region(texture, x, y, size, size, def.uv_rect, Color::WHITE);
+                    }
+                }
+                _ => {
+                    let terrain = layer
+                        .tiler
+                        .terrain_at(col, row)
+                        .and_then(|name| layer.tiler.rules().terrain(name));
+                    if let Some(terrain) = terrain {
+                        canvas.rect(x, y, size, size, terrain_color(terrain));
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn is_terrain_node(node: &SceneNode) -> bool {
+    node.properties
+        .get(AUTOTILE_RULES_PROP)
+        .is_some_and(|path| !path.trim().is_empty())
+}
+
+fn erase_down(engine: &Engine) -> bool {
+    let input = engine.input();
+    input.is_key_down(KeyCode::ShiftLeft) || input.is_key_down(KeyCode::ShiftRight)
+}
+
+fn terrain_name(layer: &TerrainLayer, terrain: usize) -> String {
+    layer
+        .tiler
+        .rules()
+        .terrains
+        .get(terrain)
+        .map(|rule| rule.name.clone())
+        .unwrap_or_default()
+}
+
+/// A stand-in colour per terrain for cells whose sheet has not loaded.
+fn terrain_color(terrain: usize) -> Color {
+    const COLORS: [(u8, u8, u8); 4] = [
+        (150, 132, 104),
+        (82, 142, 196),
+        (104, 168, 96),
+        (180, 108, 150),
+    ];
+    let (r, g, b) = COLORS[terrain % COLORS.len()];
+    Color::from_rgba8(r, g, b, 220)
+}
+
+fn terrain_tile_size(node: &SceneNode) -> u32 {
+    node.properties
+        .get(AUTOTILE_TILE_PROP)
+        .and_then(|size| size.trim().parse::<u32>().ok())
+        .filter(|&size| size > 0)
+        .unwrap_or(DEFAULT_TILE_SIZE)
+}
+
+/// Columns and rows of whole tiles that fit in the node's box; at least one
+/// of each, so a freshly added node still has a cell to paint.
+fn terrain_grid_size(node: &SceneNode) -> (usize, usize) {
+    let tile = terrain_tile_size(node) as f32;
+    let fit = |extent: f32| ((extent / tile).floor() as usize).max(1);
+    (fit(node.size[0]), fit(node.size[1]))
+}
+
+/// The scene position of the grid's bottom-left corner. Node positions are
+/// box centres.
+fn terrain_origin(node: &SceneNode) -> [f32; 2] {
+    [
+        node.position[0] - node.size[0] * 0.5,
+        node.position[1] - node.size[1] * 0.5,
+    ]
+}
+
+/// The grid cell under a scene point, if it is inside the grid.
+fn terrain_cell_at(node: &SceneNode, scene_point: [f32; 2]) -> Option<(usize, usize)> {
+    let tile = terrain_tile_size(node) as f32;
+    let origin = terrain_origin(node);
+    let (cols, rows) = terrain_grid_size(node);
+    let col = ((scene_point[0] - origin[0]) / tile).floor();
+    let row = ((scene_point[1] - origin[1]) / tile).floor();
+    (col >= 0.0 && row >= 0.0 && (col as usize) < cols && (row as usize) < rows)
+        .then_some((col as usize, row as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn terrain_node(position: [f32; 2], size: [f32; 2], tile: Option<&str>) -> SceneNode {
+        let mut properties = HashMap::new();
+        properties.insert(
+            AUTOTILE_RULES_PROP.to_string(),
+            "cave.autotile.json".to_string(),
+        );
+        if let Some(tile) = tile {
+            properties.insert(AUTOTILE_TILE_PROP.to_string(), tile.to_string());
+        }
+        SceneNode {
+            id: 1,
+            parent: None,
+            name: "cave".to_string(),
+            kind: SceneNodeKind::Empty,
+            position,
+            size,
+            visible: true,
+            script_path: String::new(),
+            runtime_prefab: String::new(),
+            asset_alias: "terrain.png".to_string(),
+            properties,
+        }
+    }
+
+    /// The grid is the node's box cut into whole tiles, bottom-left first —
+    /// the same y-up layout as the `TileMap` a game rebuilds from it.
+    #[test]
+    fn clicks_land_in_the_cell_drawn_under_them() {
+        // 100x40 box centred on (50, 20): origin (0, 0), 6 x 2 cells of 16.
+        let node = terrain_node([50.0, 20.0], [100.0, 40.0], Some("16"));
+        assert_eq!(terrain_grid_size(&node), (6, 2));
+        assert_eq!(terrain_cell_at(&node, [0.5, 0.5]), Some((0, 0)));
+        assert_eq!(terrain_cell_at(&node, [17.0, 31.0]), Some((1, 1)));
+        assert_eq!(terrain_cell_at(&node, [95.0, 5.0]), Some((5, 0)));
+        // Past the last whole tile, or outside the box, paints nothing.
+        assert_eq!(terrain_cell_at(&node, [97.0, 5.0]), None);
+        assert_eq!(terrain_cell_at(&node, [-1.0, 5.0]), None);
+        assert_eq!(terrain_cell_at(&node, [5.0, 33.0]), None);
+    }
+
+    #[test]
+    fn a_missing_or_bad_tile_size_falls_back_and_small_boxes_keep_a_cell() {
+        let node = terrain_node([0.0, 0.0], [8.0, 8.0], None);
+        assert_eq!(terrain_tile_size(&node), DEFAULT_TILE_SIZE);
+        assert_eq!(terrain_grid_size(&node), (1, 1));
+        let node = terrain_node([0.0, 0.0], [64.0, 64.0], Some("zero"));
+        assert_eq!(terrain_tile_size(&node), DEFAULT_TILE_SIZE);
+        assert!(is_terrain_node(&node));
+    }
+}
@@ -1044,6 +1044,17 @@ impl RengineNativeEditor {
             self.push_polygon_point(scene_point);
             return;
         }
+        // Likewise the terrain brush: a click paints rather than selects.
+        if self.terrain_brush_active() {
+            let scene_point = screen_to_scene_zoomed(
+                mouse,
+                layout.viewport,
+                self.active_scene_tab().viewport_pan,
+                self.viewport_zoom(layout.viewport),
+            );
+            self.press_terrain_brush(engine, scene_point);
+            return;
+        }
 
         let toolbar_rect = viewport_toolbar_rect(layout.viewport);
         if toolbar_rect.contains(mouse) {
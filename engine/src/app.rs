@@ -13,7 +13,7 @@ use winit::window::{CursorGrabMode, WindowBuilder};
 
 use crate::assets::{
     AssetBundle, AssetError, AssetPack, AssetPipeline, AudioBus, AudioClip, AudioSystem, Color,
-    MeshAsset, SpriteSheet, TextureAsset,
+    LdtkProjectAsset, MeshAsset, SpriteSheet, TextureAsset, TiledMapAsset,
 };
 use crate::canvas;
 use crate::debug::{self, DebugCommand, DebugLogLevel, DebugOverlayInfo, DebugUiState};
@@ -21,10 +21,11 @@ use crate::input::{ActionMap, GamepadAssignMode, GamepadSystem, InputState};
 use crate::math::tween::Easing;
 use crate::math::{Rng, TimeState};
 use crate::renderer::postfx::PostFxChain;
-use crate::renderer::{Frame, RenderTarget, Renderer, TextureId};
+use crate::renderer::{AtlasRegion, Frame, RenderTarget, Renderer, TextureId};
 use crate::renderer3d::{Frame3D, MeshId, Renderer3D, Vertex3D};
 use crate::scene::{Globals, Scene, Scene2D, Scene3D, SceneOp, SceneOp3D};
 use crate::text;
+use crate::world::autotile::AutotileRules;
 
 fn handle_text_event(input: &mut InputState, event: &KeyEvent) {
     if event.state != winit::event::ElementState::Pressed {
@@ -163,6 +164,8 @@ fn evict_released_asset_paths(
         assets.unload_texture(&path);
         assets.unload_mesh(&path);
         assets.unload_data(&path);
+        assets.unload_tiled_map(&path);
+        assets.unload_ldtk_project(&path);
         assets.unload_manifest(&path);
     }
 }
@@ -527,6 +530,7 @@ pub struct Engine {
     pub(crate) actions: ActionMap,
     pub(crate) rng: RefCell<Rng>,
     pub(crate) postfx_chain: PostFxChain,
+    markup_assets: canvas::MarkupAssets,
     pending_texture_requests: RefCell<Vec<PathBuf>>,
     failed_texture_requests: RefCell<HashMap<PathBuf, f32>>,
     pub(crate) window: Arc<Window>,
@@ -841,6 +845,24 @@ impl Engine {
         self.renderer.create_texture(width, height, pixels)
     }
 
+    /// Create a texture inside a shared atlas page. Sprites drawn from
+    /// textures on the same page batch into one draw call; the returned id
+    /// draws exactly like one from [`create_texture`](Self::create_texture).
+    /// Images over 1024px on a side get a texture of their own.
+    pub fn create_packed_texture(&mut self, width: u32, height: u32, pixels: &[u8]) -> TextureId {
+        self.renderer.create_packed_texture(width, height, pixels)
+    }
+
+    /// The atlas page and sub-rect `texture` draws from, if it was packed.
+    pub fn atlas_region(&self, texture: TextureId) -> Option<AtlasRegion> {
+        self.renderer.atlas_region(texture)
+    }
+
+    /// How many atlas pages packed textures have filled so far.
+    pub fn atlas_page_count(&self) -> usize {
+        self.renderer.atlas_page_count()
+    }
+
     pub fn create_render_target(&mut self, width: u32, height: u32) -> RenderTarget {
         self.renderer.create_render_target(width, height)
     }
@@ -911,14 +933,31 @@ impl Engine {
         for (alias, rel_path) in manifest.textures {
             let resolved = self.assets.resolve_path(Path::new(&rel_path));
             deps.push(resolved);
-            pack.insert_texture(alias, self.load_texture(rel_path)?);
+            let texture = if manifest.atlas.contains(&alias) {
+                self.load_packed_texture(rel_path)?
+            } else {
+                self.load_texture(rel_path)?
+            };
+            pack.insert_texture(alias, texture);
         }
         for (alias, sheet) in manifest.sprite_sheets {
             let resolved = self.assets.resolve_path(Path::new(&sheet.path));
             deps.push(resolved);
+            if let Some(rules_path) = &sheet.autotile {
+                let resolved = self.assets.resolve_path(Path::new(rules_path));
+                let text = self.assets.load_text(&resolved)?;
+                let rules = AutotileRules::parse(&resolved, &text)?;
+                deps.push(resolved);
+                pack.insert_autotile_rules(alias.clone(), Arc::new(rules));
+            }
+            let packed = manifest.atlas.contains(&alias);
             pack.insert_sprite_sheet(
                 alias,
-                self.load_sprite_sheet(sheet.path, sheet.cell_width, sheet.cell_height)?,
+                if packed {
+                    self.load_packed_sprite_sheet(sheet.path, sheet.cell_width, sheet.cell_height)?
+                } else {
+                    self.load_sprite_sheet(sheet.path, sheet.cell_width, sheet.cell_height)?
+                },
             );
         }
         for (alias, rel_path) in manifest.audio {
@@ -926,12 +965,23 @@ impl Engine {
             deps.push(resolved);
             pack.insert_audio(alias, self.load_audio(rel_path)?);
         }
+        for (alias, rel_path) in manifest.tiled_maps {
+            let map = self.load_tiled_map(rel_path)?;
+            deps.extend(map.dependencies().iter().cloned());
+            pack.insert_tiled_map(alias, map);
+        }
+        for (alias, rel_path) in manifest.ldtk_projects {
+            let project = self.load_ldtk_project(rel_path)?;
+            deps.extend(project.dependencies().iter().cloned());
+            pack.insert_ldtk_project(alias, project);
+        }
         if !manifest.meshes.is_empty() {
             return Err(AssetError::manifest_message(
                 self.assets.root(),
                 "2D Engine manifest cannot load mesh entries; use Engine3D instead",
             ));
         }
+        self.markup_assets.add_pack(&pack);
 
         Ok((pack, deps))
     }
@@ -988,6 +1038,57 @@ impl Engine {
         })
     }
 
+    /// [`load_texture`](Self::load_texture) into a shared atlas page; see
+    /// [`create_packed_texture`](Self::create_packed_texture). A path that is
+    /// already loaded comes back as it was first loaded, packed or not.
+    pub fn load_packed_texture<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<TextureAsset, AssetError> {
+        let renderer = &mut self.renderer;
+        self.assets
+            .load_packed_texture(path, |width, height, pixels| {
+                let id = renderer.create_packed_texture(width, height, pixels);
+                (id, renderer.atlas_region(id))
+            })
+    }
+
+    /// Load a Tiled map (`.tmj` or `.tmx`) with its tilesets' images.
+    pub fn load_tiled_map<P: AsRef<Path>>(&mut self, path: P) -> Result<TiledMapAsset, AssetError> {
+        self.assets.load_tiled_map(path, |width, height, pixels| {
+            self.renderer.create_texture(width, height, pixels)
+        })
+    }
+
+    /// A newer version of `map` if hot reload has re-read it since `map` was
+    /// loaded. Rebuild the level from the returned asset.
+    pub fn reloaded_tiled_map(&self, map: &TiledMapAsset) -> Option<TiledMapAsset> {
+        self.assets
+            .loaded_tiled_map(&map.path)
+            .filter(|latest| latest.revision > map.revision)
+            .cloned()
+    }
+
+    /// Load an LDtk project (`.ldtk`) with its level files and tileset images.
+    pub fn load_ldtk_project<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<LdtkProjectAsset, AssetError> {
+        self.assets
+            .load_ldtk_project(path, |width, height, pixels| {
+                self.renderer.create_texture(width, height, pixels)
+            })
+    }
+
+    /// A newer version of `project` if hot reload has re-read it since
+    /// `project` was loaded. Rebuild loaded levels from the returned asset.
+    pub fn reloaded_ldtk_project(&self, project: &LdtkProjectAsset) -> Option<LdtkProjectAsset> {
+        self.assets
+            .loaded_ldtk_project(&project.path)
+            .filter(|latest| latest.revision > project.revision)
+            .cloned()
+    }
+
     pub fn load_sprite_sheet<P: AsRef<Path>>(
         &mut self,
         path: P,
@@ -1000,6 +1101,27 @@ impl Engine {
             })
     }
 
+    /// [`load_sprite_sheet`](Self::load_sprite_sheet) with the sheet's image
+    /// packed into a shared atlas page. Cells keep their usual uv rects; the
+    /// renderer maps them into the page.
+    pub fn load_packed_sprite_sheet<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        cell_width: u32,
+        cell_height: u32,
+    ) -> Result<SpriteSheet, AssetError> {
+        let renderer = &mut self.renderer;
+        self.assets.load_packed_sprite_sheet(
+            path,
+            cell_width,
+            cell_height,
+            |width, height, pixels| {
+                let id = renderer.create_packed_texture(width, height, pixels);
+                (id, renderer.atlas_region(id))
+            },
+        )
+    }
+
     pub fn load_audio<P: AsRef<Path>>(&mut self, path: P) -> Result<AudioClip, AssetError> {
         let resolved = self.assets.resolve_path(path.as_ref());
         let bytes = self.assets.load_bytes(path)?;
@@ -1137,6 +1259,30 @@ impl Engine {
             }
         }
 
+        for result in self
+            .assets
+            .reload_changed_tiled_maps(|width, height, pixels| {
+                self.renderer.create_texture(width, height, pixels)
+            })
+        {
+            match result {
+                Ok(path) => log::info!("Reloaded tiled map {}", path.display()),
+                Err(error) => log::warn!("Tiled map reload failed: {error}"),
+            }
+        }
+
+        for result in self
+            .assets
+            .reload_changed_ldtk_projects(|width, height, pixels| {
+                self.renderer.create_texture(width, height, pixels)
+            })
+        {
+            match result {
+                Ok(path) => log::info!("Reloaded LDtk project {}", path.display()),
+                Err(error) => log::warn!("LDtk project reload failed: {error}"),
+            }
+        }
+
         for path in self.assets.invalidate_changed_manifests() {
             log::info!("Invalidated asset manifest {}", path.display());
         }
@@ -1218,6 +1364,32 @@ impl Engine {
     pub fn font(&self, id: text::FontId) -> &text::FontAtlas {
         &self.renderer.fonts[id.0]
     }
+
+    /// Draw glyphs `font` lacks from `fallbacks` instead, tried in order — a
+    /// Latin UI face backed by a CJK one, say. A char no font in the chain
+    /// has draws as `font`'s own `.notdef` box.
+    pub fn set_font_fallbacks(&mut self, font: text::FontId, fallbacks: &[text::FontId]) {
+        text::set_font_fallbacks(&mut self.renderer.fonts, font, fallbacks);
+    }
+
+    /// Switch `font` between coverage bitmaps and signed distance fields.
+    /// One SDF atlas draws crisp text at any size and enables the outline and
+    /// glow in [`TextEffects`](crate::TextEffects).
+    pub fn set_font_mode(&mut self, font: text::FontId, mode: text::FontMode) {
+        if let Some(atlas) = self.renderer.fonts.get_mut(font.0) {
+            atlas.set_mode(mode);
+        }
+    }
+
+    /// The fonts and icons `[font=..]` and `[img=..]` markup resolves —
+    /// every manifest's aliases once it loads, plus anything registered here.
+    pub fn markup_assets(&self) -> &canvas::MarkupAssets {
+        &self.markup_assets
+    }
+
+    pub fn markup_assets_mut(&mut self) -> &mut canvas::MarkupAssets {
+        &mut self.markup_assets
+    }
 }
 
 pub trait Game: 'static + Sized {
@@ -1486,6 +1658,7 @@ pub fn run<G: Game>(config: EngineConfig) -> Result<(), Box<dyn std::error::Erro
         actions: ActionMap::new(),
         rng: RefCell::new(Rng::from_time()),
         postfx_chain: PostFxChain::new(),
+        markup_assets: canvas::MarkupAssets::new(),
         pending_texture_requests: RefCell::new(Vec::new()),
         failed_texture_requests: RefCell::new(HashMap::new()),
         window: window.clone(),
@@ -1730,6 +1903,7 @@ where
         actions: ActionMap::new(),
         rng: RefCell::new(Rng::from_time()),
         postfx_chain: PostFxChain::new(),
+        markup_assets: canvas::MarkupAssets::new(),
         pending_texture_requests: RefCell::new(Vec::new()),
         failed_texture_requests: RefCell::new(HashMap::new()),
         window: window.clone(),
@@ -2181,6 +2355,22 @@ impl Engine3D {
         &self.renderer.fonts[id.0]
     }
 
+    /// Draw glyphs `font` lacks from `fallbacks` instead, tried in order — a
+    /// Latin UI face backed by a CJK one, say. A char no font in the chain
+    /// has draws as `font`'s own `.notdef` box.
+    pub fn set_font_fallbacks(&mut self, font: text::FontId, fallbacks: &[text::FontId]) {
+        text::set_font_fallbacks(&mut self.renderer.fonts, font, fallbacks);
+    }
+
+    /// Switch `font` between coverage bitmaps and signed distance fields.
+    /// One SDF atlas draws crisp text at any size and enables the outline and
+    /// glow in [`TextEffects`](crate::TextEffects).
+    pub fn set_font_mode(&mut self, font: text::FontId, mode: text::FontMode) {
+        if let Some(atlas) = self.renderer.fonts.get_mut(font.0) {
+            atlas.set_mode(mode);
+        }
+    }
+
     pub fn load_bytes<P: AsRef<Path>>(&mut self, path: P) -> Result<Arc<[u8]>, AssetError> {
         self.assets.load_bytes(path)
     }
@@ -2256,7 +2446,11 @@ impl Engine3D {
             deps.push(resolved);
             pack.insert_mesh(alias, self.load_mesh(rel_path)?);
         }
-        if !manifest.textures.is_empty() || !manifest.sprite_sheets.is_empty() {
+        if !manifest.textures.is_empty()
+            || !manifest.sprite_sheets.is_empty()
+            || !manifest.tiled_maps.is_empty()
+            || !manifest.ldtk_projects.is_empty()
+        {
             return Err(AssetError::manifest_message(
                 self.assets.root(),
                 "3D Engine manifest currently supports meshes, audio, text, and bytes only",
@@ -2453,7 +2647,8 @@ impl Engine3D {
         for result in self
             .assets
             .reload_changed_textures(|id, width, height, pixels| {
-                self.renderer.replace_texture(id, width, height, pixels)
+                self.renderer.replace_texture(id, width, height, pixels);
+                None
             })
         {
             match result {
@@ -2487,10 +2682,14 @@ impl Engine3D {
         let path = path.as_ref();
         let mut errors = self.assets.validate_manifest(path);
         if let Ok(manifest) = self.assets.peek_manifest(path) {
-            if !manifest.textures.is_empty() || !manifest.sprite_sheets.is_empty() {
+            if !manifest.textures.is_empty()
+                || !manifest.sprite_sheets.is_empty()
+                || !manifest.tiled_maps.is_empty()
+                || !manifest.ldtk_projects.is_empty()
+            {
                 errors.push(AssetError::manifest_message(
                     &self.assets.resolve_path(path),
-                    "3D Engine manifest does not support textures or sprite_sheets",
+                    "3D Engine manifest does not support textures, sprite_sheets, tiled_maps or ldtk_projects",
                 ));
             }
         }
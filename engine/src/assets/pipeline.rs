@@ -8,9 +8,12 @@ use std::time::SystemTime;
 use serde::{Deserialize, Serialize};
 
 use crate::assets::{AudioClip, Color, SpriteSheet};
-use crate::renderer::TextureId;
+use crate::renderer::{AtlasRegion, TextureId};
 use crate::renderer3d::{MeshId, Vertex3D};
 use crate::text::FontId;
+use crate::world::autotile::AutotileRules;
+use crate::world::ldtk::{LdtkLevel, LdtkLevelScene, LdtkProject};
+use crate::world::tiled::{TiledLevel, TiledMap, TiledTileset};
 
 #[derive(Debug)]
 pub enum AssetError {
@@ -46,6 +49,10 @@ pub enum AssetError {
         path: PathBuf,
         message: String,
     },
+    Map {
+        path: PathBuf,
+        message: String,
+    },
     InvalidSpriteSheet {
         path: PathBuf,
         texture_width: u32,
@@ -70,6 +77,13 @@ impl AssetError {
         }
     }
 
+    pub(crate) fn map_message(path: &Path, message: impl Into<String>) -> Self {
+        Self::Map {
+            path: path.to_path_buf(),
+            message: message.into(),
+        }
+    }
+
     pub(crate) fn scene_message(path: &Path, message: impl Into<String>) -> Self {
         Self::Scene {
             path: path.to_path_buf(),
@@ -113,6 +127,9 @@ impl fmt::Display for AssetError {
                     path.display()
                 )
             }
+            Self::Map { path, message } => {
+                write!(f, "failed to load map '{}': {message}", path.display())
+            }
             Self::InvalidSpriteSheet {
                 path,
                 texture_width,
@@ -143,6 +160,7 @@ impl std::error::Error for AssetError {
             | Self::Manifest { .. }
             | Self::Scene { .. }
             | Self::Audio { .. }
+            | Self::Map { .. }
             | Self::InvalidSpriteSheet { .. } => None,
         }
     }
@@ -154,6 +172,9 @@ pub struct TextureAsset {
     pub width: u32,
     pub height: u32,
     pub path: PathBuf,
+    /// The atlas page and sub-rect the texture was packed into, if it was.
+    /// Draws keep using `id`; the renderer maps it onto the page itself.
+    pub atlas: Option<AtlasRegion>,
 }
 
 impl TextureAsset {
@@ -161,6 +182,10 @@ impl TextureAsset {
         self.id
     }
 
+    pub fn atlas_region(&self) -> Option<AtlasRegion> {
+        self.atlas
+    }
+
     pub fn size(&self) -> glam::UVec2 {
         glam::UVec2::new(self.width, self.height)
     }
@@ -192,11 +217,78 @@ impl FontAsset {
     }
 }
 
+/// A Tiled map with its tilesets' images loaded as sprite sheets.
+///
+/// The engine re-reads the map whenever the map file or one of its external
+/// tilesets changes on disk; poll `Engine::reloaded_tiled_map` with the asset
+/// you hold to pick up the new version.
+#[derive(Debug, Clone)]
+pub struct TiledMapAsset {
+    pub map: Arc<TiledMap>,
+    /// One sheet per entry in `map.tilesets`.
+    pub sheets: Vec<SpriteSheet>,
+    pub path: PathBuf,
+    pub revision: u64,
+    dependencies: Vec<PathBuf>,
+}
+
+impl TiledMapAsset {
+    pub fn map(&self) -> &TiledMap {
+        &self.map
+    }
+
+    /// Build the map's tile layers, colliders, triggers and spawns.
+    pub fn level(&self) -> TiledLevel {
+        self.map.build(&self.sheets)
+    }
+
+    /// The map file followed by any external tileset files it uses.
+    pub fn dependencies(&self) -> &[PathBuf] {
+        &self.dependencies
+    }
+}
+
+/// An LDtk project with every level read and its tilesets' images loaded as
+/// sprite sheets.
+///
+/// The engine re-reads the project whenever it, a level file or a tileset
+/// image changes on disk; poll `Engine::reloaded_ldtk_project` with the asset
+/// you hold to pick up the new version.
+#[derive(Debug, Clone)]
+pub struct LdtkProjectAsset {
+    pub project: Arc<LdtkProject>,
+    /// One sheet per entry in `project.tilesets`.
+    pub sheets: Vec<SpriteSheet>,
+    pub path: PathBuf,
+    pub revision: u64,
+    dependencies: Vec<PathBuf>,
+}
+
+impl LdtkProjectAsset {
+    pub fn project(&self) -> &LdtkProject {
+        &self.project
+    }
+
+    /// Build a level's tile layers, IntGrid solids and entity nodes.
+    pub fn build_level(&self, level: &LdtkLevel) -> LdtkLevelScene {
+        self.project.build_level(level, &self.sheets)
+    }
+
+    /// The project file followed by its level files and tileset images.
+    pub fn dependencies(&self) -> &[PathBuf] {
+        &self.dependencies
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpriteSheetAssetDef {
     pub path: String,
     pub cell_width: u32,
     pub cell_height: u32,
+    /// Autotile rules for the sheet's terrains, relative to the asset root;
+    /// see [`AutotileRules`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub autotile: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -215,6 +307,14 @@ pub struct AssetManifest {
     pub meshes: HashMap<String, String>,
     #[serde(default)]
     pub audio: HashMap<String, String>,
+    #[serde(default)]
+    pub tiled_maps: HashMap<String, String>,
+    #[serde(default)]
+    pub ldtk_projects: HashMap<String, String>,
+    /// Aliases from `textures` and `sprite_sheets` to pack into shared atlas
+    /// pages, so sprites drawn from them batch together.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub atlas: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -225,6 +325,8 @@ pub struct AssetSummary {
     pub texture_count: usize,
     pub sprite_sheet_count: usize,
     pub mesh_count: usize,
+    pub tiled_map_count: usize,
+    pub ldtk_project_count: usize,
     pub manifest_count: usize,
     pub bytes_paths: Vec<PathBuf>,
     pub text_paths: Vec<PathBuf>,
@@ -232,6 +334,8 @@ pub struct AssetSummary {
     pub texture_paths: Vec<PathBuf>,
     pub sprite_sheet_paths: Vec<PathBuf>,
     pub mesh_paths: Vec<PathBuf>,
+    pub tiled_map_paths: Vec<PathBuf>,
+    pub ldtk_project_paths: Vec<PathBuf>,
     pub manifest_paths: Vec<PathBuf>,
 }
 
@@ -244,6 +348,9 @@ pub struct AssetPack {
     sprite_sheets: HashMap<String, SpriteSheet>,
     meshes: HashMap<String, MeshAsset>,
     audio: HashMap<String, AudioClip>,
+    tiled_maps: HashMap<String, TiledMapAsset>,
+    ldtk_projects: HashMap<String, LdtkProjectAsset>,
+    autotile_rules: HashMap<String, Arc<AutotileRules>>,
 }
 
 impl AssetPack {
@@ -279,6 +386,19 @@ impl AssetPack {
         self.audio.get(alias)
     }
 
+    pub fn tiled_map(&self, alias: &str) -> Option<&TiledMapAsset> {
+        self.tiled_maps.get(alias)
+    }
+
+    pub fn ldtk_project(&self, alias: &str) -> Option<&LdtkProjectAsset> {
+        self.ldtk_projects.get(alias)
+    }
+
+    /// The autotile rules of the sprite sheet loaded as `alias`.
+    pub fn autotile_rules(&self, alias: &str) -> Option<&Arc<AutotileRules>> {
+        self.autotile_rules.get(alias)
+    }
+
     pub fn texture_id(&self, alias: &str) -> Option<TextureId> {
         self.textures
             .get(alias)
@@ -296,6 +416,18 @@ impl AssetPack {
         self.textures.insert(alias.into(), texture);
     }
 
+    pub(crate) fn font_aliases(&self) -> impl Iterator<Item = (&str, &FontAsset)> {
+        self.fonts
+            .iter()
+            .map(|(alias, font)| (alias.as_str(), font))
+    }
+
+    pub(crate) fn texture_aliases(&self) -> impl Iterator<Item = (&str, &TextureAsset)> {
+        self.textures
+            .iter()
+            .map(|(alias, texture)| (alias.as_str(), texture))
+    }
+
     pub(crate) fn insert_bytes(&mut self, alias: String, bytes: Arc<[u8]>) {
         self.bytes.insert(alias, bytes);
     }
@@ -323,6 +455,18 @@ impl AssetPack {
     pub(crate) fn insert_audio(&mut self, alias: String, clip: AudioClip) {
         self.audio.insert(alias, clip);
     }
+
+    pub(crate) fn insert_tiled_map(&mut self, alias: String, map: TiledMapAsset) {
+        self.tiled_maps.insert(alias, map);
+    }
+
+    pub(crate) fn insert_ldtk_project(&mut self, alias: String, project: LdtkProjectAsset) {
+        self.ldtk_projects.insert(alias, project);
+    }
+
+    pub(crate) fn insert_autotile_rules(&mut self, alias: String, rules: Arc<AutotileRules>) {
+        self.autotile_rules.insert(alias, rules);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -388,8 +532,12 @@ pub(crate) struct AssetPipeline {
     textures: HashMap<PathBuf, TextureAsset>,
     sprite_sheets: HashMap<SpriteSheetKey, SpriteSheet>,
     meshes: HashMap<PathBuf, MeshAsset>,
+    tiled_maps: HashMap<PathBuf, TiledMapAsset>,
+    ldtk_projects: HashMap<PathBuf, LdtkProjectAsset>,
     texture_timestamps: HashMap<PathBuf, SystemTime>,
     mesh_timestamps: HashMap<PathBuf, SystemTime>,
+    tiled_map_timestamps: HashMap<PathBuf, SystemTime>,
+    ldtk_project_timestamps: HashMap<PathBuf, SystemTime>,
     manifest_timestamps: HashMap<PathBuf, SystemTime>,
     manifest_deps: HashMap<PathBuf, Vec<PathBuf>>,
     retained_paths: HashMap<PathBuf, usize>,
@@ -406,8 +554,12 @@ impl AssetPipeline {
             textures: HashMap::new(),
             sprite_sheets: HashMap::new(),
             meshes: HashMap::new(),
+            tiled_maps: HashMap::new(),
+            ldtk_projects: HashMap::new(),
             texture_timestamps: HashMap::new(),
             mesh_timestamps: HashMap::new(),
+            tiled_map_timestamps: HashMap::new(),
+            ldtk_project_timestamps: HashMap::new(),
             manifest_timestamps: HashMap::new(),
             manifest_deps: HashMap::new(),
             retained_paths: HashMap::new(),
@@ -517,6 +669,23 @@ impl AssetPipeline {
     where
         P: AsRef<Path>,
         F: FnOnce(u32, u32, &[u8]) -> TextureId,
+    {
+        self.load_packed_texture(path, |width, height, pixels| {
+            (create_texture(width, height, pixels), None)
+        })
+    }
+
+    /// [`load_texture`](Self::load_texture) through a `create_texture` that
+    /// may pack the image into an atlas, returning its region if it did. A
+    /// path already loaded returns the cached asset, packed or not.
+    pub fn load_packed_texture<P, F>(
+        &mut self,
+        path: P,
+        create_texture: F,
+    ) -> Result<TextureAsset, AssetError>
+    where
+        P: AsRef<Path>,
+        F: FnOnce(u32, u32, &[u8]) -> (TextureId, Option<AtlasRegion>),
     {
         let resolved = self.resolve_path(path.as_ref());
         if let Some(texture) = self.textures.get(&resolved) {
@@ -524,12 +693,13 @@ impl AssetPipeline {
         }
 
         let (width, height, rgba) = self.read_image_rgba(&resolved)?;
-        let id = create_texture(width, height, &rgba);
+        let (id, atlas) = create_texture(width, height, &rgba);
         let asset = TextureAsset {
             id,
             width,
             height,
             path: resolved.clone(),
+            atlas,
         };
         if let Ok(modified) = file_modified_time(&resolved) {
             self.texture_timestamps.insert(resolved.clone(), modified);
@@ -550,6 +720,32 @@ impl AssetPipeline {
         F: FnOnce(u32, u32, &[u8]) -> TextureId,
     {
         let texture = self.load_texture(path.as_ref(), create_texture)?;
+        self.sprite_sheet_for(texture, cell_width, cell_height)
+    }
+
+    /// [`load_sprite_sheet`](Self::load_sprite_sheet) with its image loaded
+    /// through [`load_packed_texture`](Self::load_packed_texture).
+    pub fn load_packed_sprite_sheet<P, F>(
+        &mut self,
+        path: P,
+        cell_width: u32,
+        cell_height: u32,
+        create_texture: F,
+    ) -> Result<SpriteSheet, AssetError>
+    where
+        P: AsRef<Path>,
+        F: FnOnce(u32, u32, &[u8]) -> (TextureId, Option<AtlasRegion>),
+    {
+        let texture = self.load_packed_texture(path.as_ref(), create_texture)?;
+        self.sprite_sheet_for(texture, cell_width, cell_height)
+    }
+
+    fn sprite_sheet_for(
+        &mut self,
+        texture: TextureAsset,
+        cell_width: u32,
+        cell_height: u32,
+    ) -> Result<SpriteSheet, AssetError> {
         let key = SpriteSheetKey {
             path: texture.path.clone(),
             cell_width,
@@ -585,6 +781,186 @@ impl AssetPipeline {
         Ok(sheet)
     }
 
+    /// Load a Tiled map (`.tmj` or `.tmx`), its external tilesets and their
+    /// images. Cached by path until the map or a tileset file changes.
+    pub fn load_tiled_map<P, F>(
+        &mut self,
+        path: P,
+        create_texture: F,
+    ) -> Result<TiledMapAsset, AssetError>
+    where
+        P: AsRef<Path>,
+        F: FnMut(u32, u32, &[u8]) -> TextureId,
+    {
+        let resolved = self.resolve_path(path.as_ref());
+        if let Some(map) = self.tiled_maps.get(&resolved) {
+            return Ok(map.clone());
+        }
+
+        let asset = self.read_tiled_map(&resolved, 0, create_texture)?;
+        self.tiled_maps.insert(resolved, asset.clone());
+        Ok(asset)
+    }
+
+    fn read_tiled_map<F>(
+        &mut self,
+        path: &Path,
+        revision: u64,
+        mut create_texture: F,
+    ) -> Result<TiledMapAsset, AssetError>
+    where
+        F: FnMut(u32, u32, &[u8]) -> TextureId,
+    {
+        let read = |path: &Path| {
+            fs::read_to_string(path).map_err(|source| AssetError::Io {
+                path: path.to_path_buf(),
+                source,
+            })
+        };
+        let mut map = TiledMap::parse(path, &read(path)?)?;
+        let map_dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let mut dependencies = vec![path.to_path_buf()];
+        let mut sheets = Vec::with_capacity(map.tilesets.len());
+
+        for tileset in &mut map.tilesets {
+            // Images are relative to the file that declares the tileset.
+            let mut image_dir = map_dir.clone();
+            if let Some(source) = tileset.source.clone() {
+                let tileset_path = self.resolve_path(&map_dir.join(&source));
+                let mut external = TiledTileset::parse(&tileset_path, &read(&tileset_path)?)?;
+                external.first_gid = tileset.first_gid;
+                external.source = Some(source);
+                *tileset = external;
+                image_dir = tileset_path.parent().unwrap_or(Path::new("")).to_path_buf();
+                dependencies.push(tileset_path);
+            }
+            let Some(image) = &tileset.image else {
+                return Err(AssetError::map_message(
+                    path,
+                    format!(
+                        "tileset '{}' is an image collection, which is not supported",
+                        tileset.name
+                    ),
+                ));
+            };
+            let texture = self.load_texture(image_dir.join(image), &mut create_texture)?;
+            if tileset.tile_width == 0 || tileset.tile_height == 0 {
+                return Err(AssetError::map_message(
+                    path,
+                    format!("tileset '{}' has no tile size", tileset.name),
+                ));
+            }
+            sheets.push(
+                SpriteSheet::new(
+                    texture.id,
+                    texture.width,
+                    texture.height,
+                    tileset.tile_width,
+                    tileset.tile_height,
+                )
+                .with_margin_spacing(tileset.margin, tileset.spacing),
+            );
+            dependencies.push(texture.path);
+        }
+
+        for dependency in &dependencies {
+            if let Ok(modified) = file_modified_time(dependency) {
+                self.tiled_map_timestamps
+                    .insert(dependency.clone(), modified);
+            }
+        }
+        Ok(TiledMapAsset {
+            map: Arc::new(map),
+            sheets,
+            path: path.to_path_buf(),
+            revision,
+            dependencies,
+        })
+    }
+
+    /// Load an LDtk project, its separate level files and its tileset images.
+    /// Cached by path until one of those files changes.
+    pub fn load_ldtk_project<P, F>(
+        &mut self,
+        path: P,
+        create_texture: F,
+    ) -> Result<LdtkProjectAsset, AssetError>
+    where
+        P: AsRef<Path>,
+        F: FnMut(u32, u32, &[u8]) -> TextureId,
+    {
+        let resolved = self.resolve_path(path.as_ref());
+        if let Some(project) = self.ldtk_projects.get(&resolved) {
+            return Ok(project.clone());
+        }
+
+        let asset = self.read_ldtk_project(&resolved, 0, create_texture)?;
+        self.ldtk_projects.insert(resolved, asset.clone());
+        Ok(asset)
+    }
+
+    fn read_ldtk_project<F>(
+        &mut self,
+        path: &Path,
+        revision: u64,
+        mut create_texture: F,
+    ) -> Result<LdtkProjectAsset, AssetError>
+    where
+        F: FnMut(u32, u32, &[u8]) -> TextureId,
+    {
+        let read = |path: &Path| {
+            fs::read_to_string(path).map_err(|source| AssetError::Io {
+                path: path.to_path_buf(),
+                source,
+            })
+        };
+        let mut project = LdtkProject::parse(path, &read(path)?)?;
+        let project_dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let mut dependencies = vec![path.to_path_buf()];
+
+        for level in &mut project.levels {
+            let Some(external) = level.external_path.clone() else {
+                continue;
+            };
+            let level_path = self.resolve_path(&project_dir.join(&external));
+            // The project's copy of the level already has its place in the
+            // world; the level file supplies the layers.
+            level.layers = LdtkLevel::parse(&level_path, &read(&level_path)?)?.layers;
+            dependencies.push(level_path);
+        }
+
+        let mut sheets = Vec::with_capacity(project.tilesets.len());
+        for tileset in &project.tilesets {
+            let texture =
+                self.load_texture(project_dir.join(&tileset.rel_path), &mut create_texture)?;
+            sheets.push(
+                SpriteSheet::new(
+                    texture.id,
+                    texture.width,
+                    texture.height,
+                    tileset.grid_size,
+                    tileset.grid_size,
+                )
+                .with_margin_spacing(tileset.padding, tileset.spacing),
+            );
+            dependencies.push(texture.path);
+        }
+
+        for dependency in &dependencies {
+            if let Ok(modified) = file_modified_time(dependency) {
+                self.ldtk_project_timestamps
+                    .insert(dependency.clone(), modified);
+            }
+        }
+        Ok(LdtkProjectAsset {
+            project: Arc::new(project),
+            sheets,
+            path: path.to_path_buf(),
+            revision,
+            dependencies,
+        })
+    }
+
     pub fn load_obj_mesh<P, F>(&mut self, path: P, create_mesh: F) -> Result<MeshAsset, AssetError>
     where
         P: AsRef<Path>,
@@ -641,7 +1017,7 @@ impl AssetPipeline {
         mut replace_texture: F,
     ) -> Vec<Result<PathBuf, AssetError>>
     where
-        F: FnMut(TextureId, u32, u32, &[u8]),
+        F: FnMut(TextureId, u32, u32, &[u8]) -> Option<AtlasRegion>,
     {
         let watched: Vec<(PathBuf, TextureAsset)> = self
             .textures
@@ -665,10 +1041,11 @@ impl AssetPipeline {
 
             match self.read_image_rgba(&path) {
                 Ok((width, height, rgba)) => {
-                    replace_texture(existing.id, width, height, &rgba);
+                    let atlas = replace_texture(existing.id, width, height, &rgba);
                     if let Some(texture) = self.textures.get_mut(&path) {
                         texture.width = width;
                         texture.height = height;
+                        texture.atlas = atlas;
                     }
                     self.update_sprite_sheet_dimensions(&path, width, height);
                     self.texture_timestamps.insert(path.clone(), modified);
@@ -734,6 +1111,78 @@ impl AssetPipeline {
         results
     }
 
+    /// Re-read every loaded Tiled map whose map, tileset or image file changed,
+    /// bumping its revision. A map that fails to parse keeps its last good
+    /// version until the next save.
+    pub fn reload_changed_tiled_maps<F>(
+        &mut self,
+        mut create_texture: F,
+    ) -> Vec<Result<PathBuf, AssetError>>
+    where
+        F: FnMut(u32, u32, &[u8]) -> TextureId,
+    {
+        let watched = self
+            .tiled_maps
+            .iter()
+            .map(|(path, map)| (path.clone(), map.revision, map.dependencies.clone()))
+            .collect();
+        let changed = changed_dependents(&mut self.tiled_map_timestamps, watched);
+
+        let mut results = Vec::new();
+        for (path, revision) in changed {
+            match self.read_tiled_map(&path, revision + 1, &mut create_texture) {
+                Ok(map) => {
+                    self.tiled_maps.insert(path.clone(), map);
+                    results.push(Ok(path));
+                }
+                Err(error) => results.push(Err(error)),
+            }
+        }
+
+        results
+    }
+
+    /// Re-read every loaded LDtk project whose project, level or image file
+    /// changed, bumping its revision. A project that fails to parse keeps its
+    /// last good version until the next save.
+    pub fn reload_changed_ldtk_projects<F>(
+        &mut self,
+        mut create_texture: F,
+    ) -> Vec<Result<PathBuf, AssetError>>
+    where
+        F: FnMut(u32, u32, &[u8]) -> TextureId,
+    {
+        let watched = self
+            .ldtk_projects
+            .iter()
+            .map(|(path, project)| (path.clone(), project.revision, project.dependencies.clone()))
+            .collect();
+        let changed = changed_dependents(&mut self.ldtk_project_timestamps, watched);
+
+        let mut results = Vec::new();
+        for (path, revision) in changed {
+            match self.read_ldtk_project(&path, revision + 1, &mut create_texture) {
+                Ok(project) => {
+                    self.ldtk_projects.insert(path.clone(), project);
+                    results.push(Ok(path));
+                }
+                Err(error) => results.push(Err(error)),
+            }
+        }
+
+        results
+    }
+
+    /// The latest loaded version of the LDtk project at `path`.
+    pub fn loaded_ldtk_project<P: AsRef<Path>>(&self, path: P) -> Option<&LdtkProjectAsset> {
+        self.ldtk_projects.get(&self.resolve_path(path.as_ref()))
+    }
+
+    /// The latest loaded version of the map at `path`.
+    pub fn loaded_tiled_map<P: AsRef<Path>>(&self, path: P) -> Option<&TiledMapAsset> {
+        self.tiled_maps.get(&self.resolve_path(path.as_ref()))
+    }
+
     pub fn invalidate_changed_manifests(&mut self) -> Vec<PathBuf> {
         let watched: Vec<PathBuf> = self.manifests.keys().cloned().collect();
         let mut invalidated = Vec::new();
@@ -814,6 +1263,18 @@ impl AssetPipeline {
                     .iter()
                     .map(|(alias, p)| (alias.as_str(), p.as_str())),
             )
+            .chain(
+                manifest
+                    .tiled_maps
+                    .iter()
+                    .map(|(alias, p)| (alias.as_str(), p.as_str())),
+            )
+            .chain(
+                manifest
+                    .ldtk_projects
+                    .iter()
+                    .map(|(alias, p)| (alias.as_str(), p.as_str())),
+            )
             .collect();
 
         for (_alias, rel_path) in &all_paths {
@@ -859,6 +1320,30 @@ impl AssetPipeline {
                     }
                 }
             }
+            if let Some(rules_path) = &sheet_def.autotile {
+                let rules_path = self.resolve_path(Path::new(rules_path));
+                match fs::read_to_string(&rules_path) {
+                    Ok(text) => {
+                        if let Err(e) = AutotileRules::parse(&rules_path, &text) {
+                            errors.push(e);
+                        }
+                    }
+                    Err(source) => errors.push(AssetError::Io {
+                        path: rules_path,
+                        source,
+                    }),
+                }
+            }
+        }
+
+        for alias in &manifest.atlas {
+            if !manifest.textures.contains_key(alias) && !manifest.sprite_sheets.contains_key(alias)
+            {
+                errors.push(AssetError::manifest_message(
+                    &resolved,
+                    format!("atlas entry `{alias}` is not a texture or sprite sheet alias"),
+                ));
+            }
         }
 
         errors
@@ -969,6 +1454,8 @@ impl AssetPipeline {
             texture_count: self.textures.len(),
             sprite_sheet_count: self.sprite_sheets.len(),
             mesh_count: self.meshes.len(),
+            tiled_map_count: self.tiled_maps.len(),
+            ldtk_project_count: self.ldtk_projects.len(),
             manifest_count: self.manifests.len(),
             bytes_paths: self.bytes.keys().cloned().collect(),
             text_paths: self.text.keys().cloned().collect(),
@@ -976,6 +1463,8 @@ impl AssetPipeline {
             texture_paths: self.textures.keys().cloned().collect(),
             sprite_sheet_paths: self.sprite_sheets.keys().map(|k| k.path.clone()).collect(),
             mesh_paths: self.meshes.keys().cloned().collect(),
+            tiled_map_paths: self.tiled_maps.keys().cloned().collect(),
+            ldtk_project_paths: self.ldtk_projects.keys().cloned().collect(),
             manifest_paths: self.manifests.keys().cloned().collect(),
         }
     }
@@ -1000,6 +1489,24 @@ impl AssetPipeline {
         self.mesh_timestamps.remove(&resolved);
     }
 
+    pub fn unload_tiled_map<P: AsRef<Path>>(&mut self, path: P) {
+        let resolved = self.resolve_path(path.as_ref());
+        if let Some(map) = self.tiled_maps.remove(&resolved) {
+            for dependency in &map.dependencies {
+                self.tiled_map_timestamps.remove(dependency);
+            }
+        }
+    }
+
+    pub fn unload_ldtk_project<P: AsRef<Path>>(&mut self, path: P) {
+        let resolved = self.resolve_path(path.as_ref());
+        if let Some(project) = self.ldtk_projects.remove(&resolved) {
+            for dependency in &project.dependencies {
+                self.ldtk_project_timestamps.remove(dependency);
+            }
+        }
+    }
+
     pub fn unload_data<P: AsRef<Path>>(&mut self, path: P) {
         let resolved = self.resolve_path(path.as_ref());
         self.bytes.remove(&resolved);
@@ -1219,6 +1726,39 @@ fn compute_flat_normals(vertices: &mut [Vertex3D], indices: &[u32]) {
     }
 }
 
+/// Of the `(path, revision, dependencies)` assets watched, those with a
+/// dependency newer than `timestamps` records. Assets can share a dependency,
+/// so every asset is checked before the new timestamps are recorded.
+fn changed_dependents(
+    timestamps: &mut HashMap<PathBuf, SystemTime>,
+    watched: Vec<(PathBuf, u64, Vec<PathBuf>)>,
+) -> Vec<(PathBuf, u64)> {
+    let mut touched = Vec::new();
+    let changed = watched
+        .into_iter()
+        .filter(|(_, _, dependencies)| {
+            let mut changed = false;
+            for dependency in dependencies {
+                let Ok(modified) = file_modified_time(dependency) else {
+                    continue;
+                };
+                let newer = timestamps
+                    .get(dependency)
+                    .map(|known| modified > *known)
+                    .unwrap_or(true);
+                if newer {
+                    touched.push((dependency.clone(), modified));
+                    changed = true;
+                }
+            }
+            changed
+        })
+        .map(|(path, revision, _)| (path, revision))
+        .collect();
+    timestamps.extend(touched);
+    changed
+}
+
 fn file_modified_time(path: &Path) -> Result<SystemTime, std::io::Error> {
     fs::metadata(path)?.modified()
 }
@@ -1328,6 +1868,216 @@ mod tests {
             vec![manifest, new_shared, old_shared]
         );
     }
+
+    #[test]
+    fn validate_manifest_checks_sprite_sheet_autotile_rules() {
+        use std::fs;
+
+        let base = std::env::temp_dir().join(format!("rengine_autotile_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        image::RgbaImage::new(32, 16)
+            .save(base.join("terrain.png"))
+            .unwrap();
+        fs::write(
+            base.join("terrain.assets.json"),
+            r#"{"sprite_sheets": {"terrain": {"path": "terrain.png", "cell_width": 16,
+                "cell_height": 16, "autotile": "terrain.autotile.json"}}}"#,
+        )
+        .unwrap();
+        fs::write(
+            base.join("terrain.autotile.json"),
+            r#"{"terrains": [{"name": "grass", "mode": "edges4", "fallback": [0, 0]}]}"#,
+        )
+        .unwrap();
+
+        let pipeline = AssetPipeline::new(base.clone());
+        assert!(pipeline.validate_manifest("terrain.assets.json").is_empty());
+
+        fs::write(
+            base.join("terrain.autotile.json"),
+            r#"{"terrains": [{"name": "grass", "mode": "edges4"}]}"#,
+        )
+        .unwrap();
+        let errors = pipeline.validate_manifest("terrain.assets.json");
+        assert_eq!(errors.len(), 1);
+        assert!(
+            errors[0].to_string().contains("has no tiles"),
+            "{}",
+            errors[0]
+        );
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn manifest_atlas_entries_must_name_textures_and_packed_loads_keep_their_region() {
+        use std::fs;
+
+        use crate::renderer::{AtlasRegion, TextureId};
+
+        let base = std::env::temp_dir().join(format!("rengine_atlas_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        image::RgbaImage::new(16, 16)
+            .save(base.join("coin.png"))
+            .unwrap();
+        fs::write(
+            base.join("items.assets.json"),
+            r#"{"textures": {"coin": "coin.png"}, "atlas": ["coin", "gem"]}"#,
+        )
+        .unwrap();
+
+        let mut pipeline = AssetPipeline::new(base.clone());
+        let errors = pipeline.validate_manifest("items.assets.json");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().contains("`gem`"), "{}", errors[0]);
+
+        let region = AtlasRegion {
+            page: TextureId(4),
+            uv_rect: [0.0, 0.0, 0.125, 0.125],
+        };
+        let coin = pipeline
+            .load_packed_texture("coin.png", |_, _, _| (TextureId(5), Some(region)))
+            .unwrap();
+        assert_eq!(
+            (coin.texture(), coin.atlas_region()),
+            (TextureId(5), Some(region))
+        );
+        // The cached asset comes back packed even through the plain loader.
+        let again = pipeline
+            .load_texture("coin.png", |_, _, _| unreachable!())
+            .unwrap();
+        assert_eq!(again.atlas, Some(region));
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn tiled_map_resolves_external_tilesets_and_reloads_on_change() {
+        use std::fs;
+        use std::time::SystemTime;
+
+        use crate::renderer::TextureId;
+        use crate::world::tiled::TiledLayer;
+
+        let base = std::env::temp_dir().join(format!("rengine_tiled_map_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(base.join("tiles")).unwrap();
+        image::RgbaImage::new(32, 16)
+            .save(base.join("tiles/terrain.png"))
+            .unwrap();
+        fs::write(
+            base.join("tiles/terrain.tsj"),
+            r#"{"name": "terrain", "tilewidth": 16, "tileheight": 16, "tilecount": 2,
+                "columns": 2, "image": "terrain.png", "imagewidth": 32, "imageheight": 16}"#,
+        )
+        .unwrap();
+        let map_json = |data: &str| {
+            format!(
+                r#"{{"width": 2, "height": 1, "tilewidth": 16, "tileheight": 16,
+                    "tilesets": [{{"firstgid": 1, "source": "tiles/terrain.tsj"}}],
+                    "layers": [{{"type": "tilelayer", "name": "ground", "data": {data}}}]}}"#
+            )
+        };
+        fs::write(base.join("level.tmj"), map_json("[1, 2]")).unwrap();
+
+        let mut pipeline = AssetPipeline::new(base.clone());
+        let mut created = 0;
+        let mut create = |_: u32, _: u32, _: &[u8]| {
+            created += 1;
+            TextureId(created)
+        };
+        let map = pipeline.load_tiled_map("level.tmj", &mut create).unwrap();
+        assert_eq!(map.map().tilesets[0].name, "terrain");
+        assert_eq!(map.map().tilesets[0].first_gid, 1);
+        assert_eq!(map.sheets[0].texture_width, 32);
+        assert_eq!(map.dependencies().len(), 3);
+        assert!(pipeline.reload_changed_tiled_maps(&mut create).is_empty());
+
+        fs::write(base.join("level.tmj"), map_json("[2, 2]")).unwrap();
+        let map_path = map.path.clone();
+        pipeline
+            .tiled_map_timestamps
+            .insert(map_path.clone(), SystemTime::UNIX_EPOCH);
+        let reloaded = pipeline.reload_changed_tiled_maps(&mut create);
+        assert_eq!(reloaded.len(), 1);
+        assert_eq!(reloaded[0].as_ref().unwrap(), &map_path);
+
+        let latest = pipeline.loaded_tiled_map(&map_path).unwrap();
+        assert_eq!(latest.revision, 1);
+        let TiledLayer::Tiles(ground) = &latest.map().layers[0] else {
+            panic!("expected a tile layer");
+        };
+        assert_eq!(ground.data, vec![2, 2]);
+        // The tileset image is shared with the first load, not recreated.
+        assert_eq!(created, 1);
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn ldtk_project_reads_level_files_and_reloads_on_change() {
+        use std::fs;
+        use std::time::SystemTime;
+
+        use crate::renderer::TextureId;
+
+        let base = std::env::temp_dir().join(format!("rengine_ldtk_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(base.join("world")).unwrap();
+        image::RgbaImage::new(32, 16)
+            .save(base.join("tiles.png"))
+            .unwrap();
+        fs::write(
+            base.join("world.ldtk"),
+            r#"{"worldLayout": "Free", "externalLevels": true,
+                "defs": {"tilesets": [{"uid": 1, "identifier": "Tiles", "relPath": "tiles.png",
+                                       "pxWid": 32, "pxHei": 16, "tileGridSize": 16}]},
+                "levels": [{"identifier": "Hall", "iid": "hall", "worldX": 32, "worldY": 0,
+                            "pxWid": 32, "pxHei": 16, "layerInstances": null,
+                            "externalRelPath": "world/Hall.ldtkl"}]}"#,
+        )
+        .unwrap();
+        let level_json = |csv: &str| {
+            format!(
+                r#"{{"identifier": "Hall", "iid": "hall", "pxWid": 32, "pxHei": 16,
+                    "layerInstances": [{{"__identifier": "Walls", "__type": "IntGrid",
+                        "__cWid": 2, "__cHei": 1, "__gridSize": 16, "intGridCsv": {csv}}}]}}"#
+            )
+        };
+        fs::write(base.join("world/Hall.ldtkl"), level_json("[1, 0]")).unwrap();
+
+        let mut pipeline = AssetPipeline::new(base.clone());
+        let mut created = 0;
+        let mut create = |_: u32, _: u32, _: &[u8]| {
+            created += 1;
+            TextureId(created)
+        };
+        let project = pipeline
+            .load_ldtk_project("world.ldtk", &mut create)
+            .unwrap();
+        assert_eq!(project.dependencies().len(), 3);
+        assert_eq!(project.sheets[0].texture_width, 32);
+        let hall = project.project().level("Hall").unwrap();
+        assert_eq!(hall.world_x, 32, "placement comes from the project");
+        assert_eq!(project.build_level(hall).solids.len(), 1);
+        assert!(pipeline
+            .reload_changed_ldtk_projects(&mut create)
+            .is_empty());
+
+        fs::write(base.join("world/Hall.ldtkl"), level_json("[1, 1]")).unwrap();
+        let level_path = project.dependencies()[1].clone();
+        pipeline
+            .ldtk_project_timestamps
+            .insert(level_path, SystemTime::UNIX_EPOCH);
+        let reloaded = pipeline.reload_changed_ldtk_projects(&mut create);
+        assert_eq!(reloaded.len(), 1);
+
+        let latest = pipeline.loaded_ldtk_project(&project.path).unwrap();
+        assert_eq!(latest.revision, 1);
+        let hall = latest.project().level("Hall").unwrap();
+        assert_eq!(hall.layers[0].int_grid, vec![1, 1]);
+        assert_eq!(created, 1);
+        let _ = fs::remove_dir_all(&base);
+    }
 }
 
 fn fix_winding_from_normals(vertices: &[Vertex3D], indices: &mut [u32]) {
@@ -15,6 +15,12 @@ pub struct SpriteSheet {
     pub cell_width: u32,
 
     pub cell_height: u32,
+
+    /// Pixels of padding around the whole grid.
+    pub margin: u32,
+
+    /// Pixels between neighbouring cells.
+    pub spacing: u32,
 }
 
 impl SpriteSheet {
@@ -31,15 +37,27 @@ impl SpriteSheet {
             texture_height,
             cell_width,
             cell_height,
+            margin: 0,
+            spacing: 0,
         }
     }
 
+    /// Cells laid out with padding, as tileset editors such as Tiled export
+    /// them: `margin` pixels around the grid and `spacing` between cells.
+    pub fn with_margin_spacing(mut self, margin: u32, spacing: u32) -> Self {
+        self.margin = margin;
+        self.spacing = spacing;
+        self
+    }
+
     pub fn columns(&self) -> u32 {
-        self.texture_width / self.cell_width
+        let inner = self.texture_width.saturating_sub(self.margin * 2) + self.spacing;
+        inner / (self.cell_width + self.spacing)
     }
 
     pub fn rows(&self) -> u32 {
-        self.texture_height / self.cell_height
+        let inner = self.texture_height.saturating_sub(self.margin * 2) + self.spacing;
+        inner / (self.cell_height + self.spacing)
     }
 
     pub fn uv_rect(&self, col: u32, row: u32) -> [f32; 4] {
@@ -47,7 +65,9 @@ impl SpriteSheet {
         let th = self.texture_height as f32;
         let cw = self.cell_width as f32;
         let ch = self.cell_height as f32;
-        [col as f32 * cw / tw, row as f32 * ch / th, cw / tw, ch / th]
+        let x = (self.margin + col * (self.cell_width + self.spacing)) as f32;
+        let y = (self.margin + row * (self.cell_height + self.spacing)) as f32;
+        [x / tw, y / th, cw / tw, ch / th]
     }
 }
 
@@ -504,4 +524,17 @@ mod tests {
         assert_eq!(machine.current_frame(), (1, 0));
         assert!(machine.is_finished());
     }
+
+    #[test]
+    fn padded_sheet_skips_margin_and_spacing() {
+        let sheet = SpriteSheet::new(TextureId(0), 36, 20, 16, 16).with_margin_spacing(1, 2);
+
+        assert_eq!(sheet.columns(), 2);
+        assert_eq!(sheet.rows(), 1);
+        let [u, v, w, h] = sheet.uv_rect(1, 0);
+        assert_eq!(
+            (u * 36.0, v * 20.0, w * 36.0, h * 20.0),
+            (19.0, 1.0, 16.0, 16.0)
+        );
+    }
 }
@@ -1,13 +1,17 @@
 struct VertexInput {
-    @location(0) position: vec2<f32>,
-    @location(1) color:    vec4<f32>,
-    @location(2) uv:       vec2<f32>,
+    @location(0) position:     vec2<f32>,
+    @location(1) color:        vec4<f32>,
+    @location(2) uv:           vec2<f32>,
+    @location(3) params:       vec4<f32>,
+    @location(4) effect_color: vec4<f32>,
 };
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0)       color:         vec4<f32>,
     @location(1)       uv:            vec2<f32>,
+    @location(2)       params:        vec4<f32>,
+    @location(3)       effect_color:  vec4<f32>,
 };
 
 @group(0) @binding(0) var canvas_texture: texture_2d<f32>;
@@ -19,11 +23,35 @@ fn vs_main(in: VertexInput) -> VertexOutput {
     out.clip_position = vec4<f32>(in.position, 0.0, 1.0);
     out.color         = in.color;
     out.uv            = in.uv;
+    out.params        = in.params;
+    out.effect_color  = in.effect_color;
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Sampled and differentiated before any branch: both need uniform
+    // control flow, and the mode is per-vertex.
     let sample = textureSample(canvas_texture, canvas_sampler, in.uv);
-    return sample * in.color;
+    let aa = max(fwidth(sample.a), 0.0001);
+
+    if (in.params.x < 0.5) {
+        return sample * in.color;
+    }
+
+    // Signed distance field: 0.5 is the glyph edge, and the field reaches
+    // 0 / 1 at the atlas's full spread outside / inside it. `aa` is one
+    // screen pixel in field units, so the edge stays a pixel wide at any
+    // scale. Softness pushes the ramp outward for shadows and glows.
+    let dist = sample.a;
+    let softness = max(in.params.y, aa);
+    let fill = smoothstep(0.5 - softness, 0.5 + aa, dist);
+    let outline = in.params.z;
+    if (outline <= 0.0) {
+        return vec4<f32>(in.color.rgb, in.color.a * fill);
+    }
+    let border = smoothstep(0.5 - outline - aa, 0.5 - outline + aa, dist);
+    let rgb = mix(in.effect_color.rgb, in.color.rgb, fill);
+    let alpha = mix(in.effect_color.a, in.color.a, fill) * border;
+    return vec4<f32>(rgb, alpha);
 }
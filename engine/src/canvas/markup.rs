@@ -0,0 +1,1107 @@
+//! BBCode-style rich-text markup.
+//!
+//! `"Deal [color=#f44][b]12[/b] fire[/color] damage [img=flame]"` parses once
+//! into a [`RichText`], lays out into a [`RichLayout`] through the same
+//! word-wrap rules as plain text, and draws with [`Canvas::rich_text`].
+//!
+//! Tags:
+//!
+//! - `[color=#rgb]`, `#rrggbb`, `#rrggbbaa` (sRGB, as authored in any colour
+//!   picker) or a name: `white`, `black`, `red`, `orange`, `yellow`, `green`,
+//!   `blue`, `indigo`, `violet`.
+//! - `[b]` — bold, emboldened from the regular face.
+//! - `[size=20]` in pixels, or `[size=150%]` of the enclosing size.
+//! - `[font=alias]` — a font registered in [`MarkupAssets`].
+//! - `[img=alias]` — an inline icon, one line box tall. Self-closing.
+//! - `[wave]` and `[shake]` — per-glyph animation, driven by the `time`
+//!   passed to [`Canvas::rich_text`].
+//!
+//! Each opening tag is closed by `[/name]`; tags may close out of order. `[[`
+//! is a literal `[`. Anything else in brackets — an unknown tag, a malformed
+//! value, a close with nothing open — is left in the text as written, so a
+//! `"[E] Interact"` prompt reads the same with or without markup.
+
+use std::collections::HashMap;
+
+use super::{break_words, field_units, Canvas, CanvasVertex, TextAlign, WrapWord};
+use crate::assets::{AssetPack, Color};
+use crate::math::Rng;
+use crate::renderer::TextureId;
+use crate::text::{is_rtl, FontAtlas, FontId, FontMode, FONT_SIZE};
+
+/// Radians per second a `[wave]` travels.
+const WAVE_SPEED: f32 = 6.0;
+/// Phase step between neighbouring glyphs of a `[wave]`, in radians.
+const WAVE_STEP: f32 = 0.6;
+/// Peak `[wave]` displacement, as a fraction of the text size.
+const WAVE_AMPLITUDE: f32 = 0.12;
+/// How many times a second a `[shake]` picks new offsets.
+const SHAKE_RATE: f32 = 20.0;
+/// Peak `[shake]` displacement, as a fraction of the text size.
+const SHAKE_AMPLITUDE: f32 = 0.06;
+
+/// A `[size=..]` value: absolute pixels, or a factor of the enclosing size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RichSize {
+    Px(f32),
+    Scale(f32),
+}
+
+impl RichSize {
+    fn resolve(self, base: f32) -> f32 {
+        match self {
+            RichSize::Px(px) => px,
+            RichSize::Scale(factor) => base * factor,
+        }
+    }
+
+    /// This size nested inside `outer`: a percentage of a pixel size is a
+    /// pixel size, and percentages compound.
+    fn within(self, outer: Option<RichSize>) -> RichSize {
+        match (outer, self) {
+            (Some(RichSize::Px(px)), RichSize::Scale(factor)) => RichSize::Px(px * factor),
+            (Some(RichSize::Scale(outer)), RichSize::Scale(factor)) => {
+                RichSize::Scale(outer * factor)
+            }
+            (_, size) => size,
+        }
+    }
+}
+
+/// The tags in force over one span. `None` fields fall back to whatever the
+/// caller lays the text out with.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RichStyle {
+    pub color: Option<Color>,
+    pub bold: bool,
+    pub size: Option<RichSize>,
+    pub font: Option<String>,
+    pub wave: bool,
+    pub shake: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RichContent {
+    Text(String),
+    /// An `[img=alias]` icon, resolved against [`MarkupAssets`] at layout.
+    Image(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RichSpan {
+    pub content: RichContent,
+    pub style: RichStyle,
+}
+
+/// Parsed markup: styled spans with every tag resolved.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RichText {
+    spans: Vec<RichSpan>,
+}
+
+impl RichText {
+    pub fn parse(markup: &str) -> Self {
+        let mut parser = Parser::default();
+        let mut rest = markup;
+        while let Some(at) = rest.find('[') {
+            parser.text.push_str(&rest[..at]);
+            rest = &rest[at..];
+            if let Some(after) = rest.strip_prefix("[[") {
+                parser.text.push('[');
+                rest = after;
+                continue;
+            }
+            let applied = rest
+                .find(']')
+                .filter(|&end| parse_tag(&rest[1..end]).is_some_and(|markup| parser.apply(markup)));
+            match applied {
+                Some(end) => rest = &rest[end + 1..],
+                // Not markup: the bracket is text, and scanning resumes just
+                // past it so a tag inside (`[a [b]`) still parses.
+                None => {
+                    parser.text.push('[');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        parser.text.push_str(rest);
+        parser.flush();
+        Self {
+            spans: parser.spans,
+        }
+    }
+
+    pub fn spans(&self) -> &[RichSpan] {
+        &self.spans
+    }
+
+    /// The text with every tag stripped and images dropped.
+    pub fn plain_text(&self) -> String {
+        self.spans
+            .iter()
+            .filter_map(|span| match &span.content {
+                RichContent::Text(text) => Some(text.as_str()),
+                RichContent::Image(_) => None,
+            })
+            .collect()
+    }
+
+    /// Whether nothing here needs the rich path — no styled spans and no
+    /// images — so [`plain_text`](Self::plain_text) draws identically
+    /// through the ordinary text calls.
+    pub fn is_plain(&self) -> bool {
+        self.spans.iter().all(|span| {
+            span.style == RichStyle::default() && matches!(span.content, RichContent::Text(_))
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tag {
+    Color(Color),
+    Bold,
+    Size(RichSize),
+    Font(String),
+    Wave,
+    Shake,
+}
+
+impl Tag {
+    fn name(&self) -> &'static str {
+        match self {
+            Tag::Color(_) => "color",
+            Tag::Bold => "b",
+            Tag::Size(_) => "size",
+            Tag::Font(_) => "font",
+            Tag::Wave => "wave",
+            Tag::Shake => "shake",
+        }
+    }
+}
+
+enum Markup {
+    Open(Tag),
+    Close(String),
+    Image(String),
+}
+
+/// One bracketed tag body (between `[` and `]`), or `None` if it is not
+/// markup this parser knows.
+fn parse_tag(body: &str) -> Option<Markup> {
+    if let Some(name) = body.strip_prefix('/') {
+        return Some(Markup::Close(name.to_string()));
+    }
+    let (name, value) = match body.split_once('=') {
+        Some((name, value)) => (name, Some(value.trim())),
+        None => (body, None),
+    };
+    let tag = match (name, value) {
+        ("b", None) => Tag::Bold,
+        ("wave", None) => Tag::Wave,
+        ("shake", None) => Tag::Shake,
+        ("color", Some(value)) => Tag::Color(parse_color(value)?),
+        ("size", Some(value)) => Tag::Size(parse_size(value)?),
+        ("font", Some(alias)) if !alias.is_empty() => Tag::Font(alias.to_string()),
+        ("img", Some(alias)) if !alias.is_empty() => return Some(Markup::Image(alias.to_string())),
+        _ => return None,
+    };
+    Some(Markup::Open(tag))
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    let Some(hex) = value.strip_prefix('#') else {
+        return match value {
+            "white" => Some(Color::WHITE),
+            "black" => Some(Color::BLACK),
+            "red" => Some(Color::RED),
+            "orange" => Some(Color::ORANGE),
+            "yellow" => Some(Color::YELLOW),
+            "green" => Some(Color::GREEN),
+            "blue" => Some(Color::BLUE),
+            "indigo" => Some(Color::INDIGO),
+            "violet" => Some(Color::VIOLET),
+            _ => None,
+        };
+    };
+    if !hex.is_ascii() {
+        return None;
+    }
+    let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok();
+    let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let (r, g, b, a) = match hex.len() {
+        3 | 4 => {
+            let short = |i: usize| digit(i).map(|d| d * 17);
+            let a = if hex.len() == 4 { short(3)? } else { 255 };
+            (short(0)?, short(1)?, short(2)?, a)
+        }
+        6 | 8 => {
+            let a = if hex.len() == 8 { pair(6)? } else { 255 };
+            (pair(0)?, pair(2)?, pair(4)?, a)
+        }
+        _ => return None,
+    };
+    Some(Color::from_srgb8(r, g, b, a))
+}
+
+fn parse_size(value: &str) -> Option<RichSize> {
+    let (number, percent) = match value.strip_suffix('%') {
+        Some(number) => (number, true),
+        None => (value, false),
+    };
+    let number: f32 = number.trim().parse().ok()?;
+    if !number.is_finite() || number <= 0.0 {
+        return None;
+    }
+    Some(if percent {
+        RichSize::Scale(number / 100.0)
+    } else {
+        RichSize::Px(number)
+    })
+}
+
+#[derive(Default)]
+struct Parser {
+    spans: Vec<RichSpan>,
+    open: Vec<Tag>,
+    text: String,
+}
+
+impl Parser {
+    fn style(&self) -> RichStyle {
+        let mut style = RichStyle::default();
+        for tag in &self.open {
+            match tag {
+                Tag::Color(color) => style.color = Some(*color),
+                Tag::Bold => style.bold = true,
+                Tag::Size(size) => style.size = Some(size.within(style.size)),
+                Tag::Font(alias) => style.font = Some(alias.clone()),
+                Tag::Wave => style.wave = true,
+                Tag::Shake => style.shake = true,
+            }
+        }
+        style
+    }
+
+    /// Apply one tag, or return `false` if it does nothing here (a close with
+    /// no matching open) and should stay in the text.
+    fn apply(&mut self, markup: Markup) -> bool {
+        match markup {
+            Markup::Open(tag) => {
+                self.flush();
+                self.open.push(tag);
+            }
+            Markup::Close(name) => {
+                let Some(index) = self.open.iter().rposition(|tag| tag.name() == name) else {
+                    return false;
+                };
+                self.flush();
+                self.open.remove(index);
+            }
+            Markup::Image(alias) => {
+                self.flush();
+                self.spans.push(RichSpan {
+                    content: RichContent::Image(alias),
+                    style: self.style(),
+                });
+            }
+        }
+        true
+    }
+
+    /// End the current text span. A span styled like the one before it
+    /// joins it, so `a[b][/b]b` is one span, not two.
+    fn flush(&mut self) {
+        if self.text.is_empty() {
+            return;
+        }
+        let text = std::mem::take(&mut self.text);
+        let style = self.style();
+        if let Some(RichSpan {
+            content: RichContent::Text(last),
+            style: last_style,
+        }) = self.spans.last_mut()
+        {
+            if *last_style == style {
+                last.push_str(&text);
+                return;
+            }
+        }
+        self.spans.push(RichSpan {
+            content: RichContent::Text(text),
+            style,
+        });
+    }
+}
+
+/// An image markup can place inline: a texture, the part of it to show, and
+/// that part's width over its height.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarkupImage {
+    pub texture: TextureId,
+    pub uv_rect: [f32; 4],
+    pub aspect: f32,
+}
+
+impl MarkupImage {
+    /// A whole square texture.
+    pub fn new(texture: TextureId) -> Self {
+        Self {
+            texture,
+            uv_rect: [0.0, 0.0, 1.0, 1.0],
+            aspect: 1.0,
+        }
+    }
+}
+
+/// The aliases `[font=..]` and `[img=..]` resolve against.
+///
+/// The engine keeps one of these, filled from every asset manifest it loads,
+/// so a manifest's `fonts` and `textures` aliases work in markup as-is.
+#[derive(Debug, Clone, Default)]
+pub struct MarkupAssets {
+    fonts: HashMap<String, FontId>,
+    images: HashMap<String, MarkupImage>,
+}
+
+impl MarkupAssets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_font(mut self, alias: impl Into<String>, font: FontId) -> Self {
+        self.insert_font(alias, font);
+        self
+    }
+
+    /// A whole square texture as an icon.
+    pub fn with_image(mut self, alias: impl Into<String>, texture: TextureId) -> Self {
+        self.insert_image(alias, MarkupImage::new(texture));
+        self
+    }
+
+    /// Part of a texture as an icon — a sprite-sheet cell, say. `aspect` is
+    /// the region's width over its height in pixels.
+    pub fn with_image_region(
+        mut self,
+        alias: impl Into<String>,
+        texture: TextureId,
+        uv_rect: [f32; 4],
+        aspect: f32,
+    ) -> Self {
+        self.insert_image(
+            alias,
+            MarkupImage {
+                texture,
+                uv_rect,
+                aspect,
+            },
+        );
+        self
+    }
+
+    pub fn insert_font(&mut self, alias: impl Into<String>, font: FontId) {
+        self.fonts.insert(alias.into(), font);
+    }
+
+    pub fn insert_image(&mut self, alias: impl Into<String>, image: MarkupImage) {
+        self.images.insert(alias.into(), image);
+    }
+
+    /// Register every font and texture in `pack` under its manifest alias.
+    pub fn add_pack(&mut self, pack: &AssetPack) {
+        for (alias, font) in pack.font_aliases() {
+            self.insert_font(alias, font.id);
+        }
+        for (alias, texture) in pack.texture_aliases() {
+            self.insert_image(
+                alias,
+                MarkupImage {
+                    texture: texture.id,
+                    uv_rect: [0.0, 0.0, 1.0, 1.0],
+                    aspect: texture.width as f32 / texture.height.max(1) as f32,
+                },
+            );
+        }
+    }
+
+    pub fn font(&self, alias: &str) -> Option<FontId> {
+        self.fonts.get(alias).copied()
+    }
+
+    pub fn image(&self, alias: &str) -> Option<MarkupImage> {
+        self.images.get(alias).copied()
+    }
+}
+
+/// [`RichText`] broken into positioned lines, ready to draw — and to size a
+/// box around before drawing, which is why the two are separate steps.
+///
+/// Each run is shaped, so Arabic joins and a Hebrew run reads right to left
+/// inside itself, but the runs and words of a line are placed left to right.
+/// Markup mixing directions within one line lays out in logical order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RichLayout {
+    lines: Vec<RichLine>,
+    width: f32,
+    height: f32,
+    /// The text reads right to left, for resolving [`TextAlign::Start`].
+    rtl: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RichLine {
+    width: f32,
+    height: f32,
+    /// Line top to the baseline every item on the line shares.
+    ascent: f32,
+    items: Vec<RichItem>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RichItem {
+    /// Left edge, from the line's start.
+    x: f32,
+    ascent: f32,
+    kind: RichItemKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum RichItemKind {
+    Text(RichRun),
+    Image {
+        image: MarkupImage,
+        width: f32,
+        height: f32,
+        color: Color,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RichRun {
+    text: String,
+    font: FontId,
+    size: f32,
+    color: Color,
+    bold: bool,
+    wave: bool,
+    shake: bool,
+    /// This run's first glyph counted from the start of the whole text, so a
+    /// wave rolls on across a colour change instead of restarting.
+    first_glyph: usize,
+}
+
+impl RichRun {
+    /// The animated offset of the run's `glyph`th glyph at `time`.
+    fn jitter(&self, glyph: usize, time: f32) -> (f32, f32) {
+        let mut offset = (0.0, 0.0);
+        let index = self.first_glyph + glyph;
+        if self.wave {
+            let phase = time * WAVE_SPEED - index as f32 * WAVE_STEP;
+            offset.1 += phase.sin() * self.size * WAVE_AMPLITUDE;
+        }
+        if self.shake {
+            let tick = (time * SHAKE_RATE).max(0.0) as u64;
+            let mut rng = Rng::new(((index as u64) << 32) | tick);
+            let amplitude = self.size * SHAKE_AMPLITUDE;
+            offset.0 += (rng.f32() * 2.0 - 1.0) * amplitude;
+            offset.1 += (rng.f32() * 2.0 - 1.0) * amplitude;
+        }
+        offset
+    }
+}
+
+/// Extra width a `[b]` run is drawn with: one pixel at UI sizes, growing
+/// with the text so large headings still read as bold.
+fn embolden(size: f32) -> f32 {
+    (size / 24.0).max(1.0)
+}
+
+impl RichLayout {
+    /// The widest line and the height of every line stacked.
+    pub fn size(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// This layout uniformly scaled — for a widget animating its scale,
+    /// where re-wrapping at the animated size would reflow mid-animation.
+    pub fn scaled(&self, factor: f32) -> Self {
+        let mut scaled = self.clone();
+        scaled.width *= factor;
+        scaled.height *= factor;
+        for line in &mut scaled.lines {
+            line.width *= factor;
+            line.height *= factor;
+            line.ascent *= factor;
+            for item in &mut line.items {
+                item.x *= factor;
+                item.ascent *= factor;
+                match &mut item.kind {
+                    RichItemKind::Text(run) => run.size *= factor,
+                    RichItemKind::Image { width, height, .. } => {
+                        *width *= factor;
+                        *height *= factor;
+                    }
+                }
+            }
+        }
+        scaled
+    }
+
+    /// This layout with every run and image's alpha multiplied by `alpha` —
+    /// the same as laying it out again at a colour that much fainter, for a
+    /// widget fading in or out.
+    pub fn faded(&self, alpha: f32) -> Self {
+        let mut faded = self.clone();
+        for item in faded.lines.iter_mut().flat_map(|line| &mut line.items) {
+            match &mut item.kind {
+                RichItemKind::Text(run) => run.color.a *= alpha,
+                RichItemKind::Image { color, .. } => color.a *= alpha,
+            }
+        }
+        faded
+    }
+}
+
+/// One piece of a word: a slice of one span, or an image.
+struct Fragment {
+    kind: RichItemKind,
+    width: f32,
+    line_height: f32,
+    ascent: f32,
+}
+
+#[derive(Default)]
+struct Word {
+    fragments: Vec<Fragment>,
+    space_before: f32,
+}
+
+impl Word {
+    fn width(&self) -> f32 {
+        self.fragments.iter().map(|fragment| fragment.width).sum()
+    }
+}
+
+/// The width, line height and ascent of a run in a font at a size.
+type RunMetrics<'a> = dyn Fn(FontId, &str, f32) -> (f32, f32, f32) + 'a;
+
+/// Lay `text` out in `font` at `size` and `color`, wrapped to `max_width`
+/// (`f32::INFINITY` for a single unbroken line per paragraph). `metrics`
+/// gives the width, line height and ascent of a run in a font at a size.
+///
+/// Words break exactly where [`wrap_text_measured`](super::wrap_text_measured)
+/// would break the same words: `break_words` makes the decision for both.
+/// Lines mixing sizes share one baseline and grow to fit their tallest item.
+pub(crate) fn layout(
+    text: &RichText,
+    font: FontId,
+    size: f32,
+    color: Color,
+    max_width: f32,
+    assets: &MarkupAssets,
+    metrics: &RunMetrics,
+) -> RichLayout {
+    let mut paragraphs: Vec<Vec<Word>> = vec![vec![Word::default()]];
+    let mut glyphs = 0;
+
+    for span in &text.spans {
+        let style = &span.style;
+        let span_size = style.size.map_or(size, |s| s.resolve(size));
+        let span_font = style
+            .font
+            .as_deref()
+            .and_then(|alias| assets.font(alias))
+            .unwrap_or(font);
+        // A span's own colour still fades with the text it sits in.
+        let span_color = match style.color {
+            Some(own) => Color {
+                a: own.a * color.a,
+                ..own
+            },
+            None => color,
+        };
+        let (_, line_height, ascent) = metrics(span_font, "", span_size);
+
+        let text = match &span.content {
+            RichContent::Text(text) => text,
+            RichContent::Image(alias) => {
+                // An alias nothing registered draws nothing, the way a font
+                // alias nothing registered falls back to the base face.
+                if let Some(image) = assets.image(alias) {
+                    let word = paragraphs.last_mut().unwrap().last_mut().unwrap();
+                    word.fragments.push(Fragment {
+                        kind: RichItemKind::Image {
+                            image,
+                            width: line_height * image.aspect,
+                            height: line_height,
+                            color: Color {
+                                a: color.a,
+                                ..Color::WHITE
+                            },
+                        },
+                        width: line_height * image.aspect,
+                        line_height,
+                        ascent,
+                    });
+                }
+                continue;
+            }
+        };
+
+        let push_run = |paragraphs: &mut Vec<Vec<Word>>, run: &str, glyphs: &mut usize| {
+            if run.is_empty() {
+                return;
+            }
+            let mut width = metrics(span_font, run, span_size).0;
+            if style.bold {
+                width += embolden(span_size);
+            }
+            let word = paragraphs.last_mut().unwrap().last_mut().unwrap();
+            word.fragments.push(Fragment {
+                kind: RichItemKind::Text(RichRun {
+                    text: run.to_string(),
+                    font: span_font,
+                    size: span_size,
+                    color: span_color,
+                    bold: style.bold,
+                    wave: style.wave,
+                    shake: style.shake,
+                    first_glyph: *glyphs,
+                }),
+                width,
+                line_height,
+                ascent,
+            });
+            *glyphs += run.chars().filter(|c| !c.is_control()).count();
+        };
+
+        for (p, paragraph) in text.split('\n').enumerate() {
+            if p > 0 {
+                paragraphs.push(vec![Word::default()]);
+            }
+            for (w, run) in paragraph.split(' ').enumerate() {
+                if w > 0 {
+                    let space_before = metrics(span_font, " ", span_size).0;
+                    paragraphs.last_mut().unwrap().push(Word {
+                        fragments: Vec::new(),
+                        space_before,
+                    });
+                }
+                push_run(&mut paragraphs, run, &mut glyphs);
+            }
+        }
+    }
+
+    let (_, base_height, base_ascent) = metrics(font, "", size);
+    let mut lines = Vec::new();
+    for words in &paragraphs {
+        if words.len() == 1 && words[0].fragments.is_empty() {
+            lines.push(RichLine {
+                width: 0.0,
+                height: base_height,
+                ascent: base_ascent,
+                items: Vec::new(),
+            });
+            continue;
+        }
+        let measured: Vec<WrapWord> = words
+            .iter()
+            .map(|word| WrapWord {
+                width: word.width(),
+                space_before: word.space_before,
+                empty: word.fragments.is_empty(),
+            })
+            .collect();
+        for range in break_words(&measured, max_width) {
+            let mut x = 0.0;
+            let mut items = Vec::new();
+            let (mut ascent, mut descent) = (0.0_f32, 0.0_f32);
+            for (i, word) in words[range].iter().enumerate() {
+                if i > 0 {
+                    x += word.space_before;
+                }
+                for fragment in &word.fragments {
+                    ascent = ascent.max(fragment.ascent);
+                    descent = descent.max(fragment.line_height - fragment.ascent);
+                    items.push(RichItem {
+                        x,
+                        ascent: fragment.ascent,
+                        kind: fragment.kind.clone(),
+                    });
+                    x += fragment.width;
+                }
+            }
+            if items.is_empty() {
+                (ascent, descent) = (base_ascent, base_height - base_ascent);
+            }
+            lines.push(RichLine {
+                width: x,
+                height: ascent + descent,
+                ascent,
+                items,
+            });
+        }
+    }
+    if lines.is_empty() {
+        lines.push(RichLine {
+            width: 0.0,
+            height: base_height,
+            ascent: base_ascent,
+            items: Vec::new(),
+        });
+    }
+
+    RichLayout {
+        width: lines.iter().map(|line| line.width).fold(0.0, f32::max),
+        height: lines.iter().map(|line| line.height).sum(),
+        lines,
+        rtl: is_rtl(&text.plain_text()),
+    }
+}
+
+/// Width, line height and ascent of `run` in `atlas`, or in the builtin face
+/// when there is no atlas to ask.
+fn atlas_metrics(atlas: Option<&FontAtlas>, run: &str, size: f32) -> (f32, f32, f32) {
+    match atlas {
+        Some(atlas) => {
+            let (width, height) = atlas.measure_text(run, size);
+            (width, height, atlas.baseline_below_top(0.0, size).abs())
+        }
+        None => {
+            let (width, height) = crate::text::measure_builtin_text(run, size);
+            let ascent = crate::text::builtin_baseline_below_top(0.0, size).abs();
+            (width, height, ascent)
+        }
+    }
+}
+
+impl RichText {
+    /// [`Canvas::layout_rich_text`] against a font table directly — the
+    /// engine's, for a caller sizing widgets before it has a canvas.
+    pub(crate) fn layout_in(
+        &self,
+        fonts: &[FontAtlas],
+        font: FontId,
+        size: f32,
+        color: Color,
+        max_width: f32,
+        assets: &MarkupAssets,
+    ) -> RichLayout {
+        layout(
+            self,
+            font,
+            size,
+            color,
+            max_width,
+            assets,
+            &|font, run, size| atlas_metrics(fonts.get(font.0).or(fonts.first()), run, size),
+        )
+    }
+}
+
+impl Canvas {
+    /// Lay out parsed markup in `font` at `size` and `color` — the style any
+    /// untagged text takes — wrapped to `max_width`.
+    ///
+    /// Measures without a bound atlas too, so a layout pass can size a rich
+    /// block before a frame begins.
+    pub fn layout_rich_text(
+        &self,
+        font: FontId,
+        text: &RichText,
+        size: f32,
+        color: Color,
+        max_width: f32,
+        assets: &MarkupAssets,
+    ) -> RichLayout {
+        layout(
+            text,
+            font,
+            size,
+            color,
+            max_width,
+            assets,
+            &|font, run, size| atlas_metrics(self.font_atlas_opt(font), run, size),
+        )
+    }
+
+    /// Draw a [`RichLayout`] with its first line box's top at `y`, every line
+    /// aligned on `x` as [`text_block`](Self::text_block) aligns its lines.
+    /// `time` drives `[wave]` and `[shake]`; pass the engine's running time.
+    pub fn rich_text(&mut self, x: f32, y: f32, layout: &RichLayout, align: TextAlign, time: f32) {
+        if !self.can_draw_text() {
+            return;
+        }
+        let align = align.for_direction(layout.rtl);
+        let mut top = y;
+        for line in &layout.lines {
+            let baseline = top - line.ascent;
+            let left = x + align.offset(line.width);
+            for item in &line.items {
+                let item_x = left + item.x;
+                match &item.kind {
+                    RichItemKind::Text(run) => self.rich_run(item_x, baseline, run, time),
+                    RichItemKind::Image {
+                        image,
+                        width,
+                        height,
+                        color,
+                    } => {
+                        let bottom = baseline + item.ascent - height;
+                        self.image_region(
+                            image.texture,
+                            item_x,
+                            bottom,
+                            *width,
+                            *height,
+                            image.uv_rect,
+                            *color,
+                        );
+                    }
+                }
+            }
+            top -= line.height;
+        }
+    }
+
+    /// One run's glyphs from `x` on `baseline`, each nudged by its animation.
+    fn rich_run(&mut self, x: f32, baseline: f32, run: &RichRun, time: f32) {
+        // SAFETY-adjacent: re-resolved through a pointer, as in
+        // `text_aligned_in`, so the atlas isn't borrowed across `&mut self`.
+        let atlas = self.font_atlas(run.font) as *const FontAtlas;
+        let atlas = unsafe { &*atlas };
+        let font = atlas.id().0;
+        let scale = run.size / FONT_SIZE;
+        let sdf = atlas.mode() == FontMode::Sdf;
+        let bold = if run.bold { embolden(run.size) } else { 0.0 };
+        // Bold on a distance field is an outline in the text's own colour;
+        // on a bitmap it is the glyph struck twice, `bold` pixels apart.
+        let template = CanvasVertex {
+            color: run.color.to_array(),
+            params: [
+                if sdf { 1.0 } else { 0.0 },
+                0.0,
+                if sdf {
+                    field_units(bold / 2.0, run.size)
+                } else {
+                    0.0
+                },
+                0.0,
+            ],
+            effect_color: run.color.to_array(),
+            ..CanvasVertex::PLAIN
+        };
+
+        let mut pen_x = x;
+        if let Some(glyphs) = atlas.shape(&run.text) {
+            // Animated per cluster, not per glyph, so a mark moves with the
+            // letter it sits on.
+            let mut cluster = None;
+            let mut glyph = 0;
+            for shaped in glyphs.iter() {
+                if cluster.is_some_and(|cluster| cluster != shaped.cluster) {
+                    glyph += 1;
+                }
+                cluster = Some(shaped.cluster);
+                let entry = atlas.shaped_glyph(shaped);
+                if entry.width_px > 0.0 {
+                    let (dx, dy) = run.jitter(glyph, time);
+                    let gx = pen_x + shaped.x_offset * scale + dx;
+                    let gy = baseline + shaped.y_offset * scale + dy;
+                    self.glyph_quad(font, &entry, gx, gy, scale, template);
+                    if bold > 0.0 && !sdf {
+                        self.glyph_quad(font, &entry, gx + bold, gy, scale, template);
+                    }
+                }
+                pen_x += shaped.advance * scale;
+            }
+            return;
+        }
+        let mut prev = None;
+        let mut glyph = 0;
+        for ch in run.text.chars() {
+            let Some(entry) = atlas.glyph(ch) else {
+                continue;
+            };
+            if let Some(prev) = &prev {
+                pen_x += atlas.kerning(prev, &entry) * scale;
+            }
+            if entry.width_px > 0.0 {
+                let (dx, dy) = run.jitter(glyph, time);
+                let (gx, gy) = (pen_x + dx, baseline + dy);
+                self.glyph_quad(font, &entry, gx, gy, scale, template);
+                if bold > 0.0 && !sdf {
+                    self.glyph_quad(font, &entry, gx + bold, gy, scale, template);
+                }
+            }
+            pen_x += entry.advance * scale;
+            prev = Some(entry);
+            glyph += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_span(text: &str, style: RichStyle) -> RichSpan {
+        RichSpan {
+            content: RichContent::Text(text.to_string()),
+            style,
+        }
+    }
+
+    #[test]
+    fn tags_nest_close_out_of_order_and_unknown_brackets_stay_text() {
+        let rich = RichText::parse("[E] [color=#f00]hot [b]and[/color] bold[/b] [[x]");
+        let red = Color::from_srgb8(255, 0, 0, 255);
+        assert_eq!(
+            rich.spans(),
+            &[
+                text_span("[E] ", RichStyle::default()),
+                text_span(
+                    "hot ",
+                    RichStyle {
+                        color: Some(red),
+                        ..RichStyle::default()
+                    }
+                ),
+                text_span(
+                    "and",
+                    RichStyle {
+                        color: Some(red),
+                        bold: true,
+                        ..RichStyle::default()
+                    }
+                ),
+                text_span(
+                    " bold",
+                    RichStyle {
+                        bold: true,
+                        ..RichStyle::default()
+                    }
+                ),
+                text_span(" [x]", RichStyle::default()),
+            ]
+        );
+        assert_eq!(rich.plain_text(), "[E] hot and bold [x]");
+
+        // Malformed values and stray closes are text, not silently eaten.
+        let rich = RichText::parse("[color=#zz]a[/wave][size=-3]");
+        assert!(rich.is_plain());
+        assert_eq!(rich.plain_text(), "[color=#zz]a[/wave][size=-3]");
+    }
+
+    #[test]
+    fn sizes_compound_and_images_carry_the_style_they_sit_in() {
+        let rich = RichText::parse("[size=20][size=150%]big [img=coin][/size][/size]");
+        let big = RichStyle {
+            size: Some(RichSize::Px(30.0)),
+            ..RichStyle::default()
+        };
+        assert_eq!(
+            rich.spans(),
+            &[
+                text_span("big ", big.clone()),
+                RichSpan {
+                    content: RichContent::Image("coin".to_string()),
+                    style: big,
+                },
+            ]
+        );
+        assert!(!rich.is_plain());
+        assert_eq!(
+            parse_color("#0f08"),
+            Some(Color::from_srgb8(0, 255, 0, 136))
+        );
+    }
+
+    #[test]
+    fn plain_markup_wraps_exactly_like_wrap_text() {
+        let canvas = Canvas::for_test((800, 600));
+        let text = "the quick brown fox jumps over  the lazy dog\n\nagain";
+        let measure = |run: &str| crate::text::measure_builtin_text(run, 16.0).0;
+        let expected = super::super::wrap_text_measured(text, 90.0, measure);
+
+        let layout = canvas.layout_rich_text(
+            FontId::DEFAULT,
+            &RichText::parse(text),
+            16.0,
+            Color::WHITE,
+            90.0,
+            &MarkupAssets::new(),
+        );
+        assert_eq!(layout.line_count(), expected.len());
+        for (line, expected) in layout.lines.iter().zip(&expected) {
+            assert!(
+                (line.width - measure(expected)).abs() < 0.01,
+                "{expected:?}"
+            );
+        }
+        let line_height = canvas.line_height_in(FontId::DEFAULT, 16.0);
+        assert!((layout.size().1 - line_height * expected.len() as f32).abs() < 0.01);
+    }
+
+    #[test]
+    fn mixed_sizes_share_a_baseline_and_icons_take_the_line_box() {
+        let canvas = Canvas::for_test((800, 600));
+        let assets = MarkupAssets::new().with_image("coin", TextureId(3));
+        let layout = canvas.layout_rich_text(
+            FontId::DEFAULT,
+            &RichText::parse("x [size=32]Y[/size] [img=coin][img=missing]"),
+            16.0,
+            Color::WHITE,
+            f32::INFINITY,
+            &assets,
+        );
+        let line = &layout.lines[0];
+        let big_ascent = crate::text::builtin_baseline_below_top(0.0, 32.0).abs();
+        assert!((line.ascent - big_ascent).abs() < 0.01);
+        assert!((line.height - canvas.line_height_in(FontId::DEFAULT, 32.0)).abs() < 0.01);
+
+        // "x", "Y" and one icon: the unregistered alias lays out nothing.
+        assert_eq!(line.items.len(), 3);
+        let RichItemKind::Image { width, height, .. } = line.items[2].kind else {
+            panic!("expected the coin icon");
+        };
+        assert_eq!(width, height);
+        assert!((height - canvas.line_height_in(FontId::DEFAULT, 16.0)).abs() < 0.01);
+        assert!((line.width - (line.items[2].x + width)).abs() < 0.01);
+    }
+
+    #[test]
+    fn fading_a_layout_matches_laying_it_out_fainter() {
+        let canvas = Canvas::for_test((800, 600));
+        let assets = MarkupAssets::new().with_image("coin", TextureId(3));
+        let rich = RichText::parse("[color=#f448]hot[/color] pot [img=coin]");
+        let layout = |color| {
+            canvas.layout_rich_text(FontId::DEFAULT, &rich, 16.0, color, f32::INFINITY, &assets)
+        };
+        assert_eq!(
+            layout(Color::WHITE).faded(0.5),
+            layout(Color::new(1.0, 1.0, 1.0, 0.5))
+        );
+    }
+
+    #[test]
+    fn wave_phase_runs_on_across_spans() {
+        let run = |first_glyph| RichRun {
+            text: "ab".to_string(),
+            font: FontId::DEFAULT,
+            size: 20.0,
+            color: Color::WHITE,
+            bold: false,
+            wave: true,
+            shake: false,
+            first_glyph,
+        };
+        assert_eq!(run(0).jitter(2, 0.5), run(2).jitter(0, 0.5));
+        assert_ne!(run(0).jitter(0, 0.5), run(0).jitter(1, 0.5));
+        assert_eq!(run(0).jitter(0, 0.5).0, 0.0);
+    }
+}
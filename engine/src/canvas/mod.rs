@@ -1,12 +1,97 @@
 use crate::assets::Color;
-use crate::renderer::TextureId;
-use crate::text::{FontAtlas, FontId, ATLAS_SIZE, FONT_SIZE};
+use crate::renderer::{AtlasRegion, TextureId};
+use crate::text::{
+    is_rtl, needs_shaping, FontAtlas, FontId, FontMode, GlyphEntry, ATLAS_SIZE, FONT_SIZE,
+    SDF_SPREAD,
+};
+
+mod markup;
+
+pub use markup::{
+    MarkupAssets, MarkupImage, RichContent, RichLayout, RichSize, RichSpan, RichStyle, RichText,
+};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TextAlign {
     Left,
     Center,
     Right,
+    /// The side the text starts reading from: left for English, right for
+    /// Arabic or Hebrew. Decided per draw by the text's first strongly
+    /// directional character, like CSS `text-align: start`.
+    Start,
+    /// The side the text finishes on; the opposite of [`Start`](Self::Start).
+    End,
+}
+
+impl TextAlign {
+    /// `Start` and `End` made physical for `text`. The other three are
+    /// already physical and come back unchanged.
+    pub fn resolve(self, text: &str) -> TextAlign {
+        match self {
+            TextAlign::Start | TextAlign::End => self.for_direction(is_rtl(text)),
+            physical => physical,
+        }
+    }
+
+    /// `Start` and `End` made physical for a known direction.
+    pub(crate) fn for_direction(self, rtl: bool) -> TextAlign {
+        match (self, rtl) {
+            (TextAlign::Start, false) | (TextAlign::End, true) => TextAlign::Left,
+            (TextAlign::Start, true) | (TextAlign::End, false) => TextAlign::Right,
+            (physical, _) => physical,
+        }
+    }
+
+    /// How far left of its anchor a run `width` wide starts. `Start` and
+    /// `End` read as left-to-right here; [`resolve`](Self::resolve) them
+    /// against the text first.
+    pub(crate) fn offset(self, width: f32) -> f32 {
+        match self {
+            TextAlign::Left | TextAlign::Start => 0.0,
+            TextAlign::Center => -width / 2.0,
+            TextAlign::Right | TextAlign::End => -width,
+        }
+    }
+}
+
+/// Decorations for one [`Canvas::text_with_effects`] call.
+///
+/// Sizes are pixels at the drawn text size. Outline width, shadow blur and
+/// glow radius are capped by how far the atlas's distance field reaches —
+/// 8px at the 48px raster size, so 4px on 24px text.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TextEffects {
+    outline: Option<(f32, Color)>,
+    shadow: Option<(f32, f32, f32, Color)>,
+    glow: Option<(f32, Color)>,
+}
+
+impl TextEffects {
+    pub const NONE: TextEffects = TextEffects {
+        outline: None,
+        shadow: None,
+        glow: None,
+    };
+
+    /// A border `width` pixels wide around every glyph. SDF fonts only.
+    pub fn with_outline(mut self, width: f32, color: Color) -> Self {
+        self.outline = Some((width, color));
+        self
+    }
+
+    /// A copy of the run offset by `(dx, dy)` — canvas space, so a negative
+    /// `dy` drops it below — and softened by `blur` pixels on SDF fonts.
+    pub fn with_shadow(mut self, dx: f32, dy: f32, blur: f32, color: Color) -> Self {
+        self.shadow = Some((dx, dy, blur, color));
+        self
+    }
+
+    /// A soft halo fading out over `radius` pixels. SDF fonts only.
+    pub fn with_glow(mut self, radius: f32, color: Color) -> Self {
+        self.glow = Some((radius, color));
+        self
+    }
 }
 
 #[repr(C)]
@@ -15,9 +100,27 @@ pub struct CanvasVertex {
     pub position: [f32; 2],
     pub color: [f32; 4],
     pub uv: [f32; 2],
+    /// How the fragment shader reads the texture: `[mode, softness, outline,
+    /// _]`. Mode `0` is a plain tinted sample — everything but SDF text. Mode
+    /// `1` treats alpha as a signed distance field; `softness` widens the
+    /// edge outward and `outline` grows a border in `effect_color`, both in
+    /// distance units (`0.5` is the field's full spread).
+    pub params: [f32; 4],
+    /// The outline colour for SDF text. Unused in mode `0`.
+    pub effect_color: [f32; 4],
 }
 
 impl CanvasVertex {
+    /// Every field past `uv` zeroed: a plain tinted sample. Spread it into a
+    /// literal (`..CanvasVertex::PLAIN`) for anything that isn't SDF text.
+    pub const PLAIN: CanvasVertex = CanvasVertex {
+        position: [0.0; 2],
+        color: [0.0; 4],
+        uv: [0.0; 2],
+        params: [0.0; 4],
+        effect_color: [0.0; 4],
+    };
+
     pub fn desc() -> wgpu::VertexBufferLayout<'static> {
         wgpu::VertexBufferLayout {
             array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
@@ -38,6 +141,16 @@ impl CanvasVertex {
                     shader_location: 2,
                     format: wgpu::VertexFormat::Float32x2,
                 },
+                wgpu::VertexAttribute {
+                    offset: 32,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: 48,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
             ],
         }
     }
@@ -49,7 +162,9 @@ const WHITE_UV: [f32; 2] = [1.0 / ATLAS_SIZE as f32, 1.0 / ATLAS_SIZE as f32];
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum DrawTexture {
-    Font(usize),
+    /// A font id and a page of its glyph cache. Page 0 of every font carries
+    /// the white texel solid shapes sample.
+    Font(usize, usize),
     Texture(usize),
 }
 
@@ -82,6 +197,11 @@ pub struct Canvas {
     /// drifts by half the accumulated tracking, and threading one more `f32`
     /// through every signature to guarantee that is a much larger diff than
     /// setting it once around the call.
+    ///
+    /// Shaped text (Arabic, Devanagari, anything
+    /// [`needs_shaping`](crate::text::needs_shaping)) ignores it: spacing out
+    /// joined or reordered letters breaks them apart, which is also why CSS
+    /// leaves cursive scripts unspaced.
     tracking: f32,
 }
 
@@ -101,7 +221,7 @@ impl Canvas {
             screen_size,
             clip_stack: Vec::new(),
             segment_start: 0,
-            current_texture: DrawTexture::Font(0),
+            current_texture: DrawTexture::Font(0, 0),
             atlas,
             fonts,
             tracking: 0.0,
@@ -126,18 +246,18 @@ impl Canvas {
     /// except the last, so a single glyph and an empty string are unaffected
     /// and a trailing gap never pushes right-aligned text off its anchor.
     ///
-    /// Counts the same glyphs the draw loop does — chars outside the atlas's
-    /// ASCII range are skipped there, so counting them here would measure
-    /// wider than it paints.
+    /// Counts the same glyphs the draw loop does — control characters are
+    /// skipped there, so counting them here would measure wider than it
+    /// paints. Everything else draws, if only as the font's `.notdef` box.
     fn tracking_width(&self, text: &str) -> f32 {
         Self::tracking_width_of(text, self.tracking)
     }
 
     fn tracking_width_of(text: &str, tracking: f32) -> f32 {
-        if tracking == 0.0 {
+        if tracking == 0.0 || needs_shaping(text) {
             return 0.0;
         }
-        let glyphs = text.chars().filter(|c| (*c as usize) < 128).count();
+        let glyphs = text.chars().filter(|c| !c.is_control()).count();
         tracking * (glyphs.saturating_sub(1)) as f32
     }
 
@@ -299,7 +419,7 @@ impl Canvas {
     }
 
     fn set_font(&mut self, font_id: usize) {
-        self.set_texture(DrawTexture::Font(font_id));
+        self.set_texture(DrawTexture::Font(font_id, 0));
     }
 
     fn set_texture(&mut self, texture: DrawTexture) {
@@ -340,6 +460,7 @@ impl Canvas {
                     position,
                     color: c,
                     uv,
+                    ..CanvasVertex::PLAIN
                 });
             }
         };
@@ -433,21 +554,25 @@ impl Canvas {
             position: [x0, y0],
             color: c,
             uv,
+            ..CanvasVertex::PLAIN
         };
         let v1 = CanvasVertex {
             position: [x1, y0],
             color: c,
             uv,
+            ..CanvasVertex::PLAIN
         };
         let v2 = CanvasVertex {
             position: [x1, y1],
             color: c,
             uv,
+            ..CanvasVertex::PLAIN
         };
         let v3 = CanvasVertex {
             position: [x0, y1],
             color: c,
             uv,
+            ..CanvasVertex::PLAIN
         };
         self.verts.extend_from_slice(&[v0, v2, v1, v0, v3, v2]);
     }
@@ -465,21 +590,25 @@ impl Canvas {
             position: [x0, y0],
             color: cb,
             uv,
+            ..CanvasVertex::PLAIN
         };
         let v1 = CanvasVertex {
             position: [x1, y0],
             color: cb,
             uv,
+            ..CanvasVertex::PLAIN
         };
         let v2 = CanvasVertex {
             position: [x1, y1],
             color: ct,
             uv,
+            ..CanvasVertex::PLAIN
         };
         let v3 = CanvasVertex {
             position: [x0, y1],
             color: ct,
             uv,
+            ..CanvasVertex::PLAIN
         };
         self.verts.extend_from_slice(&[v0, v2, v1, v0, v3, v2]);
     }
@@ -541,21 +670,25 @@ impl Canvas {
             position: a,
             color: c,
             uv,
+            ..CanvasVertex::PLAIN
         };
         let vb = CanvasVertex {
             position: b,
             color: c,
             uv,
+            ..CanvasVertex::PLAIN
         };
         let vc = CanvasVertex {
             position: cc,
             color: c,
             uv,
+            ..CanvasVertex::PLAIN
         };
         let vd = CanvasVertex {
             position: d,
             color: c,
             uv,
+            ..CanvasVertex::PLAIN
         };
         self.verts.extend_from_slice(&[va, vc, vd, va, vb, vc]);
     }
@@ -597,16 +730,19 @@ impl Canvas {
                     position: center,
                     color: c,
                     uv,
+                    ..CanvasVertex::PLAIN
                 },
                 CanvasVertex {
                     position: e0,
                     color: c,
                     uv,
+                    ..CanvasVertex::PLAIN
                 },
                 CanvasVertex {
                     position: e1,
                     color: c,
                     uv,
+                    ..CanvasVertex::PLAIN
                 },
             ]);
         }
@@ -645,6 +781,7 @@ impl Canvas {
             position: center,
             color: c,
             uv,
+            ..CanvasVertex::PLAIN
         };
         let step = std::f32::consts::TAU / segments as f32;
         for i in 0..segments {
@@ -664,11 +801,13 @@ impl Canvas {
                 position: p0,
                 color: c,
                 uv,
+                ..CanvasVertex::PLAIN
             };
             let v1 = CanvasVertex {
                 position: p1,
                 color: c,
                 uv,
+                ..CanvasVertex::PLAIN
             };
             self.verts.extend_from_slice(&[vc, v0, v1]);
         }
@@ -697,65 +836,149 @@ impl Canvas {
         color: Color,
         atlas: &FontAtlas,
     ) {
-        self.set_font(atlas.id().0);
+        self.text_with_effects(x, y, text, size, color, atlas, &TextEffects::NONE);
+    }
+
+    /// [`text_with_font`](Self::text_with_font) with an outline, drop shadow
+    /// or glow drawn in the same call — one run per effect, underneath the
+    /// glyphs, so nothing has to be layered by hand.
+    ///
+    /// Outline and glow are grown out of a distance field and need a font in
+    /// [`FontMode::Sdf`](crate::FontMode::Sdf); on a bitmap font they are
+    /// skipped and the shadow is a hard offset copy.
+    #[allow(clippy::too_many_arguments)]
+    pub fn text_with_effects(
+        &mut self,
+        x: f32,
+        y: f32,
+        text: &str,
+        size: f32,
+        color: Color,
+        atlas: &FontAtlas,
+        effects: &TextEffects,
+    ) {
+        let sdf = atlas.mode() == FontMode::Sdf;
+        let mode = if sdf { 1.0 } else { 0.0 };
+        let field = |px: f32| field_units(px, size);
+        let run = |color: Color, params: [f32; 4], effect_color: [f32; 4]| CanvasVertex {
+            color: color.to_array(),
+            params,
+            effect_color,
+            ..CanvasVertex::PLAIN
+        };
+
+        if let (true, Some((radius, glow))) = (sdf, effects.glow) {
+            let template = run(glow, [mode, field(radius), 0.0, 0.0], [0.0; 4]);
+            self.glyph_run(x, y, text, size, atlas, template);
+        }
+        if let Some((dx, dy, blur, shadow)) = effects.shadow {
+            let softness = if sdf { field(blur) } else { 0.0 };
+            let template = run(shadow, [mode, softness, 0.0, 0.0], [0.0; 4]);
+            self.glyph_run(x + dx, y + dy, text, size, atlas, template);
+        }
+        let template = match effects.outline {
+            Some((width, outline)) if sdf => {
+                run(color, [mode, 0.0, field(width), 0.0], outline.to_array())
+            }
+            _ => run(color, [mode, 0.0, 0.0, 0.0], [0.0; 4]),
+        };
+        self.glyph_run(x, y, text, size, atlas, template);
+    }
+
+    /// One pass of glyphs along a line, every vertex cut from `template`
+    /// (its colour and shader params; position and UV are filled in).
+    fn glyph_run(
+        &mut self,
+        x: f32,
+        y: f32,
+        text: &str,
+        size: f32,
+        atlas: &FontAtlas,
+        template: CanvasVertex,
+    ) {
+        let font = atlas.id().0;
         let scale = size / FONT_SIZE;
-        let c = color.to_array();
         let baseline = atlas.baseline_below_top(y, size);
         let mut cursor_x = x;
 
+        if let Some(glyphs) = atlas.shape(text) {
+            for glyph in glyphs.iter() {
+                let entry = atlas.shaped_glyph(glyph);
+                if entry.width_px > 0.0 {
+                    let gx = cursor_x + glyph.x_offset * scale;
+                    let gy = baseline + glyph.y_offset * scale;
+                    self.glyph_quad(font, &entry, gx, gy, scale, template);
+                }
+                cursor_x += glyph.advance * scale;
+            }
+            return;
+        }
+
+        let mut prev = None;
         for ch in text.chars() {
-            let idx = ch as usize;
-            if idx >= 128 {
+            let Some(entry) = atlas.glyph(ch) else {
                 continue;
-            }
-            let entry = match atlas.glyphs[idx] {
-                Some(e) => e,
-                None => continue,
             };
-
+            if let Some(prev) = &prev {
+                cursor_x += atlas.kerning(prev, &entry) * scale;
+            }
             if entry.width_px > 0.0 {
-                let gx = cursor_x + entry.x_offset * scale;
-                // `entry.y_offset` is `ymin` — the glyph's bottom relative to
-                // the **baseline** — so it only means anything measured from
-                // a baseline. Previously this subtracted it from
-                // `line_height` (the tallest glyph's ink height at the time)
-                // against a `y` that callers passed as a rect edge: three
-                // different origins in one expression, which is why text drew
-                // outside its own node.
-                let gy = baseline + entry.y_offset * scale;
-                let gw = entry.width_px * scale;
-                let gh = entry.height_px * scale;
-
-                let [x0, y0] = screen_to_ndc(gx, gy, self.screen_size);
-                let [x1, y1] = screen_to_ndc(gx + gw, gy + gh, self.screen_size);
-
-                let v0 = CanvasVertex {
-                    position: [x0, y0],
-                    color: c,
-                    uv: [entry.u0, entry.v1],
-                };
-                let v1 = CanvasVertex {
-                    position: [x1, y0],
-                    color: c,
-                    uv: [entry.u1, entry.v1],
-                };
-                let v2 = CanvasVertex {
-                    position: [x1, y1],
-                    color: c,
-                    uv: [entry.u1, entry.v0],
-                };
-                let v3 = CanvasVertex {
-                    position: [x0, y1],
-                    color: c,
-                    uv: [entry.u0, entry.v0],
-                };
-                self.verts.extend_from_slice(&[v0, v2, v1, v0, v3, v2]);
+                self.glyph_quad(font, &entry, cursor_x, baseline, scale, template);
             }
-
             cursor_x += entry.advance * scale + self.tracking;
+            prev = Some(entry);
         }
     }
 
+    /// Emit one glyph's quad with its pen at `pen_x` on `baseline`, bound to
+    /// whichever atlas page the glyph cache put it on.
+    fn glyph_quad(
+        &mut self,
+        font: usize,
+        entry: &GlyphEntry,
+        pen_x: f32,
+        baseline: f32,
+        scale: f32,
+        template: CanvasVertex,
+    ) {
+        self.set_texture(DrawTexture::Font(font, entry.page));
+        let gx = pen_x + entry.x_offset * scale;
+        // `entry.y_offset` is `ymin` — the glyph's bottom relative to the
+        // **baseline** — so it only means anything measured from a
+        // baseline. Previously this subtracted it from `line_height` (the
+        // tallest glyph's ink height at the time) against a `y` that callers
+        // passed as a rect edge: three different origins in one expression,
+        // which is why text drew outside its own node.
+        let gy = baseline + entry.y_offset * scale;
+        let gw = entry.width_px * scale;
+        let gh = entry.height_px * scale;
+
+        let [x0, y0] = screen_to_ndc(gx, gy, self.screen_size);
+        let [x1, y1] = screen_to_ndc(gx + gw, gy + gh, self.screen_size);
+
+        let v0 = CanvasVertex {
+            position: [x0, y0],
+            uv: [entry.u0, entry.v1],
+            ..template
+        };
+        let v1 = CanvasVertex {
+            position: [x1, y0],
+            uv: [entry.u1, entry.v1],
+            ..template
+        };
+        let v2 = CanvasVertex {
+            position: [x1, y1],
+            uv: [entry.u1, entry.v0],
+            ..template
+        };
+        let v3 = CanvasVertex {
+            position: [x0, y1],
+            uv: [entry.u0, entry.v0],
+            ..template
+        };
+        self.verts.extend_from_slice(&[v0, v2, v1, v0, v3, v2]);
+    }
+
     pub fn text_aligned(
         &mut self,
         x: f32,
@@ -784,18 +1007,14 @@ impl Canvas {
         if !self.can_draw_text() {
             return;
         }
+        let align = align.resolve(text);
         let offset = if align == TextAlign::Left {
             0.0
         } else {
             // Through `measure_text_in`, not the atlas directly: alignment has
             // to account for `tracking` or a centred run drifts left by half
             // the spacing it actually draws with.
-            let (w, _) = self.measure_text_in(font, text, size);
-            match align {
-                TextAlign::Center => -w / 2.0,
-                TextAlign::Right => -w,
-                TextAlign::Left => unreachable!(),
-            }
+            align.offset(self.measure_text_in(font, text, size).0)
         };
         // SAFETY-adjacent: re-resolve rather than holding `atlas` across the
         // `&mut self` call below.
@@ -818,58 +1037,69 @@ impl Canvas {
         size: f32,
         atlas: &FontAtlas,
     ) {
-        self.set_font(atlas.id().0);
+        let font = atlas.id().0;
         let scale = size / FONT_SIZE;
         let tracking = self.tracking;
         // `y` is the line box's top, as in `text_with_font`.
         let baseline = atlas.baseline_below_top(y, size);
         let mut cursor_x = x;
+        // Kerning carries across span boundaries: a colour change mid-word
+        // must not loosen the pair it falls between.
+        let mut prev = None;
+
+        let mode = if atlas.mode() == FontMode::Sdf {
+            1.0
+        } else {
+            0.0
+        };
+
+        // Shaped as one line, not span by span, so a colour change inside an
+        // Arabic word keeps its letters joined; each glyph takes the colour
+        // of the span its cluster starts in.
+        let joined: String = spans.iter().map(|(span_text, _)| *span_text).collect();
+        if let Some(glyphs) = atlas.shape(&joined) {
+            let mut starts = Vec::with_capacity(spans.len());
+            let mut start = 0;
+            for (span_text, _) in spans {
+                starts.push(start);
+                start += span_text.len();
+            }
+            for glyph in glyphs.iter() {
+                let span = starts.partition_point(|&start| start <= glyph.cluster) - 1;
+                let template = CanvasVertex {
+                    color: spans[span].1.to_array(),
+                    params: [mode, 0.0, 0.0, 0.0],
+                    ..CanvasVertex::PLAIN
+                };
+                let entry = atlas.shaped_glyph(glyph);
+                if entry.width_px > 0.0 {
+                    let gx = cursor_x + glyph.x_offset * scale;
+                    let gy = baseline + glyph.y_offset * scale;
+                    self.glyph_quad(font, &entry, gx, gy, scale, template);
+                }
+                cursor_x += glyph.advance * scale;
+            }
+            return;
+        }
 
         for &(span_text, span_color) in spans {
-            let c = span_color.to_array();
+            let template = CanvasVertex {
+                color: span_color.to_array(),
+                params: [mode, 0.0, 0.0, 0.0],
+                ..CanvasVertex::PLAIN
+            };
             for ch in span_text.chars() {
-                let idx = ch as usize;
-                if idx >= 128 {
+                let Some(entry) = atlas.glyph(ch) else {
                     continue;
-                }
-                let entry = match atlas.glyphs[idx] {
-                    Some(e) => e,
-                    None => continue,
                 };
-
+                if let Some(prev) = &prev {
+                    cursor_x += atlas.kerning(prev, &entry) * scale;
+                }
                 if entry.width_px > 0.0 {
-                    let gx = cursor_x + entry.x_offset * scale;
-                    let gy = baseline + entry.y_offset * scale;
-                    let gw = entry.width_px * scale;
-                    let gh = entry.height_px * scale;
-
-                    let [x0, y0] = screen_to_ndc(gx, gy, self.screen_size);
-                    let [x1, y1] = screen_to_ndc(gx + gw, gy + gh, self.screen_size);
-
-                    let v0 = CanvasVertex {
-                        position: [x0, y0],
-                        color: c,
-                        uv: [entry.u0, entry.v1],
-                    };
-                    let v1 = CanvasVertex {
-                        position: [x1, y0],
-                        color: c,
-                        uv: [entry.u1, entry.v1],
-                    };
-                    let v2 = CanvasVertex {
-                        position: [x1, y1],
-                        color: c,
-                        uv: [entry.u1, entry.v0],
-                    };
-                    let v3 = CanvasVertex {
-                        position: [x0, y1],
-                        color: c,
-                        uv: [entry.u0, entry.v0],
-                    };
-                    self.verts.extend_from_slice(&[v0, v2, v1, v0, v3, v2]);
+                    self.glyph_quad(font, &entry, cursor_x, baseline, scale, template);
                 }
-
                 cursor_x += entry.advance * scale + tracking;
+                prev = Some(entry);
             }
         }
     }
@@ -931,21 +1161,25 @@ impl Canvas {
             position: corner(-hw, -hh),
             color: c,
             uv: [u0, v_bottom],
+            ..CanvasVertex::PLAIN
         };
         let bottom_right = CanvasVertex {
             position: corner(hw, -hh),
             color: c,
             uv: [u1, v_bottom],
+            ..CanvasVertex::PLAIN
         };
         let top_right = CanvasVertex {
             position: corner(hw, hh),
             color: c,
             uv: [u1, v0],
+            ..CanvasVertex::PLAIN
         };
         let top_left = CanvasVertex {
             position: corner(-hw, hh),
             color: c,
             uv: [u0, v0],
+            ..CanvasVertex::PLAIN
         };
         self.verts.extend_from_slice(&[
             bottom_left,
@@ -981,21 +1215,25 @@ impl Canvas {
             position: [x0, y0],
             color: c,
             uv: [u0, v_bottom],
+            ..CanvasVertex::PLAIN
         };
         let bottom_right = CanvasVertex {
             position: [x1, y0],
             color: c,
             uv: [u1, v_bottom],
+            ..CanvasVertex::PLAIN
         };
         let top_right = CanvasVertex {
             position: [x1, y1],
             color: c,
             uv: [u1, v0],
+            ..CanvasVertex::PLAIN
         };
         let top_left = CanvasVertex {
             position: [x0, y1],
             color: c,
             uv: [u0, v0],
+            ..CanvasVertex::PLAIN
         };
         self.verts.extend_from_slice(&[
             bottom_left,
@@ -1032,18 +1270,14 @@ impl Canvas {
             return;
         }
         let atlas = self.font_atlas(font);
+        // Measured whole, the way it draws: kerning and shaping both carry
+        // across span boundaries.
+        let joined: String = spans.iter().map(|(s, _)| *s).collect();
+        let align = align.resolve(&joined);
         let offset = if align == TextAlign::Left {
             0.0
         } else {
-            let total_w: f32 = spans
-                .iter()
-                .map(|(s, _)| atlas.measure_text(s, size).0)
-                .sum();
-            match align {
-                TextAlign::Center => -total_w / 2.0,
-                TextAlign::Right => -total_w,
-                TextAlign::Left => unreachable!(),
-            }
+            align.offset(atlas.measure_text(&joined, size).0)
         };
         let atlas = self.font_atlas(font) as *const FontAtlas;
         self.text_spans_with_font(x + offset, y, spans, size, unsafe { &*atlas });
@@ -1105,6 +1339,9 @@ impl Canvas {
         // chrome and wrapping is for prose; nothing authored is both. Give
         // `wrap_text` the tracking if that ever stops being true.
         let lines = wrap_text(text, size, max_width, self.font_atlas(font));
+        // One direction for the whole block, so `Start` lines every line up
+        // on the same side even where a line opens with a number.
+        let align = align.resolve(text);
         self.text_block_lines_leaded_in(font, x, y, &lines, size, color, align, leading);
     }
 
@@ -1192,6 +1429,7 @@ impl Canvas {
         if !self.can_draw_text() {
             return;
         }
+        let align = align.resolve(&lines.join("\n"));
         // `y` is the first line box's top; each subsequent line is one leaded
         // step lower, so the block occupies exactly `block_height`.
         let step = self.font_atlas(font).line_height(size) * leading;
@@ -1211,12 +1449,24 @@ impl Canvas {
     }
 }
 
+/// Pixels at the drawn `size` → distance-field units, capped at the spread
+/// the atlas actually stored.
+fn field_units(px: f32, size: f32) -> f32 {
+    (px * FONT_SIZE / size / (2.0 * SDF_SPREAD)).clamp(0.0, 0.5)
+}
+
 pub fn screen_to_ndc(x: f32, y: f32, screen_size: (u32, u32)) -> [f32; 2] {
     let hw = screen_size.0 as f32 / 2.0;
     let hh = screen_size.1 as f32 / 2.0;
     [x / hw, y / hh]
 }
 
+/// Break `text` into lines no wider than `max_width` at `size`.
+///
+/// Lines come back in logical (reading) order and break only at spaces, so a
+/// grapheme cluster or a shaped word is never split. Right-to-left text is
+/// reordered per line when each line draws, which is where the bidi
+/// algorithm wants it: after the breaks, not before.
 pub fn wrap_text(text: &str, size: f32, max_width: f32, atlas: &FontAtlas) -> Vec<String> {
     wrap_text_measured(text, max_width, |run| atlas.measure_text(run, size).0)
 }
@@ -1239,41 +1489,78 @@ pub fn wrap_text_measured(
             continue;
         }
         let words: Vec<&str> = raw_line.split(' ').collect();
-        let mut current = String::new();
-        let mut current_w: f32 = 0.0;
         let space_w = measure(" ");
-        for word in &words {
-            let word_w = measure(word);
-            if current.is_empty() {
-                if word_w > max_width {
-                    lines.push((*word).to_string());
+        let measured: Vec<WrapWord> = words
+            .iter()
+            .map(|word| WrapWord {
+                width: measure(word),
+                space_before: space_w,
+                empty: word.is_empty(),
+            })
+            .collect();
+        for range in break_words(&measured, max_width) {
+            lines.push(words[range].join(" "));
+        }
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+/// One space-separated word as [`break_words`] sees it.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct WrapWord {
+    pub width: f32,
+    /// The width of the space joining this word to the one before it — per
+    /// word, because in rich text that space has a size of its own.
+    pub space_before: f32,
+    /// No text at all: what a doubled or leading space splits out.
+    pub empty: bool,
+}
+
+/// Where one paragraph's words break into lines, as ranges of word indices.
+///
+/// The decision half of [`wrap_text_measured`], pulled out so rich text — whose
+/// words are runs of differently-sized spans, not substrings — breaks by
+/// exactly the same rules: greedy fill, a word wider than the line gets a line
+/// to itself, and empty words never start a line.
+pub(crate) fn break_words(words: &[WrapWord], max_width: f32) -> Vec<std::ops::Range<usize>> {
+    let mut lines = Vec::new();
+    let mut current: Option<std::ops::Range<usize>> = None;
+    let mut current_w: f32 = 0.0;
+    for (i, word) in words.iter().enumerate() {
+        match current.as_mut() {
+            None => {
+                if word.width > max_width {
+                    lines.push(i..i + 1);
                 } else {
-                    current = (*word).to_string();
-                    current_w = word_w;
+                    current = Some(i..i + 1);
+                    current_w = word.width;
                 }
-            } else if current_w + space_w + word_w <= max_width {
-                current.push(' ');
-                current.push_str(word);
-                current_w += space_w + word_w;
-            } else {
-                lines.push(current);
-                if word_w > max_width {
-                    lines.push((*word).to_string());
-                    current = String::new();
-                    current_w = 0.0;
+            }
+            Some(range) if current_w + word.space_before + word.width <= max_width => {
+                range.end = i + 1;
+                current_w += word.space_before + word.width;
+            }
+            Some(_) => {
+                lines.extend(current.take());
+                if word.width > max_width {
+                    lines.push(i..i + 1);
                 } else {
-                    current = (*word).to_string();
-                    current_w = word_w;
+                    current = Some(i..i + 1);
+                    current_w = word.width;
                 }
             }
         }
-        if !current.is_empty() {
-            lines.push(current);
+        // A line holding nothing but an empty word is still empty: the next
+        // word starts it afresh rather than after a leading space.
+        if current.as_ref().is_some_and(|range| range.len() == 1) && word.empty {
+            current = None;
+            current_w = 0.0;
         }
     }
-    if lines.is_empty() {
-        lines.push(String::new());
-    }
+    lines.extend(current);
     lines
 }
 
@@ -1377,6 +1664,30 @@ fn required_vertex_buffer_capacity(current_capacity: usize, required_vertices: u
         .unwrap_or(required_vertices)
 }
 
+/// Point image segments that draw a packed texture at its atlas page instead,
+/// moving their uvs into the texture's sub-rect. The page's own id has no
+/// region, so running this twice over a canvas changes nothing.
+pub(crate) fn resolve_packed_textures<F>(canvases: &mut [Canvas], region: F)
+where
+    F: Fn(usize) -> Option<AtlasRegion>,
+{
+    for canvas in canvases {
+        canvas.finalize();
+        for segment in &mut canvas.segments {
+            let DrawTexture::Texture(texture) = segment.texture else {
+                continue;
+            };
+            let Some(region) = region(texture) else {
+                continue;
+            };
+            for vertex in &mut canvas.verts[segment.start..segment.start + segment.count] {
+                vertex.uv = region.map_uv(vertex.uv);
+            }
+            segment.texture = DrawTexture::Texture(region.page.0);
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn render_pass<'a, F>(
     device: &wgpu::Device,
@@ -1399,6 +1710,11 @@ pub(crate) fn render_pass<'a, F>(
     for canvas in canvases.iter_mut() {
         canvas.finalize();
     }
+    // Glyphs rasterised while these canvases were drawn have to reach their
+    // pages before anything samples them.
+    for atlas in fonts {
+        atlas.flush(device, queue);
+    }
 
     let verts: Vec<CanvasVertex> = canvases
         .iter()
@@ -1418,7 +1734,7 @@ pub(crate) fn render_pass<'a, F>(
     for canvas in canvases.iter() {
         if canvas.segments.is_empty() {
             if !canvas.verts.is_empty() {
-                global_segments.push((offset, canvas.verts.len(), None, DrawTexture::Font(0)));
+                global_segments.push((offset, canvas.verts.len(), None, DrawTexture::Font(0, 0)));
             }
         } else {
             for seg in &canvas.segments {
@@ -1450,17 +1766,19 @@ pub(crate) fn render_pass<'a, F>(
             pass.set_viewport(vx, vy, vw, vh, 0.0, 1.0);
         }
     }
-    pass.set_bind_group(0, &fonts[0].bind_group, &[]);
+    if let Some(bind_group) = fonts[0].page_bind_group(0) {
+        pass.set_bind_group(0, &bind_group, &[]);
+    }
     pass.set_vertex_buffer(0, vertex_buffer.slice(..));
 
     let needs_per_segment = global_segments
         .iter()
-        .any(|(_, _, s, texture)| s.is_some() || *texture != DrawTexture::Font(0));
+        .any(|(_, _, s, texture)| s.is_some() || *texture != DrawTexture::Font(0, 0));
 
     if needs_per_segment {
         let surface_w = canvases.first().map(|c| c.screen_size.0).unwrap_or(1);
         let surface_h = canvases.first().map(|c| c.screen_size.1).unwrap_or(1);
-        let mut bound_texture = DrawTexture::Font(0);
+        let mut bound_texture = DrawTexture::Font(0, 0);
 
         for (start, count, scissor, texture) in &global_segments {
             if *count == 0 {
@@ -1468,9 +1786,12 @@ pub(crate) fn render_pass<'a, F>(
             }
             if *texture != bound_texture {
                 match *texture {
-                    DrawTexture::Font(font_id) => {
-                        if let Some(atlas) = fonts.get(font_id) {
-                            pass.set_bind_group(0, &atlas.bind_group, &[]);
+                    DrawTexture::Font(font_id, page) => {
+                        if let Some(bind_group) = fonts
+                            .get(font_id)
+                            .and_then(|atlas| atlas.page_bind_group(page))
+                        {
+                            pass.set_bind_group(0, &bind_group, &[]);
                         }
                     }
                     DrawTexture::Texture(texture_id) => {
@@ -1515,9 +1836,36 @@ mod tests {
         // A single glyph and an empty run have no gaps at all.
         assert_eq!(w("X", 6.0), 0.0);
         assert_eq!(w("", 6.0), 0.0);
-        // Non-ASCII is skipped by the draw loop, so it must not be counted
-        // here either — otherwise the measure is wider than the paint.
-        assert_eq!(w("A\u{2022}B", 4.0), w("AB", 4.0));
+        // Non-ASCII draws through the glyph cache, so it counts like any
+        // other glyph; control characters draw nothing and must not.
+        assert_eq!(w("A\u{2022}B", 4.0), w("ABC", 4.0));
+        assert_eq!(w("A\tB", 4.0), w("AB", 4.0));
+    }
+
+    #[test]
+    fn packed_images_draw_from_their_atlas_page() {
+        let mut canvas = Canvas::for_test((200, 200));
+        canvas.image(TextureId(3), 0.0, 0.0, 10.0, 10.0);
+        canvas.image(TextureId(4), 20.0, 0.0, 10.0, 10.0);
+        let region = AtlasRegion {
+            page: TextureId(9),
+            uv_rect: [0.5, 0.0, 0.25, 0.5],
+        };
+        let lookup = |texture: usize| (texture == 3).then_some(region);
+        let mut canvases = [canvas];
+        resolve_packed_textures(&mut canvases, lookup);
+        resolve_packed_textures(&mut canvases, lookup);
+
+        let [canvas] = &canvases;
+        let textures: Vec<_> = canvas.segments.iter().map(|s| s.texture).collect();
+        assert_eq!(textures, [DrawTexture::Texture(9), DrawTexture::Texture(4)]);
+        let packed = &canvas.verts[..canvas.segments[0].count];
+        assert!(packed
+            .iter()
+            .all(|v| (0.5..=0.75).contains(&v.uv[0]) && (0.0..=0.5).contains(&v.uv[1])));
+        assert!(canvas.verts[canvas.segments[1].start..]
+            .iter()
+            .any(|v| v.uv == [1.0, 1.0]));
     }
 }
 
@@ -33,27 +33,45 @@ pub use math::Rect;
 pub use math::Rng;
 pub use math::TimeState;
 pub use math::{ease, lerp, Easing, LoopMode, Tween};
+pub use math::{Bounds2, Fixed, FixedRect, FixedVec2, Scalar, Vector2};
 pub use math::{EventQueue, Timer};
 pub use renderer::{
-    Camera2D, CameraBounds, DrawParams, Frame, NineSlice, PostEffect, PostFxChain, RenderTarget,
-    Sprite, TextureId,
+    AtlasRegion, Camera2D, CameraBounds, DrawParams, Frame, NineSlice, PostEffect, PostFxChain,
+    RenderTarget, Sprite, TextureId,
 };
 
 pub use world::tilemap;
 pub use world::{
-    aabb_overlap, aabb_overlap_layered, iso_to_screen, move_and_collide, move_and_collide_solids,
-    screen_to_iso, BodyId, CollisionLayer, Contacts2D, KinematicBody2D, MoveResult2D, OverlapEvent,
-    Solid2D, TileDef, TileMap, TriggerSystem, TriggerZone, TriggerZoneId,
+    aabb_overlap, aabb_overlap_generic, aabb_overlap_layered, constrain_kinematic, iso_to_screen,
+    line_of_sight, line_of_sight_with, move_aabb, move_and_collide, move_and_collide_shape,
+    move_and_collide_solids, move_platforms, screen_to_iso, shape_overlap, shape_overlap_layered,
+    shapes_overlap, AutotileMode, AutotileRules, Autotiler, BodyId, BoxCast2D, CcdMode2D,
+    CollisionLayer, ContactTarget2D, Contacts2D, ConvexPolygon2D, CrowdAgent, FieldOfView,
+    FlowCrowd, FlowField, GridLayout, GridLine, GridPath, GridPathCache, Hex, HexLayout,
+    HexOrientation, HitTarget2D, Impact2D, IsoBox, IsoRenderer, Joint2D, JointAnchor2D, JointId,
+    JointKind2D, KinematicBody2D, LayeredTileMap, LdtkDirection, LdtkEntity, LdtkField,
+    LdtkIntGridValue, LdtkLayer, LdtkLayerKind, LdtkLayerMap, LdtkLevel, LdtkLevelScene,
+    LdtkNeighbour, LdtkProject, LdtkTile, LdtkTileRect, LdtkTileset, LdtkValue, MoveResult2D,
+    NavAgent, NavGrid, NavMesh, OverlapEvent, PathFollower, PathSearch, PhysicsWorld2D,
+    PlatformEvent2D, Ray2D, RayHit2D, RigidBody2D, RigidBodyId, RigidContact2D, Shape2D, Solid2D,
+    SolidId, SolidIndex2D, SpatialGrid2D, SpatialKey, Stagger, StaggeredIso, Steering,
+    SteeringAgent, SweepHit2D, TerrainRule, TileDef, TileLayer, TileMap, TileShape, TiledLayer,
+    TiledLayerMap, TiledLevel, TiledMap, TiledObject, TiledObjectKind, TiledObjectLayer,
+    TiledProperties, TiledSpawn, TiledTile, TiledTileLayer, TiledTileset, TiledTrigger, TiledValue,
+    TriggerSystem, TriggerZone, TriggerZoneId, VisibilitySet, Wander, DEFAULT_MAX_SLOPE_ANGLE,
 };
 
 pub use assets::pixelart;
 pub use assets::{
     Animation, AnimationState, AnimationStateMachine, AnimationTransition, AssetBundle, AssetError,
-    AssetManifest, AssetPack, AssetSummary, AudioBus, AudioClip, AudioId, FontAsset, MeshAsset,
-    SpriteSheet, SpriteSheetAssetDef, TextureAsset,
+    AssetManifest, AssetPack, AssetSummary, AudioBus, AudioClip, AudioId, FontAsset,
+    LdtkProjectAsset, MeshAsset, SpriteSheet, SpriteSheetAssetDef, TextureAsset, TiledMapAsset,
 };
 
-pub use canvas::{screen_to_ndc, wrap_text, Canvas, CanvasVertex, TextAlign};
+pub use canvas::{
+    screen_to_ndc, wrap_text, Canvas, CanvasVertex, MarkupAssets, MarkupImage, RichLayout,
+    RichText, TextAlign, TextEffects,
+};
 pub use scene::{
     validate_editor_scene, validate_scene_dir, validate_scene_file, Bindings, EditorSceneDocument,
     EditorSceneNode, EditorSceneNodeKind, Globals, NodeHandle2D, Prefab2D, Prefab2DDef,
@@ -61,12 +79,13 @@ pub use scene::{
     SceneInstance2D, SceneInstance2DDef, SceneIssueSeverity, SceneLayer2D, SceneLibrary,
     SceneNode2D, SceneOp, SceneScript2D, SceneScriptBinding2D, SceneScriptContext2D,
     SceneScriptEvent2D, SceneScriptHost2D, SceneScriptInputEvent2D, SceneScriptRegistry2D,
-    SceneValidationIssue, SceneValidationReport, SceneWorld2D, ScriptDef, ScriptManifest,
+    SceneTriggers2D, SceneValidationIssue, SceneValidationReport, SceneWorld2D, ScriptDef, ScriptManifest,
     ScriptParamDef, ScriptParamKind, Transform2D, Transition, CURRENT_EDITOR_SCENE_VERSION,
     NESTED_SCENE_PROPERTY,
 };
 pub use text::FontAtlas;
 pub use text::FontId;
+pub use text::FontMode;
 pub use ui::{
     TooltipAnimation, TooltipExpandTrigger, TooltipOptions, TooltipPlacement, Ui, UiAnimation,
     UiAnimationOptions, UiContainerAnimation, UiContainerAnimationOptions, UiResponse, UiStyle,
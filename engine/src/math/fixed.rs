@@ -0,0 +1,492 @@
+//! Deterministic Q16.16 fixed-point numbers for lockstep and rollback
+//! simulation. Every operation is plain integer arithmetic, so two peers on
+//! different CPUs, compilers or optimisation levels produce bit-identical
+//! results — something `f32` transcendentals (`sin`, `powf`) do not promise.
+
+use std::fmt;
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::math::rect::Rect;
+
+const FRAC: u32 = 16;
+const ONE_BITS: i32 = 1 << FRAC;
+/// `1.0` in the Q32 intermediate format used by the series expansions.
+const ONE_Q32: i128 = 1 << 32;
+
+/// A signed Q16.16 fixed-point number: 16 integer bits, 16 fractional bits,
+/// range roughly ±32768 with a resolution of 1/65536.
+///
+/// Arithmetic wraps on overflow in every build profile so a debug and a
+/// release peer never diverge; division by zero panics like integer division.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Fixed(i32);
+
+impl Fixed {
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(ONE_BITS);
+    pub const HALF: Fixed = Fixed(ONE_BITS / 2);
+    /// The smallest positive value, 1/65536.
+    pub const EPSILON: Fixed = Fixed(1);
+    pub const MIN: Fixed = Fixed(i32::MIN);
+    pub const MAX: Fixed = Fixed(i32::MAX);
+    pub const PI: Fixed = Fixed(205_887);
+    pub const FRAC_PI_2: Fixed = Fixed(102_944);
+    pub const TAU: Fixed = Fixed(411_775);
+
+    pub const fn from_bits(bits: i32) -> Self {
+        Self(bits)
+    }
+
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    pub const fn from_int(n: i32) -> Self {
+        Self(n.wrapping_shl(FRAC))
+    }
+
+    /// `num / den`, rounded toward zero. Usable in `const` items, which is the
+    /// usual way to write fractional tuning constants.
+    pub const fn from_ratio(num: i32, den: i32) -> Self {
+        Self((((num as i64) << FRAC) / den as i64) as i32)
+    }
+
+    /// Converts from `f32`, rounding to the nearest step and saturating out
+    /// of range. The conversion itself is exact IEEE arithmetic, so it is
+    /// safe for loading authored values; just keep floats out of the loop.
+    pub fn from_f32(value: f32) -> Self {
+        Self((value * ONE_BITS as f32).round() as i32)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / ONE_BITS as f32
+    }
+
+    /// The integer part, rounded toward negative infinity.
+    pub const fn to_int(self) -> i32 {
+        self.0 >> FRAC
+    }
+
+    pub const fn abs(self) -> Self {
+        Self(self.0.wrapping_abs())
+    }
+
+    pub const fn signum(self) -> Self {
+        Self::from_int(self.0.signum())
+    }
+
+    pub const fn floor(self) -> Self {
+        Self(self.0 & !(ONE_BITS - 1))
+    }
+
+    pub const fn ceil(self) -> Self {
+        Self(self.0.wrapping_add(ONE_BITS - 1) & !(ONE_BITS - 1))
+    }
+
+    pub const fn round(self) -> Self {
+        Self(self.0.wrapping_add(ONE_BITS / 2) & !(ONE_BITS - 1))
+    }
+
+    pub const fn fract(self) -> Self {
+        Self(self.0 & (ONE_BITS - 1))
+    }
+
+    pub fn min(self, other: Fixed) -> Self {
+        Ord::min(self, other)
+    }
+
+    pub fn max(self, other: Fixed) -> Self {
+        Ord::max(self, other)
+    }
+
+    pub fn clamp(self, min: Fixed, max: Fixed) -> Self {
+        Ord::clamp(self, min, max)
+    }
+
+    pub fn rem_euclid(self, rhs: Fixed) -> Self {
+        Self(self.0.wrapping_rem_euclid(rhs.0))
+    }
+
+    /// `self` raised to a non-negative integer power by repeated multiplication.
+    pub fn powi(self, n: u32) -> Self {
+        (0..n).fold(Self::ONE, |acc, _| acc * self)
+    }
+
+    /// Square root, or zero for negative input.
+    pub fn sqrt(self) -> Self {
+        if self.0 <= 0 {
+            return Self::ZERO;
+        }
+        Self(isqrt((self.0 as u64) << FRAC) as i32)
+    }
+
+    /// Sine of an angle in radians, accurate to a couple of ulps.
+    pub fn sin(self) -> Self {
+        let pi = Self::PI.0;
+        let mut x = self.0.rem_euclid(Self::TAU.0);
+        if x > pi {
+            x -= Self::TAU.0;
+        }
+        if x > Self::FRAC_PI_2.0 {
+            x = pi - x;
+        } else if x < -Self::FRAC_PI_2.0 {
+            x = -pi - x;
+        }
+        // Taylor series on [-π/2, π/2] in Q32, Horner form, through x^11.
+        let x = (x as i128) << FRAC;
+        let x2 = (x * x) >> 32;
+        let mut term = ONE_Q32;
+        for k in [110, 72, 42, 20, 6] {
+            term = ONE_Q32 - ((x2 * term) >> 32) / k;
+        }
+        Self(q32_to_q16((x * term) >> 32))
+    }
+
+    pub fn cos(self) -> Self {
+        (self + Self::FRAC_PI_2).sin()
+    }
+
+    /// `2^self`; underflows to zero and saturates at [`Fixed::MAX`].
+    pub fn exp2(self) -> Self {
+        let whole = self.0 >> FRAC;
+        if whole >= 15 {
+            return Self::MAX;
+        }
+        if whole < -(FRAC as i32) - 1 {
+            return Self::ZERO;
+        }
+        // 2^frac = e^(frac·ln2), series through the 8th power in Q32.
+        const LN_2_Q32: i128 = 2_977_044_472;
+        let y = ((((self.0 & (ONE_BITS - 1)) as i128) << FRAC) * LN_2_Q32) >> 32;
+        let mut term = ONE_Q32;
+        for k in (1..=8).rev() {
+            term = ONE_Q32 + ((y * term) >> 32) / k;
+        }
+        let scaled = if whole >= 0 {
+            term << whole
+        } else {
+            term >> -whole
+        };
+        Self(q32_to_q16(scaled))
+    }
+}
+
+fn q32_to_q16(value: i128) -> i32 {
+    ((value + (1 << (FRAC - 1))) >> FRAC).clamp(i32::MIN as i128, i32::MAX as i128) as i32
+}
+
+fn isqrt(n: u64) -> u64 {
+    let mut rem = n;
+    let mut root = 0u64;
+    let mut bit = 1u64 << 62;
+    while bit > rem {
+        bit >>= 2;
+    }
+    while bit != 0 {
+        if rem >= root + bit {
+            rem -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+    root
+}
+
+impl From<i32> for Fixed {
+    fn from(n: i32) -> Self {
+        Self::from_int(n)
+    }
+}
+
+impl fmt::Debug for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fixed({})", self.to_f32())
+    }
+}
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_f32(), f)
+    }
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+    fn add(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.wrapping_add(rhs.0))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+    fn sub(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.wrapping_sub(rhs.0))
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+    fn mul(self, rhs: Fixed) -> Fixed {
+        let product = self.0 as i64 * rhs.0 as i64;
+        Fixed(((product + (1 << (FRAC - 1))) >> FRAC) as i32)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Fixed;
+    fn div(self, rhs: Fixed) -> Fixed {
+        Fixed((((self.0 as i64) << FRAC) / rhs.0 as i64) as i32)
+    }
+}
+
+impl Rem for Fixed {
+    type Output = Fixed;
+    fn rem(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.wrapping_rem(rhs.0))
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+    fn neg(self) -> Fixed {
+        Fixed(self.0.wrapping_neg())
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, rhs: Fixed) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, rhs: Fixed) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Fixed {
+    fn mul_assign(&mut self, rhs: Fixed) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Fixed {
+    fn div_assign(&mut self, rhs: Fixed) {
+        *self = *self / rhs;
+    }
+}
+
+impl RemAssign for Fixed {
+    fn rem_assign(&mut self, rhs: Fixed) {
+        *self = *self % rhs;
+    }
+}
+
+/// A 2D vector of [`Fixed`] components, the fixed-point counterpart of
+/// [`glam::Vec2`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FixedVec2 {
+    pub x: Fixed,
+    pub y: Fixed,
+}
+
+impl FixedVec2 {
+    pub const ZERO: FixedVec2 = FixedVec2::new(Fixed::ZERO, Fixed::ZERO);
+    pub const X: FixedVec2 = FixedVec2::new(Fixed::ONE, Fixed::ZERO);
+    pub const Y: FixedVec2 = FixedVec2::new(Fixed::ZERO, Fixed::ONE);
+
+    pub const fn new(x: Fixed, y: Fixed) -> Self {
+        Self { x, y }
+    }
+
+    pub fn from_vec2(v: Vec2) -> Self {
+        Self::new(Fixed::from_f32(v.x), Fixed::from_f32(v.y))
+    }
+
+    pub fn to_vec2(self) -> Vec2 {
+        Vec2::new(self.x.to_f32(), self.y.to_f32())
+    }
+
+    pub fn dot(self, other: FixedVec2) -> Fixed {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn length_squared(self) -> Fixed {
+        self.dot(self)
+    }
+
+    pub fn length(self) -> Fixed {
+        self.length_squared().sqrt()
+    }
+
+    /// The unit vector in the same direction, or zero for a zero vector.
+    pub fn normalize_or_zero(self) -> Self {
+        let len = self.length();
+        if len == Fixed::ZERO {
+            Self::ZERO
+        } else {
+            self / len
+        }
+    }
+
+    /// Unit vector at `angle` radians from the +x axis.
+    pub fn from_angle(angle: Fixed) -> Self {
+        Self::new(angle.cos(), angle.sin())
+    }
+}
+
+impl Add for FixedVec2 {
+    type Output = FixedVec2;
+    fn add(self, rhs: FixedVec2) -> FixedVec2 {
+        FixedVec2::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for FixedVec2 {
+    type Output = FixedVec2;
+    fn sub(self, rhs: FixedVec2) -> FixedVec2 {
+        FixedVec2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<Fixed> for FixedVec2 {
+    type Output = FixedVec2;
+    fn mul(self, rhs: Fixed) -> FixedVec2 {
+        FixedVec2::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Div<Fixed> for FixedVec2 {
+    type Output = FixedVec2;
+    fn div(self, rhs: Fixed) -> FixedVec2 {
+        FixedVec2::new(self.x / rhs, self.y / rhs)
+    }
+}
+
+impl Neg for FixedVec2 {
+    type Output = FixedVec2;
+    fn neg(self) -> FixedVec2 {
+        FixedVec2::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for FixedVec2 {
+    fn add_assign(&mut self, rhs: FixedVec2) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for FixedVec2 {
+    fn sub_assign(&mut self, rhs: FixedVec2) {
+        *self = *self - rhs;
+    }
+}
+
+/// An axis-aligned rectangle in [`Fixed`] units with [`Rect`]'s y-up
+/// convention: `(x, y)` is the bottom-left corner.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FixedRect {
+    pub x: Fixed,
+    pub y: Fixed,
+    pub width: Fixed,
+    pub height: Fixed,
+}
+
+impl FixedRect {
+    pub const fn new(x: Fixed, y: Fixed, width: Fixed, height: Fixed) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn from_rect(rect: &Rect) -> Self {
+        Self::new(
+            Fixed::from_f32(rect.x),
+            Fixed::from_f32(rect.y),
+            Fixed::from_f32(rect.width),
+            Fixed::from_f32(rect.height),
+        )
+    }
+
+    pub fn to_rect(&self) -> Rect {
+        Rect::new(
+            self.x.to_f32(),
+            self.y.to_f32(),
+            self.width.to_f32(),
+            self.height.to_f32(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Fixed, b: f32, tolerance: f32) -> bool {
+        (a.to_f32() - b).abs() <= tolerance
+    }
+
+    #[test]
+    fn arithmetic_round_trips_and_wraps_instead_of_panicking() {
+        let a = Fixed::from_f32(3.25);
+        let b = Fixed::from_int(-2);
+        assert_eq!((a + b).to_f32(), 1.25);
+        assert_eq!((a * b).to_f32(), -6.5);
+        assert_eq!((a / b).to_f32(), -1.625);
+        assert_eq!(Fixed::from_ratio(1, 4), Fixed::from_f32(0.25));
+        assert_eq!(Fixed::from_f32(-1.5).floor(), Fixed::from_int(-2));
+        assert_eq!(Fixed::from_f32(-1.5).to_int(), -2);
+        assert_eq!(
+            Fixed::from_f32(7.5).rem_euclid(Fixed::from_int(2)).to_f32(),
+            1.5
+        );
+        assert_eq!(Fixed::MAX + Fixed::EPSILON, Fixed::MIN);
+        assert_eq!(Fixed::MIN % -Fixed::EPSILON, Fixed::ZERO);
+        assert_eq!(Fixed::MIN.rem_euclid(-Fixed::EPSILON), Fixed::ZERO);
+    }
+
+    #[test]
+    fn transcendentals_track_f32_closely() {
+        for i in -40..=40 {
+            let x = i as f32 * 0.37;
+            let fx = Fixed::from_f32(x);
+            let angle = fx.to_f32();
+            assert!(close(fx.sin(), angle.sin(), 1e-4), "sin({angle})");
+            assert!(close(fx.cos(), angle.cos(), 1e-4), "cos({angle})");
+            if x >= 0.0 {
+                assert!(close(fx.sqrt(), angle.sqrt(), 1e-4), "sqrt({angle})");
+            }
+            if x.abs() < 10.0 {
+                let expected = 2f32.powf(angle);
+                let tolerance = 1e-4 * expected.max(1.0);
+                assert!(close(fx.exp2(), expected, tolerance), "exp2({angle})");
+            }
+        }
+        assert_eq!(Fixed::from_int(-1).sqrt(), Fixed::ZERO);
+        assert_eq!(Fixed::from_int(40).exp2(), Fixed::MAX);
+        assert_eq!(Fixed::from_int(-40).exp2(), Fixed::ZERO);
+    }
+
+    #[test]
+    fn vectors_normalize_and_convert() {
+        let v = FixedVec2::new(Fixed::from_int(3), Fixed::from_int(4));
+        assert_eq!(v.length(), Fixed::from_int(5));
+        let n = v.normalize_or_zero();
+        assert!(close(n.x, 0.6, 1e-4) && close(n.y, 0.8, 1e-4));
+        assert_eq!(FixedVec2::ZERO.normalize_or_zero(), FixedVec2::ZERO);
+        let r = FixedRect::from_rect(&Rect::new(1.0, 2.0, 3.5, 4.25));
+        assert_eq!(r.to_rect(), Rect::new(1.0, 2.0, 3.5, 4.25));
+    }
+}
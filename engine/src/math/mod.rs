@@ -1,11 +1,15 @@
+pub mod fixed;
 pub mod rect;
 pub mod rng;
+pub mod scalar;
 pub mod time;
 pub mod timer;
 pub mod tween;
 
+pub use fixed::{Fixed, FixedRect, FixedVec2};
 pub use rect::Rect;
 pub use rng::Rng;
+pub use scalar::{Bounds2, Scalar, Vector2};
 pub use time::TimeState;
 pub use timer::{EventQueue, Timer};
 pub use tween::{ease, lerp, Easing, LoopMode, Tween};
@@ -1,3 +1,5 @@
+use crate::math::fixed::Fixed;
+
 pub struct Rng {
     state: [u64; 4],
 }
@@ -73,6 +75,19 @@ impl Rng {
         min + self.f32() * (max - min)
     }
 
+    /// Uniform in `[0, 1)` as a [`Fixed`], built from the raw bits with no
+    /// float arithmetic, for rollback simulations.
+    pub fn fixed(&mut self) -> Fixed {
+        Fixed::from_bits((self.next_u64() >> 48) as i32)
+    }
+
+    pub fn fixed_range(&mut self, min: Fixed, max: Fixed) -> Fixed {
+        if min >= max {
+            return min;
+        }
+        min + self.fixed() * (max - min)
+    }
+
     pub fn bool(&mut self) -> bool {
         self.next_u64() & 1 == 1
     }
@@ -221,6 +236,16 @@ mod tests {
         assert_ne!(vals_a, vals_b);
     }
 
+    #[test]
+    fn fixed_range_bounds() {
+        let mut rng = Rng::new(7);
+        let (min, max) = (Fixed::from_int(-3), Fixed::from_ratio(5, 2));
+        for _ in 0..1000 {
+            let v = rng.fixed_range(min, max);
+            assert!(v >= min && v < max);
+        }
+    }
+
     #[test]
     fn range_bounds() {
         let mut rng = Rng::new(42);
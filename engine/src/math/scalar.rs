@@ -0,0 +1,271 @@
+//! The [`Scalar`] abstraction that lets collision, movement and tweening run
+//! on either `f32` or deterministic [`Fixed`] numbers.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Rem, Sub, SubAssign};
+
+use glam::Vec2;
+
+use crate::math::fixed::{Fixed, FixedRect, FixedVec2};
+use crate::math::rect::Rect;
+use crate::math::tween::Easing;
+
+/// A number type the simulation helpers can be generic over: `f32` for
+/// ordinary games, [`Fixed`] for netplay simulations that must never desync.
+/// Each scalar names its matching 2D vector and rectangle types.
+pub trait Scalar:
+    Copy
+    + Default
+    + PartialOrd
+    + fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+{
+    type Vec2: Vector2<Self>;
+    type Rect: Bounds2<Self>;
+
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn from_int(n: i32) -> Self;
+    fn from_f32(value: f32) -> Self;
+    fn to_f32(self) -> f32;
+
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn clamp(self, min: Self, max: Self) -> Self;
+    fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn rem_euclid(self, rhs: Self) -> Self;
+
+    /// Evaluates `easing` at `t` (clamped to `[0, 1]`) in this number type.
+    fn ease(easing: Easing, t: Self) -> Self;
+}
+
+/// A 2D vector over a [`Scalar`]: [`glam::Vec2`] or [`FixedVec2`].
+pub trait Vector2<S>:
+    Copy
+    + PartialEq
+    + fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Neg<Output = Self>
+    + Mul<S, Output = Self>
+{
+    fn new(x: S, y: S) -> Self;
+    fn x(self) -> S;
+    fn y(self) -> S;
+}
+
+/// A y-up axis-aligned rectangle over a [`Scalar`]: [`Rect`] or [`FixedRect`].
+pub trait Bounds2<S: Scalar>: Copy + PartialEq + fmt::Debug {
+    fn from_origin_size(origin: S::Vec2, size: S::Vec2) -> Self;
+    /// The bottom-left corner.
+    fn origin(&self) -> S::Vec2;
+    fn size(&self) -> S::Vec2;
+
+    fn left(&self) -> S {
+        self.origin().x()
+    }
+
+    fn right(&self) -> S {
+        self.origin().x() + self.size().x()
+    }
+
+    fn bottom(&self) -> S {
+        self.origin().y()
+    }
+
+    fn top(&self) -> S {
+        self.origin().y() + self.size().y()
+    }
+
+    fn center(&self) -> S::Vec2 {
+        let two = S::from_int(2);
+        let (origin, size) = (self.origin(), self.size());
+        S::Vec2::new(origin.x() + size.x() / two, origin.y() + size.y() / two)
+    }
+
+    fn overlaps(&self, other: &Self) -> bool {
+        self.left() < other.right()
+            && self.right() > other.left()
+            && self.bottom() < other.top()
+            && self.top() > other.bottom()
+    }
+}
+
+impl Scalar for f32 {
+    type Vec2 = Vec2;
+    type Rect = Rect;
+
+    const ZERO: f32 = 0.0;
+    const ONE: f32 = 1.0;
+
+    fn from_int(n: i32) -> Self {
+        n as f32
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn min(self, other: Self) -> Self {
+        f32::min(self, other)
+    }
+
+    fn max(self, other: Self) -> Self {
+        f32::max(self, other)
+    }
+
+    fn clamp(self, min: Self, max: Self) -> Self {
+        f32::clamp(self, min, max)
+    }
+
+    fn abs(self) -> Self {
+        f32::abs(self)
+    }
+
+    fn sqrt(self) -> Self {
+        f32::sqrt(self)
+    }
+
+    fn sin(self) -> Self {
+        f32::sin(self)
+    }
+
+    fn cos(self) -> Self {
+        f32::cos(self)
+    }
+
+    fn rem_euclid(self, rhs: Self) -> Self {
+        f32::rem_euclid(self, rhs)
+    }
+
+    fn ease(easing: Easing, t: Self) -> Self {
+        easing.apply(t)
+    }
+}
+
+impl Scalar for Fixed {
+    type Vec2 = FixedVec2;
+    type Rect = FixedRect;
+
+    const ZERO: Fixed = Fixed::ZERO;
+    const ONE: Fixed = Fixed::ONE;
+
+    fn from_int(n: i32) -> Self {
+        Fixed::from_int(n)
+    }
+
+    fn from_f32(value: f32) -> Self {
+        Fixed::from_f32(value)
+    }
+
+    fn to_f32(self) -> f32 {
+        Fixed::to_f32(self)
+    }
+
+    fn min(self, other: Self) -> Self {
+        Fixed::min(self, other)
+    }
+
+    fn max(self, other: Self) -> Self {
+        Fixed::max(self, other)
+    }
+
+    fn clamp(self, min: Self, max: Self) -> Self {
+        Fixed::clamp(self, min, max)
+    }
+
+    fn abs(self) -> Self {
+        Fixed::abs(self)
+    }
+
+    fn sqrt(self) -> Self {
+        Fixed::sqrt(self)
+    }
+
+    fn sin(self) -> Self {
+        Fixed::sin(self)
+    }
+
+    fn cos(self) -> Self {
+        Fixed::cos(self)
+    }
+
+    fn rem_euclid(self, rhs: Self) -> Self {
+        Fixed::rem_euclid(self, rhs)
+    }
+
+    fn ease(easing: Easing, t: Self) -> Self {
+        easing.apply_fixed(t)
+    }
+}
+
+impl Vector2<f32> for Vec2 {
+    fn new(x: f32, y: f32) -> Self {
+        Vec2::new(x, y)
+    }
+
+    fn x(self) -> f32 {
+        self.x
+    }
+
+    fn y(self) -> f32 {
+        self.y
+    }
+}
+
+impl Vector2<Fixed> for FixedVec2 {
+    fn new(x: Fixed, y: Fixed) -> Self {
+        FixedVec2::new(x, y)
+    }
+
+    fn x(self) -> Fixed {
+        self.x
+    }
+
+    fn y(self) -> Fixed {
+        self.y
+    }
+}
+
+impl Bounds2<f32> for Rect {
+    fn from_origin_size(origin: Vec2, size: Vec2) -> Self {
+        Rect::from_pos_size(origin, size)
+    }
+
+    fn origin(&self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+
+    fn size(&self) -> Vec2 {
+        Vec2::new(self.width, self.height)
+    }
+}
+
+impl Bounds2<Fixed> for FixedRect {
+    fn from_origin_size(origin: FixedVec2, size: FixedVec2) -> Self {
+        FixedRect::new(origin.x, origin.y, size.x, size.y)
+    }
+
+    fn origin(&self) -> FixedVec2 {
+        FixedVec2::new(self.x, self.y)
+    }
+
+    fn size(&self) -> FixedVec2 {
+        FixedVec2::new(self.width, self.height)
+    }
+}
@@ -1,5 +1,8 @@
 use std::f32::consts::PI;
 
+use crate::math::fixed::Fixed;
+use crate::math::scalar::Scalar;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Easing {
     Linear,
@@ -154,6 +157,130 @@ impl Easing {
             }
         }
     }
+
+    /// [`Easing::apply`] evaluated entirely in [`Fixed`] arithmetic, so the
+    /// curve comes out bit-identical on every machine.
+    pub fn apply_fixed(self, t: Fixed) -> Fixed {
+        let t = t.clamp(Fixed::ZERO, Fixed::ONE);
+        let one = Fixed::ONE;
+        let two = Fixed::from_int(2);
+        let n = Fixed::from_int;
+        // 2^(a·t + b), the building block of the exponential curves.
+        let exp = |a: i32, b: f32| (n(a) * t + Fixed::from_f32(b)).exp2();
+        match self {
+            Easing::Linear => t,
+
+            Easing::InQuad => t * t,
+            Easing::OutQuad => one - (one - t) * (one - t),
+            Easing::InOutQuad => {
+                if t < Fixed::HALF {
+                    two * t * t
+                } else {
+                    one - (two - two * t).powi(2) / two
+                }
+            }
+
+            Easing::InCubic => t * t * t,
+            Easing::OutCubic => one - (one - t).powi(3),
+            Easing::InOutCubic => {
+                if t < Fixed::HALF {
+                    n(4) * t * t * t
+                } else {
+                    one - (two - two * t).powi(3) / two
+                }
+            }
+
+            Easing::InQuart => t * t * t * t,
+            Easing::OutQuart => one - (one - t).powi(4),
+            Easing::InOutQuart => {
+                if t < Fixed::HALF {
+                    n(8) * t * t * t * t
+                } else {
+                    one - (two - two * t).powi(4) / two
+                }
+            }
+
+            Easing::InSine => one - (t * Fixed::FRAC_PI_2).cos(),
+            Easing::OutSine => (t * Fixed::FRAC_PI_2).sin(),
+            Easing::InOutSine => -((t * Fixed::PI).cos() - one) / two,
+
+            Easing::InExpo => {
+                if t == Fixed::ZERO {
+                    t
+                } else {
+                    exp(10, -10.0)
+                }
+            }
+            Easing::OutExpo => {
+                if t == one {
+                    t
+                } else {
+                    one - exp(-10, 0.0)
+                }
+            }
+            Easing::InOutExpo => {
+                if t == Fixed::ZERO || t == one {
+                    t
+                } else if t < Fixed::HALF {
+                    exp(20, -10.0) / two
+                } else {
+                    (two - exp(-20, 10.0)) / two
+                }
+            }
+
+            Easing::InBack => {
+                let c = Fixed::from_f32(1.70158);
+                (c + one) * t * t * t - c * t * t
+            }
+            Easing::OutBack => {
+                let c = Fixed::from_f32(1.70158);
+                let u = t - one;
+                one + (c + one) * u * u * u + c * u * u
+            }
+            Easing::InOutBack => {
+                let c = Fixed::from_f32(1.70158 * 1.525);
+                if t < Fixed::HALF {
+                    ((two * t).powi(2) * ((c + one) * two * t - c)) / two
+                } else {
+                    let u = two * t - two;
+                    (u.powi(2) * ((c + one) * u + c) + two) / two
+                }
+            }
+
+            Easing::InElastic | Easing::OutElastic | Easing::InOutElastic
+                if t == Fixed::ZERO || t == one =>
+            {
+                t
+            }
+            Easing::InElastic => {
+                let c = Fixed::TAU / n(3);
+                -exp(10, -10.0) * ((n(10) * t - Fixed::from_f32(10.75)) * c).sin()
+            }
+            Easing::OutElastic => {
+                let c = Fixed::TAU / n(3);
+                exp(-10, 0.0) * ((n(10) * t - Fixed::from_f32(0.75)) * c).sin() + one
+            }
+            Easing::InOutElastic => {
+                let c = Fixed::TAU / Fixed::from_f32(4.5);
+                let wave = ((n(20) * t - Fixed::from_f32(11.125)) * c).sin();
+                if t < Fixed::HALF {
+                    -(exp(20, -10.0) * wave) / two
+                } else {
+                    exp(-20, 10.0) * wave / two + one
+                }
+            }
+
+            Easing::OutBounce => bounce_out_fixed(t),
+            Easing::InBounce => one - bounce_out_fixed(one - t),
+            Easing::InOutBounce => {
+                if t < Fixed::HALF {
+                    (one - bounce_out_fixed(one - two * t)) / two
+                } else {
+                    (one + bounce_out_fixed(two * t - one)) / two
+                }
+            }
+        }
+    }
 }
 
 fn bounce_out(t: f32) -> f32 {
@@ -173,6 +300,24 @@ fn bounce_out(t: f32) -> f32 {
     }
 }
 
+fn bounce_out_fixed(t: Fixed) -> Fixed {
+    let n = Fixed::from_f32(7.5625);
+    let d = Fixed::from_f32(2.75);
+    let step = |offset: f32, base: f32| {
+        let t = t - Fixed::from_f32(offset) / d;
+        n * t * t + Fixed::from_f32(base)
+    };
+    if t < Fixed::ONE / d {
+        n * t * t
+    } else if t < Fixed::from_int(2) / d {
+        step(1.5, 0.75)
+    } else if t < Fixed::from_f32(2.5) / d {
+        step(2.25, 0.9375)
+    } else {
+        step(2.625, 0.984375)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum LoopMode {
     Once,
@@ -180,12 +325,15 @@ pub enum LoopMode {
     PingPong,
 }
 
+/// Animates a value from `from` to `to` over `duration`. Generic over the
+/// [`Scalar`] it runs in: the default `f32`, or [`Fixed`] for tweens that
+/// drive rollback-simulated state and must evaluate identically on every peer.
 #[derive(Clone, Debug)]
-pub struct Tween {
-    from: f32,
-    to: f32,
-    duration: f32,
-    elapsed: f32,
+pub struct Tween<S: Scalar = f32> {
+    from: S,
+    to: S,
+    duration: S,
+    elapsed: S,
     easing: Easing,
     loop_mode: LoopMode,
     finished: bool,
@@ -193,12 +341,19 @@ pub struct Tween {
 
 impl Tween {
     pub fn new(from: f32, to: f32, duration: f32, easing: Easing) -> Self {
-        assert!(duration > 0.0, "tween duration must be > 0");
+        Self::between(from, to, duration, easing)
+    }
+}
+
+impl<S: Scalar> Tween<S> {
+    /// Like [`Tween::new`], in any [`Scalar`].
+    pub fn between(from: S, to: S, duration: S, easing: Easing) -> Self {
+        assert!(duration > S::ZERO, "tween duration must be > 0");
         Self {
             from,
             to,
             duration,
-            elapsed: 0.0,
+            elapsed: S::ZERO,
             easing,
             loop_mode: LoopMode::Once,
             finished: false,
@@ -210,7 +365,7 @@ impl Tween {
         self
     }
 
-    pub fn update(&mut self, dt: f32) {
+    pub fn update(&mut self, dt: S) {
         if self.finished {
             return;
         }
@@ -228,7 +383,7 @@ impl Tween {
                 }
             }
             LoopMode::PingPong => {
-                let cycle = 2.0 * self.duration;
+                let cycle = S::from_int(2) * self.duration;
                 if self.elapsed >= cycle {
                     self.elapsed = self.elapsed.rem_euclid(cycle);
                 }
@@ -236,20 +391,20 @@ impl Tween {
         }
     }
 
-    pub fn value(&self) -> f32 {
+    pub fn value(&self) -> S {
         let t = match self.loop_mode {
             LoopMode::Once | LoopMode::Loop => self.elapsed / self.duration,
             LoopMode::PingPong => {
                 let cycle = self.elapsed / self.duration;
-                let phase = cycle % 2.0;
-                if phase <= 1.0 {
+                let phase = cycle % S::from_int(2);
+                if phase <= S::ONE {
                     phase
                 } else {
-                    2.0 - phase
+                    S::from_int(2) - phase
                 }
             }
         };
-        let eased = self.easing.apply(t);
+        let eased = S::ease(self.easing, t);
         self.from + (self.to - self.from) * eased
     }
 
@@ -258,12 +413,12 @@ impl Tween {
     }
 
     pub fn reset(&mut self) {
-        self.elapsed = 0.0;
+        self.elapsed = S::ZERO;
         self.finished = false;
     }
 
-    pub fn progress(&self) -> f32 {
-        (self.elapsed / self.duration).clamp(0.0, 1.0)
+    pub fn progress(&self) -> S {
+        (self.elapsed / self.duration).clamp(S::ZERO, S::ONE)
     }
 }
 
@@ -279,6 +434,34 @@ pub fn ease(from: f32, to: f32, t: f32, easing: Easing) -> f32 {
 mod tests {
     use super::*;
 
+    const ALL: [Easing; 25] = [
+        Easing::Linear,
+        Easing::InQuad,
+        Easing::OutQuad,
+        Easing::InOutQuad,
+        Easing::InCubic,
+        Easing::OutCubic,
+        Easing::InOutCubic,
+        Easing::InQuart,
+        Easing::OutQuart,
+        Easing::InOutQuart,
+        Easing::InSine,
+        Easing::OutSine,
+        Easing::InOutSine,
+        Easing::InExpo,
+        Easing::OutExpo,
+        Easing::InOutExpo,
+        Easing::InBack,
+        Easing::OutBack,
+        Easing::InOutBack,
+        Easing::InElastic,
+        Easing::OutElastic,
+        Easing::InOutElastic,
+        Easing::InBounce,
+        Easing::OutBounce,
+        Easing::InOutBounce,
+    ];
+
     #[test]
     fn linear_tween_interpolates() {
         let mut tw = Tween::new(0.0, 100.0, 1.0, Easing::Linear);
@@ -329,33 +512,7 @@ mod tests {
 
     #[test]
     fn easing_boundaries() {
-        for easing in [
-            Easing::Linear,
-            Easing::InQuad,
-            Easing::OutQuad,
-            Easing::InOutQuad,
-            Easing::InCubic,
-            Easing::OutCubic,
-            Easing::InOutCubic,
-            Easing::InQuart,
-            Easing::OutQuart,
-            Easing::InOutQuart,
-            Easing::InSine,
-            Easing::OutSine,
-            Easing::InOutSine,
-            Easing::InExpo,
-            Easing::OutExpo,
-            Easing::InOutExpo,
-            Easing::InBack,
-            Easing::OutBack,
-            Easing::InOutBack,
-            Easing::InElastic,
-            Easing::OutElastic,
-            Easing::InOutElastic,
-            Easing::InBounce,
-            Easing::OutBounce,
-            Easing::InOutBounce,
-        ] {
+        for easing in ALL {
             let v0 = easing.apply(0.0);
             let v1 = easing.apply(1.0);
             assert!((v0 - 0.0).abs() < 0.001, "{easing:?} at t=0 was {v0}");
@@ -363,6 +520,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn fixed_tweens_match_f32_and_land_exactly() {
+        let mut fixed = Tween::between(
+            Fixed::ZERO,
+            Fixed::from_int(100),
+            Fixed::ONE,
+            Easing::InOutCubic,
+        )
+        .looping(LoopMode::PingPong);
+        let mut float = Tween::new(0.0, 100.0, 1.0, Easing::InOutCubic).looping(LoopMode::PingPong);
+        for _ in 0..100 {
+            fixed.update(Fixed::from_ratio(1, 64));
+            float.update(1.0 / 64.0);
+            assert!((fixed.value().to_f32() - float.value()).abs() < 0.01);
+        }
+
+        let mut once = Tween::between(
+            Fixed::ONE,
+            Fixed::from_int(5),
+            Fixed::HALF,
+            Easing::OutBounce,
+        );
+        once.update(Fixed::ONE);
+        assert!(once.is_finished());
+        assert_eq!(once.value(), Fixed::from_int(5));
+        assert_eq!(once.progress(), Fixed::ONE);
+    }
+
+    #[test]
+    fn fixed_easings_track_their_f32_curves() {
+        for easing in ALL {
+            for i in 0..=20 {
+                let t = Fixed::from_ratio(i, 20);
+                let fixed = easing.apply_fixed(t).to_f32();
+                let float = easing.apply(t.to_f32());
+                assert!(
+                    (fixed - float).abs() < 2e-3,
+                    "{easing:?} at {t}: {fixed} vs {float}"
+                );
+            }
+        }
+    }
+
     #[test]
     fn ease_helper() {
         let v = ease(10.0, 20.0, 0.5, Easing::Linear);
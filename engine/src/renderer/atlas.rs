@@ -0,0 +1,385 @@
+//! Runtime texture atlas.
+//!
+//! The sprite batcher draws every run of same-texture sprites with one call,
+//! so a scene built from dozens of small PNGs breaks its batch at nearly every
+//! sprite. Textures loaded through [`Engine::create_packed_texture`] (or tagged
+//! in a manifest's `atlas` list) are instead copied into shared
+//! [`ATLAS_PAGE_SIZE`]-square pages. Each keeps its own [`TextureId`]; the
+//! renderer resolves the id to its page and sub-rect when it builds a frame,
+//! so `DrawParams`, `SpriteSheet`, `NineSlice` and canvas images need no
+//! changes to benefit.
+//!
+//! [`Engine::create_packed_texture`]: crate::Engine::create_packed_texture
+
+use super::texture::TextureId;
+
+/// Width and height of one atlas page, in pixels.
+pub(crate) const ATLAS_PAGE_SIZE: u32 = 2048;
+
+/// Largest texture, on either axis, that is packed. Bigger images — level
+/// backgrounds, full-screen art — gain nothing from sharing a page and would
+/// crowd the small sprites out, so they stay standalone.
+pub(crate) const MAX_PACKED_SIZE: u32 = 1024;
+
+/// Border copied around every packed texture. Sampling right at a sprite's
+/// edge can round onto the neighbouring texel; with the edge repeated there,
+/// it lands on the sprite's own colour instead of its neighbour's.
+const PADDING: u32 = 1;
+
+/// Where a packed texture lives: an atlas page and the part of it the texture
+/// covers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtlasRegion {
+    pub page: TextureId,
+    /// `[u, v, width, height]` inside the page, in the same 0–1 units as
+    /// [`DrawParams::uv_rect`](super::DrawParams::uv_rect).
+    pub uv_rect: [f32; 4],
+}
+
+impl AtlasRegion {
+    /// Map a uv rect given relative to the packed texture — a sprite sheet
+    /// cell, say — into the page.
+    pub fn map_uv_rect(&self, [u, v, width, height]: [f32; 4]) -> [f32; 4] {
+        let [u0, v0, region_width, region_height] = self.uv_rect;
+        [
+            u0 + u * region_width,
+            v0 + v * region_height,
+            width * region_width,
+            height * region_height,
+        ]
+    }
+
+    pub(crate) fn map_uv(&self, [u, v]: [f32; 2]) -> [f32; 2] {
+        let [u0, v0, region_width, region_height] = self.uv_rect;
+        [u0 + u * region_width, v0 + v * region_height]
+    }
+}
+
+/// A packed texture's place in its page: the region it draws from plus the
+/// pixel rect, padding excluded, its pixels were written to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct AtlasSlot {
+    pub region: AtlasRegion,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// The size reserved when the texture was packed. A replacement no
+    /// bigger than this reuses the space; the skyline never frees it.
+    room: (u32, u32),
+}
+
+impl AtlasSlot {
+    /// This slot holding a `width` × `height` texture instead, if that fits
+    /// in the room it was packed with.
+    pub fn resized(&self, width: u32, height: u32) -> Option<AtlasSlot> {
+        if width == 0 || height == 0 || width > self.room.0 || height > self.room.1 {
+            return None;
+        }
+        Some(AtlasSlot {
+            region: AtlasRegion {
+                page: self.region.page,
+                uv_rect: page_uv_rect(self.x, self.y, width, height),
+            },
+            width,
+            height,
+            ..*self
+        })
+    }
+
+    /// Where the padded copy of the pixels goes.
+    pub fn padded_origin(&self) -> (u32, u32) {
+        (self.x - PADDING, self.y - PADDING)
+    }
+
+    pub fn padded_size(&self) -> (u32, u32) {
+        (self.width + 2 * PADDING, self.height + 2 * PADDING)
+    }
+}
+
+/// Bottom-left skyline packer.
+///
+/// The packed area's top edge is kept as a list of horizontal segments. A
+/// rect goes wherever its top would end up lowest, which fills pages with
+/// mixed sprite sizes far more tightly than fixed shelves do. Space is never
+/// freed; a page is only reclaimed by dropping the whole atlas.
+#[derive(Debug, Clone)]
+pub(crate) struct Skyline {
+    width: u32,
+    height: u32,
+    /// `(x, y, width)` of each segment, left to right, covering `0..width`.
+    segments: Vec<(u32, u32, u32)>,
+}
+
+impl Skyline {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            segments: vec![(0, 0, width)],
+        }
+    }
+
+    /// Reserve a `width` × `height` rect, returning its top-left corner.
+    pub fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        if width == 0 || height == 0 || width > self.width || height > self.height {
+            return None;
+        }
+        // Lowest resulting top first, then the narrowest segment to waste
+        // the least of the skyline under the rect.
+        let (index, x, y) = (0..self.segments.len())
+            .filter_map(|index| {
+                let y = self.fit(index, width, height)?;
+                Some((index, self.segments[index].0, y))
+            })
+            .min_by_key(|&(index, _, y)| (y + height, self.segments[index].2))?;
+
+        self.segments.insert(index, (x, y + height, width));
+        let right = x + width;
+        let next = index + 1;
+        while next < self.segments.len() {
+            let (seg_x, seg_y, seg_width) = self.segments[next];
+            if seg_x >= right {
+                break;
+            }
+            let seg_right = seg_x + seg_width;
+            if seg_right <= right {
+                self.segments.remove(next);
+            } else {
+                self.segments[next] = (right, seg_y, seg_right - right);
+                break;
+            }
+        }
+        // Neighbours at the same height are one segment.
+        self.segments.dedup_by(|right, left| {
+            if left.1 == right.1 {
+                left.2 += right.2;
+                true
+            } else {
+                false
+            }
+        });
+        Some((x, y))
+    }
+
+    /// The y a `width` × `height` rect would sit at with its left edge on
+    /// segment `index`, if it fits there.
+    fn fit(&self, index: usize, width: u32, height: u32) -> Option<u32> {
+        let x = self.segments[index].0;
+        if x + width > self.width {
+            return None;
+        }
+        let mut y = 0;
+        let mut covered = 0;
+        for &(_, seg_y, seg_width) in &self.segments[index..] {
+            y = y.max(seg_y);
+            covered += seg_width;
+            if covered >= width {
+                break;
+            }
+        }
+        (y + height <= self.height).then_some(y)
+    }
+}
+
+struct AtlasPage {
+    texture: TextureId,
+    skyline: Skyline,
+}
+
+/// The renderer's atlas pages. Pages are ordinary textures; the atlas only
+/// tracks which ones are pages and how full each is.
+#[derive(Default)]
+pub(crate) struct TextureAtlas {
+    pages: Vec<AtlasPage>,
+}
+
+impl TextureAtlas {
+    /// Find room for a `width` × `height` texture. When no page has any, a
+    /// new page is opened as `new_page`, which the caller must then create.
+    /// `None` for textures too big to pack.
+    pub fn allocate(&mut self, width: u32, height: u32, new_page: TextureId) -> Option<AtlasSlot> {
+        if width == 0 || height == 0 || width > MAX_PACKED_SIZE || height > MAX_PACKED_SIZE {
+            return None;
+        }
+        let (padded_width, padded_height) = (width + 2 * PADDING, height + 2 * PADDING);
+        let found = self.pages.iter_mut().find_map(|page| {
+            let origin = page.skyline.allocate(padded_width, padded_height)?;
+            Some((page.texture, origin))
+        });
+        let (page, (x, y)) = match found {
+            Some(found) => found,
+            None => {
+                let mut skyline = Skyline::new(ATLAS_PAGE_SIZE, ATLAS_PAGE_SIZE);
+                let origin = skyline.allocate(padded_width, padded_height)?;
+                self.pages.push(AtlasPage {
+                    texture: new_page,
+                    skyline,
+                });
+                (new_page, origin)
+            }
+        };
+        let (x, y) = (x + PADDING, y + PADDING);
+        Some(AtlasSlot {
+            region: AtlasRegion {
+                page,
+                uv_rect: page_uv_rect(x, y, width, height),
+            },
+            x,
+            y,
+            width,
+            height,
+            room: (width, height),
+        })
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+}
+
+/// A pixel rect inside a page as a 0–1 uv rect.
+fn page_uv_rect(x: u32, y: u32, width: u32, height: u32) -> [f32; 4] {
+    let size = ATLAS_PAGE_SIZE as f32;
+    [
+        x as f32 / size,
+        y as f32 / size,
+        width as f32 / size,
+        height as f32 / size,
+    ]
+}
+
+/// `pixels` (RGBA, `width` × `height`) with its outermost rows and columns
+/// repeated into a [`PADDING`]-wide border.
+pub(crate) fn extrude(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+    let (width, height, padding) = (width as usize, height as usize, PADDING as usize);
+    let padded_width = width + 2 * padding;
+    let padded_height = height + 2 * padding;
+    let mut out = Vec::with_capacity(padded_width * padded_height * 4);
+    for row in 0..padded_height {
+        let src_row = row.saturating_sub(padding).min(height - 1);
+        for col in 0..padded_width {
+            let src_col = col.saturating_sub(padding).min(width - 1);
+            let at = (src_row * width + src_col) * 4;
+            out.extend_from_slice(&pixels[at..at + 4]);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overlaps(a: (u32, u32, u32, u32), b: (u32, u32, u32, u32)) -> bool {
+        a.0 < b.0 + b.2 && b.0 < a.0 + a.2 && a.1 < b.1 + b.3 && b.1 < a.1 + a.3
+    }
+
+    #[test]
+    fn skyline_packs_mixed_sizes_without_overlap() {
+        let mut skyline = Skyline::new(64, 64);
+        let sizes = [
+            (16, 16),
+            (32, 8),
+            (8, 24),
+            (16, 16),
+            (24, 12),
+            (8, 8),
+            (40, 10),
+        ];
+        let mut placed = Vec::new();
+        for (width, height) in sizes {
+            let (x, y) = skyline.allocate(width, height).expect("room left");
+            assert!(x + width <= 64 && y + height <= 64);
+            let rect = (x, y, width, height);
+            assert!(
+                placed.iter().all(|&other| !overlaps(rect, other)),
+                "{rect:?}"
+            );
+            placed.push(rect);
+        }
+        // The first row fills left to right before anything stacks.
+        assert_eq!(
+            &placed[..3],
+            &[(0, 0, 16, 16), (16, 0, 32, 8), (48, 0, 8, 24)]
+        );
+    }
+
+    #[test]
+    fn skyline_fills_a_page_exactly_then_refuses() {
+        let mut skyline = Skyline::new(32, 32);
+        for _ in 0..16 {
+            assert!(skyline.allocate(8, 8).is_some());
+        }
+        assert_eq!(skyline.segments, [(0, 32, 32)]);
+        assert_eq!(skyline.allocate(1, 1), None);
+        assert_eq!(Skyline::new(32, 32).allocate(33, 1), None);
+    }
+
+    #[test]
+    fn atlas_opens_pages_as_they_fill_and_skips_big_textures() {
+        let mut atlas = TextureAtlas::default();
+        let first = atlas.allocate(1000, 1000, TextureId(11)).unwrap();
+        assert_eq!(first.region.page, TextureId(11));
+        assert_eq!((first.x, first.y), (PADDING, PADDING));
+        assert_eq!(first.padded_origin(), (0, 0));
+        // Four 1000px squares plus padding fill a 2048 page; a new page only
+        // opens once they have.
+        for _ in 0..3 {
+            let slot = atlas.allocate(1000, 1000, TextureId(12)).unwrap();
+            assert_eq!(slot.region.page, TextureId(11));
+        }
+        let small = atlas.allocate(16, 16, TextureId(12)).unwrap();
+        assert_eq!(small.region.page, TextureId(11));
+        let spilled = atlas.allocate(1000, 1000, TextureId(12)).unwrap();
+        assert_eq!(spilled.region.page, TextureId(12));
+        assert_eq!(atlas.page_count(), 2);
+        assert!(atlas
+            .allocate(MAX_PACKED_SIZE + 1, 4, TextureId(13))
+            .is_none());
+        assert_eq!(atlas.page_count(), 2);
+    }
+
+    #[test]
+    fn a_slot_takes_back_anything_that_fits_its_room() {
+        let mut atlas = TextureAtlas::default();
+        let slot = atlas.allocate(32, 16, TextureId(4)).unwrap();
+        assert_eq!(slot.resized(32, 16), Some(slot));
+
+        let smaller = slot.resized(8, 16).unwrap();
+        assert_eq!((smaller.x, smaller.y), (slot.x, slot.y));
+        assert_eq!(smaller.region.uv_rect[2], 8.0 / ATLAS_PAGE_SIZE as f32);
+        // Shrinking keeps the room, so growing back still fits.
+        assert_eq!(smaller.resized(32, 16), Some(slot));
+        assert_eq!(smaller.resized(33, 16), None);
+        assert_eq!(slot.resized(0, 16), None);
+    }
+
+    #[test]
+    fn regions_map_texture_uvs_into_the_page() {
+        let region = AtlasRegion {
+            page: TextureId(0),
+            uv_rect: [0.5, 0.25, 0.125, 0.25],
+        };
+        assert_eq!(region.map_uv_rect([0.0, 0.0, 1.0, 1.0]), region.uv_rect);
+        // The second of four cells across a sprite sheet.
+        assert_eq!(
+            region.map_uv_rect([0.25, 0.0, 0.25, 1.0]),
+            [0.53125, 0.25, 0.03125, 0.25]
+        );
+        assert_eq!(region.map_uv([1.0, 0.5]), [0.625, 0.375]);
+    }
+
+    #[test]
+    fn extrude_repeats_edge_pixels_into_the_border() {
+        // 2×1: red then green.
+        let pixels = [255, 0, 0, 255, 0, 255, 0, 255];
+        let padded = extrude(2, 1, &pixels);
+        assert_eq!(padded.len(), 4 * 3 * 4);
+        let red = [255, 0, 0, 255];
+        let green = [0, 255, 0, 255];
+        for row in padded.chunks(16) {
+            assert_eq!(row, [red, red, green, green].concat());
+        }
+    }
+}
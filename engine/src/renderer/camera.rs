@@ -1,3 +1,4 @@
+use crate::math::Rect;
 use glam::{Mat4, Vec2};
 
 pub struct Camera2D {
@@ -108,6 +109,19 @@ impl Camera2D {
         ortho * view
     }
 
+    /// The world-space box a viewport of this size shows, grown to cover
+    /// the view when the camera is rotated.
+    pub fn view_rect(&self, viewport_width: f32, viewport_height: f32) -> Rect {
+        let half = Vec2::new(viewport_width, viewport_height) / 2.0 / self.zoom;
+        let (sin, cos) = self.rotation.sin_cos();
+        let extent = Vec2::new(
+            half.x * cos.abs() + half.y * sin.abs(),
+            half.x * sin.abs() + half.y * cos.abs(),
+        );
+        let pos = self.position + self.shake_offset - extent;
+        Rect::new(pos.x, pos.y, extent.x * 2.0, extent.y * 2.0)
+    }
+
     pub fn world_to_screen(&self, world_pos: Vec2) -> Vec2 {
         let pos = self.position + self.shake_offset;
         let dx = world_pos.x - pos.x;
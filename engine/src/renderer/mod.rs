@@ -1,9 +1,11 @@
+pub mod atlas;
 pub mod camera;
 pub mod nineslice;
 pub mod postfx;
 pub mod sprite;
 pub mod texture;
 
+pub use atlas::AtlasRegion;
 pub use camera::{Camera2D, CameraBounds};
 pub use nineslice::NineSlice;
 pub use postfx::{PostEffect, PostFxChain};
@@ -16,6 +18,7 @@ use crate::canvas::{self, Canvas, DrawTexture};
 use crate::text;
 use crate::text::FontAtlas;
 
+use atlas::{AtlasSlot, TextureAtlas};
 use postfx::PostFxPipeline;
 use sprite::Vertex;
 
@@ -84,6 +87,12 @@ impl Frame {
         self.screen_size
     }
 
+    /// The world-space box the camera shows this frame.
+    pub fn view_rect(&self) -> crate::math::Rect {
+        let (width, height) = self.screen_size;
+        self.camera.view_rect(width as f32, height as f32)
+    }
+
     pub fn draw_sprite(&mut self, params: DrawParams) {
         self.sprites.push(params);
     }
@@ -178,6 +187,40 @@ impl Frame {
     }
 }
 
+/// A sprite ready to batch: the texture actually bound for it, which for a
+/// packed texture is its atlas page, and its uv rect in that texture.
+type SortedSprite<'a> = (usize, [f32; 4], &'a DrawParams);
+
+/// Resolve packed textures to their pages, then order sprites by z and, within
+/// a z, by the texture bound, so every run sharing one is drawn in one call.
+fn sort_sprites(
+    sprites: &[DrawParams],
+    region: impl Fn(TextureId) -> Option<AtlasRegion>,
+) -> Vec<SortedSprite<'_>> {
+    let mut sorted: Vec<SortedSprite> = sprites
+        .iter()
+        .map(|sprite| match region(sprite.texture) {
+            Some(region) => (region.page.0, region.map_uv_rect(sprite.uv_rect), sprite),
+            None => (sprite.texture.0, sprite.uv_rect, sprite),
+        })
+        .collect();
+    sorted.sort_by(|a, b| a.2.z_order.cmp(&b.2.z_order).then(a.0.cmp(&b.0)));
+    sorted
+}
+
+/// `(texture, sprite count)` for each run of consecutive sprites that bind
+/// the same texture.
+fn sprite_batches(sorted: &[SortedSprite]) -> Vec<(usize, u32)> {
+    let mut batches: Vec<(usize, u32)> = Vec::new();
+    for &(texture, _, _) in sorted {
+        match batches.last_mut() {
+            Some((current, count)) if *current == texture => *count += 1,
+            _ => batches.push((texture, 1)),
+        }
+    }
+    batches
+}
+
 fn push_active_render_target(active_targets: &mut Vec<usize>, texture_index: usize) {
     assert!(
         !active_targets.contains(&texture_index),
@@ -215,10 +258,20 @@ fn validate_render_target_frame(frame: &mut Frame, texture_index: usize) {
     );
 }
 
+#[derive(Clone)]
 struct GpuTexture {
-    _texture: wgpu::Texture,
+    texture: wgpu::Texture,
     view: wgpu::TextureView,
     bind_group: wgpu::BindGroup,
+    /// Set for packed textures, whose GPU handles are their atlas page's.
+    packed: Option<AtlasSlot>,
+}
+
+fn packed_region(textures: &[GpuTexture], index: usize) -> Option<AtlasRegion> {
+    textures
+        .get(index)
+        .and_then(|texture| texture.packed)
+        .map(|slot| slot.region)
 }
 
 #[derive(Debug, Clone)]
@@ -253,6 +306,7 @@ pub(crate) struct Renderer {
     sampler: wgpu::Sampler,
     pub(crate) white_texture: TextureId,
     render_targets: Vec<TextureId>,
+    atlas: TextureAtlas,
 
     canvas_pipeline: wgpu::RenderPipeline,
     canvas_vb: wgpu::Buffer,
@@ -470,6 +524,7 @@ impl Renderer {
             sampler,
             white_texture: TextureId(0),
             render_targets: Vec::new(),
+            atlas: TextureAtlas::default(),
             canvas_pipeline,
             canvas_vb,
             canvas_vb_capacity,
@@ -565,9 +620,10 @@ impl Renderer {
         });
 
         GpuTexture {
-            _texture: texture,
+            texture,
             view,
             bind_group,
+            packed: None,
         }
     }
 
@@ -589,9 +645,105 @@ impl Renderer {
         id
     }
 
-    pub fn replace_texture(&mut self, id: TextureId, width: u32, height: u32, pixels: &[u8]) {
-        self.textures[id.0] =
-            self.build_texture(width, height, Some(pixels), false, "sprite_texture");
+    /// Like [`create_texture`](Self::create_texture), but copies the pixels
+    /// into a shared atlas page so sprites drawn from it batch with every
+    /// other packed texture on that page. Textures too big to pack get a
+    /// texture of their own.
+    pub fn create_packed_texture(&mut self, width: u32, height: u32, pixels: &[u8]) -> TextureId {
+        let Some(slot) = self.allocate_packed(width, height) else {
+            return self.create_texture(width, height, pixels);
+        };
+        self.write_packed(&slot, pixels);
+        let id = TextureId(self.textures.len());
+        self.textures.push(self.packed_texture(slot));
+        id
+    }
+
+    /// Swap a texture's pixels. A packed texture is rewritten in place as
+    /// long as the new size fits the space it was packed into; a bigger one
+    /// moves out to a texture of its own, since atlas space is never freed
+    /// and a new slot per resize would leak the old one. Returns the
+    /// texture's atlas region afterwards, if it still has one.
+    pub fn replace_texture(
+        &mut self,
+        id: TextureId,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+    ) -> Option<AtlasRegion> {
+        match self.textures[id.0]
+            .packed
+            .and_then(|slot| slot.resized(width, height))
+        {
+            Some(slot) => {
+                self.write_packed(&slot, pixels);
+                self.textures[id.0] = self.packed_texture(slot);
+                Some(slot.region)
+            }
+            None => {
+                self.textures[id.0] =
+                    self.build_texture(width, height, Some(pixels), false, "sprite_texture");
+                None
+            }
+        }
+    }
+
+    /// The atlas page and sub-rect a packed texture draws from.
+    pub fn atlas_region(&self, id: TextureId) -> Option<AtlasRegion> {
+        packed_region(&self.textures, id.0)
+    }
+
+    pub fn atlas_page_count(&self) -> usize {
+        self.atlas.page_count()
+    }
+
+    fn allocate_packed(&mut self, width: u32, height: u32) -> Option<AtlasSlot> {
+        let next = TextureId(self.textures.len());
+        let slot = self.atlas.allocate(width, height, next)?;
+        if slot.region.page == next {
+            let size = atlas::ATLAS_PAGE_SIZE;
+            let page = self.build_texture(size, size, None, false, "atlas_page");
+            self.textures.push(page);
+        }
+        Some(slot)
+    }
+
+    /// A texture slot that binds `slot`'s page.
+    fn packed_texture(&self, slot: AtlasSlot) -> GpuTexture {
+        GpuTexture {
+            packed: Some(slot),
+            ..self.textures[slot.region.page.0].clone()
+        }
+    }
+
+    fn write_packed(&self, slot: &AtlasSlot, pixels: &[u8]) {
+        assert_eq!(
+            pixels.len(),
+            (slot.width * slot.height * 4) as usize,
+            "pixel data length must match width × height × 4"
+        );
+        let padded = atlas::extrude(slot.width, slot.height, pixels);
+        let (x, y) = slot.padded_origin();
+        let (width, height) = slot.padded_size();
+        self.queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.textures[slot.region.page.0].texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            &padded,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
     }
 
     pub fn create_render_target(&mut self, width: u32, height: u32) -> RenderTarget {
@@ -644,18 +796,14 @@ impl Renderer {
             bytemuck::cast_slice(&projection.to_cols_array()),
         );
 
-        let mut sorted: Vec<&DrawParams> = frame.sprites.iter().collect();
-        sorted.sort_by(|a, b| {
-            a.z_order
-                .cmp(&b.z_order)
-                .then(a.texture.0.cmp(&b.texture.0))
-        });
+        let textures = &self.textures;
+        let sorted = sort_sprites(&frame.sprites, |texture| packed_region(textures, texture.0));
 
         let mut vertices: Vec<Vertex> = Vec::with_capacity(sorted.len() * 4);
-        for sp in &sorted {
+        for &(_, uv_rect, sp) in &sorted {
             let (x, y, w, h) = (sp.position.x, sp.position.y, sp.size.x, sp.size.y);
             let color = sp.color.to_array();
-            let [u0, v0, uw, vh] = sp.uv_rect;
+            let [u0, v0, uw, vh] = uv_rect;
             let (mut ul, mut ur) = (u0, u0 + uw);
             let (mut vt, mut vb) = (v0, v0 + vh);
             if sp.flip_x {
@@ -704,23 +852,7 @@ impl Renderer {
                 .write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&vertices));
         }
 
-        let mut batches: Vec<(usize, u32)> = Vec::new();
-        if !sorted.is_empty() {
-            let mut cur_tex = sorted[0].texture.0;
-            let mut count = 1u32;
-            for sp in sorted.iter().skip(1) {
-                if sp.texture.0 == cur_tex {
-                    count += 1;
-                } else {
-                    batches.push((cur_tex, count));
-                    cur_tex = sp.texture.0;
-                    count = 1;
-                }
-            }
-            batches.push((cur_tex, count));
-        }
-
-        batches
+        sprite_batches(&sorted)
     }
 
     fn render_frame_to_texture(
@@ -783,6 +915,9 @@ impl Renderer {
         let queue = &self.queue;
         let fonts = &self.fonts;
         let textures = &self.textures;
+        canvas::resolve_packed_textures(&mut frame.canvases, |texture_id| {
+            packed_region(textures, texture_id)
+        });
         canvas::render_pass(
             device,
             encoder,
@@ -971,6 +1106,9 @@ impl Renderer {
         let queue = &self.queue;
         let fonts = &self.fonts;
         let textures = &self.textures;
+        canvas::resolve_packed_textures(&mut frame.canvases, |texture_id| {
+            packed_region(textures, texture_id)
+        });
         canvas::render_pass(
             device,
             encoder,
@@ -1353,11 +1491,44 @@ pub(crate) fn window_to_canvas(
 #[cfg(test)]
 mod tests {
     use super::{
-        push_active_render_target, validate_render_target_frame, DrawParams, Frame, RenderTarget,
-        TextureId,
+        push_active_render_target, sort_sprites, sprite_batches, validate_render_target_frame,
+        AtlasRegion, DrawParams, Frame, RenderTarget, TextureId,
     };
     use crate::canvas::Canvas;
 
+    #[test]
+    fn packed_textures_on_one_page_share_a_batch() {
+        let sprite = |texture: usize, z: i32| {
+            DrawParams::new(TextureId(texture), glam::Vec2::ZERO, glam::Vec2::ONE)
+                .with_uv_rect([0.5, 0.0, 0.5, 1.0])
+                .with_z_order(z)
+        };
+        // Textures 1 and 2 are packed on page 7; texture 3 stands alone.
+        let sprites = [sprite(1, 0), sprite(3, 0), sprite(2, 0), sprite(1, 1)];
+        let region = |texture: TextureId| match texture.0 {
+            1 => Some(AtlasRegion {
+                page: TextureId(7),
+                uv_rect: [0.0, 0.0, 0.25, 0.25],
+            }),
+            2 => Some(AtlasRegion {
+                page: TextureId(7),
+                uv_rect: [0.25, 0.0, 0.25, 0.25],
+            }),
+            _ => None,
+        };
+
+        let sorted = sort_sprites(&sprites, region);
+        // Without the atlas this is four batches; the page carries on across
+        // the z change too.
+        assert_eq!(sprite_batches(&sorted), [(3, 1), (7, 3)]);
+        // A sprite's uv rect is relative to its own texture, and lands inside
+        // that texture's part of the page.
+        let uvs: Vec<_> = sorted.iter().map(|&(_, uv, _)| uv).collect();
+        assert_eq!(uvs[0], [0.5, 0.0, 0.5, 1.0]);
+        assert_eq!(uvs[1], [0.125, 0.0, 0.125, 0.25]);
+        assert_eq!(uvs[2], [0.375, 0.0, 0.125, 0.25]);
+    }
+
     #[test]
     fn render_target_exposes_texture_and_size() {
         let target = RenderTarget {
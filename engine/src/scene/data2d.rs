@@ -894,11 +894,11 @@ fn draw_ui_kind_dyn(
             let text = ui_text_or_placeholder(&get);
             if !text.is_empty() {
                 let color = parse_srgb_color(get("ui_color").as_deref(), Color::WHITE);
-                let align = parse_text_align(get("ui_text_align").as_deref());
+                let align = parse_text_align(get("ui_text_align").as_deref()).resolve(&text);
                 let anchor_x = match align {
-                    TextAlign::Left => tx,
+                    TextAlign::Left | TextAlign::Start => tx,
                     TextAlign::Center => tx + tw * 0.5,
-                    TextAlign::Right => tx + tw,
+                    TextAlign::Right | TextAlign::End => tx + tw,
                 };
                 let top = line_top(canvas);
                 canvas.text_aligned_in(font, anchor_x, top, &text, size, color, align);
@@ -908,11 +908,11 @@ fn draw_ui_kind_dyn(
             let color = parse_srgb_color(get("ui_color").as_deref(), Color::WHITE);
             let size = prop_f32("ui_text_size").unwrap_or(12.0);
             let text = ui_text_or_placeholder(&get);
-            let align = parse_text_align(get("ui_text_align").as_deref());
+            let align = parse_text_align(get("ui_text_align").as_deref()).resolve(&text);
             let anchor_x = match align {
-                TextAlign::Left => tx,
+                TextAlign::Left | TextAlign::Start => tx,
                 TextAlign::Center => tx + tw * 0.5,
-                TextAlign::Right => tx + tw,
+                TextAlign::Right | TextAlign::End => tx + tw,
             };
             // Canvas text takes the line box's TOP. `ty` is the padded box's
             // bottom (y-up), so a line centred in `th` starts half the slack
@@ -926,12 +926,12 @@ fn draw_ui_kind_dyn(
             let color = parse_srgb_color(get("ui_color").as_deref(), Color::WHITE);
             let size = prop_f32("ui_text_size").unwrap_or(12.0);
             let text = ui_text_or_placeholder(&get);
-            let align = parse_text_align(get("ui_text_align").as_deref());
+            let align = parse_text_align(get("ui_text_align").as_deref()).resolve(&text);
             let wrap_w = prop_f32("ui_wrap_w").unwrap_or(tw);
             let anchor_x = match align {
-                TextAlign::Left => tx,
+                TextAlign::Left | TextAlign::Start => tx,
                 TextAlign::Center => tx + tw * 0.5,
-                TextAlign::Right => tx + tw,
+                TextAlign::Right | TextAlign::End => tx + tw,
             };
             canvas.text_block_leaded_in(
                 font,
@@ -1081,11 +1081,15 @@ fn contains_unresolved_binding(text: &str) -> bool {
         .is_some_and(|close| close > 0 && !text[open + 1..open + 1 + close].contains(' '))
 }
 
-/// Parse `ui_text_align`: `left` (default) | `center` | `right`.
+/// Parse `ui_text_align`: `left` (default) | `center` | `right` | `start` |
+/// `end`. The last two follow the text's direction, so a right-to-left
+/// string aligned `start` sits against the right edge.
 fn parse_text_align(value: Option<&str>) -> TextAlign {
     match value.map(str::trim) {
         Some("center") => TextAlign::Center,
         Some("right") => TextAlign::Right,
+        Some("start") => TextAlign::Start,
+        Some("end") => TextAlign::End,
         _ => TextAlign::Left,
     }
 }
@@ -3,6 +3,7 @@ mod globals;
 mod layer2d;
 mod script2d;
 mod script_manifest;
+mod trigger2d;
 mod validation;
 mod world2d;
 
@@ -19,6 +20,7 @@ pub use script2d::{
     SceneScriptInputEvent2D, SceneScriptRegistry2D,
 };
 pub use script_manifest::{ScriptDef, ScriptManifest, ScriptParamDef, ScriptParamKind};
+pub use trigger2d::SceneTriggers2D;
 pub use validation::{
     validate_editor_scene, validate_scene_dir, validate_scene_file, SceneIssueSeverity,
     SceneValidationIssue, SceneValidationReport, CURRENT_EDITOR_SCENE_VERSION,
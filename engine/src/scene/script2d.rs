@@ -528,7 +528,9 @@ impl SceneScriptHost2D {
         }
     }
 
-    fn dispatch_event_to_node(
+    /// Deliver `event` to the scripts bound to `handle` only, matched by its
+    /// editor node id.
+    pub(crate) fn dispatch_event_to_node(
         &mut self,
         world: &mut SceneWorld2D,
         handle: NodeHandle2D,
@@ -0,0 +1,239 @@
+use std::collections::{BTreeMap, HashMap};
+
+use glam::Vec2;
+
+use crate::math::rect::Rect;
+use crate::world::{Shape2D, TriggerSystem, TriggerZone, TriggerZoneId};
+
+use super::{NodeHandle2D, SceneScriptEvent2D, SceneScriptHost2D, SceneWorld2D};
+
+impl TriggerZone {
+    /// A zone for a scene node with a `trigger` property, built around the
+    /// origin so [`SceneTriggers2D::attach`] can pin it to the node, or `None`
+    /// for any other node or one with no area.
+    ///
+    /// The zone is a circle of `trigger_radius` around the node if set, and
+    /// the node's bounds otherwise. `trigger` names the event topic (empty for
+    /// the default `"trigger"`), and `trigger_dwell`, `trigger_cooldown` and
+    /// `trigger_once` set the matching fields.
+    pub fn from_node(world: &SceneWorld2D, handle: NodeHandle2D) -> Option<Self> {
+        let node = world.get(handle)?;
+        let topic = node.property("trigger")?;
+        let zone = match node.property_f32("trigger_radius") {
+            Some(radius) => Self::shaped(Shape2D::circle(Vec2::ZERO, radius)),
+            None => {
+                let bounds = world.node_bounds(handle)?;
+                let position = world.world_transform(handle)?.position;
+                let corner = Vec2::new(bounds.x, bounds.y) - position;
+                Self::new(Rect::new(corner.x, corner.y, bounds.width, bounds.height))
+            }
+        };
+        let mut zone = zone
+            .with_dwell(node.property_f32("trigger_dwell").unwrap_or(0.0))
+            .with_cooldown(node.property_f32("trigger_cooldown").unwrap_or(0.0))
+            .with_once(node.property_bool("trigger_once").unwrap_or(false));
+        if !topic.is_empty() {
+            zone.topic = topic.to_string();
+        }
+        Some(zone)
+    }
+}
+
+/// Which zones of a [`TriggerSystem`] ride on which scene nodes.
+///
+/// The trigger system itself knows nothing of scenes; this keeps each
+/// attached zone's node and where the zone's corner sits relative to the
+/// node's world position, moves the zones along with their nodes and sends
+/// their firings to the nodes' scripts.
+#[derive(Debug, Clone, Default)]
+pub struct SceneTriggers2D {
+    nodes: BTreeMap<TriggerZoneId, (NodeHandle2D, Vec2)>,
+}
+
+impl SceneTriggers2D {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attach zone `id` of `system` to `node`. Build the zone around the
+    /// origin: its current position becomes the offset from the node it keeps
+    /// as the node moves.
+    pub fn attach(&mut self, system: &TriggerSystem, id: TriggerZoneId, node: NodeHandle2D) {
+        let rect = system.zone(id).rect;
+        self.nodes.insert(id, (node, Vec2::new(rect.x, rect.y)));
+    }
+
+    /// Stop zone `id` following its node, returning the node it rode on.
+    pub fn detach(&mut self, id: TriggerZoneId) -> Option<NodeHandle2D> {
+        self.nodes.remove(&id).map(|(node, _)| node)
+    }
+
+    pub fn node(&self, id: TriggerZoneId) -> Option<NodeHandle2D> {
+        self.nodes.get(&id).map(|&(node, _)| node)
+    }
+
+    /// Add a zone to `system` for every node in `world` with a `trigger`
+    /// property and attach it to that node (see [`TriggerZone::from_node`]).
+    pub fn add_scene_zones(
+        &mut self,
+        system: &mut TriggerSystem,
+        world: &SceneWorld2D,
+    ) -> Vec<TriggerZoneId> {
+        world
+            .handles()
+            .filter_map(|handle| Some((handle, TriggerZone::from_node(world, handle)?)))
+            .map(|(handle, zone)| {
+                let id = system.add_zone(zone);
+                self.attach(system, id, handle);
+                id
+            })
+            .collect()
+    }
+
+    /// Move every attached zone to where its node now is. Zones whose node
+    /// has been despawned are disabled.
+    ///
+    /// Only the node's world position is followed: a zone keeps its size and
+    /// shape however the node is rotated or scaled, so a rotating node's zone
+    /// is best a circle around it.
+    pub fn follow_nodes(&self, system: &mut TriggerSystem, world: &SceneWorld2D) {
+        for (&id, &(node, offset)) in &self.nodes {
+            let Some(transform) = world.world_transform(node) else {
+                system.zone_mut(id).enabled = false;
+                continue;
+            };
+            let rect = system.zone(id).rect;
+            let corner = transform.position + offset;
+            if corner != Vec2::new(rect.x, rect.y) {
+                system.set_zone_rect(id, Rect::new(corner.x, corner.y, rect.width, rect.height));
+            }
+        }
+    }
+
+    /// Send each attached zone that fired on `system`'s last tick a
+    /// [`SceneScriptEvent2D`] with its `topic`, delivered to the scripts bound
+    /// to the zone's node. The payload carries the `zone` id, the `body` id and
+    /// its `dwell` time.
+    pub fn emit_script_events(
+        &self,
+        system: &TriggerSystem,
+        host: &mut SceneScriptHost2D,
+        world: &mut SceneWorld2D,
+    ) {
+        for (zone_id, body_id) in system.fired() {
+            let Some(node) = self.node(zone_id) else {
+                continue;
+            };
+            let dwell = system.dwell_time(zone_id, body_id).unwrap_or(0.0);
+            let payload = HashMap::from([
+                ("zone".to_string(), zone_id.to_string()),
+                ("body".to_string(), body_id.to_string()),
+                ("dwell".to_string(), dwell.to_string()),
+            ]);
+            let event = SceneScriptEvent2D::Custom {
+                topic: system.zone(zone_id).topic.clone(),
+                payload,
+            };
+            host.dispatch_event_to_node(world, node, &event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{
+        Prefab2DDef, Scene2D, Scene2DDef, SceneInstance2DDef, SceneScript2D, SceneScriptContext2D,
+        SceneScriptRegistry2D,
+    };
+    use crate::world::CollisionLayer;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn scene_zones_follow_their_node_and_notify_its_scripts() {
+        struct DoorScript {
+            log: Arc<Mutex<Vec<(String, String)>>>,
+        }
+        impl SceneScript2D for DoorScript {
+            fn on_event_world(
+                &mut self,
+                _ctx: &mut SceneScriptContext2D,
+                event: &SceneScriptEvent2D,
+            ) {
+                let SceneScriptEvent2D::Custom { topic, payload } = event;
+                let body = payload.get("body").cloned().unwrap_or_default();
+                self.log.lock().unwrap().push((topic.clone(), body));
+            }
+        }
+
+        let properties = [
+            ("editor_node_id", "1"),
+            ("editor_name", "door"),
+            ("script_path", "scripts/door.rs"),
+            ("trigger", "open_door"),
+            ("trigger_radius", "16"),
+            ("trigger_once", "true"),
+        ];
+        let definition = Scene2DDef {
+            prefabs: vec![Prefab2DDef {
+                name: "marker".to_string(),
+                sprites: vec![],
+            }],
+            instances: vec![SceneInstance2DDef {
+                prefab: "marker".to_string(),
+                position: [0.0, 0.0],
+                scale: [1.0, 1.0],
+                properties: properties
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            }],
+        };
+        let scene = Scene2D::from_definition(
+            std::path::Path::new("t.scene.json"),
+            definition,
+            &crate::assets::AssetPack::default(),
+        )
+        .unwrap();
+        let mut world = SceneWorld2D::from_scene(&scene);
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let factory_log = log.clone();
+        let mut registry = SceneScriptRegistry2D::new();
+        registry.register("scripts/door.rs", move || {
+            Box::new(DoorScript {
+                log: factory_log.clone(),
+            })
+        });
+        let mut host = SceneScriptHost2D::new();
+        host.attach_scene(&scene, &registry);
+
+        let mut system = TriggerSystem::new();
+        let mut triggers = SceneTriggers2D::new();
+        let zones = triggers.add_scene_zones(&mut system, &world);
+        assert_eq!(zones.len(), 1);
+        assert!(system.zone(zones[0]).once);
+        let body = [(
+            7,
+            Rect::new(98.0, -2.0, 4.0, 4.0),
+            CollisionLayer::default(),
+        )];
+
+        system.tick(&body);
+        assert!(!system.overlapping(zones[0], 7));
+
+        let door = world.find_by_name("door").unwrap();
+        world
+            .get_mut(door)
+            .unwrap()
+            .set_position(Vec2::new(100.0, 0.0));
+        triggers.follow_nodes(&mut system, &world);
+        system.tick(&body);
+        assert_eq!(system.fired().collect::<Vec<_>>(), vec![(zones[0], 7)]);
+        triggers.emit_script_events(&system, &mut host, &mut world);
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![("open_door".to_string(), "7".to_string())]
+        );
+    }
+}
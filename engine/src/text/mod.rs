@@ -0,0 +1,1137 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::{Arc, OnceLock};
+
+mod shaping;
+
+pub(crate) use shaping::{is_rtl, needs_shaping, ShapedGlyph};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FontId(pub(crate) usize);
+
+impl FontId {
+    pub const DEFAULT: FontId = FontId(0);
+
+    /// The raw index, for a host that has to hand this id to something that
+    /// only speaks numbers — publishing it to a scene as an `ui_font` binding,
+    /// say. Construction stays crate-private: an id is only meaningful if the
+    /// renderer actually loaded that font.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// How a font's glyph cache stores its glyphs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FontMode {
+    /// Coverage bitmaps at [`FONT_SIZE`]. Exact near that size; soft when
+    /// scaled far up, shimmering when scaled far down.
+    #[default]
+    Bitmap,
+    /// Signed distance fields. One atlas stays crisp at any size, and the
+    /// canvas can grow outlines, soft shadows and glows out of the field.
+    Sdf,
+}
+
+/// One glyph as the canvas draws it: where it sits in the atlas, and how to
+/// place and advance it at [`FONT_SIZE`].
+#[derive(Clone, Copy)]
+pub(crate) struct GlyphEntry {
+    /// The atlas page the bitmap lives on; the canvas binds it per segment.
+    pub page: usize,
+    /// Which face in the atlas's fallback chain drew it. Kerning only ever
+    /// applies between two glyphs from the same face.
+    pub face: usize,
+    /// The glyph's index inside `face`, for kerning lookups.
+    pub index: u16,
+    pub u0: f32,
+    pub v0: f32,
+    pub u1: f32,
+    pub v1: f32,
+    pub width_px: f32,
+    pub height_px: f32,
+    pub x_offset: f32,
+    pub y_offset: f32,
+    pub advance: f32,
+}
+
+pub(crate) const ATLAS_SIZE: u32 = 512;
+pub(crate) const FONT_SIZE: f32 = 48.0;
+/// How many [`ATLAS_SIZE`] pages one font's glyph cache grows to before it
+/// starts evicting. Four pages hold roughly two thousand glyphs at
+/// [`FONT_SIZE`] — a screen of CJK with room to spare.
+pub(crate) const MAX_ATLAS_PAGES: usize = 4;
+/// How far an SDF glyph's field reaches past its edge, in pixels at
+/// [`FONT_SIZE`]. Also the widest outline, shadow blur or glow a draw can ask
+/// for, scaled to the drawn size.
+pub(crate) const SDF_SPREAD: f32 = 8.0;
+/// How many shaped lines one glyph cache remembers before starting over.
+const MAX_SHAPED_RUNS: usize = 256;
+
+/// One font file, read twice: fontdue rasterises and measures from `raster`,
+/// and the shaper parses `bytes` again per run, because rustybuzz borrows
+/// the font data instead of owning it.
+pub(crate) struct Face {
+    pub(crate) raster: fontdue::Font,
+    bytes: Arc<[u8]>,
+}
+
+impl Face {
+    pub(crate) fn new(bytes: &[u8]) -> Self {
+        let raster = fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default())
+            .expect("failed to parse font");
+        Self {
+            raster,
+            bytes: bytes.into(),
+        }
+    }
+}
+
+/// The face that draws `ch`: the first in `faces` that has it, or the
+/// primary's `.notdef` so a missing glyph shows up as a box instead of
+/// silently vanishing. Control characters draw nothing.
+fn resolve_glyph(faces: &[Arc<Face>], ch: char) -> Option<(usize, u16)> {
+    if ch.is_control() {
+        return None;
+    }
+    let found =
+        faces
+            .iter()
+            .enumerate()
+            .find_map(|(i, face)| match face.raster.lookup_glyph_index(ch) {
+                0 => None,
+                index => Some((i, index)),
+            });
+    Some(found.unwrap_or((0, 0)))
+}
+
+/// The kerning adjustment between two resolved glyphs at [`FONT_SIZE`].
+/// Pairs that straddle two faces have no kerning table to consult.
+fn kern_between(faces: &[Arc<Face>], left: (usize, u16), right: (usize, u16)) -> f32 {
+    if left.0 != right.0 {
+        return 0.0;
+    }
+    faces[left.0]
+        .raster
+        .horizontal_kern_indexed(left.1, right.1, FONT_SIZE)
+        .unwrap_or(0.0)
+}
+
+struct BuiltinFontMetrics {
+    faces: [Arc<Face>; 1],
+    line_height: f32,
+    ascent: f32,
+}
+
+static BUILTIN_FONT_METRICS: OnceLock<BuiltinFontMetrics> = OnceLock::new();
+
+fn builtin_font_metrics() -> &'static BuiltinFontMetrics {
+    BUILTIN_FONT_METRICS.get_or_init(|| {
+        let face = Face::new(&include_bytes!("../../assets/font.ttf")[..]);
+        // Same definition as `build_atlas_from_bytes`: the font's own line
+        // box, not the tallest glyph's ink.
+        let (ascent, line_height) = face
+            .raster
+            .horizontal_line_metrics(FONT_SIZE)
+            .map_or((FONT_SIZE * 0.8, FONT_SIZE), |m| {
+                (m.ascent, m.new_line_size)
+            });
+
+        BuiltinFontMetrics {
+            faces: [Arc::new(face)],
+            line_height,
+            ascent,
+        }
+    })
+}
+
+pub(crate) fn measure_builtin_text(text: &str, size: f32) -> (f32, f32) {
+    let metrics = builtin_font_metrics();
+    let scale = size / FONT_SIZE;
+    let faces = &metrics.faces;
+    if needs_shaping(text) {
+        let width: f32 = shaping::shape(faces, text)
+            .iter()
+            .map(|glyph| glyph.advance)
+            .sum();
+        return (width * scale, metrics.line_height * scale);
+    }
+    // The same resolve-and-kern walk as `GlyphCache::measure`, so a layout
+    // pass without an atlas sizes text exactly as the atlas will draw it.
+    let mut width = 0.0;
+    let mut prev = None;
+    for ch in text.chars() {
+        let Some(glyph) = resolve_glyph(faces, ch) else {
+            continue;
+        };
+        if let Some(prev) = prev {
+            width += kern_between(faces, prev, glyph);
+        }
+        width += faces[glyph.0]
+            .raster
+            .metrics_indexed(glyph.1, FONT_SIZE)
+            .advance_width;
+        prev = Some(glyph);
+    }
+
+    (width * scale, metrics.line_height * scale)
+}
+
+/// [`FontAtlas::baseline_below_top`] in the builtin face, for the same
+/// atlas-less layout passes [`measure_builtin_text`] serves.
+pub(crate) fn builtin_baseline_below_top(top: f32, size: f32) -> f32 {
+    top - builtin_font_metrics().ascent * (size / FONT_SIZE)
+}
+
+/// Where a rasterised glyph landed in the cache.
+#[derive(Clone, Copy)]
+struct GlyphSlot {
+    page: usize,
+    uv: [f32; 4],
+    width_px: f32,
+    height_px: f32,
+    x_offset: f32,
+    y_offset: f32,
+}
+
+/// What a char resolved to: the face and glyph that draw it, and its advance.
+struct CachedGlyph {
+    face: usize,
+    index: u16,
+    advance: f32,
+}
+
+/// One RGBA page of the cache, packed in shelves (rows of glyphs, each as
+/// tall as its tallest member).
+struct CachePage {
+    pixels: Vec<u8>,
+    cursor_x: u32,
+    cursor_y: u32,
+    row_height: u32,
+    /// The last frame any glyph on this page was drawn in; eviction takes
+    /// the page with the oldest.
+    last_used: u64,
+    /// Pixels changed since the renderer last uploaded this page.
+    dirty: bool,
+}
+
+impl CachePage {
+    fn new(size: u32, reserve_white: bool) -> Self {
+        let mut page = Self {
+            pixels: vec![0; (size * size * 4) as usize],
+            cursor_x: 0,
+            cursor_y: 0,
+            row_height: 0,
+            last_used: 0,
+            dirty: true,
+        };
+        if reserve_white {
+            // The 2x2 white texel every solid shape samples. Page 0 keeps it
+            // across evictions; glyphs pack to its right.
+            for y in 0..2u32 {
+                for x in 0..2u32 {
+                    let offset = ((y * size + x) * 4) as usize;
+                    page.pixels[offset..offset + 4].copy_from_slice(&[255; 4]);
+                }
+            }
+            page.cursor_x = 4;
+        }
+        page
+    }
+
+    /// Reserve a `w`×`h` cell, or `None` if the page is full.
+    fn allocate(&mut self, size: u32, w: u32, h: u32) -> Option<(u32, u32)> {
+        if self.cursor_x + w + 1 > size {
+            self.cursor_x = 0;
+            self.cursor_y += self.row_height + 1;
+            self.row_height = 0;
+        }
+        if w + 1 > size || self.cursor_y + h > size {
+            return None;
+        }
+        let at = (self.cursor_x, self.cursor_y);
+        self.cursor_x += w + 1;
+        self.row_height = self.row_height.max(h);
+        Some(at)
+    }
+}
+
+/// The CPU half of a [`FontAtlas`]: resolves characters through a fallback
+/// chain, rasterises them on first draw into shelf-packed pages, and evicts
+/// the least-recently-drawn page once `max_pages` are full.
+///
+/// Bitmaps are keyed by glyph, not char. A shaped run asks for glyphs that
+/// no single char maps to (ligatures, joined Arabic forms), and a glyph that
+/// both paths draw is still rasterised once.
+///
+/// Kept apart from the GPU textures so it can be exercised without a device;
+/// [`FontAtlas::flush`] is the only bridge between the two.
+pub(crate) struct GlyphCache {
+    faces: Vec<Arc<Face>>,
+    glyphs: HashMap<char, CachedGlyph>,
+    /// Where each resident glyph's bitmap is, by `(face, index)`. Absent
+    /// until the glyph is first drawn, and again after its page is evicted.
+    /// Measuring never needs a bitmap, so it never fills the atlas.
+    slots: HashMap<(usize, u16), GlyphSlot>,
+    /// Shaper output by line, since a label is measured and drawn with the
+    /// same text frame after frame. Dropped whole once it holds
+    /// [`MAX_SHAPED_RUNS`] lines, which only constantly-changing text reaches.
+    shaped: HashMap<String, Rc<[ShapedGlyph]>>,
+    pages: Vec<CachePage>,
+    page_size: u32,
+    max_pages: usize,
+    mode: FontMode,
+    /// Advanced once per rendered frame. A page drawn from in the current
+    /// frame is never evicted — its UVs are already baked into vertices
+    /// waiting for this frame's draw.
+    frame: u64,
+}
+
+impl GlyphCache {
+    pub(crate) fn new(faces: Vec<Arc<Face>>, page_size: u32, max_pages: usize) -> Self {
+        assert!(!faces.is_empty(), "a glyph cache needs a primary face");
+        Self {
+            faces,
+            glyphs: HashMap::new(),
+            slots: HashMap::new(),
+            shaped: HashMap::new(),
+            pages: vec![CachePage::new(page_size, true)],
+            page_size,
+            max_pages: max_pages.max(1),
+            mode: FontMode::Bitmap,
+            frame: 1,
+        }
+    }
+
+    /// Replace every face after the primary. Drops all cached glyphs: a char
+    /// that resolved to `.notdef` before may have a real glyph now.
+    pub(crate) fn set_fallbacks(&mut self, fallbacks: Vec<Arc<Face>>) {
+        self.faces.truncate(1);
+        self.faces.extend(fallbacks);
+        self.clear();
+    }
+
+    /// Switch between coverage bitmaps and distance fields. Every resident
+    /// bitmap is the wrong kind afterwards, so they all go.
+    pub(crate) fn set_mode(&mut self, mode: FontMode) {
+        if mode != self.mode {
+            self.mode = mode;
+            self.clear();
+        }
+    }
+
+    fn clear(&mut self) {
+        self.glyphs.clear();
+        self.slots.clear();
+        self.shaped.clear();
+        self.pages = vec![CachePage::new(self.page_size, true)];
+    }
+
+    fn cached(&mut self, ch: char) -> Option<&mut CachedGlyph> {
+        if !self.glyphs.contains_key(&ch) {
+            let (face, index) = resolve_glyph(&self.faces, ch)?;
+            let advance = self.faces[face]
+                .raster
+                .metrics_indexed(index, FONT_SIZE)
+                .advance_width;
+            self.glyphs.insert(
+                ch,
+                CachedGlyph {
+                    face,
+                    index,
+                    advance,
+                },
+            );
+        }
+        self.glyphs.get_mut(&ch)
+    }
+
+    /// Width of `text` at [`FONT_SIZE`], kerning included. Never rasterises.
+    pub(crate) fn measure(&mut self, text: &str) -> f32 {
+        if let Some(glyphs) = self.shape(text) {
+            return glyphs.iter().map(|glyph| glyph.advance).sum();
+        }
+        let mut width = 0.0;
+        let mut prev = None;
+        for ch in text.chars() {
+            let Some(glyph) = self.cached(ch) else {
+                continue;
+            };
+            let (key, advance) = ((glyph.face, glyph.index), glyph.advance);
+            if let Some(prev) = prev {
+                width += kern_between(&self.faces, prev, key);
+            }
+            width += advance;
+            prev = Some(key);
+        }
+        width
+    }
+
+    /// `text` through the shaper, or `None` if it
+    /// [needs no shaping](needs_shaping) and draws char by char.
+    pub(crate) fn shape(&mut self, text: &str) -> Option<Rc<[ShapedGlyph]>> {
+        if !needs_shaping(text) {
+            return None;
+        }
+        if let Some(glyphs) = self.shaped.get(text) {
+            return Some(glyphs.clone());
+        }
+        if self.shaped.len() >= MAX_SHAPED_RUNS {
+            self.shaped.clear();
+        }
+        let glyphs: Rc<[ShapedGlyph]> = shaping::shape(&self.faces, text).into();
+        self.shaped.insert(text.to_string(), glyphs.clone());
+        Some(glyphs)
+    }
+
+    pub(crate) fn kerning(&self, left: &GlyphEntry, right: &GlyphEntry) -> f32 {
+        kern_between(
+            &self.faces,
+            (left.face, left.index),
+            (right.face, right.index),
+        )
+    }
+
+    /// The drawable glyph for `ch`, rasterising it if it isn't resident.
+    /// `None` only for control characters.
+    ///
+    /// A glyph that cannot get a slot because every page is in use by the
+    /// current frame comes back blank but still advances: it draws next frame
+    /// instead of overwriting ink this one still needs, and the run keeps
+    /// the width it was measured at.
+    pub(crate) fn glyph(&mut self, ch: char) -> Option<GlyphEntry> {
+        let glyph = self.cached(ch)?;
+        let (face, index, advance) = (glyph.face, glyph.index, glyph.advance);
+        Some(self.entry(face, index, advance))
+    }
+
+    /// The drawable glyph for one [`ShapedGlyph`], rasterising it if it isn't
+    /// resident. Its `advance` is the font's own; a shaped run moves its pen
+    /// by the shaper's instead.
+    pub(crate) fn shaped_glyph(&mut self, glyph: &ShapedGlyph) -> GlyphEntry {
+        let advance = self.faces[glyph.face]
+            .raster
+            .metrics_indexed(glyph.index, FONT_SIZE)
+            .advance_width;
+        self.entry(glyph.face, glyph.index, advance)
+    }
+
+    fn entry(&mut self, face: usize, index: u16, advance: f32) -> GlyphEntry {
+        let frame = self.frame;
+        let slot = match self.slots.get(&(face, index)) {
+            Some(slot) => *slot,
+            None => match self.rasterise(face, index) {
+                Some(slot) => {
+                    self.slots.insert((face, index), slot);
+                    slot
+                }
+                None => self.blank_slot(),
+            },
+        };
+        if slot.width_px > 0.0 {
+            self.pages[slot.page].last_used = frame;
+        }
+        GlyphEntry {
+            page: slot.page,
+            face,
+            index,
+            u0: slot.uv[0],
+            v0: slot.uv[1],
+            u1: slot.uv[2],
+            v1: slot.uv[3],
+            width_px: slot.width_px,
+            height_px: slot.height_px,
+            x_offset: slot.x_offset,
+            y_offset: slot.y_offset,
+            advance,
+        }
+    }
+
+    fn rasterise(&mut self, face: usize, index: u16) -> Option<GlyphSlot> {
+        let (metrics, bitmap) = self.faces[face].raster.rasterize_indexed(index, FONT_SIZE);
+        let size = self.page_size;
+        if metrics.width == 0 || metrics.height == 0 {
+            // Whitespace: an advance and nothing to draw.
+            return Some(self.blank_slot());
+        }
+
+        let (pad, alpha) = match self.mode {
+            FontMode::Bitmap => (0, bitmap),
+            FontMode::Sdf => {
+                let spread = SDF_SPREAD as usize;
+                let field = signed_distance_field(&bitmap, metrics.width, metrics.height, spread);
+                (spread as u32, field)
+            }
+        };
+        let gw = metrics.width as u32 + 2 * pad;
+        let gh = metrics.height as u32 + 2 * pad;
+        let (page, (x, y)) = self.allocate(gw, gh)?;
+        let pixels = &mut self.pages[page].pixels;
+        for row in 0..gh {
+            for col in 0..gw {
+                let src = (row * gw + col) as usize;
+                let dst = (((y + row) * size + x + col) * 4) as usize;
+                pixels[dst..dst + 3].copy_from_slice(&[255; 3]);
+                pixels[dst + 3] = alpha[src];
+            }
+        }
+        self.pages[page].dirty = true;
+
+        let s = size as f32;
+        Some(GlyphSlot {
+            page,
+            uv: [
+                x as f32 / s,
+                y as f32 / s,
+                (x + gw) as f32 / s,
+                (y + gh) as f32 / s,
+            ],
+            width_px: gw as f32,
+            height_px: gh as f32,
+            x_offset: (metrics.xmin - pad as i32) as f32,
+            y_offset: (metrics.ymin - pad as i32) as f32,
+        })
+    }
+
+    /// A slot with nothing to draw. Points at the white texel so a
+    /// zero-area quad can never sample garbage.
+    fn blank_slot(&self) -> GlyphSlot {
+        let white = 1.0 / self.page_size as f32;
+        GlyphSlot {
+            page: 0,
+            uv: [white, white, white, white],
+            width_px: 0.0,
+            height_px: 0.0,
+            x_offset: 0.0,
+            y_offset: 0.0,
+        }
+    }
+
+    /// A cell on an existing page, a new page, or an evicted one — in that
+    /// order of preference.
+    fn allocate(&mut self, w: u32, h: u32) -> Option<(usize, (u32, u32))> {
+        let size = self.page_size;
+        for (i, page) in self.pages.iter_mut().enumerate() {
+            if let Some(at) = page.allocate(size, w, h) {
+                return Some((i, at));
+            }
+        }
+        let page = if self.pages.len() < self.max_pages {
+            self.pages.push(CachePage::new(size, false));
+            self.pages.len() - 1
+        } else {
+            let (lru, _) = self
+                .pages
+                .iter()
+                .enumerate()
+                .filter(|(_, page)| page.last_used < self.frame)
+                .min_by_key(|(_, page)| page.last_used)?;
+            self.evict(lru);
+            lru
+        };
+        let at = self.pages[page].allocate(size, w, h)?;
+        Some((page, at))
+    }
+
+    /// Drop every glyph on `page` and clear it for reuse. The glyphs'
+    /// metrics stay cached; only their bitmaps have to be redrawn.
+    fn evict(&mut self, page: usize) {
+        self.slots
+            .retain(|_, slot| slot.page != page || slot.width_px == 0.0);
+        self.pages[page] = CachePage::new(self.page_size, page == 0);
+    }
+
+    /// Close the current frame: everything drawn so far is on its way to the
+    /// GPU, so its pages become eligible for eviction again.
+    pub(crate) fn end_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    pub(crate) fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+}
+
+/// A distance field for a `width`×`height` coverage bitmap, padded by
+/// `spread` pixels on every side so effects have room to grow outward.
+///
+/// `128` is the glyph edge; the field ramps to `0` and `255` at `spread`
+/// pixels outside and inside it. Partially covered pixels already straddle
+/// the edge, so their coverage *is* their sub-pixel distance; everything else
+/// searches its `spread` neighbourhood for the nearest pixel on the other
+/// side. Brute force, but it runs once per glyph per cache fill.
+fn signed_distance_field(coverage: &[u8], width: usize, height: usize, spread: usize) -> Vec<u8> {
+    let (out_w, out_h) = (width + 2 * spread, height + 2 * spread);
+    let s = spread as isize;
+    let at = |x: isize, y: isize| -> u8 {
+        if x < 0 || y < 0 || x >= width as isize || y >= height as isize {
+            0
+        } else {
+            coverage[y as usize * width + x as usize]
+        }
+    };
+    let mut field = vec![0u8; out_w * out_h];
+    for oy in 0..out_h {
+        for ox in 0..out_w {
+            let (x, y) = (ox as isize - s, oy as isize - s);
+            let c = at(x, y);
+            let signed = if c > 0 && c < 255 {
+                c as f32 / 255.0 - 0.5
+            } else {
+                let inside = c >= 128;
+                let mut nearest = (s * s * 2) as f32;
+                for dy in -s..=s {
+                    for dx in -s..=s {
+                        if (at(x + dx, y + dy) >= 128) != inside {
+                            nearest = nearest.min((dx * dx + dy * dy) as f32);
+                        }
+                    }
+                }
+                let d = nearest.sqrt() - 0.5;
+                if inside {
+                    d
+                } else {
+                    -d
+                }
+            };
+            let value = (0.5 + signed / (2.0 * spread as f32)).clamp(0.0, 1.0);
+            field[oy * out_w + ox] = (value * 255.0).round() as u8;
+        }
+    }
+    field
+}
+
+/// A page's texture on the GPU, created the first time the renderer flushes
+/// the page.
+struct GpuPage {
+    texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+}
+
+pub struct FontAtlas {
+    cache: RefCell<GlyphCache>,
+    gpu_pages: RefCell<Vec<GpuPage>>,
+    layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    white_uv: [f32; 2],
+    /// The font's own line box at [`FONT_SIZE`]: `ascent - descent +
+    /// line_gap`, the same number CSS calls `normal` line-height.
+    ///
+    /// This used to be the tallest glyph's *ink* height, which is a different
+    /// quantity entirely and is why text drew outside its own node's rect:
+    /// a rect sized to it was shorter than a line, and the draw then measured
+    /// the baseline down from a top that didn't exist.
+    pub(crate) line_height: f32,
+    /// Distance from the baseline up to the line box's top, at [`FONT_SIZE`].
+    /// Positive. This is what turns a rect into a baseline.
+    pub(crate) ascent: f32,
+    pub(crate) id: FontId,
+}
+
+impl FontAtlas {
+    pub fn id(&self) -> FontId {
+        self.id
+    }
+
+    pub fn white_uv(&self) -> [f32; 2] {
+        self.white_uv
+    }
+
+    pub fn measure_text(&self, text: &str, size: f32) -> (f32, f32) {
+        let scale = size / FONT_SIZE;
+        let width = self.cache.borrow_mut().measure(text);
+        (width * scale, self.line_height * scale)
+    }
+
+    pub fn line_height(&self, size: f32) -> f32 {
+        self.line_height * (size / FONT_SIZE)
+    }
+
+    /// Where the baseline sits inside a line box whose **top** is at `top`
+    /// (y-up canvas coords, so the baseline is below it).
+    ///
+    /// The one place the rect→baseline conversion lives. Every text path goes
+    /// through it, so a node's ink lands inside the rect the layout gave it
+    /// by construction rather than by each call site guessing.
+    pub fn baseline_below_top(&self, top: f32, size: f32) -> f32 {
+        top - self.ascent * (size / FONT_SIZE)
+    }
+
+    /// How many atlas pages the glyph cache currently spans.
+    pub fn page_count(&self) -> usize {
+        self.cache.borrow().page_count()
+    }
+
+    pub(crate) fn glyph(&self, ch: char) -> Option<GlyphEntry> {
+        self.cache.borrow_mut().glyph(ch)
+    }
+
+    /// `text` shaped for drawing, or `None` when it draws char by char
+    /// through [`glyph`](Self::glyph) and [`kerning`](Self::kerning).
+    pub(crate) fn shape(&self, text: &str) -> Option<Rc<[ShapedGlyph]>> {
+        self.cache.borrow_mut().shape(text)
+    }
+
+    pub(crate) fn shaped_glyph(&self, glyph: &ShapedGlyph) -> GlyphEntry {
+        self.cache.borrow_mut().shaped_glyph(glyph)
+    }
+
+    /// Kerning between two glyphs from [`glyph`](Self::glyph), at [`FONT_SIZE`].
+    pub(crate) fn kerning(&self, left: &GlyphEntry, right: &GlyphEntry) -> f32 {
+        self.cache.borrow().kerning(left, right)
+    }
+
+    /// The primary face, so another atlas can list it as a fallback.
+    pub(crate) fn face(&self) -> Arc<Face> {
+        self.cache.borrow().faces[0].clone()
+    }
+
+    pub(crate) fn set_fallbacks(&mut self, fallbacks: Vec<Arc<Face>>) {
+        self.cache.get_mut().set_fallbacks(fallbacks);
+    }
+
+    pub fn mode(&self) -> FontMode {
+        self.cache.borrow().mode
+    }
+
+    pub(crate) fn set_mode(&mut self, mode: FontMode) {
+        self.cache.get_mut().set_mode(mode);
+    }
+
+    pub(crate) fn page_bind_group(&self, page: usize) -> Option<wgpu::BindGroup> {
+        self.gpu_pages
+            .borrow()
+            .get(page)
+            .map(|page| page.bind_group.clone())
+    }
+
+    /// Upload every page the cache touched since the last flush, creating
+    /// textures for pages that are new, then close the cache's frame. Called
+    /// by the canvas pass before it binds anything.
+    pub(crate) fn flush(&self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let mut cache = self.cache.borrow_mut();
+        let mut gpu_pages = self.gpu_pages.borrow_mut();
+        let size = cache.page_size;
+        for (i, page) in cache.pages.iter_mut().enumerate() {
+            if i >= gpu_pages.len() {
+                gpu_pages.push(self.create_page(device, size));
+            }
+            if !page.dirty {
+                continue;
+            }
+            page.dirty = false;
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &gpu_pages[i].texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                &page.pixels,
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(size * 4),
+                    rows_per_image: Some(size),
+                },
+                wgpu::Extent3d {
+                    width: size,
+                    height: size,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+        cache.end_frame();
+    }
+
+    fn create_page(&self, device: &wgpu::Device, size: u32) -> GpuPage {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("font_atlas"),
+            size: wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&Default::default());
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("font_bind_group"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        });
+        GpuPage {
+            texture,
+            bind_group,
+        }
+    }
+}
+
+/// Point `font`'s fallback chain at other loaded fonts. Ids that aren't
+/// loaded, or that name `font` itself, are skipped.
+pub(crate) fn set_font_fallbacks(fonts: &mut [FontAtlas], font: FontId, fallbacks: &[FontId]) {
+    let faces = fallbacks
+        .iter()
+        .filter(|id| **id != font)
+        .filter_map(|id| fonts.get(id.0))
+        .map(FontAtlas::face)
+        .collect();
+    if let Some(atlas) = fonts.get_mut(font.0) {
+        atlas.set_fallbacks(faces);
+    }
+}
+
+pub fn font_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("font_bind_group_layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ],
+    })
+}
+
+pub fn font_atlas(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    bind_group_layout: &wgpu::BindGroupLayout,
+) -> FontAtlas {
+    let font_bytes = include_bytes!("../../assets/font.ttf");
+    build_atlas_from_bytes(
+        device,
+        queue,
+        bind_group_layout,
+        font_bytes,
+        FontId::DEFAULT,
+    )
+}
+
+pub(crate) fn build_atlas_from_bytes(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    font_bytes: &[u8],
+    id: FontId,
+) -> FontAtlas {
+    let face = Face::new(font_bytes);
+
+    // The font's own vertical metrics, not the tallest glyph's ink box. A
+    // line box is `ascent - descent + line_gap` — the same number a browser
+    // uses for `line-height: normal`, which is what the mockups are laid out
+    // against. Measuring ink instead made every rect shorter than a real
+    // line and left the baseline undefined.
+    let (ascent, line_height) = match face.raster.horizontal_line_metrics(FONT_SIZE) {
+        Some(m) => (m.ascent, m.new_line_size),
+        // No hhea/OS2 table: fall back to the em box, which is at least
+        // self-consistent (baseline at 80% is the usual default).
+        None => (FONT_SIZE * 0.8, FONT_SIZE),
+    };
+
+    let mut cache = GlyphCache::new(vec![Arc::new(face)], ATLAS_SIZE, MAX_ATLAS_PAGES);
+    // Printable ASCII up front: every UI string leans on it, and warming it
+    // here keeps the first frame from rasterising a whole alphabet.
+    for c in 32u8..127 {
+        cache.glyph(c as char);
+    }
+
+    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("font_sampler"),
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        ..Default::default()
+    });
+
+    let atlas = FontAtlas {
+        cache: RefCell::new(cache),
+        gpu_pages: RefCell::new(Vec::new()),
+        layout: bind_group_layout.clone(),
+        sampler,
+        white_uv: [1.0 / ATLAS_SIZE as f32, 1.0 / ATLAS_SIZE as f32],
+        line_height,
+        ascent,
+        id,
+    };
+    // Page 0 has to exist before the first pass: every solid shape samples
+    // its white texel, text or not.
+    atlas.flush(device, queue);
+    atlas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The rect→baseline contract, on the real vendored faces.
+    ///
+    /// This is the bug that cost the most during the UI overhaul: `y` meant
+    /// "rect bottom" to the layout, "baseline" to the draw, and `line_height`
+    /// meant "tallest glyph's ink" to one and "line box" to the other. Text
+    /// drew tens of pixels outside its own node and every screen had been
+    /// hand-nudged against the wrong output.
+    ///
+    /// What must hold: a line box `line_height(size)` tall, with the baseline
+    /// `ascent` below its top, puts every glyph's ink inside that box.
+    fn assert_ink_fits_line_box(bytes: &[u8], name: &str) {
+        let font = fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default())
+            .expect("vendored font should parse");
+        for size in [10.0f32, 12.0, 18.0, 44.0] {
+            let m = font
+                .horizontal_line_metrics(size)
+                .expect("a UI font declares vertical metrics");
+            // Same numbers the atlas stores and `baseline_below_top` uses.
+            let (line_height, ascent) = (m.new_line_size, m.ascent);
+            let top = 0.0f32;
+            let baseline = top - ascent;
+            let bottom = top - line_height;
+            for ch in " !ABCFMWgjpqy0123456789".chars() {
+                let g = font.metrics(ch, size);
+                if g.height == 0 {
+                    continue;
+                }
+                // Canvas places a glyph's bottom at `baseline + ymin`.
+                let ink_bottom = baseline + g.ymin as f32;
+                let ink_top = ink_bottom + g.height as f32;
+                assert!(
+                    ink_top <= top + 0.5,
+                    "{name} @{size}: '{ch}' ink rises above its line box \
+                     (ink_top={ink_top}, box_top={top})"
+                );
+                assert!(
+                    ink_bottom >= bottom - 0.5,
+                    "{name} @{size}: '{ch}' ink drops below its line box \
+                     (ink_bottom={ink_bottom}, box_bottom={bottom})"
+                );
+            }
+        }
+    }
+
+    fn face(bytes: &[u8]) -> Arc<Face> {
+        Arc::new(Face::new(bytes))
+    }
+
+    /// Whether `ch`'s bitmap is in the atlas right now.
+    fn resident(cache: &GlyphCache, ch: char) -> bool {
+        let glyph = &cache.glyphs[&ch];
+        cache.slots.contains_key(&(glyph.face, glyph.index))
+    }
+
+    fn builtin_face() -> Arc<Face> {
+        face(&include_bytes!("../../assets/font.ttf")[..])
+    }
+
+    fn mono_face() -> Arc<Face> {
+        face(&include_bytes!("../../../samples/features/feature-fonts/assets/mono.ttf")[..])
+    }
+
+    #[test]
+    fn fallback_faces_draw_what_the_primary_lacks() {
+        let mut cache = GlyphCache::new(vec![builtin_face()], ATLAS_SIZE, 1);
+        // The builtin face has no box-drawing glyphs: alone, U+2500 resolves
+        // to its `.notdef`.
+        let notdef = cache.glyph('\u{2500}').unwrap();
+        assert_eq!((notdef.face, notdef.index), (0, 0));
+
+        cache.set_fallbacks(vec![mono_face()]);
+        let line = cache.glyph('\u{2500}').unwrap();
+        assert_eq!(line.face, 1, "the fallback face draws it");
+        assert!(line.width_px > 0.0);
+        // Anything the primary has still comes from the primary.
+        assert_eq!(cache.glyph('A').unwrap().face, 0);
+        assert_eq!(cache.glyph('\u{e9}').unwrap().face, 0);
+        // Nobody has CJK here: `.notdef`, not a silent gap.
+        assert_eq!(cache.glyph('\u{6f22}').unwrap().index, 0);
+        assert!(cache.glyph('\n').is_none());
+    }
+
+    #[test]
+    fn measuring_agrees_with_the_builtin_walk_and_never_rasterises() {
+        let mut cache = GlyphCache::new(vec![builtin_face()], ATLAS_SIZE, 1);
+        let text = "Cr\u{e8}me br\u{fb}l\u{e9}e \u{2014} \u{2192} 42";
+        let width = cache.measure(text);
+        assert!((width - measure_builtin_text(text, FONT_SIZE).0).abs() < 1e-3);
+        assert!(width > cache.measure("Crme brle  42"));
+        assert!(!resident(&cache, '\u{e8}'));
+    }
+
+    #[test]
+    fn marks_shape_onto_their_letter_and_share_its_bitmaps() {
+        let mut cache = GlyphCache::new(vec![mono_face()], ATLAS_SIZE, 1);
+        assert!(
+            cache.shape("caf\u{e9}").is_none(),
+            "precomposed: char by char"
+        );
+
+        // A decomposed `é` composes to the font's own `é`: same glyphs, same
+        // width as the precomposed spelling.
+        let decomposed = cache.shape("cafe\u{301}").expect("a combining mark shapes");
+        let indices: Vec<u16> = decomposed.iter().map(|glyph| glyph.index).collect();
+        let precomposed: Vec<u16> = "caf\u{e9}"
+            .chars()
+            .map(|ch| cache.glyph(ch).unwrap().index)
+            .collect();
+        assert_eq!(indices, precomposed);
+        assert!((cache.measure("cafe\u{301}") - cache.measure("caf\u{e9}")).abs() < 1e-3);
+
+        // No precomposed form: the mark stays its own glyph, in its letter's
+        // cluster, taking no width of its own.
+        let q = cache.shape("q\u{301}").unwrap();
+        assert_eq!(q.len(), 2);
+        assert_eq!((q[0].cluster, q[1].cluster), (0, 0));
+        assert_eq!(q[1].advance, 0.0);
+        assert!((cache.measure("q\u{301}") - cache.measure("q")).abs() < 1e-3);
+
+        // The `q` the shaper asks for is the bitmap the char path drew.
+        let by_char = cache.glyph('q').unwrap();
+        let by_glyph = cache.shaped_glyph(&q[0]);
+        assert_eq!(
+            (by_char.page, by_char.u0, by_char.v0),
+            (by_glyph.page, by_glyph.u0, by_glyph.v0)
+        );
+        assert_eq!(cache.slots.len(), cache.glyphs.len(), "rasterised once");
+    }
+
+    #[test]
+    fn cache_grows_pages_then_evicts_the_least_recently_drawn() {
+        let mut cache = GlyphCache::new(vec![mono_face()], 128, 2);
+        let mut chars = ('A'..='Z').chain('a'..='z').chain('\u{410}'..='\u{44f}');
+
+        // Frame 1: fill until a second page opens.
+        let mut first_page = Vec::new();
+        for ch in chars.by_ref() {
+            let entry = cache.glyph(ch).unwrap();
+            if entry.page == 1 {
+                break;
+            }
+            first_page.push(ch);
+        }
+        assert_eq!(cache.page_count(), 2);
+        cache.end_frame();
+
+        // Frame 2: touch page 1 only, then keep rasterising. Page 0 is the
+        // stale one, so it is the one recycled; the cap holds.
+        let kept = *cache
+            .glyphs
+            .iter()
+            .find(|(_, glyph)| {
+                cache
+                    .slots
+                    .get(&(glyph.face, glyph.index))
+                    .is_some_and(|slot| slot.page == 1)
+            })
+            .unwrap()
+            .0;
+        cache.glyph(kept);
+        let mut landed_on_zero = false;
+        for ch in chars.by_ref() {
+            if cache.glyph(ch).unwrap().page == 0 {
+                landed_on_zero = true;
+                break;
+            }
+        }
+        assert!(landed_on_zero, "the stale page is evicted and reused");
+        assert_eq!(cache.page_count(), 2);
+        assert!(!resident(&cache, first_page[0]));
+        assert!(resident(&cache, kept));
+        // An evicted glyph just rasterises again the next time it draws.
+        assert!(cache.glyph(first_page[0]).unwrap().width_px > 0.0);
+
+        // Both pages now hold ink this frame still needs: a glyph that
+        // cannot fit comes back blank but keeps its advance.
+        let blank = chars
+            .by_ref()
+            .map(|ch| cache.glyph(ch).unwrap())
+            .find(|entry| entry.width_px == 0.0)
+            .expect("the cache fills up within a frame");
+        assert!(blank.advance > 0.0);
+        assert_eq!(cache.page_count(), 2);
+    }
+
+    #[test]
+    fn distance_field_puts_the_edge_at_half_and_ramps_over_the_spread() {
+        // A solid 4x4 block, padded by 8: the field is 20x20.
+        let field = signed_distance_field(&[255; 16], 4, 4, 8);
+        assert_eq!(field.len(), 20 * 20);
+        let at = |x: usize, y: usize| field[y * 20 + x];
+        // Inside the block, above the edge; just outside, just below it.
+        assert!(at(9, 9) > 128);
+        assert!(at(7, 9) < 128 && at(7, 9) > 100, "got {}", at(7, 9));
+        // The corner of the padding is past the spread: fully outside.
+        assert_eq!(at(0, 0), 0);
+        // Monotonic walking away from the edge.
+        assert!(at(6, 9) < at(7, 9) && at(5, 9) < at(6, 9));
+    }
+
+    #[test]
+    fn sdf_mode_pads_glyphs_and_drops_resident_bitmaps() {
+        let mut cache = GlyphCache::new(vec![builtin_face()], ATLAS_SIZE, 1);
+        let bitmap = cache.glyph('H').unwrap();
+        cache.set_mode(FontMode::Sdf);
+        assert!(cache.glyphs.is_empty(), "bitmaps are the wrong kind now");
+
+        let sdf = cache.glyph('H').unwrap();
+        let pad = 2.0 * SDF_SPREAD;
+        assert_eq!(sdf.width_px, bitmap.width_px + pad);
+        assert_eq!(sdf.height_px, bitmap.height_px + pad);
+        // Padding grows the quad outward, so the ink stays where it was.
+        assert_eq!(sdf.x_offset, bitmap.x_offset - SDF_SPREAD);
+        assert_eq!(sdf.y_offset, bitmap.y_offset - SDF_SPREAD);
+        assert_eq!(sdf.advance, bitmap.advance);
+    }
+
+    #[test]
+    fn builtin_font_ink_stays_inside_its_line_box() {
+        assert_ink_fits_line_box(&include_bytes!("../../assets/font.ttf")[..], "builtin");
+    }
+
+    #[test]
+    fn line_height_is_the_font_line_box_not_the_tallest_glyph() {
+        // The distinction the old code collapsed. `line_height` must be the
+        // font's declared line box (ascent - descent + gap), which is
+        // strictly taller than any single glyph's ink — a face whose glyphs
+        // sit high in the em box (Silkscreen declares ascent 49.44 with a cap
+        // height of 28) is exactly where measuring ink instead goes wrong.
+        let font = fontdue::Font::from_bytes(
+            &include_bytes!("../../assets/font.ttf")[..],
+            fontdue::FontSettings::default(),
+        )
+        .unwrap();
+        let m = font.horizontal_line_metrics(FONT_SIZE).unwrap();
+        let tallest_ink = (32u8..127)
+            .map(|c| font.metrics(c as char, FONT_SIZE).height as f32)
+            .fold(0.0, f32::max);
+        assert!(
+            m.new_line_size >= tallest_ink,
+            "a line box must hold the tallest glyph: line={} ink={tallest_ink}",
+            m.new_line_size
+        );
+        assert!((m.new_line_size - (m.ascent - m.descent + m.line_gap)).abs() < 1e-3);
+    }
+}
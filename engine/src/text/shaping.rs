@@ -0,0 +1,323 @@
+//! Complex-script shaping and bidirectional text.
+//!
+//! Most UI text — Latin, Cyrillic, Greek, CJK — draws one glyph per `char`
+//! at the font's own advance, with pair kerning, and the glyph cache does
+//! exactly that. Other scripts need more. Arabic letters join and change
+//! shape with their neighbours, Indic vowel signs reorder and stack around
+//! their consonant, combining marks attach to the letter before them, and
+//! right-to-left runs read backwards across the screen.
+//!
+//! [`shape`] handles those. The Unicode bidi algorithm splits a line into
+//! visual runs. Each run is cut wherever the fallback chain hands a grapheme
+//! cluster to another face, and rustybuzz shapes each piece. What comes out
+//! is glyph ids rather than chars, in left-to-right screen order, ready to
+//! draw with a pen that only ever moves right.
+//!
+//! Shaping is per line. A wrapped paragraph breaks in logical order first
+//! (see [`wrap_text`](crate::canvas::wrap_text)) and each line is reordered
+//! on its own, which is what the bidi algorithm prescribes.
+
+use std::sync::Arc;
+
+use unicode_bidi::{bidi_class, get_base_direction_full, BidiClass, Direction, ParagraphBidiInfo};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{resolve_glyph, Face, FONT_SIZE};
+
+/// One glyph out of the shaper, positioned at [`FONT_SIZE`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct ShapedGlyph {
+    /// Which face in the fallback chain the glyph comes from.
+    pub face: usize,
+    /// The glyph's index inside `face` — not a char: a ligature or a joined
+    /// Arabic form has no char of its own.
+    pub index: u16,
+    /// How far the pen moves after this glyph.
+    pub advance: f32,
+    /// Where the glyph sits relative to the pen, y-up. Non-zero for marks
+    /// placed over or under the letter they attach to.
+    pub x_offset: f32,
+    pub y_offset: f32,
+    /// Byte offset of the cluster this glyph draws, into the shaped text.
+    pub cluster: usize,
+}
+
+/// Whether `text` needs [`shape`] to draw correctly, rather than one glyph
+/// per char at the font's advance.
+///
+/// True for right-to-left and bidi-control characters, for scripts whose
+/// letters change shape or order, and for any grapheme cluster built from
+/// more than one char (a letter with combining marks, say). ASCII never
+/// needs it, so most UI text skips the check after one pass over its bytes.
+pub(crate) fn needs_shaping(text: &str) -> bool {
+    if text.is_ascii() {
+        return false;
+    }
+    text.chars().any(is_complex)
+        || text
+            .graphemes(true)
+            .any(|cluster| cluster.chars().nth(1).is_some())
+}
+
+fn is_complex(ch: char) -> bool {
+    use BidiClass::*;
+    matches!(
+        bidi_class(ch),
+        R | AL | AN | LRE | LRO | RLE | RLO | PDF | LRI | RLI | FSI | PDI
+    ) || matches!(
+        ch as u32,
+        // Indic, Thai and Lao, Tibetan, Myanmar, Khmer and Mongolian, Devanagari
+        // Extended, and the zero-width (non-)joiners.
+        0x0900..=0x0DFF
+            | 0x0E00..=0x0FFF
+            | 0x1000..=0x109F
+            | 0x1780..=0x18AF
+            | 0xA8E0..=0xA8FF
+            | 0x200C..=0x200D
+    )
+}
+
+/// Whether `text` reads right to left: its first strongly-directional
+/// character is Hebrew, Arabic or another right-to-left script. Text with no
+/// strong character at all — digits, punctuation — reads left to right.
+pub(crate) fn is_rtl(text: &str) -> bool {
+    get_base_direction_full(text) == Direction::Rtl
+}
+
+/// Shape one line of `text` through `faces`, the first of which is the
+/// primary and the rest its fallbacks. Control characters draw nothing and
+/// are dropped, as on the unshaped path.
+pub(crate) fn shape(faces: &[Arc<Face>], text: &str) -> Vec<ShapedGlyph> {
+    let mut glyphs = Vec::new();
+    if text.is_empty() {
+        return glyphs;
+    }
+    let bidi = ParagraphBidiInfo::new(text, None);
+    let (levels, runs) = bidi.visual_runs(0..text.len());
+    for run in runs {
+        let rtl = levels[run.start].is_rtl();
+        let mut pieces = face_pieces(faces, text, run);
+        // Pieces are in reading order; a right-to-left run lays its last
+        // piece down first.
+        if rtl {
+            pieces.reverse();
+        }
+        for (face, range) in pieces {
+            shape_piece(faces, face, text, range, rtl, &mut glyphs);
+        }
+    }
+    glyphs
+}
+
+/// Split `run` into byte ranges that one face draws, cutting only between
+/// grapheme clusters so a mark is never shaped apart from its letter.
+fn face_pieces(
+    faces: &[Arc<Face>],
+    text: &str,
+    run: std::ops::Range<usize>,
+) -> Vec<(usize, std::ops::Range<usize>)> {
+    let mut pieces: Vec<(usize, std::ops::Range<usize>)> = Vec::new();
+    for (offset, cluster) in text[run.clone()].grapheme_indices(true) {
+        let start = run.start + offset;
+        let face = cluster_face(faces, cluster);
+        match pieces.last_mut() {
+            Some((last, range)) if *last == face => range.end = start + cluster.len(),
+            _ => pieces.push((face, start..start + cluster.len())),
+        }
+    }
+    pieces
+}
+
+/// The first face with a glyph for every visible char of `cluster`, or
+/// failing that whichever face draws its first char — `.notdef` in the
+/// primary if nobody does.
+fn cluster_face(faces: &[Arc<Face>], cluster: &str) -> usize {
+    let visible = || cluster.chars().filter(|&ch| !is_invisible(ch));
+    faces
+        .iter()
+        .position(|face| visible().all(|ch| face.raster.lookup_glyph_index(ch) != 0))
+        .or_else(|| {
+            visible()
+                .find_map(|ch| resolve_glyph(faces, ch))
+                .map(|(face, _)| face)
+        })
+        .unwrap_or(0)
+}
+
+/// Chars a font need not have a glyph for: controls, joiners, direction
+/// marks and variation selectors. They steer the shaper; they don't draw.
+fn is_invisible(ch: char) -> bool {
+    ch.is_control()
+        || matches!(
+            ch,
+            '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{2069}' | '\u{FE00}'..='\u{FE0F}'
+        )
+}
+
+fn shape_piece(
+    faces: &[Arc<Face>],
+    face: usize,
+    text: &str,
+    range: std::ops::Range<usize>,
+    rtl: bool,
+    out: &mut Vec<ShapedGlyph>,
+) {
+    let drawn = |cluster: usize| !text[cluster..].starts_with(char::is_control);
+    let Some(shaper) = rustybuzz::Face::from_slice(&faces[face].bytes, 0) else {
+        // fontdue read this face but rustybuzz can't: one glyph per char at
+        // its plain advance still beats drawing nothing.
+        let mut chars: Vec<_> = text[range.clone()].char_indices().collect();
+        if rtl {
+            chars.reverse();
+        }
+        for (offset, ch) in chars {
+            let index = faces[face].raster.lookup_glyph_index(ch);
+            if drawn(range.start + offset) {
+                out.push(ShapedGlyph {
+                    face,
+                    index,
+                    advance: faces[face]
+                        .raster
+                        .metrics_indexed(index, FONT_SIZE)
+                        .advance_width,
+                    x_offset: 0.0,
+                    y_offset: 0.0,
+                    cluster: range.start + offset,
+                });
+            }
+        }
+        return;
+    };
+
+    let mut buffer = rustybuzz::UnicodeBuffer::new();
+    buffer.push_str(&text[range.clone()]);
+    // The neighbours decide how the piece's first and last letters join.
+    buffer.set_pre_context(&text[..range.start]);
+    buffer.set_post_context(&text[range.end..]);
+    buffer.set_direction(if rtl {
+        rustybuzz::Direction::RightToLeft
+    } else {
+        rustybuzz::Direction::LeftToRight
+    });
+    buffer.guess_segment_properties();
+    let shaped = rustybuzz::shape(&shaper, &[], buffer);
+
+    let scale = FONT_SIZE / shaper.units_per_em() as f32;
+    for (info, pos) in shaped.glyph_infos().iter().zip(shaped.glyph_positions()) {
+        let cluster = range.start + info.cluster as usize;
+        if !drawn(cluster) {
+            continue;
+        }
+        out.push(ShapedGlyph {
+            face,
+            index: info.glyph_id as u16,
+            advance: pos.x_advance as f32 * scale,
+            x_offset: pos.x_offset as f32 * scale,
+            y_offset: pos.y_offset as f32 * scale,
+            cluster,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builtin_faces() -> Vec<Arc<Face>> {
+        vec![Arc::new(Face::new(
+            &include_bytes!("../../assets/font.ttf")[..],
+        ))]
+    }
+
+    /// DejaVu Sans cut down to Latin, Hebrew and Arabic, joining forms and
+    /// ligatures included.
+    fn rtl_face() -> Arc<Face> {
+        Arc::new(Face::new(
+            &include_bytes!("../../../samples/features/feature-bidi/assets/dejavu-sans-rtl.ttf")[..],
+        ))
+    }
+
+    #[test]
+    fn only_text_that_needs_it_takes_the_shaper() {
+        assert!(!needs_shaping("Hello, world!"));
+        assert!(!needs_shaping(
+            "Cr\u{e8}me br\u{fb}l\u{e9}e \u{2014} \u{6f22}\u{5b57}"
+        ));
+        // Hebrew, Arabic, Devanagari, and an `e` built from a combining accent.
+        assert!(needs_shaping("\u{5e9}\u{5dc}\u{5d5}\u{5dd}"));
+        assert!(needs_shaping("\u{633}\u{644}\u{627}\u{645}"));
+        assert!(needs_shaping("\u{928}\u{92e}\u{938}\u{94d}\u{924}\u{947}"));
+        assert!(needs_shaping("cafe\u{301}"));
+    }
+
+    #[test]
+    fn right_to_left_runs_come_out_in_screen_order() {
+        // "ab אבג cd": the Hebrew reads right to left inside a left-to-right
+        // line, so its clusters come out last-first between the Latin ones.
+        let text = "ab \u{5d0}\u{5d1}\u{5d2} cd";
+        let glyphs = shape(&builtin_faces(), text);
+        let clusters: Vec<usize> = glyphs.iter().map(|glyph| glyph.cluster).collect();
+        let (alef, bet, gimel) = (3, 5, 7);
+        assert_eq!(clusters, [0, 1, 2, gimel, bet, alef, 9, 10, 11]);
+        assert!(!is_rtl(text));
+
+        // The same words in a right-to-left paragraph: the Latin runs swap
+        // sides but still read left to right inside themselves.
+        let text = "\u{5d0}\u{5d1} ab";
+        assert!(is_rtl(text));
+        let clusters: Vec<usize> = shape(&builtin_faces(), text)
+            .iter()
+            .map(|glyph| glyph.cluster)
+            .collect();
+        assert_eq!(clusters, [5, 6, 4, 2, 0]);
+    }
+
+    #[test]
+    fn unshaped_text_measures_the_same_through_the_shaper() {
+        let faces = builtin_faces();
+        let text = "AVATAR Wave 42";
+        let shaped: f32 = shape(&faces, text).iter().map(|glyph| glyph.advance).sum();
+        let (plain, _) = super::super::measure_builtin_text(text, FONT_SIZE);
+        assert!((shaped - plain).abs() < 0.5, "{shaped} vs {plain}");
+        assert!(shape(&faces, "a\nb").iter().all(|glyph| glyph.cluster != 1));
+    }
+
+    #[test]
+    fn arabic_letters_join_and_lam_alef_ligates() {
+        // "سلام": seen, lam, alef, meem. Seen takes its initial form, lam and
+        // alef fuse into one glyph, and the word comes out last letter first.
+        let faces = [rtl_face()];
+        let text = "\u{633}\u{644}\u{627}\u{645}";
+        let glyphs = shape(&faces, text);
+        let clusters: Vec<usize> = glyphs.iter().map(|glyph| glyph.cluster).collect();
+        let (seen, lam, meem) = (0, 2, 6);
+        assert_eq!(clusters, [meem, lam, seen]);
+        assert!(glyphs.iter().all(|glyph| glyph.index != 0));
+
+        let isolated = |ch| faces[0].raster.lookup_glyph_index(ch);
+        assert_ne!(glyphs[2].index, isolated('\u{633}'));
+        assert_ne!(glyphs[1].index, isolated('\u{644}'));
+        assert_ne!(glyphs[1].index, isolated('\u{627}'));
+
+        // On its own, seen draws its isolated form.
+        assert_eq!(shape(&faces, "\u{633}")[0].index, isolated('\u{633}'));
+    }
+
+    #[test]
+    fn hebrew_falls_back_to_a_face_that_has_it() {
+        // "שלום" in a line the pixel face starts: the Hebrew draws from the
+        // fallback, reversed, and the Latin stays with the primary.
+        let faces = [builtin_faces().remove(0), rtl_face()];
+        let text = "hi \u{5e9}\u{5dc}\u{5d5}\u{5dd}";
+        let glyphs = shape(&faces, text);
+        let drawn: Vec<(usize, usize)> = glyphs
+            .iter()
+            .map(|glyph| (glyph.face, glyph.cluster))
+            .collect();
+        assert_eq!(
+            drawn,
+            [(0, 0), (0, 1), (0, 2), (1, 9), (1, 7), (1, 5), (1, 3)]
+        );
+        assert!(glyphs.iter().all(|glyph| glyph.index != 0));
+    }
+}
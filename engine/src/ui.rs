@@ -1,14 +1,16 @@
-use std::cell::{Cell, RefCell};
+use std::borrow::Cow;
+use std::cell::{Cell, OnceCell, RefCell};
 use std::collections::{HashMap, HashSet};
 
 use crate::app::Engine;
 use crate::assets::Color;
-use crate::canvas::{wrap_text, Canvas, TextAlign};
+use crate::canvas::{wrap_text, Canvas, RichLayout, RichText, TextAlign};
 use crate::input::InputState;
 use crate::math::Easing;
-use crate::text::FontAtlas;
+use crate::text::{FontAtlas, FontId};
 use crate::TextureId;
 use glam::Vec2;
+use unicode_segmentation::UnicodeSegmentation;
 use winit::keyboard::KeyCode;
 
 #[derive(Clone, Copy)]
@@ -237,6 +239,9 @@ impl TooltipOptions {
         self
     }
 
+    /// The fuller text shown while the expand trigger is held. Like the
+    /// tooltip's own text it accepts [`RichText`] markup, wrapped to the
+    /// tooltip's width — `"[color=#f44]12[/color] fire damage"`.
     pub fn with_advanced_text(mut self, text: impl Into<String>) -> Self {
         self.advanced_text = Some(text.into());
         self
@@ -724,6 +729,12 @@ enum Widget {
         size: f32,
         color: Color,
         align: TextAlign,
+        /// The parsed markup, for a label that has any; `text` is then the
+        /// source and only the rich path draws it.
+        rich: Option<RichText>,
+        /// `rich` laid out at `size` and `color`, filled the first time the
+        /// label is measured; sizing and drawing it reuse this.
+        layout: OnceCell<RichLayout>,
     },
     Image {
         texture: TextureId,
@@ -1012,25 +1023,28 @@ fn widget_runtime_key(widget: &Widget, widget_index: usize) -> WidgetRuntimeKey
     }
 }
 
-fn prev_char_boundary(text: &str, index: usize) -> usize {
+/// The start of the grapheme cluster before `index`. The cursor steps and
+/// deletes by cluster, so an accent or a Devanagari vowel sign goes with its
+/// letter instead of being left stranded.
+fn prev_grapheme_boundary(text: &str, index: usize) -> usize {
     if index == 0 {
         return 0;
     }
 
     text[..index]
-        .char_indices()
+        .grapheme_indices(true)
         .next_back()
         .map(|(idx, _)| idx)
         .unwrap_or(0)
 }
 
-fn next_char_boundary(text: &str, index: usize) -> usize {
+fn next_grapheme_boundary(text: &str, index: usize) -> usize {
     if index >= text.len() {
         return text.len();
     }
 
-    match text[index..].chars().next() {
-        Some(ch) => index + ch.len_utf8(),
+    match text[index..].graphemes(true).next() {
+        Some(cluster) => index + cluster.len(),
         None => text.len(),
     }
 }
@@ -1040,7 +1054,82 @@ fn clamp_char_boundary(text: &str, index: usize) -> usize {
     if text.is_char_boundary(clamped) {
         clamped
     } else {
-        prev_char_boundary(text, clamped)
+        text[..clamped]
+            .char_indices()
+            .next_back()
+            .map(|(idx, _)| idx)
+            .unwrap_or(0)
+    }
+}
+
+/// What text without tags draws on the plain path: `[[` escapes resolved
+/// like everywhere else, and the string as written when it has no brackets.
+fn plain_markup_text<'a>(text: &'a str, rich: &RichText) -> Cow<'a, str> {
+    if text.contains('[') {
+        Cow::Owned(rich.plain_text())
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// A label's widget, its markup parsed as it's pushed. Text without any
+/// keeps the plain path, so only labels that use tags pay for the rich one;
+/// those are laid out once, the first time they're measured.
+fn label_widget(text: &str, size: f32, color: Color, align: TextAlign) -> Widget {
+    let rich = RichText::parse(text);
+    let (text, align, rich) = if rich.is_plain() {
+        let plain = plain_markup_text(text, &rich);
+        (plain.to_string(), align.resolve(&plain), None)
+    } else {
+        (
+            text.to_string(),
+            align.resolve(&rich.plain_text()),
+            Some(rich),
+        )
+    };
+    Widget::Label {
+        text,
+        size,
+        color,
+        align,
+        rich,
+        layout: OnceCell::new(),
+    }
+}
+
+/// A rich label laid out on one line in the default font, as it draws: the
+/// first time it's asked for, and from `layout` after that.
+fn label_layout<'a>(
+    engine: &Engine,
+    rich: &RichText,
+    layout: &'a OnceCell<RichLayout>,
+    size: f32,
+    color: Color,
+) -> &'a RichLayout {
+    layout.get_or_init(|| {
+        rich.layout_in(
+            engine.fonts(),
+            FontId::DEFAULT,
+            size,
+            color,
+            f32::INFINITY,
+            engine.markup_assets(),
+        )
+    })
+}
+
+/// How tall a label stands: one line box, or a rich layout's full height —
+/// a `[size=..]` span or a newline makes it taller.
+fn label_height(
+    engine: &Engine,
+    rich: Option<&RichText>,
+    layout: &OnceCell<RichLayout>,
+    size: f32,
+    color: Color,
+) -> f32 {
+    match rich {
+        Some(rich) => label_layout(engine, rich, layout, size, color).size().1,
+        None => engine.font_atlas().line_height(size),
     }
 }
 
@@ -1069,11 +1158,7 @@ fn fit_text_to_width(atlas: &FontAtlas, text: &str, size: f32, max_width: f32) -
             return ellipsis.to_string();
         }
 
-        end = text[..end]
-            .char_indices()
-            .next_back()
-            .map(|(index, _)| index)
-            .unwrap_or(0);
+        end = prev_grapheme_boundary(text, end);
     }
 }
 
@@ -1383,12 +1468,13 @@ fn capture_tooltip<'a>(
 
 fn draw_tooltip(
     canvas: &mut Canvas,
-    atlas: &FontAtlas,
+    engine: &Engine,
     style: &UiStyle,
     tooltip: ActiveTooltip<'_>,
     screen_size: (u32, u32),
     visibility: f32,
 ) {
+    let atlas = engine.font_atlas();
     let margin = 8.0;
     let max_box_width = (screen_size.0 as f32 - margin * 2.0).max(1.0);
     let max_box_height = (screen_size.1 as f32 - margin * 2.0).max(1.0);
@@ -1405,18 +1491,46 @@ fn draw_tooltip(
     } else {
         tooltip.max_width.max(1.0).min(max_text_width)
     };
-    let lines = wrap_text(
-        tooltip.text,
-        style.tooltip_text_size,
-        text_wrap_width,
-        atlas,
-    );
-    let line_height = atlas.line_height(style.tooltip_text_size);
-    let text_width = lines
-        .iter()
-        .map(|line| atlas.measure_text(line, style.tooltip_text_size).0)
-        .fold(0.0, f32::max);
-    let content_height = line_height * lines.len() as f32;
+    let animation_t = visibility.clamp(0.0, 1.0);
+    let (alpha, animation_offset) = match tooltip.animation {
+        TooltipAnimation::None => (1.0, Vec2::ZERO),
+        TooltipAnimation::Fade { .. } => (animation_t, Vec2::ZERO),
+        TooltipAnimation::FadeSlide { offset, .. } => (animation_t, offset * (1.0 - animation_t)),
+    };
+    let fg = scale_alpha(style.tooltip_text_color, alpha);
+    // Markup wraps through its own layout (same break rules); anything
+    // without tags stays on the plain line-wrapped path.
+    let rich = RichText::parse(tooltip.text);
+    let (lines, layout) = if rich.is_plain() {
+        let lines = wrap_text(
+            &plain_markup_text(tooltip.text, &rich),
+            style.tooltip_text_size,
+            text_wrap_width,
+            atlas,
+        );
+        (lines, None)
+    } else {
+        let layout = rich.layout_in(
+            engine.fonts(),
+            FontId::DEFAULT,
+            style.tooltip_text_size,
+            fg,
+            text_wrap_width,
+            engine.markup_assets(),
+        );
+        (Vec::new(), Some(layout))
+    };
+    let (text_width, content_height) = match &layout {
+        Some(layout) => layout.size(),
+        None => {
+            let line_height = atlas.line_height(style.tooltip_text_size);
+            let text_width = lines
+                .iter()
+                .map(|line| atlas.measure_text(line, style.tooltip_text_size).0)
+                .fold(0.0, f32::max);
+            (text_width, line_height * lines.len() as f32)
+        }
+    };
     let box_width = if fixed_box_width > 0.0 {
         fixed_box_width
     } else {
@@ -1430,12 +1544,6 @@ fn draw_tooltip(
         .max(min_box_height);
     let half_width = screen_size.0 as f32 / 2.0;
     let half_height = screen_size.1 as f32 / 2.0;
-    let animation_t = visibility.clamp(0.0, 1.0);
-    let (alpha, animation_offset) = match tooltip.animation {
-        TooltipAnimation::None => (1.0, Vec2::ZERO),
-        TooltipAnimation::Fade { .. } => (animation_t, Vec2::ZERO),
-        TooltipAnimation::FadeSlide { offset, .. } => (animation_t, offset * (1.0 - animation_t)),
-    };
     let offset = tooltip.offset + animation_offset;
     let widget_anchor = Vec2::new(
         tooltip.rect.x + tooltip.rect.w,
@@ -1469,7 +1577,6 @@ fn draw_tooltip(
     };
     let text_clip_height = (box_height - style.tooltip_padding * 2.0).max(1.0);
     let bg = scale_alpha(style.tooltip_bg, alpha);
-    let fg = scale_alpha(style.tooltip_text_color, alpha);
 
     canvas.rect(x, top - box_height, box_width, box_height, bg);
     canvas.push_clip(
@@ -1478,14 +1585,23 @@ fn draw_tooltip(
         (box_width - style.tooltip_padding * 2.0).max(1.0),
         text_clip_height,
     );
-    canvas.text_block_lines(
-        x + style.tooltip_padding,
-        top - style.tooltip_padding,
-        &lines,
-        style.tooltip_text_size,
-        fg,
-        TextAlign::Left,
-    );
+    match &layout {
+        Some(layout) => canvas.rich_text(
+            x + style.tooltip_padding,
+            top - style.tooltip_padding,
+            layout,
+            TextAlign::Left,
+            engine.time().total_time(),
+        ),
+        None => canvas.text_block_lines(
+            x + style.tooltip_padding,
+            top - style.tooltip_padding,
+            &lines,
+            style.tooltip_text_size,
+            fg,
+            TextAlign::Left,
+        ),
+    }
     canvas.pop_clip();
 }
 
@@ -1529,6 +1645,35 @@ mod tests {
         assert_eq!(response.drop_for(9), Some(3));
         assert_eq!(response.drop_for(2), None);
     }
+
+    #[test]
+    fn labels_take_the_rich_path_only_when_they_use_markup() {
+        let Widget::Label { text, rich, .. } =
+            label_widget("[E] Open", 16.0, Color::WHITE, TextAlign::Left)
+        else {
+            unreachable!()
+        };
+        assert_eq!(text, "[E] Open");
+        assert!(rich.is_none());
+
+        // Escapes read the same on the plain path as on the rich one.
+        let Widget::Label { text, rich, .. } =
+            label_widget("[[E] Open", 16.0, Color::WHITE, TextAlign::Left)
+        else {
+            unreachable!()
+        };
+        assert_eq!(text, "[E] Open");
+        assert!(rich.is_none());
+
+        let markup = "[b]Loot:[/b] 3 [img=coin]";
+        let Widget::Label { text, rich, .. } =
+            label_widget(markup, 16.0, Color::WHITE, TextAlign::Center)
+        else {
+            unreachable!()
+        };
+        assert_eq!(text, markup);
+        assert_eq!(rich, Some(RichText::parse(markup)));
+    }
 }
 
 pub struct UiResponse {
@@ -1977,22 +2122,24 @@ impl Ui {
         self
     }
 
+    /// A line of text. Accepts [`RichText`] markup — `[color=..]`, `[b]`,
+    /// `[img=..]` and the rest — with aliases resolved through
+    /// [`Engine::markup_assets`].
+    ///
+    /// Aligned [`TextAlign::Start`]: on the left, unless the text reads right
+    /// to left.
     pub fn label(&mut self, text: &str, size: f32, color: Color) {
-        self.push_widget(Widget::Label {
-            text: text.to_string(),
-            size,
-            color,
-            align: TextAlign::Left,
-        });
+        self.push_widget(label_widget(text, size, color, TextAlign::Start));
     }
 
     pub fn label_centered(&mut self, text: &str, size: f32, color: Color) {
-        self.push_widget(Widget::Label {
-            text: text.to_string(),
-            size,
-            color,
-            align: TextAlign::Center,
-        });
+        self.push_widget(label_widget(text, size, color, TextAlign::Center));
+    }
+
+    /// [`label`](Self::label) against either edge of the layout column, or
+    /// its start or end for the text's direction.
+    pub fn label_aligned(&mut self, text: &str, size: f32, color: Color, align: TextAlign) {
+        self.push_widget(label_widget(text, size, color, align));
     }
 
     pub fn image(&mut self, texture: TextureId, size: Vec2) {
@@ -2241,12 +2388,19 @@ impl Ui {
         remaining: &[Widget],
         resolved_styles: &[UiStyle],
         widget_style_overrides: &[Option<UiWidgetStyle>],
-        atlas: &FontAtlas,
+        engine: &Engine,
     ) -> f32 {
+        let atlas = engine.font_atlas();
         let style = &resolved_styles[widget_index];
         let style_override = widget_style_overrides[widget_index].as_ref();
         match widget {
-            Widget::Label { size, .. } => atlas.line_height(*size) + style.spacing,
+            Widget::Label {
+                size,
+                color,
+                rich,
+                layout,
+                ..
+            } => label_height(engine, rich.as_ref(), layout, *size, *color) + style.spacing,
             Widget::Image { size, .. } => size.y + style.spacing,
             Widget::Button { .. } => {
                 let lh = atlas.line_height(style.text_size);
@@ -2274,7 +2428,7 @@ impl Ui {
                         &child_slice[i + 1..],
                         resolved_styles,
                         widget_style_overrides,
-                        atlas,
+                        engine,
                     );
                     i += 1;
                 }
@@ -2307,7 +2461,7 @@ impl Ui {
                         &child_slice[ci + 1..],
                         resolved_styles,
                         widget_style_overrides,
-                        atlas,
+                        engine,
                     );
                     max_h = max_h.max(ch);
                     ci += 1;
@@ -2330,7 +2484,7 @@ impl Ui {
                         &child_slice[ci + 1..],
                         resolved_styles,
                         widget_style_overrides,
-                        atlas,
+                        engine,
                     );
                     row_max = row_max.max(ch);
                     if (ci + 1) % cols == 0 || ci + 1 == child_slice.len() {
@@ -2347,10 +2501,11 @@ impl Ui {
 
     fn compute_focusable_rects(
         &self,
-        atlas: &FontAtlas,
+        engine: &Engine,
         resolved_styles: &[UiStyle],
         widget_style_overrides: &[Option<UiWidgetStyle>],
     ) -> Vec<(usize, f32, f32, f32, f32)> {
+        let atlas = engine.font_atlas();
         let mut rects = Vec::new();
         let mut cursor_y = self.y;
         let mut base_x = self.x;
@@ -2381,8 +2536,15 @@ impl Ui {
             let style_override = widget_style_overrides[i].as_ref();
 
             match &self.widgets[i] {
-                Widget::Label { size, .. } => {
-                    cursor_y -= atlas.line_height(*size) + style.spacing;
+                Widget::Label {
+                    size,
+                    color,
+                    rich,
+                    layout,
+                    ..
+                } => {
+                    cursor_y -=
+                        label_height(engine, rich.as_ref(), layout, *size, *color) + style.spacing;
                 }
                 Widget::Image { size, .. } => {
                     cursor_y -= size.y + style.spacing;
@@ -2625,7 +2787,6 @@ impl Ui {
 
     pub fn update(&mut self, engine: &Engine) -> UiResponse {
         let input = engine.input();
-        let atlas = engine.font_atlas();
         let resolved_style_cache = self.take_or_build_resolved_style_cache();
         let resolved_styles = &resolved_style_cache.resolved_styles;
         let widget_style_overrides = &resolved_style_cache.widget_style_overrides;
@@ -2676,7 +2837,7 @@ impl Ui {
             self.focus_index = 0;
         }
 
-        let rects = self.compute_focusable_rects(atlas, &resolved_styles, &widget_style_overrides);
+        let rects = self.compute_focusable_rects(engine, resolved_styles, widget_style_overrides);
         let (mx, my) = input.mouse_position();
 
         for (rect_idx, &(_, rx, ry, rw, rh)) in rects.iter().enumerate() {
@@ -2835,19 +2996,19 @@ impl Ui {
                 *cursor = edited.len();
             }
             if input.is_key_pressed(KeyCode::ArrowLeft) {
-                *cursor = prev_char_boundary(&edited, *cursor);
+                *cursor = prev_grapheme_boundary(&edited, *cursor);
             }
             if input.is_key_pressed(KeyCode::ArrowRight) {
-                *cursor = next_char_boundary(&edited, *cursor);
+                *cursor = next_grapheme_boundary(&edited, *cursor);
             }
             if input.is_key_pressed(KeyCode::Backspace) && *cursor > 0 {
-                let start = prev_char_boundary(&edited, *cursor);
+                let start = prev_grapheme_boundary(&edited, *cursor);
                 edited.replace_range(start..*cursor, "");
                 *cursor = start;
                 text_changed = true;
             }
             if input.is_key_pressed(KeyCode::Delete) && *cursor < edited.len() {
-                let end = next_char_boundary(&edited, *cursor);
+                let end = next_grapheme_boundary(&edited, *cursor);
                 edited.replace_range(*cursor..end, "");
                 text_changed = true;
             }
@@ -2926,7 +3087,7 @@ impl Ui {
                                     &child_slice[ci + 1..],
                                     &resolved_styles,
                                     &widget_style_overrides,
-                                    atlas,
+                                    engine,
                                 );
                             }
                             let max_scroll = (content_h - *height).max(0.0);
@@ -2945,7 +3106,7 @@ impl Ui {
                             &self.widgets[si + 1..],
                             &resolved_styles,
                             &widget_style_overrides,
-                            atlas,
+                            engine,
                         );
                         let skip = match other {
                             Widget::Panel { children, .. }
@@ -2982,6 +3143,7 @@ impl Ui {
         let atlas = engine.font_atlas();
         let input = engine.input();
         let dt = engine.dt();
+        let time = engine.time().total_time();
         let mut cursor_y = self.y;
         let mut base_x = self.x;
         let mut current_width = self.width;
@@ -3057,20 +3219,26 @@ impl Ui {
                     size,
                     color,
                     align,
+                    rich,
+                    layout,
                 } => {
-                    let lh = atlas.line_height(*size);
+                    let layout = rich
+                        .as_ref()
+                        .map(|rich| label_layout(engine, rich, layout, *size, *color));
+                    let lh = layout
+                        .as_ref()
+                        .map_or_else(|| atlas.line_height(*size), |layout| layout.size().1);
                     let ax = match align {
-                        TextAlign::Left => base_x,
+                        TextAlign::Left | TextAlign::Start => base_x,
                         TextAlign::Center => base_x + current_width / 2.0,
-                        TextAlign::Right => base_x + current_width,
+                        TextAlign::Right | TextAlign::End => base_x + current_width,
                     };
                     let label_rect = if has_tooltip || has_animation {
-                        let text_width = atlas.measure_text(text, *size).0;
-                        let left = match align {
-                            TextAlign::Left => ax,
-                            TextAlign::Center => ax - text_width / 2.0,
-                            TextAlign::Right => ax - text_width,
+                        let text_width = match &layout {
+                            Some(layout) => layout.size().0,
+                            None => atlas.measure_text(text, *size).0,
                         };
+                        let left = ax + align.offset(text_width);
                         Some(UiRect {
                             x: left,
                             y: cursor_y - lh,
@@ -3102,19 +3270,37 @@ impl Ui {
                         }
                         let label_pos =
                             animated_point(Vec2::new(ax, cursor_y), label_rect, render_animation);
-                        canvas.text_aligned(
-                            label_pos.x,
-                            label_pos.y,
-                            text,
-                            animated_size(*size, render_animation),
-                            scale_alpha(*color, render_animation.alpha),
-                            *align,
-                        );
+                        let draw_size = animated_size(*size, render_animation);
+                        let draw_color = scale_alpha(*color, render_animation.alpha);
+                        match layout {
+                            // Scaled rather than re-laid out at `draw_size`, so
+                            // the spans keep their proportions mid-animation.
+                            Some(layout) => canvas.rich_text(
+                                label_pos.x,
+                                label_pos.y,
+                                &layout
+                                    .faded(render_animation.alpha.clamp(0.0, 1.0))
+                                    .scaled(draw_size / *size),
+                                *align,
+                                time,
+                            ),
+                            None => canvas.text_aligned(
+                                label_pos.x,
+                                label_pos.y,
+                                text,
+                                draw_size,
+                                draw_color,
+                                *align,
+                            ),
+                        }
                     } else {
                         if has_tooltip {
                             tooltip_rect = label_rect;
                         }
-                        canvas.text_aligned(ax, cursor_y, text, *size, *color, *align);
+                        match layout {
+                            Some(layout) => canvas.rich_text(ax, cursor_y, layout, *align, time),
+                            None => canvas.text_aligned(ax, cursor_y, text, *size, *color, *align),
+                        }
                     }
                     cursor_y -= lh + style.spacing;
                 }
@@ -3470,7 +3656,7 @@ impl Ui {
                                 &child_slice[ci + 1..],
                                 &resolved_styles,
                                 &widget_style_overrides,
-                                atlas,
+                                engine,
                             );
                             ci += 1;
                         }
@@ -4328,7 +4514,7 @@ impl Ui {
             if runtime.elapsed >= tooltip.delay {
                 draw_tooltip(
                     canvas,
-                    atlas,
+                    engine,
                     &resolved_styles[tooltip.widget_index],
                     tooltip,
                     engine.window_size(),
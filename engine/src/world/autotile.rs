@@ -0,0 +1,718 @@
+//! Rule-based autotiling: paint a *terrain* into a cell and the cell and its
+//! neighbours pick the sprite-sheet variant that matches which of their
+//! neighbours share the terrain.
+//!
+//! Rules live in an [`AutotileRules`] set, built in code or parsed from a JSON
+//! file that sits next to the terrain's sprite sheet (an asset manifest's
+//! sprite sheet entry names it with `"autotile"`). Each [`TerrainRule`] picks
+//! an [`AutotileMode`] — 4-bit edge or corner Wang masks, or the 8-bit
+//! "blob" mask with its 47 distinct tiles — and maps masks to sheet cells.
+//!
+//! An [`Autotiler`] keeps the terrain painted into each cell of one
+//! [`TileMap`] and writes the matching tiles into it: [`Autotiler::apply`]
+//! for a whole map built at load time, [`Autotiler::set_terrain`] for single
+//! edits, which refresh the 3×3 block around the cell.
+//!
+//! ```json
+//! {
+//!   "terrains": [
+//!     {"name": "wall", "mode": "blob8", "layout": {"start": [0, 0], "columns": 8},
+//!      "connects_to": ["door"]},
+//!     {"name": "water", "mode": "edges4", "collides": false, "fallback": [0, 6],
+//!      "tiles": {"0": [[0, 6]], "15": [[1, 6], [2, 6]]}}
+//!   ]
+//! }
+//! ```
+//!
+//! `layout` lays a terrain's tiles out in ascending mask order (see
+//! [`AutotileMode::masks`]) from `start`, wrapping after `columns` cells;
+//! `tiles` maps a mask to one or more cells, picked per cell for variety.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::assets::{AssetError, SpriteSheet};
+use crate::world::tilemap::{TileDef, TileMap};
+
+/// How a terrain cell reads its neighbours into a mask. With `N` meaning the
+/// row above (`row + 1`):
+///
+/// - `Edges4`: `N = 1`, `E = 2`, `S = 4`, `W = 8` — 16 tiles.
+/// - `Corners4`: `NE = 1`, `SE = 2`, `SW = 4`, `NW = 8`, each set when both
+///   edges and the diagonal meeting at that corner connect — 16 tiles.
+/// - `Blob8`: `N = 1`, `NE = 2`, `E = 4`, `SE = 8`, `S = 16`, `SW = 32`,
+///   `W = 64`, `NW = 128`, with a diagonal only counted when both of its
+///   edges are — 47 tiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutotileMode {
+    Edges4,
+    Corners4,
+    Blob8,
+}
+
+/// `(dx, dy)` of each blob bit, clockwise from north.
+const BLOB_DIRS: [(i32, i32); 8] = [
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+];
+
+impl AutotileMode {
+    /// The mask of a cell whose neighbours all connect.
+    pub fn full_mask(self) -> u8 {
+        match self {
+            Self::Edges4 | Self::Corners4 => 0b1111,
+            Self::Blob8 => 0xff,
+        }
+    }
+
+    /// Every mask this mode can produce, ascending.
+    pub fn masks(self) -> Vec<u8> {
+        match self {
+            Self::Edges4 | Self::Corners4 => (0..16).collect(),
+            Self::Blob8 => (0..=0xff)
+                .filter(|&mask| self.mask(|dx, dy| blob_bit(mask, dx, dy)) == mask)
+                .collect(),
+        }
+    }
+
+    /// The mask for a cell, given whether its neighbour at `(dx, dy)`
+    /// connects.
+    pub fn mask(self, connects: impl Fn(i32, i32) -> bool) -> u8 {
+        let n = connects(0, 1);
+        let e = connects(1, 0);
+        let s = connects(0, -1);
+        let w = connects(-1, 0);
+        match self {
+            Self::Edges4 => bits(&[n, e, s, w]),
+            Self::Corners4 => bits(&[
+                n && e && connects(1, 1),
+                s && e && connects(1, -1),
+                s && w && connects(-1, -1),
+                n && w && connects(-1, 1),
+            ]),
+            Self::Blob8 => bits(&[
+                n,
+                n && e && connects(1, 1),
+                e,
+                s && e && connects(1, -1),
+                s,
+                s && w && connects(-1, -1),
+                w,
+                n && w && connects(-1, 1),
+            ]),
+        }
+    }
+}
+
+fn bits(flags: &[bool]) -> u8 {
+    flags
+        .iter()
+        .enumerate()
+        .fold(0, |mask, (bit, &set)| mask | (u8::from(set) << bit))
+}
+
+/// Reads a neighbour back out of a blob mask; the masks that survive a round
+/// trip through [`AutotileMode::mask`] are the 47 reachable ones.
+fn blob_bit(mask: u8, dx: i32, dy: i32) -> bool {
+    let bit = BLOB_DIRS
+        .iter()
+        .position(|&dir| dir == (dx, dy))
+        .unwrap_or(0);
+    mask & (1 << bit) != 0
+}
+
+/// How one terrain turns masks into sheet cells.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerrainRule {
+    pub name: String,
+    pub mode: AutotileMode,
+    /// Sheet cells `[col, row]` for each mask; several cells are picked
+    /// between per map cell.
+    pub variants: HashMap<u8, Vec<[u32; 2]>>,
+    /// The cell for masks without a variant. Defaults to the full mask's.
+    pub fallback: Option<[u32; 2]>,
+    /// Other terrains this one treats as its own when building masks.
+    pub connects_to: Vec<String>,
+    pub collides: bool,
+}
+
+impl TerrainRule {
+    pub fn new(name: impl Into<String>, mode: AutotileMode) -> Self {
+        Self {
+            name: name.into(),
+            mode,
+            variants: HashMap::new(),
+            fallback: None,
+            connects_to: Vec::new(),
+            collides: true,
+        }
+    }
+
+    pub fn with_variant(mut self, mask: u8, cell: [u32; 2]) -> Self {
+        self.variants.entry(mask).or_default().push(cell);
+        self
+    }
+
+    /// Tiles laid out in [`AutotileMode::masks`] order from `start`,
+    /// `columns` cells to a row.
+    pub fn with_mask_order(mut self, start: [u32; 2], columns: u32) -> Self {
+        let columns = columns.max(1);
+        for (index, mask) in self.mode.masks().into_iter().enumerate() {
+            let index = index as u32;
+            let cell = [start[0] + index % columns, start[1] + index / columns];
+            self.variants.entry(mask).or_default().push(cell);
+        }
+        self
+    }
+
+    pub fn with_fallback(mut self, cell: [u32; 2]) -> Self {
+        self.fallback = Some(cell);
+        self
+    }
+
+    pub fn with_connects_to(mut self, terrain: impl Into<String>) -> Self {
+        self.connects_to.push(terrain.into());
+        self
+    }
+
+    pub fn with_collision(mut self, collides: bool) -> Self {
+        self.collides = collides;
+        self
+    }
+
+    /// The sheet cell for `mask`; `pick` chooses between variants.
+    pub fn cell_for(&self, mask: u8, pick: u32) -> Option<[u32; 2]> {
+        let pick_from =
+            |cells: &Vec<[u32; 2]>| cells.get(pick as usize % cells.len().max(1)).copied();
+        self.variants
+            .get(&mask)
+            .and_then(pick_from)
+            .or(self.fallback)
+            .or_else(|| {
+                self.variants
+                    .get(&self.mode.full_mask())
+                    .and_then(pick_from)
+            })
+    }
+
+    fn cells(&self) -> impl Iterator<Item = [u32; 2]> + '_ {
+        self.variants
+            .values()
+            .flatten()
+            .copied()
+            .chain(self.fallback)
+    }
+}
+
+/// A set of terrains that can be painted into one map.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AutotileRules {
+    pub terrains: Vec<TerrainRule>,
+}
+
+#[derive(Deserialize)]
+struct RulesFile {
+    terrains: Vec<TerrainFile>,
+}
+
+#[derive(Deserialize)]
+struct TerrainFile {
+    name: String,
+    mode: AutotileMode,
+    #[serde(default)]
+    layout: Option<LayoutFile>,
+    #[serde(default)]
+    tiles: HashMap<String, Vec<[u32; 2]>>,
+    #[serde(default)]
+    fallback: Option<[u32; 2]>,
+    #[serde(default)]
+    connects_to: Vec<String>,
+    #[serde(default = "default_collides")]
+    collides: bool,
+}
+
+#[derive(Deserialize)]
+struct LayoutFile {
+    start: [u32; 2],
+    #[serde(default)]
+    columns: Option<u32>,
+}
+
+fn default_collides() -> bool {
+    true
+}
+
+impl AutotileRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_terrain(mut self, rule: TerrainRule) -> Self {
+        self.terrains.push(rule);
+        self
+    }
+
+    /// Parse a rules file; see the [module docs](self) for the format.
+    pub fn parse(path: &Path, text: &str) -> Result<Self, AssetError> {
+        let file: RulesFile = serde_json::from_str(text).map_err(|source| AssetError::Json {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let error = |message: String| AssetError::map_message(path, message);
+
+        let mut rules = Self::new();
+        for terrain in file.terrains {
+            if rules.terrain(&terrain.name).is_some() {
+                return Err(error(format!(
+                    "terrain '{}' is defined twice",
+                    terrain.name
+                )));
+            }
+            let mut rule =
+                TerrainRule::new(&terrain.name, terrain.mode).with_collision(terrain.collides);
+            rule.fallback = terrain.fallback;
+            rule.connects_to = terrain.connects_to;
+            if let Some(layout) = terrain.layout {
+                let columns = layout.columns.unwrap_or(terrain.mode.masks().len() as u32);
+                rule = rule.with_mask_order(layout.start, columns);
+            }
+            let valid = terrain.mode.masks();
+            for (key, cells) in terrain.tiles {
+                let mask = key
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|mask| valid.contains(mask))
+                    .ok_or_else(|| {
+                        error(format!(
+                            "terrain '{}': '{key}' is not a {:?} mask",
+                            terrain.name, terrain.mode
+                        ))
+                    })?;
+                // Explicit tiles replace any from the layout.
+                rule.variants.insert(mask, cells);
+            }
+            if rule.cell_for(rule.mode.full_mask(), 0).is_none() {
+                return Err(error(format!(
+                    "terrain '{}' has no tiles; give it a layout, tiles or a fallback",
+                    terrain.name
+                )));
+            }
+            rules.terrains.push(rule);
+        }
+
+        for rule in &rules.terrains {
+            if let Some(missing) = rule
+                .connects_to
+                .iter()
+                .find(|name| rules.terrain(name).is_none())
+            {
+                return Err(error(format!(
+                    "terrain '{}' connects to unknown terrain '{missing}'",
+                    rule.name
+                )));
+            }
+        }
+        Ok(rules)
+    }
+
+    /// The index of a terrain by name.
+    pub fn terrain(&self, name: &str) -> Option<usize> {
+        self.terrains.iter().position(|rule| rule.name == name)
+    }
+}
+
+/// The terrain painted into each cell of one [`TileMap`], and the tiles the
+/// rules register in it.
+pub struct Autotiler {
+    rules: Arc<AutotileRules>,
+    width: usize,
+    height: usize,
+    cells: Vec<Option<usize>>,
+    /// Per terrain, the map's tile id for each sheet cell.
+    tiles: Vec<HashMap<[u32; 2], usize>>,
+    /// `links[a][b]`: terrain `a` treats terrain `b` as its own.
+    links: Vec<Vec<bool>>,
+    edges_connect: bool,
+}
+
+impl Autotiler {
+    /// An empty terrain grid for a `width` × `height` map. Every tile the
+    /// rules use is registered up front through `add_tile` — usually
+    /// `|def| map.add_tile(def)`, or a [`LayeredTileMap`](super::LayeredTileMap)'s
+    /// so the ids are valid on every layer.
+    pub fn new(
+        rules: Arc<AutotileRules>,
+        sheet: &SpriteSheet,
+        width: usize,
+        height: usize,
+        mut add_tile: impl FnMut(TileDef) -> usize,
+    ) -> Self {
+        let mut tiles = Vec::with_capacity(rules.terrains.len());
+        for rule in &rules.terrains {
+            let mut cells: Vec<[u32; 2]> = rule.cells().collect();
+            cells.sort_unstable();
+            cells.dedup();
+            let ids = cells
+                .into_iter()
+                .map(|cell| {
+                    let def = TileDef::solid(sheet.texture)
+                        .with_uv(sheet.uv_rect(cell[0], cell[1]))
+                        .with_collision(rule.collides);
+                    (cell, add_tile(def))
+                })
+                .collect();
+            tiles.push(ids);
+        }
+        let links = rules
+            .terrains
+            .iter()
+            .enumerate()
+            .map(|(a, rule)| {
+                rules
+                    .terrains
+                    .iter()
+                    .enumerate()
+                    .map(|(b, other)| a == b || rule.connects_to.contains(&other.name))
+                    .collect()
+            })
+            .collect();
+        Self {
+            rules,
+            width,
+            height,
+            cells: vec![None; width * height],
+            tiles,
+            links,
+            edges_connect: true,
+        }
+    }
+
+    /// Whether cells past the map's edge count as every terrain, so terrain
+    /// running off the map shows no border there. On by default.
+    pub fn with_edges_connect(mut self, edges_connect: bool) -> Self {
+        self.edges_connect = edges_connect;
+        self
+    }
+
+    pub fn rules(&self) -> &AutotileRules {
+        &self.rules
+    }
+
+    pub fn terrain_at(&self, col: usize, row: usize) -> Option<&str> {
+        let terrain = (*self.cells.get(self.index(col, row)?)?)?;
+        Some(self.rules.terrains[terrain].name.as_str())
+    }
+
+    /// Paint (or with `None`, erase) a terrain without touching any map;
+    /// follow a batch of these with [`Autotiler::apply`]. Returns false for
+    /// an unknown terrain or a cell outside the grid.
+    pub fn paint(&mut self, col: usize, row: usize, terrain: Option<&str>) -> bool {
+        let Some(index) = self.index(col, row) else {
+            return false;
+        };
+        let terrain = match terrain {
+            Some(name) => match self.rules.terrain(name) {
+                Some(terrain) => Some(terrain),
+                None => return false,
+            },
+            None => None,
+        };
+        self.cells[index] = terrain;
+        true
+    }
+
+    /// Paint a terrain and update the cell and its eight neighbours in `map`.
+    pub fn set_terrain(
+        &mut self,
+        map: &mut TileMap,
+        col: usize,
+        row: usize,
+        terrain: Option<&str>,
+    ) -> bool {
+        if !self.paint(col, row, terrain) {
+            return false;
+        }
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let (c, r) = (col as i64 + dx, row as i64 + dy);
+                if c >= 0 && r >= 0 {
+                    self.refresh(map, c as usize, r as usize);
+                }
+            }
+        }
+        true
+    }
+
+    /// The painted terrain as text, for keeping a layer in a scene node
+    /// property: rows from the bottom up separated by `;`, cells by `,`, and
+    /// unpainted cells left empty, so `rock,rock,;,water,` is a 3 × 2 grid.
+    /// The editor's terrain brush stores layers this way.
+    pub fn terrain_text(&self) -> String {
+        self.cells
+            .chunks(self.width.max(1))
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.map_or("", |terrain| &self.rules.terrains[terrain].name))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    /// [`Autotiler::paint`] every cell of a [`Autotiler::terrain_text`]
+    /// grid; follow with [`Autotiler::apply`]. Cells past this grid's size
+    /// and unknown terrains are skipped. Returns how many cells were painted.
+    pub fn paint_text(&mut self, text: &str) -> usize {
+        let mut painted = 0;
+        for (row, cells) in text.split(';').enumerate() {
+            for (col, name) in cells.split(',').enumerate() {
+                let name = name.trim();
+                if !name.is_empty() && self.paint(col, row, Some(name)) {
+                    painted += 1;
+                }
+            }
+        }
+        painted
+    }
+
+    /// Write every cell's tile into `map`, e.g. after generating a level.
+    /// Cells without terrain are cleared.
+    pub fn apply(&self, map: &mut TileMap) {
+        for row in 0..self.height {
+            for col in 0..self.width {
+                self.refresh(map, col, row);
+            }
+        }
+    }
+
+    /// The mask a terrain cell currently reads from its neighbours.
+    pub fn mask_at(&self, col: usize, row: usize) -> Option<u8> {
+        let terrain = (*self.cells.get(self.index(col, row)?)?)?;
+        let rule = &self.rules.terrains[terrain];
+        Some(
+            rule.mode
+                .mask(|dx, dy| self.connects(terrain, col, row, dx, dy)),
+        )
+    }
+
+    fn refresh(&self, map: &mut TileMap, col: usize, row: usize) {
+        let Some(index) = self.index(col, row) else {
+            return;
+        };
+        let Some(terrain) = self.cells[index] else {
+            map.set(col, row, None);
+            return;
+        };
+        let rule = &self.rules.terrains[terrain];
+        let mask = rule
+            .mode
+            .mask(|dx, dy| self.connects(terrain, col, row, dx, dy));
+        let tile = rule
+            .cell_for(mask, cell_hash(col, row))
+            .and_then(|cell| self.tiles[terrain].get(&cell).copied());
+        map.set(col, row, tile);
+    }
+
+    fn connects(&self, terrain: usize, col: usize, row: usize, dx: i32, dy: i32) -> bool {
+        let (c, r) = (col as i64 + i64::from(dx), row as i64 + i64::from(dy));
+        if c < 0 || r < 0 || c >= self.width as i64 || r >= self.height as i64 {
+            return self.edges_connect;
+        }
+        self.cells[r as usize * self.width + c as usize]
+            .is_some_and(|other| self.links[terrain][other])
+    }
+
+    fn index(&self, col: usize, row: usize) -> Option<usize> {
+        (col < self.width && row < self.height).then_some(row * self.width + col)
+    }
+}
+
+/// A stable per-cell number for choosing between tile variants.
+fn cell_hash(col: usize, row: usize) -> u32 {
+    let h = (col as u32).wrapping_mul(0x9e37_79b1) ^ (row as u32).wrapping_mul(0x85eb_ca77);
+    (h ^ (h >> 15)).wrapping_mul(0x2c1b_3c6d) >> 8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::TextureId;
+
+    const N: u8 = 1;
+    const E: u8 = 4;
+    const S: u8 = 16;
+    const W: u8 = 64;
+
+    fn sheet() -> SpriteSheet {
+        SpriteSheet::new(TextureId(3), 128, 128, 16, 16)
+    }
+
+    /// The sheet cell a map cell shows, read back from its tile's UVs.
+    fn shown(map: &TileMap, col: usize, row: usize) -> Option<[u32; 2]> {
+        let uv = map.tile(map.get(col, row)?)?.uv_rect;
+        Some([(uv[0] * 8.0).round() as u32, (uv[1] * 8.0).round() as u32])
+    }
+
+    #[test]
+    fn modes_produce_the_expected_mask_counts() {
+        assert_eq!(AutotileMode::Edges4.masks().len(), 16);
+        assert_eq!(AutotileMode::Corners4.masks().len(), 16);
+        let blob = AutotileMode::Blob8.masks();
+        assert_eq!(blob.len(), 47);
+        // A lone NE corner only counts with both of its edges.
+        assert!(!blob.contains(&2));
+        assert!(blob.contains(&(N | 2 | E)));
+
+        let all = |_: i32, _: i32| true;
+        let only_north = |dx: i32, dy: i32| (dx, dy) == (0, 1);
+        assert_eq!(AutotileMode::Blob8.mask(all), 0xff);
+        assert_eq!(AutotileMode::Edges4.mask(only_north), 1);
+        assert_eq!(AutotileMode::Corners4.mask(only_north), 0);
+        let no_diagonals = |dx: i32, dy: i32| dx == 0 || dy == 0;
+        assert_eq!(AutotileMode::Blob8.mask(no_diagonals), N | E | S | W);
+    }
+
+    #[test]
+    fn rules_parse_layouts_tiles_and_reject_bad_masks() {
+        let rules = AutotileRules::parse(
+            Path::new("terrain.autotile.json"),
+            r#"{"terrains": [
+                {"name": "wall", "mode": "blob8", "layout": {"start": [0, 1], "columns": 8},
+                 "connects_to": ["water"]},
+                {"name": "water", "mode": "edges4", "collides": false, "fallback": [7, 7],
+                 "tiles": {"0": [[0, 0]], "15": [[1, 0], [2, 0]]}}
+            ]}"#,
+        )
+        .unwrap();
+        let wall = &rules.terrains[rules.terrain("wall").unwrap()];
+        assert_eq!(wall.cell_for(0, 0), Some([0, 1]));
+        // 0xff is the last of the 47 masks: index 46 is row 5, column 6.
+        assert_eq!(wall.cell_for(0xff, 0), Some([6, 6]));
+        let water = &rules.terrains[1];
+        assert!(!water.collides);
+        assert_eq!(water.cell_for(15, 1), Some([2, 0]));
+        assert_eq!(water.cell_for(5, 0), Some([7, 7]));
+
+        let bad_mask = AutotileRules::parse(
+            Path::new("bad.json"),
+            r#"{"terrains": [{"name": "a", "mode": "blob8", "tiles": {"2": [[0, 0]]}}]}"#,
+        )
+        .unwrap_err();
+        assert!(
+            bad_mask.to_string().contains("not a Blob8 mask"),
+            "{bad_mask}"
+        );
+        let unknown = AutotileRules::parse(
+            Path::new("bad.json"),
+            r#"{"terrains": [{"name": "a", "mode": "edges4", "fallback": [0, 0],
+                "connects_to": ["b"]}]}"#,
+        )
+        .unwrap_err();
+        assert!(
+            unknown.to_string().contains("unknown terrain 'b'"),
+            "{unknown}"
+        );
+    }
+
+    #[test]
+    fn setting_terrain_updates_the_cell_and_its_neighbours() {
+        let rules = AutotileRules::new().with_terrain(
+            TerrainRule::new("road", AutotileMode::Edges4).with_mask_order([0, 0], 16),
+        );
+        let mut map = TileMap::new(5, 3, 16.0);
+        let mut tiler = Autotiler::new(Arc::new(rules), &sheet(), 5, 3, |def| map.add_tile(def))
+            .with_edges_connect(false);
+
+        assert!(tiler.set_terrain(&mut map, 1, 1, Some("road")));
+        assert_eq!(shown(&map, 1, 1), Some([0, 0]));
+        assert!(tiler.set_terrain(&mut map, 2, 1, Some("road")));
+        // West end now has an east neighbour (mask 2), east end a west one (8).
+        assert_eq!(shown(&map, 1, 1), Some([2, 0]));
+        assert_eq!(shown(&map, 2, 1), Some([8, 0]));
+
+        assert!(tiler.set_terrain(&mut map, 2, 2, Some("road")));
+        assert_eq!(tiler.mask_at(2, 1), Some(1 | 8));
+        assert!(tiler.set_terrain(&mut map, 2, 1, None));
+        assert_eq!(map.get(2, 1), None);
+        assert_eq!(shown(&map, 1, 1), Some([0, 0]));
+        assert_eq!(tiler.terrain_at(2, 2), Some("road"));
+
+        assert!(!tiler.set_terrain(&mut map, 0, 0, Some("lava")));
+        assert!(!tiler.set_terrain(&mut map, 9, 0, Some("road")));
+    }
+
+    #[test]
+    fn bulk_painting_connects_terrains_and_map_edges() {
+        let rules = AutotileRules::new()
+            .with_terrain(
+                TerrainRule::new("wall", AutotileMode::Blob8)
+                    .with_mask_order([0, 0], 8)
+                    .with_connects_to("door"),
+            )
+            .with_terrain(
+                TerrainRule::new("door", AutotileMode::Edges4)
+                    .with_fallback([7, 7])
+                    .with_collision(false),
+            );
+        let mut map = TileMap::new(3, 3, 16.0);
+        let mut tiler = Autotiler::new(Arc::new(rules), &sheet(), 3, 3, |def| map.add_tile(def));
+        for row in 0..3 {
+            for col in 0..3 {
+                tiler.paint(col, row, Some("wall"));
+            }
+        }
+        tiler.paint(1, 1, Some("door"));
+        tiler.apply(&mut map);
+
+        // The wall treats the door and the map edge as wall.
+        assert_eq!(tiler.mask_at(0, 0), Some(0xff));
+        assert_eq!(shown(&map, 0, 0), Some([6, 5]));
+        // The door does not connect back to the wall.
+        assert_eq!(tiler.mask_at(1, 1), Some(0));
+        assert_eq!(shown(&map, 1, 1), Some([7, 7]));
+        assert!(map.solid_tile(1, 1).is_none());
+    }
+
+    #[test]
+    fn terrain_text_rebuilds_the_same_map() {
+        let rules = Arc::new(
+            AutotileRules::new()
+                .with_terrain(
+                    TerrainRule::new("rock", AutotileMode::Edges4).with_mask_order([0, 0], 16),
+                )
+                .with_terrain(
+                    TerrainRule::new("water", AutotileMode::Edges4).with_fallback([0, 1]),
+                ),
+        );
+        let mut edited = TileMap::new(3, 2, 16.0);
+        let mut tiler = Autotiler::new(rules.clone(), &sheet(), 3, 2, |def| edited.add_tile(def));
+        tiler.set_terrain(&mut edited, 0, 0, Some("rock"));
+        tiler.set_terrain(&mut edited, 1, 0, Some("rock"));
+        tiler.set_terrain(&mut edited, 1, 1, Some("water"));
+        let text = tiler.terrain_text();
+        assert_eq!(text, "rock,rock,;,water,");
+
+        let mut loaded = TileMap::new(3, 2, 16.0);
+        let mut rebuilt = Autotiler::new(rules, &sheet(), 3, 2, |def| loaded.add_tile(def));
+        assert_eq!(rebuilt.paint_text(&text), 3);
+        rebuilt.apply(&mut loaded);
+        for row in 0..2 {
+            for col in 0..3 {
+                assert_eq!(shown(&loaded, col, row), shown(&edited, col, row));
+            }
+        }
+        // Too-big grids and unknown terrains are skipped rather than fatal.
+        assert_eq!(rebuilt.paint_text("lava,rock,rock,rock;;rock"), 2);
+    }
+}
@@ -0,0 +1,1332 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::assets::Color;
+use crate::canvas::Canvas;
+use crate::world::joints::{
+    correct_joint_positions, prepare_joints, solve_joints, store_impulses, Joint2D, JointAnchor2D,
+    JointId,
+};
+use crate::world::physics::{CollisionLayer, Solid2D};
+use crate::world::shape::{shape_contact, ConvexPolygon2D, Shape2D, ShapeContact};
+
+pub type RigidBodyId = usize;
+
+/// Fraction of the remaining penetration the solver removes per step.
+const BAUMGARTE: f32 = 0.2;
+/// Penetration left alone so resting contacts stay touching between steps.
+const PENETRATION_SLOP: f32 = 0.5;
+/// Closing speeds (px/s) below this never bounce, so resting bodies settle
+/// instead of hopping on the few steps of gravity they gain between contacts.
+const RESTITUTION_THRESHOLD: f32 = 60.0;
+/// Contact points closer than this to last step's reuse its impulses.
+const WARM_START_DISTANCE: f32 = 2.0;
+const SLEEP_LINEAR_SPEED: f32 = 4.0;
+const SLEEP_ANGULAR_SPEED: f32 = 0.05;
+const TIME_TO_SLEEP: f32 = 0.5;
+/// Passes pulling stretched joints back together after positions integrate.
+const JOINT_POSITION_ITERATIONS: usize = 3;
+
+/// A simulated body driven by forces, impulses and contacts.
+///
+/// `shape` is local to the body: its centre of mass sits at the origin and it
+/// is placed in the world by `position` and `rotation` (radians,
+/// counter-clockwise). Bodies with zero inverse mass are fixed: they collide
+/// but never move.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RigidBody2D {
+    pub shape: Shape2D,
+    pub position: Vec2,
+    pub rotation: f32,
+    pub velocity: Vec2,
+    pub angular_velocity: f32,
+    /// Bounciness, `0.0` (none) to `1.0` (elastic). Pairs use the larger value.
+    pub restitution: f32,
+    /// Coulomb friction coefficient. Pairs use the geometric mean.
+    pub friction: f32,
+    pub gravity_scale: f32,
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    pub layer: CollisionLayer,
+    force: Vec2,
+    torque: f32,
+    inv_mass: f32,
+    inv_inertia: f32,
+    sleeping: bool,
+    sleep_time: f32,
+}
+
+impl RigidBody2D {
+    /// A moving body whose mass and rotational inertia come from the area of
+    /// `shape` (given in world space) times `density`.
+    pub fn dynamic(shape: Shape2D, density: f32) -> Self {
+        let (area, centroid, inertia) = mass_properties(&shape);
+        let mass = area * density;
+        Self {
+            inv_mass: inverse(mass),
+            inv_inertia: inverse(inertia * density),
+            ..Self::placed(shape, centroid)
+        }
+    }
+
+    /// An immovable body, for level geometry that should live in the
+    /// [`PhysicsWorld2D`] rather than in a `&[Solid2D]` slice.
+    pub fn fixed(shape: Shape2D) -> Self {
+        Self::placed(shape, shape.center())
+    }
+
+    fn placed(shape: Shape2D, origin: Vec2) -> Self {
+        Self {
+            shape: shape.translated(-origin),
+            position: origin,
+            rotation: 0.0,
+            velocity: Vec2::ZERO,
+            angular_velocity: 0.0,
+            restitution: 0.0,
+            friction: 0.5,
+            gravity_scale: 1.0,
+            linear_damping: 0.0,
+            angular_damping: 0.0,
+            layer: CollisionLayer::default(),
+            force: Vec2::ZERO,
+            torque: 0.0,
+            inv_mass: 0.0,
+            inv_inertia: 0.0,
+            sleeping: false,
+            sleep_time: 0.0,
+        }
+    }
+
+    pub fn with_velocity(mut self, velocity: Vec2) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    pub fn with_angular_velocity(mut self, angular_velocity: f32) -> Self {
+        self.angular_velocity = angular_velocity;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.restitution = restitution;
+        self
+    }
+
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    pub fn with_gravity_scale(mut self, gravity_scale: f32) -> Self {
+        self.gravity_scale = gravity_scale;
+        self
+    }
+
+    /// Velocity lost per second, as a fraction: `v *= 1 / (1 + dt * damping)`.
+    pub fn with_damping(mut self, linear: f32, angular: f32) -> Self {
+        self.linear_damping = linear;
+        self.angular_damping = angular;
+        self
+    }
+
+    pub fn with_layer(mut self, layer: CollisionLayer) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    /// Override the mass, scaling the rotational inertia to match. Ignored on
+    /// fixed bodies.
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        if self.inv_mass > 0.0 && mass > 0.0 {
+            self.inv_inertia *= self.inv_mass * mass;
+            self.inv_mass = 1.0 / mass;
+        }
+        self
+    }
+
+    /// Never rotate, like a character capsule that should stay upright.
+    pub fn with_fixed_rotation(mut self) -> Self {
+        self.inv_inertia = 0.0;
+        self.angular_velocity = 0.0;
+        self
+    }
+
+    /// `0.0` for fixed bodies.
+    pub fn mass(&self) -> f32 {
+        inverse(self.inv_mass)
+    }
+
+    pub fn inertia(&self) -> f32 {
+        inverse(self.inv_inertia)
+    }
+
+    pub fn is_dynamic(&self) -> bool {
+        self.inv_mass > 0.0
+    }
+
+    pub fn is_sleeping(&self) -> bool {
+        self.sleeping
+    }
+
+    /// Put a sleeping body back into the simulation. Needed after editing a
+    /// sleeping body's velocity or position directly; the `apply_*` methods
+    /// wake it themselves.
+    pub fn wake(&mut self) {
+        self.sleeping = false;
+        self.sleep_time = 0.0;
+    }
+
+    /// The body's collider in world space.
+    pub fn collider(&self) -> Shape2D {
+        let pivot = self.shape.center();
+        let offset = Vec2::from_angle(self.rotation).rotate(pivot) - pivot;
+        self.shape
+            .rotated(self.rotation)
+            .translated(self.position + offset)
+    }
+
+    /// Velocity of the world-space `point` as if it were attached to the body.
+    pub fn velocity_at(&self, point: Vec2) -> Vec2 {
+        self.velocity + (point - self.position).perp() * self.angular_velocity
+    }
+
+    /// Accumulate a force (mass·px/s²) through the centre of mass, applied
+    /// and cleared on the next step.
+    pub fn apply_force(&mut self, force: Vec2) {
+        self.force += force;
+        self.wake();
+    }
+
+    pub fn apply_torque(&mut self, torque: f32) {
+        self.torque += torque;
+        self.wake();
+    }
+
+    /// Instantly change momentum by `impulse` applied at the world-space
+    /// `point`; off-centre impulses also spin the body.
+    pub fn apply_impulse(&mut self, impulse: Vec2, point: Vec2) {
+        self.velocity += impulse * self.inv_mass;
+        self.angular_velocity += (point - self.position).perp_dot(impulse) * self.inv_inertia;
+        self.wake();
+    }
+
+    pub fn apply_angular_impulse(&mut self, impulse: f32) {
+        self.angular_velocity += impulse * self.inv_inertia;
+        self.wake();
+    }
+
+    fn is_active(&self) -> bool {
+        self.is_dynamic() && !self.sleeping
+    }
+}
+
+/// What a body in a contact is touching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ContactTarget2D {
+    Body(RigidBodyId),
+    /// Index into the solids slice passed to [`PhysicsWorld2D::step`].
+    Solid(usize),
+}
+
+/// One solved contact point from the last [`PhysicsWorld2D::step`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RigidContact2D {
+    pub body: RigidBodyId,
+    pub other: ContactTarget2D,
+    pub point: Vec2,
+    /// Points from `body` into `other`.
+    pub normal: Vec2,
+    pub depth: f32,
+    /// Normal impulse the solver applied, handy for impact sounds.
+    pub impulse: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+struct ContactPoint {
+    position: Vec2,
+    depth: f32,
+    normal_impulse: f32,
+    tangent_impulse: f32,
+    normal_mass: f32,
+    tangent_mass: f32,
+    bias: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct Manifold {
+    body: RigidBodyId,
+    other: ContactTarget2D,
+    normal: Vec2,
+    points: [ContactPoint; 2],
+    count: usize,
+    friction: f32,
+    restitution: f32,
+}
+
+impl Manifold {
+    fn key(&self) -> (RigidBodyId, ContactTarget2D) {
+        (self.body, self.other)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SolverBody {
+    pub(crate) position: Vec2,
+    pub(crate) rotation: f32,
+    pub(crate) velocity: Vec2,
+    pub(crate) angular_velocity: f32,
+    pub(crate) inv_mass: f32,
+    pub(crate) inv_inertia: f32,
+}
+
+impl SolverBody {
+    pub(crate) fn apply(&mut self, impulse: Vec2, arm: Vec2) {
+        self.velocity += impulse * self.inv_mass;
+        self.angular_velocity += arm.perp_dot(impulse) * self.inv_inertia;
+    }
+}
+
+/// A set of [`RigidBody2D`]s stepped together by a sequential-impulse solver.
+///
+/// Call [`PhysicsWorld2D::step`] from `Game::fixed_update` with the fixed
+/// timestep; the solver is tuned for a constant `dt`. Bodies collide with each
+/// other and with the same `&[Solid2D]` level geometry the kinematic movers
+/// use, filtered by [`CollisionLayer`]. Moving solids (non-zero
+/// [`Solid2D::velocity`]) drag and shove bodies through friction and contact.
+///
+/// [`Joint2D`]s tie bodies to each other or to points in the world and are
+/// solved in the same passes as the contacts; bodies a joint connects do not
+/// collide unless it sets `collide_connected`.
+///
+/// Every phase runs over bodies in id order and contacts in `(body, other)`
+/// order, with no hashed containers, so identical inputs give bit-identical
+/// results on one build. It runs in `f32`, though, so peers on different
+/// CPUs or compilers are not guaranteed to agree. The whole world (including cached contact and joint impulses) is
+/// `Clone` and serde-serializable for rollback snapshots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhysicsWorld2D {
+    /// Acceleration applied to every body, scaled by its `gravity_scale`.
+    pub gravity: Vec2,
+    /// Solver passes per step: more settle stacks better at a higher cost.
+    pub velocity_iterations: usize,
+    bodies: Vec<Option<RigidBody2D>>,
+    free: Vec<RigidBodyId>,
+    manifolds: Vec<Manifold>,
+    joints: Vec<Option<Joint2D>>,
+    free_joints: Vec<JointId>,
+}
+
+impl Default for PhysicsWorld2D {
+    fn default() -> Self {
+        Self::new(Vec2::new(0.0, -980.0))
+    }
+}
+
+impl PhysicsWorld2D {
+    pub fn new(gravity: Vec2) -> Self {
+        Self {
+            gravity,
+            velocity_iterations: 8,
+            bodies: Vec::new(),
+            free: Vec::new(),
+            manifolds: Vec::new(),
+            joints: Vec::new(),
+            free_joints: Vec::new(),
+        }
+    }
+
+    pub fn with_velocity_iterations(mut self, iterations: usize) -> Self {
+        self.velocity_iterations = iterations;
+        self
+    }
+
+    pub fn insert(&mut self, body: RigidBody2D) -> RigidBodyId {
+        match self.free.pop() {
+            Some(id) => {
+                self.bodies[id] = Some(body);
+                id
+            }
+            None => {
+                self.bodies.push(Some(body));
+                self.bodies.len() - 1
+            }
+        }
+    }
+
+    /// Remove a body and the joints attached to it, waking whatever was
+    /// touching or jointed to it so stacks it supported fall.
+    pub fn remove(&mut self, id: RigidBodyId) -> Option<RigidBody2D> {
+        let body = self.bodies.get_mut(id)?.take()?;
+        let mut touching = Vec::new();
+        for joint_id in 0..self.joints.len() {
+            let Some(joint) = self.joints[joint_id] else {
+                continue;
+            };
+            let (a, b) = joint.bodies();
+            if a == Some(id) || b == Some(id) {
+                touching.extend(a.into_iter().chain(b));
+                self.joints[joint_id] = None;
+                self.free_joints.push(joint_id);
+            }
+        }
+        self.manifolds.retain(|m| {
+            let involved = m.body == id || m.other == ContactTarget2D::Body(id);
+            if involved {
+                touching.push(m.body);
+                if let ContactTarget2D::Body(other) = m.other {
+                    touching.push(other);
+                }
+            }
+            !involved
+        });
+        for other in touching {
+            if let Some(Some(other)) = self.bodies.get_mut(other) {
+                other.wake();
+            }
+        }
+        self.free.push(id);
+        Some(body)
+    }
+
+    pub fn get(&self, id: RigidBodyId) -> Option<&RigidBody2D> {
+        self.bodies.get(id)?.as_ref()
+    }
+
+    pub fn get_mut(&mut self, id: RigidBodyId) -> Option<&mut RigidBody2D> {
+        self.bodies.get_mut(id)?.as_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bodies.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.bodies.clear();
+        self.free.clear();
+        self.manifolds.clear();
+        self.joints.clear();
+        self.free_joints.clear();
+    }
+
+    pub fn insert_joint(&mut self, joint: Joint2D) -> JointId {
+        self.wake_jointed(&joint);
+        match self.free_joints.pop() {
+            Some(id) => {
+                self.joints[id] = Some(joint);
+                id
+            }
+            None => {
+                self.joints.push(Some(joint));
+                self.joints.len() - 1
+            }
+        }
+    }
+
+    /// Remove a joint, waking the bodies it held so they fall free.
+    pub fn remove_joint(&mut self, id: JointId) -> Option<Joint2D> {
+        let joint = self.joints.get_mut(id)?.take()?;
+        self.wake_jointed(&joint);
+        self.free_joints.push(id);
+        Some(joint)
+    }
+
+    pub fn joint(&self, id: JointId) -> Option<&Joint2D> {
+        self.joints.get(id)?.as_ref()
+    }
+
+    /// Mutable access to a joint; wakes its bodies, since moving an anchor or
+    /// changing a length should take effect even on a sleeping body.
+    pub fn joint_mut(&mut self, id: JointId) -> Option<&mut Joint2D> {
+        let joint = (*self.joints.get(id)?)?;
+        self.wake_jointed(&joint);
+        self.joints[id].as_mut()
+    }
+
+    /// Every joint in ascending id order.
+    pub fn joints(&self) -> impl Iterator<Item = (JointId, &Joint2D)> + '_ {
+        self.joints
+            .iter()
+            .enumerate()
+            .filter_map(|(id, joint)| joint.as_ref().map(|joint| (id, joint)))
+    }
+
+    /// An anchor on body `id` at the world-space `point`, for building joints
+    /// from where things are in the level.
+    pub fn body_anchor(&self, id: RigidBodyId, point: Vec2) -> Option<JointAnchor2D> {
+        let body = self.get(id)?;
+        Some(JointAnchor2D::Body {
+            body: id,
+            local: Vec2::from_angle(-body.rotation).rotate(point - body.position),
+        })
+    }
+
+    /// Where `anchor` currently is in the world.
+    pub fn anchor_position(&self, anchor: &JointAnchor2D) -> Option<Vec2> {
+        match anchor.body() {
+            Some(id) => {
+                let body = self.get(id)?;
+                Some(anchor.placed(body.position, body.rotation))
+            }
+            None => Some(anchor.placed(Vec2::ZERO, 0.0)),
+        }
+    }
+
+    fn wake_jointed(&mut self, joint: &Joint2D) {
+        let (a, b) = joint.bodies();
+        for id in a.into_iter().chain(b) {
+            if let Some(Some(body)) = self.bodies.get_mut(id) {
+                body.wake();
+            }
+        }
+    }
+
+    /// Body pairs a joint connects without `collide_connected`, sorted.
+    fn jointed_pairs(&self) -> Vec<(RigidBodyId, RigidBodyId)> {
+        let mut pairs: Vec<_> = self
+            .joints()
+            .filter(|(_, joint)| !joint.collide_connected)
+            .filter_map(|(_, joint)| match joint.bodies() {
+                (Some(a), Some(b)) => Some((a.min(b), a.max(b))),
+                _ => None,
+            })
+            .collect();
+        pairs.sort_unstable();
+        pairs
+    }
+
+    /// Outline every body's collider, mark contact points with their normals
+    /// and draw each joint between its anchors, in world coordinates — for
+    /// tuning with the canvas mapped onto the world. Sleeping bodies are
+    /// drawn dimmed.
+    pub fn debug_draw(&self, canvas: &mut Canvas) {
+        let awake = Color::from_rgba8(120, 220, 140, 255);
+        let asleep = Color::from_rgba8(70, 110, 90, 255);
+        let fixed = Color::from_rgba8(150, 150, 160, 255);
+        for (_, body) in self.bodies() {
+            let color = if !body.is_dynamic() {
+                fixed
+            } else if body.is_sleeping() {
+                asleep
+            } else {
+                awake
+            };
+            match body.collider() {
+                Shape2D::Circle { center, radius } => {
+                    canvas.circle(center.x, center.y, radius, 1.0, 20, color);
+                    let spoke = center + Vec2::from_angle(body.rotation) * radius;
+                    canvas.line(center.x, center.y, spoke.x, spoke.y, 1.0, color);
+                }
+                Shape2D::Capsule { a, b, radius } => {
+                    let side = (b - a).normalize_or_zero().perp() * radius;
+                    for offset in [side, -side] {
+                        let (p, q) = (a + offset, b + offset);
+                        canvas.line(p.x, p.y, q.x, q.y, 1.0, color);
+                    }
+                    canvas.circle(a.x, a.y, radius, 1.0, 16, color);
+                    canvas.circle(b.x, b.y, radius, 1.0, 16, color);
+                }
+                shape => {
+                    let points = match shape {
+                        Shape2D::Aabb(rect) => ConvexPolygon2D::from_rect(&rect).points().to_vec(),
+                        Shape2D::Polygon(ref polygon) => polygon.points().to_vec(),
+                        _ => unreachable!(),
+                    };
+                    let mut outline: Vec<(f32, f32)> = points.iter().map(|p| (p.x, p.y)).collect();
+                    outline.push(outline[0]);
+                    canvas.polyline(&outline, 1.0, color);
+                }
+            }
+        }
+
+        let contact = Color::from_rgba8(255, 90, 70, 255);
+        for point in self.contacts() {
+            let p = point.point;
+            let tip = p + point.normal * 8.0;
+            canvas.rect(p.x - 1.5, p.y - 1.5, 3.0, 3.0, contact);
+            canvas.line(p.x, p.y, tip.x, tip.y, 1.0, contact);
+        }
+
+        let joint_color = Color::from_rgba8(240, 200, 90, 255);
+        for (_, joint) in self.joints() {
+            let (Some(a), Some(b)) = (
+                self.anchor_position(&joint.a),
+                self.anchor_position(&joint.b),
+            ) else {
+                continue;
+            };
+            canvas.line(a.x, a.y, b.x, b.y, 1.0, joint_color);
+            canvas.circle_filled(a.x, a.y, 2.5, 8, joint_color);
+            canvas.circle_filled(b.x, b.y, 2.5, 8, joint_color);
+        }
+    }
+
+    /// Every body in ascending id order.
+    pub fn bodies(&self) -> impl Iterator<Item = (RigidBodyId, &RigidBody2D)> + '_ {
+        self.bodies
+            .iter()
+            .enumerate()
+            .filter_map(|(id, body)| body.as_ref().map(|body| (id, body)))
+    }
+
+    /// The lowest-id body whose collider contains `point`.
+    pub fn body_at(&self, point: Vec2) -> Option<RigidBodyId> {
+        self.bodies()
+            .find(|(_, body)| body.collider().contains_point(point))
+            .map(|(id, _)| id)
+    }
+
+    /// Contact points found and solved during the last step.
+    pub fn contacts(&self) -> impl Iterator<Item = RigidContact2D> + '_ {
+        self.manifolds.iter().flat_map(|m| {
+            m.points[..m.count].iter().map(move |point| RigidContact2D {
+                body: m.body,
+                other: m.other,
+                point: point.position,
+                normal: m.normal,
+                depth: point.depth,
+                impulse: point.normal_impulse,
+            })
+        })
+    }
+
+    /// Advance the simulation by `dt` seconds against the static or moving
+    /// `solids`.
+    pub fn step(&mut self, dt: f32, solids: &[Solid2D]) {
+        if dt <= 0.0 {
+            return;
+        }
+
+        for body in self.bodies.iter_mut().flatten() {
+            if !body.is_active() {
+                continue;
+            }
+            body.velocity += (self.gravity * body.gravity_scale + body.force * body.inv_mass) * dt;
+            body.angular_velocity += body.torque * body.inv_inertia * dt;
+            body.velocity *= 1.0 / (1.0 + dt * body.linear_damping);
+            body.angular_velocity *= 1.0 / (1.0 + dt * body.angular_damping);
+            body.force = Vec2::ZERO;
+            body.torque = 0.0;
+        }
+
+        let mut manifolds = self.collide(dt, solids);
+        let islands = self.islands(&manifolds);
+        self.wake_touched(&manifolds, &islands, solids);
+        self.warm_start_from_previous(&mut manifolds);
+
+        let mut solver: Vec<SolverBody> = self
+            .bodies
+            .iter()
+            .map(|body| match body {
+                Some(body) if body.is_active() => SolverBody {
+                    position: body.position,
+                    rotation: body.rotation,
+                    velocity: body.velocity,
+                    angular_velocity: body.angular_velocity,
+                    inv_mass: body.inv_mass,
+                    inv_inertia: body.inv_inertia,
+                },
+                Some(body) => SolverBody {
+                    position: body.position,
+                    rotation: body.rotation,
+                    ..SolverBody::default()
+                },
+                None => SolverBody::default(),
+            })
+            .collect();
+
+        let mut joint_rows = prepare_joints(&self.joints, &mut solver, dt);
+        for manifold in &mut manifolds {
+            prepare(manifold, &mut solver, solids, dt);
+        }
+        for _ in 0..self.velocity_iterations {
+            solve_joints(&mut joint_rows, &mut solver);
+            for manifold in &mut manifolds {
+                solve(manifold, &mut solver, solids);
+            }
+        }
+        store_impulses(&joint_rows, &mut self.joints);
+
+        for state in &mut solver {
+            state.position += state.velocity * dt;
+            state.rotation += state.angular_velocity * dt;
+        }
+        for _ in 0..JOINT_POSITION_ITERATIONS {
+            correct_joint_positions(&self.joints, &mut solver);
+        }
+
+        for (slot, state) in self.bodies.iter_mut().zip(&solver) {
+            let Some(body) = slot else {
+                continue;
+            };
+            if !body.is_active() {
+                continue;
+            }
+            body.velocity = state.velocity;
+            body.angular_velocity = state.angular_velocity;
+            body.position = state.position;
+            body.rotation = state.rotation;
+        }
+
+        self.update_sleep(dt, &islands);
+        self.manifolds = manifolds;
+    }
+
+    /// Narrowphase for every pair involving a dynamic body. Sleeping bodies
+    /// keep their contacts so islands stay connected and wake as a whole;
+    /// they just are not moved by the solver.
+    fn collide(&self, dt: f32, solids: &[Solid2D]) -> Vec<Manifold> {
+        let colliders: Vec<Option<Shape2D>> = self
+            .bodies
+            .iter()
+            .map(|body| body.as_ref().map(RigidBody2D::collider))
+            .collect();
+        let bounds: Vec<_> = colliders
+            .iter()
+            .map(|shape| shape.as_ref().map(Shape2D::bounds))
+            .collect();
+        let dynamic = |id: RigidBodyId| self.bodies[id].is_some_and(|b| b.is_dynamic());
+        let jointed = self.jointed_pairs();
+        let mut manifolds = Vec::new();
+
+        // Sort-and-sweep along x, then sort the pairs so contact order never
+        // depends on where bodies happen to be.
+        let mut order: Vec<RigidBodyId> = (0..self.bodies.len())
+            .filter(|&id| bounds[id].is_some())
+            .collect();
+        let left = |id: RigidBodyId| bounds[id].map_or(0.0, |r| r.left());
+        order.sort_by(|&a, &b| left(a).total_cmp(&left(b)).then(a.cmp(&b)));
+        let mut pairs = Vec::new();
+        for (k, &i) in order.iter().enumerate() {
+            let (Some(bi), Some(a)) = (bounds[i], self.bodies[i].as_ref()) else {
+                continue;
+            };
+            for &j in &order[k + 1..] {
+                let (Some(bj), Some(b)) = (bounds[j], self.bodies[j].as_ref()) else {
+                    continue;
+                };
+                if bj.left() >= bi.right() {
+                    break;
+                }
+                if (dynamic(i) || dynamic(j))
+                    && bi.overlaps(&bj)
+                    && a.layer.interacts_with(&b.layer)
+                {
+                    pairs.push((i.min(j), i.max(j)));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs.retain(|pair| jointed.binary_search(pair).is_err());
+
+        for (a, b) in pairs {
+            let (Some(shape_a), Some(shape_b)) = (colliders[a], colliders[b]) else {
+                continue;
+            };
+            let (Some(body_a), Some(body_b)) = (self.bodies[a], self.bodies[b]) else {
+                continue;
+            };
+            if let Some(contact) = shape_contact(&shape_a, &shape_b) {
+                manifolds.push(manifold(
+                    a,
+                    ContactTarget2D::Body(b),
+                    contact,
+                    (body_a.friction * body_b.friction).sqrt(),
+                    body_a.restitution.max(body_b.restitution),
+                ));
+            }
+        }
+
+        for (id, slot) in self.bodies.iter().enumerate() {
+            let (Some(body), Some(shape), Some(area)) = (slot, colliders[id], bounds[id]) else {
+                continue;
+            };
+            if !body.is_dynamic() {
+                continue;
+            }
+            for (index, solid) in solids.iter().enumerate() {
+                if !solid.rect.overlaps(&area) || !body.layer.interacts_with(&solid.layer) {
+                    continue;
+                }
+                let Some(contact) = shape_contact(&shape, &solid.collider()) else {
+                    continue;
+                };
+                if solid.one_way {
+                    // Only catch bodies landing from above, and only by as
+                    // much as this step's fall explains.
+                    let fall = (solid.velocity.y - body.velocity.y).max(0.0) * dt;
+                    let deepest = contact
+                        .points()
+                        .iter()
+                        .fold(0.0f32, |deepest, &(_, depth)| deepest.max(depth));
+                    if contact.normal.y > -0.7 || deepest > fall + 2.0 * PENETRATION_SLOP {
+                        continue;
+                    }
+                }
+                manifolds.push(manifold(
+                    id,
+                    ContactTarget2D::Solid(index),
+                    contact,
+                    body.friction,
+                    body.restitution,
+                ));
+            }
+        }
+
+        manifolds.sort_by_key(Manifold::key);
+        manifolds
+    }
+
+    /// Union-find over body-body contacts and joints between dynamic bodies;
+    /// returns each body's island root. Fixed bodies, solids and world
+    /// anchors never join islands.
+    fn islands(&self, manifolds: &[Manifold]) -> Vec<RigidBodyId> {
+        let mut parent: Vec<RigidBodyId> = (0..self.bodies.len()).collect();
+        fn find(parent: &mut [RigidBodyId], mut id: RigidBodyId) -> RigidBodyId {
+            while parent[id] != id {
+                parent[id] = parent[parent[id]];
+                id = parent[id];
+            }
+            id
+        }
+        let dynamic = |id: RigidBodyId| self.bodies[id].is_some_and(|b| b.is_dynamic());
+        let contact_pairs = manifolds.iter().filter_map(|m| match m.other {
+            ContactTarget2D::Body(other) => Some((m.body, other)),
+            ContactTarget2D::Solid(_) => None,
+        });
+        let joint_pairs = self.joints().filter_map(|(_, joint)| match joint.bodies() {
+            (Some(a), Some(b)) => Some((a, b)),
+            _ => None,
+        });
+        for (a, b) in contact_pairs.chain(joint_pairs) {
+            if a < parent.len() && b < parent.len() && dynamic(a) && dynamic(b) {
+                let a = find(&mut parent, a);
+                let b = find(&mut parent, b);
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+        (0..parent.len()).map(|id| find(&mut parent, id)).collect()
+    }
+
+    /// Wake every sleeping body sharing an island with an awake one, or
+    /// touched by a moving solid.
+    fn wake_touched(
+        &mut self,
+        manifolds: &[Manifold],
+        islands: &[RigidBodyId],
+        solids: &[Solid2D],
+    ) {
+        let mut awake = vec![false; self.bodies.len()];
+        for (id, body) in self.bodies.iter().enumerate() {
+            if body.is_some_and(|b| b.is_active()) {
+                awake[islands[id]] = true;
+            }
+        }
+        for m in manifolds {
+            if let ContactTarget2D::Solid(index) = m.other {
+                if solids[index].velocity != Vec2::ZERO {
+                    awake[islands[m.body]] = true;
+                }
+            }
+        }
+        for (id, slot) in self.bodies.iter_mut().enumerate() {
+            if let Some(body) = slot {
+                if body.sleeping && awake[islands[id]] {
+                    body.wake();
+                }
+            }
+        }
+    }
+
+    fn warm_start_from_previous(&self, manifolds: &mut [Manifold]) {
+        for manifold in manifolds.iter_mut() {
+            let Ok(index) = self
+                .manifolds
+                .binary_search_by_key(&manifold.key(), Manifold::key)
+            else {
+                continue;
+            };
+            let previous = &self.manifolds[index];
+            for point in &mut manifold.points[..manifold.count] {
+                let matched = previous.points[..previous.count].iter().find(|old| {
+                    old.position.distance_squared(point.position)
+                        < WARM_START_DISTANCE * WARM_START_DISTANCE
+                });
+                if let Some(old) = matched {
+                    point.normal_impulse = old.normal_impulse;
+                    point.tangent_impulse = old.tangent_impulse;
+                }
+            }
+        }
+    }
+
+    /// Bodies that stayed slow for [`TIME_TO_SLEEP`] stop simulating, one
+    /// whole island at a time so stacks never sleep half-way.
+    fn update_sleep(&mut self, dt: f32, islands: &[RigidBodyId]) {
+        let mut island_time = vec![f32::INFINITY; self.bodies.len()];
+        for (id, slot) in self.bodies.iter_mut().enumerate() {
+            let Some(body) = slot else {
+                continue;
+            };
+            if !body.is_active() {
+                continue;
+            }
+            let resting = body.velocity.length_squared() <= SLEEP_LINEAR_SPEED * SLEEP_LINEAR_SPEED
+                && body.angular_velocity.abs() <= SLEEP_ANGULAR_SPEED;
+            body.sleep_time = if resting { body.sleep_time + dt } else { 0.0 };
+            let root = islands[id];
+            island_time[root] = island_time[root].min(body.sleep_time);
+        }
+        for (id, slot) in self.bodies.iter_mut().enumerate() {
+            let Some(body) = slot else {
+                continue;
+            };
+            if body.is_active() && island_time[islands[id]] >= TIME_TO_SLEEP {
+                body.sleeping = true;
+                body.velocity = Vec2::ZERO;
+                body.angular_velocity = 0.0;
+            }
+        }
+    }
+}
+
+fn manifold(
+    body: RigidBodyId,
+    other: ContactTarget2D,
+    contact: ShapeContact,
+    friction: f32,
+    restitution: f32,
+) -> Manifold {
+    let mut points = [ContactPoint::default(); 2];
+    for (slot, &(position, depth)) in points.iter_mut().zip(contact.points()) {
+        slot.position = position;
+        slot.depth = depth;
+    }
+    Manifold {
+        body,
+        other,
+        normal: contact.normal,
+        points,
+        count: contact.count,
+        friction,
+        restitution,
+    }
+}
+
+/// Run `f` on the two solver bodies of a manifold. Solids get a throwaway
+/// immovable body carrying their velocity.
+fn with_pair<R>(
+    a: RigidBodyId,
+    other: ContactTarget2D,
+    solver: &mut [SolverBody],
+    solids: &[Solid2D],
+    f: impl FnOnce(&mut SolverBody, &mut SolverBody) -> R,
+) -> R {
+    match other {
+        ContactTarget2D::Body(b) if a < b => {
+            let (low, high) = solver.split_at_mut(b);
+            f(&mut low[a], &mut high[0])
+        }
+        ContactTarget2D::Body(b) => {
+            let (low, high) = solver.split_at_mut(a);
+            f(&mut high[0], &mut low[b])
+        }
+        ContactTarget2D::Solid(index) => {
+            let mut solid = SolverBody {
+                velocity: solids[index].velocity,
+                ..SolverBody::default()
+            };
+            f(&mut solver[a], &mut solid)
+        }
+    }
+}
+
+fn relative_velocity(a: &SolverBody, b: &SolverBody, ra: Vec2, rb: Vec2) -> Vec2 {
+    (b.velocity + rb.perp() * b.angular_velocity) - (a.velocity + ra.perp() * a.angular_velocity)
+}
+
+fn prepare(manifold: &mut Manifold, solver: &mut [SolverBody], solids: &[Solid2D], dt: f32) {
+    let normal = manifold.normal;
+    let tangent = normal.perp();
+    let restitution = manifold.restitution;
+    let count = manifold.count;
+    let other = manifold.other;
+    let points = &mut manifold.points;
+    with_pair(manifold.body, other, solver, solids, |a, b| {
+        for point in &mut points[..count] {
+            let ra = point.position - a.position;
+            let rb = match other {
+                ContactTarget2D::Body(_) => point.position - b.position,
+                ContactTarget2D::Solid(_) => Vec2::ZERO,
+            };
+            let effective = |axis: Vec2| {
+                a.inv_mass
+                    + b.inv_mass
+                    + a.inv_inertia * ra.perp_dot(axis).powi(2)
+                    + b.inv_inertia * rb.perp_dot(axis).powi(2)
+            };
+            point.normal_mass = inverse(effective(normal));
+            point.tangent_mass = inverse(effective(tangent));
+
+            let closing = relative_velocity(a, b, ra, rb).dot(normal);
+            let bounce = if closing < -RESTITUTION_THRESHOLD {
+                -restitution * closing
+            } else {
+                0.0
+            };
+            let correction = BAUMGARTE / dt * (point.depth - PENETRATION_SLOP).max(0.0);
+            point.bias = bounce.max(correction);
+
+            let impulse = normal * point.normal_impulse + tangent * point.tangent_impulse;
+            a.apply(-impulse, ra);
+            b.apply(impulse, rb);
+        }
+    });
+}
+
+fn solve(manifold: &mut Manifold, solver: &mut [SolverBody], solids: &[Solid2D]) {
+    let normal = manifold.normal;
+    let tangent = normal.perp();
+    let friction = manifold.friction;
+    let count = manifold.count;
+    let other = manifold.other;
+    let points = &mut manifold.points;
+    with_pair(manifold.body, other, solver, solids, |a, b| {
+        for point in &mut points[..count] {
+            let ra = point.position - a.position;
+            let rb = match other {
+                ContactTarget2D::Body(_) => point.position - b.position,
+                ContactTarget2D::Solid(_) => Vec2::ZERO,
+            };
+
+            let closing = relative_velocity(a, b, ra, rb).dot(normal);
+            let delta = point.normal_mass * (point.bias - closing);
+            let total = (point.normal_impulse + delta).max(0.0);
+            let applied = normal * (total - point.normal_impulse);
+            point.normal_impulse = total;
+            a.apply(-applied, ra);
+            b.apply(applied, rb);
+
+            let slide = relative_velocity(a, b, ra, rb).dot(tangent);
+            let limit = friction * point.normal_impulse;
+            let total = (point.tangent_impulse - point.tangent_mass * slide).clamp(-limit, limit);
+            let applied = tangent * (total - point.tangent_impulse);
+            point.tangent_impulse = total;
+            a.apply(-applied, ra);
+            b.apply(applied, rb);
+        }
+    });
+}
+
+fn inverse(value: f32) -> f32 {
+    if value > 0.0 {
+        1.0 / value
+    } else {
+        0.0
+    }
+}
+
+/// Area, centroid and rotational inertia about the centroid, at unit density.
+fn mass_properties(shape: &Shape2D) -> (f32, Vec2, f32) {
+    match *shape {
+        Shape2D::Circle { center, radius } => {
+            let area = std::f32::consts::PI * radius * radius;
+            (area, center, area * radius * radius * 0.5)
+        }
+        Shape2D::Capsule { a, b, radius } => {
+            let length = a.distance(b);
+            let body = 2.0 * radius * length;
+            let caps = std::f32::consts::PI * radius * radius;
+            let inertia = body * (length * length + 4.0 * radius * radius) / 12.0
+                + caps * (radius * radius * 0.5 + length * length * 0.25);
+            (body + caps, (a + b) * 0.5, inertia)
+        }
+        Shape2D::Aabb(rect) => polygon_mass(ConvexPolygon2D::from_rect(&rect).points()),
+        Shape2D::Polygon(ref polygon) => polygon_mass(polygon.points()),
+    }
+}
+
+fn polygon_mass(points: &[Vec2]) -> (f32, Vec2, f32) {
+    let origin = points[0];
+    let mut area = 0.0;
+    let mut center = Vec2::ZERO;
+    let mut inertia = 0.0;
+    for window in points[1..].windows(2) {
+        let e1 = window[0] - origin;
+        let e2 = window[1] - origin;
+        let cross = e1.perp_dot(e2);
+        let triangle = cross * 0.5;
+        area += triangle;
+        center += (e1 + e2) * (triangle / 3.0);
+        let xx = e1.x * e1.x + e2.x * e1.x + e2.x * e2.x;
+        let yy = e1.y * e1.y + e2.y * e1.y + e2.y * e2.y;
+        inertia += cross / 12.0 * (xx + yy);
+    }
+    if area <= 0.0 {
+        return (0.0, origin, 0.0);
+    }
+    center /= area;
+    (
+        area,
+        origin + center,
+        inertia - area * center.length_squared(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::rect::Rect;
+
+    const STEP: f32 = 1.0 / 60.0;
+
+    fn floor() -> Vec<Solid2D> {
+        vec![Solid2D::solid(Rect::new(-500.0, -20.0, 1000.0, 20.0))]
+    }
+
+    fn crate_at(x: f32, y: f32) -> RigidBody2D {
+        RigidBody2D::dynamic(
+            Shape2D::oriented_box(Vec2::new(x, y), Vec2::splat(10.0), 0.0),
+            1.0,
+        )
+    }
+
+    fn run(world: &mut PhysicsWorld2D, solids: &[Solid2D], steps: usize) {
+        for _ in 0..steps {
+            world.step(STEP, solids);
+        }
+    }
+
+    #[test]
+    fn mass_comes_from_area_and_density() {
+        let body = RigidBody2D::dynamic(
+            Shape2D::oriented_box(Vec2::ZERO, Vec2::new(2.0, 1.0), 0.0),
+            3.0,
+        );
+        assert!((body.mass() - 24.0).abs() < 1e-4);
+        // Box inertia m (w² + h²) / 12 about its centre.
+        assert!((body.inertia() - 24.0 * (16.0 + 4.0) / 12.0).abs() < 1e-3);
+        assert_eq!(
+            RigidBody2D::fixed(Shape2D::circle(Vec2::ZERO, 5.0)).mass(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn a_crate_falls_and_comes_to_rest_on_the_floor() {
+        let mut world = PhysicsWorld2D::default();
+        let id = world.insert(crate_at(0.0, 100.0));
+        run(&mut world, &floor(), 180);
+        let body = world.get(id).unwrap();
+        assert!((body.position.y - 10.0).abs() < 1.0, "{}", body.position.y);
+        assert!(body.velocity.length() < 1.0);
+        assert!(body.rotation.abs() < 1e-3);
+        assert_eq!(world.contacts().filter(|c| c.body == id).count(), 2);
+    }
+
+    #[test]
+    fn restitution_makes_a_ball_bounce() {
+        let solids = floor();
+        let mut peaks = Vec::new();
+        for restitution in [0.0, 0.8] {
+            let mut world = PhysicsWorld2D::default();
+            let ball = world.insert(
+                RigidBody2D::dynamic(Shape2D::circle(Vec2::new(0.0, 200.0), 10.0), 1.0)
+                    .with_restitution(restitution),
+            );
+            let mut landed = false;
+            let mut peak = 0.0f32;
+            for _ in 0..120 {
+                world.step(STEP, &solids);
+                let body = world.get(ball).unwrap();
+                landed |= body.velocity.y > 0.0;
+                if landed {
+                    peak = peak.max(body.position.y);
+                }
+            }
+            peaks.push(peak);
+        }
+        assert!(peaks[0] < 15.0, "{peaks:?}");
+        assert!(peaks[1] > 100.0, "{peaks:?}");
+    }
+
+    #[test]
+    fn friction_stops_a_sliding_crate() {
+        let solids = floor();
+        let mut stops = Vec::new();
+        for friction in [0.0, 0.8] {
+            let mut world = PhysicsWorld2D::default();
+            let id = world.insert(
+                crate_at(0.0, 10.0)
+                    .with_friction(friction)
+                    .with_velocity(Vec2::new(200.0, 0.0)),
+            );
+            run(&mut world, &solids, 60);
+            stops.push(world.get(id).unwrap().velocity.x);
+        }
+        assert!(stops[0] > 190.0, "{stops:?}");
+        assert!(stops[1].abs() < 1.0, "{stops:?}");
+    }
+
+    #[test]
+    fn off_centre_impulse_spins_the_body() {
+        let mut world = PhysicsWorld2D::new(Vec2::ZERO);
+        let id = world.insert(crate_at(0.0, 0.0));
+        let body = world.get_mut(id).unwrap();
+        body.apply_impulse(Vec2::new(0.0, 400.0), Vec2::new(10.0, 0.0));
+        assert!(body.velocity.y > 0.0);
+        assert!(body.angular_velocity > 0.0);
+        run(&mut world, &[], 10);
+        assert!(world.get(id).unwrap().rotation > 0.0);
+    }
+
+    #[test]
+    fn a_stack_settles_falls_asleep_and_wakes_on_impulse() {
+        let solids = floor();
+        let mut world = PhysicsWorld2D::default();
+        let ids: Vec<_> = (0..3)
+            .map(|i| world.insert(crate_at(0.0, 10.0 + i as f32 * 20.5)))
+            .collect();
+        run(&mut world, &solids, 240);
+        for (i, &id) in ids.iter().enumerate() {
+            let body = world.get(id).unwrap();
+            assert!(body.is_sleeping(), "crate {i} still awake");
+            assert!((body.position.y - (10.0 + i as f32 * 20.0)).abs() < 1.5);
+            assert!(body.position.x.abs() < 0.5);
+        }
+
+        world
+            .get_mut(ids[0])
+            .unwrap()
+            .apply_impulse(Vec2::new(100.0, 0.0), Vec2::new(0.0, 10.0));
+        world.step(STEP, &solids);
+        assert!(ids.iter().all(|&id| !world.get(id).unwrap().is_sleeping()));
+    }
+
+    #[test]
+    fn layers_that_do_not_interact_pass_through() {
+        let solids = floor();
+        let mut world = PhysicsWorld2D::default();
+        let ghost = CollisionLayer::new(CollisionLayer::ENEMY, CollisionLayer::WORLD);
+        let bottom = world.insert(crate_at(0.0, 10.0).with_layer(ghost));
+        let top = world.insert(crate_at(0.0, 60.0).with_layer(ghost));
+        run(&mut world, &solids, 120);
+        let (bottom, top) = (world.get(bottom).unwrap(), world.get(top).unwrap());
+        assert!((bottom.position.y - 10.0).abs() < 1.0);
+        assert!((top.position.y - 10.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn one_way_solids_catch_falling_bodies_only() {
+        let solids = vec![Solid2D::one_way(Rect::new(-50.0, 0.0, 100.0, 4.0))];
+        let mut world = PhysicsWorld2D::default();
+        let lander = world.insert(crate_at(0.0, 60.0));
+        run(&mut world, &solids, 60);
+        assert!((world.get(lander).unwrap().position.y - 14.0).abs() < 1.0);
+
+        let mut world = PhysicsWorld2D::new(Vec2::ZERO);
+        let rocket = world.insert(crate_at(0.0, -40.0).with_velocity(Vec2::new(0.0, 300.0)));
+        run(&mut world, &solids, 30);
+        assert!(world.get(rocket).unwrap().position.y > 60.0);
+    }
+
+    #[test]
+    fn rollback_snapshots_replay_bit_for_bit() {
+        let solids = floor();
+        let mut world = PhysicsWorld2D::default();
+        for i in 0..6 {
+            world.insert(
+                RigidBody2D::dynamic(
+                    Shape2D::oriented_box(
+                        Vec2::new(i as f32 * 7.0, 30.0 + i as f32 * 25.0),
+                        Vec2::new(12.0, 8.0),
+                        i as f32 * 0.3,
+                    ),
+                    1.0,
+                )
+                .with_restitution(0.2),
+            );
+        }
+        run(&mut world, &solids, 30);
+
+        let first = world.body_anchor(0, Vec2::new(0.0, 38.0)).unwrap();
+        world.insert_joint(Joint2D::rope(
+            JointAnchor2D::World(Vec2::new(0.0, 200.0)),
+            first,
+            120.0,
+        ));
+        world.insert_joint(Joint2D::spring(
+            JointAnchor2D::Body {
+                body: 2,
+                local: Vec2::ZERO,
+            },
+            JointAnchor2D::Body {
+                body: 4,
+                local: Vec2::new(6.0, 0.0),
+            },
+            30.0,
+            3.0,
+            0.2,
+        ));
+        run(&mut world, &solids, 30);
+
+        let snapshot = serde_json::to_string(&world).unwrap();
+        run(&mut world, &solids, 90);
+        let mut replay: PhysicsWorld2D = serde_json::from_str(&snapshot).unwrap();
+        run(&mut replay, &solids, 90);
+        assert_eq!(replay, world);
+    }
+
+    #[test]
+    fn removing_a_body_drops_its_joints_and_wakes_the_other_end() {
+        let mut world = PhysicsWorld2D::default();
+        let a = world.insert(crate_at(0.0, 0.0));
+        let b = world.insert(crate_at(50.0, 0.0));
+        let joint = world.insert_joint(Joint2D::distance(
+            JointAnchor2D::Body {
+                body: a,
+                local: Vec2::ZERO,
+            },
+            JointAnchor2D::Body {
+                body: b,
+                local: Vec2::ZERO,
+            },
+            50.0,
+        ));
+        run(&mut world, &floor(), 120);
+        assert!(world.get(b).unwrap().is_sleeping());
+
+        world.remove(a);
+        assert!(world.joint(joint).is_none());
+        assert_eq!(world.joints().count(), 0);
+        assert!(!world.get(b).unwrap().is_sleeping());
+    }
+
+    #[test]
+    fn debug_draw_outlines_bodies_contacts_and_joints() {
+        let mut world = PhysicsWorld2D::default();
+        let id = world.insert(crate_at(0.0, 5.0));
+        run(&mut world, &floor(), 10);
+        let mut canvas = Canvas::for_test((800, 600));
+        world.debug_draw(&mut canvas);
+        let bodies_only = canvas.vertices().len();
+        assert!(bodies_only > 0);
+
+        world.insert_joint(Joint2D::rope(
+            JointAnchor2D::World(Vec2::new(0.0, 100.0)),
+            world.body_anchor(id, Vec2::new(0.0, 10.0)).unwrap(),
+            100.0,
+        ));
+        let mut canvas = Canvas::for_test((800, 600));
+        world.debug_draw(&mut canvas);
+        assert!(canvas.vertices().len() > bodies_only);
+    }
+}
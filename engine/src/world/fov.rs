@@ -0,0 +1,527 @@
+use crate::world::tilemap::TileMap;
+
+/// One bit per cell of a grid: the cells a viewer can see right now, or
+/// every cell they have ever seen.
+///
+/// Keep one set per frame from [`FieldOfView::compute`] and fold it into an
+/// "explored" set with [`VisibilitySet::union_with`]; the pair is what
+/// [`TileMap::draw_fog`] needs to dim remembered tiles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisibilitySet {
+    width: usize,
+    height: usize,
+    bits: Vec<u64>,
+}
+
+impl VisibilitySet {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            bits: vec![0; (width * height).div_ceil(64)],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether a cell is in the set; cells off the grid never are.
+    pub fn contains(&self, col: usize, row: usize) -> bool {
+        self.index(col, row)
+            .is_some_and(|i| self.bits[i / 64] & (1 << (i % 64)) != 0)
+    }
+
+    /// Add a cell, ignoring cells off the grid.
+    pub fn insert(&mut self, col: usize, row: usize) {
+        if let Some(i) = self.index(col, row) {
+            self.bits[i / 64] |= 1 << (i % 64);
+        }
+    }
+
+    pub fn remove(&mut self, col: usize, row: usize) {
+        if let Some(i) = self.index(col, row) {
+            self.bits[i / 64] &= !(1 << (i % 64));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.fill(0);
+    }
+
+    /// Make the set cover a grid of a new size, emptying it.
+    pub fn reset(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.bits.clear();
+        self.bits.resize((width * height).div_ceil(64), 0);
+    }
+
+    /// How many cells are in the set.
+    pub fn len(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|&word| word == 0)
+    }
+
+    /// Add every cell of `other`, a set over the same grid.
+    pub fn union_with(&mut self, other: &VisibilitySet) {
+        debug_assert_eq!((self.width, self.height), (other.width, other.height));
+        for (word, other) in self.bits.iter_mut().zip(&other.bits) {
+            *word |= other;
+        }
+    }
+
+    /// The cells in the set, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.bits.iter().enumerate().flat_map(move |(w, &word)| {
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                if rest == 0 {
+                    return None;
+                }
+                let bit = rest.trailing_zeros() as usize;
+                rest &= rest - 1;
+                let i = w * 64 + bit;
+                Some((i % self.width, i / self.width))
+            })
+        })
+    }
+
+    fn index(&self, col: usize, row: usize) -> Option<usize> {
+        (col < self.width && row < self.height).then(|| row * self.width + col)
+    }
+}
+
+/// What a viewer standing in one cell can see, by symmetric recursive
+/// shadowcasting.
+///
+/// Each quadrant is scanned row by row outwards from the viewer, narrowing
+/// the visible slope range at every opaque cell. Floor cells are only seen
+/// when their centre lies inside the range, which makes the result symmetric:
+/// if `a` sees `b`, `b` sees `a`. Opaque cells are seen whenever any part of
+/// them is lit, so wall faces show up. The viewer's own cell is always
+/// visible. Cells off the grid count as opaque.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldOfView {
+    pub origin: (usize, usize),
+    /// How far the viewer sees, in cells. Unlimited by default.
+    pub radius: f32,
+}
+
+impl FieldOfView {
+    pub fn new(origin: (usize, usize)) -> Self {
+        Self {
+            origin,
+            radius: f32::INFINITY,
+        }
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// The cells visible on `map`, with [`TileMap::is_opaque`] cells blocking
+    /// sight. Cells are treated as squares whatever the map's
+    /// [`GridLayout`](crate::world::GridLayout).
+    pub fn compute(&self, map: &TileMap) -> VisibilitySet {
+        let mut visible = VisibilitySet::new(map.width, map.height);
+        self.compute_into(map, &mut visible);
+        visible
+    }
+
+    /// Like [`FieldOfView::compute`], reusing `visible`'s storage.
+    pub fn compute_into(&self, map: &TileMap, visible: &mut VisibilitySet) {
+        visible.reset(map.width, map.height);
+        self.scan(
+            map.width,
+            map.height,
+            |col, row| map.is_opaque(col, row),
+            visible,
+        );
+    }
+
+    /// Shadowcast over any `width` × `height` grid whose opaque cells are
+    /// reported by `opaque`, such as a map with doors the AI knows are shut.
+    /// `visible` is cleared first and resized to the grid.
+    pub fn compute_with(
+        &self,
+        width: usize,
+        height: usize,
+        opaque: impl Fn(usize, usize) -> bool,
+        visible: &mut VisibilitySet,
+    ) {
+        visible.reset(width, height);
+        self.scan(width, height, opaque, visible);
+    }
+
+    fn scan(
+        &self,
+        width: usize,
+        height: usize,
+        opaque: impl Fn(usize, usize) -> bool,
+        visible: &mut VisibilitySet,
+    ) {
+        let (ox, oy) = (self.origin.0 as i64, self.origin.1 as i64);
+        if self.origin.0 >= width || self.origin.1 >= height || self.radius < 0.0 {
+            return;
+        }
+        visible.insert(self.origin.0, self.origin.1);
+        // Cells whose centre is within the radius, rounded so the edge of the
+        // circle is not ragged.
+        let reach = (self.radius + 0.5) * (self.radius + 0.5);
+        let max_depth = (self.radius + 0.5).min((width + height) as f32).floor() as i64;
+
+        for quadrant in Quadrant::ALL {
+            // (depth, col) in quadrant space to a grid cell, if on the grid.
+            let cell = |depth: i64, col: i64| {
+                let (x, y) = quadrant.transform(ox, oy, depth, col);
+                (x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height)
+                    .then_some((x as usize, y as usize))
+            };
+            let is_wall = |depth: i64, col: i64| cell(depth, col).is_none_or(|(x, y)| opaque(x, y));
+
+            let mut rows = vec![Row {
+                depth: 1,
+                start: Slope::new(-1, 1),
+                end: Slope::new(1, 1),
+            }];
+            while let Some(mut row) = rows.pop() {
+                if row.depth > max_depth {
+                    continue;
+                }
+                let mut prev_wall = None;
+                for col in row.columns() {
+                    let wall = is_wall(row.depth, col);
+                    let lit = wall || row.is_symmetric(col);
+                    let in_reach = (row.depth * row.depth + col * col) as f32 <= reach;
+                    if lit && in_reach {
+                        if let Some((x, y)) = cell(row.depth, col) {
+                            visible.insert(x, y);
+                        }
+                    }
+                    match (prev_wall, wall) {
+                        (Some(true), false) => row.start = Slope::of_edge(row.depth, col),
+                        (Some(false), true) => rows.push(Row {
+                            end: Slope::of_edge(row.depth, col),
+                            ..row.next()
+                        }),
+                        _ => {}
+                    }
+                    prev_wall = Some(wall);
+                }
+                if prev_wall == Some(false) {
+                    rows.push(row.next());
+                }
+            }
+        }
+    }
+}
+
+/// Whether `to` can be seen from `from` along a straight line of cells, with
+/// only the cells strictly between them checked against
+/// [`TileMap::is_opaque`].
+///
+/// The line is walked with Bresenham's algorithm from each end in turn and
+/// either clear walk counts, so the answer is the same both ways round.
+pub fn line_of_sight(map: &TileMap, from: (usize, usize), to: (usize, usize)) -> bool {
+    line_of_sight_with(from, to, |col, row| map.is_opaque(col, row))
+}
+
+/// [`line_of_sight`] over any grid whose opaque cells are reported by
+/// `opaque`.
+pub fn line_of_sight_with(
+    from: (usize, usize),
+    to: (usize, usize),
+    opaque: impl Fn(usize, usize) -> bool,
+) -> bool {
+    let clear = |a, b| {
+        let mut cells = GridLine::new(a, b);
+        cells.next();
+        cells
+            .take_while(|&cell| cell != b)
+            .all(|(col, row)| !opaque(col, row))
+    };
+    clear(from, to) || clear(to, from)
+}
+
+/// The cells of a Bresenham line from one cell to another, both included.
+#[derive(Debug, Clone)]
+pub struct GridLine {
+    x: i64,
+    y: i64,
+    end: (i64, i64),
+    dx: i64,
+    dy: i64,
+    step_x: i64,
+    step_y: i64,
+    error: i64,
+    done: bool,
+}
+
+impl GridLine {
+    pub fn new(from: (usize, usize), to: (usize, usize)) -> Self {
+        let (x0, y0) = (from.0 as i64, from.1 as i64);
+        let (x1, y1) = (to.0 as i64, to.1 as i64);
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        Self {
+            x: x0,
+            y: y0,
+            end: (x1, y1),
+            dx,
+            dy,
+            step_x: if x0 < x1 { 1 } else { -1 },
+            step_y: if y0 < y1 { 1 } else { -1 },
+            error: dx + dy,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for GridLine {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        if self.done {
+            return None;
+        }
+        let cell = (self.x as usize, self.y as usize);
+        if (self.x, self.y) == self.end {
+            self.done = true;
+            return Some(cell);
+        }
+        let doubled = 2 * self.error;
+        if doubled >= self.dy {
+            self.error += self.dy;
+            self.x += self.step_x;
+        }
+        if doubled <= self.dx {
+            self.error += self.dx;
+            self.y += self.step_y;
+        }
+        Some(cell)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Quadrant {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Quadrant {
+    const ALL: [Quadrant; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    fn transform(self, ox: i64, oy: i64, depth: i64, col: i64) -> (i64, i64) {
+        match self {
+            Self::Up => (ox + col, oy + depth),
+            Self::Down => (ox + col, oy - depth),
+            Self::Right => (ox + depth, oy + col),
+            Self::Left => (ox - depth, oy + col),
+        }
+    }
+}
+
+/// An exact slope `num / den` across a quadrant, `den > 0`.
+#[derive(Debug, Clone, Copy)]
+struct Slope {
+    num: i64,
+    den: i64,
+}
+
+impl Slope {
+    fn new(num: i64, den: i64) -> Self {
+        Self { num, den }
+    }
+
+    /// The slope through the near edge of a cell's column.
+    fn of_edge(depth: i64, col: i64) -> Self {
+        Self::new(2 * col - 1, 2 * depth)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Row {
+    depth: i64,
+    start: Slope,
+    end: Slope,
+}
+
+impl Row {
+    /// Columns whose cells the slope range touches: from `depth * start`
+    /// rounded half up to `depth * end` rounded half down.
+    fn columns(&self) -> std::ops::RangeInclusive<i64> {
+        let (s, e) = (self.start, self.end);
+        let first = (2 * self.depth * s.num + s.den).div_euclid(2 * s.den);
+        let last = -(e.den - 2 * self.depth * e.num).div_euclid(2 * e.den);
+        first..=last
+    }
+
+    /// Whether the cell's centre lies inside the slope range.
+    fn is_symmetric(&self, col: i64) -> bool {
+        col * self.start.den >= self.depth * self.start.num
+            && col * self.end.den <= self.depth * self.end.num
+    }
+
+    fn next(&self) -> Row {
+        Row {
+            depth: self.depth + 1,
+            ..*self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::rng::Rng;
+    use crate::renderer::TextureId;
+    use crate::world::tilemap::TileDef;
+
+    /// A map from rows of `#` (opaque wall) and `.` (floor), top row last so
+    /// the picture reads with y up.
+    fn map(rows: &[&str]) -> TileMap {
+        let mut map = TileMap::new(rows[0].len(), rows.len(), 10.0);
+        let wall = map.add_tile(TileDef::solid(TextureId(0)).with_opaque(true));
+        let floor = map.add_tile(TileDef::solid(TextureId(0)).with_collision(false));
+        for (row, line) in rows.iter().rev().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                map.set(col, row, Some(if c == '#' { wall } else { floor }));
+            }
+        }
+        map
+    }
+
+    #[test]
+    fn open_ground_is_seen_out_to_the_radius() {
+        let open = map(&["..........."; 11]);
+        let all = FieldOfView::new((5, 5)).compute(&open);
+        assert_eq!(all.len(), 121);
+
+        let near = FieldOfView::new((5, 5)).with_radius(2.0).compute(&open);
+        assert!(near.contains(5, 5) && near.contains(7, 5) && near.contains(6, 7));
+        assert!(!near.contains(8, 5) && !near.contains(7, 7));
+        // Zero radius sees the viewer's own cell only.
+        assert_eq!(
+            FieldOfView::new((5, 5))
+                .with_radius(0.0)
+                .compute(&open)
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![(5, 5)]
+        );
+    }
+
+    #[test]
+    fn walls_are_seen_and_cast_shadows() {
+        let room = map(&[
+            "#########",
+            "#.......#",
+            "#.......#",
+            "#...#...#",
+            "#.......#",
+            "#.......#",
+            "#########",
+        ]);
+        // The viewer stands below the pillar at (4, 3).
+        let seen = FieldOfView::new((4, 1)).compute(&room);
+        assert!(seen.contains(4, 3), "the pillar's face is lit");
+        assert!(
+            !seen.contains(4, 4) && !seen.contains(4, 5),
+            "straight behind it is dark"
+        );
+        assert!(seen.contains(1, 5) && seen.contains(7, 5));
+        assert!(
+            seen.contains(0, 0) && seen.contains(8, 6),
+            "corners of the outer wall"
+        );
+        // A shut room hides the one next door.
+        let rooms = map(&["#######", "#..#..#", "#..#..#", "#######"]);
+        let seen = FieldOfView::new((1, 1)).compute(&rooms);
+        assert!(seen.contains(3, 1) && seen.contains(3, 2));
+        assert!(!seen.contains(4, 1) && !seen.contains(5, 2));
+    }
+
+    #[test]
+    fn visibility_is_symmetric_between_floor_cells() {
+        let mut rng = Rng::new(4);
+        let rows: Vec<String> = (0..14)
+            .map(|_| {
+                (0..14)
+                    .map(|_| if rng.chance(0.25) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        let cave = map(&rows.iter().map(String::as_str).collect::<Vec<_>>());
+        let floors: Vec<(usize, usize)> = (0..14)
+            .flat_map(|row| (0..14).map(move |col| (col, row)))
+            .filter(|&(col, row)| !cave.is_opaque(col, row))
+            .collect();
+        let views: Vec<VisibilitySet> = floors
+            .iter()
+            .map(|&cell| FieldOfView::new(cell).compute(&cave))
+            .collect();
+        for (a, view_a) in floors.iter().zip(&views) {
+            for (b, view_b) in floors.iter().zip(&views) {
+                assert_eq!(
+                    view_a.contains(b.0, b.1),
+                    view_b.contains(a.0, a.1),
+                    "{a:?} {b:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn lines_of_sight_walk_bresenham_cells() {
+        let line: Vec<_> = GridLine::new((0, 0), (4, 2)).collect();
+        assert_eq!(line, vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]);
+        assert_eq!(
+            GridLine::new((3, 3), (3, 3)).collect::<Vec<_>>(),
+            vec![(3, 3)]
+        );
+        assert_eq!(GridLine::new((2, 5), (2, 2)).count(), 4);
+
+        let room = map(&[".......", "...#...", "......."]);
+        assert!(!line_of_sight(&room, (0, 1), (6, 1)));
+        assert!(line_of_sight(&room, (0, 0), (6, 0)));
+        // Opaque ends do not block: a wall can be looked at.
+        assert!(line_of_sight(&room, (1, 1), (3, 1)));
+        assert_eq!(
+            line_of_sight(&room, (0, 2), (6, 0)),
+            line_of_sight(&room, (6, 0), (0, 2))
+        );
+    }
+
+    #[test]
+    fn visibility_sets_merge_into_explored() {
+        let mut seen = VisibilitySet::new(70, 3);
+        assert!(seen.is_empty());
+        seen.insert(69, 2);
+        seen.insert(1, 0);
+        seen.insert(70, 0); // off the grid
+        assert_eq!(seen.iter().collect::<Vec<_>>(), vec![(1, 0), (69, 2)]);
+
+        let mut explored = VisibilitySet::new(70, 3);
+        explored.insert(5, 1);
+        explored.union_with(&seen);
+        assert_eq!(explored.len(), 3);
+        seen.remove(69, 2);
+        assert!(!seen.contains(69, 2) && explored.contains(69, 2));
+        seen.clear();
+        assert!(seen.is_empty());
+    }
+}
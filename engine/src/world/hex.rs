@@ -0,0 +1,403 @@
+use glam::Vec2;
+use std::ops::{Add, Mul, Neg, Sub};
+
+const SQRT_3: f32 = 1.732_050_8;
+
+/// Whether a hex grid's cells have a corner or a flat edge at the top.
+///
+/// Pointy-topped hexes sit in horizontal rows; flat-topped hexes sit in
+/// vertical columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HexOrientation {
+    #[default]
+    Pointy,
+    Flat,
+}
+
+/// Which half of a zig-zag grid is pushed out by half a cell in offset
+/// coordinates: the odd or the even rows (columns, for flat-topped hexes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Stagger {
+    #[default]
+    Odd,
+    Even,
+}
+
+impl Stagger {
+    /// How far `line` is pushed along the staggered axis, in half cells.
+    ///
+    /// Odd staggering pushes odd lines forwards; even staggering pulls odd
+    /// lines back instead, so cell `(0, 0)` stays put either way and the two
+    /// conventions differ only in which way the zig-zag starts.
+    pub(crate) fn shift(self, line: i32) -> i32 {
+        match self {
+            Stagger::Odd => line & 1,
+            Stagger::Even => -(line & 1),
+        }
+    }
+}
+
+/// A hex cell in axial coordinates.
+///
+/// The third cube coordinate is implied, `s = -q - r`; [`Hex::s`] and
+/// [`Hex::from_cube`] convert. `q` runs along the rows of a pointy-topped
+/// grid (the columns of a flat-topped one) and `r` down the screen, so the
+/// same cell is the same `Hex` in either orientation; only [`HexLayout`]
+/// decides where it is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    pub const ZERO: Hex = Hex::new(0, 0);
+
+    /// The six steps to a neighbouring cell, going round the hex.
+    /// [`Hex::neighbour`] takes an index into this table.
+    pub const DIRECTIONS: [Hex; 6] = [
+        Hex::new(1, 0),
+        Hex::new(1, -1),
+        Hex::new(0, -1),
+        Hex::new(-1, 0),
+        Hex::new(-1, 1),
+        Hex::new(0, 1),
+    ];
+
+    pub const fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    /// A cell from cube coordinates, which must sum to zero.
+    pub fn from_cube(q: i32, r: i32, s: i32) -> Self {
+        debug_assert_eq!(q + r + s, 0, "cube coordinates must sum to zero");
+        Self { q, r }
+    }
+
+    pub fn s(self) -> i32 {
+        -self.q - self.r
+    }
+
+    /// The cell as cube coordinates `(q, r, s)`.
+    pub fn cube(self) -> (i32, i32, i32) {
+        (self.q, self.r, self.s())
+    }
+
+    /// The cell containing fractional axial coordinates.
+    pub fn round(q: f32, r: f32) -> Self {
+        let s = -q - r;
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        // Rounding each coordinate alone can break `q + r + s = 0`; rebuild
+        // the one that moved furthest from the other two.
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+        Self::new(rq as i32, rr as i32)
+    }
+
+    /// The neighbour in one of the six [`Hex::DIRECTIONS`], wrapping the
+    /// index.
+    pub fn neighbour(self, direction: usize) -> Self {
+        self + Self::DIRECTIONS[direction % 6]
+    }
+
+    pub fn neighbours(self) -> [Hex; 6] {
+        Self::DIRECTIONS.map(|step| self + step)
+    }
+
+    /// Steps from the origin cell.
+    pub fn length(self) -> i32 {
+        (self.q.abs() + self.r.abs() + self.s().abs()) / 2
+    }
+
+    /// Steps between two cells, moving only between neighbours.
+    pub fn distance(self, other: Hex) -> i32 {
+        (self - other).length()
+    }
+
+    /// The cells exactly `radius` steps away, going round from the
+    /// south-west corner. A radius of zero is the cell itself.
+    pub fn ring(self, radius: u32) -> Vec<Hex> {
+        if radius == 0 {
+            return vec![self];
+        }
+        let mut cells = Vec::with_capacity(6 * radius as usize);
+        let mut cell = self + Self::DIRECTIONS[4] * radius as i32;
+        for direction in 0..6 {
+            for _ in 0..radius {
+                cells.push(cell);
+                cell = cell.neighbour(direction);
+            }
+        }
+        cells
+    }
+
+    /// Every cell within `radius` steps, rings from the centre outwards.
+    pub fn spiral(self, radius: u32) -> Vec<Hex> {
+        (0..=radius).flat_map(|ring| self.ring(ring)).collect()
+    }
+
+    /// Every cell within `radius` steps, row by row.
+    pub fn range(self, radius: u32) -> Vec<Hex> {
+        let n = radius as i32;
+        let mut cells = Vec::with_capacity((3 * n * (n + 1) + 1) as usize);
+        for q in -n..=n {
+            for r in (-n).max(-q - n)..=n.min(-q + n) {
+                cells.push(self + Hex::new(q, r));
+            }
+        }
+        cells
+    }
+
+    /// The cells a straight line from this cell's centre to `to`'s passes
+    /// through, both ends included, one per step.
+    pub fn line_to(self, to: Hex) -> Vec<Hex> {
+        let steps = self.distance(to);
+        if steps == 0 {
+            return vec![self];
+        }
+        // Nudge off the exact edges so ties between two cells break the
+        // same way along the whole line.
+        let (aq, ar) = (self.q as f32 + 1e-6, self.r as f32 + 2e-6);
+        let (bq, br) = (to.q as f32 + 1e-6, to.r as f32 + 2e-6);
+        (0..=steps)
+            .map(|i| {
+                let t = i as f32 / steps as f32;
+                Hex::round(aq + (bq - aq) * t, ar + (br - ar) * t)
+            })
+            .collect()
+    }
+
+    /// The cell's `(col, row)` offset coordinates: rows staggered for
+    /// pointy-topped grids, columns for flat-topped ones.
+    pub fn to_offset(self, orientation: HexOrientation, stagger: Stagger) -> (i32, i32) {
+        match orientation {
+            HexOrientation::Pointy => {
+                let col = self.q + (self.r - stagger.shift(self.r)) / 2;
+                (col, self.r)
+            }
+            HexOrientation::Flat => {
+                let row = self.r + (self.q - stagger.shift(self.q)) / 2;
+                (self.q, row)
+            }
+        }
+    }
+
+    /// The cell at `(col, row)` in offset coordinates; the inverse of
+    /// [`Hex::to_offset`].
+    pub fn from_offset(col: i32, row: i32, orientation: HexOrientation, stagger: Stagger) -> Self {
+        match orientation {
+            HexOrientation::Pointy => Hex::new(col - (row - stagger.shift(row)) / 2, row),
+            HexOrientation::Flat => Hex::new(col, row - (col - stagger.shift(col)) / 2),
+        }
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, rhs: Hex) -> Hex {
+        Hex::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, rhs: Hex) -> Hex {
+        Hex::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    fn mul(self, rhs: i32) -> Hex {
+        Hex::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+
+    fn neg(self) -> Hex {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+/// Where the cells of a hex grid sit in the world.
+///
+/// `size` is the distance from a hex's centre to its corners. Positions are
+/// relative to the centre of [`Hex::ZERO`], with `r` running down the screen
+/// as [`iso_to_screen`](crate::world::iso_to_screen) rows do. `stagger` picks
+/// the offset convention used by [`HexLayout::offset_to_world`] and
+/// [`HexLayout::world_to_offset`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HexLayout {
+    pub orientation: HexOrientation,
+    pub size: f32,
+    pub stagger: Stagger,
+}
+
+impl HexLayout {
+    pub fn new(orientation: HexOrientation, size: f32) -> Self {
+        Self {
+            orientation,
+            size,
+            stagger: Stagger::Odd,
+        }
+    }
+
+    pub fn with_stagger(mut self, stagger: Stagger) -> Self {
+        self.stagger = stagger;
+        self
+    }
+
+    /// The width and height of one hex's bounding box.
+    pub fn cell_size(&self) -> Vec2 {
+        match self.orientation {
+            HexOrientation::Pointy => Vec2::new(SQRT_3, 2.0) * self.size,
+            HexOrientation::Flat => Vec2::new(2.0, SQRT_3) * self.size,
+        }
+    }
+
+    /// The centre of a cell.
+    pub fn hex_to_world(&self, hex: Hex) -> Vec2 {
+        let (q, r) = (hex.q as f32, hex.r as f32);
+        let (x, down) = match self.orientation {
+            HexOrientation::Pointy => (SQRT_3 * (q + r / 2.0), 1.5 * r),
+            HexOrientation::Flat => (1.5 * q, SQRT_3 * (r + q / 2.0)),
+        };
+        Vec2::new(x, -down) * self.size
+    }
+
+    /// The cell containing a point.
+    pub fn world_to_hex(&self, point: Vec2) -> Hex {
+        let (x, down) = (point.x / self.size, -point.y / self.size);
+        match self.orientation {
+            HexOrientation::Pointy => {
+                let r = down * 2.0 / 3.0;
+                Hex::round(x / SQRT_3 - r / 2.0, r)
+            }
+            HexOrientation::Flat => {
+                let q = x * 2.0 / 3.0;
+                Hex::round(q, down / SQRT_3 - q / 2.0)
+            }
+        }
+    }
+
+    /// The six corners of a cell, counter-clockwise.
+    pub fn corners(&self, hex: Hex) -> [Vec2; 6] {
+        let center = self.hex_to_world(hex);
+        let start = match self.orientation {
+            HexOrientation::Pointy => 30.0f32,
+            HexOrientation::Flat => 0.0,
+        };
+        std::array::from_fn(|i| {
+            let angle = (start + 60.0 * i as f32).to_radians();
+            center + Vec2::from_angle(angle) * self.size
+        })
+    }
+
+    /// The centre of the cell at `(col, row)` in offset coordinates.
+    pub fn offset_to_world(&self, col: i32, row: i32) -> Vec2 {
+        self.hex_to_world(Hex::from_offset(col, row, self.orientation, self.stagger))
+    }
+
+    /// The offset coordinates of the cell containing a point.
+    pub fn world_to_offset(&self, point: Vec2) -> (i32, i32) {
+        self.world_to_hex(point)
+            .to_offset(self.orientation, self.stagger)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbours_rings_and_ranges_agree_with_distance() {
+        let center = Hex::new(2, -3);
+        for (i, n) in center.neighbours().into_iter().enumerate() {
+            assert_eq!(center.distance(n), 1);
+            assert_eq!(center.neighbour(i + 6), n);
+        }
+        assert_eq!(center.ring(0), vec![center]);
+        for radius in 1..4 {
+            let ring = center.ring(radius);
+            assert_eq!(ring.len(), 6 * radius as usize);
+            assert!(ring.iter().all(|h| h.distance(center) == radius as i32));
+            // Consecutive ring cells are neighbours, all the way round.
+            for (a, b) in ring.iter().zip(ring.iter().cycle().skip(1)) {
+                assert_eq!(a.distance(*b), 1);
+            }
+        }
+        let mut range = center.range(3);
+        let mut spiral = center.spiral(3);
+        assert_eq!(range.len(), 37);
+        range.sort_by_key(|h| (h.q, h.r));
+        spiral.sort_by_key(|h| (h.q, h.r));
+        assert_eq!(range, spiral);
+        assert_eq!(Hex::from_cube(1, 2, -3).cube(), (1, 2, -3));
+    }
+
+    #[test]
+    fn lines_step_between_neighbours() {
+        let a = Hex::new(-2, 1);
+        let b = Hex::new(3, -4);
+        let line = a.line_to(b);
+        assert_eq!(line.len(), a.distance(b) as usize + 1);
+        assert_eq!((line[0], *line.last().unwrap()), (a, b));
+        for pair in line.windows(2) {
+            assert_eq!(pair[0].distance(pair[1]), 1);
+        }
+        assert_eq!(a.line_to(a), vec![a]);
+    }
+
+    #[test]
+    fn offset_coordinates_round_trip_in_every_convention() {
+        for orientation in [HexOrientation::Pointy, HexOrientation::Flat] {
+            for stagger in [Stagger::Odd, Stagger::Even] {
+                for hex in Hex::new(1, -1).range(4) {
+                    let (col, row) = hex.to_offset(orientation, stagger);
+                    assert_eq!(Hex::from_offset(col, row, orientation, stagger), hex);
+                }
+            }
+        }
+        // Odd rows of a pointy grid sit half a cell right of the even ones.
+        let layout = HexLayout::new(HexOrientation::Pointy, 10.0);
+        let even = layout.offset_to_world(0, 0);
+        let odd = layout.offset_to_world(0, 1);
+        assert!((odd.x - even.x - SQRT_3 * 5.0).abs() < 1e-4);
+        assert!(odd.y < even.y);
+        let layout = layout.with_stagger(Stagger::Even);
+        assert!(layout.offset_to_world(0, 1).x < layout.offset_to_world(0, 0).x);
+    }
+
+    #[test]
+    fn world_positions_round_trip_through_the_cell_containing_them() {
+        for orientation in [HexOrientation::Pointy, HexOrientation::Flat] {
+            let layout = HexLayout::new(orientation, 12.0);
+            for hex in Hex::ZERO.range(5) {
+                let center = layout.hex_to_world(hex);
+                assert_eq!(layout.world_to_hex(center), hex);
+                for corner in layout.corners(hex) {
+                    assert!((corner.distance(center) - 12.0).abs() < 1e-3);
+                    // Just inside each corner is still this cell.
+                    assert_eq!(layout.world_to_hex(center + (corner - center) * 0.9), hex);
+                }
+                for n in hex.neighbours() {
+                    let gap = layout.hex_to_world(n).distance(center);
+                    assert!((gap - 12.0 * SQRT_3).abs() < 1e-3);
+                }
+            }
+            let size = layout.cell_size();
+            let corners = layout.corners(Hex::ZERO);
+            let max = corners.iter().fold(Vec2::ZERO, |m, c| m.max(c.abs()));
+            assert!((max * 2.0 - size).abs().max_element() < 1e-3);
+        }
+    }
+}
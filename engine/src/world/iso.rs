@@ -1,3 +1,4 @@
+use crate::world::hex::Stagger;
 use glam::Vec2;
 
 pub fn iso_to_screen(col: i32, row: i32, tile_width: f32, tile_height: f32) -> Vec2 {
@@ -13,3 +14,185 @@ pub fn screen_to_iso(screen: Vec2, tile_width: f32, tile_height: f32) -> (i32, i
     let row = (sy / (tile_height / 2.0) - sx / (tile_width / 2.0)) / 2.0;
     (col.round() as i32, row.round() as i32)
 }
+
+/// A staggered isometric grid, Tiled's "staggered" maps: diamond tiles
+/// packed in zig-zag rows so a rectangular map stays rectangular on screen.
+///
+/// Cell `(col, row)` has its centre at `to_screen(col, row)`, with rows
+/// running down the screen half a tile apart and every other row shifted
+/// sideways by half a tile, as [`Stagger`] picks. Under the hood every cell
+/// is also a cell of the plain diamond grid of [`iso_to_screen`];
+/// [`StaggeredIso::to_diamond`] converts, and neighbours, distances and lines
+/// are worked out there.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StaggeredIso {
+    pub tile_width: f32,
+    pub tile_height: f32,
+    pub stagger: Stagger,
+}
+
+impl StaggeredIso {
+    pub fn new(tile_width: f32, tile_height: f32) -> Self {
+        Self {
+            tile_width,
+            tile_height,
+            stagger: Stagger::Odd,
+        }
+    }
+
+    pub fn with_stagger(mut self, stagger: Stagger) -> Self {
+        self.stagger = stagger;
+        self
+    }
+
+    pub fn to_screen(&self, col: i32, row: i32) -> Vec2 {
+        let x = (2 * col + self.stagger.shift(row)) as f32 * (self.tile_width / 2.0);
+        let y = row as f32 * (self.tile_height / 2.0);
+        Vec2::new(x, -y)
+    }
+
+    /// The cell whose diamond contains a screen position.
+    pub fn from_screen(&self, screen: Vec2) -> (i32, i32) {
+        let (col, row) = screen_to_iso(screen, self.tile_width, self.tile_height);
+        self.from_diamond(col, row)
+    }
+
+    /// The same cell in the diamond grid of [`iso_to_screen`].
+    pub fn to_diamond(&self, col: i32, row: i32) -> (i32, i32) {
+        let across = 2 * col + self.stagger.shift(row);
+        ((row + across) / 2, (row - across) / 2)
+    }
+
+    pub fn from_diamond(&self, col: i32, row: i32) -> (i32, i32) {
+        let down = col + row;
+        ((col - row - self.stagger.shift(down)) / 2, down)
+    }
+
+    /// The four cells sharing an edge with a cell: down-right, down-left,
+    /// up-left and up-right.
+    pub fn neighbours(&self, col: i32, row: i32) -> [(i32, i32); 4] {
+        let (c, r) = self.to_diamond(col, row);
+        [(c + 1, r), (c, r + 1), (c - 1, r), (c, r - 1)].map(|(c, r)| self.from_diamond(c, r))
+    }
+
+    /// Steps between two cells, moving only across edges.
+    pub fn distance(&self, a: (i32, i32), b: (i32, i32)) -> i32 {
+        let (ac, ar) = self.to_diamond(a.0, a.1);
+        let (bc, br) = self.to_diamond(b.0, b.1);
+        (ac - bc).abs() + (ar - br).abs()
+    }
+
+    /// The cells exactly `radius` steps away, going round from the
+    /// down-right corner. A radius of zero is the cell itself.
+    pub fn ring(&self, center: (i32, i32), radius: u32) -> Vec<(i32, i32)> {
+        if radius == 0 {
+            return vec![center];
+        }
+        let (c, r) = self.to_diamond(center.0, center.1);
+        let n = radius as i32;
+        (0..4 * n)
+            .map(|i| {
+                let k = i % n;
+                let (dc, dr) = match i / n {
+                    0 => (n - k, k),
+                    1 => (-k, n - k),
+                    2 => (k - n, -k),
+                    _ => (k, k - n),
+                };
+                self.from_diamond(c + dc, r + dr)
+            })
+            .collect()
+    }
+
+    /// Every cell within `radius` steps.
+    pub fn range(&self, center: (i32, i32), radius: u32) -> Vec<(i32, i32)> {
+        let (c, r) = self.to_diamond(center.0, center.1);
+        let n = radius as i32;
+        let mut cells = Vec::with_capacity((2 * n * (n + 1) + 1) as usize);
+        for dc in -n..=n {
+            let reach = n - dc.abs();
+            for dr in -reach..=reach {
+                cells.push(self.from_diamond(c + dc, r + dr));
+            }
+        }
+        cells
+    }
+
+    /// The cells a straight line between two cell centres crosses, both ends
+    /// included, each one edge away from the last.
+    pub fn line(&self, from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+        let (mut c, mut r) = self.to_diamond(from.0, from.1);
+        let (tc, tr) = self.to_diamond(to.0, to.1);
+        let (nc, nr) = ((tc - c).abs(), (tr - r).abs());
+        let (sc, sr) = ((tc - c).signum(), (tr - r).signum());
+        let mut cells = Vec::with_capacity((nc + nr + 1) as usize);
+        cells.push(from);
+        let (mut ic, mut ir) = (0, 0);
+        while ic < nc || ir < nr {
+            // Step along whichever axis the line crosses next: compare
+            // (ic + 0.5) / nc against (ir + 0.5) / nr without dividing.
+            if (1 + 2 * ic) * nr < (1 + 2 * ir) * nc {
+                c += sc;
+                ic += 1;
+            } else {
+                r += sr;
+                ir += 1;
+            }
+            cells.push(self.from_diamond(c, r));
+        }
+        cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn staggered_cells_sit_on_the_diamond_grid() {
+        for stagger in [Stagger::Odd, Stagger::Even] {
+            let grid = StaggeredIso::new(64.0, 32.0).with_stagger(stagger);
+            for row in -4..5 {
+                for col in -4..5 {
+                    let (c, r) = grid.to_diamond(col, row);
+                    assert_eq!(grid.to_screen(col, row), iso_to_screen(c, r, 64.0, 32.0));
+                    assert_eq!(grid.from_diamond(c, r), (col, row));
+                    let center = grid.to_screen(col, row);
+                    assert_eq!(grid.from_screen(center + Vec2::new(20.0, 0.0)), (col, row));
+                }
+            }
+        }
+        // Odd rows sit half a tile right under odd staggering, left under even.
+        let odd = StaggeredIso::new(64.0, 32.0);
+        assert_eq!(odd.to_screen(0, 1), Vec2::new(32.0, -16.0));
+        let even = odd.with_stagger(Stagger::Even);
+        assert_eq!(even.to_screen(0, 1), Vec2::new(-32.0, -16.0));
+    }
+
+    #[test]
+    fn staggered_neighbours_rings_and_lines_agree_with_distance() {
+        let grid = StaggeredIso::new(64.0, 32.0);
+        let center = (3, 5);
+        assert_eq!(grid.neighbours(3, 5), [(4, 6), (3, 6), (3, 4), (4, 4)]);
+        for n in grid.neighbours(3, 5) {
+            assert_eq!(grid.distance(center, n), 1);
+        }
+        for radius in 0..4 {
+            let ring = grid.ring(center, radius);
+            assert_eq!(ring.len(), (4 * radius).max(1) as usize);
+            assert!(ring
+                .iter()
+                .all(|&c| grid.distance(center, c) == radius as i32));
+        }
+        let range = grid.range(center, 3);
+        assert_eq!(range.len(), 25);
+        assert!(range.iter().all(|&c| grid.distance(center, c) <= 3));
+
+        let line = grid.line((0, 0), (5, 9));
+        assert_eq!(line.len(), grid.distance((0, 0), (5, 9)) as usize + 1);
+        assert_eq!(*line.last().unwrap(), (5, 9));
+        for pair in line.windows(2) {
+            assert_eq!(grid.distance(pair[0], pair[1]), 1);
+        }
+    }
+}
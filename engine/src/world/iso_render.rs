@@ -0,0 +1,473 @@
+use crate::math::Rect;
+use crate::renderer::{DrawParams, Frame};
+use crate::world::tilemap::{LayeredTileMap, TileMap};
+use glam::{Vec2, Vec3};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// A box in an isometric world: a footprint on the grid in fractional cells
+/// (`x` is the column, `y` the row) and the elevation levels it spans (`z`).
+///
+/// Cell `(col, row)` covers `col - 0.5..col + 0.5` and `row - 0.5..row + 0.5`,
+/// the diamond [`iso_to_screen`](crate::world::iso_to_screen) centres on it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IsoBox {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl IsoBox {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    /// The column of cells `(col, row)` from `elevation` up `height` levels.
+    pub fn cell(col: i32, row: i32, elevation: f32, height: f32) -> Self {
+        let center = Vec2::new(col as f32, row as f32);
+        Self::standing(center, Vec2::ONE, elevation, height)
+    }
+
+    /// A footprint `size` cells across centred on `position`, from
+    /// `elevation` up `height` levels: a character or prop standing there.
+    pub fn standing(position: Vec2, size: Vec2, elevation: f32, height: f32) -> Self {
+        let half = size / 2.0;
+        Self {
+            min: (position - half).extend(elevation),
+            max: (position + half).extend(elevation + height),
+        }
+    }
+
+    /// The middle of the footprint, on the grid.
+    pub fn footprint_center(&self) -> Vec2 {
+        (self.min.truncate() + self.max.truncate()) / 2.0
+    }
+
+    /// Which of two boxes must be drawn first where their sprites overlap.
+    ///
+    /// Boxes apart in height put the lower one first. Otherwise the box with
+    /// the smaller column or row range comes first, as rows and columns both
+    /// grow towards the viewer. `None` means no axis decides: the boxes
+    /// interpenetrate, or sit side by side across the screen.
+    pub fn draw_order(&self, other: &IsoBox) -> Option<Ordering> {
+        let apart = |below: bool, above: bool| {
+            (below != above).then_some(if below {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            })
+        };
+        let z = apart(self.max.z <= other.min.z, other.max.z <= self.min.z);
+        if z.is_some() {
+            return z;
+        }
+        let x = apart(self.max.x <= other.min.x, other.max.x <= self.min.x);
+        let y = apart(self.max.y <= other.min.y, other.max.y <= self.min.y);
+        match (x, y) {
+            (Some(x), Some(y)) if x != y => None,
+            (Some(order), _) | (_, Some(order)) => Some(order),
+            (None, None) => None,
+        }
+    }
+
+    /// A rough depth for breaking ties: how far towards the viewer the box's
+    /// middle is.
+    fn depth(&self) -> f32 {
+        let center = self.footprint_center();
+        center.x + center.y + (self.min.z + self.max.z) * 0.5
+    }
+}
+
+/// One queued sprite.
+struct IsoItem {
+    bounds: IsoBox,
+    params: DrawParams,
+    /// Screen rect of the sprite and the box's silhouette together.
+    rect: Rect,
+}
+
+/// Draws an isometric scene back to front: the tiles of one or more
+/// [`TileMap`]s plus any sprites standing among them.
+///
+/// Every sprite is queued with the [`IsoBox`] it occupies. [`IsoRenderer::flush`]
+/// finds the sprites whose screen rects overlap, orders each such pair with
+/// [`IsoBox::draw_order`] and topologically sorts the lot, so a character
+/// walks behind a wall and in front of it again, and a log several cells
+/// long sorts correctly against someone walking along it, with no z-order
+/// values to tune. Pairs no axis decides, and any cycle, fall back to depth.
+///
+/// Cells sit on the diamond grid of [`iso_to_screen`](crate::world::iso_to_screen),
+/// moved to `origin`; each elevation level lifts them `level_height` up the
+/// screen.
+pub struct IsoRenderer {
+    pub tile_width: f32,
+    pub tile_height: f32,
+    /// Screen units one elevation level rises.
+    pub level_height: f32,
+    /// Screen position of cell `(0, 0)`'s centre at elevation 0.
+    pub origin: Vec2,
+    /// The z-order of the rearmost sprite; each one in front takes the next.
+    pub z_order: i32,
+    items: Vec<IsoItem>,
+}
+
+impl IsoRenderer {
+    pub fn new(tile_width: f32, tile_height: f32) -> Self {
+        Self {
+            tile_width,
+            tile_height,
+            level_height: tile_height,
+            origin: Vec2::ZERO,
+            z_order: 0,
+            items: Vec::new(),
+        }
+    }
+
+    pub fn with_level_height(mut self, level_height: f32) -> Self {
+        self.level_height = level_height;
+        self
+    }
+
+    pub fn with_origin(mut self, origin: Vec2) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    pub fn with_z_order(mut self, z_order: i32) -> Self {
+        self.z_order = z_order;
+        self
+    }
+
+    /// The screen position of a point on the grid: `(col, row, elevation)`.
+    pub fn to_screen(&self, point: Vec3) -> Vec2 {
+        let x = (point.x - point.y) * (self.tile_width / 2.0);
+        let y = (point.x + point.y) * (self.tile_height / 2.0) - point.z * self.level_height;
+        self.origin + Vec2::new(x, -y)
+    }
+
+    /// The fractional `(col, row)` under a screen position, on the plane at
+    /// `elevation`.
+    pub fn from_screen(&self, screen: Vec2, elevation: f32) -> Vec2 {
+        let local = screen - self.origin - Vec2::new(0.0, elevation * self.level_height);
+        let across = local.x / (self.tile_width / 2.0);
+        let down = -local.y / (self.tile_height / 2.0);
+        Vec2::new(across + down, down - across) / 2.0
+    }
+
+    /// Where a sprite standing in `bounds` touches the ground: the middle of
+    /// its footprint at its base.
+    pub fn anchor(&self, bounds: &IsoBox) -> Vec2 {
+        self.to_screen(bounds.footprint_center().extend(bounds.min.z))
+    }
+
+    /// The screen rect the box covers: where a sprite drawn to fill it goes.
+    pub fn silhouette(&self, bounds: &IsoBox) -> Rect {
+        let corners = [
+            Vec3::new(bounds.min.x, bounds.min.y, bounds.max.z),
+            Vec3::new(bounds.max.x, bounds.max.y, bounds.min.z),
+            Vec3::new(bounds.min.x, bounds.max.y, bounds.min.z),
+            Vec3::new(bounds.max.x, bounds.min.y, bounds.min.z),
+        ];
+        let (mut min, mut max) = (Vec2::INFINITY, Vec2::NEG_INFINITY);
+        for corner in corners {
+            let p = self.to_screen(corner);
+            min = min.min(p);
+            max = max.max(p);
+        }
+        Rect::from_pos_size(min, max - min)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Queue a sprite occupying `bounds`. It is drawn as `params` say, apart
+    /// from its z-order.
+    pub fn add(&mut self, bounds: IsoBox, params: DrawParams) {
+        let sprite = Rect::from_pos_size(params.position - params.origin, params.size);
+        let rect = union(sprite, self.silhouette(&bounds));
+        self.items.push(IsoItem {
+            bounds,
+            params,
+            rect,
+        });
+    }
+
+    /// Queue the map's tiles whose sprites overlap `view`, usually
+    /// `frame.view_rect()`.
+    ///
+    /// Each tile stands on the map's [`TileMap::elevation`] and rises its
+    /// [`TileDef::height`](crate::world::TileDef::height) levels; its texture
+    /// is stretched over the block's silhouette, so a flat tile is one
+    /// diamond and a wall one level high is a diamond plus two side faces.
+    /// The map's own layout and origin are ignored: cells are placed on this
+    /// renderer's grid.
+    pub fn add_tilemap(&mut self, map: &TileMap, view: Rect) {
+        // Tall tiles reach up into the view from below it.
+        let tallest = (0..)
+            .map_while(|id| map.tile(id))
+            .fold(0.0f32, |tallest, def| tallest.max(def.height));
+        let reach = Rect::new(
+            view.x,
+            view.y - tallest * self.level_height,
+            view.width,
+            view.height + tallest * self.level_height,
+        );
+        let (mut min, mut max) = (Vec2::INFINITY, Vec2::NEG_INFINITY);
+        for x in [reach.left(), reach.right()] {
+            for y in [reach.bottom(), reach.top()] {
+                let cell = self.from_screen(Vec2::new(x, y), map.elevation);
+                min = min.min(cell);
+                max = max.max(cell);
+            }
+        }
+        let span = |min: f32, max: f32, len: usize| {
+            let start = (min - 0.5).floor().clamp(0.0, len as f32) as usize;
+            let end = (max + 1.5).ceil().clamp(0.0, len as f32) as usize;
+            start..end
+        };
+        let cols = span(min.x, max.x, map.width);
+        let rows = span(min.y, max.y, map.height);
+
+        map.visit_cells(cols, rows, false, |col, row, id| {
+            let Some(def) = map.tile(id) else {
+                return;
+            };
+            let bounds = IsoBox::cell(col as i32, row as i32, map.elevation, def.height);
+            let rect = self.silhouette(&bounds);
+            if !rect.overlaps(&view) {
+                return;
+            }
+            let params = DrawParams::new(
+                def.texture,
+                rect.center(),
+                Vec2::new(rect.width, rect.height),
+            )
+            .with_centered_origin()
+            .with_rotation(def.rotation)
+            .with_color(def.color)
+            .with_uv_rect(def.uv_rect)
+            .with_flip_x(def.flip_x)
+            .with_flip_y(def.flip_y);
+            self.items.push(IsoItem {
+                bounds,
+                params,
+                rect,
+            });
+        });
+    }
+
+    /// [`IsoRenderer::add_tilemap`] for every visible layer, each at its own
+    /// elevation.
+    pub fn add_layers(&mut self, map: &LayeredTileMap, view: Rect) {
+        for layer in map.layers().iter().filter(|layer| layer.visible) {
+            self.add_tilemap(&layer.map, view);
+        }
+    }
+
+    /// Indices of the queued sprites, in the order they were added, sorted
+    /// back to front.
+    pub fn order(&self) -> Vec<usize> {
+        let n = self.items.len();
+        // Rank by depth so ties and cycles resolve the same way every frame.
+        let mut by_depth: Vec<usize> = (0..n).collect();
+        by_depth.sort_by(|&a, &b| {
+            let (a_box, b_box) = (&self.items[a].bounds, &self.items[b].bounds);
+            a_box.depth().total_cmp(&b_box.depth()).then(a.cmp(&b))
+        });
+        let mut rank = vec![0; n];
+        for (r, &i) in by_depth.iter().enumerate() {
+            rank[i] = r;
+        }
+
+        // Sweep across the screen so only sprites whose rects overlap are
+        // compared.
+        let mut by_left: Vec<usize> = (0..n).collect();
+        by_left.sort_by(|&a, &b| {
+            let (a, b) = (&self.items[a].rect, &self.items[b].rect);
+            a.left().total_cmp(&b.left())
+        });
+        let mut in_front: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut behind_count = vec![0usize; n];
+        for (k, &a) in by_left.iter().enumerate() {
+            let a_rect = self.items[a].rect;
+            for &b in &by_left[k + 1..] {
+                let b_rect = self.items[b].rect;
+                if b_rect.left() >= a_rect.right() {
+                    break;
+                }
+                if b_rect.bottom() >= a_rect.top() || a_rect.bottom() >= b_rect.top() {
+                    continue;
+                }
+                let (back, front) = match self.items[a].bounds.draw_order(&self.items[b].bounds) {
+                    Some(Ordering::Less) => (a, b),
+                    Some(Ordering::Greater) => (b, a),
+                    _ => continue,
+                };
+                in_front[back].push(front);
+                behind_count[front] += 1;
+            }
+        }
+
+        // Kahn's algorithm, shallowest first among the sprites free to go.
+        let mut ready: BinaryHeap<Reverse<(usize, usize)>> = (0..n)
+            .filter(|&i| behind_count[i] == 0)
+            .map(|i| Reverse((rank[i], i)))
+            .collect();
+        let mut placed = vec![false; n];
+        let mut order = Vec::with_capacity(n);
+        let mut shallowest = 0;
+        while order.len() < n {
+            let next = match ready.pop() {
+                Some(Reverse((_, i))) => i,
+                None => {
+                    // A cycle: break it at its shallowest sprite.
+                    while placed[by_depth[shallowest]] {
+                        shallowest += 1;
+                    }
+                    by_depth[shallowest]
+                }
+            };
+            if placed[next] {
+                continue;
+            }
+            placed[next] = true;
+            order.push(next);
+            for &front in &in_front[next] {
+                behind_count[front] = behind_count[front].saturating_sub(1);
+                if behind_count[front] == 0 && !placed[front] {
+                    ready.push(Reverse((rank[front], front)));
+                }
+            }
+        }
+        order
+    }
+
+    /// Draw everything queued back to front, from `z_order` upwards, and
+    /// empty the queue.
+    pub fn flush(&mut self, frame: &mut Frame) {
+        let order = self.order();
+        let mut items: Vec<Option<IsoItem>> = self.items.drain(..).map(Some).collect();
+        for (z, i) in order.into_iter().enumerate() {
+            if let Some(item) = items[i].take() {
+                frame.draw_sprite(item.params.with_z_order(self.z_order + z as i32));
+            }
+        }
+    }
+}
+
+fn union(a: Rect, b: Rect) -> Rect {
+    let min = Vec2::new(a.left().min(b.left()), a.bottom().min(b.bottom()));
+    let max = Vec2::new(a.right().max(b.right()), a.top().max(b.top()));
+    Rect::from_pos_size(min, max - min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::TextureId;
+    use crate::world::tilemap::TileDef;
+
+    fn sprite(renderer: &IsoRenderer, bounds: &IsoBox) -> DrawParams {
+        let size = Vec2::new(24.0, 48.0);
+        DrawParams::new(TextureId(1), renderer.anchor(bounds), size)
+            .with_origin(Vec2::new(size.x / 2.0, 0.0))
+    }
+
+    /// Where each of the queued sprites lands in the draw order.
+    fn places(renderer: &IsoRenderer) -> Vec<usize> {
+        let order = renderer.order();
+        let mut places = vec![0; order.len()];
+        for (place, &i) in order.iter().enumerate() {
+            places[i] = place;
+        }
+        places
+    }
+
+    #[test]
+    fn characters_walk_behind_and_in_front_of_walls() {
+        let mut renderer = IsoRenderer::new(64.0, 32.0);
+        let wall = IsoBox::cell(5, 5, 0.0, 1.0);
+        let floor = IsoBox::cell(4, 5, 0.0, 0.0);
+        for (x, in_front) in [(4.3, false), (5.7, true)] {
+            let hero = IsoBox::standing(Vec2::new(x, 5.0), Vec2::splat(0.4), 0.0, 1.5);
+            renderer.add(wall, sprite(&renderer, &wall));
+            renderer.add(floor, sprite(&renderer, &floor));
+            renderer.add(hero, sprite(&renderer, &hero));
+            let places = places(&renderer);
+            assert_eq!(places[2] > places[0], in_front, "hero at col {x}");
+            assert!(places[1] < places[2]);
+            renderer.items.clear();
+        }
+
+        // Standing on top of the wall beats everything lower down.
+        let up = IsoBox::standing(Vec2::new(5.0, 5.0), Vec2::splat(0.4), 1.0, 1.5);
+        assert_eq!(up.draw_order(&wall), Some(Ordering::Greater));
+        assert_eq!(wall.draw_order(&up), Some(Ordering::Less));
+    }
+
+    #[test]
+    fn a_long_log_sorts_against_someone_walking_past_its_end() {
+        let mut renderer = IsoRenderer::new(64.0, 32.0);
+        // Five cells along the columns, one row deep.
+        let log = IsoBox::new(Vec3::new(1.5, 4.5, 0.0), Vec3::new(6.5, 5.5, 0.5));
+        // Just in front of the log's near end: its middle is shallower than
+        // the log's, so sorting by depth alone would draw it behind.
+        let hero = IsoBox::standing(Vec2::new(2.0, 5.9), Vec2::splat(0.4), 0.0, 1.5);
+        assert!(hero.depth() < log.depth());
+        renderer.add(log, sprite(&renderer, &log));
+        renderer.add(hero, sprite(&renderer, &hero));
+        assert_eq!(renderer.order(), vec![0, 1]);
+
+        // Just behind it, the other way round.
+        let hero = IsoBox::standing(Vec2::new(4.0, 4.1), Vec2::splat(0.4), 0.0, 1.5);
+        renderer.items.truncate(1);
+        renderer.add(hero, sprite(&renderer, &hero));
+        assert_eq!(renderer.order(), vec![1, 0]);
+    }
+
+    #[test]
+    fn tilemaps_stack_by_elevation_and_tile_height() {
+        let mut ground = TileMap::new(8, 8, 1.0);
+        let grass = ground.add_tile(TileDef::solid(TextureId(0)));
+        let wall = ground.add_tile(TileDef::solid(TextureId(0)).with_height(1.0));
+        for row in 0..8 {
+            for col in 0..8 {
+                ground.set(col, row, Some(grass));
+            }
+        }
+        ground.set(3, 3, Some(wall));
+        let mut upper = TileMap::new(8, 8, 1.0).with_elevation(1.0);
+        let roof = upper.add_tile(TileDef::solid(TextureId(0)));
+        upper.set(3, 3, Some(roof));
+
+        let mut renderer = IsoRenderer::new(64.0, 32.0).with_z_order(10);
+        let everything = Rect::new(-1000.0, -1000.0, 2000.0, 2000.0);
+        renderer.add_tilemap(&ground, everything);
+        renderer.add_tilemap(&upper, everything);
+        assert_eq!(renderer.len(), 65);
+        let hero = IsoBox::standing(Vec2::new(4.0, 3.0), Vec2::splat(0.4), 0.0, 1.5);
+        renderer.add(hero, sprite(&renderer, &hero));
+
+        let places = places(&renderer);
+        let (wall, roof, hero) = (places[3 * 8 + 3], places[64], places[65]);
+        assert!(wall < roof && wall < hero);
+
+        let mut frame = Frame::new();
+        renderer.flush(&mut frame);
+        assert!(renderer.is_empty());
+        let z: Vec<i32> = frame.sprites.iter().map(|sprite| sprite.z_order).collect();
+        assert_eq!(z, (10..76).collect::<Vec<_>>());
+        // The wall block is a diamond plus a level of side faces.
+        assert_eq!(frame.sprites[wall].size, Vec2::new(64.0, 64.0));
+        assert_eq!(frame.sprites[roof].size, Vec2::new(64.0, 32.0));
+
+        // A view over one corner of the map only queues the tiles under it.
+        let corner = renderer.silhouette(&IsoBox::cell(0, 0, 0.0, 0.0));
+        renderer.add_tilemap(&ground, corner);
+        assert!(renderer.len() < 10, "{} tiles", renderer.len());
+    }
+}
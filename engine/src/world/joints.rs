@@ -0,0 +1,633 @@
+use glam::{Mat2, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::world::dynamics::{RigidBodyId, SolverBody};
+use crate::world::physics::KinematicBody2D;
+
+pub type JointId = usize;
+
+/// Stretch (px) the position pass leaves uncorrected, so chains at rest do
+/// not jitter.
+const JOINT_SLOP: f32 = 0.05;
+/// Most position error (px) one correction pass removes, so a badly
+/// stretched joint snaps back over several steps instead of exploding.
+const MAX_JOINT_CORRECTION: f32 = 20.0;
+
+/// One end of a [`Joint2D`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum JointAnchor2D {
+    /// A point fixed to a body, relative to its centre of mass in the body's
+    /// unrotated frame. [`PhysicsWorld2D::body_anchor`] builds one from a
+    /// world-space point.
+    ///
+    /// [`PhysicsWorld2D::body_anchor`]: crate::world::dynamics::PhysicsWorld2D::body_anchor
+    Body { body: RigidBodyId, local: Vec2 },
+    /// A fixed point in the world. Move it between steps to drag the joint
+    /// along, e.g. to hang a chain from a [`KinematicBody2D`].
+    World(Vec2),
+}
+
+/// What a [`Joint2D`] enforces between its two anchors.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum JointKind2D {
+    /// Keeps the anchors exactly `length` apart, like a rigid rod.
+    Distance { length: f32 },
+    /// Keeps the anchors at most `max_length` apart; slack when closer.
+    Rope { max_length: f32 },
+    /// Pulls the anchors towards `rest_length` apart. `frequency` (Hz) sets
+    /// the stiffness independently of the bodies' masses; `damping_ratio` of
+    /// `1.0` stops the oscillation without overshoot.
+    Spring {
+        rest_length: f32,
+        frequency: f32,
+        damping_ratio: f32,
+    },
+    /// Pins the anchors together and lets the bodies rotate freely about the
+    /// shared point.
+    Hinge,
+}
+
+/// A constraint between two [`RigidBody2D`](crate::world::dynamics::RigidBody2D)s,
+/// or a body and a point in the world, stepped by
+/// [`PhysicsWorld2D`](crate::world::dynamics::PhysicsWorld2D) alongside its
+/// contacts.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Joint2D {
+    pub a: JointAnchor2D,
+    pub b: JointAnchor2D,
+    pub kind: JointKind2D,
+    /// Let the two connected bodies collide with each other. Off by default,
+    /// so links of a chain can overlap at the pivots.
+    pub collide_connected: bool,
+    impulse: Vec2,
+}
+
+impl Joint2D {
+    pub fn new(a: JointAnchor2D, b: JointAnchor2D, kind: JointKind2D) -> Self {
+        Self {
+            a,
+            b,
+            kind,
+            collide_connected: false,
+            impulse: Vec2::ZERO,
+        }
+    }
+
+    pub fn distance(a: JointAnchor2D, b: JointAnchor2D, length: f32) -> Self {
+        Self::new(a, b, JointKind2D::Distance { length })
+    }
+
+    pub fn rope(a: JointAnchor2D, b: JointAnchor2D, max_length: f32) -> Self {
+        Self::new(a, b, JointKind2D::Rope { max_length })
+    }
+
+    pub fn spring(
+        a: JointAnchor2D,
+        b: JointAnchor2D,
+        rest_length: f32,
+        frequency: f32,
+        damping_ratio: f32,
+    ) -> Self {
+        Self::new(
+            a,
+            b,
+            JointKind2D::Spring {
+                rest_length,
+                frequency,
+                damping_ratio,
+            },
+        )
+    }
+
+    pub fn hinge(a: JointAnchor2D, b: JointAnchor2D) -> Self {
+        Self::new(a, b, JointKind2D::Hinge)
+    }
+
+    pub fn with_collide_connected(mut self, collide: bool) -> Self {
+        self.collide_connected = collide;
+        self
+    }
+
+    /// The impulse the joint applied to its `b` end during the last step
+    /// (`a` received the opposite). Divide by `dt` for the force, e.g. to
+    /// break a rope under too much load.
+    pub fn impulse(&self) -> Vec2 {
+        self.impulse
+    }
+
+    /// The bodies this joint connects, in `(a, b)` order.
+    pub fn bodies(&self) -> (Option<RigidBodyId>, Option<RigidBodyId>) {
+        (self.a.body(), self.b.body())
+    }
+}
+
+impl JointAnchor2D {
+    pub fn body(&self) -> Option<RigidBodyId> {
+        match *self {
+            Self::Body { body, .. } => Some(body),
+            Self::World(_) => None,
+        }
+    }
+
+    /// World position of the anchor for a body at `position`, `rotation`.
+    pub(crate) fn placed(&self, position: Vec2, rotation: f32) -> Vec2 {
+        match *self {
+            Self::Body { local, .. } => position + Vec2::from_angle(rotation).rotate(local),
+            Self::World(point) => point,
+        }
+    }
+}
+
+/// Per-step solver data for one joint.
+pub(crate) struct JointRow {
+    joint: usize,
+    a: Option<RigidBodyId>,
+    b: Option<RigidBodyId>,
+    ra: Vec2,
+    rb: Vec2,
+    constraint: RowConstraint,
+}
+
+enum RowConstraint {
+    /// Distance, rope and spring: one impulse along `axis`.
+    Axial {
+        axis: Vec2,
+        mass: f32,
+        bias: f32,
+        gamma: f32,
+        impulse: f32,
+        /// Ropes only pull.
+        pull_only: bool,
+    },
+    Point {
+        mass: Mat2,
+        impulse: Vec2,
+    },
+}
+
+/// Build the solver rows for every joint whose bodies exist and warm-start
+/// them with last step's impulses.
+pub(crate) fn prepare_joints(
+    joints: &[Option<Joint2D>],
+    solver: &mut [SolverBody],
+    dt: f32,
+) -> Vec<JointRow> {
+    let mut rows = Vec::new();
+    for (index, joint) in joints.iter().enumerate() {
+        let Some(joint) = joint else {
+            continue;
+        };
+        let Some(ends) = JointEnds::new(joint, solver) else {
+            continue;
+        };
+        let constraint = match joint.kind {
+            JointKind2D::Hinge => RowConstraint::Point {
+                mass: ends.point_mass(),
+                impulse: joint.impulse,
+            },
+            kind => {
+                let delta = ends.pb - ends.pa;
+                let length = delta.length();
+                let axis = if length > 1e-4 {
+                    delta / length
+                } else {
+                    Vec2::Y
+                };
+                let k = ends.axial_mass(axis);
+                let mut mass = if k > 0.0 { 1.0 / k } else { 0.0 };
+                let (bias, gamma, pull_only) = match kind {
+                    JointKind2D::Distance { .. } => (0.0, 0.0, false),
+                    // While slack, let the ends separate up to the rope's
+                    // length this step but no further.
+                    JointKind2D::Rope { max_length } => {
+                        ((length - max_length).min(0.0) / dt, 0.0, true)
+                    }
+                    JointKind2D::Spring {
+                        rest_length,
+                        frequency,
+                        damping_ratio,
+                    } => {
+                        let omega = std::f32::consts::TAU * frequency.max(0.0);
+                        let stiffness = mass * omega * omega;
+                        let damping = 2.0 * mass * damping_ratio.max(0.0) * omega;
+                        let soft = dt * (damping + dt * stiffness);
+                        if soft <= 0.0 {
+                            continue;
+                        }
+                        let gamma = 1.0 / soft;
+                        let bias = (length - rest_length) * dt * stiffness * gamma;
+                        mass = 1.0 / (k + gamma);
+                        (bias, gamma, false)
+                    }
+                    JointKind2D::Hinge => unreachable!(),
+                };
+                let mut impulse = joint.impulse.dot(axis);
+                if pull_only {
+                    impulse = impulse.min(0.0);
+                }
+                RowConstraint::Axial {
+                    axis,
+                    mass,
+                    bias,
+                    gamma,
+                    impulse,
+                    pull_only,
+                }
+            }
+        };
+
+        let row = JointRow {
+            joint: index,
+            a: ends.a,
+            b: ends.b,
+            ra: ends.ra,
+            rb: ends.rb,
+            constraint,
+        };
+        row.apply(solver, row.total_impulse());
+        rows.push(row);
+    }
+    rows
+}
+
+/// One sequential-impulse pass over the joints.
+pub(crate) fn solve_joints(rows: &mut [JointRow], solver: &mut [SolverBody]) {
+    for row in rows {
+        let relative = row.relative_velocity(solver);
+        let applied = match &mut row.constraint {
+            RowConstraint::Axial {
+                axis,
+                mass,
+                bias,
+                gamma,
+                impulse,
+                pull_only,
+            } => {
+                let closing = relative.dot(*axis);
+                let delta = -*mass * (closing + *bias + *gamma * *impulse);
+                let mut total = *impulse + delta;
+                if *pull_only {
+                    total = total.min(0.0);
+                }
+                let applied = *axis * (total - *impulse);
+                *impulse = total;
+                applied
+            }
+            RowConstraint::Point { mass, impulse } => {
+                let delta = -(*mass * relative);
+                *impulse += delta;
+                delta
+            }
+        };
+        row.apply(solver, applied);
+    }
+}
+
+/// Pull rigid joints (distance, taut rope, hinge) back together after the
+/// positions were integrated, removing the drift a velocity solver lets
+/// build up as bodies swing. Springs are soft and left alone.
+pub(crate) fn correct_joint_positions(joints: &[Option<Joint2D>], solver: &mut [SolverBody]) {
+    for joint in joints.iter().flatten() {
+        let Some(ends) = JointEnds::new(joint, solver) else {
+            continue;
+        };
+        let delta = ends.pb - ends.pa;
+        let length = delta.length();
+        let axis = if length > 1e-4 {
+            delta / length
+        } else {
+            Vec2::Y
+        };
+        let correction = match joint.kind {
+            JointKind2D::Spring { .. } => continue,
+            JointKind2D::Hinge => {
+                let error = delta - delta.clamp_length_max(JOINT_SLOP);
+                -(ends.point_mass() * error.clamp_length_max(MAX_JOINT_CORRECTION))
+            }
+            JointKind2D::Distance { length: target } | JointKind2D::Rope { max_length: target } => {
+                let mut error = length - target;
+                if matches!(joint.kind, JointKind2D::Rope { .. }) {
+                    error = error.max(0.0);
+                }
+                let error = (error - error.clamp(-JOINT_SLOP, JOINT_SLOP))
+                    .clamp(-MAX_JOINT_CORRECTION, MAX_JOINT_CORRECTION);
+                let k = ends.axial_mass(axis);
+                axis * (if k > 0.0 { -error / k } else { 0.0 })
+            }
+        };
+        ends.shift(solver, correction);
+    }
+}
+
+/// Store the solved impulses back on the joints for next step's warm start.
+pub(crate) fn store_impulses(rows: &[JointRow], joints: &mut [Option<Joint2D>]) {
+    for row in rows {
+        if let Some(joint) = &mut joints[row.joint] {
+            joint.impulse = row.total_impulse();
+        }
+    }
+}
+
+/// A joint's two ends resolved against the solver bodies; world anchors
+/// stand in as immovable bodies.
+struct JointEnds {
+    a: Option<RigidBodyId>,
+    b: Option<RigidBodyId>,
+    body_a: SolverBody,
+    body_b: SolverBody,
+    pa: Vec2,
+    pb: Vec2,
+    ra: Vec2,
+    rb: Vec2,
+}
+
+impl JointEnds {
+    /// `None` when a body is missing or neither end can move.
+    fn new(joint: &Joint2D, solver: &[SolverBody]) -> Option<Self> {
+        let (a, b) = joint.bodies();
+        if a.is_some_and(|id| id >= solver.len()) || b.is_some_and(|id| id >= solver.len()) {
+            return None;
+        }
+        let body = |id: Option<RigidBodyId>| id.map_or(SolverBody::default(), |id| solver[id]);
+        let (body_a, body_b) = (body(a), body(b));
+        if body_a.inv_mass + body_b.inv_mass == 0.0
+            && body_a.inv_inertia == 0.0
+            && body_b.inv_inertia == 0.0
+        {
+            return None;
+        }
+        let pa = joint.a.placed(body_a.position, body_a.rotation);
+        let pb = joint.b.placed(body_b.position, body_b.rotation);
+        Some(Self {
+            a,
+            b,
+            body_a,
+            body_b,
+            pa,
+            pb,
+            ra: if a.is_some() {
+                pa - body_a.position
+            } else {
+                Vec2::ZERO
+            },
+            rb: if b.is_some() {
+                pb - body_b.position
+            } else {
+                Vec2::ZERO
+            },
+        })
+    }
+
+    /// Inverse effective mass along `axis`.
+    fn axial_mass(&self, axis: Vec2) -> f32 {
+        self.body_a.inv_mass
+            + self.body_b.inv_mass
+            + self.body_a.inv_inertia * self.ra.perp_dot(axis).powi(2)
+            + self.body_b.inv_inertia * self.rb.perp_dot(axis).powi(2)
+    }
+
+    /// Effective mass matrix for pinning the two anchors together.
+    fn point_mass(&self) -> Mat2 {
+        let (ra, rb) = (self.ra, self.rb);
+        let (ia, ib) = (self.body_a.inv_inertia, self.body_b.inv_inertia);
+        let m = self.body_a.inv_mass + self.body_b.inv_mass;
+        let cross = -ia * ra.x * ra.y - ib * rb.x * rb.y;
+        let k = Mat2::from_cols(
+            Vec2::new(m + ia * ra.y * ra.y + ib * rb.y * rb.y, cross),
+            Vec2::new(cross, m + ia * ra.x * ra.x + ib * rb.x * rb.x),
+        );
+        if k.determinant().abs() > f32::EPSILON {
+            k.inverse()
+        } else {
+            Mat2::ZERO
+        }
+    }
+
+    /// Move the ends apart by a position-level impulse `p` on `b`.
+    fn shift(&self, solver: &mut [SolverBody], p: Vec2) {
+        if let Some(a) = self.a {
+            let body = &mut solver[a];
+            body.position -= p * body.inv_mass;
+            body.rotation -= self.ra.perp_dot(p) * body.inv_inertia;
+        }
+        if let Some(b) = self.b {
+            let body = &mut solver[b];
+            body.position += p * body.inv_mass;
+            body.rotation += self.rb.perp_dot(p) * body.inv_inertia;
+        }
+    }
+}
+
+impl JointRow {
+    fn total_impulse(&self) -> Vec2 {
+        match self.constraint {
+            RowConstraint::Axial { axis, impulse, .. } => axis * impulse,
+            RowConstraint::Point { impulse, .. } => impulse,
+        }
+    }
+
+    fn relative_velocity(&self, solver: &[SolverBody]) -> Vec2 {
+        let velocity = |id: Option<RigidBodyId>, r: Vec2| {
+            id.map_or(Vec2::ZERO, |id| {
+                solver[id].velocity + r.perp() * solver[id].angular_velocity
+            })
+        };
+        velocity(self.b, self.rb) - velocity(self.a, self.ra)
+    }
+
+    fn apply(&self, solver: &mut [SolverBody], impulse: Vec2) {
+        if let Some(a) = self.a {
+            solver[a].apply(-impulse, self.ra);
+        }
+        if let Some(b) = self.b {
+            solver[b].apply(impulse, self.rb);
+        }
+    }
+}
+
+/// Constrain a kinematic controller's centre to `anchor` with `kind`, by
+/// adjusting its velocity before [`KinematicBody2D::step`] — a grappling
+/// hook, a leash or a swing. Distance, rope and hinge project the predicted
+/// position back onto the allowed range, so the body swings around the
+/// anchor and collisions still stop it; a spring accelerates it with the
+/// body treated as unit mass. The body never pushes back on the anchor.
+pub fn constrain_kinematic(dt: f32, body: &mut KinematicBody2D, anchor: Vec2, kind: JointKind2D) {
+    if dt <= 0.0 {
+        return;
+    }
+    let center = body.bounds.center();
+    // The velocity the body's next step will move with, gravity included.
+    let velocity = body.velocity + body.gravity * dt;
+    let predicted = center + velocity * dt;
+    let offset = predicted - anchor;
+    let length = offset.length();
+    let direction = if length > 1e-4 {
+        offset / length
+    } else {
+        Vec2::ZERO
+    };
+
+    let target = match kind {
+        JointKind2D::Distance { length: target } => anchor + direction * target,
+        JointKind2D::Rope { max_length } if length > max_length => anchor + direction * max_length,
+        JointKind2D::Rope { .. } => return,
+        JointKind2D::Hinge => anchor,
+        JointKind2D::Spring {
+            rest_length,
+            frequency,
+            damping_ratio,
+        } => {
+            let omega = std::f32::consts::TAU * frequency.max(0.0);
+            let to_body = center - anchor;
+            let distance = to_body.length();
+            if distance <= 1e-4 {
+                return;
+            }
+            let axis = to_body / distance;
+            let stretch = distance - rest_length;
+            let closing = body.velocity.dot(axis);
+            let accel = -(omega * omega * stretch + 2.0 * damping_ratio * omega * closing);
+            body.velocity += axis * accel * dt;
+            return;
+        }
+    };
+    body.velocity = (target - center) / dt - body.gravity * dt;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::rect::Rect;
+    use crate::world::dynamics::{PhysicsWorld2D, RigidBody2D};
+    use crate::world::shape::Shape2D;
+
+    const STEP: f32 = 1.0 / 60.0;
+
+    fn ball(x: f32, y: f32) -> RigidBody2D {
+        RigidBody2D::dynamic(Shape2D::circle(Vec2::new(x, y), 5.0), 1.0)
+    }
+
+    fn centre(id: RigidBodyId) -> JointAnchor2D {
+        JointAnchor2D::Body {
+            body: id,
+            local: Vec2::ZERO,
+        }
+    }
+
+    fn run(world: &mut PhysicsWorld2D, steps: usize) {
+        for _ in 0..steps {
+            world.step(STEP, &[]);
+        }
+    }
+
+    #[test]
+    fn distance_joint_swings_a_pendulum_at_fixed_length() {
+        let mut world = PhysicsWorld2D::default();
+        let bob = world.insert(ball(100.0, 0.0));
+        world.insert_joint(Joint2D::distance(
+            JointAnchor2D::World(Vec2::ZERO),
+            centre(bob),
+            100.0,
+        ));
+
+        let mut lowest = f32::INFINITY;
+        for _ in 0..120 {
+            run(&mut world, 1);
+            let position = world.get(bob).unwrap().position;
+            assert!((position.length() - 100.0).abs() < 1.0, "{position}");
+            lowest = lowest.min(position.y);
+        }
+        assert!(lowest < -95.0);
+    }
+
+    #[test]
+    fn rope_is_slack_until_it_runs_out() {
+        let mut world = PhysicsWorld2D::default();
+        let bob = world.insert(ball(0.0, -20.0));
+        world.insert_joint(Joint2D::rope(
+            JointAnchor2D::World(Vec2::ZERO),
+            centre(bob),
+            80.0,
+        ));
+
+        run(&mut world, 6);
+        // Free fall for the first few steps: well short of the rope length.
+        let falling = world.get(bob).unwrap();
+        assert!(falling.position.y < -20.0 && falling.position.y > -80.0);
+        assert!(falling.velocity.y < -50.0);
+
+        run(&mut world, 120);
+        let hanging = world.get(bob).unwrap().position;
+        assert!((hanging.y + 80.0).abs() < 1.0, "{hanging}");
+    }
+
+    #[test]
+    fn spring_settles_at_its_stretched_rest_length() {
+        let mut world = PhysicsWorld2D::default();
+        let bob = world.insert(ball(0.0, -50.0));
+        let joint = world.insert_joint(Joint2D::spring(
+            JointAnchor2D::World(Vec2::ZERO),
+            centre(bob),
+            50.0,
+            2.0,
+            0.7,
+        ));
+
+        run(&mut world, 600);
+        let y = world.get(bob).unwrap().position.y;
+        // Gravity stretches it past rest: k * x = m * g with k = m * omega^2.
+        let omega = std::f32::consts::TAU * 2.0;
+        let sag = 980.0 / (omega * omega);
+        assert!((y + 50.0 + sag).abs() < 1.0, "{y}");
+        let load = world.joint(joint).unwrap().impulse().length() / STEP;
+        let mass = world.get(bob).unwrap().mass();
+        assert!((load - mass * 980.0).abs() < mass * 980.0 * 0.05);
+    }
+
+    #[test]
+    fn hinge_chain_hangs_straight_down_without_self_collision() {
+        let mut world = PhysicsWorld2D::default();
+        let mut previous = JointAnchor2D::World(Vec2::ZERO);
+        let mut links = Vec::new();
+        for i in 0..5 {
+            let x = 10.0 + i as f32 * 20.0;
+            let link = world.insert(
+                RigidBody2D::dynamic(
+                    Shape2D::oriented_box(Vec2::new(x, 0.0), Vec2::new(12.0, 3.0), 0.0),
+                    1.0,
+                )
+                .with_damping(2.0, 2.0),
+            );
+            let hinge_point = Vec2::new(x - 10.0, 0.0);
+            let anchor = world.body_anchor(link, hinge_point).unwrap();
+            world.insert_joint(Joint2D::hinge(previous, anchor));
+            previous = world.body_anchor(link, Vec2::new(x + 10.0, 0.0)).unwrap();
+            links.push(link);
+        }
+
+        run(&mut world, 600);
+        let tip = world.get(*links.last().unwrap()).unwrap().position;
+        assert!(tip.x.abs() < 2.0, "{tip}");
+        assert!((tip.y + 90.0).abs() < 3.0, "{tip}");
+        assert_eq!(world.contacts().count(), 0);
+    }
+
+    #[test]
+    fn kinematic_body_swings_on_a_rope() {
+        let mut body = KinematicBody2D::new(Rect::new(95.0, -5.0, 10.0, 10.0));
+        let anchor = Vec2::ZERO;
+        let rope = JointKind2D::Rope { max_length: 100.0 };
+        let floor = [Rect::new(-500.0, -400.0, 1000.0, 10.0)];
+
+        let mut lowest = f32::INFINITY;
+        for _ in 0..60 {
+            constrain_kinematic(STEP, &mut body, anchor, rope);
+            body.step(STEP, &floor);
+            let center = body.bounds.center();
+            assert!(center.length() < 100.5, "{center}");
+            lowest = lowest.min(center.y);
+        }
+        assert!(lowest < -95.0);
+        assert!(body.bounds.center().x < 50.0);
+    }
+}
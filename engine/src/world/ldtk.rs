@@ -0,0 +1,1133 @@
+//! Importer for projects made in the [LDtk](https://ldtk.io) level editor.
+//!
+//! [`LdtkProject::parse`] reads a `.ldtk` project into a close mirror of the
+//! file: tilesets, IntGrid value names and every level with its layers,
+//! entities, fields and neighbours, all still in LDtk's y-down pixels.
+//! [`LdtkProject::build_level`] then turns one level into engine types in
+//! world space — a [`TileMap`] per tile or auto-layer, [`Solid2D`]s for the
+//! IntGrid cells and a [`SceneWorld2D`] holding one [`SceneNode2D`] per entity
+//! — so a game can build the levels near the camera and drop the rest.
+//!
+//! World space is LDtk's world flipped upright: `y = -worldY`, so levels keep
+//! their layout and a level at the world origin hangs below `y = 0`.
+//!
+//! Projects are normally loaded through the asset pipeline (`ldtk_projects`
+//! in an asset manifest, or `Engine::load_ldtk_project`), which reads external
+//! level files, loads tileset images and hot-reloads the project on save.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use glam::Vec2;
+use serde_json::Value;
+
+use crate::assets::{AssetError, Color, SpriteSheet};
+use crate::math::rect::Rect;
+use crate::renderer::Frame;
+use crate::scene::{PrefabSprite2D, SceneNode2D, SceneWorld2D};
+use crate::world::physics::Solid2D;
+use crate::world::tilemap::{TileDef, TileMap};
+
+/// An image tileset. LDtk's built-in icon atlas is skipped, since it has no
+/// image file to load.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LdtkTileset {
+    pub uid: i64,
+    pub identifier: String,
+    /// Image path relative to the project file.
+    pub rel_path: String,
+    pub px_width: u32,
+    pub px_height: u32,
+    pub grid_size: u32,
+    pub spacing: u32,
+    pub padding: u32,
+}
+
+/// A named IntGrid value from a layer definition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LdtkIntGridValue {
+    pub value: i32,
+    pub identifier: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LdtkLayerKind {
+    IntGrid,
+    Entities,
+    Tiles,
+    AutoLayer,
+}
+
+/// A placed tile, in the layer's y-down pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LdtkTile {
+    pub px: [i32; 2],
+    /// Top-left pixel of the tile in the tileset image.
+    pub src: [u32; 2],
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub alpha: f32,
+}
+
+/// A pixel rectangle of a tileset image, as used by entity and field tiles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LdtkTileRect {
+    pub tileset_uid: i64,
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+/// A field value. Colors, enums, file paths and multi-line text are strings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LdtkValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    /// A grid cell of the layer the field's entity is on, or of the level's
+    /// default grid for level fields.
+    Point {
+        cx: i32,
+        cy: i32,
+    },
+    EntityRef {
+        entity_iid: String,
+        level_iid: String,
+    },
+    Tile(LdtkTileRect),
+    Array(Vec<LdtkValue>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LdtkField {
+    pub name: String,
+    pub value: LdtkValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LdtkEntity {
+    pub identifier: String,
+    pub iid: String,
+    /// The pivot point, in the layer's y-down pixels.
+    pub px: [i32; 2],
+    pub pivot: Vec2,
+    pub width: u32,
+    pub height: u32,
+    pub tags: Vec<String>,
+    pub tile: Option<LdtkTileRect>,
+    pub fields: Vec<LdtkField>,
+}
+
+impl LdtkEntity {
+    pub fn field(&self, name: &str) -> Option<&LdtkValue> {
+        field(&self.fields, name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LdtkLayer {
+    pub identifier: String,
+    pub kind: LdtkLayerKind,
+    pub layer_def_uid: i64,
+    /// Size in cells.
+    pub width: usize,
+    pub height: usize,
+    pub grid_size: u32,
+    /// Total pixel offset of the layer within its level.
+    pub offset: [i32; 2],
+    pub visible: bool,
+    pub opacity: f32,
+    pub tileset_uid: Option<i64>,
+    /// Row-major IntGrid values, top row first; `0` is empty.
+    pub int_grid: Vec<i32>,
+    /// Painted tiles, or the tiles an auto-layer's rules placed, bottom first.
+    pub tiles: Vec<LdtkTile>,
+    pub entities: Vec<LdtkEntity>,
+}
+
+impl LdtkLayer {
+    /// The IntGrid value at a cell, counting rows down from the top as LDtk
+    /// does; `0` when empty or out of range.
+    pub fn int_at(&self, col: usize, row: usize) -> i32 {
+        if col < self.width && row < self.height {
+            self.int_grid
+                .get(row * self.width + col)
+                .copied()
+                .unwrap_or(0)
+        } else {
+            0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LdtkDirection {
+    North,
+    South,
+    East,
+    West,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+    /// The neighbour sits at a lower world depth.
+    Below,
+    /// The neighbour sits at a higher world depth.
+    Above,
+    /// The neighbour overlaps this level.
+    Overlap,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LdtkNeighbour {
+    pub level_iid: String,
+    pub dir: LdtkDirection,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LdtkLevel {
+    pub identifier: String,
+    pub iid: String,
+    pub uid: i64,
+    /// Top-left corner in LDtk's y-down world pixels.
+    pub world_x: i32,
+    pub world_y: i32,
+    pub world_depth: i32,
+    pub width: u32,
+    pub height: u32,
+    pub neighbours: Vec<LdtkNeighbour>,
+    pub fields: Vec<LdtkField>,
+    /// Layers in LDtk's order, top layer first. Empty until loaded when the
+    /// project saves levels to separate files.
+    pub layers: Vec<LdtkLayer>,
+    /// The level's own file, relative to the project, for projects saved with
+    /// "separate level files".
+    pub external_path: Option<String>,
+}
+
+impl LdtkLevel {
+    /// Parse a separate level file (`.ldtkl`).
+    pub fn parse(path: &Path, text: &str) -> Result<Self, AssetError> {
+        let value: Value = serde_json::from_str(text)
+            .map_err(|error| AssetError::map_message(path, error.to_string()))?;
+        Ok(json_level(&value))
+    }
+
+    /// World-space bounds.
+    pub fn bounds(&self) -> Rect {
+        Rect::new(
+            self.world_x as f32,
+            -(self.world_y as f32 + self.height as f32),
+            self.width as f32,
+            self.height as f32,
+        )
+    }
+
+    pub fn layer(&self, identifier: &str) -> Option<&LdtkLayer> {
+        self.layers
+            .iter()
+            .find(|layer| layer.identifier == identifier)
+    }
+
+    pub fn field(&self, name: &str) -> Option<&LdtkValue> {
+        field(&self.fields, name)
+    }
+
+    /// Whether the level's layers have been read yet.
+    pub fn is_loaded(&self) -> bool {
+        self.external_path.is_none() || !self.layers.is_empty()
+    }
+
+    /// A point in this level's y-down pixels, in world space.
+    pub fn to_world(&self, px: Vec2) -> Vec2 {
+        Vec2::new(self.world_x as f32 + px.x, -(self.world_y as f32 + px.y))
+    }
+}
+
+/// An LDtk project: tilesets, IntGrid value names and every level.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LdtkProject {
+    pub tilesets: Vec<LdtkTileset>,
+    /// IntGrid value names, keyed by layer definition uid.
+    pub int_grid_values: HashMap<i64, Vec<LdtkIntGridValue>>,
+    pub levels: Vec<LdtkLevel>,
+}
+
+impl LdtkProject {
+    /// Parse a `.ldtk` project. Levels saved to separate files keep empty
+    /// layers until [`LdtkLevel::parse`] reads them.
+    pub fn parse(path: &Path, text: &str) -> Result<Self, AssetError> {
+        let value: Value = serde_json::from_str(text)
+            .map_err(|error| AssetError::map_message(path, error.to_string()))?;
+        json_project(&value).map_err(|message| AssetError::map_message(path, message))
+    }
+
+    pub fn level(&self, identifier: &str) -> Option<&LdtkLevel> {
+        self.levels
+            .iter()
+            .find(|level| level.identifier == identifier)
+    }
+
+    pub fn level_by_iid(&self, iid: &str) -> Option<&LdtkLevel> {
+        self.levels.iter().find(|level| level.iid == iid)
+    }
+
+    /// The level containing a world-space point. Where levels overlap, the
+    /// one at the highest world depth wins.
+    pub fn level_at(&self, point: Vec2) -> Option<&LdtkLevel> {
+        self.levels
+            .iter()
+            .filter(|level| level.bounds().contains_point(point))
+            .max_by_key(|level| level.world_depth)
+    }
+
+    /// Levels whose bounds overlap a world-space rect, such as the camera view
+    /// grown by a streaming margin.
+    pub fn levels_in(&self, rect: Rect) -> impl Iterator<Item = &LdtkLevel> + '_ {
+        self.levels
+            .iter()
+            .filter(move |level| level.bounds().overlaps(&rect))
+    }
+
+    /// A level's neighbours that exist in this project.
+    pub fn neighbours<'a>(
+        &'a self,
+        level: &'a LdtkLevel,
+    ) -> impl Iterator<Item = (&'a LdtkLevel, LdtkDirection)> + 'a {
+        level.neighbours.iter().filter_map(|neighbour| {
+            self.level_by_iid(&neighbour.level_iid)
+                .map(|level| (level, neighbour.dir))
+        })
+    }
+
+    /// Build one level into world-space engine types. `sheets` holds one
+    /// sprite sheet per entry in `tilesets`; tiles from a tileset without a
+    /// sheet are left out, but collision and entities are still built.
+    ///
+    /// Every non-zero IntGrid cell collides, merged into as few solids as
+    /// possible. Values named `one_way` or `platform` become one-way solids,
+    /// and values named `none` or `decor` do not collide at all.
+    pub fn build_level(&self, level: &LdtkLevel, sheets: &[SpriteSheet]) -> LdtkLevelScene {
+        let mut built = LdtkLevelScene {
+            identifier: level.identifier.clone(),
+            iid: level.iid.clone(),
+            bounds: level.bounds(),
+            layers: Vec::new(),
+            solids: Vec::new(),
+            scene: SceneWorld2D::new(),
+        };
+        let sheets: HashMap<i64, (&LdtkTileset, &SpriteSheet)> = self
+            .tilesets
+            .iter()
+            .zip(sheets)
+            .map(|(tileset, sheet)| (tileset.uid, (tileset, sheet)))
+            .collect();
+
+        // LDtk lists layers top first.
+        for layer in level.layers.iter().rev() {
+            let top_left =
+                level.to_world(Vec2::new(layer.offset[0] as f32, layer.offset[1] as f32));
+            let grid = layer.grid_size as f32;
+            match layer.kind {
+                LdtkLayerKind::Entities => {
+                    for entity in &layer.entities {
+                        let node = entity_node(entity, layer, top_left, &sheets);
+                        built.scene.spawn(node);
+                    }
+                }
+                LdtkLayerKind::IntGrid => {
+                    let names = self.int_grid_values.get(&layer.layer_def_uid);
+                    let name_of = |value: i32| {
+                        names
+                            .and_then(|names| names.iter().find(|named| named.value == value))
+                            .map(|named| named.identifier.to_ascii_lowercase())
+                            .unwrap_or_default()
+                    };
+                    let mut kinds: HashMap<i32, Option<bool>> = HashMap::new();
+                    for &value in &layer.int_grid {
+                        kinds
+                            .entry(value)
+                            .or_insert_with(|| match name_of(value).as_str() {
+                                _ if value == 0 => None,
+                                "none" | "decor" => None,
+                                "one_way" | "oneway" | "platform" => Some(true),
+                                _ => Some(false),
+                            });
+                    }
+                    for one_way in [false, true] {
+                        let solid = |col: usize, row: usize| {
+                            kinds.get(&layer.int_at(col, row)).copied().flatten() == Some(one_way)
+                        };
+                        // One-way cells merge along rows only, so every solid
+                        // keeps a walkable top.
+                        for (col, row, w, h) in
+                            merge_cells(layer.width, layer.height, !one_way, solid)
+                        {
+                            let rect = Rect::new(
+                                top_left.x + col as f32 * grid,
+                                top_left.y - (row + h) as f32 * grid,
+                                w as f32 * grid,
+                                h as f32 * grid,
+                            );
+                            built.solids.push(if one_way {
+                                Solid2D::one_way(rect)
+                            } else {
+                                Solid2D::solid(rect)
+                            });
+                        }
+                    }
+                }
+                LdtkLayerKind::Tiles | LdtkLayerKind::AutoLayer => {}
+            }
+            // IntGrid layers can carry auto-layer tiles too.
+            if layer.tiles.is_empty() {
+                continue;
+            }
+            let Some(&(tileset, sheet)) = layer.tileset_uid.and_then(|uid| sheets.get(&uid)) else {
+                continue;
+            };
+            let origin = Vec2::new(top_left.x, top_left.y - layer.height as f32 * grid);
+            built.layers.extend(
+                tile_maps(layer, tileset, sheet, origin)
+                    .into_iter()
+                    .map(|map| LdtkLayerMap {
+                        name: layer.identifier.clone(),
+                        kind: layer.kind,
+                        visible: layer.visible,
+                        map,
+                    }),
+            );
+        }
+        built
+    }
+}
+
+/// A tile or auto-layer built into a [`TileMap`]. Cells holding stacked
+/// tiles spill the upper tiles into further maps with the same name, placed
+/// just after it.
+pub struct LdtkLayerMap {
+    pub name: String,
+    pub kind: LdtkLayerKind,
+    pub visible: bool,
+    pub map: TileMap,
+}
+
+/// One [`LdtkLevel`] converted to world-space engine types.
+///
+/// Each entity is a [`SceneNode2D`] named by its iid, with the entity
+/// identifier as its prefab, LDtk tags as node tags, its box as the node's
+/// position (bottom-left corner) and size, and each field as a property.
+/// Points become `"x,y"` world positions at the cell centre, entity
+/// references the target's iid, arrays comma-separated items; null fields
+/// are left unset. Entities with a tile get it as their sprite.
+pub struct LdtkLevelScene {
+    pub identifier: String,
+    pub iid: String,
+    pub bounds: Rect,
+    /// Tile layers, bottom layer first.
+    pub layers: Vec<LdtkLayerMap>,
+    pub solids: Vec<Solid2D>,
+    pub scene: SceneWorld2D,
+}
+
+impl LdtkLevelScene {
+    pub fn layer(&self, name: &str) -> Option<&TileMap> {
+        self.layers
+            .iter()
+            .find(|layer| layer.name == name)
+            .map(|layer| &layer.map)
+    }
+
+    /// Draw the visible tile layers bottom first, then the entities.
+    pub fn draw(&self, frame: &mut Frame) {
+        for layer in self.layers.iter().filter(|layer| layer.visible) {
+            layer.map.draw(frame);
+        }
+        self.scene.draw(frame);
+    }
+}
+
+fn field<'a>(fields: &'a [LdtkField], name: &str) -> Option<&'a LdtkValue> {
+    fields
+        .iter()
+        .find(|field| field.name == name)
+        .map(|field| &field.value)
+}
+
+fn tile_uv(tileset: &LdtkTileset, x: u32, y: u32, w: u32, h: u32) -> [f32; 4] {
+    let (tw, th) = (
+        tileset.px_width.max(1) as f32,
+        tileset.px_height.max(1) as f32,
+    );
+    [x as f32 / tw, y as f32 / th, w as f32 / tw, h as f32 / th]
+}
+
+/// A placed tile's look: source pixel, flips and alpha bits.
+type TileKey = ([u32; 2], bool, bool, u32);
+
+fn tile_maps(
+    layer: &LdtkLayer,
+    tileset: &LdtkTileset,
+    sheet: &SpriteSheet,
+    origin: Vec2,
+) -> Vec<TileMap> {
+    let grid = layer.grid_size.max(1);
+    let new_map = || TileMap::new(layer.width, layer.height, grid as f32).with_origin(origin);
+    let mut maps = vec![new_map()];
+    // Per map, the TileDef id of each distinct tile look.
+    let mut ids: Vec<HashMap<TileKey, usize>> = vec![HashMap::new()];
+    let mut depth: HashMap<(usize, usize), usize> = HashMap::new();
+
+    for tile in &layer.tiles {
+        if tile.px[0] < 0 || tile.px[1] < 0 {
+            continue;
+        }
+        let col = tile.px[0] as usize / grid as usize;
+        let row_down = tile.px[1] as usize / grid as usize;
+        if col >= layer.width || row_down >= layer.height {
+            continue;
+        }
+        let row = layer.height - 1 - row_down;
+        let alpha = tile.alpha * layer.opacity;
+
+        let level = depth.entry((col, row)).or_insert(0);
+        if *level == maps.len() {
+            maps.push(new_map());
+            ids.push(HashMap::new());
+        }
+        let map = &mut maps[*level];
+        let key = (tile.src, tile.flip_x, tile.flip_y, alpha.to_bits());
+        let id = *ids[*level].entry(key).or_insert_with(|| {
+            let size = tileset.grid_size;
+            map.add_tile(
+                TileDef::colored(sheet.texture, Color::new(1.0, 1.0, 1.0, alpha))
+                    .with_uv(tile_uv(tileset, tile.src[0], tile.src[1], size, size))
+                    .with_flip(tile.flip_x, tile.flip_y)
+                    .with_collision(false),
+            )
+        });
+        map.set(col, row, Some(id));
+        *level += 1;
+    }
+    maps
+}
+
+fn entity_node(
+    entity: &LdtkEntity,
+    layer: &LdtkLayer,
+    top_left: Vec2,
+    sheets: &HashMap<i64, (&LdtkTileset, &SpriteSheet)>,
+) -> SceneNode2D {
+    let size = Vec2::new(entity.width as f32, entity.height as f32);
+    let left = entity.px[0] as f32 - entity.pivot.x * size.x;
+    let bottom = entity.px[1] as f32 - entity.pivot.y * size.y + size.y;
+    let position = top_left + Vec2::new(left, -bottom);
+
+    let mut node = SceneNode2D::new(entity.identifier.clone())
+        .with_name(entity.iid.clone())
+        .with_position(position);
+    node.set_size(size);
+    node.set_visible(layer.visible);
+    node.set_property("layer", layer.identifier.clone());
+    for tag in &entity.tags {
+        node.add_tag(tag.clone());
+    }
+    let grid = layer.grid_size as f32;
+    let cell = |cx: i32, cy: i32| top_left + Vec2::new(cx as f32 + 0.5, -(cy as f32 + 0.5)) * grid;
+    for field in &entity.fields {
+        if let Some(value) = property_string(&field.value, &cell) {
+            node.set_property(field.name.clone(), value);
+        }
+    }
+    if let Some(tile) = entity.tile {
+        if let Some(&(tileset, sheet)) = sheets.get(&tile.tileset_uid) {
+            node.set_sprites(vec![PrefabSprite2D {
+                texture: sheet.texture,
+                offset: Vec2::ZERO,
+                size,
+                color: Color::WHITE,
+                uv_rect: tile_uv(tileset, tile.x, tile.y, tile.w, tile.h),
+                flip_x: false,
+                flip_y: false,
+            }]);
+        }
+    }
+    node
+}
+
+fn property_string(value: &LdtkValue, cell: &dyn Fn(i32, i32) -> Vec2) -> Option<String> {
+    Some(match value {
+        LdtkValue::Null => return None,
+        LdtkValue::Bool(value) => value.to_string(),
+        LdtkValue::Int(value) => value.to_string(),
+        LdtkValue::Float(value) => value.to_string(),
+        LdtkValue::String(value) => value.clone(),
+        LdtkValue::Point { cx, cy } => {
+            let point = cell(*cx, *cy);
+            format!("{},{}", point.x, point.y)
+        }
+        LdtkValue::EntityRef { entity_iid, .. } => entity_iid.clone(),
+        LdtkValue::Tile(tile) => format!("{},{},{},{}", tile.x, tile.y, tile.w, tile.h),
+        LdtkValue::Array(items) => items
+            .iter()
+            .filter_map(|item| property_string(item, cell))
+            .collect::<Vec<_>>()
+            .join(","),
+    })
+}
+
+/// Cover the cells where `filled` holds with rects `(col, row, w, h)`, rows
+/// counted down from the top. Runs along each row merge first; with
+/// `vertical`, identical runs in consecutive rows then merge into one rect.
+fn merge_cells(
+    width: usize,
+    height: usize,
+    vertical: bool,
+    filled: impl Fn(usize, usize) -> bool,
+) -> Vec<(usize, usize, usize, usize)> {
+    let mut rects: Vec<(usize, usize, usize, usize)> = Vec::new();
+    // Rects still growing downward, keyed by their column span.
+    let mut open: HashMap<(usize, usize), usize> = HashMap::new();
+    for row in 0..height {
+        let mut next_open = HashMap::new();
+        let mut col = 0;
+        while col < width {
+            if !filled(col, row) {
+                col += 1;
+                continue;
+            }
+            let start = col;
+            while col < width && filled(col, row) {
+                col += 1;
+            }
+            let span = (start, col - start);
+            match open.get(&span) {
+                Some(&index) if vertical => {
+                    rects[index].3 += 1;
+                    next_open.insert(span, index);
+                }
+                _ => {
+                    next_open.insert(span, rects.len());
+                    rects.push((start, row, col - start, 1));
+                }
+            }
+        }
+        open = next_open;
+    }
+    rects
+}
+
+// --- JSON ---
+
+fn json_str(value: &Value, key: &str) -> String {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn json_i64(value: &Value, key: &str) -> i64 {
+    value.get(key).and_then(Value::as_i64).unwrap_or(0)
+}
+
+fn json_f32(value: &Value, key: &str, default: f32) -> f32 {
+    value
+        .get(key)
+        .and_then(Value::as_f64)
+        .map_or(default, |value| value as f32)
+}
+
+fn json_array<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn json_pair(value: &Value, key: &str) -> [i32; 2] {
+    let pair = json_array(value, key);
+    let at = |index: usize| pair.get(index).and_then(Value::as_i64).unwrap_or(0) as i32;
+    [at(0), at(1)]
+}
+
+fn json_value(value: &Value) -> LdtkValue {
+    match value {
+        Value::Null => LdtkValue::Null,
+        Value::Bool(value) => LdtkValue::Bool(*value),
+        Value::Number(number) => match number.as_i64() {
+            Some(int) => LdtkValue::Int(int),
+            None => LdtkValue::Float(number.as_f64().unwrap_or(0.0)),
+        },
+        Value::String(text) => LdtkValue::String(text.clone()),
+        Value::Array(items) => LdtkValue::Array(items.iter().map(json_value).collect()),
+        Value::Object(_) if value.get("cx").is_some() => LdtkValue::Point {
+            cx: json_i64(value, "cx") as i32,
+            cy: json_i64(value, "cy") as i32,
+        },
+        Value::Object(_) if value.get("entityIid").is_some() => LdtkValue::EntityRef {
+            entity_iid: json_str(value, "entityIid"),
+            level_iid: json_str(value, "levelIid"),
+        },
+        Value::Object(_) if value.get("tilesetUid").is_some() => {
+            LdtkValue::Tile(json_tile_rect(value))
+        }
+        Value::Object(_) => LdtkValue::String(value.to_string()),
+    }
+}
+
+fn json_fields(value: &Value) -> Vec<LdtkField> {
+    json_array(value, "fieldInstances")
+        .iter()
+        .map(|field| {
+            let raw = field.get("__value").unwrap_or(&Value::Null);
+            // Whole-number floats are written without a fraction.
+            let value = match (json_value(raw), json_str(field, "__type").contains("Float")) {
+                (LdtkValue::Int(int), true) => LdtkValue::Float(int as f64),
+                (LdtkValue::Array(items), true) => LdtkValue::Array(
+                    items
+                        .into_iter()
+                        .map(|item| match item {
+                            LdtkValue::Int(int) => LdtkValue::Float(int as f64),
+                            item => item,
+                        })
+                        .collect(),
+                ),
+                (value, _) => value,
+            };
+            LdtkField {
+                name: json_str(field, "__identifier"),
+                value,
+            }
+        })
+        .collect()
+}
+
+fn json_tile_rect(value: &Value) -> LdtkTileRect {
+    let u = |key: &str| json_i64(value, key).max(0) as u32;
+    LdtkTileRect {
+        tileset_uid: json_i64(value, "tilesetUid"),
+        x: u("x"),
+        y: u("y"),
+        w: u("w"),
+        h: u("h"),
+    }
+}
+
+fn json_tiles(value: &Value, key: &str) -> Vec<LdtkTile> {
+    json_array(value, key)
+        .iter()
+        .map(|tile| {
+            let flags = json_i64(tile, "f");
+            let src = json_pair(tile, "src");
+            LdtkTile {
+                px: json_pair(tile, "px"),
+                src: [src[0].max(0) as u32, src[1].max(0) as u32],
+                flip_x: flags & 1 != 0,
+                flip_y: flags & 2 != 0,
+                alpha: json_f32(tile, "a", 1.0),
+            }
+        })
+        .collect()
+}
+
+fn json_entity(value: &Value) -> LdtkEntity {
+    let pivot = json_array(value, "__pivot");
+    let pivot_at = |index: usize| pivot.get(index).and_then(Value::as_f64).unwrap_or(0.0) as f32;
+    LdtkEntity {
+        identifier: json_str(value, "__identifier"),
+        iid: json_str(value, "iid"),
+        px: json_pair(value, "px"),
+        pivot: Vec2::new(pivot_at(0), pivot_at(1)),
+        width: json_i64(value, "width").max(0) as u32,
+        height: json_i64(value, "height").max(0) as u32,
+        tags: json_array(value, "__tags")
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        tile: value
+            .get("__tile")
+            .filter(|tile| tile.is_object())
+            .map(json_tile_rect),
+        fields: json_fields(value),
+    }
+}
+
+fn json_layer(value: &Value) -> LdtkLayer {
+    let kind = match json_str(value, "__type").as_str() {
+        "IntGrid" => LdtkLayerKind::IntGrid,
+        "Entities" => LdtkLayerKind::Entities,
+        "Tiles" => LdtkLayerKind::Tiles,
+        _ => LdtkLayerKind::AutoLayer,
+    };
+    let mut tiles = json_tiles(value, "autoLayerTiles");
+    tiles.extend(json_tiles(value, "gridTiles"));
+    LdtkLayer {
+        identifier: json_str(value, "__identifier"),
+        kind,
+        layer_def_uid: json_i64(value, "layerDefUid"),
+        width: json_i64(value, "__cWid").max(0) as usize,
+        height: json_i64(value, "__cHei").max(0) as usize,
+        grid_size: json_i64(value, "__gridSize").max(1) as u32,
+        offset: [
+            json_i64(value, "__pxTotalOffsetX") as i32,
+            json_i64(value, "__pxTotalOffsetY") as i32,
+        ],
+        visible: value
+            .get("visible")
+            .and_then(Value::as_bool)
+            .unwrap_or(true),
+        opacity: json_f32(value, "__opacity", 1.0),
+        tileset_uid: value.get("__tilesetDefUid").and_then(Value::as_i64),
+        int_grid: json_array(value, "intGridCsv")
+            .iter()
+            .map(|cell| cell.as_i64().unwrap_or(0) as i32)
+            .collect(),
+        tiles,
+        entities: json_array(value, "entityInstances")
+            .iter()
+            .map(json_entity)
+            .collect(),
+    }
+}
+
+fn json_direction(dir: &str) -> Option<LdtkDirection> {
+    Some(match dir {
+        "n" => LdtkDirection::North,
+        "s" => LdtkDirection::South,
+        "e" => LdtkDirection::East,
+        "w" => LdtkDirection::West,
+        "ne" => LdtkDirection::NorthEast,
+        "nw" => LdtkDirection::NorthWest,
+        "se" => LdtkDirection::SouthEast,
+        "sw" => LdtkDirection::SouthWest,
+        "<" => LdtkDirection::Below,
+        ">" => LdtkDirection::Above,
+        "o" => LdtkDirection::Overlap,
+        _ => return None,
+    })
+}
+
+fn json_level(value: &Value) -> LdtkLevel {
+    LdtkLevel {
+        identifier: json_str(value, "identifier"),
+        iid: json_str(value, "iid"),
+        uid: json_i64(value, "uid"),
+        world_x: json_i64(value, "worldX") as i32,
+        world_y: json_i64(value, "worldY") as i32,
+        world_depth: json_i64(value, "worldDepth") as i32,
+        width: json_i64(value, "pxWid").max(0) as u32,
+        height: json_i64(value, "pxHei").max(0) as u32,
+        neighbours: json_array(value, "__neighbours")
+            .iter()
+            .filter_map(|neighbour| {
+                Some(LdtkNeighbour {
+                    level_iid: json_str(neighbour, "levelIid"),
+                    dir: json_direction(&json_str(neighbour, "dir"))?,
+                })
+            })
+            .collect(),
+        fields: json_fields(value),
+        layers: json_array(value, "layerInstances")
+            .iter()
+            .map(json_layer)
+            .collect(),
+        external_path: value
+            .get("externalRelPath")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    }
+}
+
+fn json_project(value: &Value) -> Result<LdtkProject, String> {
+    if !value.is_object() || value.get("defs").is_none() {
+        return Err("not an LDtk project (no \"defs\")".to_string());
+    }
+    let defs = &value["defs"];
+    let tilesets = json_array(defs, "tilesets")
+        .iter()
+        .filter_map(|tileset| {
+            Some(LdtkTileset {
+                uid: json_i64(tileset, "uid"),
+                identifier: json_str(tileset, "identifier"),
+                rel_path: tileset.get("relPath")?.as_str()?.to_string(),
+                px_width: json_i64(tileset, "pxWid").max(0) as u32,
+                px_height: json_i64(tileset, "pxHei").max(0) as u32,
+                grid_size: json_i64(tileset, "tileGridSize").max(1) as u32,
+                spacing: json_i64(tileset, "spacing").max(0) as u32,
+                padding: json_i64(tileset, "padding").max(0) as u32,
+            })
+        })
+        .collect();
+    let int_grid_values = json_array(defs, "layers")
+        .iter()
+        .map(|layer| {
+            let values = json_array(layer, "intGridValues")
+                .iter()
+                .map(|named| LdtkIntGridValue {
+                    value: json_i64(named, "value") as i32,
+                    identifier: json_str(named, "identifier"),
+                })
+                .collect();
+            (json_i64(layer, "uid"), values)
+        })
+        .collect();
+
+    // Multi-world projects keep levels, and their layout, per world.
+    let worlds = json_array(value, "worlds");
+    let worlds = if worlds.is_empty() {
+        std::slice::from_ref(value)
+    } else {
+        worlds
+    };
+    let mut levels = Vec::new();
+    for world in worlds {
+        let mut world_levels: Vec<LdtkLevel> =
+            json_array(world, "levels").iter().map(json_level).collect();
+        // Linear layouts leave world positions at -1: lay the levels out in
+        // a row or column ourselves.
+        let mut cursor = 0;
+        match world.get("worldLayout").and_then(Value::as_str) {
+            Some("LinearHorizontal") => {
+                for level in &mut world_levels {
+                    (level.world_x, level.world_y) = (cursor, 0);
+                    cursor += level.width as i32;
+                }
+            }
+            Some("LinearVertical") => {
+                for level in &mut world_levels {
+                    (level.world_x, level.world_y) = (0, cursor);
+                    cursor += level.height as i32;
+                }
+            }
+            _ => {}
+        }
+        levels.extend(world_levels);
+    }
+
+    Ok(LdtkProject {
+        tilesets,
+        int_grid_values,
+        levels,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::TextureId;
+
+    /// Two GridVania levels side by side; the first has an IntGrid layer with
+    /// auto-tiles, a painted tile layer and an entity layer.
+    const PROJECT: &str = r#"{
+        "jsonVersion": "1.5.3",
+        "worldLayout": "GridVania",
+        "externalLevels": false,
+        "defs": {
+            "tilesets": [
+                {"uid": 1, "identifier": "Terrain", "relPath": "terrain.png",
+                 "pxWid": 64, "pxHei": 32, "tileGridSize": 16, "spacing": 0, "padding": 0},
+                {"uid": 2, "identifier": "Internal_Icons", "relPath": null,
+                 "embedAtlas": "LdtkIcons", "pxWid": 1024, "pxHei": 1024, "tileGridSize": 16}
+            ],
+            "layers": [
+                {"uid": 10, "identifier": "Collision", "type": "IntGrid", "gridSize": 16,
+                 "intGridValues": [
+                    {"value": 1, "identifier": "wall"},
+                    {"value": 2, "identifier": "platform"},
+                    {"value": 3, "identifier": "decor"}
+                 ]}
+            ]
+        },
+        "levels": [
+            {"identifier": "Start", "iid": "lvl-a", "uid": 0, "worldX": 0, "worldY": 0,
+             "worldDepth": 0, "pxWid": 64, "pxHei": 48,
+             "__neighbours": [{"levelIid": "lvl-b", "dir": "e"}, {"levelIid": "gone", "dir": "w"}],
+             "fieldInstances": [{"__identifier": "music", "__type": "String", "__value": "cave"}],
+             "layerInstances": [
+                {"__identifier": "Entities", "__type": "Entities", "__cWid": 4, "__cHei": 3,
+                 "__gridSize": 16, "__pxTotalOffsetX": 0, "__pxTotalOffsetY": 0,
+                 "__tilesetDefUid": null, "layerDefUid": 11, "visible": true, "__opacity": 1,
+                 "intGridCsv": [], "autoLayerTiles": [], "gridTiles": [],
+                 "entityInstances": [
+                    {"__identifier": "Player", "iid": "ent-player", "px": [24, 32],
+                     "__pivot": [0.5, 1], "width": 16, "height": 16, "__tags": ["actor"],
+                     "__tile": {"tilesetUid": 1, "x": 48, "y": 16, "w": 16, "h": 16},
+                     "fieldInstances": [
+                        {"__identifier": "hp", "__type": "Int", "__value": 3},
+                        {"__identifier": "speed", "__type": "Float", "__value": 2},
+                        {"__identifier": "door", "__type": "EntityRef",
+                         "__value": {"entityIid": "ent-door", "layerIid": "l", "levelIid": "lvl-b", "worldIid": "w"}},
+                        {"__identifier": "patrol", "__type": "Array<Point>",
+                         "__value": [{"cx": 0, "cy": 0}, {"cx": 3, "cy": 2}]},
+                        {"__identifier": "note", "__type": "String", "__value": null}
+                     ]}
+                 ]},
+                {"__identifier": "Decor", "__type": "Tiles", "__cWid": 4, "__cHei": 3,
+                 "__gridSize": 16, "__pxTotalOffsetX": 0, "__pxTotalOffsetY": 0,
+                 "__tilesetDefUid": 1, "layerDefUid": 12, "visible": true, "__opacity": 0.5,
+                 "intGridCsv": [], "autoLayerTiles": [], "entityInstances": [],
+                 "gridTiles": [
+                    {"px": [0, 0], "src": [16, 0], "f": 1, "t": 1, "a": 1},
+                    {"px": [0, 0], "src": [32, 0], "f": 0, "t": 2, "a": 1},
+                    {"px": [48, 0], "src": [16, 0], "f": 1, "t": 1, "a": 1}
+                 ]},
+                {"__identifier": "Collision", "__type": "IntGrid", "__cWid": 4, "__cHei": 3,
+                 "__gridSize": 16, "__pxTotalOffsetX": 0, "__pxTotalOffsetY": 0,
+                 "__tilesetDefUid": 1, "layerDefUid": 10, "visible": true, "__opacity": 1,
+                 "intGridCsv": [0, 2, 2, 3,
+                                1, 0, 0, 1,
+                                1, 1, 1, 1],
+                 "autoLayerTiles": [{"px": [0, 32], "src": [0, 0], "f": 0, "t": 0, "a": 1}],
+                 "gridTiles": [], "entityInstances": []}
+             ]},
+            {"identifier": "Cave", "iid": "lvl-b", "uid": 1, "worldX": 64, "worldY": 16,
+             "worldDepth": 0, "pxWid": 32, "pxHei": 32,
+             "__neighbours": [{"levelIid": "lvl-a", "dir": "w"}],
+             "fieldInstances": [], "layerInstances": []}
+        ]
+    }"#;
+
+    fn sheet() -> SpriteSheet {
+        SpriteSheet::new(TextureId(7), 64, 32, 16, 16)
+    }
+
+    fn project() -> LdtkProject {
+        LdtkProject::parse(Path::new("world.ldtk"), PROJECT).unwrap()
+    }
+
+    #[test]
+    fn levels_expose_world_bounds_and_neighbours() {
+        let project = project();
+        assert_eq!(project.tilesets.len(), 1, "the icon atlas has no image");
+        let start = project.level("Start").unwrap();
+        assert_eq!(start.bounds(), Rect::new(0.0, -48.0, 64.0, 48.0));
+        let cave = project.level("Cave").unwrap();
+        assert_eq!(cave.bounds(), Rect::new(64.0, -48.0, 32.0, 32.0));
+        assert_eq!(
+            start.field("music"),
+            Some(&LdtkValue::String("cave".into()))
+        );
+
+        let neighbours: Vec<_> = project
+            .neighbours(start)
+            .map(|(level, dir)| (level.identifier.as_str(), dir))
+            .collect();
+        assert_eq!(neighbours, vec![("Cave", LdtkDirection::East)]);
+
+        assert_eq!(
+            project.level_at(Vec2::new(70.0, -20.0)).unwrap().iid,
+            "lvl-b"
+        );
+        assert!(project.level_at(Vec2::new(70.0, -4.0)).is_none());
+        let view = Rect::new(50.0, -10.0, 8.0, 8.0);
+        assert_eq!(project.levels_in(view).count(), 1);
+    }
+
+    #[test]
+    fn int_grid_cells_become_merged_solids() {
+        let project = project();
+        let level = project.build_level(project.level("Start").unwrap(), &[]);
+        // Without a sheet, no tiles are built but collision is.
+        assert!(level.layers.is_empty());
+
+        let mut solid: Vec<Rect> = level
+            .solids
+            .iter()
+            .filter(|s| !s.one_way)
+            .map(|s| s.rect)
+            .collect();
+        solid.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        assert_eq!(
+            solid,
+            vec![
+                Rect::new(0.0, -48.0, 64.0, 16.0),
+                Rect::new(0.0, -32.0, 16.0, 16.0),
+                Rect::new(48.0, -32.0, 16.0, 16.0),
+            ]
+        );
+        let one_way: Vec<Rect> = level
+            .solids
+            .iter()
+            .filter(|s| s.one_way)
+            .map(|s| s.rect)
+            .collect();
+        assert_eq!(one_way, vec![Rect::new(16.0, -16.0, 32.0, 16.0)]);
+    }
+
+    #[test]
+    fn tile_layers_stack_into_maps_bottom_first() {
+        let project = project();
+        let level = project.build_level(project.level("Start").unwrap(), &[sheet()]);
+        let names: Vec<&str> = level.layers.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, vec!["Collision", "Decor", "Decor"]);
+
+        let auto = level.layer("Collision").unwrap();
+        assert_eq!(auto.origin, Vec2::new(0.0, -48.0));
+        assert!(
+            auto.get(0, 0).is_some(),
+            "bottom row from the bottom px row"
+        );
+        assert!(auto.solid_tile(0, 0).is_none(), "IntGrid owns collision");
+
+        let decor = &level.layers[1].map;
+        let tile = decor.tile(decor.get(0, 2).unwrap()).unwrap();
+        assert!(tile.flip_x && !tile.flip_y);
+        assert_eq!(tile.uv_rect, [0.25, 0.0, 0.25, 0.5]);
+        assert_eq!(tile.color.a, 0.5);
+        assert_eq!(decor.get(3, 2), decor.get(0, 2));
+        let above = &level.layers[2].map;
+        assert!(above.get(0, 2).is_some() && above.get(3, 2).is_none());
+    }
+
+    #[test]
+    fn entities_become_scene_nodes_with_field_properties() {
+        let project = project();
+        let level = project.build_level(project.level("Start").unwrap(), &[sheet()]);
+        let handle = level.scene.find_by_name("ent-player").unwrap();
+        let player = level.scene.get(handle).unwrap();
+        assert_eq!(player.prefab(), "Player");
+        assert!(player.has_tag("actor"));
+        assert_eq!(player.position(), Vec2::new(16.0, -32.0));
+        assert_eq!(player.size(), Some(Vec2::new(16.0, 16.0)));
+        assert_eq!(player.property_i64("hp"), Some(3));
+        assert_eq!(player.property_f32("speed"), Some(2.0));
+        assert_eq!(player.property("door"), Some("ent-door"));
+        assert_eq!(player.property("patrol"), Some("8,-8,56,-40"));
+        assert_eq!(player.property("layer"), Some("Entities"));
+        assert!(player.property("note").is_none());
+        assert_eq!(player.sprites()[0].uv_rect, [0.75, 0.5, 0.25, 0.5]);
+        assert_eq!(
+            level.scene.node_bounds(handle),
+            Some(Rect::new(16.0, -32.0, 16.0, 16.0))
+        );
+    }
+
+    #[test]
+    fn linear_layouts_and_external_levels() {
+        let project = LdtkProject::parse(
+            Path::new("strip.ldtk"),
+            r#"{"worldLayout": "LinearHorizontal", "externalLevels": true, "defs": {},
+                "levels": [
+                    {"identifier": "A", "iid": "a", "worldX": -1, "worldY": -1, "pxWid": 100,
+                     "pxHei": 50, "layerInstances": null, "externalRelPath": "strip/A.ldtkl"},
+                    {"identifier": "B", "iid": "b", "worldX": -1, "worldY": -1, "pxWid": 80,
+                     "pxHei": 60, "layerInstances": null, "externalRelPath": "strip/B.ldtkl"}
+                ]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            project.level("B").unwrap().bounds(),
+            Rect::new(100.0, -60.0, 80.0, 60.0)
+        );
+        assert!(!project.level("A").unwrap().is_loaded());
+
+        let error = LdtkProject::parse(Path::new("bad.ldtk"), r#"{"levels": []}"#).unwrap_err();
+        assert!(error.to_string().contains("not an LDtk project"), "{error}");
+    }
+}
@@ -1,12 +1,55 @@
+pub mod autotile;
+pub mod dynamics;
+pub mod fov;
+pub mod hex;
 pub mod iso;
+pub mod iso_render;
+pub mod joints;
+pub mod ldtk;
+pub mod nav;
 pub mod physics;
+pub mod platform;
+pub mod raycast;
+pub mod shape;
+pub mod spatial;
+pub mod steering;
+pub mod tiled;
 pub mod tilemap;
 pub mod trigger;
 
-pub use iso::{iso_to_screen, screen_to_iso};
+pub use autotile::{AutotileMode, AutotileRules, Autotiler, TerrainRule};
+pub use dynamics::{ContactTarget2D, PhysicsWorld2D, RigidBody2D, RigidBodyId, RigidContact2D};
+pub use fov::{line_of_sight, line_of_sight_with, FieldOfView, GridLine, VisibilitySet};
+pub use hex::{Hex, HexLayout, HexOrientation, Stagger};
+pub use iso::{iso_to_screen, screen_to_iso, StaggeredIso};
+pub use iso_render::{IsoBox, IsoRenderer};
+pub use joints::{constrain_kinematic, Joint2D, JointAnchor2D, JointId, JointKind2D};
+pub use ldtk::{
+    LdtkDirection, LdtkEntity, LdtkField, LdtkIntGridValue, LdtkLayer, LdtkLayerKind, LdtkLayerMap,
+    LdtkLevel, LdtkLevelScene, LdtkNeighbour, LdtkProject, LdtkTile, LdtkTileRect, LdtkTileset,
+    LdtkValue,
+};
+pub use nav::{
+    CrowdAgent, FlowCrowd, FlowField, GridPath, GridPathCache, NavAgent, NavGrid, NavMesh,
+    PathSearch,
+};
 pub use physics::{
-    aabb_overlap, aabb_overlap_layered, move_and_collide, move_and_collide_solids, CollisionLayer,
-    Contacts2D, KinematicBody2D, MoveResult2D, Solid2D,
+    aabb_overlap, aabb_overlap_generic, aabb_overlap_layered, move_aabb, move_and_collide,
+    move_and_collide_shape, move_and_collide_solids, CcdMode2D, CollisionLayer, Contacts2D,
+    Impact2D, KinematicBody2D, MoveResult2D, Solid2D, DEFAULT_MAX_SLOPE_ANGLE,
+};
+pub use platform::{move_platforms, PlatformEvent2D};
+pub use raycast::{BoxCast2D, HitTarget2D, Ray2D, RayHit2D, SweepHit2D};
+pub use shape::{
+    shape_overlap, shape_overlap_layered, shapes_overlap, ConvexPolygon2D, Shape2D,
+    MAX_POLYGON_VERTICES,
+};
+pub use spatial::{SolidId, SolidIndex2D, SpatialGrid2D, SpatialKey};
+pub use steering::{PathFollower, Steering, SteeringAgent, Wander};
+pub use tiled::{
+    TiledLayer, TiledLayerMap, TiledLevel, TiledMap, TiledObject, TiledObjectKind,
+    TiledObjectLayer, TiledProperties, TiledSpawn, TiledTile, TiledTileLayer, TiledTileset,
+    TiledTrigger, TiledValue,
 };
-pub use tilemap::{TileDef, TileMap};
+pub use tilemap::{GridLayout, LayeredTileMap, TileDef, TileLayer, TileMap, TileShape};
 pub use trigger::{BodyId, OverlapEvent, TriggerSystem, TriggerZone, TriggerZoneId};
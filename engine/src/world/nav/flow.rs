@@ -0,0 +1,564 @@
+//! Flow fields: one search from the goals that any number of agents sample,
+//! and crowd steering that keeps the agents following them apart.
+
+use std::collections::BinaryHeap;
+
+use glam::Vec2;
+
+use super::grid::{NavGrid, SQRT_2, STEPS};
+use super::{NavAgent, OpenNode};
+use crate::math::rect::Rect;
+use crate::world::spatial::{SpatialGrid2D, SpatialKey};
+
+const NO_NEXT: usize = usize::MAX;
+
+/// Directions towards the nearest of a set of goal cells, for every cell of
+/// a [`NavGrid`] at once.
+///
+/// The integration field holds each cell's cost to reach a goal, found by a
+/// single Dijkstra search outwards from all goals; the direction field
+/// points each cell at its cheapest neighbour. Costs and blocked cells are
+/// read the same way as [`NavGrid::find_path`] for the agent the field was
+/// built for, so a wide agent's field flows around gaps it does not fit.
+///
+/// After the grid changes, [`FlowField::update`] repairs only the cells
+/// whose route was touched: closing a wall re-integrates the cells that
+/// flowed through it, and opening a door spreads the shorter distances out
+/// from the door.
+#[derive(Debug, Clone)]
+pub struct FlowField {
+    width: usize,
+    height: usize,
+    cell_size: f32,
+    origin: Vec2,
+    need: u16,
+    diagonals: bool,
+    goals: Vec<(usize, usize)>,
+    /// Each cell's cost to enter as of the last integration; `None` where
+    /// the agent cannot stand.
+    costs: Vec<Option<f32>>,
+    integration: Vec<f32>,
+    /// The neighbour each cell flows into.
+    next: Vec<usize>,
+    revision: u64,
+}
+
+impl FlowField {
+    pub fn new(grid: &NavGrid, agent: &NavAgent, goals: &[(usize, usize)]) -> Self {
+        let n = grid.width() * grid.height();
+        let mut field = Self {
+            width: grid.width(),
+            height: grid.height(),
+            cell_size: grid.cell_size(),
+            origin: grid.origin(),
+            need: grid.required_clearance(agent),
+            diagonals: agent.diagonals,
+            goals: Vec::new(),
+            costs: vec![None; n],
+            integration: vec![f32::INFINITY; n],
+            next: vec![NO_NEXT; n],
+            revision: grid.revision(),
+        };
+        field.set_goals(grid, goals);
+        field
+    }
+
+    pub fn goals(&self) -> &[(usize, usize)] {
+        &self.goals
+    }
+
+    /// Replace the goals and integrate the whole field again.
+    pub fn set_goals(&mut self, grid: &NavGrid, goals: &[(usize, usize)]) {
+        self.goals = goals
+            .iter()
+            .copied()
+            .filter(|&(col, row)| col < self.width && row < self.height)
+            .collect();
+        self.revision = grid.revision();
+        for index in 0..self.costs.len() {
+            self.costs[index] = self.cost_in(grid, index);
+        }
+        self.integration.fill(f32::INFINITY);
+        self.next.fill(NO_NEXT);
+        let mut open = BinaryHeap::new();
+        for goal in self.goals.clone() {
+            self.seed_goal(goal, &mut open);
+        }
+        self.relax(grid, open);
+    }
+
+    /// Add a goal, spreading its shorter distances without a full
+    /// integration. Removing goals goes through [`FlowField::set_goals`].
+    pub fn add_goal(&mut self, grid: &NavGrid, goal: (usize, usize)) {
+        if goal.0 >= self.width || goal.1 >= self.height || self.goals.contains(&goal) {
+            return;
+        }
+        self.update(grid);
+        self.goals.push(goal);
+        let mut open = BinaryHeap::new();
+        self.seed_goal(goal, &mut open);
+        self.relax(grid, open);
+    }
+
+    /// Bring the field up to date with `grid` after cells changed, and
+    /// return how many cells had their routes re-integrated. Does nothing
+    /// while the grid's revision is unchanged.
+    pub fn update(&mut self, grid: &NavGrid) -> usize {
+        if grid.revision() == self.revision {
+            return 0;
+        }
+        self.revision = grid.revision();
+        let mut roots = Vec::new();
+        for index in 0..self.costs.len() {
+            let cost = self.cost_in(grid, index);
+            if cost != self.costs[index] {
+                self.costs[index] = cost;
+                roots.push(index);
+                // Neighbours may step through the cell or past its corner.
+                roots.extend(self.neighbours(index).map(|(next, _, _)| next));
+            }
+        }
+        if roots.is_empty() {
+            return 0;
+        }
+
+        // Everything upstream of a changed cell loses its route.
+        let mut affected = vec![false; self.costs.len()];
+        let mut count = 0;
+        while let Some(index) = roots.pop() {
+            if affected[index] {
+                continue;
+            }
+            affected[index] = true;
+            count += 1;
+            self.integration[index] = f32::INFINITY;
+            self.next[index] = NO_NEXT;
+            roots.extend(
+                self.neighbours(index)
+                    .filter(|&(from, _, _)| self.next[from] == index && !affected[from])
+                    .map(|(from, _, _)| from),
+            );
+        }
+
+        // Re-seed the cleared cells from the intact field around them, then
+        // let shorter distances spread as far as they reach.
+        let mut open = BinaryHeap::new();
+        for goal in self.goals.clone() {
+            if affected[goal.1 * self.width + goal.0] {
+                self.seed_goal(goal, &mut open);
+            }
+        }
+        for index in (0..affected.len()).filter(|&index| affected[index]) {
+            let cell = ((index % self.width) as i64, (index / self.width) as i64);
+            let neighbours: Vec<_> = self.neighbours(index).collect();
+            for (to, step, length) in neighbours {
+                if affected[to] || !self.integration[to].is_finite() {
+                    continue;
+                }
+                if !grid.can_step(cell, step, self.need) {
+                    continue;
+                }
+                let Some(cost) = self.costs[to] else {
+                    continue;
+                };
+                let distance = self.integration[to] + length * cost;
+                if distance < self.integration[index] {
+                    self.integration[index] = distance;
+                    self.next[index] = to;
+                }
+            }
+            if self.integration[index].is_finite() {
+                open.push(OpenNode {
+                    f: self.integration[index],
+                    g: self.integration[index],
+                    index,
+                });
+            }
+        }
+        self.relax(grid, open);
+        count
+    }
+
+    /// The cost of reaching the nearest goal from a cell, or `None` if no
+    /// goal can be reached.
+    pub fn distance(&self, col: usize, row: usize) -> Option<f32> {
+        let index = self.index(col, row)?;
+        Some(self.integration[index]).filter(|distance| distance.is_finite())
+    }
+
+    /// The unit direction from a cell's centre to the next cell on its way
+    /// to a goal; zero on goals and cells that cannot reach one.
+    pub fn direction(&self, col: usize, row: usize) -> Vec2 {
+        match self.index(col, row).map(|index| self.next[index]) {
+            Some(next) if next != NO_NEXT => {
+                let to = Vec2::new((next % self.width) as f32, (next / self.width) as f32);
+                (to - Vec2::new(col as f32, row as f32)).normalize()
+            }
+            _ => Vec2::ZERO,
+        }
+    }
+
+    /// The flow at a world position: the directions of the four cells around
+    /// it, blended by distance so agents turn smoothly between cells.
+    pub fn sample(&self, point: Vec2) -> Vec2 {
+        let local = (point - self.origin) / self.cell_size - 0.5;
+        let base = local.floor();
+        let t = local - base;
+        let mut flow = Vec2::ZERO;
+        for (dx, dy, weight) in [
+            (0, 0, (1.0 - t.x) * (1.0 - t.y)),
+            (1, 0, t.x * (1.0 - t.y)),
+            (0, 1, (1.0 - t.x) * t.y),
+            (1, 1, t.x * t.y),
+        ] {
+            let (col, row) = (base.x as i64 + dx, base.y as i64 + dy);
+            if col >= 0 && row >= 0 {
+                flow += self.direction(col as usize, row as usize) * weight;
+            }
+        }
+        match flow.try_normalize() {
+            Some(flow) => flow,
+            None => self
+                .cell_at(point)
+                .map_or(Vec2::ZERO, |(col, row)| self.direction(col, row)),
+        }
+    }
+
+    pub fn cell_at(&self, point: Vec2) -> Option<(usize, usize)> {
+        let local = (point - self.origin) / self.cell_size;
+        if local.x < 0.0 || local.y < 0.0 {
+            return None;
+        }
+        let (col, row) = (local.x as usize, local.y as usize);
+        (col < self.width && row < self.height).then_some((col, row))
+    }
+
+    /// Whether a position is in a goal cell.
+    pub fn is_at_goal(&self, point: Vec2) -> bool {
+        self.cell_at(point)
+            .is_some_and(|cell| self.goals.contains(&cell))
+    }
+
+    fn index(&self, col: usize, row: usize) -> Option<usize> {
+        (col < self.width && row < self.height).then_some(row * self.width + col)
+    }
+
+    fn cost_in(&self, grid: &NavGrid, index: usize) -> Option<f32> {
+        let (col, row) = (index % self.width, index / self.width);
+        if grid.open(col as i64, row as i64, self.need) {
+            grid.cost(col, row)
+        } else {
+            None
+        }
+    }
+
+    /// In-bounds neighbours of a cell, with the step to each and its length.
+    fn neighbours(&self, index: usize) -> impl Iterator<Item = (usize, (i64, i64), f32)> + '_ {
+        let (col, row) = ((index % self.width) as i64, (index / self.width) as i64);
+        let steps = if self.diagonals {
+            &STEPS[..]
+        } else {
+            &STEPS[..4]
+        };
+        steps.iter().filter_map(move |&(dx, dy)| {
+            let (c, r) = (col + dx, row + dy);
+            (c >= 0 && r >= 0 && (c as usize) < self.width && (r as usize) < self.height).then(
+                || {
+                    let length = if dx != 0 && dy != 0 { SQRT_2 } else { 1.0 };
+                    (r as usize * self.width + c as usize, (dx, dy), length)
+                },
+            )
+        })
+    }
+
+    fn seed_goal(&mut self, (col, row): (usize, usize), open: &mut BinaryHeap<OpenNode>) {
+        let index = row * self.width + col;
+        if self.costs[index].is_some() {
+            self.integration[index] = 0.0;
+            self.next[index] = NO_NEXT;
+            open.push(OpenNode {
+                f: 0.0,
+                g: 0.0,
+                index,
+            });
+        }
+    }
+
+    /// Dijkstra outwards from `open`: a cell's distance is the cheapest
+    /// neighbour's plus the cost of stepping into it.
+    fn relax(&mut self, grid: &NavGrid, mut open: BinaryHeap<OpenNode>) {
+        while let Some(node) = open.pop() {
+            if node.g > self.integration[node.index] {
+                continue;
+            }
+            let Some(cost) = self.costs[node.index] else {
+                continue;
+            };
+            let cell = (
+                (node.index % self.width) as i64,
+                (node.index / self.width) as i64,
+            );
+            let steps = if self.diagonals {
+                &STEPS[..]
+            } else {
+                &STEPS[..4]
+            };
+            for &step in steps {
+                if !grid.can_step(cell, step, self.need) {
+                    continue;
+                }
+                let from = (cell.1 + step.1) as usize * self.width + (cell.0 + step.0) as usize;
+                let length = if step.0 != 0 && step.1 != 0 {
+                    SQRT_2
+                } else {
+                    1.0
+                };
+                let distance = node.g + length * cost;
+                if distance < self.integration[from] {
+                    self.integration[from] = distance;
+                    self.next[from] = node.index;
+                    open.push(OpenNode {
+                        f: distance,
+                        g: distance,
+                        index: from,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// One member of a crowd following a [`FlowField`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CrowdAgent {
+    pub position: Vec2,
+    pub velocity: Vec2,
+}
+
+impl CrowdAgent {
+    pub fn new(position: Vec2) -> Self {
+        Self {
+            position,
+            velocity: Vec2::ZERO,
+        }
+    }
+}
+
+/// Steers a crowd along a [`FlowField`] while pushing members apart, so a
+/// swarm spreads through corridors instead of stacking into one point.
+///
+/// [`FlowCrowd::steer`] only updates velocities; move each agent with them
+/// through the game's own collision, e.g. as a
+/// [`KinematicBody2D`](crate::world::physics::KinematicBody2D)'s velocity.
+#[derive(Debug, Clone)]
+pub struct FlowCrowd {
+    pub max_speed: f32,
+    /// How fast velocities turn towards the desired one, in units per
+    /// second squared.
+    pub acceleration: f32,
+    /// Members closer than this push each other apart.
+    pub separation: f32,
+    /// How strongly separation counts against the flow, as a fraction of
+    /// `max_speed` at zero distance.
+    pub separation_weight: f32,
+    neighbours: SpatialGrid2D,
+    nearby: Vec<SpatialKey>,
+}
+
+impl FlowCrowd {
+    pub fn new(max_speed: f32) -> Self {
+        Self {
+            max_speed,
+            acceleration: max_speed * 8.0,
+            separation: 16.0,
+            separation_weight: 1.5,
+            neighbours: SpatialGrid2D::new(16.0),
+            nearby: Vec::new(),
+        }
+    }
+
+    pub fn with_acceleration(mut self, acceleration: f32) -> Self {
+        self.acceleration = acceleration;
+        self
+    }
+
+    pub fn with_separation(mut self, distance: f32, weight: f32) -> Self {
+        self.separation = distance.max(f32::EPSILON);
+        self.separation_weight = weight;
+        self.neighbours = SpatialGrid2D::new(self.separation);
+        self
+    }
+
+    /// Turn each agent's velocity towards the flow at its position plus a
+    /// push away from close neighbours. Agents in a goal cell only spread
+    /// out, and slow to a stop once they have room.
+    pub fn steer(&mut self, field: &FlowField, agents: &mut [CrowdAgent], dt: f32) {
+        self.neighbours.clear();
+        for agent in agents.iter() {
+            self.neighbours
+                .insert(Rect::from_pos_size(agent.position, Vec2::ZERO));
+        }
+
+        let reach = Vec2::splat(self.separation * 2.0);
+        for index in 0..agents.len() {
+            let position = agents[index].position;
+            self.neighbours.query_into(
+                &Rect::from_pos_size(position - reach / 2.0, reach),
+                &mut self.nearby,
+            );
+            let mut push = Vec2::ZERO;
+            for &other in &self.nearby {
+                if other == index {
+                    continue;
+                }
+                let away = position - agents[other].position;
+                let distance = away.length();
+                if distance >= self.separation {
+                    continue;
+                }
+                // Agents on the same spot split along a fixed per-pair axis.
+                let dir = away.try_normalize().unwrap_or_else(|| {
+                    let angle = (index.min(other) * 7 + index.max(other)) as f32;
+                    let dir = Vec2::from_angle(angle);
+                    if index < other {
+                        dir
+                    } else {
+                        -dir
+                    }
+                });
+                push += dir * (1.0 - distance / self.separation);
+            }
+
+            let flow = if field.is_at_goal(position) {
+                Vec2::ZERO
+            } else {
+                field.sample(position)
+            };
+            let desired =
+                (flow + push * self.separation_weight).clamp_length_max(1.0) * self.max_speed;
+            let agent = &mut agents[index];
+            let change = desired - agent.velocity;
+            agent.velocity += change.clamp_length_max(self.acceleration * dt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Walls with a door at (6, 3..5), a mud strip and open ground.
+    fn grid() -> NavGrid {
+        let mut grid = NavGrid::new(12, 8, 10.0);
+        for row in 0..8 {
+            if !(3..5).contains(&row) {
+                grid.set_cost(6, row, None);
+            }
+        }
+        for col in 1..5 {
+            grid.set_cost(col, 5, Some(4.0));
+        }
+        grid
+    }
+
+    fn assert_matches(field: &FlowField, fresh: &FlowField) {
+        for index in 0..field.integration.len() {
+            let (a, b) = (field.integration[index], fresh.integration[index]);
+            assert!(
+                (a.is_infinite() && b.is_infinite()) || (a - b).abs() < 1e-3,
+                "cell {index}: {a} vs {b}"
+            );
+        }
+    }
+
+    #[test]
+    fn distances_match_a_star_to_the_nearest_goal() {
+        let grid = grid();
+        let agent = NavAgent::new(0.0);
+        let goals = [(10, 1), (2, 7)];
+        let field = FlowField::new(&grid, &agent, &goals);
+        for row in 0..8 {
+            for col in 0..12 {
+                let best = goals
+                    .iter()
+                    .filter_map(|&goal| grid.find_path((col, row), goal, &agent))
+                    .map(|path| path.cost)
+                    .reduce(f32::min);
+                match (field.distance(col, row), best) {
+                    (Some(a), Some(b)) => assert!((a - b).abs() < 1e-3, "({col}, {row})"),
+                    (a, b) => assert_eq!(a, b, "({col}, {row})"),
+                }
+            }
+        }
+
+        // Following the directions from anywhere reaches a goal.
+        assert_ne!(field.direction(0, 0), Vec2::ZERO);
+        let mut index = 0;
+        while field.next[index] != NO_NEXT {
+            index = field.next[index];
+        }
+        assert!(goals.contains(&(index % 12, index / 12)));
+    }
+
+    #[test]
+    fn updates_repair_the_field_like_a_fresh_integration() {
+        let mut grid = grid();
+        let agent = NavAgent::new(0.0);
+        let goals = [(10, 4)];
+        let mut field = FlowField::new(&grid, &agent, &goals);
+        assert_eq!(field.update(&grid), 0);
+
+        // Shut the door: the whole left side loses its route.
+        grid.set_cost(6, 3, None);
+        grid.set_cost(6, 4, None);
+        assert!(field.update(&grid) > 0);
+        assert_eq!(field.distance(0, 0), None);
+        assert_matches(&field, &FlowField::new(&grid, &agent, &goals));
+
+        // Open a different door and drain the mud.
+        grid.set_cost(6, 0, Some(1.0));
+        for col in 1..5 {
+            grid.set_cost(col, 5, Some(1.0));
+        }
+        field.update(&grid);
+        assert!(field.distance(0, 0).is_some());
+        assert_matches(&field, &FlowField::new(&grid, &agent, &goals));
+
+        // An edit far from every route leaves the rest alone.
+        grid.set_cost(11, 7, Some(3.0));
+        assert!(field.update(&grid) <= 9);
+        assert_matches(&field, &FlowField::new(&grid, &agent, &goals));
+
+        field.add_goal(&grid, (0, 7));
+        assert_matches(&field, &FlowField::new(&grid, &agent, &[(10, 4), (0, 7)]));
+
+        // A wide agent's field does not fit the one-cell door.
+        let wide = FlowField::new(&grid, &NavAgent::new(8.0), &goals);
+        assert_eq!(wide.distance(2, 2), None);
+    }
+
+    #[test]
+    fn crowds_follow_the_flow_without_stacking() {
+        let grid = NavGrid::new(20, 10, 10.0);
+        let field = FlowField::new(&grid, &NavAgent::new(0.0), &[(18, 5)]);
+        let mut crowd = FlowCrowd::new(40.0).with_separation(8.0, 2.0);
+        let mut agents = vec![CrowdAgent::new(Vec2::new(25.0, 55.0)); 6];
+        for _ in 0..300 {
+            crowd.steer(&field, &mut agents, 1.0 / 60.0);
+            for agent in &mut agents {
+                agent.position += agent.velocity / 60.0;
+            }
+        }
+        let goal = grid.cell_center(18, 5);
+        for (i, a) in agents.iter().enumerate() {
+            assert!(
+                a.position.distance(goal) < 25.0,
+                "agent {i} at {}",
+                a.position
+            );
+            for b in &agents[i + 1..] {
+                assert!(a.position.distance(b.position) > 2.0);
+            }
+        }
+    }
+}
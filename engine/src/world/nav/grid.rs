@@ -0,0 +1,920 @@
+//! A* and jump point search over a walkability grid.
+
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use glam::Vec2;
+
+use super::{NavAgent, OpenNode, PathSearch};
+use crate::world::tilemap::TileMap;
+
+const NO_PARENT: usize = usize::MAX;
+pub(super) const SQRT_2: f32 = std::f32::consts::SQRT_2;
+/// Neighbour offsets: the four edges first, then the diagonals.
+pub(super) const STEPS: [(i64, i64); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (-1, 1),
+    (1, -1),
+    (-1, -1),
+];
+
+/// Which cells of a grid can be walked and what each costs to enter.
+///
+/// Cells are addressed like a [`TileMap`]'s — `(col, row)` with row 0 at the
+/// bottom — and usually built from one with [`NavGrid::from_tilemap`]. Each
+/// cell also tracks its clearance, the distance to the nearest blocked cell,
+/// so agents wider than a cell stay out of gaps they do not fit.
+#[derive(Debug, Clone)]
+pub struct NavGrid {
+    width: usize,
+    height: usize,
+    cell_size: f32,
+    origin: Vec2,
+    /// `None` for blocked cells; walkable cells cost at least 1.
+    costs: Vec<Option<f32>>,
+    /// Chebyshev distance in cells to the nearest blocked cell or the grid
+    /// edge; 0 for blocked cells.
+    clearance: Vec<u16>,
+    /// Walkable cells costing more than 1, which rule out jump point search.
+    weighted: usize,
+    revision: u64,
+}
+
+impl NavGrid {
+    /// A grid with every cell walkable at cost 1.
+    pub fn new(width: usize, height: usize, cell_size: f32) -> Self {
+        let mut grid = Self {
+            width,
+            height,
+            cell_size,
+            origin: Vec2::ZERO,
+            costs: vec![Some(1.0); width * height],
+            clearance: vec![0; width * height],
+            weighted: 0,
+            revision: 0,
+        };
+        grid.update_clearance();
+        grid
+    }
+
+    pub fn with_origin(mut self, origin: Vec2) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// A grid matching `map`, with every cell holding a colliding tile
+    /// blocked and the rest costing 1. Only square maps line up in the
+    /// world; other [`GridLayout`](crate::world::GridLayout)s keep their cell
+    /// indices but not their positions.
+    pub fn from_tilemap(map: &TileMap) -> Self {
+        let mut grid = Self::new(map.width, map.height, map.tile_size).with_origin(map.origin);
+        grid.sync_tilemap(map);
+        grid.revision = 0;
+        grid
+    }
+
+    /// A grid matching `map`, with each cell's cost chosen from its tile id:
+    /// `None` blocks the cell.
+    pub fn from_tilemap_with(
+        map: &TileMap,
+        cost: impl FnMut(Option<usize>) -> Option<f32>,
+    ) -> Self {
+        let mut grid = Self::new(map.width, map.height, map.tile_size).with_origin(map.origin);
+        grid.sync_tilemap_with(map, cost);
+        grid.revision = 0;
+        grid
+    }
+
+    /// Re-read walkability from `map` after its tiles changed, the same way
+    /// as [`NavGrid::from_tilemap`]. Returns how many cells changed.
+    pub fn sync_tilemap(&mut self, map: &TileMap) -> usize {
+        self.read_cells(|col, row| map.solid_tile(col, row).is_none().then_some(1.0))
+    }
+
+    /// Re-read costs from `map` after its tiles changed, the same way as
+    /// [`NavGrid::from_tilemap_with`]. Returns how many cells changed.
+    pub fn sync_tilemap_with(
+        &mut self,
+        map: &TileMap,
+        mut cost: impl FnMut(Option<usize>) -> Option<f32>,
+    ) -> usize {
+        self.read_cells(|col, row| cost(map.get(col, row)))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    pub fn origin(&self) -> Vec2 {
+        self.origin
+    }
+
+    /// Bumped by every change to the grid; see [`GridPathCache`].
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// The cost of entering a cell, or `None` if it is blocked or outside the
+    /// grid.
+    pub fn cost(&self, col: usize, row: usize) -> Option<f32> {
+        self.index(col, row).and_then(|index| self.costs[index])
+    }
+
+    /// Set a cell's cost, or block it with `None`. Costs below 1 are raised
+    /// to 1 so the search heuristic stays admissible. Returns whether the
+    /// cell changed.
+    pub fn set_cost(&mut self, col: usize, row: usize, cost: Option<f32>) -> bool {
+        let changed = self.write_cell(col, row, cost);
+        if changed {
+            self.revision += 1;
+            self.update_clearance();
+        }
+        changed
+    }
+
+    /// Cells between this one and the nearest blocked cell or grid edge,
+    /// counting diagonals as one step; 0 for blocked cells.
+    pub fn clearance(&self, col: usize, row: usize) -> u16 {
+        self.index(col, row)
+            .map_or(0, |index| self.clearance[index])
+    }
+
+    /// Whether `agent` can stand in a cell without touching a blocked one.
+    pub fn is_walkable(&self, col: usize, row: usize, agent: &NavAgent) -> bool {
+        self.clearance(col, row) >= self.required_clearance(agent)
+    }
+
+    /// The cell containing a world position.
+    pub fn cell_at(&self, point: Vec2) -> Option<(usize, usize)> {
+        let local = (point - self.origin) / self.cell_size;
+        if local.x < 0.0 || local.y < 0.0 {
+            return None;
+        }
+        let (col, row) = (local.x as usize, local.y as usize);
+        (col < self.width && row < self.height).then_some((col, row))
+    }
+
+    pub fn cell_center(&self, col: usize, row: usize) -> Vec2 {
+        self.origin + (Vec2::new(col as f32, row as f32) + 0.5) * self.cell_size
+    }
+
+    /// The cheapest path between two cells, or `None` if either is not
+    /// walkable for `agent` or they are not connected.
+    pub fn find_path(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+        agent: &NavAgent,
+    ) -> Option<GridPath> {
+        let need = self.required_clearance(agent);
+        let open = |(col, row): (usize, usize)| self.open(col as i64, row as i64, need);
+        if !open(from) || !open(to) {
+            return None;
+        }
+        if from == to {
+            return Some(GridPath {
+                cells: vec![from],
+                cost: 0.0,
+            });
+        }
+        match agent.search {
+            PathSearch::Jps if agent.diagonals && self.weighted == 0 => {
+                self.jump_point_search(from, to, need)
+            }
+            _ => self.a_star(from, to, agent.diagonals, need),
+        }
+    }
+
+    /// A path between two world positions as the points to walk through:
+    /// the corners of the cell path, ending exactly at `to`.
+    pub fn find_points(&self, from: Vec2, to: Vec2, agent: &NavAgent) -> Option<Vec<Vec2>> {
+        let path = self.find_path(self.cell_at(from)?, self.cell_at(to)?, agent)?;
+        let mut points = path.points(self);
+        points.remove(0);
+        match points.last_mut() {
+            Some(last) => *last = to,
+            None => points.push(to),
+        }
+        Some(points)
+    }
+
+    pub(super) fn required_clearance(&self, agent: &NavAgent) -> u16 {
+        // A cell with clearance `c` has `c - 0.5` cells of open ground around
+        // its centre.
+        (agent.radius / self.cell_size + 0.5).ceil().max(1.0) as u16
+    }
+
+    fn index(&self, col: usize, row: usize) -> Option<usize> {
+        (col < self.width && row < self.height).then_some(row * self.width + col)
+    }
+
+    pub(super) fn open(&self, col: i64, row: i64, need: u16) -> bool {
+        col >= 0
+            && row >= 0
+            && (col as usize) < self.width
+            && (row as usize) < self.height
+            && self.clearance[row as usize * self.width + col as usize] >= need
+    }
+
+    /// Whether a single step between neighbouring cells is allowed: both
+    /// open, and a diagonal does not cut a blocked corner.
+    pub(super) fn can_step(&self, (col, row): (i64, i64), (dx, dy): (i64, i64), need: u16) -> bool {
+        self.open(col + dx, row + dy, need)
+            && (dx == 0
+                || dy == 0
+                || (self.open(col + dx, row, need) && self.open(col, row + dy, need)))
+    }
+
+    fn read_cells(&mut self, mut cost: impl FnMut(usize, usize) -> Option<f32>) -> usize {
+        let mut changed = 0;
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if self.write_cell(col, row, cost(col, row)) {
+                    changed += 1;
+                }
+            }
+        }
+        if changed > 0 {
+            self.revision += 1;
+            self.update_clearance();
+        }
+        changed
+    }
+
+    fn write_cell(&mut self, col: usize, row: usize, cost: Option<f32>) -> bool {
+        let Some(index) = self.index(col, row) else {
+            return false;
+        };
+        let cost = cost
+            .filter(|cost| cost.is_finite())
+            .map(|cost| cost.max(1.0));
+        let old = self.costs[index];
+        if old == cost {
+            return false;
+        }
+        let weighted = |cost: Option<f32>| cost.is_some_and(|cost| cost > 1.0);
+        self.weighted = self.weighted + usize::from(weighted(cost)) - usize::from(weighted(old));
+        self.costs[index] = cost;
+        true
+    }
+
+    /// A two-pass chessboard distance transform from the blocked cells and
+    /// the grid edge.
+    fn update_clearance(&mut self) {
+        let (w, h) = (self.width, self.height);
+        for row in 0..h {
+            for col in 0..w {
+                let index = row * w + col;
+                self.clearance[index] = match self.costs[index] {
+                    None => 0,
+                    Some(_) => (col + 1)
+                        .min(row + 1)
+                        .min(w - col)
+                        .min(h - row)
+                        .min(u16::MAX as usize) as u16,
+                };
+            }
+        }
+        let mut relax = |col: usize, row: usize, offsets: &[(i64, i64); 4]| {
+            let index = row * w + col;
+            for &(dx, dy) in offsets {
+                let (c, r) = (col as i64 + dx, row as i64 + dy);
+                if c >= 0 && r >= 0 && (c as usize) < w && (r as usize) < h {
+                    let near = self.clearance[r as usize * w + c as usize].saturating_add(1);
+                    self.clearance[index] = self.clearance[index].min(near);
+                }
+            }
+        };
+        for row in 0..h {
+            for col in 0..w {
+                relax(col, row, &[(-1, 0), (-1, -1), (0, -1), (1, -1)]);
+            }
+        }
+        for row in (0..h).rev() {
+            for col in (0..w).rev() {
+                relax(col, row, &[(1, 0), (1, 1), (0, 1), (-1, 1)]);
+            }
+        }
+    }
+
+    fn heuristic(&self, a: usize, b: usize, diagonals: bool) -> f32 {
+        let dx = (a % self.width).abs_diff(b % self.width) as f32;
+        let dy = (a / self.width).abs_diff(b / self.width) as f32;
+        if diagonals {
+            dx.max(dy) + (SQRT_2 - 1.0) * dx.min(dy)
+        } else {
+            dx + dy
+        }
+    }
+
+    fn a_star(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+        diagonals: bool,
+        need: u16,
+    ) -> Option<GridPath> {
+        let w = self.width;
+        let (start, goal) = (from.1 * w + from.0, to.1 * w + to.0);
+        let mut g = vec![f32::INFINITY; w * self.height];
+        let mut parent = vec![NO_PARENT; w * self.height];
+        let mut open = BinaryHeap::new();
+        g[start] = 0.0;
+        open.push(OpenNode {
+            f: self.heuristic(start, goal, diagonals),
+            g: 0.0,
+            index: start,
+        });
+        let steps = if diagonals { &STEPS[..] } else { &STEPS[..4] };
+
+        while let Some(node) = open.pop() {
+            if node.g > g[node.index] {
+                continue;
+            }
+            if node.index == goal {
+                return Some(self.trace(&parent, goal, node.g));
+            }
+            let cell = ((node.index % w) as i64, (node.index / w) as i64);
+            for &step in steps {
+                if !self.can_step(cell, step, need) {
+                    continue;
+                }
+                let next = (cell.1 + step.1) as usize * w + (cell.0 + step.0) as usize;
+                let length = if step.0 != 0 && step.1 != 0 {
+                    SQRT_2
+                } else {
+                    1.0
+                };
+                let cost = node.g + length * self.costs[next].unwrap_or(1.0);
+                if cost < g[next] {
+                    g[next] = cost;
+                    parent[next] = node.index;
+                    open.push(OpenNode {
+                        f: cost + self.heuristic(next, goal, diagonals),
+                        g: cost,
+                        index: next,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Jump point search (the variant that never cuts corners), on a grid
+    /// where every walkable cell costs 1.
+    fn jump_point_search(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+        need: u16,
+    ) -> Option<GridPath> {
+        let w = self.width;
+        let (start, goal) = (from.1 * w + from.0, to.1 * w + to.0);
+        let goal_cell = (to.0 as i64, to.1 as i64);
+        let mut g = vec![f32::INFINITY; w * self.height];
+        let mut parent = vec![NO_PARENT; w * self.height];
+        let mut open = BinaryHeap::new();
+        g[start] = 0.0;
+        open.push(OpenNode {
+            f: self.heuristic(start, goal, true),
+            g: 0.0,
+            index: start,
+        });
+
+        while let Some(node) = open.pop() {
+            if node.g > g[node.index] {
+                continue;
+            }
+            if node.index == goal {
+                let mut path = self.trace(&parent, goal, node.g);
+                path.fill_jumps();
+                return Some(path);
+            }
+            let cell = ((node.index % w) as i64, (node.index / w) as i64);
+            let came_from = (parent[node.index] != NO_PARENT).then(|| {
+                let p = parent[node.index];
+                (
+                    (cell.0 - (p % w) as i64).signum(),
+                    (cell.1 - (p / w) as i64).signum(),
+                )
+            });
+            for dir in self.pruned_directions(cell, came_from, need) {
+                let Some(jump) = self.jump((cell.0 + dir.0, cell.1 + dir.1), dir, goal_cell, need)
+                else {
+                    continue;
+                };
+                let next = jump.1 as usize * w + jump.0 as usize;
+                let cost = node.g + self.heuristic(node.index, next, true);
+                if cost < g[next] {
+                    g[next] = cost;
+                    parent[next] = node.index;
+                    open.push(OpenNode {
+                        f: cost + self.heuristic(next, goal, true),
+                        g: cost,
+                        index: next,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    fn pruned_directions(
+        &self,
+        (x, y): (i64, i64),
+        came_from: Option<(i64, i64)>,
+        need: u16,
+    ) -> Vec<(i64, i64)> {
+        let open = |dx: i64, dy: i64| self.open(x + dx, y + dy, need);
+        let Some((dx, dy)) = came_from else {
+            return STEPS
+                .into_iter()
+                .filter(|&step| self.can_step((x, y), step, need))
+                .collect();
+        };
+        let mut dirs = Vec::with_capacity(5);
+        if dx != 0 && dy != 0 {
+            if open(0, dy) {
+                dirs.push((0, dy));
+            }
+            if open(dx, 0) {
+                dirs.push((dx, 0));
+            }
+            if open(0, dy) && open(dx, 0) {
+                dirs.push((dx, dy));
+            }
+        } else if dx != 0 {
+            let (up, down) = (open(0, 1), open(0, -1));
+            if open(dx, 0) {
+                dirs.push((dx, 0));
+                if up {
+                    dirs.push((dx, 1));
+                }
+                if down {
+                    dirs.push((dx, -1));
+                }
+            }
+            if up {
+                dirs.push((0, 1));
+            }
+            if down {
+                dirs.push((0, -1));
+            }
+        } else {
+            let (right, left) = (open(1, 0), open(-1, 0));
+            if open(0, dy) {
+                dirs.push((0, dy));
+                if right {
+                    dirs.push((1, dy));
+                }
+                if left {
+                    dirs.push((-1, dy));
+                }
+            }
+            if right {
+                dirs.push((1, 0));
+            }
+            if left {
+                dirs.push((-1, 0));
+            }
+        }
+        dirs
+    }
+
+    /// Walk from `(x, y)` in `dir` until reaching the goal or a cell with a
+    /// forced neighbour.
+    fn jump(
+        &self,
+        (mut x, mut y): (i64, i64),
+        (dx, dy): (i64, i64),
+        goal: (i64, i64),
+        need: u16,
+    ) -> Option<(i64, i64)> {
+        let open = |x: i64, y: i64| self.open(x, y, need);
+        loop {
+            if !open(x, y) {
+                return None;
+            }
+            if (x, y) == goal {
+                return Some((x, y));
+            }
+            if dx != 0 && dy != 0 {
+                if self.jump((x + dx, y), (dx, 0), goal, need).is_some()
+                    || self.jump((x, y + dy), (0, dy), goal, need).is_some()
+                {
+                    return Some((x, y));
+                }
+                if !(open(x + dx, y) && open(x, y + dy)) {
+                    return None;
+                }
+            } else if dx != 0 {
+                if (open(x, y - 1) && !open(x - dx, y - 1))
+                    || (open(x, y + 1) && !open(x - dx, y + 1))
+                {
+                    return Some((x, y));
+                }
+            } else if (open(x - 1, y) && !open(x - 1, y - dy))
+                || (open(x + 1, y) && !open(x + 1, y - dy))
+            {
+                return Some((x, y));
+            }
+            x += dx;
+            y += dy;
+        }
+    }
+
+    fn trace(&self, parent: &[usize], goal: usize, cost: f32) -> GridPath {
+        let mut cells = vec![(goal % self.width, goal / self.width)];
+        let mut index = goal;
+        while parent[index] != NO_PARENT {
+            index = parent[index];
+            cells.push((index % self.width, index / self.width));
+        }
+        cells.reverse();
+        GridPath { cells, cost }
+    }
+
+    /// The cost of walking `cells` in order, or `None` if a step is no longer
+    /// allowed.
+    fn walk_cost(&self, cells: &[(usize, usize)], agent: &NavAgent) -> Option<f32> {
+        let need = self.required_clearance(agent);
+        let first = cells.first()?;
+        if !self.open(first.0 as i64, first.1 as i64, need) {
+            return None;
+        }
+        let mut cost = 0.0;
+        for pair in cells.windows(2) {
+            let (a, b) = (
+                (pair[0].0 as i64, pair[0].1 as i64),
+                (pair[1].0 as i64, pair[1].1 as i64),
+            );
+            let step = (b.0 - a.0, b.1 - a.1);
+            let diagonal = step.0 != 0 && step.1 != 0;
+            if step.0.abs() > 1 || step.1.abs() > 1 || (diagonal && !agent.diagonals) {
+                return None;
+            }
+            if !self.can_step(a, step, need) {
+                return None;
+            }
+            let length = if diagonal { SQRT_2 } else { 1.0 };
+            cost += length * self.costs[b.1 as usize * self.width + b.0 as usize]?;
+        }
+        Some(cost)
+    }
+}
+
+/// A path through a [`NavGrid`], one entry per cell walked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridPath {
+    pub cells: Vec<(usize, usize)>,
+    /// Distance walked, scaled by the cost of each cell entered.
+    pub cost: f32,
+}
+
+impl GridPath {
+    /// World positions of the cells where the path turns, from the first
+    /// cell to the last.
+    pub fn points(&self, grid: &NavGrid) -> Vec<Vec2> {
+        let mut points = Vec::new();
+        for (i, &(col, row)) in self.cells.iter().enumerate() {
+            let turns = match (
+                i.checked_sub(1).map(|p| self.cells[p]),
+                self.cells.get(i + 1),
+            ) {
+                (Some(prev), Some(&next)) => {
+                    let into = (col as i64 - prev.0 as i64, row as i64 - prev.1 as i64);
+                    let out = (next.0 as i64 - col as i64, next.1 as i64 - row as i64);
+                    into != out
+                }
+                _ => true,
+            };
+            if turns {
+                points.push(grid.cell_center(col, row));
+            }
+        }
+        points
+    }
+
+    /// Whether `agent` can still walk the whole path at no more than its
+    /// cost. A change that opens a shortcut elsewhere leaves a path valid.
+    pub fn is_valid(&self, grid: &NavGrid, agent: &NavAgent) -> bool {
+        grid.walk_cost(&self.cells, agent)
+            .is_some_and(|cost| cost <= self.cost + 1e-3)
+    }
+
+    /// Expand jump points into every cell between them.
+    fn fill_jumps(&mut self) {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for pair in self.cells.windows(2) {
+            let (mut x, mut y) = (pair[0].0 as i64, pair[0].1 as i64);
+            let (tx, ty) = (pair[1].0 as i64, pair[1].1 as i64);
+            while (x, y) != (tx, ty) {
+                cells.push((x as usize, y as usize));
+                x += (tx - x).signum();
+                y += (ty - y).signum();
+            }
+        }
+        cells.extend(self.cells.last().copied());
+        self.cells = cells;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct PathKey {
+    from: (usize, usize),
+    to: (usize, usize),
+    radius: u32,
+    diagonals: bool,
+    search: PathSearch,
+}
+
+#[derive(Debug, Clone)]
+struct CachedPath {
+    revision: u64,
+    path: Option<GridPath>,
+}
+
+/// Remembers grid paths so agents asking for the same route share one
+/// search.
+///
+/// When the grid's revision moves on, a cached path is checked cell by cell
+/// and only searched again if a change blocked it or made it dearer; a
+/// cached "no path" is always searched again, since any change might have
+/// opened one. The oldest entries are dropped past `capacity`.
+#[derive(Debug, Clone)]
+pub struct GridPathCache {
+    capacity: usize,
+    entries: HashMap<PathKey, CachedPath>,
+    order: VecDeque<PathKey>,
+    searches: u64,
+}
+
+impl GridPathCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            searches: 0,
+        }
+    }
+
+    /// [`NavGrid::find_path`], served from the cache when it can be.
+    pub fn find_path(
+        &mut self,
+        grid: &NavGrid,
+        from: (usize, usize),
+        to: (usize, usize),
+        agent: &NavAgent,
+    ) -> Option<&GridPath> {
+        let key = PathKey {
+            from,
+            to,
+            radius: agent.radius.to_bits(),
+            diagonals: agent.diagonals,
+            search: agent.search,
+        };
+        let fresh = match self.entries.get_mut(&key) {
+            Some(entry) if entry.revision == grid.revision() => true,
+            Some(CachedPath {
+                revision,
+                path: Some(path),
+            }) if path.is_valid(grid, agent) => {
+                *revision = grid.revision();
+                true
+            }
+            _ => false,
+        };
+        if !fresh {
+            self.searches += 1;
+            let entry = CachedPath {
+                revision: grid.revision(),
+                path: grid.find_path(from, to, agent),
+            };
+            if self.entries.insert(key, entry).is_none() {
+                self.order.push_back(key);
+                while self.order.len() > self.capacity {
+                    if let Some(oldest) = self.order.pop_front() {
+                        self.entries.remove(&oldest);
+                    }
+                }
+            }
+        }
+        self.entries.get(&key)?.path.as_ref()
+    }
+
+    /// How many searches the cache has run, counting first requests and
+    /// replans.
+    pub fn searches(&self) -> u64 {
+        self.searches
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Rng;
+    use crate::renderer::TextureId;
+    use crate::world::tilemap::TileDef;
+
+    /// A grid from rows of text, top row first: `#` blocked, digits are
+    /// costs, anything else costs 1.
+    fn grid(rows: &[&str]) -> NavGrid {
+        let height = rows.len();
+        let mut grid = NavGrid::new(rows[0].len(), height, 10.0);
+        for (i, line) in rows.iter().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                let cost = match c {
+                    '#' => None,
+                    c => Some(c.to_digit(10).unwrap_or(1) as f32),
+                };
+                grid.set_cost(col, height - 1 - i, cost);
+            }
+        }
+        grid
+    }
+
+    fn assert_walkable(grid: &NavGrid, path: &GridPath, agent: &NavAgent) {
+        let cost = grid
+            .walk_cost(&path.cells, agent)
+            .expect("path steps are legal");
+        assert!((cost - path.cost).abs() < 1e-3, "{cost} vs {}", path.cost);
+    }
+
+    #[test]
+    fn a_star_prefers_cheap_cells_and_never_cuts_corners() {
+        let g = grid(&[
+            ".....", //
+            ".###.", //
+            ".999.", //
+            ".....", //
+        ]);
+        let agent = NavAgent::new(0.0);
+        let path = g.find_path((0, 1), (4, 1), &agent).unwrap();
+        // Around the expensive row rather than through it.
+        assert!(path.cells.iter().all(|&(_, row)| row <= 1 || row == 3));
+        assert!(path
+            .cells
+            .iter()
+            .all(|&cell| g.cost(cell.0, cell.1) == Some(1.0)));
+        assert_walkable(&g, &path, &agent);
+
+        // A blocked corner forces two straight steps instead of a diagonal.
+        let g = grid(&["..", "#."]);
+        let path = g.find_path((0, 1), (1, 0), &agent).unwrap();
+        assert_eq!(path.cells, vec![(0, 1), (1, 1), (1, 0)]);
+
+        let rook = NavAgent::new(0.0).with_diagonals(false);
+        let path = grid(&["...", "...", "..."])
+            .find_path((0, 0), (2, 2), &rook)
+            .unwrap();
+        assert_eq!(path.cells.len(), 5);
+        assert_eq!(path.cost, 4.0);
+    }
+
+    #[test]
+    fn wide_agents_keep_clear_of_walls_and_skip_narrow_gaps() {
+        let g = grid(&[
+            "##########", //
+            "#........#", //
+            "#........#", //
+            "#........#", //
+            "####.#####", //
+            "#........#", //
+            "#........#", //
+            "#........#", //
+            "##########", //
+        ]);
+        assert_eq!(g.clearance(4, 4), 1);
+        assert_eq!(g.clearance(4, 2), 2);
+        let small = NavAgent::new(4.0);
+        let wide = NavAgent::new(12.0);
+        assert!(g.is_walkable(4, 4, &small));
+        assert!(!g.is_walkable(4, 4, &wide));
+        assert!(g.find_path((2, 2), (2, 6), &small).is_some());
+        assert!(g.find_path((2, 2), (2, 6), &wide).is_none());
+
+        // A wide agent walks the middle of a room, not along its walls.
+        let path = g.find_path((2, 2), (7, 2), &wide).unwrap();
+        assert!(path
+            .cells
+            .iter()
+            .all(|&(col, row)| g.clearance(col, row) >= 2));
+    }
+
+    #[test]
+    fn jump_point_search_matches_a_star() {
+        let mut rng = Rng::new(11);
+        for _ in 0..40 {
+            let mut g = NavGrid::new(24, 18, 1.0);
+            for row in 0..18 {
+                for col in 0..24 {
+                    if rng.chance(0.3) {
+                        g.set_cost(col, row, None);
+                    }
+                }
+            }
+            let from = (rng.usize(24), rng.usize(18));
+            let to = (rng.usize(24), rng.usize(18));
+            let a_star = g.find_path(from, to, &NavAgent::new(0.0));
+            let jps = g.find_path(from, to, &NavAgent::new(0.0).with_search(PathSearch::Jps));
+            assert_eq!(a_star.is_some(), jps.is_some());
+            if let (Some(a_star), Some(jps)) = (a_star, jps) {
+                assert!((a_star.cost - jps.cost).abs() < 1e-3);
+                assert_eq!(jps.cells.first(), Some(&from));
+                assert_eq!(jps.cells.last(), Some(&to));
+                assert_walkable(&g, &jps, &NavAgent::new(0.0));
+            }
+        }
+    }
+
+    #[test]
+    fn tilemaps_build_grids_and_points_follow_corners() {
+        let mut map = TileMap::new(6, 4, 16.0).with_origin(Vec2::new(-48.0, 0.0));
+        let wall = map.add_tile(TileDef::solid(TextureId(0)));
+        let mud = map.add_tile(TileDef::solid(TextureId(0)).with_collision(false));
+        for row in 0..3 {
+            map.set(2, row, Some(wall));
+        }
+        map.set(4, 3, Some(mud));
+
+        let g = NavGrid::from_tilemap(&map);
+        assert_eq!(g.cost(2, 0), None);
+        assert_eq!(g.cost(4, 3), Some(1.0));
+        assert_eq!(g.cell_at(Vec2::new(-40.0, 8.0)), Some((0, 0)));
+        let points = g
+            .find_points(
+                Vec2::new(-40.0, 8.0),
+                Vec2::new(40.0, 4.0),
+                &NavAgent::new(0.0),
+            )
+            .unwrap();
+        assert_eq!(points.last(), Some(&Vec2::new(40.0, 4.0)));
+        // Up and over the wall through the gap in the top row.
+        assert!(points.iter().any(|p| p.y > 48.0));
+
+        let weighted = NavGrid::from_tilemap_with(&map, |tile| match tile {
+            Some(id) if id == wall => None,
+            Some(_) => Some(5.0),
+            None => Some(1.0),
+        });
+        assert_eq!(weighted.cost(4, 3), Some(5.0));
+        assert_eq!(weighted.revision(), 0);
+    }
+
+    #[test]
+    fn cache_reuses_paths_until_a_change_touches_them() {
+        let mut g = NavGrid::new(8, 3, 1.0);
+        let agent = NavAgent::new(0.0);
+        let mut cache = GridPathCache::new(8);
+        let first = cache.find_path(&g, (0, 0), (7, 0), &agent).unwrap().clone();
+        assert_eq!(first.cells.len(), 8);
+        cache.find_path(&g, (0, 0), (7, 0), &agent);
+        assert_eq!(cache.searches(), 1);
+
+        // A change off the path keeps it.
+        g.set_cost(3, 2, None);
+        assert_eq!(cache.find_path(&g, (0, 0), (7, 0), &agent), Some(&first));
+        assert_eq!(cache.searches(), 1);
+
+        // Blocking a cell on it replans around the block.
+        g.set_cost(3, 0, None);
+        let around = cache.find_path(&g, (0, 0), (7, 0), &agent).unwrap().clone();
+        assert_eq!(cache.searches(), 2);
+        assert!(!around.cells.contains(&(3, 0)));
+        assert!(!first.is_valid(&g, &agent));
+
+        // Walling the goal off is cached as no path until the next change.
+        g.set_cost(3, 1, None);
+        assert!(cache.find_path(&g, (0, 0), (7, 0), &agent).is_none());
+        assert!(cache.find_path(&g, (0, 0), (7, 0), &agent).is_none());
+        assert_eq!(cache.searches(), 3);
+        g.set_cost(3, 2, Some(1.0));
+        assert!(cache.find_path(&g, (0, 0), (7, 0), &agent).is_some());
+        assert_eq!(cache.searches(), 4);
+    }
+}
@@ -0,0 +1,521 @@
+//! Navigation meshes: walkable polygons triangulated into a graph of
+//! triangles, searched with A* and smoothed with a funnel pass.
+
+use std::collections::{BinaryHeap, HashMap};
+
+use glam::Vec2;
+
+use super::{NavAgent, OpenNode};
+use crate::world::shape::{in_triangle, triangulate};
+
+const NO_PARENT: usize = usize::MAX;
+/// Vertices closer than this are welded, so regions added separately connect
+/// where they share an edge.
+const WELD: f32 = 1e-3;
+
+#[derive(Debug, Clone)]
+struct NavTriangle {
+    /// Counter-clockwise.
+    vertices: [usize; 3],
+    /// The triangle across edge `i`, from vertex `i` to vertex `i + 1`.
+    neighbours: [Option<usize>; 3],
+}
+
+/// Walkable space as connected triangles.
+///
+/// Add walkable regions with [`NavMesh::add_polygon`]: a simple outline in
+/// either winding, with optional holes for obstacles inside it. Regions
+/// connect wherever they share both ends of an edge, so a level can be built
+/// from rooms and the doorways between them.
+#[derive(Debug, Clone, Default)]
+pub struct NavMesh {
+    vertices: Vec<Vec2>,
+    triangles: Vec<NavTriangle>,
+    welded: HashMap<(i64, i64), usize>,
+    /// Edges with a triangle on only one side so far, by sorted vertex pair.
+    open_edges: HashMap<(usize, usize), (usize, usize)>,
+}
+
+impl NavMesh {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A mesh of hole-free regions.
+    pub fn from_polygons(polygons: &[Vec<Vec2>]) -> Self {
+        let mut mesh = Self::new();
+        for polygon in polygons {
+            mesh.add_polygon(polygon, &[]);
+        }
+        mesh
+    }
+
+    /// Triangulate a walkable region and join it to the mesh. Holes must lie
+    /// inside the outline and not touch each other.
+    pub fn add_polygon(&mut self, outline: &[Vec2], holes: &[Vec<Vec2>]) {
+        if outline.len() < 3 {
+            return;
+        }
+        let mut points = outline.to_vec();
+        if signed_area(&points) < 0.0 {
+            points.reverse();
+        }
+        let mut holes: Vec<Vec<Vec2>> = holes
+            .iter()
+            .filter(|hole| hole.len() >= 3)
+            .map(|hole| {
+                let mut hole = hole.clone();
+                if signed_area(&hole) > 0.0 {
+                    hole.reverse();
+                }
+                hole
+            })
+            .collect();
+        // Bridge the right-most hole first so later bridges can cross
+        // towards the outline past it.
+        holes.sort_by(|a, b| max_x(b).total_cmp(&max_x(a)));
+        for i in 0..holes.len() {
+            bridge_hole(&mut points, &holes[i], &holes[i + 1..]);
+        }
+        for triangle in triangulate(&points) {
+            self.add_triangle(triangle);
+        }
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Every triangle's corners, counter-clockwise.
+    pub fn triangles(&self) -> impl Iterator<Item = [Vec2; 3]> + '_ {
+        self.triangles
+            .iter()
+            .map(|triangle| triangle.vertices.map(|v| self.vertices[v]))
+    }
+
+    /// The triangle containing a point.
+    pub fn triangle_at(&self, point: Vec2) -> Option<usize> {
+        self.triangles.iter().position(|triangle| {
+            let [a, b, c] = triangle.vertices.map(|v| self.vertices[v]);
+            in_triangle(point, a, b, c)
+        })
+    }
+
+    /// The point on the mesh nearest to `point`.
+    pub fn closest_point(&self, point: Vec2) -> Option<Vec2> {
+        self.locate(point).map(|(_, point)| point)
+    }
+
+    /// The shortest path from `from` to `to` as the points to walk through,
+    /// ending at `to`. Ends off the mesh are moved to the nearest point on
+    /// it; a moved start is the first point. Doorways narrower than the
+    /// agent are not used, and corners are passed at `agent.radius`.
+    pub fn find_path(&self, from: Vec2, to: Vec2, agent: &NavAgent) -> Option<Vec<Vec2>> {
+        let (start_triangle, start) = self.locate(from)?;
+        let (goal_triangle, goal) = self.locate(to)?;
+        let corridor = self.corridor(start_triangle, start, goal_triangle, goal, agent.radius)?;
+
+        let mut portals = vec![(start, start)];
+        for pair in corridor.windows(2) {
+            let triangle = &self.triangles[pair[0]];
+            let edge = triangle
+                .neighbours
+                .iter()
+                .position(|&n| n == Some(pair[1]))?;
+            let right = self.vertices[triangle.vertices[edge]];
+            let left = self.vertices[triangle.vertices[(edge + 1) % 3]];
+            let inset = (left - right).normalize_or_zero() * agent.radius;
+            portals.push((left - inset, right + inset));
+        }
+        portals.push((goal, goal));
+
+        let mut points = funnel(&portals);
+        if start != from {
+            points.insert(0, start);
+        }
+        Some(points)
+    }
+
+    fn add_triangle(&mut self, corners: [Vec2; 3]) {
+        let vertices = corners.map(|corner| self.weld(corner));
+        if vertices[0] == vertices[1] || vertices[1] == vertices[2] || vertices[0] == vertices[2] {
+            return;
+        }
+        let index = self.triangles.len();
+        let mut neighbours = [None; 3];
+        for (edge, neighbour) in neighbours.iter_mut().enumerate() {
+            let (a, b) = (vertices[edge], vertices[(edge + 1) % 3]);
+            let key = (a.min(b), a.max(b));
+            match self.open_edges.remove(&key) {
+                Some((other, other_edge)) => {
+                    *neighbour = Some(other);
+                    self.triangles[other].neighbours[other_edge] = Some(index);
+                }
+                None => {
+                    self.open_edges.insert(key, (index, edge));
+                }
+            }
+        }
+        self.triangles.push(NavTriangle {
+            vertices,
+            neighbours,
+        });
+    }
+
+    fn weld(&mut self, point: Vec2) -> usize {
+        let key = (
+            (point.x / WELD).round() as i64,
+            (point.y / WELD).round() as i64,
+        );
+        *self.welded.entry(key).or_insert_with(|| {
+            self.vertices.push(point);
+            self.vertices.len() - 1
+        })
+    }
+
+    fn locate(&self, point: Vec2) -> Option<(usize, Vec2)> {
+        if let Some(triangle) = self.triangle_at(point) {
+            return Some((triangle, point));
+        }
+        self.triangles
+            .iter()
+            .enumerate()
+            .map(|(index, triangle)| {
+                let [a, b, c] = triangle.vertices.map(|v| self.vertices[v]);
+                let nearest = [(a, b), (b, c), (c, a)]
+                    .into_iter()
+                    .map(|(p, q)| closest_on_segment(point, p, q))
+                    .min_by(|p, q| {
+                        p.distance_squared(point)
+                            .total_cmp(&q.distance_squared(point))
+                    })
+                    .unwrap_or(a);
+                (index, nearest)
+            })
+            .min_by(|(_, p), (_, q)| {
+                p.distance_squared(point)
+                    .total_cmp(&q.distance_squared(point))
+            })
+    }
+
+    /// A* over triangles, through edges at least as wide as the agent.
+    fn corridor(
+        &self,
+        start: usize,
+        start_point: Vec2,
+        goal: usize,
+        goal_point: Vec2,
+        radius: f32,
+    ) -> Option<Vec<usize>> {
+        let n = self.triangles.len();
+        let mut g = vec![f32::INFINITY; n];
+        let mut parent = vec![NO_PARENT; n];
+        // Where the search entered each triangle, for measuring steps.
+        let mut entry = vec![Vec2::ZERO; n];
+        let mut open = BinaryHeap::new();
+        g[start] = 0.0;
+        entry[start] = start_point;
+        open.push(OpenNode {
+            f: start_point.distance(goal_point),
+            g: 0.0,
+            index: start,
+        });
+
+        while let Some(node) = open.pop() {
+            if node.g > g[node.index] {
+                continue;
+            }
+            if node.index == goal {
+                let mut corridor = vec![goal];
+                let mut index = goal;
+                while parent[index] != NO_PARENT {
+                    index = parent[index];
+                    corridor.push(index);
+                }
+                corridor.reverse();
+                return Some(corridor);
+            }
+            let triangle = &self.triangles[node.index];
+            for (edge, neighbour) in triangle.neighbours.iter().enumerate() {
+                let Some(next) = *neighbour else {
+                    continue;
+                };
+                let a = self.vertices[triangle.vertices[edge]];
+                let b = self.vertices[triangle.vertices[(edge + 1) % 3]];
+                if a.distance(b) < radius * 2.0 {
+                    continue;
+                }
+                let crossing = closest_on_segment(entry[node.index], a, b);
+                let cost = node.g + entry[node.index].distance(crossing);
+                if cost < g[next] {
+                    g[next] = cost;
+                    parent[next] = node.index;
+                    entry[next] = crossing;
+                    open.push(OpenNode {
+                        f: cost + crossing.distance(goal_point),
+                        g: cost,
+                        index: next,
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+/// The simple stupid funnel algorithm: pull a string through the portals,
+/// given as `(left, right)` seen walking from the first to the last. Returns
+/// the corners after the first portal, ending at the last.
+fn funnel(portals: &[(Vec2, Vec2)]) -> Vec<Vec2> {
+    // Positive when `c` is left of the line from `a` through `b`.
+    let side = |a: Vec2, b: Vec2, c: Vec2| (b - a).perp_dot(c - a);
+    let mut points = Vec::new();
+    let mut apex = portals[0].0;
+    let (mut left, mut right) = portals[0];
+    let (mut left_index, mut right_index) = (0, 0);
+    let mut i = 1;
+    while i < portals.len() {
+        let (next_left, next_right) = portals[i];
+
+        if side(apex, right, next_right) >= 0.0 {
+            if apex == right || side(apex, left, next_right) < 0.0 {
+                right = next_right;
+                right_index = i;
+            } else {
+                // The right side crossed the left: the left is a corner.
+                points.push(left);
+                apex = left;
+                let apex_index = left_index;
+                (left, right) = (apex, apex);
+                (left_index, right_index) = (apex_index, apex_index);
+                i = apex_index + 1;
+                continue;
+            }
+        }
+
+        if side(apex, left, next_left) <= 0.0 {
+            if apex == left || side(apex, right, next_left) > 0.0 {
+                left = next_left;
+                left_index = i;
+            } else {
+                points.push(right);
+                apex = right;
+                let apex_index = right_index;
+                (left, right) = (apex, apex);
+                (left_index, right_index) = (apex_index, apex_index);
+                i = apex_index + 1;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    let goal = portals[portals.len() - 1].0;
+    if points.last() != Some(&goal) {
+        points.push(goal);
+    }
+    points
+}
+
+fn signed_area(points: &[Vec2]) -> f32 {
+    (0..points.len())
+        .map(|i| points[i].perp_dot(points[(i + 1) % points.len()]))
+        .sum::<f32>()
+        / 2.0
+}
+
+fn max_x(points: &[Vec2]) -> f32 {
+    points.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max)
+}
+
+fn closest_on_segment(point: Vec2, a: Vec2, b: Vec2) -> Vec2 {
+    let ab = b - a;
+    let t = ((point - a).dot(ab) / ab.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+    a + ab * t
+}
+
+/// Whether two segments cross at a point inside both.
+fn segments_cross(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    let side = |p: Vec2, q: Vec2, r: Vec2| (q - p).perp_dot(r - p);
+    let (d1, d2) = (side(c, d, a), side(c, d, b));
+    let (d3, d4) = (side(a, b, c), side(a, b, d));
+    ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+}
+
+/// Splice a clockwise hole into a counter-clockwise outline through a
+/// bridge from the hole's right-most vertex to the nearest outline vertex it
+/// can see, leaving one polygon to ear-clip.
+fn bridge_hole(outline: &mut Vec<Vec2>, hole: &[Vec2], later_holes: &[Vec<Vec2>]) {
+    let start = (0..hole.len())
+        .max_by(|&a, &b| hole[a].x.total_cmp(&hole[b].x))
+        .unwrap_or(0);
+    let from = hole[start];
+    let edges = |points: &[Vec2]| {
+        (0..points.len())
+            .map(|i| (points[i], points[(i + 1) % points.len()]))
+            .collect::<Vec<_>>()
+    };
+    let mut walls = edges(outline);
+    walls.extend(edges(hole));
+    for other in later_holes {
+        walls.extend(edges(other));
+    }
+
+    let mut candidates: Vec<usize> = (0..outline.len()).collect();
+    candidates.sort_by(|&a, &b| {
+        outline[a]
+            .distance_squared(from)
+            .total_cmp(&outline[b].distance_squared(from))
+    });
+    let visible = candidates.into_iter().find(|&i| {
+        let to = outline[i];
+        walls.iter().all(|&(a, b)| {
+            a == to || b == to || a == from || b == from || !segments_cross(from, to, a, b)
+        })
+    });
+    let Some(to) = visible else {
+        return;
+    };
+
+    let mut spliced = Vec::with_capacity(outline.len() + hole.len() + 2);
+    spliced.extend_from_slice(&outline[..=to]);
+    spliced.extend((0..=hole.len()).map(|k| hole[(start + k) % hole.len()]));
+    spliced.extend_from_slice(&outline[to..]);
+    *outline = spliced;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Vec<Vec2> {
+        vec![
+            Vec2::new(x, y),
+            Vec2::new(x + w, y),
+            Vec2::new(x + w, y + h),
+            Vec2::new(x, y + h),
+        ]
+    }
+
+    /// Whether a polyline stays clear of the inside of a rectangle.
+    fn avoids(points: &[Vec2], min: Vec2, max: Vec2) -> bool {
+        points.windows(2).all(|pair| {
+            (0..=20).all(|k| {
+                let p = pair[0].lerp(pair[1], k as f32 / 20.0);
+                !(p.x > min.x + 0.01
+                    && p.x < max.x - 0.01
+                    && p.y > min.y + 0.01
+                    && p.y < max.y - 0.01)
+            })
+        })
+    }
+
+    #[test]
+    fn funnel_pulls_the_path_tight_around_an_inner_corner() {
+        let l_shape = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(100.0, 20.0),
+            Vec2::new(20.0, 20.0),
+            Vec2::new(20.0, 100.0),
+            Vec2::new(0.0, 100.0),
+        ];
+        let mesh = NavMesh::from_polygons(&[l_shape]);
+        assert_eq!(mesh.triangle_count(), 4);
+
+        let (from, to) = (Vec2::new(90.0, 10.0), Vec2::new(10.0, 90.0));
+        let path = mesh.find_path(from, to, &NavAgent::new(0.0)).unwrap();
+        assert_eq!(path, vec![Vec2::new(20.0, 20.0), to]);
+
+        // A wider agent swings wide of the corner.
+        let path = mesh.find_path(from, to, &NavAgent::new(4.0)).unwrap();
+        assert_eq!(path.len(), 2);
+        assert!((path[0].distance(Vec2::new(20.0, 20.0)) - 4.0).abs() < 1e-3);
+        assert!(path[0].x < 20.0 && path[0].y < 20.0);
+
+        // In line of sight the path is a straight line.
+        let path = mesh.find_path(from, Vec2::new(10.0, 10.0), &NavAgent::new(0.0));
+        assert_eq!(path, Some(vec![Vec2::new(10.0, 10.0)]));
+    }
+
+    #[test]
+    fn holes_are_walked_around() {
+        let mut mesh = NavMesh::new();
+        mesh.add_polygon(
+            &rect(0.0, 0.0, 100.0, 60.0),
+            &[rect(40.0, 10.0, 20.0, 40.0), rect(75.0, 25.0, 10.0, 10.0)],
+        );
+        // n + h - 2 + 2 per hole.
+        assert_eq!(mesh.triangle_count(), 4 + 8 - 2 + 4);
+        assert!(mesh.triangle_at(Vec2::new(50.0, 30.0)).is_none());
+        assert!(mesh.triangle_at(Vec2::new(50.0, 5.0)).is_some());
+
+        let (from, to) = (Vec2::new(10.0, 30.0), Vec2::new(95.0, 30.0));
+        let path = mesh.find_path(from, to, &NavAgent::new(0.0)).unwrap();
+        let mut walked = vec![from];
+        walked.extend(&path);
+        assert!(avoids(
+            &walked,
+            Vec2::new(40.0, 10.0),
+            Vec2::new(60.0, 50.0)
+        ));
+        assert!(avoids(
+            &walked,
+            Vec2::new(75.0, 25.0),
+            Vec2::new(85.0, 35.0)
+        ));
+        assert_eq!(path.last(), Some(&to));
+        // It hugs one of the big obstacle's corners.
+        assert!([(40.0, 10.0), (40.0, 50.0)]
+            .iter()
+            .any(|&(x, y)| path.contains(&Vec2::new(x, y))));
+    }
+
+    #[test]
+    fn regions_join_at_shared_edges_and_narrow_doors_stop_wide_agents() {
+        let rooms = [
+            rect(0.0, 0.0, 40.0, 40.0),
+            // A doorway 10 wide between the two rooms.
+            rect(40.0, 15.0, 10.0, 10.0),
+            rect(50.0, 0.0, 40.0, 40.0),
+        ];
+        let mut mesh = NavMesh::from_polygons(&rooms);
+        let (from, to) = (Vec2::new(5.0, 5.0), Vec2::new(85.0, 35.0));
+        // The doorway's ends are not corners of the rooms, so nothing joins.
+        assert!(mesh.find_path(from, to, &NavAgent::new(0.0)).is_none());
+
+        mesh = NavMesh::from_polygons(&[
+            vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(40.0, 0.0),
+                Vec2::new(40.0, 15.0),
+                Vec2::new(40.0, 25.0),
+                Vec2::new(40.0, 40.0),
+                Vec2::new(0.0, 40.0),
+            ],
+            rect(40.0, 15.0, 10.0, 10.0),
+            vec![
+                Vec2::new(50.0, 0.0),
+                Vec2::new(90.0, 0.0),
+                Vec2::new(90.0, 40.0),
+                Vec2::new(50.0, 40.0),
+                Vec2::new(50.0, 25.0),
+                Vec2::new(50.0, 15.0),
+            ],
+        ]);
+        let path = mesh.find_path(from, to, &NavAgent::new(2.0)).unwrap();
+        assert_eq!(path.last(), Some(&to));
+        assert!(path.iter().all(|p| p.y > 14.0 || p.x < 40.0 || p.x > 50.0));
+        assert!(mesh.find_path(from, to, &NavAgent::new(6.0)).is_none());
+
+        // Ends off the mesh snap onto it.
+        let path = mesh
+            .find_path(
+                Vec2::new(-10.0, 20.0),
+                Vec2::new(20.0, 20.0),
+                &NavAgent::new(0.0),
+            )
+            .unwrap();
+        assert_eq!(path, vec![Vec2::new(0.0, 20.0), Vec2::new(20.0, 20.0)]);
+    }
+}
@@ -1,7 +1,12 @@
 use crate::math::rect::Rect;
+use crate::math::scalar::{Bounds2, Scalar, Vector2};
+use crate::world::raycast::{BoxCast2D, HitTarget2D};
+use crate::world::shape::{shape_overlap, Shape2D};
+use crate::world::spatial::SolidIndex2D;
 use glam::Vec2;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CollisionLayer {
     pub layer: u32,
     pub mask: u32,
@@ -54,10 +59,16 @@ pub fn aabb_overlap_layered(
 }
 
 pub fn aabb_overlap(a: &Rect, b: &Rect) -> Option<Vec2> {
-    let overlap_x = f32::min(a.right(), b.right()) - f32::max(a.left(), b.left());
-    let overlap_y = f32::min(a.top(), b.top()) - f32::max(a.bottom(), b.bottom());
+    aabb_overlap_generic(a, b)
+}
+
+/// [`aabb_overlap`] over any [`Scalar`] rectangle, e.g.
+/// [`FixedRect`](crate::math::FixedRect) in a rollback simulation.
+pub fn aabb_overlap_generic<S: Scalar, R: Bounds2<S>>(a: &R, b: &R) -> Option<S::Vec2> {
+    let overlap_x = S::min(a.right(), b.right()) - S::max(a.left(), b.left());
+    let overlap_y = S::min(a.top(), b.top()) - S::max(a.bottom(), b.bottom());
 
-    if overlap_x <= 0.0 || overlap_y <= 0.0 {
+    if overlap_x <= S::ZERO || overlap_y <= S::ZERO {
         return None;
     }
 
@@ -65,48 +76,134 @@ pub fn aabb_overlap(a: &Rect, b: &Rect) -> Option<Vec2> {
     let center_b = b.center();
 
     if overlap_x < overlap_y {
-        let sign = if center_a.x < center_b.x { -1.0 } else { 1.0 };
-        Some(Vec2::new(sign * overlap_x, 0.0))
+        let sign = if center_a.x() < center_b.x() {
+            -S::ONE
+        } else {
+            S::ONE
+        };
+        Some(S::Vec2::new(sign * overlap_x, S::ZERO))
     } else {
-        let sign = if center_a.y < center_b.y { -1.0 } else { 1.0 };
-        Some(Vec2::new(0.0, sign * overlap_y))
+        let sign = if center_a.y() < center_b.y() {
+            -S::ONE
+        } else {
+            S::ONE
+        };
+        Some(S::Vec2::new(S::ZERO, sign * overlap_y))
     }
 }
 
 /// The faces of a moving body that came into contact with a solid during a
 /// [`move_and_collide`] resolution. Coordinates follow [`Rect`]'s y-up
 /// convention, so `bottom` is a floor/ground contact and `top` a ceiling.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub struct Contacts2D {
     pub left: bool,
     pub right: bool,
     pub top: bool,
     pub bottom: bool,
+    /// Unit normal of the floor under the body: [`Vec2::Y`] on flat ground,
+    /// tilted on a slope, and zero whenever `bottom` is false.
+    pub ground_normal: Vec2,
 }
 
 impl Contacts2D {
     pub fn any(&self) -> bool {
         self.left || self.right || self.top || self.bottom
     }
+
+    /// Angle of the ground from horizontal in radians, or `None` when airborne.
+    pub fn ground_angle(&self) -> Option<f32> {
+        self.bottom
+            .then(|| self.ground_normal.y.clamp(-1.0, 1.0).acos())
+    }
 }
 
+/// Steepest surface, in radians from horizontal, that the movers treat as
+/// floor (50°). Anything steeper is a wall the body slides down.
+pub const DEFAULT_MAX_SLOPE_ANGLE: f32 = 0.872_664_6;
+
+/// How far a walkable surface is allowed to lift a body in one step beyond
+/// what its motion explains, so float error never drops it through a
+/// one-way slope.
+const SLOPE_SKIN: f32 = 0.01;
+
 /// Result of moving an AABB against a set of static solids.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct MoveResult2D {
     /// The resolved bottom-left position of the body after collision response.
     pub position: Vec2,
     pub contacts: Contacts2D,
+    /// The solid the body ended up standing on — its index in the slice (or
+    /// its [`SolidId`](crate::world::spatial::SolidId)) — if any. A body
+    /// riding a moving solid reports it here, so animation and audio can react.
+    pub platform: Option<usize>,
 }
 
+/// How a [`KinematicBody2D`] keeps fast motion from tunneling through thin
+/// solids. Motion shorter than the body on both axes cannot skip over a solid,
+/// so every mode moves such steps exactly like [`CcdMode2D::Off`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CcdMode2D {
+    /// Resolve the whole step at once. Cheapest; bodies moving further than
+    /// their own size in one step can pass through thin solids.
+    #[default]
+    Off,
+    /// Sweep the body's box along its motion, stop at the first time of
+    /// impact and slide the rest of the motion along the surface. Exact for
+    /// AABB bodies; shaped bodies fall back to [`CcdMode2D::Substep`].
+    Swept,
+    /// Split the motion into pieces no longer than half the body, stopping
+    /// each at the first surface it enters and sliding along it. Works for
+    /// any shape; costs one overlap query per piece.
+    Substep,
+}
+
+/// The first solid a CCD body ran into during its last step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Impact2D {
+    /// Fraction of the step's motion completed before contact, in `0..=1`.
+    pub time: f32,
+    /// Bottom-left of the body's bounds at the moment of contact.
+    pub position: Vec2,
+    /// Where the body touched the surface — where a decal or spark goes.
+    pub point: Vec2,
+    /// Unit normal of the struck surface, facing the body.
+    pub normal: Vec2,
+    /// Index of the solid in the slice (or its
+    /// [`SolidId`](crate::world::spatial::SolidId)).
+    pub solid: usize,
+}
+
+/// Most surfaces a swept body slides along in one step before it stops.
+const MAX_CCD_SLIDES: usize = 4;
+/// Cap on [`CcdMode2D::Substep`] pieces per step, bounding the cost of
+/// absurd speeds (the body can tunnel again beyond it).
+const MAX_CCD_SUBSTEPS: usize = 32;
+
 /// A static collider for [`move_and_collide_solids`].
 ///
 /// A plain solid blocks from every direction. A `one_way` solid (a drop-through
 /// platform) only stops a body landing on it from above: the body passes freely
 /// through it horizontally and when moving upward from below.
+///
+/// A solid is an axis-aligned `rect` unless it carries a `shape` (see
+/// [`Solid2D::shaped`]), in which case `rect` is the shape's bounding box and
+/// the shape is stored relative to the rect's bottom-left corner — moving the
+/// rect moves the shape with it.
+///
+/// A solid with a non-zero `velocity` is kinematic: it stays put for the
+/// movers, and [`move_platforms`](crate::world::platform::move_platforms)
+/// advances it each step, carrying riders and pushing bodies in its way.
+///
+/// `layer` is consulted by spatial queries such as
+/// [`Ray2D`](crate::world::raycast::Ray2D); the movers ignore it.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Solid2D {
     pub rect: Rect,
     pub one_way: bool,
+    pub layer: CollisionLayer,
+    pub shape: Option<Shape2D>,
+    pub velocity: Vec2,
 }
 
 impl Solid2D {
@@ -115,6 +212,9 @@ impl Solid2D {
         Self {
             rect,
             one_way: false,
+            layer: CollisionLayer::default(),
+            shape: None,
+            velocity: Vec2::ZERO,
         }
     }
 
@@ -123,8 +223,61 @@ impl Solid2D {
         Self {
             rect,
             one_way: true,
+            ..Self::solid(rect)
+        }
+    }
+
+    /// A solid with a non-rectangular collider, given in world space.
+    pub fn shaped(shape: Shape2D) -> Self {
+        let (rect, local) = split_shape(shape);
+        Self {
+            shape: Some(local),
+            ..Self::solid(rect)
+        }
+    }
+
+    /// A ramp filling `rect` from its bottom edge up to a straight surface
+    /// that starts `left_height` above the bottom on the left and ends
+    /// `right_height` above it on the right.
+    ///
+    /// A square rect with heights `(0, h)` is a 45° slope; `(0, h / 2)` and
+    /// `(h / 2, h)` are the two halves of a shallow "half" slope. Heights are
+    /// clamped to `0..=rect.height`. Combine with
+    /// [`Solid2D::with_one_way`] for a drop-through slope.
+    pub fn slope(rect: Rect, left_height: f32, right_height: f32) -> Self {
+        let left = left_height.clamp(0.0, rect.height);
+        let right = right_height.clamp(0.0, rect.height);
+        let points = [
+            Vec2::new(rect.left(), rect.bottom()),
+            Vec2::new(rect.right(), rect.bottom()),
+            Vec2::new(rect.right(), rect.bottom() + right),
+            Vec2::new(rect.left(), rect.bottom() + left),
+        ];
+        match Shape2D::polygon(&points) {
+            Some(shape) => Self::shaped(shape),
+            None => Self::solid(Rect::new(rect.x, rect.y, rect.width, left.max(right))),
         }
     }
+
+    pub fn with_one_way(mut self, one_way: bool) -> Self {
+        self.one_way = one_way;
+        self
+    }
+
+    pub fn with_layer(mut self, layer: CollisionLayer) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    pub fn with_velocity(mut self, velocity: Vec2) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// The solid's collider in world space.
+    pub fn collider(&self) -> Shape2D {
+        place_shape(self.rect, self.shape)
+    }
 }
 
 impl From<Rect> for Solid2D {
@@ -133,6 +286,66 @@ impl From<Rect> for Solid2D {
     }
 }
 
+fn split_shape(shape: Shape2D) -> (Rect, Shape2D) {
+    let rect = shape.bounds();
+    (rect, shape.translated(-Vec2::new(rect.x, rect.y)))
+}
+
+fn place_shape(rect: Rect, shape: Option<Shape2D>) -> Shape2D {
+    match shape {
+        Some(shape) => shape.translated(Vec2::new(rect.x, rect.y)),
+        None => Shape2D::Aabb(rect),
+    }
+}
+
+/// A set of solids a mover can pull candidates from: a plain slice (every
+/// solid, in order) or a broadphase that narrows the set to a region.
+pub(crate) trait SolidSource {
+    /// Fill `out` with the ids of every solid that could overlap `region`,
+    /// sorted ascending.
+    fn gather(&self, region: &Rect, out: &mut Vec<usize>);
+
+    fn solid_at(&self, id: usize) -> Option<Solid2D>;
+
+    /// True when `gather` ignores the region and always returns every solid,
+    /// so the mover never needs to re-gather.
+    fn exhaustive(&self) -> bool {
+        false
+    }
+}
+
+impl SolidSource for [Solid2D] {
+    fn gather(&self, _region: &Rect, out: &mut Vec<usize>) {
+        out.clear();
+        out.extend(0..self.len());
+    }
+
+    fn solid_at(&self, id: usize) -> Option<Solid2D> {
+        self.get(id).copied()
+    }
+
+    fn exhaustive(&self) -> bool {
+        true
+    }
+}
+
+impl SolidSource for [Rect] {
+    fn gather(&self, _region: &Rect, out: &mut Vec<usize>) {
+        out.clear();
+        out.extend(0..self.len());
+    }
+
+    fn solid_at(&self, id: usize) -> Option<Solid2D> {
+        self.get(id).copied().map(Solid2D::solid)
+    }
+
+    fn exhaustive(&self) -> bool {
+        true
+    }
+}
+
+const MAX_RESOLVE_PASSES: usize = 4;
+
 /// Move an axis-aligned `body` by `motion` against static `solids`, resolving
 /// overlaps axis-by-axis (X then Y) and reporting which faces made contact.
 ///
@@ -144,118 +357,388 @@ impl From<Rect> for Solid2D {
 /// below your smallest solid (or substep) for very fast bodies.
 ///
 /// See [`move_and_collide_solids`] for one-way (drop-through) platform support.
+///
+/// This runs in `f32` only and is not determinism-safe across peers; a
+/// rollback or lockstep simulation moves its boxes with [`move_aabb`] on
+/// [`FixedRect`](crate::math::FixedRect) instead.
 pub fn move_and_collide(body: Rect, motion: Vec2, solids: &[Rect]) -> MoveResult2D {
-    let mut rect = body;
+    resolve_move(
+        &Shape2D::Aabb(body),
+        motion,
+        solids,
+        DEFAULT_MAX_SLOPE_ANGLE,
+    )
+}
+
+/// Like [`move_and_collide`] but against [`Solid2D`] colliders, so some can be
+/// one-way (drop-through) platforms.
+///
+/// One-way solids are skipped during horizontal resolution and when the body is
+/// moving upward, and they only stop a downward landing when the body was at or
+/// above the platform's top before this step — so a body can jump up through a
+/// drop-through platform and land on it, but never get shoved sideways or popped
+/// up by one.
+///
+/// Like every mover here except [`move_aabb`], it is `f32` only and not
+/// determinism-safe across peers.
+pub fn move_and_collide_solids(body: Rect, motion: Vec2, solids: &[Solid2D]) -> MoveResult2D {
+    resolve_move(
+        &Shape2D::Aabb(body),
+        motion,
+        solids,
+        DEFAULT_MAX_SLOPE_ANGLE,
+    )
+}
+
+/// The axis-by-axis AABB mover of [`move_and_collide`], generic over
+/// [`Scalar`] so a rollback simulation can run it on
+/// [`FixedRect`](crate::math::FixedRect) and resolve every move
+/// bit-identically on each peer. It shares its rect-against-rect snapping with
+/// [`move_and_collide`], so both resolve a plain box identically, without the
+/// f32-only extras: slopes, shapes, one-way platforms and CCD. The same
+/// no-tunneling caveat applies. Returns the resolved body and the faces that
+/// made contact.
+pub fn move_aabb<S: Scalar, R: Bounds2<S>>(
+    body: R,
+    motion: S::Vec2,
+    solids: &[R],
+) -> (R, Contacts2D) {
+    let size = body.size();
+    let mut pos = body.origin();
     let mut contacts = Contacts2D::default();
 
-    // X axis first.
-    rect.x += motion.x;
-    if motion.x != 0.0 {
+    if motion.x() != S::ZERO {
+        pos = S::Vec2::new(pos.x() + motion.x(), pos.y());
         for solid in solids {
-            if rect.overlaps(solid) {
-                if motion.x > 0.0 {
-                    rect.x = solid.left() - rect.width;
-                    contacts.right = true;
-                } else {
-                    rect.x = solid.right();
-                    contacts.left = true;
-                }
-            }
+            snap_x(&mut pos, size, motion.x(), solid, &mut contacts);
         }
     }
-
-    // Then Y axis (y-up: positive motion moves upward toward ceilings).
-    rect.y += motion.y;
-    if motion.y != 0.0 {
+    if motion.y() != S::ZERO {
+        pos = S::Vec2::new(pos.x(), pos.y() + motion.y());
         for solid in solids {
-            if rect.overlaps(solid) {
-                if motion.y > 0.0 {
-                    rect.y = solid.bottom() - rect.height;
-                    contacts.top = true;
-                } else {
-                    rect.y = solid.top();
-                    contacts.bottom = true;
-                }
-            }
+            snap_y(&mut pos, size, motion.y(), solid, &mut contacts);
         }
     }
 
-    MoveResult2D {
-        position: Vec2::new(rect.x, rect.y),
-        contacts,
+    (R::from_origin_size(pos, size), contacts)
+}
+
+/// Snap a box of `size` at `pos`, moving `dx` along X, flush against `solid`
+/// if it overlaps it, recording the face that made contact. Returns whether
+/// it did.
+fn snap_x<S: Scalar, R: Bounds2<S>>(
+    pos: &mut S::Vec2,
+    size: S::Vec2,
+    dx: S,
+    solid: &R,
+    contacts: &mut Contacts2D,
+) -> bool {
+    if !R::from_origin_size(*pos, size).overlaps(solid) {
+        return false;
     }
+    let x = if dx > S::ZERO {
+        contacts.right = true;
+        solid.left() - size.x()
+    } else {
+        contacts.left = true;
+        solid.right()
+    };
+    *pos = S::Vec2::new(x, pos.y());
+    true
 }
 
-/// Like [`move_and_collide`] but against [`Solid2D`] colliders, so some can be
-/// one-way (drop-through) platforms.
+/// [`snap_x`] along Y; landing on top of `solid` reports flat ground.
+fn snap_y<S: Scalar, R: Bounds2<S>>(
+    pos: &mut S::Vec2,
+    size: S::Vec2,
+    dy: S,
+    solid: &R,
+    contacts: &mut Contacts2D,
+) -> bool {
+    if !R::from_origin_size(*pos, size).overlaps(solid) {
+        return false;
+    }
+    let y = if dy > S::ZERO {
+        contacts.top = true;
+        solid.bottom() - size.y()
+    } else {
+        contacts.bottom = true;
+        contacts.ground_normal = Vec2::Y;
+        solid.top()
+    };
+    *pos = S::Vec2::new(pos.x(), y);
+    true
+}
+
+/// Move any [`Shape2D`] body against [`Solid2D`]s. `position` in the result is
+/// the bottom-left of the body's bounding box after the move.
 ///
-/// One-way solids are skipped during horizontal resolution and when the body is
-/// moving upward, and they only stop a downward landing when the body was at or
-/// above the platform's top before this step — so a body can jump up through a
-/// drop-through platform and land on it, but never get shoved sideways or popped
-/// up by one.
-pub fn move_and_collide_solids(body: Rect, motion: Vec2, solids: &[Solid2D]) -> MoveResult2D {
-    let mut rect = body;
-    let mut contacts = Contacts2D::default();
+/// An AABB body against rectangular solids is resolved exactly like
+/// [`move_and_collide_solids`]. Whenever a circle, capsule, polygon, or rotated
+/// box is involved the overlap is resolved from the minimum translation vector
+/// of [`shape_overlap`]:
+///
+/// - a surface no steeper than `max_slope_angle` (radians from horizontal) is
+///   floor — the body is lifted straight up onto it, so it neither slides down
+///   a slope while standing nor gets pushed back while walking up one;
+/// - anything steeper is pushed out along the vector and reported as a wall
+///   (or `top` for ceilings).
+///
+/// Shaped one-way solids (drop-through slopes) only ever lift a body onto
+/// their walkable top, and only by as much as this step's motion explains, so
+/// a body jumping up through one is not popped onto it.
+///
+/// Shapes and slopes are `f32` only, and their resolution leans on square
+/// roots and trigonometry, so this is not determinism-safe across peers.
+pub fn move_and_collide_shape(
+    body: &Shape2D,
+    motion: Vec2,
+    solids: &[Solid2D],
+    max_slope_angle: f32,
+) -> MoveResult2D {
+    resolve_move(body, motion, solids, max_slope_angle)
+}
+
+pub(crate) fn resolve_move<S: SolidSource + ?Sized>(
+    body: &Shape2D,
+    motion: Vec2,
+    solids: &S,
+    max_slope_angle: f32,
+) -> MoveResult2D {
+    let bounds = body.bounds();
+    let slope = max_slope_angle.clamp(0.0, std::f32::consts::FRAC_PI_2);
+    let mut mover = Mover {
+        local: body.translated(-Vec2::new(bounds.x, bounds.y)),
+        aabb: matches!(body, Shape2D::Aabb(_)),
+        walkable: slope.cos(),
+        lift_reach: (-motion.y).max(0.0) + motion.x.abs() * slope.tan() + SLOPE_SKIN,
+        width: bounds.width,
+        height: bounds.height,
+        pos: Vec2::new(bounds.x, bounds.y),
+        contacts: Contacts2D::default(),
+        platform: None,
+        candidates: Vec::new(),
+    };
 
     // X axis first — one-way platforms never block horizontal motion.
-    rect.x += motion.x;
+    let start = mover.pos;
+    mover.pos.x += motion.x;
     if motion.x != 0.0 {
-        for solid in solids {
-            if solid.one_way {
-                continue;
+        mover.pass(solids, start, |mover, id, solid| {
+            if solid.one_way && solid.shape.is_none() {
+                return Push::None;
             }
-            if rect.overlaps(&solid.rect) {
-                if motion.x > 0.0 {
-                    rect.x = solid.rect.left() - rect.width;
-                    contacts.right = true;
-                } else {
-                    rect.x = solid.rect.right();
-                    contacts.left = true;
+            if mover.aabb && solid.shape.is_none() {
+                let size = Vec2::new(mover.width, mover.height);
+                let (pos, contacts) = (&mut mover.pos, &mut mover.contacts);
+                if !snap_x(pos, size, motion.x, &solid.rect, contacts) {
+                    return Push::None;
                 }
+                return Push::Snap;
             }
-        }
+            mover.push_out(id, solid)
+        });
     }
 
     // Then Y axis. Capture the pre-move bottom so a one-way platform only
     // catches a body that was above it (not one the body is rising through).
-    let pre_move_bottom = rect.y;
-    rect.y += motion.y;
+    let start = mover.pos;
+    let pre_move_bottom = mover.pos.y;
+    mover.pos.y += motion.y;
     if motion.y != 0.0 {
-        for solid in solids {
-            if solid.one_way && !(motion.y < 0.0 && pre_move_bottom >= solid.rect.top()) {
-                continue;
+        mover.pass(solids, start, |mover, id, solid| {
+            if solid.one_way
+                && !(motion.y < 0.0
+                    && (solid.shape.is_some() || pre_move_bottom >= solid.rect.top()))
+            {
+                return Push::None;
             }
-            if rect.overlaps(&solid.rect) {
-                if motion.y > 0.0 {
-                    rect.y = solid.rect.bottom() - rect.height;
-                    contacts.top = true;
-                } else {
-                    rect.y = solid.rect.top();
-                    contacts.bottom = true;
+            if mover.aabb && solid.shape.is_none() {
+                let size = Vec2::new(mover.width, mover.height);
+                let (pos, contacts) = (&mut mover.pos, &mut mover.contacts);
+                if !snap_y(pos, size, motion.y, &solid.rect, contacts) {
+                    return Push::None;
+                }
+                if motion.y < 0.0 {
+                    mover.platform = Some(id);
                 }
+                return Push::Snap;
             }
-        }
+            mover.push_out(id, solid)
+        });
     }
 
     MoveResult2D {
-        position: Vec2::new(rect.x, rect.y),
-        contacts,
+        position: mover.pos,
+        contacts: mover.contacts,
+        platform: mover.platform,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Push {
+    None,
+    Snap,
+    Shape,
+}
+
+struct Mover {
+    local: Shape2D,
+    aabb: bool,
+    /// Cosine of the steepest walkable surface.
+    walkable: f32,
+    /// Largest lift a one-way slope may apply this move.
+    lift_reach: f32,
+    width: f32,
+    height: f32,
+    pos: Vec2,
+    contacts: Contacts2D,
+    platform: Option<usize>,
+    candidates: Vec<usize>,
+}
+
+impl Mover {
+    fn rect(&self) -> Rect {
+        Rect::new(self.pos.x, self.pos.y, self.width, self.height)
+    }
+
+    fn push_out(&mut self, id: usize, solid: &Solid2D) -> Push {
+        let body = self.local.translated(self.pos);
+        let Some(mtv) = shape_overlap(&body, &solid.collider()) else {
+            return Push::None;
+        };
+        let normal = mtv.normalize_or_zero();
+        if normal.y > 0.0 && normal.y >= self.walkable {
+            // Floor: lift straight up by the depth measured along the normal.
+            let lift = mtv.length() / normal.y;
+            if solid.one_way && lift > self.lift_reach {
+                return Push::None;
+            }
+            self.pos.y += lift;
+            self.contacts.bottom = true;
+            self.contacts.ground_normal = normal;
+            self.platform = Some(id);
+            return Push::Shape;
+        }
+        if solid.one_way {
+            return Push::None;
+        }
+
+        self.pos += mtv;
+        if normal.y < -0.5 {
+            self.contacts.top = true;
+        } else if normal.x > 0.0 {
+            self.contacts.left = true;
+        } else {
+            self.contacts.right = true;
+        }
+        Push::Shape
+    }
+
+    /// Visit candidate solids in id order, resolving each. The candidate set
+    /// always covers every position the body has occupied this pass: if a push
+    /// moves it outside, the region grows and newly reachable solids with a
+    /// higher id join — so a broadphase gives the same answer as a full scan.
+    /// Shape pushes can uncover new overlaps, so those re-run the pass.
+    fn pass<S: SolidSource + ?Sized>(
+        &mut self,
+        solids: &S,
+        start: Vec2,
+        mut resolve: impl FnMut(&mut Mover, usize, &Solid2D) -> Push,
+    ) {
+        let mut candidates = std::mem::take(&mut self.candidates);
+        let start = Rect::new(start.x, start.y, self.width, self.height);
+        for _ in 0..MAX_RESOLVE_PASSES {
+            let mut region = rect_union(&start, &self.rect());
+            solids.gather(&region, &mut candidates);
+            let mut shape_pushed = false;
+
+            let mut i = 0;
+            while i < candidates.len() {
+                let id = candidates[i];
+                i += 1;
+                let Some(solid) = solids.solid_at(id) else {
+                    continue;
+                };
+                let push = resolve(self, id, &solid);
+                shape_pushed |= push == Push::Shape;
+                if push != Push::None && !solids.exhaustive() {
+                    let rect = self.rect();
+                    if !rect_contains(&region, &rect) {
+                        region = rect_union(&region, &rect);
+                        solids.gather(&region, &mut candidates);
+                        i = candidates.partition_point(|&other| other <= id);
+                    }
+                }
+            }
+
+            if !shape_pushed {
+                break;
+            }
+        }
+        self.candidates = candidates;
     }
 }
 
-/// A simple kinematic AABB body with gravity, integrated against static solids.
+fn rect_union(a: &Rect, b: &Rect) -> Rect {
+    let left = a.left().min(b.left());
+    let bottom = a.bottom().min(b.bottom());
+    let right = a.right().max(b.right());
+    let top = a.top().max(b.top());
+    Rect::new(left, bottom, right - left, top - bottom)
+}
+
+fn rect_contains(outer: &Rect, inner: &Rect) -> bool {
+    inner.left() >= outer.left()
+        && inner.right() <= outer.right()
+        && inner.bottom() >= outer.bottom()
+        && inner.top() <= outer.top()
+}
+
+/// A simple kinematic body with gravity, integrated against static solids.
 ///
 /// This is the minimal "character controller" primitive for platformers and
 /// top-down games: set [`KinematicBody2D::velocity`] each frame (jump impulses,
 /// horizontal input) and call [`KinematicBody2D::step`] to integrate, resolve
 /// collisions, and learn about ground/wall/ceiling contacts.
+///
+/// The collider is `bounds` unless a `shape` is attached with
+/// [`KinematicBody2D::with_shape`]; like [`Solid2D`], the shape is stored
+/// relative to the bottom-left of `bounds`, so teleporting the body is still
+/// just a matter of writing `bounds.x` / `bounds.y`.
+///
+/// Slopes ([`Solid2D::slope`]) up to `max_slope_angle` are walkable: the body
+/// rides up them at full horizontal speed and stands on them without sliding.
+/// Set `ground_snap` (see [`KinematicBody2D::with_ground_snap`]) so a body that
+/// was grounded stays glued to the floor when walking downhill or over small
+/// steps instead of briefly going airborne.
+///
+/// Fast bodies such as bullets opt into continuous collision detection with
+/// [`KinematicBody2D::with_ccd`]; they then report where they first struck a
+/// solid each step in `impact`.
+///
+/// The body and its [`Contacts2D`] are `f32` only and not determinism-safe
+/// across peers. Rollback games keep their own fixed-point position and
+/// velocity and move with [`move_aabb`], as `feature-fixed-point` does.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct KinematicBody2D {
     pub bounds: Rect,
     pub velocity: Vec2,
     pub gravity: Vec2,
     pub contacts: Contacts2D,
+    pub shape: Option<Shape2D>,
+    /// Steepest walkable surface in radians from horizontal.
+    pub max_slope_angle: f32,
+    /// How far below its resolved position a grounded body searches for floor
+    /// to stick to. `0.0` disables sticking.
+    pub ground_snap: f32,
+    /// The solid the body stood on after its last step (see
+    /// [`MoveResult2D::platform`]); [`move_platforms`](crate::world::platform::move_platforms)
+    /// carries the body along when that solid moves.
+    pub platform: Option<usize>,
+    pub ccd: CcdMode2D,
+    /// The first solid the body hit during its last step, when `ccd` is on.
+    pub impact: Option<Impact2D>,
 }
 
 impl KinematicBody2D {
@@ -266,9 +749,24 @@ impl KinematicBody2D {
             velocity: Vec2::ZERO,
             gravity: Vec2::new(0.0, -980.0),
             contacts: Contacts2D::default(),
+            shape: None,
+            max_slope_angle: DEFAULT_MAX_SLOPE_ANGLE,
+            ground_snap: 0.0,
+            platform: None,
+            ccd: CcdMode2D::Off,
+            impact: None,
         }
     }
 
+    /// Replace the collider with a world-space `shape`; `bounds` becomes its
+    /// bounding box.
+    pub fn with_shape(mut self, shape: Shape2D) -> Self {
+        let (bounds, local) = split_shape(shape);
+        self.bounds = bounds;
+        self.shape = Some(local);
+        self
+    }
+
     pub fn with_gravity(mut self, gravity: Vec2) -> Self {
         self.gravity = gravity;
         self
@@ -279,6 +777,29 @@ impl KinematicBody2D {
         self
     }
 
+    pub fn with_max_slope_angle(mut self, radians: f32) -> Self {
+        self.max_slope_angle = radians;
+        self
+    }
+
+    /// Stick to the ground within `distance` while grounded and not moving
+    /// up. It needs to cover the drop of one step down your steepest slope:
+    /// `run_speed * dt * tan(slope angle)`.
+    pub fn with_ground_snap(mut self, distance: f32) -> Self {
+        self.ground_snap = distance;
+        self
+    }
+
+    pub fn with_ccd(mut self, mode: CcdMode2D) -> Self {
+        self.ccd = mode;
+        self
+    }
+
+    /// The body's collider in world space.
+    pub fn collider(&self) -> Shape2D {
+        place_shape(self.bounds, self.shape)
+    }
+
     /// True when the body's last [`KinematicBody2D::step`] landed on a floor.
     pub fn on_ground(&self) -> bool {
         self.contacts.bottom
@@ -288,19 +809,197 @@ impl KinematicBody2D {
     /// integrate velocity, resolve, and zero the velocity components that ran
     /// into a solid so the body rests (rather than accumulating force).
     pub fn step(&mut self, dt: f32, solids: &[Rect]) {
-        let motion = self.integrate(dt);
-        let result = move_and_collide(self.bounds, motion, solids);
-        self.apply_move_result(result);
+        self.step_in(dt, solids);
     }
 
     /// Like [`KinematicBody2D::step`] but against [`Solid2D`] colliders, so the
-    /// level can include one-way (drop-through) platforms.
+    /// level can include one-way (drop-through) platforms, slopes, and shaped
+    /// solids.
     pub fn step_solids(&mut self, dt: f32, solids: &[Solid2D]) {
+        self.step_in(dt, solids);
+    }
+
+    /// Like [`KinematicBody2D::step_solids`] but against a [`SolidIndex2D`]
+    /// broadphase, for levels with thousands of solids.
+    pub fn step_indexed(&mut self, dt: f32, solids: &SolidIndex2D) {
+        self.step_in(dt, solids);
+    }
+
+    fn step_in<S: SolidSource + ?Sized>(&mut self, dt: f32, solids: &S) {
+        let was_grounded = self.contacts.bottom;
         let motion = self.integrate(dt);
-        let result = move_and_collide_solids(self.bounds, motion, solids);
+        let (mut result, impact) = match self.ccd {
+            CcdMode2D::Off => (
+                resolve_move(&self.collider(), motion, solids, self.max_slope_angle),
+                None,
+            ),
+            CcdMode2D::Swept if self.shape.is_none() => self.swept_move(motion, solids),
+            CcdMode2D::Swept | CcdMode2D::Substep => self.substep_move(motion, solids),
+        };
+        self.impact = impact;
+
+        if self.ground_snap > 0.0
+            && was_grounded
+            && !result.contacts.bottom
+            && self.velocity.y <= 0.0
+        {
+            let moved = Rect::new(
+                result.position.x,
+                result.position.y,
+                self.bounds.width,
+                self.bounds.height,
+            );
+            let probe = resolve_move(
+                &place_shape(moved, self.shape),
+                Vec2::new(0.0, -self.ground_snap),
+                solids,
+                self.max_slope_angle,
+            );
+            if probe.contacts.bottom {
+                result.position = probe.position;
+                result.contacts.bottom = true;
+                result.contacts.ground_normal = probe.contacts.ground_normal;
+                result.platform = probe.platform;
+            }
+        }
+
         self.apply_move_result(result);
     }
 
+    /// True when `motion` is short enough that no solid can fit between the
+    /// start and end boxes.
+    fn cannot_tunnel(&self, motion: Vec2) -> bool {
+        motion.x.abs() < self.bounds.width && motion.y.abs() < self.bounds.height
+    }
+
+    fn swept_move<S: SolidSource + ?Sized>(
+        &self,
+        motion: Vec2,
+        solids: &S,
+    ) -> (MoveResult2D, Option<Impact2D>) {
+        let size = Vec2::new(self.bounds.width, self.bounds.height);
+        let mut result = MoveResult2D {
+            position: Vec2::new(self.bounds.x, self.bounds.y),
+            contacts: Contacts2D::default(),
+            platform: None,
+        };
+        let mut remaining = motion;
+        let mut impact = None;
+
+        for _ in 0..MAX_CCD_SLIDES {
+            let rect = Rect::from_pos_size(result.position, size);
+            let hit = BoxCast2D::new(rect, remaining).cast_entry(solids);
+            let hit = hit.map(|hit| Impact2D {
+                time: hit.fraction,
+                position: hit.position,
+                point: hit.point,
+                normal: hit.normal,
+                solid: match hit.target {
+                    HitTarget2D::Solid(id) => id,
+                    HitTarget2D::Tile { .. } => unreachable!("solid sources hold no tiles"),
+                },
+            });
+            impact = impact.or(hit);
+            match hit {
+                Some(hit) if !self.cannot_tunnel(remaining) => {
+                    result.position = hit.position;
+                    remaining = self.touch(&mut result, &hit, remaining * (1.0 - hit.time));
+                    if remaining.length_squared() < 1e-8 {
+                        break;
+                    }
+                }
+                _ => {
+                    let step = resolve_move(
+                        &Shape2D::Aabb(rect),
+                        remaining,
+                        solids,
+                        self.max_slope_angle,
+                    );
+                    merge_move(&mut result, step);
+                    break;
+                }
+            }
+        }
+        (result, impact)
+    }
+
+    fn substep_move<S: SolidSource + ?Sized>(
+        &self,
+        motion: Vec2,
+        solids: &S,
+    ) -> (MoveResult2D, Option<Impact2D>) {
+        let size = Vec2::new(self.bounds.width, self.bounds.height);
+        let pieces = (motion.x.abs() / (size.x * 0.5))
+            .max(motion.y.abs() / (size.y * 0.5))
+            .ceil()
+            .clamp(1.0, MAX_CCD_SUBSTEPS as f32) as usize;
+        let piece = motion / pieces as f32;
+        let mut result = MoveResult2D {
+            position: Vec2::new(self.bounds.x, self.bounds.y),
+            contacts: Contacts2D::default(),
+            platform: None,
+        };
+        let mut impact = None;
+        let mut ids = Vec::new();
+
+        for k in 0..pieces {
+            let body = place_shape(Rect::from_pos_size(result.position, size), self.shape);
+            let hit =
+                first_entry(&body, piece, solids, &mut ids).map(|(time, normal, id)| Impact2D {
+                    time,
+                    position: result.position + piece * time,
+                    point: body.translated(piece * time).support_point(-normal),
+                    normal,
+                    solid: id,
+                });
+            if impact.is_none() {
+                impact = hit.map(|hit| Impact2D {
+                    time: (k as f32 + hit.time) / pieces as f32,
+                    ..hit
+                });
+            }
+            match hit {
+                // Stop at the surface and slide the rest of the piece along
+                // it, so a piece never sinks deep enough into a thin solid to
+                // be pushed out of its far side.
+                Some(hit) if pieces > 1 => {
+                    result.position = hit.position;
+                    let slide = self.touch(&mut result, &hit, piece * (1.0 - hit.time));
+                    let body = place_shape(Rect::from_pos_size(hit.position, size), self.shape);
+                    let step = resolve_move(&body, slide, solids, self.max_slope_angle);
+                    merge_move(&mut result, step);
+                }
+                _ => {
+                    let step = resolve_move(&body, piece, solids, self.max_slope_angle);
+                    merge_move(&mut result, step);
+                }
+            }
+        }
+        (result, impact)
+    }
+
+    /// Record a CCD contact with `hit`'s surface in `result` and return the
+    /// part of `remaining` that slides along it.
+    fn touch(&self, result: &mut MoveResult2D, hit: &Impact2D, remaining: Vec2) -> Vec2 {
+        let normal = hit.normal;
+        let walkable = self
+            .max_slope_angle
+            .clamp(0.0, std::f32::consts::FRAC_PI_2)
+            .cos();
+        if normal.y > 0.0 && normal.y >= walkable {
+            result.contacts.bottom = true;
+            result.contacts.ground_normal = normal;
+            result.platform = Some(hit.solid);
+        } else if normal.y < -0.5 {
+            result.contacts.top = true;
+        } else if normal.x > 0.0 {
+            result.contacts.left = true;
+        } else {
+            result.contacts.right = true;
+        }
+        remaining - normal * remaining.dot(normal).min(0.0)
+    }
+
     fn integrate(&mut self, dt: f32) -> Vec2 {
         self.velocity += self.gravity * dt;
         self.velocity * dt
@@ -310,6 +1009,7 @@ impl KinematicBody2D {
         self.bounds.x = result.position.x;
         self.bounds.y = result.position.y;
         self.contacts = result.contacts;
+        self.platform = result.platform;
 
         if result.contacts.left || result.contacts.right {
             self.velocity.x = 0.0;
@@ -320,6 +1020,70 @@ impl KinematicBody2D {
     }
 }
 
+fn merge_move(into: &mut MoveResult2D, step: MoveResult2D) {
+    into.position = step.position;
+    into.contacts.left |= step.contacts.left;
+    into.contacts.right |= step.contacts.right;
+    into.contacts.top |= step.contacts.top;
+    if step.contacts.bottom {
+        into.contacts.bottom = true;
+        into.contacts.ground_normal = step.contacts.ground_normal;
+    }
+    into.platform = step.platform.or(into.platform);
+}
+
+/// The solid `body` enters first while moving by `motion`, as `(fraction of
+/// motion, surface normal, id)`. `motion` must be shorter than the body so no
+/// solid is skipped; the entry time is then bisected from the overlap test,
+/// which stays exact even when the motion pierces a solid thinner than it.
+/// Applies the movers' one-way rules.
+fn first_entry<S: SolidSource + ?Sized>(
+    body: &Shape2D,
+    motion: Vec2,
+    solids: &S,
+    ids: &mut Vec<usize>,
+) -> Option<(f32, Vec2, usize)> {
+    const BISECTIONS: usize = 16;
+
+    let moved = body.translated(motion);
+    let bottom = body.bounds().bottom();
+    solids.gather(&rect_union(&body.bounds(), &moved.bounds()), ids);
+    let mut best: Option<(f32, Vec2, usize)> = None;
+    for &id in ids.iter() {
+        let Some(solid) = solids.solid_at(id) else {
+            continue;
+        };
+        if solid.one_way
+            && !(motion.y < 0.0 && (solid.shape.is_some() || bottom >= solid.rect.top()))
+        {
+            continue;
+        }
+        let collider = solid.collider();
+        let Some(mut mtv) = shape_overlap(&moved, &collider) else {
+            continue;
+        };
+        let (mut lo, mut hi) = (0.0, 1.0);
+        for _ in 0..BISECTIONS {
+            let mid = (lo + hi) * 0.5;
+            match shape_overlap(&body.translated(motion * mid), &collider) {
+                Some(overlap) => {
+                    hi = mid;
+                    mtv = overlap;
+                }
+                None => lo = mid,
+            }
+        }
+        let normal = mtv.normalize_or_zero();
+        if motion.dot(normal) >= 0.0 || (solid.one_way && normal.y <= 0.0) {
+            continue;
+        }
+        if best.is_none_or(|(t, _, _)| lo < t) {
+            best = Some((lo, normal, id));
+        }
+    }
+    best
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -341,6 +1105,44 @@ mod tests {
         assert!((result.position.x + 10.0 - wall.left()).abs() < 1e-3);
     }
 
+    #[test]
+    fn generic_mover_agrees_in_f32_and_fixed_point() {
+        use crate::math::{Fixed, FixedRect, FixedVec2};
+
+        let solids = [
+            rect(-100.0, 0.0, 200.0, 10.0),
+            rect(15.0, 10.0, 10.0, 100.0),
+        ];
+        let fixed_solids = solids.map(|s| FixedRect::from_rect(&s));
+        let mut body = rect(0.0, 40.0, 10.0, 10.0);
+        let mut fixed_body = FixedRect::from_rect(&body);
+        let motion = Vec2::new(2.0, -3.0);
+        let mut contacts = Contacts2D::default();
+        for _ in 0..20 {
+            let (next, c) = move_aabb(body, motion, &solids);
+            let shared = move_and_collide(body, motion, &solids);
+            assert_eq!((shared.position, shared.contacts), (next.origin(), c));
+            let (fixed_next, fixed_c) =
+                move_aabb(fixed_body, FixedVec2::from_vec2(motion), &fixed_solids);
+            assert_eq!(c, fixed_c);
+            assert_eq!(fixed_next.to_rect(), next);
+            (body, fixed_body, contacts) = (next, fixed_next, c);
+        }
+        // Landed on the floor and pressed flush against the wall.
+        assert!(contacts.bottom && contacts.right);
+        assert_eq!(fixed_body.x + fixed_body.width, Fixed::from_int(15));
+        assert_eq!(fixed_body.y, Fixed::from_int(10));
+
+        let overlap = aabb_overlap_generic(&fixed_body, &fixed_solids[0]);
+        assert_eq!(overlap, None);
+        let sunk = FixedRect {
+            y: Fixed::from_int(8),
+            ..fixed_body
+        };
+        let push = aabb_overlap_generic(&sunk, &fixed_solids[0]).unwrap();
+        assert_eq!(push, FixedVec2::new(Fixed::ZERO, Fixed::from_int(2)));
+    }
+
     #[test]
     fn falling_body_lands_and_rests_on_floor() {
         let floor = rect(-100.0, 0.0, 200.0, 10.0); // top at y = 10
@@ -430,4 +1232,262 @@ mod tests {
         assert!(result.contacts.right);
         assert!((result.position.x + 10.0 - wall.rect.left()).abs() < 1e-3);
     }
+
+    #[test]
+    fn indexed_step_lands_like_the_slice_step() {
+        let solids = [
+            Solid2D::solid(rect(-100.0, 0.0, 200.0, 10.0)),
+            Solid2D::one_way(rect(-20.0, 30.0, 40.0, 4.0)),
+        ];
+        let index = SolidIndex2D::from_solids(16.0, &solids);
+        let mut a = KinematicBody2D::new(rect(0.0, 80.0, 10.0, 10.0));
+        let mut b = a;
+
+        for _ in 0..240 {
+            a.step_solids(1.0 / 60.0, &solids);
+            b.step_indexed(1.0 / 60.0, &index);
+        }
+
+        assert_eq!(a, b);
+        assert!(b.on_ground());
+    }
+
+    #[test]
+    fn circle_body_rests_on_a_ramp_instead_of_its_bounds() {
+        let ramp = Solid2D::shaped(
+            Shape2D::polygon(&[
+                Vec2::new(0.0, 0.0),
+                Vec2::new(100.0, 0.0),
+                Vec2::new(0.0, 100.0),
+            ])
+            .unwrap(),
+        );
+        let ball = Shape2D::circle(Vec2::new(50.0, 58.0), 5.0);
+        let result = move_and_collide_shape(
+            &ball,
+            Vec2::new(0.0, -10.0),
+            &[ramp],
+            DEFAULT_MAX_SLOPE_ANGLE,
+        );
+
+        assert!(result.contacts.bottom);
+        // A walkable slope lifts straight up: no sideways drift.
+        assert!((result.position.x - 45.0).abs() < 1e-4);
+        let center = result.position + Vec2::splat(5.0);
+        let distance = (center.x + center.y - 100.0) / std::f32::consts::SQRT_2;
+        assert!((distance - 5.0).abs() < 1e-3);
+        let n = result.contacts.ground_normal;
+        assert!((n - Vec2::ONE.normalize()).length() < 1e-4);
+    }
+
+    #[test]
+    fn capsule_body_lands_on_rect_floor() {
+        let floor = [Solid2D::solid(rect(-100.0, 0.0, 200.0, 10.0))];
+        let mut body = KinematicBody2D::new(rect(0.0, 0.0, 0.0, 0.0))
+            .with_shape(Shape2D::vertical_capsule(Vec2::new(0.0, 60.0), 10.0, 6.0));
+        assert_eq!(body.bounds, rect(-6.0, 44.0, 12.0, 32.0));
+
+        for _ in 0..120 {
+            body.step_solids(1.0 / 60.0, &floor);
+        }
+
+        assert!(body.on_ground());
+        assert!((body.bounds.bottom() - 10.0).abs() < 1e-3);
+        assert!(matches!(body.collider(), Shape2D::Capsule { .. }));
+    }
+
+    fn walk(body: &mut KinematicBody2D, solids: &[Solid2D], vx: f32, steps: usize) {
+        for _ in 0..steps {
+            body.velocity.x = vx;
+            body.step_solids(1.0 / 60.0, solids);
+        }
+    }
+
+    #[test]
+    fn body_walks_up_a_45_degree_slope_at_full_speed() {
+        let solids = [
+            Solid2D::solid(rect(-200.0, -10.0, 200.0, 10.0)),
+            Solid2D::slope(rect(0.0, -10.0, 100.0, 100.0), 0.0, 100.0),
+        ];
+        let mut body = KinematicBody2D::new(rect(-40.0, 0.0, 10.0, 10.0));
+        walk(&mut body, &solids, 0.0, 10);
+        assert!(body.on_ground());
+        assert_eq!(body.contacts.ground_normal, Vec2::Y);
+
+        walk(&mut body, &solids, 120.0, 30);
+        assert!(body.on_ground());
+        assert!((body.bounds.x - 20.0).abs() < 1e-2);
+        // Bottom-right corner sits on the surface y = x - 10.
+        assert!((body.bounds.y - (body.bounds.right() - 10.0)).abs() < 1e-2);
+        let angle = body.contacts.ground_angle().unwrap();
+        assert!((angle - std::f32::consts::FRAC_PI_4).abs() < 1e-3);
+    }
+
+    #[test]
+    fn body_stands_still_on_a_slope() {
+        let solids = [Solid2D::slope(rect(0.0, 0.0, 100.0, 50.0), 0.0, 50.0)];
+        let mut body = KinematicBody2D::new(rect(45.0, 40.0, 10.0, 10.0));
+        walk(&mut body, &solids, 0.0, 120);
+        let x = body.bounds.x;
+        walk(&mut body, &solids, 0.0, 120);
+        assert!(body.on_ground());
+        assert_eq!(body.bounds.x, x);
+    }
+
+    #[test]
+    fn slopes_steeper_than_the_limit_are_walls() {
+        let solids = [
+            Solid2D::solid(rect(-200.0, -10.0, 400.0, 10.0)),
+            Solid2D::slope(rect(0.0, 0.0, 20.0, 100.0), 0.0, 100.0),
+        ];
+        let mut body = KinematicBody2D::new(rect(-20.0, 0.0, 10.0, 10.0));
+        walk(&mut body, &solids, 120.0, 60);
+        assert!(body.contacts.right);
+        assert!(body.bounds.right() < 1.0);
+
+        let mut climber = KinematicBody2D::new(rect(-20.0, 0.0, 10.0, 10.0))
+            .with_max_slope_angle(85f32.to_radians());
+        walk(&mut climber, &solids, 120.0, 60);
+        // Climbs over the ramp and drops down its far side.
+        assert!(climber.bounds.left() > 20.0);
+    }
+
+    #[test]
+    fn ground_snap_keeps_the_body_on_a_downhill_slope() {
+        let solids = [
+            Solid2D::slope(rect(0.0, 0.0, 100.0, 100.0), 100.0, 0.0),
+            Solid2D::solid(rect(100.0, -10.0, 200.0, 10.0)),
+        ];
+        let mut loose = KinematicBody2D::new(rect(0.0, 100.0, 10.0, 10.0));
+        let mut sticky = loose.with_ground_snap(6.0);
+        walk(&mut loose, &solids, 0.0, 2);
+        walk(&mut sticky, &solids, 0.0, 2);
+
+        let mut loose_airborne = false;
+        for _ in 0..40 {
+            walk(&mut loose, &solids, 240.0, 1);
+            walk(&mut sticky, &solids, 240.0, 1);
+            loose_airborne |= !loose.on_ground();
+            assert!(sticky.on_ground());
+        }
+        assert!(loose_airborne);
+    }
+
+    #[test]
+    fn one_way_slope_catches_from_above_and_passes_from_below() {
+        let slope = Solid2D::slope(rect(0.0, 0.0, 100.0, 50.0), 0.0, 50.0).with_one_way(true);
+
+        let mut faller = KinematicBody2D::new(rect(50.0, 80.0, 10.0, 10.0));
+        walk(&mut faller, &[slope], 0.0, 90);
+        assert!(faller.on_ground());
+        assert!((faller.bounds.y - 30.0).abs() < 1e-2);
+
+        let mut jumper = KinematicBody2D::new(rect(50.0, -20.0, 10.0, 10.0))
+            .with_velocity(Vec2::new(0.0, 500.0));
+        jumper.step_solids(1.0 / 60.0, &[slope]);
+        for _ in 0..9 {
+            jumper.step_solids(1.0 / 60.0, &[slope]);
+            assert!(!jumper.contacts.left && !jumper.contacts.right);
+        }
+        assert!(jumper.bounds.y > 30.0);
+    }
+
+    fn bullet(ccd: CcdMode2D) -> KinematicBody2D {
+        // 100px per 1/60s step: 25 times the bullet's own width.
+        KinematicBody2D::new(rect(0.0, 0.0, 4.0, 4.0))
+            .with_gravity(Vec2::ZERO)
+            .with_velocity(Vec2::new(6000.0, 0.0))
+            .with_ccd(ccd)
+    }
+
+    #[test]
+    fn fast_body_without_ccd_tunnels_through_a_thin_wall() {
+        let wall = Solid2D::solid(rect(50.0, -20.0, 2.0, 40.0));
+        let mut body = bullet(CcdMode2D::Off);
+
+        body.step_solids(1.0 / 60.0, &[wall]);
+
+        assert!(body.bounds.x > wall.rect.right());
+        assert!(body.impact.is_none());
+    }
+
+    #[test]
+    fn swept_ccd_stops_at_a_thin_wall_and_reports_the_impact() {
+        let wall = Solid2D::solid(rect(50.0, -20.0, 2.0, 40.0));
+        let mut body = bullet(CcdMode2D::Swept);
+
+        body.step_solids(
+            1.0 / 60.0,
+            &[Solid2D::solid(rect(500.0, 0.0, 1.0, 1.0)), wall],
+        );
+
+        assert!(body.contacts.right);
+        assert!((body.bounds.right() - wall.rect.left()).abs() < 1e-3);
+        let impact = body.impact.unwrap();
+        assert_eq!(impact.solid, 1);
+        assert!((impact.time - 0.46).abs() < 1e-3);
+        assert!((impact.normal - Vec2::new(-1.0, 0.0)).length() < 1e-4);
+        assert!((impact.point.x - 50.0).abs() < 1e-3);
+        assert!((impact.position - Vec2::new(46.0, 0.0)).length() < 1e-3);
+    }
+
+    #[test]
+    fn swept_ccd_slides_the_rest_of_the_motion_along_the_surface() {
+        let floor = Solid2D::solid(rect(-500.0, -2.0, 1000.0, 2.0));
+        let mut body = KinematicBody2D::new(rect(0.0, 100.0, 4.0, 4.0))
+            .with_gravity(Vec2::ZERO)
+            .with_velocity(Vec2::new(6000.0, -12000.0))
+            .with_ccd(CcdMode2D::Swept);
+
+        body.step_solids(1.0 / 60.0, &[floor]);
+
+        assert!(body.on_ground());
+        assert!(body.bounds.y.abs() < 1e-3);
+        // 50px forward before landing, the other 50px slid along the floor.
+        assert!((body.bounds.x - 100.0).abs() < 1e-2);
+        assert!((body.impact.unwrap().time - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn substep_ccd_stops_a_fast_circle_at_a_thin_wall() {
+        let wall = Solid2D::solid(rect(50.0, -20.0, 2.0, 40.0));
+        for mode in [CcdMode2D::Substep, CcdMode2D::Swept] {
+            // 25 pieces of 4px: within MAX_CCD_SUBSTEPS for an 8px ball.
+            let mut body = bullet(mode).with_shape(Shape2D::circle(Vec2::new(4.0, 4.0), 4.0));
+
+            body.step_solids(1.0 / 60.0, &[wall]);
+
+            assert!(body.contacts.right);
+            assert!(body.bounds.right() <= wall.rect.left() + 1e-3);
+            let impact = body.impact.unwrap();
+            assert!((impact.time - 0.42).abs() < 1e-3);
+            assert!((impact.normal - Vec2::new(-1.0, 0.0)).length() < 1e-3);
+            assert!((impact.point - Vec2::new(50.0, 4.0)).length() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn slow_ccd_bodies_move_exactly_like_plain_ones() {
+        let floor = rect(-100.0, 0.0, 200.0, 10.0);
+        let start =
+            KinematicBody2D::new(rect(0.0, 50.0, 10.0, 10.0)).with_velocity(Vec2::new(120.0, 0.0));
+        let mut plain = start;
+        let mut swept = start.with_ccd(CcdMode2D::Swept);
+        let mut substep = start.with_ccd(CcdMode2D::Substep);
+
+        let mut landed = None;
+        for frame in 0..120 {
+            plain.step(1.0 / 60.0, &[floor]);
+            swept.step(1.0 / 60.0, &[floor]);
+            substep.step(1.0 / 60.0, &[floor]);
+            if let (None, Some(impact)) = (landed, swept.impact) {
+                landed = Some(frame);
+                assert!(impact.normal.y > 0.99);
+            }
+            assert_eq!(plain.bounds, swept.bounds);
+            assert!((plain.bounds.y - substep.bounds.y).abs() < 1e-3);
+            assert_eq!(plain.contacts.bottom, substep.contacts.bottom);
+        }
+        assert!(landed.is_some());
+    }
 }
@@ -0,0 +1,235 @@
+use glam::Vec2;
+
+use crate::world::physics::{resolve_move, KinematicBody2D, Solid2D, SolidSource};
+use crate::world::shape::{shape_overlap, shapes_overlap};
+
+/// What a moving solid did to a body during [`move_platforms`]. `body` indexes
+/// the bodies slice and `solid` the solids slice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlatformEvent2D {
+    /// The body was standing on the solid and moved with it.
+    Carried { body: usize, solid: usize },
+    /// The solid ran into the body and shoved it aside.
+    Pushed { body: usize, solid: usize },
+    /// The solid ran into the body and something else stopped the body from
+    /// getting out of the way. The body is left overlapping the solid; games
+    /// usually kill or respawn it.
+    Squashed { body: usize, solid: usize },
+}
+
+impl PlatformEvent2D {
+    pub fn body(&self) -> usize {
+        match *self {
+            Self::Carried { body, .. }
+            | Self::Pushed { body, .. }
+            | Self::Squashed { body, .. } => body,
+        }
+    }
+
+    pub fn solid(&self) -> usize {
+        match *self {
+            Self::Carried { solid, .. }
+            | Self::Pushed { solid, .. }
+            | Self::Squashed { solid, .. } => solid,
+        }
+    }
+}
+
+/// Advance every kinematic solid (non-zero [`Solid2D::velocity`]) by
+/// `velocity * dt` and make the bodies react, returning what happened.
+///
+/// Call it once per fixed step *before* stepping the bodies. Solids move in
+/// index order; for each one:
+///
+/// - bodies whose [`KinematicBody2D::platform`] is that solid and that are on
+///   the ground ride along by the same motion (still blocked by other solids);
+/// - other bodies the solid now overlaps are pushed out of it, and reported
+///   as squashed if the remaining solids leave them nowhere to go.
+///
+/// One-way solids carry riders but never push. Events are ordered by solid,
+/// then body, so the result is deterministic.
+pub fn move_platforms(
+    dt: f32,
+    solids: &mut [Solid2D],
+    bodies: &mut [KinematicBody2D],
+) -> Vec<PlatformEvent2D> {
+    let mut events = Vec::new();
+    for id in 0..solids.len() {
+        let motion = solids[id].velocity * dt;
+        if motion == Vec2::ZERO {
+            continue;
+        }
+        let riders: Vec<bool> = bodies
+            .iter()
+            .map(|body| body.platform == Some(id) && body.on_ground())
+            .collect();
+        solids[id].rect.x += motion.x;
+        solids[id].rect.y += motion.y;
+
+        let solid = solids[id];
+        let others = Excluding {
+            solids: &*solids,
+            skip: id,
+        };
+        for (index, body) in bodies.iter_mut().enumerate() {
+            if riders[index] {
+                let result = resolve_move(&body.collider(), motion, &others, body.max_slope_angle);
+                body.bounds.x = result.position.x;
+                body.bounds.y = result.position.y;
+                events.push(PlatformEvent2D::Carried {
+                    body: index,
+                    solid: id,
+                });
+                continue;
+            }
+            if solid.one_way {
+                continue;
+            }
+            let Some(push) = shape_overlap(&body.collider(), &solid.collider()) else {
+                continue;
+            };
+
+            let result = resolve_move(&body.collider(), push, &others, body.max_slope_angle);
+            body.bounds.x = result.position.x;
+            body.bounds.y = result.position.y;
+            if push.y > 0.0 {
+                // Scooped up from below: the body now stands on the solid.
+                body.contacts.bottom = true;
+                body.contacts.ground_normal = Vec2::Y;
+                body.platform = Some(id);
+                body.velocity.y = body.velocity.y.max(0.0);
+            }
+
+            if shapes_overlap(&body.collider(), &solid.collider()) {
+                events.push(PlatformEvent2D::Squashed {
+                    body: index,
+                    solid: id,
+                });
+            } else {
+                events.push(PlatformEvent2D::Pushed {
+                    body: index,
+                    solid: id,
+                });
+            }
+        }
+    }
+    events
+}
+
+/// Every solid but one, so a moving solid never blocks the bodies it moves.
+struct Excluding<'a> {
+    solids: &'a [Solid2D],
+    skip: usize,
+}
+
+impl SolidSource for Excluding<'_> {
+    fn gather(&self, _region: &crate::math::rect::Rect, out: &mut Vec<usize>) {
+        out.clear();
+        out.extend(0..self.solids.len());
+    }
+
+    fn solid_at(&self, id: usize) -> Option<Solid2D> {
+        if id == self.skip {
+            None
+        } else {
+            self.solids.get(id).copied()
+        }
+    }
+
+    fn exhaustive(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::rect::Rect;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    fn tick(solids: &mut [Solid2D], bodies: &mut [KinematicBody2D]) -> Vec<PlatformEvent2D> {
+        let events = move_platforms(DT, solids, bodies);
+        for body in bodies.iter_mut() {
+            body.step_solids(DT, solids);
+        }
+        events
+    }
+
+    #[test]
+    fn elevator_carries_its_rider_up_and_down() {
+        let mut solids = [Solid2D::solid(Rect::new(0.0, 0.0, 60.0, 10.0))];
+        let mut bodies = [KinematicBody2D::new(Rect::new(20.0, 10.0, 10.0, 10.0))];
+        tick(&mut solids, &mut bodies);
+        assert_eq!(bodies[0].platform, Some(0));
+
+        solids[0].velocity = Vec2::new(0.0, 60.0);
+        for _ in 0..60 {
+            let events = tick(&mut solids, &mut bodies);
+            assert_eq!(events, [PlatformEvent2D::Carried { body: 0, solid: 0 }]);
+        }
+        assert!((solids[0].rect.top() - 70.0).abs() < 1e-2);
+        assert!((bodies[0].bounds.bottom() - solids[0].rect.top()).abs() < 1e-3);
+
+        solids[0].velocity = Vec2::new(0.0, -120.0);
+        for _ in 0..30 {
+            tick(&mut solids, &mut bodies);
+            assert!(bodies[0].on_ground());
+        }
+        assert!((bodies[0].bounds.bottom() - solids[0].rect.top()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn sideways_platform_carries_and_reports_the_platform() {
+        let mut solids =
+            [Solid2D::solid(Rect::new(0.0, 0.0, 40.0, 10.0)).with_velocity(Vec2::new(90.0, 0.0))];
+        let mut bodies = [KinematicBody2D::new(Rect::new(10.0, 10.0, 10.0, 10.0))];
+        tick(&mut solids, &mut bodies);
+        let start = bodies[0].bounds.x - solids[0].rect.x;
+        for _ in 0..60 {
+            tick(&mut solids, &mut bodies);
+        }
+        assert_eq!(bodies[0].platform, Some(0));
+        assert!((bodies[0].bounds.x - solids[0].rect.x - start).abs() < 1e-3);
+    }
+
+    #[test]
+    fn moving_wall_pushes_then_squashes_against_a_static_wall() {
+        let mut solids = [
+            Solid2D::solid(Rect::new(-100.0, -10.0, 300.0, 10.0)),
+            Solid2D::solid(Rect::new(-40.0, 0.0, 10.0, 40.0)).with_velocity(Vec2::new(120.0, 0.0)),
+            Solid2D::solid(Rect::new(40.0, 0.0, 10.0, 40.0)),
+        ];
+        let mut bodies = [KinematicBody2D::new(Rect::new(0.0, 0.0, 10.0, 10.0))];
+
+        let mut pushed = false;
+        let mut squashed = false;
+        for _ in 0..60 {
+            for event in tick(&mut solids, &mut bodies) {
+                match event {
+                    PlatformEvent2D::Pushed { body: 0, solid: 1 } => pushed = true,
+                    PlatformEvent2D::Squashed { body: 0, solid: 1 } => squashed = true,
+                    other => panic!("unexpected {other:?}"),
+                }
+            }
+            if squashed {
+                break;
+            }
+        }
+        assert!(pushed && squashed);
+        assert!(bodies[0].bounds.right() <= 40.0 + 1e-3);
+    }
+
+    #[test]
+    fn one_way_platform_rises_through_a_body_without_pushing() {
+        let mut solids =
+            [Solid2D::one_way(Rect::new(0.0, -30.0, 40.0, 5.0))
+                .with_velocity(Vec2::new(0.0, 60.0))];
+        let mut bodies =
+            [KinematicBody2D::new(Rect::new(10.0, 0.0, 10.0, 10.0)).with_gravity(Vec2::ZERO)];
+        for _ in 0..60 {
+            assert!(move_platforms(DT, &mut solids, &mut bodies).is_empty());
+        }
+        assert_eq!(bodies[0].bounds.y, 0.0);
+    }
+}
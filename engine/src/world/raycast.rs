@@ -0,0 +1,819 @@
+use crate::math::rect::Rect;
+use crate::world::physics::{aabb_overlap, CollisionLayer, Solid2D, SolidSource};
+use crate::world::shape::{ray_shape, shape_overlap, sweep_rect_shape, Shape2D};
+use crate::world::spatial::SolidIndex2D;
+use crate::world::tilemap::{GridLayout, TileMap, TileShape};
+use glam::Vec2;
+
+/// What a cast struck: a solid by its index in the queried slice (or its
+/// [`SolidId`](crate::world::spatial::SolidId) for a [`SolidIndex2D`]), or an
+/// occupied [`TileMap`] cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HitTarget2D {
+    Solid(usize),
+    Tile { col: usize, row: usize, tile: usize },
+}
+
+/// The first surface a [`Ray2D`] reached.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit2D {
+    pub point: Vec2,
+    /// Unit normal of the face that was struck, pointing back toward the ray.
+    pub normal: Vec2,
+    /// Distance from the ray origin to `point`.
+    pub distance: f32,
+    pub target: HitTarget2D,
+}
+
+/// The first surface a swept [`BoxCast2D`] reached.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepHit2D {
+    /// Bottom-left of the box at the moment of impact.
+    pub position: Vec2,
+    /// A point on the contact face, midway along the touching span.
+    pub point: Vec2,
+    pub normal: Vec2,
+    /// Distance travelled along the motion before impact.
+    pub distance: f32,
+    /// Fraction of the requested motion completed before impact, in `0..=1`.
+    pub fraction: f32,
+    pub target: HitTarget2D,
+}
+
+/// A ray (or, with a finite `max_distance`, a segment) for hitscan, probes,
+/// line-of-sight and picking against [`Solid2D`]s and [`TileMap`]s.
+///
+/// Targets are filtered with [`CollisionLayer::interacts_with`]. One-way
+/// solids only report hits on their top face when the ray is heading down,
+/// mirroring how [`move_and_collide_solids`](crate::world::physics::move_and_collide_solids)
+/// treats them. A ray that starts inside a target hits it at distance `0`
+/// with a normal facing back along the ray.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray2D {
+    pub origin: Vec2,
+    pub direction: Vec2,
+    pub max_distance: f32,
+    pub layer: CollisionLayer,
+}
+
+impl Ray2D {
+    /// An unbounded ray; `direction` is normalized (a zero direction never hits).
+    pub fn new(origin: Vec2, direction: Vec2) -> Self {
+        Self {
+            origin,
+            direction: direction.normalize_or_zero(),
+            max_distance: f32::INFINITY,
+            layer: CollisionLayer::default(),
+        }
+    }
+
+    /// A ray from `from` that stops at `to`.
+    pub fn segment(from: Vec2, to: Vec2) -> Self {
+        Self::new(from, to - from).with_max_distance(from.distance(to))
+    }
+
+    pub fn with_max_distance(mut self, max_distance: f32) -> Self {
+        self.max_distance = max_distance;
+        self
+    }
+
+    pub fn with_layer(mut self, layer: CollisionLayer) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    pub fn point_at(&self, distance: f32) -> Vec2 {
+        self.origin + self.direction * distance
+    }
+
+    /// Distance and face normal where this ray enters `rect`, ignoring layers.
+    pub fn intersect_rect(&self, rect: &Rect) -> Option<(f32, Vec2)> {
+        if self.direction == Vec2::ZERO {
+            return None;
+        }
+        ray_rect(self.origin, self.direction, self.max_distance, rect)
+    }
+
+    pub fn cast_solids(&self, solids: &[Solid2D]) -> Option<RayHit2D> {
+        self.cast_solid_iter(solids.iter().enumerate())
+    }
+
+    /// Like [`Ray2D::cast_solids`] but only visits solids whose grid buckets the
+    /// ray's bounds touch. Unbounded rays fall back to every solid in the index.
+    pub fn cast_index(&self, index: &SolidIndex2D) -> Option<RayHit2D> {
+        match self.bounds() {
+            Some(bounds) => self.cast_solid_iter(index.query(&bounds)),
+            None => self.cast_solid_iter(index.solids()),
+        }
+    }
+
+    /// Walk the map's cells along the ray (Amanatides–Woo DDA) and report the
+    /// first occupied one. Layouts other than [`GridLayout::Square`] test the
+    /// outline of every colliding cell under the ray instead.
+    pub fn cast_tilemap(&self, tilemap: &TileMap) -> Option<RayHit2D> {
+        if self.direction == Vec2::ZERO
+            || tilemap.width == 0
+            || tilemap.height == 0
+            || !self.layer.interacts_with(&tilemap.layer)
+        {
+            return None;
+        }
+        if tilemap.layout != GridLayout::Square {
+            return self.cast_cell_outlines(tilemap);
+        }
+        // Walk the grid in map-local space; hit points stay in world space.
+        let origin = self.origin - tilemap.origin;
+        let size = tilemap.tile_size;
+        let bounds = Rect::new(0.0, 0.0, tilemap.world_width(), tilemap.world_height());
+        let (entry, entry_normal) = if bounds.contains_point(origin) {
+            (0.0, -self.direction)
+        } else {
+            ray_rect(origin, self.direction, self.max_distance, &bounds)?
+        };
+
+        let start = origin + self.direction * entry;
+        let max_col = tilemap.width as i64 - 1;
+        let max_row = tilemap.height as i64 - 1;
+        let mut col = ((start.x / size).floor() as i64).clamp(0, max_col);
+        let mut row = ((start.y / size).floor() as i64).clamp(0, max_row);
+
+        let step_x: i64 = if self.direction.x > 0.0 { 1 } else { -1 };
+        let step_y: i64 = if self.direction.y > 0.0 { 1 } else { -1 };
+        let delta_x = (size / self.direction.x).abs();
+        let delta_y = (size / self.direction.y).abs();
+        let next_boundary = |cell: i64, step: i64| (cell + i64::from(step > 0)) as f32 * size;
+        let mut t_x = if self.direction.x == 0.0 {
+            f32::INFINITY
+        } else {
+            (next_boundary(col, step_x) - origin.x) / self.direction.x
+        };
+        let mut t_y = if self.direction.y == 0.0 {
+            f32::INFINITY
+        } else {
+            (next_boundary(row, step_y) - origin.y) / self.direction.y
+        };
+
+        let mut distance = entry;
+        let mut normal = entry_normal;
+        loop {
+            if distance > self.max_distance {
+                return None;
+            }
+            let (c, r) = (col as usize, row as usize);
+            if let Some(tile) = tilemap.solid_tile(c, r) {
+                let target = HitTarget2D::Tile {
+                    col: c,
+                    row: r,
+                    tile,
+                };
+                if tilemap.tile_shape(c, r) == Some(TileShape::Full) {
+                    return Some(RayHit2D {
+                        point: self.point_at(distance),
+                        normal,
+                        distance,
+                        target,
+                    });
+                }
+                // A shaped tile can be missed; keep walking if it is.
+                let solid = tilemap.cell_solid(c, r)?;
+                if let Some(hit) = self.cast_solid_iter([(0, &solid)]) {
+                    return Some(RayHit2D { target, ..hit });
+                }
+            }
+            if t_x < t_y {
+                col += step_x;
+                distance = t_x;
+                t_x += delta_x;
+                normal = Vec2::new(-step_x as f32, 0.0);
+            } else {
+                row += step_y;
+                distance = t_y;
+                t_y += delta_y;
+                normal = Vec2::new(0.0, -step_y as f32);
+            }
+            if col < 0 || row < 0 || col > max_col || row > max_row {
+                return None;
+            }
+        }
+    }
+
+    /// The rect the ray sweeps out, or `None` for an unbounded ray.
+    fn bounds(&self) -> Option<Rect> {
+        if !self.max_distance.is_finite() {
+            return None;
+        }
+        let end = self.point_at(self.max_distance);
+        Some(Rect::new(
+            self.origin.x.min(end.x),
+            self.origin.y.min(end.y),
+            (end.x - self.origin.x).abs(),
+            (end.y - self.origin.y).abs(),
+        ))
+    }
+
+    fn cast_cell_outlines(&self, tilemap: &TileMap) -> Option<RayHit2D> {
+        let (cols, rows) = tilemap.cell_range(self.bounds().unwrap_or_else(|| tilemap.bounds()));
+        let mut best: Option<RayHit2D> = None;
+        tilemap.visit_cells(cols, rows, true, |col, row, tile| {
+            let Some(solid) = tilemap.cell_solid(col, row) else {
+                return;
+            };
+            if let Some(hit) = self.cast_solid_iter([(0, &solid)]) {
+                if best.is_none_or(|b| hit.distance < b.distance) {
+                    let target = HitTarget2D::Tile { col, row, tile };
+                    best = Some(RayHit2D { target, ..hit });
+                }
+            }
+        });
+        best
+    }
+
+    fn cast_solid_iter<'a>(
+        &self,
+        solids: impl IntoIterator<Item = (usize, &'a Solid2D)>,
+    ) -> Option<RayHit2D> {
+        if self.direction == Vec2::ZERO {
+            return None;
+        }
+        let mut best: Option<RayHit2D> = None;
+        for (index, solid) in solids {
+            if !self.layer.interacts_with(&solid.layer) {
+                continue;
+            }
+            let hit = match solid.shape {
+                None => ray_rect(self.origin, self.direction, self.max_distance, &solid.rect),
+                Some(_) => ray_shape(
+                    self.origin,
+                    self.direction,
+                    self.max_distance,
+                    &solid.collider(),
+                ),
+            };
+            let Some((distance, normal)) = hit else {
+                continue;
+            };
+            if solid.one_way && !faces_up(solid, normal) {
+                continue;
+            }
+            if best.is_none_or(|hit| distance < hit.distance) {
+                best = Some(RayHit2D {
+                    point: self.point_at(distance),
+                    normal,
+                    distance,
+                    target: HitTarget2D::Solid(index),
+                });
+            }
+        }
+        best
+    }
+}
+
+/// An axis-aligned box swept along `motion`, for "where would this body stop"
+/// queries, thick bullets and ground probes wider than a single ray.
+///
+/// Contact uses the same strict touching rule as [`Rect::overlaps`], so a box
+/// resting on a floor can sweep sideways along it without reporting a hit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoxCast2D {
+    pub rect: Rect,
+    pub motion: Vec2,
+    pub layer: CollisionLayer,
+}
+
+impl BoxCast2D {
+    pub fn new(rect: Rect, motion: Vec2) -> Self {
+        Self {
+            rect,
+            motion,
+            layer: CollisionLayer::default(),
+        }
+    }
+
+    pub fn with_layer(mut self, layer: CollisionLayer) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    pub fn cast_solids(&self, solids: &[Solid2D]) -> Option<SweepHit2D> {
+        self.cast_solid_iter(solids.iter().enumerate())
+    }
+
+    pub fn cast_index(&self, index: &SolidIndex2D) -> Option<SweepHit2D> {
+        self.cast_solid_iter(index.query(&self.swept_bounds()))
+    }
+
+    pub fn cast_tilemap(&self, tilemap: &TileMap) -> Option<SweepHit2D> {
+        if !self.layer.interacts_with(&tilemap.layer) {
+            return None;
+        }
+        let (cols, rows) = tilemap.cell_range(self.swept_bounds());
+        let square = tilemap.layout == GridLayout::Square;
+
+        let mut best: Option<SweepHit2D> = None;
+        for row in rows {
+            for col in cols.clone() {
+                let Some(tile) = tilemap.solid_tile(col, row) else {
+                    continue;
+                };
+                let target = HitTarget2D::Tile { col, row, tile };
+                let hit = match tilemap.tile_shape(col, row) {
+                    Some(TileShape::Full) if square => {
+                        self.sweep(&tilemap.cell_rect(col, row), target)
+                    }
+                    _ => tilemap.cell_solid(col, row).and_then(|solid| {
+                        let hit = self.cast_solid_iter([(0, &solid)])?;
+                        Some(SweepHit2D { target, ..hit })
+                    }),
+                };
+                if let Some(hit) = hit {
+                    if best.is_none_or(|b| hit.fraction < b.fraction) {
+                        best = Some(hit);
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    fn cast_solid_iter<'a>(
+        &self,
+        solids: impl IntoIterator<Item = (usize, &'a Solid2D)>,
+    ) -> Option<SweepHit2D> {
+        let mut best: Option<SweepHit2D> = None;
+        for (index, solid) in solids {
+            if !self.layer.interacts_with(&solid.layer) {
+                continue;
+            }
+            if solid.one_way && self.rect.bottom() < solid.rect.top() {
+                continue;
+            }
+            let target = HitTarget2D::Solid(index);
+            let hit = match solid.shape {
+                None => self.sweep(&solid.rect, target),
+                Some(_) => self.sweep_shape(&solid.collider(), target),
+            };
+            let Some(hit) = hit else {
+                continue;
+            };
+            if solid.one_way && !faces_up(solid, hit.normal) {
+                continue;
+            }
+            if best.is_none_or(|b| hit.fraction < b.fraction) {
+                best = Some(hit);
+            }
+        }
+        best
+    }
+
+    /// The first solid the box runs into, by the movers' blocking rules
+    /// rather than layers: every solid blocks, one-way solids only from above.
+    /// Solids the box already overlaps are left to the resolver, so a body
+    /// resting in a floor's skin is not pinned in place. Drives
+    /// [`CcdMode2D::Swept`](crate::world::physics::CcdMode2D::Swept).
+    pub(crate) fn cast_entry<S: SolidSource + ?Sized>(&self, solids: &S) -> Option<SweepHit2D> {
+        let mut ids = Vec::new();
+        solids.gather(&self.swept_bounds(), &mut ids);
+        let mut best: Option<SweepHit2D> = None;
+        for id in ids {
+            let Some(solid) = solids.solid_at(id) else {
+                continue;
+            };
+            if solid.one_way && self.rect.bottom() < solid.rect.top() {
+                continue;
+            }
+            let target = HitTarget2D::Solid(id);
+            let hit = match solid.shape {
+                None if self.rect.overlaps(&solid.rect) => None,
+                None => self.sweep(&solid.rect, target),
+                Some(_) => {
+                    let shape = solid.collider();
+                    if shape_overlap(&Shape2D::Aabb(self.rect), &shape).is_some() {
+                        None
+                    } else {
+                        self.sweep_shape(&shape, target)
+                    }
+                }
+            };
+            let Some(hit) = hit else {
+                continue;
+            };
+            if solid.one_way && !faces_up(&solid, hit.normal) {
+                continue;
+            }
+            if best.is_none_or(|b| hit.fraction < b.fraction) {
+                best = Some(hit);
+            }
+        }
+        best
+    }
+
+    fn swept_bounds(&self) -> Rect {
+        let r = self.rect;
+        let left = r.left().min(r.left() + self.motion.x);
+        let bottom = r.bottom().min(r.bottom() + self.motion.y);
+        Rect::new(
+            left,
+            bottom,
+            r.width + self.motion.x.abs(),
+            r.height + self.motion.y.abs(),
+        )
+    }
+
+    fn sweep_shape(&self, shape: &Shape2D, target: HitTarget2D) -> Option<SweepHit2D> {
+        let length = self.motion.length();
+        let half = Vec2::new(self.rect.width, self.rect.height) * 0.5;
+        let start = self.rect.center();
+
+        if let Some(mtv) = shape_overlap(&Shape2D::Aabb(self.rect), shape) {
+            return Some(SweepHit2D {
+                position: Vec2::new(self.rect.x, self.rect.y),
+                point: start,
+                normal: mtv.normalize_or_zero(),
+                distance: 0.0,
+                fraction: 0.0,
+                target,
+            });
+        }
+        if length == 0.0 {
+            return None;
+        }
+
+        let direction = self.motion / length;
+        let (distance, normal) = sweep_rect_shape(&self.rect, direction, length, shape)?;
+        let center = start + direction * distance;
+        // The box corner (or face midpoint) that leads into the contact.
+        let lead = |n: f32, h: f32| if n.abs() > 1e-4 { h * n.signum() } else { 0.0 };
+        let reach = Vec2::new(lead(normal.x, half.x), lead(normal.y, half.y));
+        Some(SweepHit2D {
+            position: center - half,
+            point: center - reach,
+            normal,
+            distance,
+            fraction: distance / length,
+            target,
+        })
+    }
+
+    fn sweep(&self, target_rect: &Rect, target: HitTarget2D) -> Option<SweepHit2D> {
+        let length = self.motion.length();
+        let half = Vec2::new(self.rect.width, self.rect.height) * 0.5;
+        let start = self.rect.center();
+
+        if self.rect.overlaps(target_rect) {
+            let mtv = aabb_overlap(&self.rect, target_rect).unwrap_or(Vec2::ZERO);
+            return Some(SweepHit2D {
+                position: Vec2::new(self.rect.x, self.rect.y),
+                point: start,
+                normal: mtv.normalize_or_zero(),
+                distance: 0.0,
+                fraction: 0.0,
+                target,
+            });
+        }
+        if length == 0.0 {
+            return None;
+        }
+
+        let expanded = Rect::new(
+            target_rect.x - half.x,
+            target_rect.y - half.y,
+            target_rect.width + self.rect.width,
+            target_rect.height + self.rect.height,
+        );
+        let direction = self.motion / length;
+        let (distance, normal) = ray_rect(start, direction, length, &expanded)?;
+        let center = start + direction * distance;
+        let moved = Rect::new(
+            center.x - half.x,
+            center.y - half.y,
+            self.rect.width,
+            self.rect.height,
+        );
+
+        let point = if normal.x != 0.0 {
+            let lo = moved.bottom().max(target_rect.bottom());
+            let hi = moved.top().min(target_rect.top());
+            let x = if normal.x < 0.0 {
+                target_rect.left()
+            } else {
+                target_rect.right()
+            };
+            Vec2::new(x, (lo + hi) * 0.5)
+        } else {
+            let lo = moved.left().max(target_rect.left());
+            let hi = moved.right().min(target_rect.right());
+            let y = if normal.y < 0.0 {
+                target_rect.bottom()
+            } else {
+                target_rect.top()
+            };
+            Vec2::new((lo + hi) * 0.5, y)
+        };
+
+        Some(SweepHit2D {
+            position: Vec2::new(moved.x, moved.y),
+            point,
+            normal,
+            distance,
+            fraction: distance / length,
+            target,
+        })
+    }
+}
+
+/// One-way solids only count hits on their upward-facing surface: the top
+/// face of a plain rect, or any surface of a shaped platform that faces up.
+fn faces_up(solid: &Solid2D, normal: Vec2) -> bool {
+    match solid.shape {
+        None => normal == Vec2::Y,
+        Some(_) => normal.y > 0.0,
+    }
+}
+
+fn ray_rect(origin: Vec2, direction: Vec2, max_distance: f32, rect: &Rect) -> Option<(f32, Vec2)> {
+    let mut t_enter = f32::NEG_INFINITY;
+    let mut t_exit = f32::INFINITY;
+    let mut normal = Vec2::ZERO;
+
+    let axes = [
+        (origin.x, direction.x, rect.left(), rect.right(), Vec2::X),
+        (origin.y, direction.y, rect.bottom(), rect.top(), Vec2::Y),
+    ];
+    for (o, d, min, max, axis) in axes {
+        if d == 0.0 {
+            if o <= min || o >= max {
+                return None;
+            }
+            continue;
+        }
+        let t_min = (min - o) / d;
+        let t_max = (max - o) / d;
+        let (near, far, face) = if t_min < t_max {
+            (t_min, t_max, -axis)
+        } else {
+            (t_max, t_min, axis)
+        };
+        if near > t_enter {
+            t_enter = near;
+            normal = face;
+        }
+        t_exit = t_exit.min(far);
+    }
+
+    if t_exit <= t_enter || t_exit <= 0.0 {
+        return None;
+    }
+    if t_enter < 0.0 {
+        return Some((0.0, -direction));
+    }
+    if t_enter > max_distance {
+        return None;
+    }
+    Some((t_enter, normal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::TextureId;
+    use crate::world::hex::{HexLayout, HexOrientation};
+    use crate::world::tilemap::TileDef;
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Rect {
+        Rect::new(x, y, w, h)
+    }
+
+    #[test]
+    fn ray_hits_nearest_solid_face() {
+        let solids = [
+            Solid2D::solid(rect(50.0, -10.0, 10.0, 20.0)),
+            Solid2D::solid(rect(20.0, -10.0, 10.0, 20.0)),
+        ];
+        let hit = Ray2D::new(Vec2::ZERO, Vec2::X)
+            .cast_solids(&solids)
+            .unwrap();
+
+        assert_eq!(hit.target, HitTarget2D::Solid(1));
+        assert_eq!(hit.normal, Vec2::new(-1.0, 0.0));
+        assert!((hit.distance - 20.0).abs() < 1e-4);
+        assert!((hit.point - Vec2::new(20.0, 0.0)).length() < 1e-4);
+    }
+
+    #[test]
+    fn segment_stops_short_and_layers_filter() {
+        let wall = Solid2D::solid(rect(20.0, -10.0, 10.0, 20.0));
+        assert!(Ray2D::segment(Vec2::ZERO, Vec2::new(15.0, 0.0))
+            .cast_solids(&[wall])
+            .is_none());
+
+        let enemy_only = wall.with_layer(CollisionLayer::symmetric(CollisionLayer::ENEMY));
+        let ray = Ray2D::new(Vec2::ZERO, Vec2::X)
+            .with_layer(CollisionLayer::symmetric(CollisionLayer::WORLD));
+        assert!(ray.cast_solids(&[enemy_only]).is_none());
+    }
+
+    #[test]
+    fn one_way_solid_only_blocks_from_above() {
+        let platform = [Solid2D::one_way(rect(-20.0, 0.0, 40.0, 4.0))];
+
+        let down = Ray2D::new(Vec2::new(0.0, 20.0), Vec2::NEG_Y).cast_solids(&platform);
+        assert_eq!(down.map(|hit| hit.normal), Some(Vec2::Y));
+
+        let up = Ray2D::new(Vec2::new(0.0, -20.0), Vec2::Y).cast_solids(&platform);
+        assert!(up.is_none());
+    }
+
+    #[test]
+    fn ray_walks_tilemap_cells() {
+        let mut map = TileMap::new(8, 8, 10.0);
+        let wall = map.add_tile(TileDef::solid(TextureId(0)));
+        map.set(5, 2, Some(wall));
+
+        let hit = Ray2D::new(Vec2::new(5.0, 25.0), Vec2::X)
+            .cast_tilemap(&map)
+            .unwrap();
+        assert_eq!(
+            hit.target,
+            HitTarget2D::Tile {
+                col: 5,
+                row: 2,
+                tile: wall
+            }
+        );
+        assert_eq!(hit.normal, Vec2::new(-1.0, 0.0));
+        assert!((hit.distance - 45.0).abs() < 1e-4);
+
+        let outside = Ray2D::new(Vec2::new(-30.0, 25.0), Vec2::X)
+            .cast_tilemap(&map)
+            .unwrap();
+        assert!((outside.point.x - 50.0).abs() < 1e-4);
+
+        assert!(Ray2D::new(Vec2::new(5.0, 55.0), Vec2::X)
+            .cast_tilemap(&map)
+            .is_none());
+    }
+
+    #[test]
+    fn casts_follow_a_tilemap_origin() {
+        let mut map = TileMap::new(8, 8, 10.0).with_origin(Vec2::new(100.0, -40.0));
+        let wall = map.add_tile(TileDef::solid(TextureId(0)));
+        map.set(5, 2, Some(wall));
+
+        let hit = Ray2D::new(Vec2::new(105.0, -15.0), Vec2::X)
+            .cast_tilemap(&map)
+            .unwrap();
+        assert!(matches!(
+            hit.target,
+            HitTarget2D::Tile { col: 5, row: 2, .. }
+        ));
+        assert!((hit.point - Vec2::new(150.0, -15.0)).length() < 1e-4);
+
+        let sweep = BoxCast2D::new(rect(100.0, -18.0, 6.0, 6.0), Vec2::new(100.0, 0.0))
+            .cast_tilemap(&map)
+            .unwrap();
+        assert!((sweep.position.x - 144.0).abs() < 1e-4);
+        assert_eq!(
+            map.collide_rect(&rect(148.0, -18.0, 4.0, 4.0)),
+            Some(Vec2::new(-2.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn casts_hit_tile_shapes_not_whole_cells() {
+        let mut map = TileMap::new(8, 4, 10.0);
+        let half = map.add_tile(TileDef::solid(TextureId(0)).with_shape(TileShape::HALF_BOTTOM));
+        let ramp = map.add_tile(TileDef::solid(TextureId(0)).with_shape(TileShape::SLOPE_UP));
+        let ledge = map.add_tile(TileDef::solid(TextureId(0)).with_shape(TileShape::OneWay));
+        map.set(1, 0, Some(half));
+        map.set(3, 0, Some(ramp));
+        map.set(5, 0, Some(ledge));
+
+        let down = |x: f32| Ray2D::new(Vec2::new(x, 35.0), Vec2::NEG_Y).cast_tilemap(&map);
+        assert!((down(15.0).unwrap().point.y - 5.0).abs() < 1e-4);
+        let hit = down(37.5).unwrap();
+        assert!((hit.point.y - 7.5).abs() < 1e-4);
+        assert!(hit.normal.x < 0.0 && hit.normal.y > 0.0);
+
+        // Through the empty corner above the ramp's low end, then the floor
+        // of the map: nothing.
+        let skim = Ray2D::segment(Vec2::new(25.0, 8.0), Vec2::new(32.0, 8.0));
+        assert!(skim.cast_tilemap(&map).is_none());
+
+        // The ledge stops a box falling onto it but not one rising through.
+        let fall = BoxCast2D::new(rect(51.0, 20.0, 8.0, 8.0), Vec2::new(0.0, -20.0))
+            .cast_tilemap(&map)
+            .unwrap();
+        assert!((fall.position.y - 10.0).abs() < 1e-4);
+        assert!(matches!(fall.target, HitTarget2D::Tile { col: 5, .. }));
+        let rise = BoxCast2D::new(rect(51.0, -9.0, 8.0, 8.0), Vec2::new(0.0, 30.0));
+        assert!(rise.cast_tilemap(&map).is_none());
+    }
+
+    #[test]
+    fn casts_hit_the_outlines_of_diamond_and_hex_cells() {
+        let diamond = GridLayout::Diamond {
+            width: 40.0,
+            height: 20.0,
+        };
+        let mut map = TileMap::new(6, 6, 1.0).with_layout(diamond);
+        let wall = map.add_tile(TileDef::solid(TextureId(0)));
+        map.set(2, 2, Some(wall));
+        // Down onto the upper-right edge of the diamond centred at (0, -40).
+        let hit = Ray2D::new(Vec2::new(5.0, 20.0), Vec2::NEG_Y)
+            .cast_tilemap(&map)
+            .unwrap();
+        assert!((hit.distance - 52.5).abs() < 1e-3);
+        assert!(hit.normal.x > 0.0 && hit.normal.y > 0.0);
+        assert!(matches!(
+            hit.target,
+            HitTarget2D::Tile { col: 2, row: 2, .. }
+        ));
+        assert!(Ray2D::new(Vec2::new(5.0, 20.0), Vec2::NEG_Y)
+            .with_max_distance(50.0)
+            .cast_tilemap(&map)
+            .is_none());
+
+        let hex = HexLayout::new(HexOrientation::Pointy, 10.0);
+        let mut map = TileMap::new(5, 5, 1.0).with_layout(GridLayout::Hex(hex));
+        let wall = map.add_tile(TileDef::solid(TextureId(0)));
+        map.set(2, 2, Some(wall));
+        let center = map.cell_center(2, 2);
+        // A box sliding along the row stops at the hex's flat left side.
+        let sweep = BoxCast2D::new(rect(-20.0, center.y - 2.0, 4.0, 4.0), Vec2::new(100.0, 0.0))
+            .cast_tilemap(&map)
+            .unwrap();
+        let side = center.x - 10.0 * 3f32.sqrt() / 2.0;
+        assert!((sweep.position.x + 4.0 - side).abs() < 1e-3);
+        assert!(matches!(
+            sweep.target,
+            HitTarget2D::Tile { col: 2, row: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn box_cast_reports_stop_position_and_ignores_grazing_contact() {
+        let floor = Solid2D::solid(rect(-100.0, -10.0, 200.0, 10.0));
+        let wall = Solid2D::solid(rect(40.0, 0.0, 10.0, 50.0));
+        let body = rect(0.0, 0.0, 10.0, 10.0);
+
+        let hit = BoxCast2D::new(body, Vec2::new(100.0, 0.0))
+            .cast_solids(&[floor, wall])
+            .unwrap();
+        assert_eq!(hit.target, HitTarget2D::Solid(1));
+        assert_eq!(hit.normal, Vec2::new(-1.0, 0.0));
+        assert!((hit.position.x - 30.0).abs() < 1e-4);
+        assert!((hit.fraction - 0.3).abs() < 1e-4);
+        assert!((hit.point - Vec2::new(40.0, 5.0)).length() < 1e-4);
+
+        let probe = BoxCast2D::new(rect(0.0, 5.0, 10.0, 10.0), Vec2::new(0.0, -20.0))
+            .cast_solids(&[floor])
+            .unwrap();
+        assert_eq!(probe.normal, Vec2::Y);
+        assert!((probe.distance - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn indexed_casts_match_slice_casts() {
+        let solids: Vec<Solid2D> = (0..40)
+            .map(|i| Solid2D::solid(rect(i as f32 * 25.0, (i % 5) as f32 * 12.0, 8.0, 8.0)))
+            .collect();
+        let index = SolidIndex2D::from_solids(32.0, &solids);
+        let ray = Ray2D::segment(Vec2::new(-10.0, 20.0), Vec2::new(900.0, 30.0));
+
+        assert_eq!(ray.cast_solids(&solids), ray.cast_index(&index));
+
+        let sweep = BoxCast2D::new(rect(-20.0, 0.0, 6.0, 6.0), Vec2::new(600.0, 40.0));
+        assert_eq!(sweep.cast_solids(&solids), sweep.cast_index(&index));
+    }
+
+    #[test]
+    fn casts_follow_shaped_solids() {
+        let ramp = Shape2D::polygon(&[
+            Vec2::new(0.0, 0.0),
+            Vec2::new(40.0, 0.0),
+            Vec2::new(40.0, 40.0),
+        ])
+        .unwrap();
+        let solids = [Solid2D::shaped(ramp)];
+
+        // Straight down onto the slope, well inside the bounding box's top.
+        let hit = Ray2D::new(Vec2::new(30.0, 100.0), Vec2::NEG_Y)
+            .cast_solids(&solids)
+            .unwrap();
+        assert!((hit.point.y - 30.0).abs() < 1e-3);
+        assert!((hit.normal - Vec2::new(-1.0, 1.0).normalize()).length() < 1e-4);
+
+        // The empty corner of the bounding box is not a hit.
+        assert!(Ray2D::new(Vec2::new(-10.0, 35.0), Vec2::X)
+            .with_max_distance(40.0)
+            .cast_solids(&solids)
+            .is_none());
+
+        let hit = BoxCast2D::new(rect(20.0, 100.0, 10.0, 10.0), Vec2::new(0.0, -200.0))
+            .cast_solids(&solids)
+            .unwrap();
+        // The box's lower-right corner meets the slope first.
+        assert!((hit.position.y - 30.0).abs() < 1e-3);
+        assert!((hit.point - Vec2::new(30.0, 30.0)).length() < 1e-3);
+    }
+}
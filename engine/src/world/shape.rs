@@ -0,0 +1,945 @@
+use crate::math::rect::Rect;
+use crate::world::physics::{aabb_overlap, CollisionLayer};
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+pub const MAX_POLYGON_VERTICES: usize = 8;
+
+/// A convex polygon with at most [`MAX_POLYGON_VERTICES`] points, stored
+/// counter-clockwise. Fixed capacity keeps [`Shape2D`] (and the colliders that
+/// embed it) `Copy`. Serialized as its point list and rebuilt through
+/// [`ConvexPolygon2D::new`], so a saved polygon is checked like a new one.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Vec2>", into = "Vec<Vec2>")]
+pub struct ConvexPolygon2D {
+    points: [Vec2; MAX_POLYGON_VERTICES],
+    len: usize,
+}
+
+impl ConvexPolygon2D {
+    /// Build from any point cloud by taking its convex hull. Returns `None` for
+    /// fewer than three non-collinear points or a hull with too many vertices.
+    pub fn new(points: &[Vec2]) -> Option<Self> {
+        let hull = convex_hull(points);
+        if hull.len() < 3 || hull.len() > MAX_POLYGON_VERTICES {
+            return None;
+        }
+        let mut polygon = Self {
+            points: [Vec2::ZERO; MAX_POLYGON_VERTICES],
+            len: hull.len(),
+        };
+        polygon.points[..hull.len()].copy_from_slice(&hull);
+        Some(polygon)
+    }
+
+    pub fn from_rect(rect: &Rect) -> Self {
+        Self::from_ccw(&[
+            Vec2::new(rect.left(), rect.bottom()),
+            Vec2::new(rect.right(), rect.bottom()),
+            Vec2::new(rect.right(), rect.top()),
+            Vec2::new(rect.left(), rect.top()),
+        ])
+    }
+
+    /// A box of `half_extents` centred on `center`, rotated by `rotation`
+    /// radians (counter-clockwise).
+    pub fn oriented_box(center: Vec2, half_extents: Vec2, rotation: f32) -> Self {
+        let rot = Vec2::from_angle(rotation);
+        let corner = |x: f32, y: f32| center + rot.rotate(Vec2::new(x, y));
+        Self::from_ccw(&[
+            corner(-half_extents.x, -half_extents.y),
+            corner(half_extents.x, -half_extents.y),
+            corner(half_extents.x, half_extents.y),
+            corner(-half_extents.x, half_extents.y),
+        ])
+    }
+
+    pub fn points(&self) -> &[Vec2] {
+        &self.points[..self.len]
+    }
+
+    pub fn centroid(&self) -> Vec2 {
+        let points = self.points();
+        points.iter().copied().sum::<Vec2>() / points.len() as f32
+    }
+
+    fn from_ccw(points: &[Vec2]) -> Self {
+        let mut polygon = Self {
+            points: [Vec2::ZERO; MAX_POLYGON_VERTICES],
+            len: points.len(),
+        };
+        polygon.points[..points.len()].copy_from_slice(points);
+        polygon
+    }
+
+    fn map(&self, f: impl Fn(Vec2) -> Vec2) -> Self {
+        let mut out = *self;
+        for point in &mut out.points[..self.len] {
+            *point = f(*point);
+        }
+        out
+    }
+}
+
+impl TryFrom<Vec<Vec2>> for ConvexPolygon2D {
+    type Error = String;
+
+    fn try_from(points: Vec<Vec2>) -> Result<Self, Self::Error> {
+        let mut polygon = Self::new(&points).ok_or_else(|| {
+            format!(
+                "expected 3 to {MAX_POLYGON_VERTICES} convex points, got {}",
+                points.len()
+            )
+        })?;
+        // Keep the saved starting vertex so a round trip compares equal.
+        if let Some(start) = polygon
+            .points()
+            .iter()
+            .position(|p| Some(p) == points.first())
+        {
+            polygon.points[..polygon.len].rotate_left(start);
+        }
+        Ok(polygon)
+    }
+}
+
+impl From<ConvexPolygon2D> for Vec<Vec2> {
+    fn from(polygon: ConvexPolygon2D) -> Self {
+        polygon.points().to_vec()
+    }
+}
+
+/// A convex collision shape in world space.
+///
+/// Circles and capsules are "rounded" shapes (a point or segment core plus a
+/// radius); polygons and AABBs have sharp corners. Every pair is handled by one
+/// separating-axis test over the cores' edge normals and vertex-to-vertex
+/// directions, which is exact for this family of shapes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Shape2D {
+    Aabb(Rect),
+    Circle { center: Vec2, radius: f32 },
+    Capsule { a: Vec2, b: Vec2, radius: f32 },
+    Polygon(ConvexPolygon2D),
+}
+
+impl Shape2D {
+    pub fn circle(center: Vec2, radius: f32) -> Self {
+        Self::Circle { center, radius }
+    }
+
+    pub fn capsule(a: Vec2, b: Vec2, radius: f32) -> Self {
+        Self::Capsule { a, b, radius }
+    }
+
+    /// An upright capsule — the usual character collider — whose straight
+    /// section spans `half_height` above and below `center`.
+    pub fn vertical_capsule(center: Vec2, half_height: f32, radius: f32) -> Self {
+        let offset = Vec2::new(0.0, half_height);
+        Self::Capsule {
+            a: center - offset,
+            b: center + offset,
+            radius,
+        }
+    }
+
+    pub fn oriented_box(center: Vec2, half_extents: Vec2, rotation: f32) -> Self {
+        if rotation == 0.0 {
+            Self::Aabb(Rect::from_pos_size(
+                center - half_extents,
+                half_extents * 2.0,
+            ))
+        } else {
+            Self::Polygon(ConvexPolygon2D::oriented_box(
+                center,
+                half_extents,
+                rotation,
+            ))
+        }
+    }
+
+    pub fn polygon(points: &[Vec2]) -> Option<Self> {
+        ConvexPolygon2D::new(points).map(Self::Polygon)
+    }
+
+    pub fn bounds(&self) -> Rect {
+        match *self {
+            Self::Aabb(rect) => rect,
+            Self::Circle { center, radius } => {
+                Rect::from_pos_size(center - Vec2::splat(radius), Vec2::splat(radius * 2.0))
+            }
+            Self::Capsule { a, b, radius } => {
+                let min = a.min(b) - Vec2::splat(radius);
+                let max = a.max(b) + Vec2::splat(radius);
+                Rect::from_pos_size(min, max - min)
+            }
+            Self::Polygon(ref polygon) => {
+                let points = polygon.points();
+                let (min, max) = points[1..]
+                    .iter()
+                    .fold((points[0], points[0]), |(min, max), &p| {
+                        (min.min(p), max.max(p))
+                    });
+                Rect::from_pos_size(min, max - min)
+            }
+        }
+    }
+
+    pub fn center(&self) -> Vec2 {
+        match *self {
+            Self::Aabb(rect) => rect.center(),
+            Self::Circle { center, .. } => center,
+            Self::Capsule { a, b, .. } => (a + b) * 0.5,
+            Self::Polygon(ref polygon) => polygon.centroid(),
+        }
+    }
+
+    pub fn translated(&self, offset: Vec2) -> Self {
+        match *self {
+            Self::Aabb(rect) => Self::Aabb(Rect::new(
+                rect.x + offset.x,
+                rect.y + offset.y,
+                rect.width,
+                rect.height,
+            )),
+            Self::Circle { center, radius } => Self::Circle {
+                center: center + offset,
+                radius,
+            },
+            Self::Capsule { a, b, radius } => Self::Capsule {
+                a: a + offset,
+                b: b + offset,
+                radius,
+            },
+            Self::Polygon(ref polygon) => Self::Polygon(polygon.map(|p| p + offset)),
+        }
+    }
+
+    /// Rotate counter-clockwise by `angle` radians about [`Shape2D::center`].
+    /// A rotated AABB becomes a polygon.
+    pub fn rotated(&self, angle: f32) -> Self {
+        if angle == 0.0 {
+            return *self;
+        }
+        let pivot = self.center();
+        let rot = Vec2::from_angle(angle);
+        let turn = |p: Vec2| pivot + rot.rotate(p - pivot);
+        match *self {
+            Self::Aabb(rect) => Self::Polygon(ConvexPolygon2D::from_rect(&rect).map(turn)),
+            Self::Circle { .. } => *self,
+            Self::Capsule { a, b, radius } => Self::Capsule {
+                a: turn(a),
+                b: turn(b),
+                radius,
+            },
+            Self::Polygon(ref polygon) => Self::Polygon(polygon.map(turn)),
+        }
+    }
+
+    /// Strict containment: points exactly on the boundary are outside, the
+    /// same touching rule as [`Rect::overlaps`].
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        shape_overlap(&Self::circle(point, 0.0), self).is_some()
+    }
+
+    /// The point of the shape furthest along `direction`; the middle of the
+    /// edge when a whole edge faces that way.
+    pub(crate) fn support_point(&self, direction: Vec2) -> Vec2 {
+        let core = self.core();
+        let points = core.points();
+        let reach = points
+            .iter()
+            .map(|p| p.dot(direction))
+            .fold(f32::NEG_INFINITY, f32::max);
+        let (sum, count) = points
+            .iter()
+            .filter(|p| p.dot(direction) >= reach - 1e-4)
+            .fold((Vec2::ZERO, 0.0), |(sum, count), &p| (sum + p, count + 1.0));
+        sum / count + direction.normalize_or_zero() * core.radius
+    }
+
+    fn core(&self) -> Core {
+        match *self {
+            Self::Aabb(rect) => Core::polygon(ConvexPolygon2D::from_rect(&rect).points()),
+            Self::Circle { center, radius } => Core {
+                points: [center; MAX_POLYGON_VERTICES],
+                len: 1,
+                radius,
+            },
+            Self::Capsule { a, b, radius } => {
+                let mut core = Core::polygon(&[a, b]);
+                core.radius = radius;
+                core
+            }
+            Self::Polygon(ref polygon) => Core::polygon(polygon.points()),
+        }
+    }
+}
+
+impl From<Rect> for Shape2D {
+    fn from(rect: Rect) -> Self {
+        Self::Aabb(rect)
+    }
+}
+
+struct Core {
+    points: [Vec2; MAX_POLYGON_VERTICES],
+    len: usize,
+    radius: f32,
+}
+
+impl Core {
+    fn polygon(points: &[Vec2]) -> Self {
+        let mut core = Self {
+            points: [Vec2::ZERO; MAX_POLYGON_VERTICES],
+            len: points.len(),
+            radius: 0.0,
+        };
+        core.points[..points.len()].copy_from_slice(points);
+        core
+    }
+
+    fn points(&self) -> &[Vec2] {
+        &self.points[..self.len]
+    }
+
+    fn project(&self, axis: Vec2) -> (f32, f32) {
+        let (min, max) = self
+            .points()
+            .iter()
+            .map(|p| p.dot(axis))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), d| {
+                (lo.min(d), hi.max(d))
+            });
+        (min - self.radius, max + self.radius)
+    }
+
+    /// The vertex or edge of the core furthest along `direction`.
+    fn feature(&self, direction: Vec2) -> Feature {
+        let points = self.points();
+        let len = points.len();
+        let mut best = 0;
+        for i in 1..len {
+            if points[i].dot(direction) > points[best].dot(direction) {
+                best = i;
+            }
+        }
+        let facing = |p0: Vec2, p1: Vec2| (p1 - p0).normalize_or_zero().dot(direction).abs();
+        match len {
+            0 | 1 => Feature::Vertex(points[best]),
+            2 if facing(points[0], points[1]) < FACE_TOLERANCE => {
+                Feature::Edge(points[0], points[1])
+            }
+            2 => Feature::Vertex(points[best]),
+            _ => {
+                let prev = points[(best + len - 1) % len];
+                let next = points[(best + 1) % len];
+                if facing(prev, points[best]) < facing(points[best], next) {
+                    Feature::Edge(prev, points[best])
+                } else {
+                    Feature::Edge(points[best], next)
+                }
+            }
+        }
+    }
+
+    fn edge_normals(&self, mut visit: impl FnMut(Vec2)) {
+        let points = self.points();
+        match points.len() {
+            0 | 1 => {}
+            2 => {
+                if let Some(n) = (points[1] - points[0]).perp().try_normalize() {
+                    visit(n);
+                }
+            }
+            len => {
+                for i in 0..len {
+                    let edge = points[(i + 1) % len] - points[i];
+                    if let Some(n) = Vec2::new(edge.y, -edge.x).try_normalize() {
+                        visit(n);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Minimum translation vector that pushes `a` out of `b`, or `None` when they
+/// do not overlap (touching counts as not overlapping).
+///
+/// Two AABBs defer to [`aabb_overlap`] so rect-only code keeps its exact
+/// results; every other pairing runs the separating-axis test.
+pub fn shape_overlap(a: &Shape2D, b: &Shape2D) -> Option<Vec2> {
+    if let (Shape2D::Aabb(ra), Shape2D::Aabb(rb)) = (a, b) {
+        return aabb_overlap(ra, rb);
+    }
+
+    let core_a = a.core();
+    let core_b = b.core();
+    let mut best: Option<(f32, Vec2)> = None;
+    let mut separated = false;
+    let mut test_axis = |axis: Vec2| {
+        if separated {
+            return;
+        }
+        let (a_min, a_max) = core_a.project(axis);
+        let (b_min, b_max) = core_b.project(axis);
+        let push_negative = a_max - b_min;
+        let push_positive = b_max - a_min;
+        let depth = push_negative.min(push_positive);
+        if depth <= 0.0 {
+            separated = true;
+            return;
+        }
+        if best.is_none_or(|(d, _)| depth < d) {
+            let mtv = if push_negative < push_positive {
+                -axis * depth
+            } else {
+                axis * depth
+            };
+            best = Some((depth, mtv));
+        }
+    };
+
+    core_a.edge_normals(&mut test_axis);
+    core_b.edge_normals(&mut test_axis);
+    if core_a.radius + core_b.radius > 0.0 {
+        for &pa in core_a.points() {
+            for &pb in core_b.points() {
+                if let Some(axis) = (pa - pb).try_normalize() {
+                    test_axis(axis);
+                }
+            }
+        }
+    }
+
+    if separated {
+        return None;
+    }
+    match best {
+        Some((_, mtv)) => Some(mtv),
+        None => {
+            let depth = core_a.radius + core_b.radius;
+            (depth > 0.0).then_some(Vec2::new(0.0, depth))
+        }
+    }
+}
+
+pub fn shape_overlap_layered(
+    a: &Shape2D,
+    a_layer: &CollisionLayer,
+    b: &Shape2D,
+    b_layer: &CollisionLayer,
+) -> Option<Vec2> {
+    if !a_layer.interacts_with(b_layer) {
+        return None;
+    }
+    shape_overlap(a, b)
+}
+
+pub fn shapes_overlap(a: &Shape2D, b: &Shape2D) -> bool {
+    shape_overlap(a, b).is_some()
+}
+
+/// Contact points between two overlapping shapes, for the rigid-body solver.
+///
+/// `normal` points from `a` into `b`. Each point sits halfway between the two
+/// surfaces and carries its own penetration depth; flat faces resting on each
+/// other produce two points so stacks do not rock.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ShapeContact {
+    pub normal: Vec2,
+    pub points: [(Vec2, f32); 2],
+    pub count: usize,
+}
+
+impl ShapeContact {
+    pub fn points(&self) -> &[(Vec2, f32)] {
+        &self.points[..self.count]
+    }
+}
+
+enum Feature {
+    Vertex(Vec2),
+    Edge(Vec2, Vec2),
+}
+
+/// Edges within this cosine of perpendicular to the normal count as faces.
+const FACE_TOLERANCE: f32 = 0.1;
+
+pub(crate) fn shape_contact(a: &Shape2D, b: &Shape2D) -> Option<ShapeContact> {
+    let mtv = shape_overlap(a, b)?;
+    let depth = mtv.length();
+    let normal = (-mtv).try_normalize()?;
+    let core_a = a.core();
+    let core_b = b.core();
+    let mut contact = ShapeContact {
+        normal,
+        points: [(Vec2::ZERO, 0.0); 2],
+        count: 1,
+    };
+
+    let (edge_a, edge_b) = match (core_a.feature(normal), core_b.feature(-normal)) {
+        (Feature::Vertex(p), _) => {
+            contact.points[0] = (p + normal * (core_a.radius - depth * 0.5), depth);
+            return Some(contact);
+        }
+        (_, Feature::Vertex(p)) => {
+            contact.points[0] = (p - normal * (core_b.radius - depth * 0.5), depth);
+            return Some(contact);
+        }
+        (Feature::Edge(a0, a1), Feature::Edge(b0, b1)) => ((a0, a1), (b0, b1)),
+    };
+
+    // The face more perpendicular to the normal is the reference; the other
+    // is clipped to its extent.
+    let slant = |(p0, p1): (Vec2, Vec2)| (p1 - p0).normalize_or_zero().dot(normal).abs();
+    let (reference, incident, face_normal, ref_radius, inc_radius) =
+        if slant(edge_a) <= slant(edge_b) {
+            (edge_a, edge_b, normal, core_a.radius, core_b.radius)
+        } else {
+            (edge_b, edge_a, -normal, core_b.radius, core_a.radius)
+        };
+    let tangent = (reference.1 - reference.0).normalize_or_zero();
+    let lo = reference.0.dot(tangent).min(reference.1.dot(tangent));
+    let hi = reference.0.dot(tangent).max(reference.1.dot(tangent));
+    let (mut p0, mut p1) = incident;
+    let (d0, d1) = (p0.dot(tangent), p1.dot(tangent));
+    if d0 != d1 {
+        let at = |d: f32| incident.0 + (incident.1 - incident.0) * ((d - d0) / (d1 - d0));
+        if d0 < lo {
+            p0 = at(lo);
+        } else if d0 > hi {
+            p0 = at(hi);
+        }
+        if d1 < lo {
+            p1 = at(lo);
+        } else if d1 > hi {
+            p1 = at(hi);
+        }
+    }
+
+    contact.count = 0;
+    for point in [p0, p1] {
+        let surface = point - face_normal * inc_radius;
+        let point_depth = ref_radius - (surface - reference.0).dot(face_normal);
+        if point_depth > 0.0 && contact.count < 2 {
+            contact.points[contact.count] =
+                (surface + face_normal * (point_depth * 0.5), point_depth);
+            contact.count += 1;
+        }
+    }
+    if contact.count == 2 && contact.points[0].0.distance_squared(contact.points[1].0) < 1e-6 {
+        contact.count = 1;
+    }
+    if contact.count == 0 {
+        // Clipping lost every point (a corner grazing a face end); fall back
+        // to the deepest incident vertex.
+        let deepest = if p0.dot(face_normal) < p1.dot(face_normal) {
+            p0
+        } else {
+            p1
+        };
+        let surface = deepest - face_normal * inc_radius;
+        contact.points[0] = (surface + face_normal * (depth * 0.5), depth);
+        contact.count = 1;
+    }
+    Some(contact)
+}
+
+/// Ray against a shape: distance to the entry point and the surface normal
+/// there, or `(0, -direction)` when the ray starts inside.
+pub(crate) fn ray_shape(
+    origin: Vec2,
+    direction: Vec2,
+    max_distance: f32,
+    shape: &Shape2D,
+) -> Option<(f32, Vec2)> {
+    let core = shape.core();
+    ray_rounded(origin, direction, max_distance, core.points(), core.radius)
+}
+
+/// Sweep `rect` along `direction` against a shape by casting its center
+/// against the Minkowski sum of the shape and the box.
+pub(crate) fn sweep_rect_shape(
+    rect: &Rect,
+    direction: Vec2,
+    max_distance: f32,
+    shape: &Shape2D,
+) -> Option<(f32, Vec2)> {
+    let core = shape.core();
+    let half = Vec2::new(rect.width, rect.height) * 0.5;
+    let mut summed = Vec::with_capacity(core.len * 4);
+    for &p in core.points() {
+        for corner in [
+            Vec2::new(-half.x, -half.y),
+            Vec2::new(half.x, -half.y),
+            Vec2::new(half.x, half.y),
+            Vec2::new(-half.x, half.y),
+        ] {
+            summed.push(p + corner);
+        }
+    }
+    let hull = convex_hull(&summed);
+    ray_rounded(rect.center(), direction, max_distance, &hull, core.radius)
+}
+
+fn ray_rounded(
+    origin: Vec2,
+    direction: Vec2,
+    max_distance: f32,
+    points: &[Vec2],
+    radius: f32,
+) -> Option<(f32, Vec2)> {
+    if radius <= 0.0 {
+        return ray_polygon(origin, direction, max_distance, points);
+    }
+
+    if distance_to_core(origin, points) < radius {
+        return Some((0.0, -direction));
+    }
+
+    let mut best: Option<(f32, Vec2)> = None;
+    let mut consider = |t: f32, normal: Vec2| {
+        if (0.0..=max_distance).contains(&t) && best.is_none_or(|(b, _)| t < b) {
+            best = Some((t, normal));
+        }
+    };
+
+    for &p in points {
+        let to = origin - p;
+        let b = to.dot(direction);
+        let c = to.length_squared() - radius * radius;
+        let disc = b * b - c;
+        if disc > 0.0 {
+            let t = -b - disc.sqrt();
+            consider(t, (origin + direction * t - p) / radius);
+        }
+    }
+
+    let len = points.len();
+    let edges = match len {
+        0 | 1 => 0,
+        2 => 2,
+        _ => len,
+    };
+    for i in 0..edges {
+        let a = points[i % len];
+        let b = points[(i + 1) % len];
+        let edge = b - a;
+        let Some(normal) = Vec2::new(edge.y, -edge.x).try_normalize() else {
+            continue;
+        };
+        let denom = direction.dot(normal);
+        if denom >= 0.0 {
+            continue;
+        }
+        let start = a + normal * radius;
+        let t = normal.dot(start - origin) / denom;
+        let along = (origin + direction * t - start).dot(edge) / edge.length_squared();
+        if (0.0..=1.0).contains(&along) {
+            consider(t, normal);
+        }
+    }
+
+    best
+}
+
+fn ray_polygon(
+    origin: Vec2,
+    direction: Vec2,
+    max_distance: f32,
+    points: &[Vec2],
+) -> Option<(f32, Vec2)> {
+    let len = points.len();
+    if len < 3 {
+        return None;
+    }
+    let mut t_enter = f32::NEG_INFINITY;
+    let mut t_exit = f32::INFINITY;
+    let mut enter_normal = Vec2::ZERO;
+    for i in 0..len {
+        let a = points[i];
+        let edge = points[(i + 1) % len] - a;
+        let Some(normal) = Vec2::new(edge.y, -edge.x).try_normalize() else {
+            continue;
+        };
+        let inside = normal.dot(a - origin);
+        let denom = direction.dot(normal);
+        if denom == 0.0 {
+            if inside <= 0.0 {
+                return None;
+            }
+            continue;
+        }
+        let t = inside / denom;
+        if denom < 0.0 {
+            if t > t_enter {
+                t_enter = t;
+                enter_normal = normal;
+            }
+        } else {
+            t_exit = t_exit.min(t);
+        }
+    }
+
+    if t_exit <= t_enter || t_exit <= 0.0 {
+        return None;
+    }
+    if t_enter < 0.0 {
+        return Some((0.0, -direction));
+    }
+    if t_enter > max_distance {
+        return None;
+    }
+    Some((t_enter, enter_normal))
+}
+
+fn distance_to_core(point: Vec2, points: &[Vec2]) -> f32 {
+    let len = points.len();
+    match len {
+        0 => f32::INFINITY,
+        1 => point.distance(points[0]),
+        _ => {
+            let inside = len >= 3
+                && (0..len).all(|i| {
+                    let edge = points[(i + 1) % len] - points[i];
+                    edge.perp_dot(point - points[i]) > 0.0
+                });
+            if inside {
+                return 0.0;
+            }
+            (0..len)
+                .map(|i| {
+                    let a = points[i];
+                    let edge = points[(i + 1) % len] - a;
+                    let t = ((point - a).dot(edge) / edge.length_squared()).clamp(0.0, 1.0);
+                    point.distance(a + edge * t)
+                })
+                .fold(f32::INFINITY, f32::min)
+        }
+    }
+}
+
+fn convex_hull(points: &[Vec2]) -> Vec<Vec2> {
+    let mut sorted: Vec<Vec2> = points.to_vec();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    let cross = |o: Vec2, a: Vec2, b: Vec2| (a - o).perp_dot(b - o);
+    let mut hull: Vec<Vec2> = Vec::with_capacity(sorted.len() * 2);
+    for &p in &sorted {
+        while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    // The upper chain must never pop back into the finished lower chain.
+    let lower = hull.len() + 1;
+    for &p in sorted.iter().rev().skip(1) {
+        while hull.len() >= lower && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    hull.pop();
+    hull
+}
+
+/// Ear-clip a counter-clockwise simple polygon.
+pub(crate) fn triangulate(points: &[Vec2]) -> Vec<[Vec2; 3]> {
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut triangles = Vec::new();
+    while remaining.len() > 3 {
+        let m = remaining.len();
+        let mut clipped = false;
+        for i in 0..m {
+            let a = points[remaining[(i + m - 1) % m]];
+            let b = points[remaining[i]];
+            let c = points[remaining[(i + 1) % m]];
+            let turn = (b - a).perp_dot(c - b);
+            if turn.abs() < 1e-6 {
+                // Collinear: drop the middle vertex without a triangle.
+                remaining.remove(i);
+                clipped = true;
+                break;
+            }
+            if turn < 0.0 {
+                continue;
+            }
+            let blocked = remaining.iter().any(|&j| {
+                let p = points[j];
+                p != a && p != b && p != c && in_triangle(p, a, b, c)
+            });
+            if !blocked {
+                triangles.push([a, b, c]);
+                remaining.remove(i);
+                clipped = true;
+                break;
+            }
+        }
+        if !clipped {
+            // Self-intersecting input; keep what was clipped so far.
+            return triangles;
+        }
+    }
+    if remaining.len() == 3 {
+        triangles.push([remaining[0], remaining[1], remaining[2]].map(|i| points[i]));
+    }
+    triangles
+}
+
+pub(crate) fn in_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    (b - a).perp_dot(p - a) >= 0.0
+        && (c - b).perp_dot(p - b) >= 0.0
+        && (a - c).perp_dot(p - c) >= 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn polygons_round_trip_as_points_and_reject_bad_payloads() {
+        let polygon = ConvexPolygon2D::oriented_box(Vec2::new(3.0, 1.0), Vec2::new(2.0, 1.0), 0.5);
+        let json = serde_json::to_string(&Shape2D::Polygon(polygon)).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Shape2D>(&json).unwrap(),
+            Shape2D::Polygon(polygon)
+        );
+
+        let nonagon: Vec<Vec2> = (0..9)
+            .map(|i| Vec2::from_angle(i as f32 * std::f32::consts::TAU / 9.0) * 10.0)
+            .collect();
+        for count in [0, 1, 2, 9] {
+            let json = serde_json::to_string(&nonagon[..count]).unwrap();
+            assert!(serde_json::from_str::<ConvexPolygon2D>(&json).is_err());
+        }
+        let collinear = serde_json::to_string(&[Vec2::ZERO, Vec2::X, Vec2::X * 2.0]).unwrap();
+        assert!(serde_json::from_str::<ConvexPolygon2D>(&collinear).is_err());
+    }
+
+    #[test]
+    fn aabb_pairs_match_aabb_overlap() {
+        let a = Rect::new(0.0, 0.0, 10.0, 10.0);
+        let b = Rect::new(8.0, 2.0, 10.0, 10.0);
+        assert_eq!(
+            shape_overlap(&Shape2D::Aabb(a), &Shape2D::Aabb(b)),
+            aabb_overlap(&a, &b)
+        );
+    }
+
+    #[test]
+    fn circles_push_apart_along_the_center_line() {
+        let a = Shape2D::circle(Vec2::ZERO, 5.0);
+        let b = Shape2D::circle(Vec2::new(8.0, 0.0), 5.0);
+        let mtv = shape_overlap(&a, &b).unwrap();
+        assert!((mtv - Vec2::new(-2.0, 0.0)).length() < 1e-4);
+
+        let apart = Shape2D::circle(Vec2::new(10.0, 0.0), 5.0);
+        assert!(shape_overlap(&a, &apart).is_none());
+    }
+
+    #[test]
+    fn circle_near_box_corner_uses_the_corner_direction() {
+        let boxed = Shape2D::Aabb(Rect::new(0.0, 0.0, 10.0, 10.0));
+        let near_corner = Shape2D::circle(Vec2::new(13.0, 13.0), 4.0);
+        assert!(shape_overlap(&near_corner, &boxed).is_none());
+
+        let touching_corner = Shape2D::circle(Vec2::new(12.0, 12.0), 4.0);
+        let mtv = shape_overlap(&touching_corner, &boxed).unwrap();
+        assert!(mtv.x > 0.0 && mtv.y > 0.0);
+        assert!((mtv.x - mtv.y).abs() < 1e-4);
+    }
+
+    #[test]
+    fn rotated_box_overlap_respects_orientation() {
+        let diamond =
+            Shape2D::oriented_box(Vec2::ZERO, Vec2::splat(10.0), std::f32::consts::FRAC_PI_4);
+        // An axis-aligned box would cover (9, 9); the diamond does not.
+        let probe = Shape2D::Aabb(Rect::new(8.5, 8.5, 1.0, 1.0));
+        assert!(shape_overlap(&probe, &diamond).is_none());
+        assert!(diamond.contains_point(Vec2::new(13.0, 0.0)));
+        assert!(!diamond.contains_point(Vec2::new(15.0, 0.0)));
+    }
+
+    #[test]
+    fn capsule_rests_on_ramp_with_a_sloped_normal() {
+        let ramp = Shape2D::polygon(&[
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(100.0, 100.0),
+        ])
+        .unwrap();
+        let capsule = Shape2D::vertical_capsule(Vec2::new(50.0, 55.0), 10.0, 5.0);
+        let mtv = shape_overlap(&capsule, &ramp).unwrap();
+        let n = mtv.normalize();
+        assert!((n - Vec2::new(-1.0, 1.0).normalize()).length() < 1e-4);
+    }
+
+    #[test]
+    fn polygon_hull_drops_interior_points_and_is_ccw() {
+        let poly = ConvexPolygon2D::new(&[
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(5.0, 5.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(0.0, 10.0),
+        ])
+        .unwrap();
+        assert_eq!(poly.points().len(), 4);
+        let p = poly.points();
+        let area: f32 = (0..p.len())
+            .map(|i| p[i].perp_dot(p[(i + 1) % p.len()]))
+            .sum();
+        assert!(area > 0.0);
+        assert!(ConvexPolygon2D::new(&[Vec2::ZERO, Vec2::X, Vec2::new(2.0, 0.0)]).is_none());
+    }
+
+    #[test]
+    fn resting_faces_give_two_contact_points() {
+        let floor = Shape2D::Aabb(Rect::new(-50.0, -10.0, 100.0, 10.0));
+        let crate_box = Shape2D::Aabb(Rect::new(-5.0, -1.0, 10.0, 10.0));
+        let contact = shape_contact(&crate_box, &floor).unwrap();
+        assert_eq!(contact.normal, Vec2::new(0.0, -1.0));
+        assert_eq!(contact.count, 2);
+        for &(point, depth) in contact.points() {
+            assert!((depth - 1.0).abs() < 1e-5);
+            assert!((point.y - -0.5).abs() < 1e-5);
+            assert!((point.x.abs() - 5.0).abs() < 1e-5);
+        }
+
+        let ball = Shape2D::circle(Vec2::new(0.0, 3.0), 4.0);
+        let contact = shape_contact(&ball, &floor).unwrap();
+        assert_eq!(contact.count, 1);
+        assert!((contact.points[0].0 - Vec2::new(0.0, -0.5)).length() < 1e-5);
+    }
+
+    #[test]
+    fn bounds_cover_every_variant() {
+        let capsule = Shape2D::capsule(Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0), 2.0);
+        assert_eq!(capsule.bounds(), Rect::new(-2.0, -2.0, 14.0, 4.0));
+        let moved = capsule.translated(Vec2::new(1.0, 1.0));
+        assert_eq!(moved.bounds(), Rect::new(-1.0, -1.0, 14.0, 4.0));
+    }
+
+    #[test]
+    fn rays_hit_rounded_and_sharp_shapes() {
+        let circle = Shape2D::circle(Vec2::new(10.0, 0.0), 2.0);
+        let (t, n) = ray_shape(Vec2::ZERO, Vec2::X, 100.0, &circle).unwrap();
+        assert!((t - 8.0).abs() < 1e-4);
+        assert!((n - Vec2::NEG_X).length() < 1e-4);
+
+        let capsule = Shape2D::capsule(Vec2::new(-5.0, 10.0), Vec2::new(5.0, 10.0), 1.0);
+        let (t, n) = ray_shape(Vec2::ZERO, Vec2::Y, 100.0, &capsule).unwrap();
+        assert!((t - 9.0).abs() < 1e-4);
+        assert!((n - Vec2::NEG_Y).length() < 1e-4);
+
+        let diamond = Shape2D::oriented_box(Vec2::new(20.0, 0.0), Vec2::splat(2.0), 0.5);
+        assert!(ray_shape(Vec2::ZERO, Vec2::Y, 100.0, &diamond).is_none());
+        assert!(ray_shape(Vec2::ZERO, Vec2::X, 100.0, &diamond).is_some());
+    }
+}
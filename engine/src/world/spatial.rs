@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+
+use crate::math::rect::Rect;
+use crate::world::physics::{
+    resolve_move, MoveResult2D, Solid2D, SolidSource, DEFAULT_MAX_SLOPE_ANGLE,
+};
+use crate::world::shape::Shape2D;
+
+pub type SpatialKey = usize;
+
+pub type SolidId = SpatialKey;
+
+/// A uniform-grid broadphase over axis-aligned rectangles.
+///
+/// Every entry is bucketed into each `cell_size` cell its rect touches, so a
+/// region query only visits the entries near that region instead of scanning
+/// everything. Keys are dense slot indices (reused after [`SpatialGrid2D::remove`])
+/// and query results always come back sorted by key, so callers that depend on
+/// iteration order stay deterministic regardless of the bucket hashing.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid2D {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<SpatialKey>>,
+    entries: Vec<Option<GridEntry>>,
+    free: Vec<SpatialKey>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GridEntry {
+    rect: Rect,
+    cells: CellRange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CellRange {
+    min_x: i32,
+    min_y: i32,
+    max_x: i32,
+    max_y: i32,
+}
+
+impl CellRange {
+    fn cells(self) -> impl Iterator<Item = (i32, i32)> {
+        (self.min_y..=self.max_y).flat_map(move |y| (self.min_x..=self.max_x).map(move |x| (x, y)))
+    }
+}
+
+impl SpatialGrid2D {
+    pub fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0, "SpatialGrid2D cell_size must be positive");
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            entries: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn insert(&mut self, rect: Rect) -> SpatialKey {
+        let cells = self.cell_range(&rect);
+        let key = match self.free.pop() {
+            Some(key) => key,
+            None => {
+                self.entries.push(None);
+                self.entries.len() - 1
+            }
+        };
+        self.entries[key] = Some(GridEntry { rect, cells });
+        for cell in cells.cells() {
+            self.cells.entry(cell).or_default().push(key);
+        }
+        key
+    }
+
+    /// Move an existing entry. Only the buckets that actually changed are
+    /// touched, so nudging a body within its current cells is just a store.
+    pub fn update(&mut self, key: SpatialKey, rect: Rect) -> bool {
+        let new_cells = self.cell_range(&rect);
+        let Some(entry) = self.entries.get_mut(key).and_then(Option::as_mut) else {
+            return false;
+        };
+        let old_cells = entry.cells;
+        entry.rect = rect;
+        entry.cells = new_cells;
+        if old_cells != new_cells {
+            self.unlink(key, old_cells);
+            for cell in new_cells.cells() {
+                self.cells.entry(cell).or_default().push(key);
+            }
+        }
+        true
+    }
+
+    pub fn remove(&mut self, key: SpatialKey) -> Option<Rect> {
+        let entry = self.entries.get_mut(key)?.take()?;
+        self.unlink(key, entry.cells);
+        self.free.push(key);
+        Some(entry.rect)
+    }
+
+    pub fn get(&self, key: SpatialKey) -> Option<Rect> {
+        self.entries.get(key)?.as_ref().map(|entry| entry.rect)
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+        self.free.clear();
+    }
+
+    /// Collect every key whose bucket range touches `region` into `out`
+    /// (cleared first), sorted ascending and deduplicated. This is a
+    /// conservative candidate set: callers still run their exact overlap test.
+    pub fn query_into(&self, region: &Rect, out: &mut Vec<SpatialKey>) {
+        out.clear();
+        for cell in self.cell_range(region).cells() {
+            if let Some(keys) = self.cells.get(&cell) {
+                out.extend_from_slice(keys);
+            }
+        }
+        out.sort_unstable();
+        out.dedup();
+    }
+
+    pub fn query(&self, region: &Rect) -> Vec<SpatialKey> {
+        let mut out = Vec::new();
+        self.query_into(region, &mut out);
+        out
+    }
+
+    fn cell_range(&self, rect: &Rect) -> CellRange {
+        let inv = 1.0 / self.cell_size;
+        CellRange {
+            min_x: (rect.left() * inv).floor() as i32,
+            min_y: (rect.bottom() * inv).floor() as i32,
+            max_x: (rect.right() * inv).floor() as i32,
+            max_y: (rect.top() * inv).floor() as i32,
+        }
+    }
+
+    fn unlink(&mut self, key: SpatialKey, range: CellRange) {
+        for cell in range.cells() {
+            if let Some(keys) = self.cells.get_mut(&cell) {
+                if let Some(index) = keys.iter().position(|&k| k == key) {
+                    keys.swap_remove(index);
+                }
+                if keys.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+    }
+}
+
+/// A registry of [`Solid2D`]s backed by a [`SpatialGrid2D`], for levels with
+/// too many colliders to scan every step.
+///
+/// Solids are addressed by [`SolidId`] and can be moved or removed
+/// incrementally. [`SolidIndex2D::move_and_collide`] produces exactly the same
+/// result as [`move_and_collide_solids`](crate::world::physics::move_and_collide_solids)
+/// over [`SolidIndex2D::solids`] (ascending id order), just without visiting
+/// solids far from the body.
+#[derive(Debug, Clone)]
+pub struct SolidIndex2D {
+    grid: SpatialGrid2D,
+    solids: Vec<Option<Solid2D>>,
+}
+
+impl SolidIndex2D {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            grid: SpatialGrid2D::new(cell_size),
+            solids: Vec::new(),
+        }
+    }
+
+    pub fn from_solids(cell_size: f32, solids: &[Solid2D]) -> Self {
+        let mut index = Self::new(cell_size);
+        for &solid in solids {
+            index.insert(solid);
+        }
+        index
+    }
+
+    pub fn insert(&mut self, solid: Solid2D) -> SolidId {
+        let id = self.grid.insert(solid.rect);
+        if id == self.solids.len() {
+            self.solids.push(Some(solid));
+        } else {
+            self.solids[id] = Some(solid);
+        }
+        id
+    }
+
+    pub fn set(&mut self, id: SolidId, solid: Solid2D) -> bool {
+        if !self.grid.update(id, solid.rect) {
+            return false;
+        }
+        self.solids[id] = Some(solid);
+        true
+    }
+
+    pub fn set_rect(&mut self, id: SolidId, rect: Rect) -> bool {
+        match self.get(id) {
+            Some(solid) => self.set(id, Solid2D { rect, ..solid }),
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, id: SolidId) -> Option<Solid2D> {
+        self.grid.remove(id)?;
+        self.solids[id].take()
+    }
+
+    pub fn get(&self, id: SolidId) -> Option<Solid2D> {
+        self.solids.get(id).copied().flatten()
+    }
+
+    pub fn len(&self) -> usize {
+        self.grid.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.grid.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.grid.clear();
+        self.solids.clear();
+    }
+
+    pub fn grid(&self) -> &SpatialGrid2D {
+        &self.grid
+    }
+
+    /// All live solids in ascending id order — the order the indexed mover
+    /// is equivalent to.
+    pub fn solids(&self) -> impl Iterator<Item = (SolidId, &Solid2D)> + '_ {
+        self.solids
+            .iter()
+            .enumerate()
+            .filter_map(|(id, solid)| solid.as_ref().map(|solid| (id, solid)))
+    }
+
+    pub fn query(&self, region: &Rect) -> impl Iterator<Item = (SolidId, &Solid2D)> + '_ {
+        self.grid
+            .query(region)
+            .into_iter()
+            .filter_map(move |id| self.solids[id].as_ref().map(|solid| (id, solid)))
+    }
+
+    /// Broadphase version of
+    /// [`move_and_collide_solids`](crate::world::physics::move_and_collide_solids).
+    ///
+    /// Candidates are gathered for the swept region of each axis pass and
+    /// visited in id order. If a snap pushes the body outside the region
+    /// gathered so far, the region grows and any newly reachable solids with a
+    /// higher id join the pass, so the outcome matches the brute-force scan
+    /// bit for bit.
+    pub fn move_and_collide(&self, body: Rect, motion: glam::Vec2) -> MoveResult2D {
+        resolve_move(&Shape2D::Aabb(body), motion, self, DEFAULT_MAX_SLOPE_ANGLE)
+    }
+
+    /// Broadphase version of
+    /// [`move_and_collide_shape`](crate::world::physics::move_and_collide_shape).
+    pub fn move_and_collide_shape(
+        &self,
+        body: &Shape2D,
+        motion: glam::Vec2,
+        max_slope_angle: f32,
+    ) -> MoveResult2D {
+        resolve_move(body, motion, self, max_slope_angle)
+    }
+}
+
+impl SolidSource for SolidIndex2D {
+    fn gather(&self, region: &Rect, out: &mut Vec<usize>) {
+        self.grid.query_into(region, out);
+    }
+
+    fn solid_at(&self, id: usize) -> Option<Solid2D> {
+        self.get(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Rng;
+    use crate::world::physics::{move_and_collide_shape, move_and_collide_solids};
+    use glam::Vec2;
+
+    fn random_solids(rng: &mut Rng, count: usize) -> Vec<Solid2D> {
+        (0..count)
+            .map(|_| {
+                let rect = Rect::new(
+                    rng.f32_range(-400.0, 400.0),
+                    rng.f32_range(-400.0, 400.0),
+                    rng.f32_range(4.0, 80.0),
+                    rng.f32_range(4.0, 80.0),
+                );
+                if rng.chance(0.3) {
+                    Solid2D::one_way(rect)
+                } else {
+                    Solid2D::solid(rect)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn query_returns_sorted_unique_keys_near_the_region() {
+        let mut grid = SpatialGrid2D::new(32.0);
+        let a = grid.insert(Rect::new(0.0, 0.0, 100.0, 100.0));
+        let b = grid.insert(Rect::new(500.0, 500.0, 10.0, 10.0));
+        let c = grid.insert(Rect::new(40.0, 40.0, 10.0, 10.0));
+
+        let hits = grid.query(&Rect::new(30.0, 30.0, 30.0, 30.0));
+        assert_eq!(hits, vec![a, c]);
+        assert!(!hits.contains(&b));
+    }
+
+    #[test]
+    fn update_and_remove_keep_buckets_consistent() {
+        let mut grid = SpatialGrid2D::new(16.0);
+        let key = grid.insert(Rect::new(0.0, 0.0, 8.0, 8.0));
+
+        assert!(grid.update(key, Rect::new(200.0, 200.0, 8.0, 8.0)));
+        assert!(grid.query(&Rect::new(0.0, 0.0, 8.0, 8.0)).is_empty());
+        assert_eq!(grid.query(&Rect::new(195.0, 195.0, 8.0, 8.0)), vec![key]);
+
+        assert!(grid.remove(key).is_some());
+        assert!(grid.query(&Rect::new(195.0, 195.0, 8.0, 8.0)).is_empty());
+        assert!(grid.is_empty());
+        assert_eq!(grid.insert(Rect::new(0.0, 0.0, 1.0, 1.0)), key);
+    }
+
+    #[test]
+    fn indexed_mover_matches_brute_force() {
+        let mut rng = Rng::new(7);
+        let solids = random_solids(&mut rng, 300);
+        let index = SolidIndex2D::from_solids(48.0, &solids);
+
+        for _ in 0..2000 {
+            let body = Rect::new(
+                rng.f32_range(-420.0, 420.0),
+                rng.f32_range(-420.0, 420.0),
+                rng.f32_range(4.0, 40.0),
+                rng.f32_range(4.0, 40.0),
+            );
+            let motion = Vec2::new(rng.f32_range(-60.0, 60.0), rng.f32_range(-60.0, 60.0));
+
+            assert_eq!(
+                index.move_and_collide(body, motion),
+                move_and_collide_solids(body, motion, &solids)
+            );
+        }
+    }
+
+    #[test]
+    fn indexed_mover_tracks_moved_and_removed_solids() {
+        let mut index = SolidIndex2D::new(32.0);
+        let wall = index.insert(Solid2D::solid(Rect::new(15.0, 0.0, 10.0, 100.0)));
+        let body = Rect::new(0.0, 0.0, 10.0, 10.0);
+
+        assert!(
+            index
+                .move_and_collide(body, Vec2::new(20.0, 0.0))
+                .contacts
+                .right
+        );
+
+        index.set_rect(wall, Rect::new(300.0, 0.0, 10.0, 100.0));
+        assert!(
+            !index
+                .move_and_collide(body, Vec2::new(20.0, 0.0))
+                .contacts
+                .right
+        );
+
+        index.set_rect(wall, Rect::new(15.0, 0.0, 10.0, 100.0));
+        index.remove(wall);
+        assert!(!index
+            .move_and_collide(body, Vec2::new(20.0, 0.0))
+            .contacts
+            .any());
+    }
+
+    #[test]
+    fn indexed_shape_mover_matches_brute_force() {
+        let mut rng = Rng::new(19);
+        let mut solids = random_solids(&mut rng, 200);
+        for solid in solids.iter_mut().step_by(3) {
+            let c = solid.rect.center();
+            let shape = if rng.chance(0.5) {
+                Shape2D::circle(c, solid.rect.width.min(solid.rect.height) * 0.5)
+            } else {
+                Shape2D::oriented_box(c, Vec2::new(solid.rect.width, solid.rect.height) * 0.5, 0.4)
+            };
+            *solid = Solid2D::shaped(shape).with_one_way(solid.one_way);
+        }
+        let index = SolidIndex2D::from_solids(48.0, &solids);
+
+        for _ in 0..1000 {
+            let center = Vec2::new(rng.f32_range(-420.0, 420.0), rng.f32_range(-420.0, 420.0));
+            let body = Shape2D::vertical_capsule(
+                center,
+                rng.f32_range(0.0, 16.0),
+                rng.f32_range(3.0, 12.0),
+            );
+            let motion = Vec2::new(rng.f32_range(-60.0, 60.0), rng.f32_range(-60.0, 60.0));
+
+            assert_eq!(
+                index.move_and_collide_shape(&body, motion, DEFAULT_MAX_SLOPE_ANGLE),
+                move_and_collide_shape(&body, motion, &solids, DEFAULT_MAX_SLOPE_ANGLE)
+            );
+        }
+    }
+}
@@ -0,0 +1,547 @@
+use crate::math::rect::Rect;
+use crate::math::rng::Rng;
+use crate::world::physics::{KinematicBody2D, Solid2D};
+use crate::world::raycast::{BoxCast2D, SweepHit2D};
+use crate::world::tilemap::TileMap;
+use glam::Vec2;
+
+/// What steering behaviours know about the agent they steer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SteeringAgent {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub max_speed: f32,
+    /// The largest change in velocity per second the behaviours may make.
+    pub max_force: f32,
+    /// Half the agent's width, for obstacle avoidance.
+    pub radius: f32,
+}
+
+impl SteeringAgent {
+    pub fn new(position: Vec2, max_speed: f32) -> Self {
+        Self {
+            position,
+            velocity: Vec2::ZERO,
+            max_speed,
+            max_force: max_speed * 4.0,
+            radius: 0.0,
+        }
+    }
+
+    /// An agent at the centre of a body's bounds, moving with its velocity.
+    pub fn from_body(body: &KinematicBody2D, max_speed: f32) -> Self {
+        Self::new(body.bounds.center(), max_speed)
+            .with_velocity(body.velocity)
+            .with_radius(body.bounds.width.max(body.bounds.height) / 2.0)
+    }
+
+    pub fn with_velocity(mut self, velocity: Vec2) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    pub fn with_max_force(mut self, max_force: f32) -> Self {
+        self.max_force = max_force;
+        self
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// The way the agent is moving, or zero when it stands still.
+    pub fn heading(&self) -> Vec2 {
+        self.velocity.normalize_or_zero()
+    }
+}
+
+/// A weighted blend of steering behaviours for one agent and one step.
+///
+/// Each behaviour adds a steering force — the change from the agent's
+/// velocity to the one the behaviour wants — with a weight. The forces are
+/// averaged by weight, so weights only matter relative to each other (a
+/// total below 1 leaves part of the change undone), and behaviours with
+/// nothing to do, like a flee out of panic range, do not count.
+/// [`Steering::velocity`] applies the blend, at most `max_force` per second,
+/// and returns a velocity capped at `max_speed`, ready to store in a
+/// [`KinematicBody2D`]'s `velocity`:
+///
+/// ```ignore
+/// let agent = SteeringAgent::from_body(&body, 120.0);
+/// body.velocity = Steering::new(&agent, dt)
+///     .arrive(target, 48.0, 1.0)
+///     .separation(&flock, 24.0, 1.5)
+///     .avoid_tiles(&map, 0.5, 2.0)
+///     .velocity();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Steering<'a> {
+    agent: &'a SteeringAgent,
+    dt: f32,
+    sum: Vec2,
+    weight: f32,
+}
+
+impl<'a> Steering<'a> {
+    pub fn new(agent: &'a SteeringAgent, dt: f32) -> Self {
+        Self {
+            agent,
+            dt,
+            sum: Vec2::ZERO,
+            weight: 0.0,
+        }
+    }
+
+    /// The blended change in velocity the behaviours ask for.
+    pub fn force(&self) -> Vec2 {
+        self.sum / self.weight.max(1.0)
+    }
+
+    /// The agent's velocity after applying the blended force for this step.
+    pub fn velocity(&self) -> Vec2 {
+        let change = self
+            .force()
+            .clamp_length_max(self.agent.max_force * self.dt);
+        (self.agent.velocity + change).clamp_length_max(self.agent.max_speed)
+    }
+
+    /// Add a force computed elsewhere.
+    pub fn add(mut self, force: Vec2, weight: f32) -> Self {
+        self.sum += force * weight;
+        self.weight += weight.abs();
+        self
+    }
+
+    /// Steer towards a velocity, given as the one wanted at full weight.
+    pub fn towards(self, desired: Vec2, weight: f32) -> Self {
+        let force = desired.clamp_length_max(self.agent.max_speed) - self.agent.velocity;
+        self.add(force, weight)
+    }
+
+    /// Head for `target` at full speed.
+    pub fn seek(self, target: Vec2, weight: f32) -> Self {
+        let desired = (target - self.agent.position).normalize_or_zero() * self.agent.max_speed;
+        self.towards(desired, weight)
+    }
+
+    /// Run from `threat` at full speed while it is within `panic_distance`.
+    pub fn flee(self, threat: Vec2, panic_distance: f32, weight: f32) -> Self {
+        let away = self.agent.position - threat;
+        if away.length_squared() > panic_distance * panic_distance {
+            return self;
+        }
+        let desired = away.normalize_or_zero() * self.agent.max_speed;
+        self.towards(desired, weight)
+    }
+
+    /// Head for `target`, slowing down inside `slowing_radius` to stop on it.
+    pub fn arrive(self, target: Vec2, slowing_radius: f32, weight: f32) -> Self {
+        let offset = target - self.agent.position;
+        let distance = offset.length();
+        let speed = if distance < slowing_radius {
+            self.agent.max_speed * distance / slowing_radius
+        } else {
+            self.agent.max_speed
+        };
+        let desired = offset.normalize_or_zero() * speed;
+        self.towards(desired, weight)
+    }
+
+    /// Seek where a moving target will be by the time the agent could get
+    /// there.
+    pub fn pursue(self, position: Vec2, velocity: Vec2, weight: f32) -> Self {
+        let target = position + velocity * self.lead_time(position);
+        self.seek(target, weight)
+    }
+
+    /// Flee from where a moving threat will be.
+    pub fn evade(self, position: Vec2, velocity: Vec2, panic_distance: f32, weight: f32) -> Self {
+        if self.agent.position.distance_squared(position) > panic_distance * panic_distance {
+            return self;
+        }
+        let threat = position + velocity * self.lead_time(position);
+        self.flee(threat, f32::INFINITY, weight)
+    }
+
+    /// Amble about, steering towards a point that drifts around a circle
+    /// held out in front of the agent.
+    pub fn wander(self, wander: &mut Wander, rng: &mut Rng, weight: f32) -> Self {
+        wander.angle += rng.f32_range(-1.0, 1.0) * wander.jitter * self.dt;
+        let heading = match self.agent.heading() {
+            Vec2::ZERO => Vec2::from_angle(wander.angle),
+            heading => heading,
+        };
+        let center = self.agent.position + heading * wander.distance;
+        let target = center + heading.rotate(Vec2::from_angle(wander.angle)) * wander.radius;
+        self.seek(target, weight)
+    }
+
+    /// Walk a [`PathFollower`]'s points in order, arriving at the last one
+    /// unless the path loops.
+    pub fn follow_path(self, path: &mut PathFollower, weight: f32) -> Self {
+        let Some(target) = path.advance(self.agent.position) else {
+            // A finished path keeps the agent on its last point.
+            return match path.points.last() {
+                Some(&last) if path.is_finished() => self.arrive(last, path.reach * 2.0, weight),
+                _ => self,
+            };
+        };
+        if path.is_last() && !path.looped {
+            self.arrive(target, path.reach * 2.0, weight)
+        } else {
+            self.seek(target, weight)
+        }
+    }
+
+    /// Turn away from solids the agent would reach within `lookahead`
+    /// seconds at full speed on its current heading.
+    pub fn avoid_solids(self, solids: &[Solid2D], lookahead: f32, weight: f32) -> Self {
+        let hit = self
+            .feeler(lookahead)
+            .and_then(|cast| cast.cast_solids(solids));
+        self.avoid(hit, weight)
+    }
+
+    /// Turn away from a [`TileMap`]'s colliding tiles the agent would reach
+    /// within `lookahead` seconds at full speed on its current heading.
+    pub fn avoid_tiles(self, map: &TileMap, lookahead: f32, weight: f32) -> Self {
+        let hit = self
+            .feeler(lookahead)
+            .and_then(|cast| cast.cast_tilemap(map));
+        self.avoid(hit, weight)
+    }
+
+    /// Push away from neighbours closer than `distance`, harder the closer
+    /// they are. Neighbours at the agent's own position are skipped, so a
+    /// whole flock including the agent can be passed.
+    pub fn separation(self, neighbours: &[SteeringAgent], distance: f32, weight: f32) -> Self {
+        let mut push = Vec2::ZERO;
+        for other in self.near(neighbours, distance) {
+            let away = self.agent.position - other.position;
+            let gap = away.length();
+            push += away / gap * (1.0 - gap / distance);
+        }
+        if push == Vec2::ZERO {
+            return self;
+        }
+        let desired = push.clamp_length_max(1.0) * self.agent.max_speed;
+        self.towards(desired, weight)
+    }
+
+    /// Match the average velocity of neighbours within `distance`.
+    pub fn alignment(self, neighbours: &[SteeringAgent], distance: f32, weight: f32) -> Self {
+        let (sum, count) = self
+            .near(neighbours, distance)
+            .fold((Vec2::ZERO, 0), |(sum, count), other| {
+                (sum + other.velocity, count + 1)
+            });
+        if count == 0 {
+            return self;
+        }
+        self.towards(sum / count as f32, weight)
+    }
+
+    /// Seek the centre of neighbours within `distance`.
+    pub fn cohesion(self, neighbours: &[SteeringAgent], distance: f32, weight: f32) -> Self {
+        let (sum, count) = self
+            .near(neighbours, distance)
+            .fold((Vec2::ZERO, 0), |(sum, count), other| {
+                (sum + other.position, count + 1)
+            });
+        if count == 0 {
+            return self;
+        }
+        self.seek(sum / count as f32, weight)
+    }
+
+    fn lead_time(&self, position: Vec2) -> f32 {
+        let speed = self.agent.max_speed.max(self.agent.velocity.length());
+        if speed <= f32::EPSILON {
+            0.0
+        } else {
+            self.agent.position.distance(position) / speed
+        }
+    }
+
+    fn near<'b>(
+        &self,
+        neighbours: &'b [SteeringAgent],
+        distance: f32,
+    ) -> impl Iterator<Item = &'b SteeringAgent> + 'b {
+        let (position, range) = (self.agent.position, distance * distance);
+        neighbours.iter().filter(move |other| {
+            let d = position.distance_squared(other.position);
+            d > 0.0 && d < range
+        })
+    }
+
+    /// The agent's square swept along its heading for `lookahead` seconds
+    /// at full speed, so slowing down does not shorten the warning.
+    fn feeler(&self, lookahead: f32) -> Option<BoxCast2D> {
+        let motion = self.agent.heading() * self.agent.max_speed * lookahead;
+        if motion == Vec2::ZERO {
+            return None;
+        }
+        let size = Vec2::splat(self.agent.radius * 2.0);
+        let rect = Rect::from_pos_size(self.agent.position - size / 2.0, size);
+        Some(BoxCast2D::new(rect, motion))
+    }
+
+    /// Steer off a surface ahead: sideways, to the side its normal leans
+    /// towards, and a little back along the normal, harder the sooner it
+    /// would be reached. The push does not brake, so the agent turns away
+    /// instead of stalling against the surface.
+    fn avoid(self, hit: Option<SweepHit2D>, weight: f32) -> Self {
+        let Some(hit) = hit else {
+            return self;
+        };
+        let heading = self.agent.heading();
+        let side = hit.normal - heading * hit.normal.dot(heading);
+        // Head-on, turn left.
+        let side = side.try_normalize().unwrap_or(heading.perp());
+        let urgency = 1.0 - hit.fraction;
+        let force = (side + hit.normal * 0.5) * self.agent.max_speed * urgency;
+        self.add(force, weight)
+    }
+}
+
+/// State for [`Steering::wander`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wander {
+    /// How far ahead of the agent the wander circle sits.
+    pub distance: f32,
+    pub radius: f32,
+    /// How fast the point on the circle drifts, in radians per second.
+    pub jitter: f32,
+    pub angle: f32,
+}
+
+impl Wander {
+    pub fn new(distance: f32, radius: f32, jitter: f32) -> Self {
+        Self {
+            distance,
+            radius,
+            jitter,
+            angle: 0.0,
+        }
+    }
+}
+
+/// A list of points to walk for [`Steering::follow_path`], such as
+/// [`NavGrid::find_points`](crate::world::nav::NavGrid::find_points).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathFollower {
+    pub points: Vec<Vec2>,
+    /// How close the agent must come to a point before heading for the next.
+    pub reach: f32,
+    /// Start over from the first point after the last.
+    pub looped: bool,
+    index: usize,
+}
+
+impl PathFollower {
+    pub fn new(points: Vec<Vec2>, reach: f32) -> Self {
+        Self {
+            points,
+            reach,
+            looped: false,
+            index: 0,
+        }
+    }
+
+    pub fn with_looped(mut self, looped: bool) -> Self {
+        self.looped = looped;
+        self
+    }
+
+    /// Replace the points, starting again from the first.
+    pub fn set_points(&mut self, points: Vec<Vec2>) {
+        self.points = points;
+        self.index = 0;
+    }
+
+    /// The point being headed for.
+    pub fn target(&self) -> Option<Vec2> {
+        self.points.get(self.index).copied()
+    }
+
+    /// Whether the agent has reached the last point of a path that does not
+    /// loop.
+    pub fn is_finished(&self) -> bool {
+        !self.looped && self.index >= self.points.len()
+    }
+
+    fn is_last(&self) -> bool {
+        self.index + 1 == self.points.len()
+    }
+
+    /// Move past every point within reach of `position` and return the one
+    /// to head for.
+    fn advance(&mut self, position: Vec2) -> Option<Vec2> {
+        let reach = self.reach * self.reach;
+        for _ in 0..self.points.len() {
+            let target = self.target()?;
+            if position.distance_squared(target) > reach {
+                return Some(target);
+            }
+            self.index += 1;
+            if self.looped && self.index == self.points.len() {
+                self.index = 0;
+            }
+        }
+        self.target()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    /// Run `steer` on one agent for `seconds`, integrating its position.
+    fn simulate(
+        agent: &mut SteeringAgent,
+        seconds: f32,
+        mut steer: impl FnMut(&SteeringAgent) -> Vec2,
+    ) -> Vec<Vec2> {
+        let mut trail = Vec::new();
+        for _ in 0..(seconds / DT) as usize {
+            agent.velocity = steer(agent);
+            agent.position += agent.velocity * DT;
+            trail.push(agent.position);
+        }
+        trail
+    }
+
+    #[test]
+    fn seek_flee_arrive_and_pursue_head_the_right_way() {
+        let agent = SteeringAgent::new(Vec2::ZERO, 100.0);
+        let seek = Steering::new(&agent, DT).seek(Vec2::new(10.0, 0.0), 10.0);
+        assert!(seek.force().x > 0.0 && seek.force().y.abs() < 1e-4);
+        let flee = Steering::new(&agent, DT).flee(Vec2::new(10.0, 0.0), 50.0, 1.0);
+        assert!(flee.force().x < 0.0);
+        // Out of panic range nothing happens.
+        let calm = Steering::new(&agent, DT).flee(Vec2::new(100.0, 0.0), 50.0, 1.0);
+        assert_eq!(calm.force(), Vec2::ZERO);
+        // Forces are capped at max_force, speeds at max_speed.
+        let v = seek.velocity();
+        assert!((v.length() - agent.max_force * DT).abs() < 1e-3, "{v}");
+
+        let mut walker = SteeringAgent::new(Vec2::ZERO, 100.0);
+        let target = Vec2::new(200.0, 50.0);
+        simulate(&mut walker, 5.0, |a| {
+            Steering::new(a, DT).arrive(target, 60.0, 1.0).velocity()
+        });
+        assert!(walker.position.distance(target) < 1.0);
+        assert!(walker.velocity.length() < 5.0);
+
+        // Pursuit leads a target crossing in front.
+        let chaser = SteeringAgent::new(Vec2::ZERO, 100.0);
+        let lead =
+            Steering::new(&chaser, DT).pursue(Vec2::new(100.0, 0.0), Vec2::new(0.0, 50.0), 1.0);
+        assert!(lead.force().y > 0.0);
+        let evade = Steering::new(&chaser, DT).evade(
+            Vec2::new(100.0, 0.0),
+            Vec2::new(0.0, 50.0),
+            500.0,
+            1.0,
+        );
+        assert!(evade.force().x < 0.0 && evade.force().y < 0.0);
+    }
+
+    #[test]
+    fn wandering_is_seeded_smooth_and_never_speeds() {
+        let run = |seed| {
+            let mut agent = SteeringAgent::new(Vec2::ZERO, 50.0).with_velocity(Vec2::X * 50.0);
+            let mut wander = Wander::new(40.0, 20.0, 4.0);
+            let mut rng = Rng::new(seed);
+            simulate(&mut agent, 4.0, |a| {
+                Steering::new(a, DT)
+                    .wander(&mut wander, &mut rng, 1.0)
+                    .velocity()
+            })
+        };
+        let trail = run(9);
+        assert_eq!(trail, run(9));
+        assert_ne!(trail, run(10));
+        for pair in trail.windows(2) {
+            assert!(pair[0].distance(pair[1]) <= 50.0 * DT + 1e-4);
+        }
+    }
+
+    #[test]
+    fn obstacle_avoidance_steers_around_walls() {
+        let wall = Rect::new(100.0, -40.0, 20.0, 80.0);
+        let solids = [Solid2D::solid(wall)];
+        let target = Vec2::new(220.0, 0.0);
+        let mut agent = SteeringAgent::new(Vec2::new(0.0, 5.0), 80.0).with_radius(6.0);
+        let trail = simulate(&mut agent, 8.0, |a| {
+            Steering::new(a, DT)
+                .arrive(target, 40.0, 1.0)
+                .avoid_solids(&solids, 0.6, 4.0)
+                .velocity()
+        });
+        // The agent's square may brush the wall's corners, but never overlap.
+        let inflated = Rect::new(
+            wall.x - 5.5,
+            wall.y - 5.5,
+            wall.width + 11.0,
+            wall.height + 11.0,
+        );
+        assert!(trail.iter().all(|p| !inflated.contains_point(*p)));
+        assert!(agent.position.distance(target) < 10.0);
+    }
+
+    #[test]
+    fn paths_are_followed_to_the_end_and_loops_repeat() {
+        let points = vec![
+            Vec2::new(50.0, 0.0),
+            Vec2::new(50.0, 50.0),
+            Vec2::new(0.0, 50.0),
+        ];
+        let mut path = PathFollower::new(points.clone(), 8.0);
+        let mut agent = SteeringAgent::new(Vec2::ZERO, 60.0);
+        let trail = simulate(&mut agent, 6.0, |a| {
+            Steering::new(a, DT).follow_path(&mut path, 1.0).velocity()
+        });
+        assert!(path.is_finished());
+        assert!(agent.position.distance(points[2]) < 8.0);
+        assert!(trail.iter().any(|p| p.distance(points[1]) < 8.0));
+
+        let mut loop_path = PathFollower::new(points, 8.0).with_looped(true);
+        let mut agent = SteeringAgent::new(Vec2::ZERO, 60.0);
+        simulate(&mut agent, 12.0, |a| {
+            Steering::new(a, DT)
+                .follow_path(&mut loop_path, 1.0)
+                .velocity()
+        });
+        assert!(!loop_path.is_finished());
+        assert!(loop_path.target().is_some());
+    }
+
+    #[test]
+    fn flocks_separate_align_and_cohere() {
+        let flock = [
+            SteeringAgent::new(Vec2::new(0.0, 0.0), 50.0).with_velocity(Vec2::new(10.0, 0.0)),
+            SteeringAgent::new(Vec2::new(4.0, 0.0), 50.0).with_velocity(Vec2::new(0.0, 10.0)),
+            SteeringAgent::new(Vec2::new(60.0, 0.0), 50.0).with_velocity(Vec2::new(0.0, 10.0)),
+        ];
+        let me = &flock[0];
+        let apart = Steering::new(me, DT).separation(&flock, 10.0, 1.0).force();
+        assert!(apart.x < 0.0);
+        // Only the close neighbour counts for alignment.
+        let aligned = Steering::new(me, DT).alignment(&flock, 10.0, 1.0).force();
+        assert!((aligned - Vec2::new(-10.0, 10.0)).length() < 1e-4);
+        let together = Steering::new(me, DT).cohesion(&flock, 100.0, 1.0).force();
+        assert!(together.x > 0.0);
+        // Nobody in range, no force.
+        assert_eq!(
+            Steering::new(&flock[2], DT)
+                .separation(&flock, 10.0, 1.0)
+                .force(),
+            Vec2::ZERO
+        );
+    }
+}
@@ -0,0 +1,1602 @@
+//! Importer for maps made in the [Tiled](https://www.mapeditor.org) editor.
+//!
+//! Both the JSON (`.tmj`/`.tsj`) and XML (`.tmx`/`.tsx`) formats parse into
+//! the same [`TiledMap`], which mirrors the file closely: tile layers keep
+//! their raw global tile ids (flip flags included) and objects keep Tiled's
+//! y-down pixel coordinates. [`TiledMap::build`] then turns that into engine
+//! types in world space — one [`TileMap`] per tile layer, [`Solid2D`]s,
+//! [`TriggerZone`]s and spawn markers — flipping rows so the map's bottom
+//! edge sits on `y = 0`.
+//!
+//! Maps are normally loaded through the asset pipeline (`tiled_maps` in an
+//! asset manifest, or `Engine::load_tiled_map`), which resolves external
+//! tilesets, loads tileset images as [`SpriteSheet`]s and hot-reloads the map
+//! when any of its files change.
+//!
+//! Only finite, orthogonal maps with square tiles are supported, and tile
+//! layer data must be CSV or uncompressed Base64.
+
+use std::collections::{BTreeMap, HashMap};
+use std::f32::consts::FRAC_PI_2;
+use std::path::Path;
+
+use glam::Vec2;
+use serde_json::Value;
+
+use crate::assets::{AssetError, SpriteSheet};
+use crate::math::rect::Rect;
+use crate::renderer::Frame;
+use crate::world::physics::Solid2D;
+use crate::world::shape::{triangulate, Shape2D, MAX_POLYGON_VERTICES};
+use crate::world::tilemap::{TileDef, TileMap};
+use crate::world::trigger::{TriggerSystem, TriggerZone, TriggerZoneId};
+
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+const ROTATED_HEXAGONAL_120: u32 = 0x1000_0000;
+const GID_MASK: u32 =
+    !(FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY | FLIPPED_DIAGONALLY | ROTATED_HEXAGONAL_120);
+
+/// Radius of the capsules a polyline object becomes when used as a collider.
+const POLYLINE_RADIUS: f32 = 1.0;
+
+/// A custom property value. Colors, files and class values are kept as their
+/// string form; object references are the referenced object id.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TiledValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+/// The custom properties attached to a map, layer, tile or object.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TiledProperties(BTreeMap<String, TiledValue>);
+
+impl TiledProperties {
+    pub fn get(&self, name: &str) -> Option<&TiledValue> {
+        self.0.get(name)
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        match self.get(name)? {
+            TiledValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn get_int(&self, name: &str) -> Option<i64> {
+        match self.get(name)? {
+            TiledValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// A float property, also accepting an int so designers needn't care.
+    pub fn get_float(&self, name: &str) -> Option<f64> {
+        match self.get(name)? {
+            TiledValue::Float(value) => Some(*value),
+            TiledValue::Int(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        match self.get(name)? {
+            TiledValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &TiledValue)> {
+        self.0.iter().map(|(name, value)| (name.as_str(), value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, value: TiledValue) {
+        self.0.insert(name.into(), value);
+    }
+}
+
+/// The geometry of a [`TiledObject`]. Polygon and polyline points are relative
+/// to the object's position, in Tiled's y-down pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TiledObjectKind {
+    Rect,
+    Ellipse,
+    Point,
+    Polygon(Vec<Vec2>),
+    Polyline(Vec<Vec2>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledObject {
+    pub id: u32,
+    pub name: String,
+    /// The object's class (called "type" in some Tiled versions).
+    pub class: String,
+    /// Top-left corner in y-down map pixels; bottom-left for tile objects.
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// Clockwise rotation about `(x, y)`, in degrees.
+    pub rotation: f32,
+    pub kind: TiledObjectKind,
+    /// Set for tile objects: the raw global tile id, flip flags included.
+    pub gid: Option<u32>,
+    pub visible: bool,
+    pub properties: TiledProperties,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TiledTile {
+    pub class: String,
+    pub properties: TiledProperties,
+    /// Collision shapes from the tile collision editor, in tile pixels.
+    pub collision: Vec<TiledObject>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TiledTileset {
+    pub first_gid: u32,
+    pub name: String,
+    /// The external tileset file this came from, as written in the map.
+    pub source: Option<String>,
+    /// The tileset image, relative to the file that declared it.
+    pub image: Option<String>,
+    pub image_width: u32,
+    pub image_height: u32,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub columns: u32,
+    pub tile_count: u32,
+    pub margin: u32,
+    pub spacing: u32,
+    /// Per-tile data, keyed by local tile id. Tiles without properties or
+    /// collision have no entry.
+    pub tiles: BTreeMap<u32, TiledTile>,
+}
+
+impl TiledTileset {
+    /// Parse a standalone `.tsj` or `.tsx` tileset. `path` is only used to
+    /// report errors; the format is detected from the contents.
+    pub fn parse(path: &Path, text: &str) -> Result<Self, AssetError> {
+        let parsed = if is_xml(text) {
+            parse_xml(text).and_then(|root| xml_tileset(&root))
+        } else {
+            serde_json::from_str::<Value>(text)
+                .map_err(|error| error.to_string())
+                .and_then(|root| json_tileset(&root))
+        };
+        parsed.map_err(|message| AssetError::map_message(path, message))
+    }
+
+    /// Whether any tile in the set has collision shapes. Tilesets without any
+    /// make every tile collide as a full cell.
+    pub fn has_collision(&self) -> bool {
+        self.tiles.values().any(|tile| !tile.collision.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledTileLayer {
+    pub name: String,
+    pub class: String,
+    pub visible: bool,
+    /// Raw global tile ids, row by row from the top, flip flags included.
+    /// `0` is an empty cell.
+    pub data: Vec<u32>,
+    pub properties: TiledProperties,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledObjectLayer {
+    pub name: String,
+    pub class: String,
+    pub visible: bool,
+    pub objects: Vec<TiledObject>,
+    pub properties: TiledProperties,
+}
+
+/// A tile or object layer. Group layers are flattened into their children;
+/// image layers are skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TiledLayer {
+    Tiles(TiledTileLayer),
+    Objects(TiledObjectLayer),
+}
+
+impl TiledLayer {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Tiles(layer) => &layer.name,
+            Self::Objects(layer) => &layer.name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TiledMap {
+    /// Size in tiles.
+    pub width: usize,
+    pub height: usize,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub tilesets: Vec<TiledTileset>,
+    pub layers: Vec<TiledLayer>,
+    pub properties: TiledProperties,
+}
+
+impl TiledMap {
+    /// Parse a `.tmj` or `.tmx` map. External tilesets are left as
+    /// placeholders holding only `first_gid` and `source`; the asset pipeline
+    /// fills them in. `path` is only used to report errors.
+    pub fn parse(path: &Path, text: &str) -> Result<Self, AssetError> {
+        let parsed = if is_xml(text) {
+            parse_xml(text).and_then(|root| xml_map(&root))
+        } else {
+            serde_json::from_str::<Value>(text)
+                .map_err(|error| error.to_string())
+                .and_then(|root| json_map(&root))
+        };
+        parsed.map_err(|message| AssetError::map_message(path, message))
+    }
+
+    /// The tileset a global tile id belongs to, with the id local to it.
+    pub fn tileset_for(&self, gid: u32) -> Option<(usize, u32)> {
+        let gid = gid & GID_MASK;
+        if gid == 0 {
+            return None;
+        }
+        self.tilesets
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, tileset)| tileset.first_gid <= gid)
+            .map(|(index, tileset)| (index, gid - tileset.first_gid))
+    }
+
+    pub fn pixel_height(&self) -> f32 {
+        self.height as f32 * self.tile_height as f32
+    }
+
+    /// Convert a point from Tiled's y-down map pixels to world space.
+    pub fn to_world(&self, point: Vec2) -> Vec2 {
+        Vec2::new(point.x, self.pixel_height() - point.y)
+    }
+
+    /// Build world-space tile maps, colliders, triggers and spawn markers.
+    /// `sheets` holds one sprite sheet per entry in `tilesets`.
+    ///
+    /// Objects are sorted by class (falling back to their layer's class):
+    /// `solid` becomes a [`Solid2D`], `one_way` (or `platform`) a one-way
+    /// solid, `trigger` a [`TriggerZone`], and everything else — including
+    /// every point — a [`TiledSpawn`]. Concave polygons are split into
+    /// convex pieces, each its own solid or trigger.
+    ///
+    /// A tile with collision shapes covering the whole cell collides as a
+    /// [`TileMap`] cell; narrower shapes become extra solids. When a tileset
+    /// has no collision shapes at all, all its tiles collide. A `collides`
+    /// bool property on a tile or tile layer overrides both. An `opaque` bool
+    /// property on a tile or tile layer makes its cells block sight.
+    pub fn build(&self, sheets: &[SpriteSheet]) -> TiledLevel {
+        let mut level = TiledLevel::default();
+        let place = |point: Vec2| self.to_world(point);
+        for layer in &self.layers {
+            match layer {
+                TiledLayer::Tiles(layer) => {
+                    let map = self.build_tile_layer(layer, sheets, &mut level.solids);
+                    level.layers.push(TiledLayerMap {
+                        name: layer.name.clone(),
+                        class: layer.class.clone(),
+                        visible: layer.visible,
+                        map,
+                        properties: layer.properties.clone(),
+                    });
+                }
+                TiledLayer::Objects(layer) => {
+                    for object in &layer.objects {
+                        place_object(object, layer, &place, &mut level);
+                    }
+                }
+            }
+        }
+        level
+    }
+
+    fn build_tile_layer(
+        &self,
+        layer: &TiledTileLayer,
+        sheets: &[SpriteSheet],
+        solids: &mut Vec<Solid2D>,
+    ) -> TileMap {
+        let tile_size = self.tile_width as f32;
+        let mut map = TileMap::new(self.width, self.height, tile_size);
+        let layer_collides = layer.properties.get_bool("collides").unwrap_or(true);
+        let layer_opaque = layer.properties.get_bool("opaque");
+        // Each distinct raw gid (tile plus flips) gets one TileDef.
+        let mut defs: HashMap<u32, usize> = HashMap::new();
+
+        for (index, &raw) in layer.data.iter().enumerate() {
+            let Some((set_index, local)) = self.tileset_for(raw) else {
+                continue;
+            };
+            let Some(sheet) = sheets.get(set_index) else {
+                continue;
+            };
+            let tileset = &self.tilesets[set_index];
+            let tile = tileset.tiles.get(&local);
+            let (grid, partial) = tile_collision(tileset, tile, layer_collides);
+            let opaque = tile
+                .and_then(|tile| tile.properties.get_bool("opaque"))
+                .or(layer_opaque)
+                .unwrap_or(false);
+            let id = *defs.entry(raw).or_insert_with(|| {
+                let columns = sheet.columns().max(1);
+                let def = TileDef::solid(sheet.texture)
+                    .with_uv(sheet.uv_rect(local % columns, local / columns))
+                    .with_collision(grid)
+                    .with_opaque(opaque);
+                let def = if raw & FLIPPED_DIAGONALLY != 0 {
+                    // Tiled transposes first, then flips. A transpose is a
+                    // vertical flip followed by a clockwise quarter turn, and
+                    // the turn swaps which axis the later flips act on.
+                    def.with_flip(
+                        raw & FLIPPED_VERTICALLY != 0,
+                        raw & FLIPPED_HORIZONTALLY == 0,
+                    )
+                    .with_rotation(-FRAC_PI_2)
+                } else {
+                    def.with_flip(
+                        raw & FLIPPED_HORIZONTALLY != 0,
+                        raw & FLIPPED_VERTICALLY != 0,
+                    )
+                };
+                map.add_tile(def)
+            });
+
+            let col = index % self.width;
+            let row = self.height - 1 - index / self.width;
+            map.set(col, row, Some(id));
+
+            if partial.is_empty() {
+                continue;
+            }
+            let (tw, th) = (tileset.tile_width as f32, tileset.tile_height as f32);
+            let scale = tile_size / tw.max(1.0);
+            let top_left = Vec2::new(col as f32, (row + 1) as f32) * tile_size;
+            let place = |point: Vec2| {
+                let mut p = point;
+                if raw & FLIPPED_DIAGONALLY != 0 {
+                    p = Vec2::new(p.y, p.x);
+                }
+                if raw & FLIPPED_HORIZONTALLY != 0 {
+                    p.x = tw - p.x;
+                }
+                if raw & FLIPPED_VERTICALLY != 0 {
+                    p.y = th - p.y;
+                }
+                top_left + Vec2::new(p.x, -p.y) * scale
+            };
+            for object in partial {
+                for shape in object_shapes(object, &place) {
+                    let mut solid = solid_from(shape, false);
+                    solid.layer = map.layer;
+                    solids.push(solid);
+                }
+            }
+        }
+        map
+    }
+}
+
+/// A tile layer built into a [`TileMap`].
+pub struct TiledLayerMap {
+    pub name: String,
+    pub class: String,
+    pub visible: bool,
+    pub map: TileMap,
+    pub properties: TiledProperties,
+}
+
+/// A trigger object. A concave trigger polygon yields several of these that
+/// share the object's id, name and properties.
+#[derive(Debug, Clone)]
+pub struct TiledTrigger {
+    pub id: u32,
+    pub name: String,
+    pub class: String,
+    /// The object layer it came from.
+    pub layer: String,
+    pub zone: TriggerZone,
+    pub properties: TiledProperties,
+}
+
+/// A marker object — a spawn point, pickup or anything the game places itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledSpawn {
+    pub id: u32,
+    pub name: String,
+    pub class: String,
+    /// The object layer it came from.
+    pub layer: String,
+    /// The point itself for point objects, otherwise the centre of `rect`.
+    pub position: Vec2,
+    /// World-space bounds; empty for point objects.
+    pub rect: Rect,
+    pub gid: Option<u32>,
+    pub properties: TiledProperties,
+}
+
+/// A [`TiledMap`] converted to world-space engine types.
+#[derive(Default)]
+pub struct TiledLevel {
+    pub layers: Vec<TiledLayerMap>,
+    pub solids: Vec<Solid2D>,
+    pub triggers: Vec<TiledTrigger>,
+    pub spawns: Vec<TiledSpawn>,
+}
+
+impl TiledLevel {
+    pub fn layer(&self, name: &str) -> Option<&TileMap> {
+        self.layers
+            .iter()
+            .find(|layer| layer.name == name)
+            .map(|layer| &layer.map)
+    }
+
+    pub fn spawn(&self, name: &str) -> Option<&TiledSpawn> {
+        self.spawns.iter().find(|spawn| spawn.name == name)
+    }
+
+    pub fn spawns_of<'a>(&'a self, class: &'a str) -> impl Iterator<Item = &'a TiledSpawn> + 'a {
+        self.spawns.iter().filter(move |spawn| spawn.class == class)
+    }
+
+    /// Every collider in the level as solids: the object and tile-shape
+    /// `solids` plus the colliding cells of each tile layer.
+    pub fn all_solids(&self) -> Vec<Solid2D> {
+        let mut solids = self.solids.clone();
+        for layer in &self.layers {
+            solids.extend(layer.map.solid_rects().into_iter().map(|rect| {
+                let mut solid = Solid2D::solid(rect);
+                solid.layer = layer.map.layer;
+                solid
+            }));
+        }
+        solids
+    }
+
+    /// Add every trigger to `system`, returning zone ids in `triggers` order.
+    pub fn add_triggers(&self, system: &mut TriggerSystem) -> Vec<TriggerZoneId> {
+        self.triggers
+            .iter()
+            .map(|trigger| system.add_zone(trigger.zone.clone()))
+            .collect()
+    }
+
+    /// Draw the visible tile layers in file order, bottom layer first.
+    pub fn draw(&self, frame: &mut Frame) {
+        for layer in self.layers.iter().filter(|layer| layer.visible) {
+            layer.map.draw(frame);
+        }
+    }
+}
+
+/// Whether a tile collides as a whole grid cell, and which of its collision
+/// shapes must instead become separate solids.
+fn tile_collision<'a>(
+    tileset: &TiledTileset,
+    tile: Option<&'a TiledTile>,
+    layer_collides: bool,
+) -> (bool, &'a [TiledObject]) {
+    if !layer_collides {
+        return (false, &[]);
+    }
+    let forced = tile.and_then(|tile| tile.properties.get_bool("collides"));
+    if forced == Some(false) {
+        return (false, &[]);
+    }
+    match tile {
+        Some(tile) if !tile.collision.is_empty() => {
+            let (tw, th) = (tileset.tile_width as f32, tileset.tile_height as f32);
+            let full = tile.collision.iter().any(|object| {
+                object.kind == TiledObjectKind::Rect
+                    && object.rotation == 0.0
+                    && object.x <= 0.0
+                    && object.y <= 0.0
+                    && object.x + object.width >= tw
+                    && object.y + object.height >= th
+            });
+            if full {
+                (true, &[])
+            } else {
+                (false, &tile.collision)
+            }
+        }
+        _ => (forced == Some(true) || !tileset.has_collision(), &[]),
+    }
+}
+
+fn place_object(
+    object: &TiledObject,
+    layer: &TiledObjectLayer,
+    place: &dyn Fn(Vec2) -> Vec2,
+    level: &mut TiledLevel,
+) {
+    let class = if object.class.is_empty() {
+        &layer.class
+    } else {
+        &object.class
+    };
+    let shapes = object_shapes(object, place);
+    let kind = class.to_ascii_lowercase();
+    match kind.as_str() {
+        "solid" | "one_way" | "oneway" | "platform" if !shapes.is_empty() => {
+            let one_way = kind != "solid" || object.properties.get_bool("one_way") == Some(true);
+            level
+                .solids
+                .extend(shapes.into_iter().map(|shape| solid_from(shape, one_way)));
+        }
+        "trigger" if !shapes.is_empty() => {
+            for shape in shapes {
+                let zone = match shape {
+                    Shape2D::Aabb(rect) => TriggerZone::new(rect),
+                    shape => TriggerZone::shaped(shape),
+                };
+                level.triggers.push(TiledTrigger {
+                    id: object.id,
+                    name: object.name.clone(),
+                    class: class.to_string(),
+                    layer: layer.name.clone(),
+                    zone,
+                    properties: object.properties.clone(),
+                });
+            }
+        }
+        _ => {
+            let (position, rect) = match shapes.split_first() {
+                Some((first, rest)) => {
+                    let rect = rest
+                        .iter()
+                        .fold(first.bounds(), |acc, shape| union(acc, shape.bounds()));
+                    (rect.center(), rect)
+                }
+                None => {
+                    let point = place(Vec2::new(object.x, object.y));
+                    (point, Rect::from_pos_size(point, Vec2::ZERO))
+                }
+            };
+            level.spawns.push(TiledSpawn {
+                id: object.id,
+                name: object.name.clone(),
+                class: class.to_string(),
+                layer: layer.name.clone(),
+                position,
+                rect,
+                gid: object.gid,
+                properties: object.properties.clone(),
+            });
+        }
+    }
+}
+
+fn solid_from(shape: Shape2D, one_way: bool) -> Solid2D {
+    let mut solid = match shape {
+        Shape2D::Aabb(rect) => Solid2D::solid(rect),
+        shape => Solid2D::shaped(shape),
+    };
+    solid.one_way = one_way;
+    solid
+}
+
+fn union(a: Rect, b: Rect) -> Rect {
+    let min = Vec2::new(a.left().min(b.left()), a.bottom().min(b.bottom()));
+    let max = Vec2::new(a.right().max(b.right()), a.top().max(b.top()));
+    Rect::from_pos_size(min, max - min)
+}
+
+/// An object's colliders in world space. `place` maps a point in the object's
+/// parent space (y-down pixels) to world space; points yield nothing.
+fn object_shapes(object: &TiledObject, place: &dyn Fn(Vec2) -> Vec2) -> Vec<Shape2D> {
+    let (sin, cos) = object.rotation.to_radians().sin_cos();
+    let origin = Vec2::new(object.x, object.y);
+    // Tile objects are anchored at their bottom-left corner.
+    let lift = if object.gid.is_some() {
+        object.height
+    } else {
+        0.0
+    };
+    let world = |local: Vec2| {
+        let local = Vec2::new(local.x, local.y - lift);
+        place(origin + Vec2::new(local.x * cos - local.y * sin, local.x * sin + local.y * cos))
+    };
+    let (w, h) = (object.width, object.height);
+
+    match &object.kind {
+        TiledObjectKind::Point => Vec::new(),
+        TiledObjectKind::Rect => {
+            if w <= 0.0 || h <= 0.0 {
+                return Vec::new();
+            }
+            let corners = [
+                Vec2::ZERO,
+                Vec2::new(w, 0.0),
+                Vec2::new(w, h),
+                Vec2::new(0.0, h),
+            ]
+            .map(world);
+            let axis_aligned = (0..4).all(|i| {
+                let edge = corners[(i + 1) % 4] - corners[i];
+                edge.x.abs() < 1e-3 || edge.y.abs() < 1e-3
+            });
+            if axis_aligned {
+                vec![Shape2D::Aabb(bounds_of(&corners))]
+            } else {
+                Shape2D::polygon(&corners).into_iter().collect()
+            }
+        }
+        TiledObjectKind::Ellipse => {
+            if w <= 0.0 || h <= 0.0 {
+                return Vec::new();
+            }
+            let center = world(Vec2::new(w, h) / 2.0);
+            let across = world(Vec2::new(w, h / 2.0)) - center;
+            let down = world(Vec2::new(w / 2.0, h)) - center;
+            let (major, minor) = if across.length() >= down.length() {
+                (across, down.length())
+            } else {
+                (down, across.length())
+            };
+            // An ellipse that isn't a circle becomes the capsule spanning it.
+            if major.length() - minor < 1e-3 {
+                vec![Shape2D::circle(center, minor)]
+            } else {
+                let axis = major.normalize() * (major.length() - minor);
+                vec![Shape2D::capsule(center - axis, center + axis, minor)]
+            }
+        }
+        TiledObjectKind::Polygon(points) => {
+            let points: Vec<Vec2> = points.iter().map(|&p| world(p)).collect();
+            convex_pieces(&points)
+        }
+        TiledObjectKind::Polyline(points) => points
+            .windows(2)
+            .map(|pair| Shape2D::capsule(world(pair[0]), world(pair[1]), POLYLINE_RADIUS))
+            .collect(),
+    }
+}
+
+fn bounds_of(points: &[Vec2]) -> Rect {
+    let min = points
+        .iter()
+        .fold(Vec2::splat(f32::MAX), |acc, p| acc.min(*p));
+    let max = points
+        .iter()
+        .fold(Vec2::splat(f32::MIN), |acc, p| acc.max(*p));
+    Rect::from_pos_size(min, max - min)
+}
+
+/// A polygon as one convex shape when it is one, otherwise as triangles.
+fn convex_pieces(points: &[Vec2]) -> Vec<Shape2D> {
+    let mut points = points.to_vec();
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if points.len() < 3 {
+        return Vec::new();
+    }
+    let area: f32 = (0..points.len())
+        .map(|i| points[i].perp_dot(points[(i + 1) % points.len()]))
+        .sum();
+    if area < 0.0 {
+        points.reverse();
+    }
+    let n = points.len();
+    let convex = (0..n).all(|i| {
+        let (a, b, c) = (points[i], points[(i + 1) % n], points[(i + 2) % n]);
+        (b - a).perp_dot(c - b) >= -1e-4
+    });
+    if convex && n <= MAX_POLYGON_VERTICES {
+        return Shape2D::polygon(&points).into_iter().collect();
+    }
+    triangulate(&points)
+        .iter()
+        .filter_map(|triangle| Shape2D::polygon(triangle))
+        .collect()
+}
+
+fn is_xml(text: &str) -> bool {
+    text.trim_start().starts_with('<')
+}
+
+fn check_map(map: &TiledMap, orientation: &str, infinite: bool) -> Result<(), String> {
+    if orientation != "orthogonal" {
+        return Err(format!("{orientation} maps are not supported"));
+    }
+    if infinite {
+        return Err("infinite maps are not supported".into());
+    }
+    if map.tile_width == 0 || map.tile_width != map.tile_height {
+        return Err(format!(
+            "tiles must be square, found {}x{}",
+            map.tile_width, map.tile_height
+        ));
+    }
+    for layer in &map.layers {
+        if let TiledLayer::Tiles(tiles) = layer {
+            if tiles.data.len() != map.width * map.height {
+                return Err(format!(
+                    "layer '{}' has {} tiles, expected {}",
+                    tiles.name,
+                    tiles.data.len(),
+                    map.width * map.height
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn decode_layer_data(text: &str, encoding: &str, compression: &str) -> Result<Vec<u32>, String> {
+    if !compression.is_empty() {
+        return Err(format!(
+            "{compression}-compressed layer data is not supported; save with CSV or \
+             uncompressed Base64"
+        ));
+    }
+    match encoding {
+        "csv" => text
+            .split(',')
+            .map(str::trim)
+            .filter(|cell| !cell.is_empty())
+            .map(|cell| cell.parse().map_err(|_| format!("bad tile id '{cell}'")))
+            .collect(),
+        "base64" => {
+            let bytes = decode_base64(text)?;
+            Ok(bytes
+                .chunks_exact(4)
+                .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect())
+        }
+        other => Err(format!("unknown layer encoding '{other}'")),
+    }
+}
+
+fn decode_base64(text: &str) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in text.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            c if c.is_ascii_whitespace() => continue,
+            c => return Err(format!("invalid Base64 character '{}'", c as char)),
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(out)
+}
+
+// --- JSON (.tmj / .tsj) ---
+
+fn json_str(value: &Value, key: &str) -> String {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn json_u32(value: &Value, key: &str) -> u32 {
+    value.get(key).and_then(Value::as_u64).unwrap_or(0) as u32
+}
+
+fn json_f32(value: &Value, key: &str) -> f32 {
+    value.get(key).and_then(Value::as_f64).unwrap_or(0.0) as f32
+}
+
+fn json_bool(value: &Value, key: &str, default: bool) -> bool {
+    value.get(key).and_then(Value::as_bool).unwrap_or(default)
+}
+
+fn json_array<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn json_class(value: &Value) -> String {
+    // Tiled 1.9 wrote "class"; earlier and later versions write "type".
+    let class = json_str(value, "class");
+    if class.is_empty() {
+        json_str(value, "type")
+    } else {
+        class
+    }
+}
+
+fn json_properties(value: &Value) -> TiledProperties {
+    let mut properties = TiledProperties::default();
+    for property in json_array(value, "properties") {
+        let name = json_str(property, "name");
+        let raw = property.get("value").unwrap_or(&Value::Null);
+        let value = match (property.get("type").and_then(Value::as_str), raw) {
+            (_, Value::Bool(value)) => TiledValue::Bool(*value),
+            (Some("float"), Value::Number(n)) => TiledValue::Float(n.as_f64().unwrap_or(0.0)),
+            (_, Value::Number(n)) => match n.as_i64() {
+                Some(int) => TiledValue::Int(int),
+                None => TiledValue::Float(n.as_f64().unwrap_or(0.0)),
+            },
+            (_, Value::String(text)) => TiledValue::String(text.clone()),
+            (_, other) => TiledValue::String(other.to_string()),
+        };
+        properties.insert(name, value);
+    }
+    properties
+}
+
+fn json_points(value: &Value, key: &str) -> Vec<Vec2> {
+    json_array(value, key)
+        .iter()
+        .map(|point| Vec2::new(json_f32(point, "x"), json_f32(point, "y")))
+        .collect()
+}
+
+fn json_object(value: &Value) -> TiledObject {
+    let kind = if value.get("polygon").is_some() {
+        TiledObjectKind::Polygon(json_points(value, "polygon"))
+    } else if value.get("polyline").is_some() {
+        TiledObjectKind::Polyline(json_points(value, "polyline"))
+    } else if json_bool(value, "point", false) {
+        TiledObjectKind::Point
+    } else if json_bool(value, "ellipse", false) {
+        TiledObjectKind::Ellipse
+    } else {
+        TiledObjectKind::Rect
+    };
+    TiledObject {
+        id: json_u32(value, "id"),
+        name: json_str(value, "name"),
+        class: json_class(value),
+        x: json_f32(value, "x"),
+        y: json_f32(value, "y"),
+        width: json_f32(value, "width"),
+        height: json_f32(value, "height"),
+        rotation: json_f32(value, "rotation"),
+        kind,
+        gid: value
+            .get("gid")
+            .and_then(Value::as_u64)
+            .map(|gid| gid as u32),
+        visible: json_bool(value, "visible", true),
+        properties: json_properties(value),
+    }
+}
+
+fn json_tileset(value: &Value) -> Result<TiledTileset, String> {
+    let mut tiles = BTreeMap::new();
+    for tile in json_array(value, "tiles") {
+        let collision = tile
+            .get("objectgroup")
+            .map(|group| {
+                json_array(group, "objects")
+                    .iter()
+                    .map(json_object)
+                    .collect()
+            })
+            .unwrap_or_default();
+        tiles.insert(
+            json_u32(tile, "id"),
+            TiledTile {
+                class: json_class(tile),
+                properties: json_properties(tile),
+                collision,
+            },
+        );
+    }
+    let image = json_str(value, "image");
+    Ok(TiledTileset {
+        first_gid: json_u32(value, "firstgid"),
+        name: json_str(value, "name"),
+        source: value
+            .get("source")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        image: (!image.is_empty()).then_some(image),
+        image_width: json_u32(value, "imagewidth"),
+        image_height: json_u32(value, "imageheight"),
+        tile_width: json_u32(value, "tilewidth"),
+        tile_height: json_u32(value, "tileheight"),
+        columns: json_u32(value, "columns"),
+        tile_count: json_u32(value, "tilecount"),
+        margin: json_u32(value, "margin"),
+        spacing: json_u32(value, "spacing"),
+        tiles,
+    })
+}
+
+fn json_layers(layers: &[Value], out: &mut Vec<TiledLayer>) -> Result<(), String> {
+    for layer in layers {
+        let name = json_str(layer, "name");
+        // A layer's "type" is its kind, so only "class" names its class.
+        let class = json_str(layer, "class");
+        let visible = json_bool(layer, "visible", true);
+        let properties = json_properties(layer);
+        match layer.get("type").and_then(Value::as_str) {
+            Some("tilelayer") => {
+                let data = match layer.get("data") {
+                    Some(Value::String(text)) => decode_layer_data(
+                        text,
+                        &json_str(layer, "encoding"),
+                        &json_str(layer, "compression"),
+                    )?,
+                    Some(Value::Array(cells)) => cells
+                        .iter()
+                        .map(|cell| cell.as_u64().unwrap_or(0) as u32)
+                        .collect(),
+                    _ => return Err(format!("layer '{name}' has no tile data")),
+                };
+                out.push(TiledLayer::Tiles(TiledTileLayer {
+                    name,
+                    class,
+                    visible,
+                    data,
+                    properties,
+                }));
+            }
+            Some("objectgroup") => out.push(TiledLayer::Objects(TiledObjectLayer {
+                name,
+                class,
+                visible,
+                objects: json_array(layer, "objects")
+                    .iter()
+                    .map(json_object)
+                    .collect(),
+                properties,
+            })),
+            Some("group") => json_layers(json_array(layer, "layers"), out)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn json_map(value: &Value) -> Result<TiledMap, String> {
+    let mut map = TiledMap {
+        width: json_u32(value, "width") as usize,
+        height: json_u32(value, "height") as usize,
+        tile_width: json_u32(value, "tilewidth"),
+        tile_height: json_u32(value, "tileheight"),
+        tilesets: json_array(value, "tilesets")
+            .iter()
+            .map(json_tileset)
+            .collect::<Result<_, _>>()?,
+        layers: Vec::new(),
+        properties: json_properties(value),
+    };
+    json_layers(json_array(value, "layers"), &mut map.layers)?;
+    let orientation = value
+        .get("orientation")
+        .and_then(Value::as_str)
+        .unwrap_or("orthogonal");
+    check_map(&map, orientation, json_bool(value, "infinite", false))?;
+    Ok(map)
+}
+
+// --- XML (.tmx / .tsx) ---
+
+struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlElement>,
+    text: String,
+}
+
+impl XmlElement {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn attr_str(&self, name: &str) -> String {
+        self.attr(name).unwrap_or_default().to_string()
+    }
+
+    fn attr_parse<T: std::str::FromStr>(&self, name: &str, default: T) -> Result<T, String> {
+        match self.attr(name) {
+            Some(text) => text
+                .trim()
+                .parse()
+                .map_err(|_| format!("<{}> has invalid {name}=\"{text}\"", self.name)),
+            None => Ok(default),
+        }
+    }
+
+    fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> + 'a {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    fn class(&self) -> String {
+        // Tiled 1.9 wrote "class"; earlier and later versions write "type".
+        self.attr("class")
+            .or_else(|| self.attr("type"))
+            .unwrap_or_default()
+            .to_string()
+    }
+}
+
+struct XmlReader<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> XmlReader<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn skip_past(&mut self, end: &str) -> Result<&'a str, String> {
+        let rest = self.rest();
+        let at = rest
+            .find(end)
+            .ok_or_else(|| format!("unterminated XML, expected '{end}'"))?;
+        self.pos += at + end.len();
+        Ok(&rest[..at])
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            Ok(())
+        } else {
+            Err(format!("malformed XML, expected '{token}'"))
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, String> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '=' | '/' | '>'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err("malformed XML, expected a name".into());
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    fn skip_prolog(&mut self) -> Result<(), String> {
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn element(&mut self) -> Result<XmlElement, String> {
+        self.expect("<")?;
+        let mut element = XmlElement {
+            name: self.name()?.to_string(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+            text: String::new(),
+        };
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let key = self.name()?.to_string();
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let quote = if self.rest().starts_with('\'') {
+                "'"
+            } else {
+                "\""
+            };
+            self.expect(quote)?;
+            let value = unescape_xml(self.skip_past(quote)?);
+            element.attributes.push((key, value));
+        }
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("</") {
+                self.pos += 2;
+                let close = self.name()?;
+                if close != element.name {
+                    return Err(format!(
+                        "malformed XML, <{}> closed by </{close}>",
+                        element.name
+                    ));
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                return Ok(element);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.pos += "<![CDATA[".len();
+                element.text.push_str(self.skip_past("]]>")?);
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with('<') {
+                element.children.push(self.element()?);
+            } else {
+                let end = rest
+                    .find('<')
+                    .ok_or_else(|| format!("unterminated <{}>", element.name))?;
+                element.text.push_str(&unescape_xml(&rest[..end]));
+                self.pos += end;
+            }
+        }
+    }
+}
+
+fn parse_xml(text: &str) -> Result<XmlElement, String> {
+    let mut reader = XmlReader { text, pos: 0 };
+    reader.skip_prolog()?;
+    reader.element()
+}
+
+fn unescape_xml(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find('&') {
+        out.push_str(&rest[..at]);
+        rest = &rest[at..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn xml_properties(element: &XmlElement) -> Result<TiledProperties, String> {
+    let mut properties = TiledProperties::default();
+    let Some(list) = element.child("properties") else {
+        return Ok(properties);
+    };
+    for property in list.children_named("property") {
+        let name = property.attr_str("name");
+        // Multi-line strings are stored as the element's text.
+        let text = property
+            .attr("value")
+            .map(str::to_string)
+            .unwrap_or_else(|| property.text.clone());
+        let value = match property.attr("type").unwrap_or("string") {
+            "bool" => TiledValue::Bool(text == "true"),
+            "int" | "object" => TiledValue::Int(
+                text.parse()
+                    .map_err(|_| format!("property '{name}' has invalid int '{text}'"))?,
+            ),
+            "float" => TiledValue::Float(
+                text.parse()
+                    .map_err(|_| format!("property '{name}' has invalid float '{text}'"))?,
+            ),
+            _ => TiledValue::String(text),
+        };
+        properties.insert(name, value);
+    }
+    Ok(properties)
+}
+
+fn xml_points(text: &str) -> Result<Vec<Vec2>, String> {
+    text.split_whitespace()
+        .map(|pair| {
+            let (x, y) = pair
+                .split_once(',')
+                .ok_or_else(|| format!("bad point '{pair}'"))?;
+            let x = x.parse().map_err(|_| format!("bad point '{pair}'"))?;
+            let y = y.parse().map_err(|_| format!("bad point '{pair}'"))?;
+            Ok(Vec2::new(x, y))
+        })
+        .collect()
+}
+
+fn xml_object(element: &XmlElement) -> Result<TiledObject, String> {
+    let kind = if let Some(polygon) = element.child("polygon") {
+        TiledObjectKind::Polygon(xml_points(&polygon.attr_str("points"))?)
+    } else if let Some(polyline) = element.child("polyline") {
+        TiledObjectKind::Polyline(xml_points(&polyline.attr_str("points"))?)
+    } else if element.child("point").is_some() {
+        TiledObjectKind::Point
+    } else if element.child("ellipse").is_some() {
+        TiledObjectKind::Ellipse
+    } else {
+        TiledObjectKind::Rect
+    };
+    Ok(TiledObject {
+        id: element.attr_parse("id", 0)?,
+        name: element.attr_str("name"),
+        class: element.class(),
+        x: element.attr_parse("x", 0.0)?,
+        y: element.attr_parse("y", 0.0)?,
+        width: element.attr_parse("width", 0.0)?,
+        height: element.attr_parse("height", 0.0)?,
+        rotation: element.attr_parse("rotation", 0.0)?,
+        kind,
+        gid: element
+            .attr("gid")
+            .map(|_| element.attr_parse("gid", 0))
+            .transpose()?,
+        visible: element.attr("visible") != Some("0"),
+        properties: xml_properties(element)?,
+    })
+}
+
+fn xml_tileset(element: &XmlElement) -> Result<TiledTileset, String> {
+    let mut tiles = BTreeMap::new();
+    for tile in element.children_named("tile") {
+        let collision = match tile.child("objectgroup") {
+            Some(group) => group
+                .children_named("object")
+                .map(xml_object)
+                .collect::<Result<_, _>>()?,
+            None => Vec::new(),
+        };
+        tiles.insert(
+            tile.attr_parse("id", 0)?,
+            TiledTile {
+                class: tile.class(),
+                properties: xml_properties(tile)?,
+                collision,
+            },
+        );
+    }
+    let image = element.child("image");
+    Ok(TiledTileset {
+        first_gid: element.attr_parse("firstgid", 0)?,
+        name: element.attr_str("name"),
+        source: element.attr("source").map(str::to_string),
+        image: image.map(|image| image.attr_str("source")),
+        image_width: image.map_or(Ok(0), |image| image.attr_parse("width", 0))?,
+        image_height: image.map_or(Ok(0), |image| image.attr_parse("height", 0))?,
+        tile_width: element.attr_parse("tilewidth", 0)?,
+        tile_height: element.attr_parse("tileheight", 0)?,
+        columns: element.attr_parse("columns", 0)?,
+        tile_count: element.attr_parse("tilecount", 0)?,
+        margin: element.attr_parse("margin", 0)?,
+        spacing: element.attr_parse("spacing", 0)?,
+        tiles,
+    })
+}
+
+fn xml_layers(element: &XmlElement, out: &mut Vec<TiledLayer>) -> Result<(), String> {
+    for child in &element.children {
+        let name = child.attr_str("name");
+        let visible = child.attr("visible") != Some("0");
+        match child.name.as_str() {
+            "layer" => {
+                let data = child
+                    .child("data")
+                    .ok_or_else(|| format!("layer '{name}' has no tile data"))?;
+                let cells = match data.attr("encoding") {
+                    Some(encoding) => decode_layer_data(
+                        &data.text,
+                        encoding,
+                        data.attr("compression").unwrap_or_default(),
+                    )?,
+                    // The oldest format: one <tile gid="..."/> per cell.
+                    None => data
+                        .children_named("tile")
+                        .map(|tile| tile.attr_parse("gid", 0))
+                        .collect::<Result<_, _>>()?,
+                };
+                out.push(TiledLayer::Tiles(TiledTileLayer {
+                    name,
+                    class: child.attr_str("class"),
+                    visible,
+                    data: cells,
+                    properties: xml_properties(child)?,
+                }));
+            }
+            "objectgroup" => out.push(TiledLayer::Objects(TiledObjectLayer {
+                name,
+                class: child.attr_str("class"),
+                visible,
+                objects: child
+                    .children_named("object")
+                    .map(xml_object)
+                    .collect::<Result<_, _>>()?,
+                properties: xml_properties(child)?,
+            })),
+            "group" => xml_layers(child, out)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn xml_map(root: &XmlElement) -> Result<TiledMap, String> {
+    if root.name != "map" {
+        return Err(format!("expected <map>, found <{}>", root.name));
+    }
+    let mut map = TiledMap {
+        width: root.attr_parse("width", 0)?,
+        height: root.attr_parse("height", 0)?,
+        tile_width: root.attr_parse("tilewidth", 0)?,
+        tile_height: root.attr_parse("tileheight", 0)?,
+        tilesets: root
+            .children_named("tileset")
+            .map(xml_tileset)
+            .collect::<Result<_, _>>()?,
+        layers: Vec::new(),
+        properties: xml_properties(root)?,
+    };
+    xml_layers(root, &mut map.layers)?;
+    check_map(
+        &map,
+        root.attr("orientation").unwrap_or("orthogonal"),
+        root.attr("infinite") == Some("1"),
+    )?;
+    Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::TextureId;
+
+    const TMJ: &str = r#"{
+        "width": 3, "height": 2, "tilewidth": 16, "tileheight": 16,
+        "orientation": "orthogonal", "infinite": false,
+        "properties": [{"name": "music", "type": "string", "value": "cave"}],
+        "tilesets": [{
+            "firstgid": 1, "name": "terrain", "tilewidth": 16, "tileheight": 16,
+            "tilecount": 8, "columns": 4, "image": "terrain.png",
+            "imagewidth": 64, "imageheight": 32,
+            "tiles": [
+                {"id": 0, "objectgroup": {"objects": [
+                    {"id": 1, "x": 0, "y": 0, "width": 16, "height": 16}
+                ]}},
+                {"id": 1, "objectgroup": {"objects": [
+                    {"id": 1, "x": 0, "y": 8, "width": 16, "height": 8}
+                ]}}
+            ]
+        }],
+        "layers": [
+            {"type": "tilelayer", "name": "ground", "width": 3, "height": 2,
+             "visible": true, "data": [0, 0, 2, 1, 2147483649, 7]},
+            {"type": "group", "name": "things", "layers": [
+                {"type": "objectgroup", "name": "zones", "class": "trigger", "objects": [
+                    {"id": 4, "name": "exit", "x": 32, "y": 0, "width": 16, "height": 16,
+                     "properties": [{"name": "to", "type": "string", "value": "level2"}]},
+                    {"id": 5, "name": "start", "point": true, "x": 8, "y": 24},
+                    {"id": 6, "type": "solid", "x": 0, "y": 0, "polygon": [
+                        {"x": 0, "y": 0}, {"x": 32, "y": 0}, {"x": 32, "y": 8},
+                        {"x": 16, "y": 8}, {"x": 16, "y": 16}, {"x": 0, "y": 16}
+                    ]}
+                ]}
+            ]}
+        ]
+    }"#;
+
+    const TMX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <map version="1.10" orientation="orthogonal" width="3" height="2"
+             tilewidth="16" tileheight="16" infinite="0">
+          <properties><property name="music" value="cave"/></properties>
+          <tileset firstgid="1" name="terrain" tilewidth="16" tileheight="16"
+                   tilecount="8" columns="4">
+            <image source="terrain.png" width="64" height="32"/>
+            <tile id="0"><objectgroup><object id="1" x="0" y="0" width="16" height="16"/></objectgroup></tile>
+            <tile id="1"><objectgroup><object id="1" x="0" y="8" width="16" height="8"/></objectgroup></tile>
+          </tileset>
+          <layer id="1" name="ground" width="3" height="2">
+            <data encoding="base64">AAAAAAAAAAACAAAAAQAAAAEAAIAHAAAA</data>
+          </layer>
+          <group name="things">
+            <objectgroup name="zones" class="trigger">
+              <object id="4" name="exit" x="32" y="0" width="16" height="16">
+                <properties><property name="to" value="level2"/></properties>
+              </object>
+              <object id="5" name="start" x="8" y="24"><point/></object>
+              <object id="6" type="solid" x="0" y="0">
+                <polygon points="0,0 32,0 32,8 16,8 16,16 0,16"/>
+              </object>
+            </objectgroup>
+          </group>
+        </map>"#;
+
+    fn sheet() -> SpriteSheet {
+        SpriteSheet::new(TextureId(0), 64, 32, 16, 16)
+    }
+
+    #[test]
+    fn json_and_xml_maps_parse_to_the_same_map() {
+        let json = TiledMap::parse(Path::new("a.tmj"), TMJ).unwrap();
+        let xml = TiledMap::parse(Path::new("a.tmx"), TMX).unwrap();
+
+        assert_eq!(json.layers, xml.layers);
+        assert_eq!(json.tilesets, xml.tilesets);
+        assert_eq!(
+            json.properties.get_str("music"),
+            xml.properties.get_str("music")
+        );
+        let TiledLayer::Tiles(ground) = &json.layers[0] else {
+            panic!("expected a tile layer");
+        };
+        assert_eq!(ground.data, vec![0, 0, 2, 1, FLIPPED_HORIZONTALLY | 1, 7]);
+    }
+
+    #[test]
+    fn tile_layers_flip_rows_and_apply_flip_flags() {
+        let map = TiledMap::parse(Path::new("a.tmj"), TMJ).unwrap();
+        let level = map.build(&[sheet()]);
+        let ground = level.layer("ground").unwrap();
+
+        // Tiled's top row becomes the TileMap's top row (row 1).
+        let plain = ground.tile(ground.get(0, 0).unwrap()).unwrap();
+        let flipped = ground.tile(ground.get(1, 0).unwrap()).unwrap();
+        assert_eq!(ground.get(0, 1), None);
+        assert!(!plain.flip_x && flipped.flip_x);
+        assert_eq!(plain.uv_rect, flipped.uv_rect);
+        let seventh = ground.tile(ground.get(2, 0).unwrap()).unwrap();
+        assert_eq!(seventh.uv_rect, sheet().uv_rect(2, 1));
+    }
+
+    #[test]
+    fn tile_collision_shapes_split_between_grid_and_solids() {
+        let map = TiledMap::parse(Path::new("a.tmx"), TMX).unwrap();
+        let level = map.build(&[sheet()]);
+        let ground = level.layer("ground").unwrap();
+
+        // Tile 0 has a full-cell box; tile 1 only its bottom half.
+        assert!(ground.solid_tile(0, 0).is_some());
+        assert!(ground.solid_tile(2, 1).is_none());
+        let slab = level
+            .solids
+            .iter()
+            .find(|solid| solid.rect.x == 32.0)
+            .unwrap();
+        assert_eq!(slab.rect, Rect::new(32.0, 16.0, 16.0, 8.0));
+        // Tile 6 sits in a tileset with collision but has none of its own.
+        assert!(ground.solid_tile(2, 0).is_none());
+        assert_eq!(ground.solid_rects(), vec![Rect::new(0.0, 0.0, 32.0, 16.0)]);
+        assert_eq!(level.all_solids().len(), level.solids.len() + 1);
+
+        // An `opaque` layer property makes its cells block sight.
+        assert!(!ground.is_opaque(0, 0));
+        let mut map = map;
+        for layer in &mut map.layers {
+            if let TiledLayer::Tiles(layer) = layer {
+                layer
+                    .properties
+                    .0
+                    .insert("opaque".into(), TiledValue::Bool(true));
+            }
+        }
+        let level = map.build(&[sheet()]);
+        assert!(level.layer("ground").unwrap().is_opaque(0, 0));
+    }
+
+    #[test]
+    fn objects_become_triggers_solids_and_spawns() {
+        let map = TiledMap::parse(Path::new("a.tmj"), TMJ).unwrap();
+        let level = map.build(&[sheet()]);
+
+        let exit = &level.triggers[0];
+        assert_eq!(exit.name, "exit");
+        assert_eq!(exit.zone.rect, Rect::new(32.0, 16.0, 16.0, 16.0));
+        assert_eq!(exit.properties.get_str("to"), Some("level2"));
+
+        let start = level.spawn("start").unwrap();
+        assert_eq!(start.position, Vec2::new(8.0, 8.0));
+        assert_eq!(start.class, "trigger");
+
+        // The concave L-shaped polygon is split into convex pieces.
+        let pieces: Vec<_> = level.solids.iter().filter(|s| s.shape.is_some()).collect();
+        assert!(pieces.len() >= 2);
+        let covered = |p: Vec2| pieces.iter().any(|s| s.collider().contains_point(p));
+        assert!(covered(Vec2::new(8.0, 20.0)) && covered(Vec2::new(24.0, 28.0)));
+        assert!(!covered(Vec2::new(24.0, 20.0)));
+
+        let mut triggers = TriggerSystem::new();
+        assert_eq!(level.add_triggers(&mut triggers), vec![0]);
+    }
+
+    #[test]
+    fn diagonal_flip_turns_the_tile_a_quarter() {
+        let mut map = TiledMap::parse(Path::new("a.tmj"), TMJ).unwrap();
+        if let TiledLayer::Tiles(ground) = &mut map.layers[0] {
+            ground.data[3] = FLIPPED_DIAGONALLY | 1;
+        }
+        let level = map.build(&[sheet()]);
+        let ground = level.layer("ground").unwrap();
+        let def = ground.tile(ground.get(0, 0).unwrap()).unwrap();
+
+        assert!((def.rotation + FRAC_PI_2).abs() < 1e-6);
+        assert!(!def.flip_x && def.flip_y);
+    }
+
+    #[test]
+    fn unsupported_maps_report_why() {
+        let infinite = TMJ.replace("\"infinite\": false", "\"infinite\": true");
+        let error = TiledMap::parse(Path::new("a.tmj"), &infinite).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("infinite maps are not supported"));
+
+        let zlib = TMX.replace(
+            "encoding=\"base64\"",
+            "encoding=\"base64\" compression=\"zlib\"",
+        );
+        let error = TiledMap::parse(Path::new("a.tmx"), &zlib).unwrap_err();
+        assert!(error.to_string().contains("zlib-compressed"));
+    }
+}
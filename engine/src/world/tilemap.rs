@@ -1,30 +1,254 @@
 use crate::assets::Color;
+use crate::math::Rect;
 use crate::renderer::{DrawParams, Frame, TextureId};
+use crate::world::fov::VisibilitySet;
+use crate::world::hex::{Hex, HexLayout};
+use crate::world::iso::{iso_to_screen, screen_to_iso, StaggeredIso};
+use crate::world::physics::{aabb_overlap, CollisionLayer, Solid2D};
+use crate::world::shape::{shape_overlap, Shape2D};
 use glam::Vec2;
+use std::ops::Range;
 
+/// A grid of tiles, stored in square chunks of [`TileMap::CHUNK_SIZE`] cells
+/// so that empty regions cost nothing and drawing and collision only visit
+/// the chunks they touch.
 pub struct TileMap {
     pub width: usize,
     pub height: usize,
     pub tile_size: f32,
-    cells: Vec<Option<usize>>,
+    /// World position of the bottom-left corner of cell `(0, 0)`, or of its
+    /// centre for layouts other than [`GridLayout::Square`].
+    pub origin: Vec2,
+    pub layout: GridLayout,
+    pub layer: CollisionLayer,
+    /// Sprite z-order the tiles draw at.
+    pub z_order: i32,
+    /// How far the map scrolls per unit of camera movement: `(1, 1)` moves
+    /// with the world, smaller values lag behind like a distant background
+    /// and `(0, 0)` stays fixed on screen. Only drawing is affected.
+    pub parallax: Vec2,
+    /// The elevation level the map's tiles stand on, for
+    /// [`IsoRenderer`](crate::world::iso_render::IsoRenderer).
+    pub elevation: f32,
+    chunks_wide: usize,
+    chunks: Vec<Option<Box<TileChunk>>>,
     tiles: Vec<TileDef>,
 }
 
+/// One chunk's cells, dropped again once every cell is cleared.
+struct TileChunk {
+    cells: [Option<usize>; TileMap::CHUNK_CELLS],
+    filled: usize,
+    /// Cells whose tile collides, or whose tile was not defined when set.
+    solid: usize,
+}
+
+impl TileChunk {
+    fn empty() -> Self {
+        Self {
+            cells: [None; TileMap::CHUNK_CELLS],
+            filled: 0,
+            solid: 0,
+        }
+    }
+}
+
+/// The collider of a tile within its cell, used when [`TileDef::collides`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TileShape {
+    /// The whole cell.
+    Full,
+    /// Part of the cell as `[x, y, width, height]` fractions of it, measured
+    /// from the bottom-left corner.
+    Rect([f32; 4]),
+    /// A floor rising from the bottom edge to `left` and `right`, fractions of
+    /// the cell height at either side. Two tiles with `0.0..0.5` and
+    /// `0.5..1.0` make a shallow ramp.
+    Slope { left: f32, right: f32 },
+    /// A drop-through ledge filling the cell that only stops bodies landing
+    /// on its top.
+    OneWay,
+}
+
+impl TileShape {
+    pub const HALF_BOTTOM: Self = Self::Rect([0.0, 0.0, 1.0, 0.5]);
+    pub const HALF_TOP: Self = Self::Rect([0.0, 0.5, 1.0, 0.5]);
+    pub const HALF_LEFT: Self = Self::Rect([0.0, 0.0, 0.5, 1.0]);
+    pub const HALF_RIGHT: Self = Self::Rect([0.5, 0.0, 0.5, 1.0]);
+    /// A 45° ramp rising to the right.
+    pub const SLOPE_UP: Self = Self::Slope {
+        left: 0.0,
+        right: 1.0,
+    };
+    /// A 45° ramp falling to the right.
+    pub const SLOPE_DOWN: Self = Self::Slope {
+        left: 1.0,
+        right: 0.0,
+    };
+
+    /// The shape's bounding box inside `cell`.
+    pub fn bounds(&self, cell: Rect) -> Rect {
+        match *self {
+            Self::Full | Self::OneWay => cell,
+            Self::Rect([x, y, w, h]) => Rect::new(
+                cell.x + x * cell.width,
+                cell.y + y * cell.height,
+                w * cell.width,
+                h * cell.height,
+            ),
+            Self::Slope { left, right } => Rect::new(
+                cell.x,
+                cell.y,
+                cell.width,
+                left.max(right).clamp(0.0, 1.0) * cell.height,
+            ),
+        }
+    }
+
+    /// The shape as a [`Solid2D`] occupying `cell`.
+    pub fn solid(&self, cell: Rect) -> Solid2D {
+        match *self {
+            Self::Full | Self::Rect(_) => Solid2D::solid(self.bounds(cell)),
+            Self::Slope { left, right } => {
+                Solid2D::slope(cell, left * cell.height, right * cell.height)
+            }
+            Self::OneWay => Solid2D::one_way(cell),
+        }
+    }
+
+    /// The push that moves `rect` out of this shape placed in `cell`.
+    ///
+    /// Without a velocity to go on, slopes lift any body standing over them
+    /// onto the surface under its centre, and one-way cells only lift a body
+    /// whose feet are in the top quarter of the cell. Bodies that need exact
+    /// landings should step against [`TileMap::solids_in`] instead.
+    pub fn push_out(&self, cell: Rect, rect: &Rect) -> Option<Vec2> {
+        match *self {
+            Self::Full | Self::Rect(_) => aabb_overlap(rect, &self.bounds(cell)),
+            Self::Slope { left, right } => {
+                let bounds = self.bounds(cell);
+                if !rect.overlaps(&bounds) {
+                    return None;
+                }
+                if rect.bottom() < cell.bottom() {
+                    return aabb_overlap(rect, &bounds);
+                }
+                let t = (rect.center().x.clamp(cell.left(), cell.right()) - cell.x) / cell.width;
+                let floor = cell.y + (left + (right - left) * t) * cell.height;
+                (rect.bottom() < floor).then(|| Vec2::new(0.0, floor - rect.bottom()))
+            }
+            Self::OneWay => {
+                let top = cell.top();
+                let landing = rect.bottom() >= top - cell.height * 0.25;
+                (landing && rect.overlaps(&cell)).then(|| Vec2::new(0.0, top - rect.bottom()))
+            }
+        }
+    }
+}
+
+/// How a [`TileMap`]'s cells are laid out in the world.
+///
+/// Square maps keep `origin` at the bottom-left corner of cell `(0, 0)`, with
+/// rows running up. The other layouts follow [`iso_to_screen`]: `origin` is
+/// the centre of cell `(0, 0)` and rows run down the screen, as they do in
+/// Tiled's isometric, staggered and hexagonal maps. Their cells collide as
+/// their whole outline; [`TileShape`]s only apply to square cells.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum GridLayout {
+    /// Square cells of the map's `tile_size`.
+    #[default]
+    Square,
+    /// Diamond tiles `width` by `height`, columns running down to the right
+    /// and rows down to the left.
+    Diamond { width: f32, height: f32 },
+    /// Diamond tiles in zig-zag rows.
+    Staggered(StaggeredIso),
+    /// Hexes, addressed by their offset coordinates (see [`Hex::to_offset`]).
+    Hex(HexLayout),
+}
+
+impl GridLayout {
+    /// The centre of a cell, relative to the map origin.
+    fn center(&self, col: i32, row: i32, tile_size: f32) -> Vec2 {
+        match self {
+            Self::Square => Vec2::new(col as f32 + 0.5, row as f32 + 0.5) * tile_size,
+            Self::Diamond { width, height } => iso_to_screen(col, row, *width, *height),
+            Self::Staggered(grid) => grid.to_screen(col, row),
+            Self::Hex(layout) => layout.offset_to_world(col, row),
+        }
+    }
+
+    /// The size of a cell's bounding box.
+    fn cell_size(&self, tile_size: f32) -> Vec2 {
+        match self {
+            Self::Square => Vec2::splat(tile_size),
+            Self::Diamond { width, height } => Vec2::new(*width, *height),
+            Self::Staggered(grid) => Vec2::new(grid.tile_width, grid.tile_height),
+            Self::Hex(layout) => layout.cell_size(),
+        }
+    }
+
+    /// The cell containing a point relative to the map origin, which may lie
+    /// off the map.
+    fn cell_at(&self, local: Vec2, tile_size: f32) -> (i32, i32) {
+        match self {
+            Self::Square => {
+                let cell = (local / tile_size).floor();
+                (cell.x as i32, cell.y as i32)
+            }
+            Self::Diamond { width, height } => screen_to_iso(local, *width, *height),
+            Self::Staggered(grid) => grid.from_screen(local),
+            Self::Hex(layout) => layout.world_to_offset(local),
+        }
+    }
+
+    /// A cell's corners relative to the map origin, counter-clockwise.
+    fn outline(&self, col: i32, row: i32, tile_size: f32) -> Vec<Vec2> {
+        if let Self::Hex(layout) = self {
+            let hex = Hex::from_offset(col, row, layout.orientation, layout.stagger);
+            return layout.corners(hex).to_vec();
+        }
+        let center = self.center(col, row, tile_size);
+        let half = self.cell_size(tile_size) / 2.0;
+        let corners: [(f32, f32); 4] = match self {
+            Self::Square => [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)],
+            _ => [(0.0, -1.0), (1.0, 0.0), (0.0, 1.0), (-1.0, 0.0)],
+        };
+        corners
+            .iter()
+            .map(|&(x, y)| center + Vec2::new(x, y) * half)
+            .collect()
+    }
+}
+
 #[derive(Clone)]
 pub struct TileDef {
     pub texture: TextureId,
     pub color: Color,
 
     pub uv_rect: [f32; 4],
+    pub flip_x: bool,
+    pub flip_y: bool,
+    /// Counter-clockwise turn about the cell centre, in radians.
+    pub rotation: f32,
+    /// Whether cells using this tile block bodies and casts. Decorative tiles
+    /// turn this off.
+    pub collides: bool,
+    /// The part of the cell that collides.
+    pub shape: TileShape,
+    /// Whether cells using this tile block sight for
+    /// [`FieldOfView`](crate::world::fov::FieldOfView) and
+    /// [`line_of_sight`](crate::world::fov::line_of_sight). Off by default.
+    pub opaque: bool,
+    /// Elevation levels the tile rises above its map, for
+    /// [`IsoRenderer`](crate::world::iso_render::IsoRenderer): 0 for floor,
+    /// 1 for a wall a level high.
+    pub height: f32,
 }
 
 impl TileDef {
     pub fn solid(texture: TextureId) -> Self {
-        Self {
-            texture,
-            color: Color::WHITE,
-            uv_rect: [0.0, 0.0, 1.0, 1.0],
-        }
+        Self::colored(texture, Color::WHITE)
     }
 
     pub fn colored(texture: TextureId, color: Color) -> Self {
@@ -32,6 +256,13 @@ impl TileDef {
             texture,
             color,
             uv_rect: [0.0, 0.0, 1.0, 1.0],
+            flip_x: false,
+            flip_y: false,
+            rotation: 0.0,
+            collides: true,
+            shape: TileShape::Full,
+            opaque: false,
+            height: 0.0,
         }
     }
 
@@ -39,19 +270,95 @@ impl TileDef {
         self.uv_rect = uv;
         self
     }
+
+    pub fn with_flip(mut self, flip_x: bool, flip_y: bool) -> Self {
+        self.flip_x = flip_x;
+        self.flip_y = flip_y;
+        self
+    }
+
+    pub fn with_rotation(mut self, radians: f32) -> Self {
+        self.rotation = radians;
+        self
+    }
+
+    pub fn with_collision(mut self, collides: bool) -> Self {
+        self.collides = collides;
+        self
+    }
+
+    pub fn with_shape(mut self, shape: TileShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn with_opaque(mut self, opaque: bool) -> Self {
+        self.opaque = opaque;
+        self
+    }
+
+    pub fn with_height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
 }
 
 impl TileMap {
+    /// Cells along each side of a storage chunk.
+    pub const CHUNK_SIZE: usize = 16;
+    const CHUNK_CELLS: usize = Self::CHUNK_SIZE * Self::CHUNK_SIZE;
+
     pub fn new(width: usize, height: usize, tile_size: f32) -> Self {
+        let chunks_wide = width.div_ceil(Self::CHUNK_SIZE);
+        let chunks_high = height.div_ceil(Self::CHUNK_SIZE);
         Self {
             width,
             height,
             tile_size,
-            cells: vec![None; width * height],
+            origin: Vec2::ZERO,
+            layout: GridLayout::Square,
+            layer: CollisionLayer::default(),
+            z_order: 0,
+            parallax: Vec2::ONE,
+            elevation: 0.0,
+            chunks_wide,
+            chunks: std::iter::repeat_with(|| None)
+                .take(chunks_wide * chunks_high)
+                .collect(),
             tiles: Vec::new(),
         }
     }
 
+    pub fn with_origin(mut self, origin: Vec2) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    pub fn with_layout(mut self, layout: GridLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn with_layer(mut self, layer: CollisionLayer) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    pub fn with_z_order(mut self, z_order: i32) -> Self {
+        self.z_order = z_order;
+        self
+    }
+
+    pub fn with_parallax(mut self, parallax: Vec2) -> Self {
+        self.parallax = parallax;
+        self
+    }
+
+    pub fn with_elevation(mut self, elevation: f32) -> Self {
+        self.elevation = elevation;
+        self
+    }
+
     pub fn add_tile(&mut self, def: TileDef) -> usize {
         let id = self.tiles.len();
         self.tiles.push(def);
@@ -59,66 +366,267 @@ impl TileMap {
     }
 
     pub fn set(&mut self, col: usize, row: usize, tile: Option<usize>) {
-        if col < self.width && row < self.height {
-            self.cells[row * self.width + col] = tile;
+        if col >= self.width || row >= self.height {
+            return;
+        }
+        let index = self.chunk_index(col, row);
+        let slot = &mut self.chunks[index];
+        if slot.is_none() && tile.is_none() {
+            return;
+        }
+        let chunk = slot.get_or_insert_with(|| Box::new(TileChunk::empty()));
+        let old = std::mem::replace(&mut chunk.cells[Self::cell_index(col, row)], tile);
+        let collides = |id: Option<usize>| {
+            usize::from(id.is_some_and(|id| self.tiles.get(id).is_none_or(|def| def.collides)))
+        };
+        chunk.filled = (chunk.filled + usize::from(tile.is_some())) - usize::from(old.is_some());
+        chunk.solid = (chunk.solid + collides(tile)).saturating_sub(collides(old));
+        if chunk.filled == 0 {
+            *slot = None;
         }
     }
 
     pub fn get(&self, col: usize, row: usize) -> Option<usize> {
         if col < self.width && row < self.height {
-            self.cells[row * self.width + col]
+            self.chunks[self.chunk_index(col, row)]
+                .as_ref()
+                .and_then(|chunk| chunk.cells[Self::cell_index(col, row)])
         } else {
             None
         }
     }
 
+    /// Clear every cell, keeping the tile definitions.
+    pub fn clear(&mut self) {
+        self.chunks.iter_mut().for_each(|chunk| *chunk = None);
+    }
+
+    /// How many chunks hold at least one tile.
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.iter().filter(|chunk| chunk.is_some()).count()
+    }
+
+    pub fn tile(&self, id: usize) -> Option<&TileDef> {
+        self.tiles.get(id)
+    }
+
+    /// The tile at a cell, but only if that tile collides.
+    pub fn solid_tile(&self, col: usize, row: usize) -> Option<usize> {
+        self.get(col, row)
+            .filter(|&id| self.tiles.get(id).is_none_or(|def| def.collides))
+    }
+
+    /// Whether the cell's tile blocks sight.
+    pub fn is_opaque(&self, col: usize, row: usize) -> bool {
+        self.get(col, row)
+            .is_some_and(|id| self.tiles.get(id).is_some_and(|def| def.opaque))
+    }
+
+    /// The cell containing a world position.
+    pub fn cell_at(&self, point: Vec2) -> Option<(usize, usize)> {
+        let (col, row) = self.layout.cell_at(point - self.origin, self.tile_size);
+        let on_map =
+            (0..self.width as i32).contains(&col) && (0..self.height as i32).contains(&row);
+        on_map.then_some((col as usize, row as usize))
+    }
+
+    /// The collider of the cell's tile, if it has one.
+    pub fn tile_shape(&self, col: usize, row: usize) -> Option<TileShape> {
+        let id = self.solid_tile(col, row)?;
+        Some(self.tiles.get(id).map_or(TileShape::Full, |def| def.shape))
+    }
+
+    pub fn cell_center(&self, col: usize, row: usize) -> Vec2 {
+        self.origin + self.layout.center(col as i32, row as i32, self.tile_size)
+    }
+
+    /// The bottom-left corner of the cell's bounding box.
     pub fn cell_position(&self, col: usize, row: usize) -> Vec2 {
-        Vec2::new(col as f32 * self.tile_size, row as f32 * self.tile_size)
+        match self.layout {
+            GridLayout::Square => {
+                self.origin + Vec2::new(col as f32 * self.tile_size, row as f32 * self.tile_size)
+            }
+            _ => self.cell_center(col, row) - self.layout.cell_size(self.tile_size) / 2.0,
+        }
+    }
+
+    /// The cell's bounding box.
+    pub fn cell_rect(&self, col: usize, row: usize) -> Rect {
+        Rect::from_pos_size(
+            self.cell_position(col, row),
+            self.layout.cell_size(self.tile_size),
+        )
+    }
+
+    /// The cell's corners in world space, counter-clockwise.
+    pub fn cell_outline(&self, col: usize, row: usize) -> Vec<Vec2> {
+        let outline = self.layout.outline(col as i32, row as i32, self.tile_size);
+        outline.into_iter().map(|p| self.origin + p).collect()
+    }
+
+    /// The cell's collider as a world-space [`Solid2D`] on the map's layer.
+    pub fn cell_solid(&self, col: usize, row: usize) -> Option<Solid2D> {
+        let shape = self.tile_shape(col, row)?;
+        let solid = match self.layout {
+            GridLayout::Square => shape.solid(self.cell_rect(col, row)),
+            _ => {
+                Solid2D::shaped(self.cell_shape(col, row)).with_one_way(shape == TileShape::OneWay)
+            }
+        };
+        Some(solid.with_layer(self.layer))
+    }
+
+    /// The cells on the map sharing an edge with a cell.
+    pub fn neighbours(&self, col: usize, row: usize) -> Vec<(usize, usize)> {
+        let (col, row) = (col as i32, row as i32);
+        let cells: Vec<(i32, i32)> = match self.layout {
+            GridLayout::Square | GridLayout::Diamond { .. } => {
+                vec![
+                    (col + 1, row),
+                    (col, row + 1),
+                    (col - 1, row),
+                    (col, row - 1),
+                ]
+            }
+            GridLayout::Staggered(grid) => grid.neighbours(col, row).to_vec(),
+            GridLayout::Hex(layout) => {
+                let hex = Hex::from_offset(col, row, layout.orientation, layout.stagger);
+                hex.neighbours()
+                    .iter()
+                    .map(|n| n.to_offset(layout.orientation, layout.stagger))
+                    .collect()
+            }
+        };
+        cells
+            .into_iter()
+            .filter(|&(c, r)| c >= 0 && r >= 0 && c < self.width as i32 && r < self.height as i32)
+            .map(|(c, r)| (c as usize, r as usize))
+            .collect()
+    }
+
+    /// The world-space rect covering every cell.
+    pub fn bounds(&self) -> Rect {
+        if self.layout == GridLayout::Square || self.width == 0 || self.height == 0 {
+            return Rect::new(
+                self.origin.x,
+                self.origin.y,
+                self.width as f32 * self.tile_size,
+                self.height as f32 * self.tile_size,
+            );
+        }
+        // The extreme cells all sit on the map's edge.
+        let (w, h) = (self.width - 1, self.height - 1);
+        let edge = (0..=w)
+            .flat_map(|col| [(col, 0), (col, h)])
+            .chain((0..=h).flat_map(|row| [(0, row), (w, row)]));
+        let (mut min, mut max) = (Vec2::INFINITY, Vec2::NEG_INFINITY);
+        for (col, row) in edge {
+            let rect = self.cell_rect(col, row);
+            min = min.min(Vec2::new(rect.left(), rect.bottom()));
+            max = max.max(Vec2::new(rect.right(), rect.top()));
+        }
+        Rect::from_pos_size(min, max - min)
     }
 
     pub fn world_width(&self) -> f32 {
-        self.width as f32 * self.tile_size
+        self.bounds().width
     }
 
     pub fn world_height(&self) -> f32 {
-        self.height as f32 * self.tile_size
+        self.bounds().height
     }
 
-    pub fn collide_rect(&self, rect: &crate::math::Rect) -> Option<Vec2> {
-        use super::physics::aabb_overlap;
-        use crate::math::rect::Rect;
+    /// Full-cell colliding tiles as rects, with horizontal runs merged.
+    /// Shaped tiles are left out; [`TileMap::solids_in`] covers them. Only
+    /// square cells are rects, so other layouts return none.
+    pub fn solid_rects(&self) -> Vec<Rect> {
+        let mut rects = Vec::new();
+        if self.layout != GridLayout::Square {
+            return rects;
+        }
+        for row in 0..self.height {
+            let mut col = 0;
+            while col < self.width {
+                if self.tile_shape(col, row) != Some(TileShape::Full) {
+                    col += 1;
+                    continue;
+                }
+                let start = col;
+                while col < self.width && self.tile_shape(col, row) == Some(TileShape::Full) {
+                    col += 1;
+                }
+                rects.push(Rect::new(
+                    self.origin.x + start as f32 * self.tile_size,
+                    self.origin.y + row as f32 * self.tile_size,
+                    (col - start) as f32 * self.tile_size,
+                    self.tile_size,
+                ));
+            }
+        }
+        rects
+    }
 
-        let col_min = ((rect.x / self.tile_size).floor() as isize).max(0) as usize;
-        let col_max = (((rect.x + rect.width) / self.tile_size).ceil() as usize).min(self.width);
-        let row_min = ((rect.y / self.tile_size).floor() as isize).max(0) as usize;
-        let row_max = (((rect.y + rect.height) / self.tile_size).ceil() as usize).min(self.height);
+    /// Colliders for every colliding cell overlapping `area`, for bodies that
+    /// step against [`Solid2D`]s rather than the grid. Runs of full and of
+    /// one-way cells along a row merge into one solid each.
+    pub fn solids_in(&self, area: Rect) -> Vec<Solid2D> {
+        let (cols, rows) = self.cell_range(area);
+        let mut solids: Vec<Solid2D> = Vec::new();
+        if self.layout != GridLayout::Square {
+            self.visit_cells(cols, rows, true, |col, row, _| {
+                if self.cell_rect(col, row).overlaps(&area) {
+                    solids.extend(self.cell_solid(col, row));
+                }
+            });
+            return solids;
+        }
+        // The run the last solid belongs to, so the next cell can extend it.
+        let mut run: Option<(usize, usize, TileShape)> = None;
+        self.visit_cells(cols, rows, true, |col, row, id| {
+            let shape = self.tiles.get(id).map_or(TileShape::Full, |def| def.shape);
+            let merges = matches!(shape, TileShape::Full | TileShape::OneWay);
+            if merges && run == Some((col, row, shape)) {
+                if let Some(last) = solids.last_mut() {
+                    last.rect.width += self.tile_size;
+                }
+            } else {
+                solids.push(shape.solid(self.cell_rect(col, row)).with_layer(self.layer));
+            }
+            run = merges.then_some((col + 1, row, shape));
+        });
+        solids
+    }
 
+    /// Colliders for the whole map; see [`TileMap::solids_in`].
+    pub fn solids(&self) -> Vec<Solid2D> {
+        self.solids_in(self.bounds())
+    }
+
+    /// The summed push that moves `rect` out of the colliding cells it
+    /// overlaps, honouring each tile's [`TileShape`]. Only chunks under the
+    /// rect are visited.
+    pub fn collide_rect(&self, rect: &Rect) -> Option<Vec2> {
+        let (cols, rows) = self.cell_range(*rect);
         let mut total_mtv = Vec2::ZERO;
         let mut collided = false;
 
-        for row in row_min..row_max {
-            for col in col_min..col_max {
-                if self.cells[row * self.width + col].is_some() {
-                    let tile_rect = Rect::new(
-                        col as f32 * self.tile_size,
-                        row as f32 * self.tile_size,
-                        self.tile_size,
-                        self.tile_size,
-                    );
-
-                    let adjusted = Rect::new(
-                        rect.x + total_mtv.x,
-                        rect.y + total_mtv.y,
-                        rect.width,
-                        rect.height,
-                    );
-                    if let Some(mtv) = aabb_overlap(&adjusted, &tile_rect) {
-                        total_mtv += mtv;
-                        collided = true;
-                    }
-                }
+        self.visit_cells(cols, rows, true, |col, row, id| {
+            let shape = self.tiles.get(id).map_or(TileShape::Full, |def| def.shape);
+            let adjusted = Rect::new(
+                rect.x + total_mtv.x,
+                rect.y + total_mtv.y,
+                rect.width,
+                rect.height,
+            );
+            let mtv = match self.layout {
+                GridLayout::Square => shape.push_out(self.cell_rect(col, row), &adjusted),
+                _ => shape_overlap(&Shape2D::Aabb(adjusted), &self.cell_shape(col, row)),
+            };
+            if let Some(mtv) = mtv {
+                total_mtv += mtv;
+                collided = true;
             }
-        }
+        });
 
         if collided {
             Some(total_mtv)
@@ -127,37 +635,512 @@ impl TileMap {
         }
     }
 
+    /// Draw the tiles the frame's camera can see.
     pub fn draw(&self, frame: &mut Frame) {
-        let cam = &frame.camera;
-
-        let half_w = 600.0;
-        let half_h = 400.0;
-        let left = ((cam.position.x - half_w) / self.tile_size)
-            .floor()
-            .max(0.0) as usize;
-        let right = ((cam.position.x + half_w) / self.tile_size).ceil() as usize;
-        let bottom = ((cam.position.y - half_h) / self.tile_size)
-            .floor()
-            .max(0.0) as usize;
-        let top = ((cam.position.y + half_h) / self.tile_size).ceil() as usize;
-
-        let right = right.min(self.width);
-        let top = top.min(self.height);
-
-        let size = Vec2::new(self.tile_size, self.tile_size);
-
-        for row in bottom..top {
-            for col in left..right {
-                if let Some(tile_id) = self.cells[row * self.width + col] {
-                    let def = &self.tiles[tile_id];
-                    let pos = Vec2::new(col as f32 * self.tile_size, row as f32 * self.tile_size);
-                    frame.draw_sprite(
-                        DrawParams::new(def.texture, pos, size)
-                            .with_color(def.color)
-                            .with_uv_rect(def.uv_rect),
-                    );
+        let view = frame.view_rect();
+        self.draw_view(frame, view);
+    }
+
+    /// Draw the tiles inside `view`, a world-space rect as the camera sees
+    /// it. Parallax shifts the map relative to the camera before culling.
+    pub fn draw_view(&self, frame: &mut Frame, view: Rect) {
+        self.draw_tinted(frame, view, |_, _| Some(Color::WHITE));
+    }
+
+    /// Draw the tiles the camera can see as fog of war: cells in `visible`
+    /// as usual, cells only in `explored` tinted by `dim`, and nothing else.
+    pub fn draw_fog(
+        &self,
+        frame: &mut Frame,
+        visible: &VisibilitySet,
+        explored: &VisibilitySet,
+        dim: Color,
+    ) {
+        let view = frame.view_rect();
+        self.draw_tinted(frame, view, |col, row| {
+            if visible.contains(col, row) {
+                Some(Color::WHITE)
+            } else {
+                explored.contains(col, row).then_some(dim)
+            }
+        });
+    }
+
+    /// Draw the tiles inside `view`, each multiplied by the tint `tint`
+    /// picks for its cell, or skipped when it picks none.
+    fn draw_tinted(
+        &self,
+        frame: &mut Frame,
+        view: Rect,
+        tint: impl Fn(usize, usize) -> Option<Color>,
+    ) {
+        let shift = frame.camera.position * (Vec2::ONE - self.parallax);
+        let (cols, rows) = self.cell_range(Rect::new(
+            view.x - shift.x,
+            view.y - shift.y,
+            view.width,
+            view.height,
+        ));
+        let size = self.layout.cell_size(self.tile_size);
+
+        self.visit_cells(cols, rows, false, |col, row, tile_id| {
+            let Some(def) = self.tiles.get(tile_id) else {
+                return;
+            };
+            let Some(tint) = tint(col, row) else {
+                return;
+            };
+            let color = Color::new(
+                def.color.r * tint.r,
+                def.color.g * tint.g,
+                def.color.b * tint.b,
+                def.color.a * tint.a,
+            );
+            let center = self.cell_center(col, row) + shift;
+            frame.draw_sprite(
+                DrawParams::new(def.texture, center, size)
+                    .with_centered_origin()
+                    .with_rotation(def.rotation)
+                    .with_color(color)
+                    .with_uv_rect(def.uv_rect)
+                    .with_flip_x(def.flip_x)
+                    .with_flip_y(def.flip_y)
+                    .with_z_order(self.z_order),
+            );
+        });
+    }
+
+    fn chunk_index(&self, col: usize, row: usize) -> usize {
+        (row / Self::CHUNK_SIZE) * self.chunks_wide + col / Self::CHUNK_SIZE
+    }
+
+    fn cell_index(col: usize, row: usize) -> usize {
+        (row % Self::CHUNK_SIZE) * Self::CHUNK_SIZE + col % Self::CHUNK_SIZE
+    }
+
+    /// The cell's outline as a world-space collider.
+    fn cell_shape(&self, col: usize, row: usize) -> Shape2D {
+        Shape2D::polygon(&self.cell_outline(col, row))
+            .unwrap_or_else(|| Shape2D::Aabb(self.cell_rect(col, row)))
+    }
+
+    /// The cells a world-space rect touches, clamped to the map. Layouts
+    /// other than square get every cell that might touch it.
+    pub(crate) fn cell_range(&self, rect: Rect) -> (Range<usize>, Range<usize>) {
+        if self.layout != GridLayout::Square {
+            // Row and column numbers grow steadily across the plane, so the
+            // rect's corners bound them, give or take a cell for the zig-zag.
+            let (mut min, mut max) = ((i32::MAX, i32::MAX), (i32::MIN, i32::MIN));
+            for x in [rect.left(), rect.right()] {
+                for y in [rect.bottom(), rect.top()] {
+                    let local = Vec2::new(x, y) - self.origin;
+                    let (col, row) = self.layout.cell_at(local, self.tile_size);
+                    min = (min.0.min(col), min.1.min(row));
+                    max = (max.0.max(col), max.1.max(row));
+                }
+            }
+            let clamp = |value: i32, len: usize| value.clamp(0, len as i32) as usize;
+            return (
+                clamp(min.0 - 1, self.width)..clamp(max.0 + 2, self.width),
+                clamp(min.1 - 1, self.height)..clamp(max.1 + 2, self.height),
+            );
+        }
+        let (x, y) = (rect.x - self.origin.x, rect.y - self.origin.y);
+        let col_min = (x / self.tile_size).floor().max(0.0) as usize;
+        let col_max =
+            (((x + rect.width) / self.tile_size).ceil().max(0.0) as usize).min(self.width);
+        let row_min = (y / self.tile_size).floor().max(0.0) as usize;
+        let row_max =
+            (((y + rect.height) / self.tile_size).ceil().max(0.0) as usize).min(self.height);
+        (col_min..col_max, row_min..row_max)
+    }
+
+    /// Call `f(col, row, tile)` for each occupied cell in the ranges, row by
+    /// row, skipping empty chunks and, when `solid_only`, chunks and cells
+    /// that do not collide.
+    pub(crate) fn visit_cells(
+        &self,
+        cols: Range<usize>,
+        rows: Range<usize>,
+        solid_only: bool,
+        mut f: impl FnMut(usize, usize, usize),
+    ) {
+        for row in rows {
+            let mut col = cols.start;
+            while col < cols.end {
+                let span_end = ((col / Self::CHUNK_SIZE + 1) * Self::CHUNK_SIZE).min(cols.end);
+                let chunk = self.chunks[self.chunk_index(col, row)]
+                    .as_ref()
+                    .filter(|chunk| !solid_only || chunk.solid > 0);
+                if let Some(chunk) = chunk {
+                    for c in col..span_end {
+                        let Some(id) = chunk.cells[Self::cell_index(c, row)] else {
+                            continue;
+                        };
+                        if !solid_only || self.tiles.get(id).is_none_or(|def| def.collides) {
+                            f(c, row, id);
+                        }
+                    }
                 }
+                col = span_end;
             }
         }
     }
 }
+
+/// One named layer of a [`LayeredTileMap`].
+pub struct TileLayer {
+    pub name: String,
+    pub map: TileMap,
+    pub visible: bool,
+    /// Whether the layer takes part in the stack's collision queries.
+    pub collides: bool,
+}
+
+/// Several [`TileMap`]s over the same grid, sharing one tile palette: a
+/// parallax backdrop, the terrain bodies collide with, foreground decoration.
+/// Each layer keeps its own z-order, parallax and visibility.
+pub struct LayeredTileMap {
+    width: usize,
+    height: usize,
+    tile_size: f32,
+    origin: Vec2,
+    layout: GridLayout,
+    tiles: Vec<TileDef>,
+    layers: Vec<TileLayer>,
+}
+
+impl LayeredTileMap {
+    pub fn new(width: usize, height: usize, tile_size: f32) -> Self {
+        Self {
+            width,
+            height,
+            tile_size,
+            origin: Vec2::ZERO,
+            layout: GridLayout::Square,
+            tiles: Vec::new(),
+            layers: Vec::new(),
+        }
+    }
+
+    pub fn with_origin(mut self, origin: Vec2) -> Self {
+        self.origin = origin;
+        for layer in &mut self.layers {
+            layer.map.origin = origin;
+        }
+        self
+    }
+
+    pub fn with_layout(mut self, layout: GridLayout) -> Self {
+        self.layout = layout;
+        for layer in &mut self.layers {
+            layer.map.layout = layout;
+        }
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn tile_size(&self) -> f32 {
+        self.tile_size
+    }
+
+    pub fn origin(&self) -> Vec2 {
+        self.origin
+    }
+
+    pub fn layout(&self) -> GridLayout {
+        self.layout
+    }
+
+    /// Add a tile to the shared palette; the id is valid in every layer.
+    pub fn add_tile(&mut self, def: TileDef) -> usize {
+        for layer in &mut self.layers {
+            layer.map.add_tile(def.clone());
+        }
+        let id = self.tiles.len();
+        self.tiles.push(def);
+        id
+    }
+
+    pub fn tile(&self, id: usize) -> Option<&TileDef> {
+        self.tiles.get(id)
+    }
+
+    /// Add an empty, visible, colliding layer drawn at `z_order`. A layer
+    /// that already has this name is replaced.
+    pub fn add_layer(&mut self, name: impl Into<String>, z_order: i32) -> &mut TileLayer {
+        let name = name.into();
+        self.layers.retain(|layer| layer.name != name);
+        let mut map = TileMap::new(self.width, self.height, self.tile_size)
+            .with_origin(self.origin)
+            .with_layout(self.layout)
+            .with_z_order(z_order);
+        for def in &self.tiles {
+            map.add_tile(def.clone());
+        }
+        self.layers.push(TileLayer {
+            name,
+            map,
+            visible: true,
+            collides: true,
+        });
+        self.layers.last_mut().unwrap()
+    }
+
+    pub fn remove_layer(&mut self, name: &str) -> Option<TileLayer> {
+        let index = self.layers.iter().position(|layer| layer.name == name)?;
+        Some(self.layers.remove(index))
+    }
+
+    pub fn layer(&self, name: &str) -> Option<&TileLayer> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut TileLayer> {
+        self.layers.iter_mut().find(|layer| layer.name == name)
+    }
+
+    /// Layers in the order they were added.
+    pub fn layers(&self) -> &[TileLayer] {
+        &self.layers
+    }
+
+    /// Set a cell on the named layer; unknown layers are ignored.
+    pub fn set(&mut self, layer: &str, col: usize, row: usize, tile: Option<usize>) {
+        if let Some(layer) = self.layer_mut(layer) {
+            layer.map.set(col, row, tile);
+        }
+    }
+
+    fn colliding(&self) -> impl Iterator<Item = &TileMap> {
+        self.layers
+            .iter()
+            .filter(|layer| layer.collides)
+            .map(|layer| &layer.map)
+    }
+
+    /// [`TileMap::collide_rect`] across every colliding layer.
+    pub fn collide_rect(&self, rect: &Rect) -> Option<Vec2> {
+        let mut total: Option<Vec2> = None;
+        for map in self.colliding() {
+            let offset = total.unwrap_or(Vec2::ZERO);
+            let moved = Rect::new(
+                rect.x + offset.x,
+                rect.y + offset.y,
+                rect.width,
+                rect.height,
+            );
+            if let Some(mtv) = map.collide_rect(&moved) {
+                total = Some(offset + mtv);
+            }
+        }
+        total
+    }
+
+    /// [`TileMap::solids_in`] across every colliding layer.
+    pub fn solids_in(&self, area: Rect) -> Vec<Solid2D> {
+        self.colliding()
+            .flat_map(|map| map.solids_in(area))
+            .collect()
+    }
+
+    /// Draw every visible layer; each sorts by its own z-order.
+    pub fn draw(&self, frame: &mut Frame) {
+        let view = frame.view_rect();
+        for layer in self.layers.iter().filter(|layer| layer.visible) {
+            layer.map.draw_view(frame, view);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::hex::HexOrientation;
+
+    fn map(width: usize, height: usize) -> (TileMap, usize) {
+        let mut map = TileMap::new(width, height, 10.0);
+        let wall = map.add_tile(TileDef::solid(TextureId(0)));
+        (map, wall)
+    }
+
+    #[test]
+    fn chunks_are_allocated_on_write_and_freed_when_empty() {
+        let (mut map, wall) = map(512, 512);
+        assert_eq!(map.chunk_count(), 0);
+        map.set(0, 0, Some(wall));
+        map.set(15, 15, Some(wall));
+        map.set(300, 400, Some(wall));
+        map.set(600, 0, Some(wall)); // out of bounds
+        assert_eq!(map.chunk_count(), 2);
+        assert_eq!(map.get(300, 400), Some(wall));
+        assert_eq!(map.get(301, 400), None);
+
+        map.set(300, 400, None);
+        map.set(299, 399, None); // clearing an empty chunk allocates nothing
+        assert_eq!(map.chunk_count(), 1);
+        map.clear();
+        assert_eq!(map.get(0, 0), None);
+        assert_eq!(map.chunk_count(), 0);
+    }
+
+    #[test]
+    fn tile_shapes_push_bodies_out() {
+        let (mut map, wall) = map(8, 4);
+        let half = map.add_tile(TileDef::solid(TextureId(0)).with_shape(TileShape::HALF_BOTTOM));
+        let ramp = map.add_tile(TileDef::solid(TextureId(0)).with_shape(TileShape::SLOPE_UP));
+        let ledge = map.add_tile(TileDef::solid(TextureId(0)).with_shape(TileShape::OneWay));
+        map.set(0, 0, Some(wall));
+        map.set(2, 0, Some(half));
+        map.set(4, 0, Some(ramp));
+        map.set(6, 1, Some(ledge));
+
+        // Sinking into the top half of a half tile is empty space.
+        assert_eq!(map.collide_rect(&Rect::new(20.0, 6.0, 10.0, 10.0)), None);
+        let push = map.collide_rect(&Rect::new(22.0, 3.0, 4.0, 10.0)).unwrap();
+        assert!((push.y - 2.0).abs() < 1e-4, "{push:?}");
+
+        // A body centred three quarters up the ramp stands on its surface.
+        let push = map.collide_rect(&Rect::new(45.0, 2.0, 5.0, 10.0)).unwrap();
+        assert!((push.y - 5.5).abs() < 1e-4, "{push:?}");
+
+        // One-way: lands from just above, passes when deep inside.
+        let push = map.collide_rect(&Rect::new(61.0, 19.0, 8.0, 8.0)).unwrap();
+        assert!((push.y - 1.0).abs() < 1e-4, "{push:?}");
+        assert_eq!(map.collide_rect(&Rect::new(61.0, 12.0, 8.0, 8.0)), None);
+
+        assert_eq!(map.tile_shape(6, 1), Some(TileShape::OneWay));
+        assert_eq!(map.solid_rects(), vec![Rect::new(0.0, 0.0, 10.0, 10.0)]);
+    }
+
+    #[test]
+    fn solids_merge_runs_and_only_cover_the_area() {
+        let (mut map, wall) = map(64, 4);
+        let ledge = map.add_tile(TileDef::solid(TextureId(0)).with_shape(TileShape::OneWay));
+        let decor = map.add_tile(TileDef::solid(TextureId(0)).with_collision(false));
+        for col in 10..20 {
+            map.set(col, 0, Some(wall));
+        }
+        for col in 20..23 {
+            map.set(col, 0, Some(ledge));
+        }
+        map.set(23, 0, Some(decor));
+        map.set(60, 0, Some(wall));
+
+        let solids = map.solids_in(Rect::new(95.0, 0.0, 200.0, 5.0));
+        assert_eq!(solids.len(), 2);
+        assert_eq!(solids[0].rect, Rect::new(100.0, 0.0, 100.0, 10.0));
+        assert!(!solids[0].one_way);
+        assert_eq!(solids[1].rect, Rect::new(200.0, 0.0, 30.0, 10.0));
+        assert!(solids[1].one_way);
+        assert_eq!(map.solids().len(), 3);
+    }
+
+    #[test]
+    fn hex_and_staggered_layouts_share_lookup_and_collision() {
+        let hex = HexLayout::new(HexOrientation::Pointy, 10.0);
+        let (map, wall) = map(6, 6);
+        let mut map = map.with_layout(GridLayout::Hex(hex));
+        map.set(3, 2, Some(wall));
+        for row in 0..6 {
+            for col in 0..6 {
+                assert_eq!(map.cell_at(map.cell_center(col, row)), Some((col, row)));
+            }
+        }
+        assert_eq!(map.neighbours(2, 2).len(), 6);
+        assert_eq!(map.neighbours(0, 0).len(), 2);
+        // Six columns plus the half cell odd rows stick out, by a hex's
+        // height plus five rows of three quarters of one.
+        let bounds = map.bounds();
+        assert!((bounds.width - 6.5 * 10.0 * 3f32.sqrt()).abs() < 1e-3);
+        assert!((bounds.height - 95.0).abs() < 1e-3);
+
+        let center = map.cell_center(3, 2);
+        let push = map
+            .collide_rect(&Rect::from_pos_size(
+                center - Vec2::new(11.0, 2.0),
+                Vec2::splat(4.0),
+            ))
+            .unwrap();
+        assert!(push.x < 0.0 && push.y.abs() < 1e-4);
+        let solids = map.solids();
+        assert_eq!(solids.len(), 1);
+        assert!(solids[0].shape.is_some());
+        assert!(map.solid_rects().is_empty());
+
+        let staggered = StaggeredIso::new(64.0, 32.0);
+        let map = TileMap::new(4, 4, 1.0).with_layout(GridLayout::Staggered(staggered));
+        for row in 0..4 {
+            for col in 0..4 {
+                let center = map.cell_center(col, row);
+                assert_eq!(map.cell_at(center + Vec2::new(0.0, 7.0)), Some((col, row)));
+            }
+        }
+        assert_eq!(map.cell_rect(0, 1), Rect::new(0.0, -32.0, 64.0, 32.0));
+        assert_eq!(map.neighbours(1, 1), vec![(2, 2), (1, 2), (1, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn drawing_culls_to_the_view_and_applies_parallax_and_z_order() {
+        let (map, wall) = map(512, 512);
+        let mut map = map.with_z_order(-5).with_parallax(Vec2::splat(0.5));
+        for row in 0..512 {
+            for col in 0..512 {
+                map.set(col, row, Some(wall));
+            }
+        }
+        let mut frame = Frame::new();
+        frame.camera.position = Vec2::new(200.0, 0.0);
+        map.draw_view(&mut frame, Rect::new(160.0, 0.0, 40.0, 20.0));
+
+        // Half parallax shifts the map by 100: view cols 6..10 see 4 x 2 cells.
+        assert_eq!(frame.sprites.len(), 8);
+        assert!(frame.sprites.iter().all(|sprite| sprite.z_order == -5));
+        let first = frame.sprites[0].position;
+        assert_eq!(first, Vec2::new(165.0, 5.0));
+    }
+
+    #[test]
+    fn layers_share_a_palette_and_only_colliding_layers_block() {
+        let mut layers = LayeredTileMap::new(16, 16, 10.0).with_origin(Vec2::new(-80.0, 0.0));
+        let wall = layers.add_tile(TileDef::solid(TextureId(0)));
+        layers.add_layer("terrain", 0);
+        let backdrop = layers.add_layer("backdrop", -10);
+        backdrop.collides = false;
+        backdrop.map.parallax = Vec2::splat(0.5);
+        let bush = layers.add_tile(TileDef::solid(TextureId(1)));
+
+        layers.set("backdrop", 0, 0, Some(bush));
+        assert_eq!(layers.collide_rect(&Rect::new(-78.0, 2.0, 4.0, 4.0)), None);
+        layers.set("terrain", 1, 0, Some(wall));
+        let push = layers
+            .collide_rect(&Rect::new(-70.0, 8.0, 10.0, 10.0))
+            .unwrap();
+        assert_eq!(push, Vec2::new(0.0, 2.0));
+        assert_eq!(
+            layers.solids_in(Rect::new(-80.0, 0.0, 160.0, 160.0)).len(),
+            1
+        );
+
+        assert_eq!(
+            layers
+                .layer("backdrop")
+                .unwrap()
+                .map
+                .tile(bush)
+                .unwrap()
+                .texture,
+            TextureId(1)
+        );
+        assert_eq!(layers.layers().len(), 2);
+        assert!(layers.remove_layer("backdrop").is_some());
+        assert!(layers.layer("backdrop").is_none());
+    }
+}
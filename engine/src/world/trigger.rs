@@ -1,7 +1,11 @@
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap};
+
+use glam::Vec2;
 
 use crate::math::rect::Rect;
 use crate::world::physics::CollisionLayer;
+use crate::world::shape::{shapes_overlap, Shape2D};
+use crate::world::spatial::{SpatialGrid2D, SpatialKey};
 
 pub type TriggerZoneId = usize;
 
@@ -12,6 +16,17 @@ pub struct TriggerZone {
     pub rect: Rect,
     pub layer: CollisionLayer,
     pub enabled: bool,
+    pub shape: Option<Shape2D>,
+    /// Seconds a body must stay inside before the zone fires for it; 0 fires
+    /// as it enters.
+    pub dwell: f32,
+    /// Seconds after firing before the zone can fire again, for any body.
+    pub cooldown: f32,
+    /// Disable the zone once it has fired.
+    pub once: bool,
+    /// Topic a firing is reported under; a zone on a scene node sends it to
+    /// the node's scripts.
+    pub topic: String,
 }
 
 impl TriggerZone {
@@ -20,6 +35,26 @@ impl TriggerZone {
             rect,
             layer: CollisionLayer::default(),
             enabled: true,
+            shape: None,
+            dwell: 0.0,
+            cooldown: 0.0,
+            once: false,
+            topic: "trigger".to_string(),
+        }
+    }
+
+    pub fn shaped(shape: Shape2D) -> Self {
+        let rect = shape.bounds();
+        Self {
+            shape: Some(shape.translated(-Vec2::new(rect.x, rect.y))),
+            ..Self::new(rect)
+        }
+    }
+
+    pub fn collider(&self) -> Shape2D {
+        match self.shape {
+            Some(shape) => shape.translated(Vec2::new(self.rect.x, self.rect.y)),
+            None => Shape2D::Aabb(self.rect),
         }
     }
 
@@ -27,6 +62,26 @@ impl TriggerZone {
         self.layer = layer;
         self
     }
+
+    pub fn with_dwell(mut self, seconds: f32) -> Self {
+        self.dwell = seconds;
+        self
+    }
+
+    pub fn with_cooldown(mut self, seconds: f32) -> Self {
+        self.cooldown = seconds;
+        self
+    }
+
+    pub fn with_once(mut self, once: bool) -> Self {
+        self.once = once;
+        self
+    }
+
+    pub fn with_topic(mut self, topic: impl Into<String>) -> Self {
+        self.topic = topic.into();
+        self
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -36,10 +91,29 @@ pub enum OverlapEvent {
     Exit,
 }
 
+/// A body's current stay in a zone.
+#[derive(Debug, Clone, Copy)]
+struct Visit {
+    /// The clock when it entered.
+    since: f64,
+    fired: bool,
+}
+
 pub struct TriggerSystem {
     zones: Vec<TriggerZone>,
-    prev_overlaps: Vec<HashSet<BodyId>>,
+    prev_overlaps: Vec<HashMap<BodyId, Visit>>,
+    /// Seconds of [`TriggerSystem::advance`] so far, in `f64` so a dwell or
+    /// cooldown of a fraction of a second still measures exactly after days
+    /// of play.
+    clock: f64,
+    /// When each zone's cooldown runs out.
+    ready_at: Vec<f64>,
+    fired: Vec<(TriggerZoneId, BodyId)>,
     current_events: BTreeMap<(TriggerZoneId, BodyId), OverlapEvent>,
+    broadphase: Option<SpatialGrid2D>,
+    dirty_zones: Vec<TriggerZoneId>,
+    overlaps: Vec<(TriggerZoneId, BodyId)>,
+    candidates: Vec<SpatialKey>,
 }
 
 impl TriggerSystem {
@@ -47,14 +121,49 @@ impl TriggerSystem {
         Self {
             zones: Vec::new(),
             prev_overlaps: Vec::new(),
+            clock: 0.0,
+            ready_at: Vec::new(),
+            fired: Vec::new(),
             current_events: BTreeMap::new(),
+            broadphase: None,
+            dirty_zones: Vec::new(),
+            overlaps: Vec::new(),
+            candidates: Vec::new(),
         }
     }
 
+    pub fn with_broadphase(cell_size: f32) -> Self {
+        let mut system = Self::new();
+        system.enable_broadphase(cell_size);
+        system
+    }
+
+    pub fn enable_broadphase(&mut self, cell_size: f32) {
+        let mut grid = SpatialGrid2D::new(cell_size);
+        for zone in &self.zones {
+            grid.insert(zone.rect);
+        }
+        self.broadphase = Some(grid);
+        self.dirty_zones.clear();
+    }
+
+    pub fn disable_broadphase(&mut self) {
+        self.broadphase = None;
+        self.dirty_zones.clear();
+    }
+
+    pub fn has_broadphase(&self) -> bool {
+        self.broadphase.is_some()
+    }
+
     pub fn add_zone(&mut self, zone: TriggerZone) -> TriggerZoneId {
         let id = self.zones.len();
+        if let Some(grid) = &mut self.broadphase {
+            grid.insert(zone.rect);
+        }
         self.zones.push(zone);
-        self.prev_overlaps.push(HashSet::new());
+        self.prev_overlaps.push(HashMap::new());
+        self.ready_at.push(0.0);
         id
     }
 
@@ -63,19 +172,51 @@ impl TriggerSystem {
     }
 
     pub fn zone_mut(&mut self, id: TriggerZoneId) -> &mut TriggerZone {
+        if self.broadphase.is_some() {
+            self.dirty_zones.push(id);
+        }
         &mut self.zones[id]
     }
 
+    pub fn set_zone_rect(&mut self, id: TriggerZoneId, rect: Rect) {
+        self.zones[id].rect = rect;
+        if let Some(grid) = &mut self.broadphase {
+            grid.update(id, rect);
+        }
+    }
+
     pub fn zone_count(&self) -> usize {
         self.zones.len()
     }
 
+    /// Let `dt` seconds pass for dwell times and cooldowns. Call once a frame
+    /// before [`TriggerSystem::tick`].
+    pub fn advance(&mut self, dt: f32) {
+        self.clock += f64::from(dt);
+    }
+
     pub fn tick(&mut self, bodies: &[(BodyId, Rect, CollisionLayer)]) {
+        self.tick_bodies(bodies);
+    }
+
+    pub fn tick_shapes(&mut self, bodies: &[(BodyId, Shape2D, CollisionLayer)]) {
+        self.tick_bodies(bodies);
+    }
+
+    fn tick_bodies<B: TriggerBody>(&mut self, bodies: &[B]) {
         self.current_events.clear();
 
-        for (zone_id, zone) in self.zones.iter().enumerate() {
+        if self.broadphase.is_some() {
+            self.collect_overlaps_indexed(bodies);
+        } else {
+            self.collect_overlaps(bodies);
+        }
+
+        self.fired.clear();
+        let mut cursor = 0;
+        for (zone_id, zone) in self.zones.iter_mut().enumerate() {
             if !zone.enabled {
-                for &body_id in &self.prev_overlaps[zone_id] {
+                for &body_id in self.prev_overlaps[zone_id].keys() {
                     self.current_events
                         .insert((zone_id, body_id), OverlapEvent::Exit);
                 }
@@ -83,25 +224,41 @@ impl TriggerSystem {
                 continue;
             }
 
-            let prev: Vec<BodyId> = self.prev_overlaps[zone_id].drain().collect();
+            let prev = std::mem::take(&mut self.prev_overlaps[zone_id]);
 
-            for &(body_id, ref body_rect, ref body_layer) in bodies {
-                if !zone.layer.interacts_with(body_layer) {
-                    continue;
-                }
-                if zone.rect.overlaps(body_rect) {
-                    let event = if prev.contains(&body_id) {
-                        OverlapEvent::Stay
-                    } else {
-                        OverlapEvent::Enter
-                    };
-                    self.current_events.insert((zone_id, body_id), event);
-                    self.prev_overlaps[zone_id].insert(body_id);
+            while cursor < self.overlaps.len() && self.overlaps[cursor].0 == zone_id {
+                let body_id = self.overlaps[cursor].1;
+                cursor += 1;
+                let (event, mut visit) = match prev.get(&body_id) {
+                    Some(&visit) => (OverlapEvent::Stay, visit),
+                    None => {
+                        let visit = Visit {
+                            since: self.clock,
+                            fired: false,
+                        };
+                        (OverlapEvent::Enter, visit)
+                    }
+                };
+                // Each visit fires once, as soon as it has lasted long enough
+                // and the zone is off cooldown.
+                if zone.enabled
+                    && !visit.fired
+                    && self.clock - visit.since >= f64::from(zone.dwell)
+                    && self.clock >= self.ready_at[zone_id]
+                {
+                    visit.fired = true;
+                    self.fired.push((zone_id, body_id));
+                    self.ready_at[zone_id] = self.clock + f64::from(zone.cooldown);
+                    if zone.once {
+                        zone.enabled = false;
+                    }
                 }
+                self.current_events.insert((zone_id, body_id), event);
+                self.prev_overlaps[zone_id].insert(body_id, visit);
             }
 
-            for &body_id in &prev {
-                if !self.prev_overlaps[zone_id].contains(&body_id) {
+            for &body_id in prev.keys() {
+                if !self.prev_overlaps[zone_id].contains_key(&body_id) {
                     self.current_events
                         .insert((zone_id, body_id), OverlapEvent::Exit);
                 }
@@ -109,6 +266,41 @@ impl TriggerSystem {
         }
     }
 
+    fn collect_overlaps<B: TriggerBody>(&mut self, bodies: &[B]) {
+        self.overlaps.clear();
+        for (zone_id, zone) in self.zones.iter().enumerate() {
+            if !zone.enabled {
+                continue;
+            }
+            for body in bodies {
+                if zone.layer.interacts_with(body.layer()) && body.overlaps(zone) {
+                    self.overlaps.push((zone_id, body.id()));
+                }
+            }
+        }
+    }
+
+    fn collect_overlaps_indexed<B: TriggerBody>(&mut self, bodies: &[B]) {
+        let Some(grid) = &mut self.broadphase else {
+            return;
+        };
+        for zone_id in self.dirty_zones.drain(..) {
+            grid.update(zone_id, self.zones[zone_id].rect);
+        }
+
+        self.overlaps.clear();
+        for body in bodies {
+            grid.query_into(&body.bounds(), &mut self.candidates);
+            for &zone_id in &self.candidates {
+                let zone = &self.zones[zone_id];
+                if zone.enabled && zone.layer.interacts_with(body.layer()) && body.overlaps(zone) {
+                    self.overlaps.push((zone_id, body.id()));
+                }
+            }
+        }
+        self.overlaps.sort_by_key(|&(zone_id, _)| zone_id);
+    }
+
     pub fn events(&self) -> impl Iterator<Item = (TriggerZoneId, BodyId, OverlapEvent)> + '_ {
         self.current_events
             .iter()
@@ -130,7 +322,18 @@ impl TriggerSystem {
     }
 
     pub fn overlapping(&self, zone_id: TriggerZoneId, body_id: BodyId) -> bool {
-        self.prev_overlaps[zone_id].contains(&body_id)
+        self.prev_overlaps[zone_id].contains_key(&body_id)
+    }
+
+    /// How long the body has been inside the zone, if it is.
+    pub fn dwell_time(&self, zone_id: TriggerZoneId, body_id: BodyId) -> Option<f32> {
+        let visit = self.prev_overlaps[zone_id].get(&body_id)?;
+        Some((self.clock - visit.since) as f32)
+    }
+
+    /// The zones that fired on the last tick, and the body each fired for.
+    pub fn fired(&self) -> impl Iterator<Item = (TriggerZoneId, BodyId)> + '_ {
+        self.fired.iter().copied()
     }
 
     pub fn event_for(&self, zone_id: TriggerZoneId, body_id: BodyId) -> Option<OverlapEvent> {
@@ -138,8 +341,223 @@ impl TriggerSystem {
     }
 }
 
+trait TriggerBody {
+    fn id(&self) -> BodyId;
+    fn bounds(&self) -> Rect;
+    fn layer(&self) -> &CollisionLayer;
+    fn overlaps(&self, zone: &TriggerZone) -> bool;
+}
+
+impl TriggerBody for (BodyId, Rect, CollisionLayer) {
+    fn id(&self) -> BodyId {
+        self.0
+    }
+
+    fn bounds(&self) -> Rect {
+        self.1
+    }
+
+    fn layer(&self) -> &CollisionLayer {
+        &self.2
+    }
+
+    fn overlaps(&self, zone: &TriggerZone) -> bool {
+        match zone.shape {
+            None => zone.rect.overlaps(&self.1),
+            Some(_) => {
+                zone.rect.overlaps(&self.1)
+                    && shapes_overlap(&zone.collider(), &Shape2D::Aabb(self.1))
+            }
+        }
+    }
+}
+
+impl TriggerBody for (BodyId, Shape2D, CollisionLayer) {
+    fn id(&self) -> BodyId {
+        self.0
+    }
+
+    fn bounds(&self) -> Rect {
+        self.1.bounds()
+    }
+
+    fn layer(&self) -> &CollisionLayer {
+        &self.2
+    }
+
+    fn overlaps(&self, zone: &TriggerZone) -> bool {
+        zone.rect.overlaps(&self.1.bounds()) && shapes_overlap(&zone.collider(), &self.1)
+    }
+}
+
 impl Default for TriggerSystem {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Rng;
+
+    fn random_rect(rng: &mut Rng, max_size: f32) -> Rect {
+        Rect::new(
+            rng.f32_range(-300.0, 300.0),
+            rng.f32_range(-300.0, 300.0),
+            rng.f32_range(2.0, max_size),
+            rng.f32_range(2.0, max_size),
+        )
+    }
+
+    #[test]
+    fn broadphase_tick_matches_brute_force_events() {
+        let mut rng = Rng::new(11);
+        let mut brute = TriggerSystem::new();
+        let mut indexed = TriggerSystem::with_broadphase(40.0);
+        for i in 0..120 {
+            let mut zone = TriggerZone::new(random_rect(&mut rng, 90.0));
+            if i % 7 == 0 {
+                zone = zone.with_layer(CollisionLayer::new(
+                    CollisionLayer::TRIGGER,
+                    CollisionLayer::PLAYER,
+                ));
+            }
+            brute.add_zone(zone.clone());
+            indexed.add_zone(zone);
+        }
+
+        let mut bodies: Vec<(BodyId, Rect, CollisionLayer)> = (0..60)
+            .map(|id| {
+                let layer = if id % 2 == 0 {
+                    CollisionLayer::symmetric(CollisionLayer::PLAYER | CollisionLayer::TRIGGER)
+                } else {
+                    CollisionLayer::symmetric(CollisionLayer::ENEMY)
+                };
+                (id, random_rect(&mut rng, 30.0), layer)
+            })
+            .collect();
+
+        for frame in 0..40 {
+            for body in &mut bodies {
+                body.1.x += rng.f32_range(-25.0, 25.0);
+                body.1.y += rng.f32_range(-25.0, 25.0);
+            }
+            if frame % 5 == 0 {
+                let id = rng.usize(brute.zone_count());
+                let rect = random_rect(&mut rng, 90.0);
+                brute.zone_mut(id).rect = rect;
+                indexed.zone_mut(id).rect = rect;
+                let enabled = !brute.zone(id).enabled;
+                brute.zone_mut(id).enabled = enabled;
+                indexed.zone_mut(id).enabled = enabled;
+            }
+
+            brute.tick(&bodies);
+            indexed.tick(&bodies);
+
+            let expected: Vec<_> = brute.events().collect();
+            let actual: Vec<_> = indexed.events().collect();
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn set_zone_rect_moves_the_indexed_zone() {
+        let mut triggers = TriggerSystem::with_broadphase(16.0);
+        let zone = triggers.add_zone(TriggerZone::new(Rect::new(0.0, 0.0, 10.0, 10.0)));
+        let body = [(
+            1,
+            Rect::new(200.0, 200.0, 4.0, 4.0),
+            CollisionLayer::default(),
+        )];
+
+        triggers.tick(&body);
+        assert!(!triggers.overlapping(zone, 1));
+
+        triggers.set_zone_rect(zone, Rect::new(198.0, 198.0, 10.0, 10.0));
+        triggers.tick(&body);
+        assert_eq!(triggers.event_for(zone, 1), Some(OverlapEvent::Enter));
+    }
+
+    #[test]
+    fn shaped_zone_ignores_bodies_in_its_bounding_box_corners() {
+        let mut system = TriggerSystem::new();
+        let zone = system.add_zone(TriggerZone::shaped(Shape2D::circle(Vec2::ZERO, 50.0)));
+        let layer = CollisionLayer::default();
+
+        system.tick(&[(1, Rect::new(40.0, 40.0, 8.0, 8.0), layer)]);
+        assert_eq!(system.event_for(zone, 1), None);
+
+        system.tick_shapes(&[(1, Shape2D::circle(Vec2::new(30.0, 30.0), 5.0), layer)]);
+        assert_eq!(system.event_for(zone, 1), Some(OverlapEvent::Enter));
+    }
+
+    #[test]
+    fn zones_fire_after_dwelling_then_cool_down_or_disarm() {
+        let mut system = TriggerSystem::new();
+        let plate = system.add_zone(
+            TriggerZone::new(Rect::new(0.0, 0.0, 10.0, 10.0))
+                .with_dwell(2.0)
+                .with_cooldown(5.0),
+        );
+        let lever =
+            system.add_zone(TriggerZone::new(Rect::new(20.0, 0.0, 10.0, 10.0)).with_once(true));
+        let layer = CollisionLayer::default();
+        let on_plate = [(1, Rect::new(2.0, 2.0, 4.0, 4.0), layer)];
+        let fired = |system: &TriggerSystem| system.fired().collect::<Vec<_>>();
+
+        system.tick(&on_plate);
+        system.advance(1.5);
+        system.tick(&on_plate);
+        assert_eq!(system.dwell_time(plate, 1), Some(1.5));
+        assert!(fired(&system).is_empty());
+        system.advance(0.5);
+        system.tick(&on_plate);
+        assert_eq!(fired(&system), vec![(plate, 1)]);
+        // Once per visit.
+        system.advance(1.0);
+        system.tick(&on_plate);
+        assert!(fired(&system).is_empty());
+
+        // Stepping off and back on dwells afresh, then waits out the cooldown.
+        system.advance(0.5);
+        system.tick(&[]);
+        system.tick(&on_plate);
+        system.advance(2.5);
+        system.tick(&on_plate);
+        assert!(fired(&system).is_empty());
+        system.advance(1.0);
+        system.tick(&on_plate);
+        assert_eq!(fired(&system), vec![(plate, 1)]);
+
+        // A one-shot lever fires on entry, then disarms and lets go.
+        let on_lever = [(2, Rect::new(22.0, 2.0, 4.0, 4.0), layer)];
+        system.tick(&on_lever);
+        assert_eq!(fired(&system), vec![(lever, 2)]);
+        assert!(!system.zone(lever).enabled);
+        system.tick(&on_lever);
+        assert_eq!(system.event_for(lever, 2), Some(OverlapEvent::Exit));
+        assert!(fired(&system).is_empty());
+    }
+
+    #[test]
+    fn dwell_times_stay_exact_after_days_of_play() {
+        let mut system = TriggerSystem::new();
+        let plate =
+            system.add_zone(TriggerZone::new(Rect::new(0.0, 0.0, 10.0, 10.0)).with_dwell(0.1));
+        let on_plate = [(1, Rect::new(2.0, 2.0, 4.0, 4.0), CollisionLayer::default())];
+
+        // A frame is far below an f32's resolution three days in.
+        system.advance(3.0 * 24.0 * 3600.0);
+        system.tick(&on_plate);
+        let mut frames = 0;
+        while system.fired().next().is_none() && frames < 60 {
+            system.advance(1.0 / 60.0);
+            system.tick(&on_plate);
+            frames += 1;
+        }
+        assert_eq!(frames, 6);
+        assert!((system.dwell_time(plate, 1).unwrap() - 0.1).abs() < 1e-4);
+    }
+}
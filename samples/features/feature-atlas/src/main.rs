@@ -0,0 +1,213 @@
+//! Feature: runtime texture atlas.
+//!
+//! A few hundred procedural gems are packed with
+//! [`Engine::create_packed_texture`], so they share one atlas page and draw
+//! as a single batch. A badge is written to a PNG in a scratch folder and
+//! loaded with [`Engine::load_packed_texture`]; Space rewrites the file at
+//! the next size in [`BADGE_SIZES`] and hot reload swaps it in. Shrinking,
+//! and growing back to the size it was packed at, keep the badge in its
+//! original slot. Growing past that moves it out to a texture of its own,
+//! so no page space is claimed twice.
+//!
+//! Coordinates are centered and y-up.
+
+use std::path::{Path, PathBuf};
+
+use rengine::pixelart::{lighten, PixelCanvas};
+use rengine::*;
+
+const GEMS: usize = 240;
+const GEM_COLUMNS: usize = 20;
+/// The badge is packed at the first size and rewritten at each of these in
+/// turn.
+const BADGE_SIZES: [u32; 4] = [48, 32, 48, 96];
+const BADGE: &str = "badge.png";
+
+/// A round gem `size` pixels across in a colour drawn from `seed`.
+fn gem_pixels(size: u32, seed: u64) -> Vec<u8> {
+    let mut rng = Rng::new(seed);
+    let color = Color::new(
+        rng.f32_range(0.3, 1.0),
+        rng.f32_range(0.3, 1.0),
+        rng.f32_range(0.3, 1.0),
+        1.0,
+    );
+    let mut pc = PixelCanvas::new(size, size);
+    pc.fill(Color::new(0.0, 0.0, 0.0, 0.0));
+    let r = size as i32 / 2;
+    pc.fill_circle(r, r, r - 1, color);
+    pc.fill_circle(r - r / 3, r - r / 3, (r / 3).max(1), lighten(color, 1.4));
+    pc.into_bytes()
+}
+
+/// A square badge with a border, so its edges show when it is resized.
+fn badge_pixels(size: u32) -> Vec<u8> {
+    let mut pc = PixelCanvas::new(size, size);
+    pc.fill(Color::from_rgba8(250, 200, 80, 255));
+    let inset = (size / 8) as i32;
+    let inner = size as i32 - 2 * inset;
+    pc.fill_rect(
+        inset,
+        inset,
+        inner,
+        inner,
+        Color::from_rgba8(60, 40, 120, 255),
+    );
+    pc.into_bytes()
+}
+
+fn write_badge(dir: &Path, size: u32) -> image::ImageResult<()> {
+    image::save_buffer(
+        dir.join(BADGE),
+        &badge_pixels(size),
+        size,
+        size,
+        image::ExtendedColorType::Rgba8,
+    )
+}
+
+struct AtlasDemo {
+    dir: PathBuf,
+    gems: Vec<(TextureId, u32)>,
+    badge: TextureId,
+    next_size: usize,
+}
+
+impl Game for AtlasDemo {
+    fn new(engine: &mut Engine) -> Self {
+        let dir = std::env::temp_dir().join("rengine-feature-atlas");
+        std::fs::create_dir_all(&dir).expect("failed to create the scratch folder");
+        write_badge(&dir, BADGE_SIZES[0]).expect("failed to write the badge");
+        engine.set_asset_root(&dir);
+
+        let gems = (0..GEMS)
+            .map(|i| {
+                let size = 10 + (i % 4) as u32 * 4;
+                let texture = engine.create_packed_texture(size, size, &gem_pixels(size, i as u64));
+                (texture, size)
+            })
+            .collect();
+        let badge = engine
+            .load_packed_texture(BADGE)
+            .expect("failed to load the badge")
+            .id;
+        Self {
+            dir,
+            gems,
+            badge,
+            next_size: 1,
+        }
+    }
+
+    fn update(&mut self, engine: &Engine, _frame: &mut Frame) {
+        if engine.input().is_key_pressed(KeyCode::Space) {
+            let size = BADGE_SIZES[self.next_size];
+            if let Err(error) = write_badge(&self.dir, size) {
+                eprintln!("Could not rewrite the badge: {error}");
+            }
+            self.next_size = (self.next_size + 1) % BADGE_SIZES.len();
+        }
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(24, 26, 36, 255);
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+
+        for (i, &(texture, size)) in self.gems.iter().enumerate() {
+            let (col, row) = (i % GEM_COLUMNS, i / GEM_COLUMNS);
+            let x = -hw + 30.0 + col as f32 * 26.0;
+            let y = hh - 90.0 - row as f32 * 26.0;
+            canvas.image(texture, x, y, size as f32, size as f32);
+        }
+
+        let badge = engine.loaded_texture(BADGE);
+        let (badge_w, badge_h) = badge.as_ref().map_or((0, 0), |b| (b.width, b.height));
+        canvas.image(
+            self.badge,
+            hw - 200.0,
+            -40.0,
+            badge_w as f32,
+            badge_h as f32,
+        );
+        let placement = match engine.atlas_region(self.badge) {
+            Some(region) => {
+                let gem_page = engine.atlas_region(self.gems[0].0).map(|gem| gem.page);
+                format!(
+                    "{} at uv ({:.4}, {:.4})",
+                    if gem_page == Some(region.page) {
+                        "the gems' page"
+                    } else {
+                        "another page"
+                    },
+                    region.uv_rect[0],
+                    region.uv_rect[1]
+                )
+            }
+            None => "a texture of its own".to_string(),
+        };
+
+        canvas.text(
+            -hw + 12.0,
+            hh - 24.0,
+            &format!(
+                "{} gems packed into {} atlas page(s)",
+                self.gems.len(),
+                engine.atlas_page_count()
+            ),
+            14.0,
+            Color::WHITE,
+        );
+        canvas.text(
+            -hw + 12.0,
+            hh - 44.0,
+            &format!(
+                "Space: rewrite the badge at {}px  |  now {badge_w}px in {placement}",
+                BADGE_SIZES[self.next_size]
+            ),
+            14.0,
+            Color::from_rgba8(180, 200, 230, 255),
+        );
+    }
+}
+
+fn main() {
+    rengine::run::<AtlasDemo>(EngineConfig {
+        title: "Feature: Texture Atlas".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        hot_reload: true,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gems_and_badges_are_whole_rgba_images() {
+        for size in [10, 14, 18, 22] {
+            assert_eq!(gem_pixels(size, 7).len(), (size * size * 4) as usize);
+        }
+        for size in BADGE_SIZES {
+            assert_eq!(badge_pixels(size).len(), (size * size * 4) as usize);
+        }
+    }
+
+    #[test]
+    fn a_rewritten_badge_reads_back_at_its_new_size() {
+        let dir = std::env::temp_dir().join("rengine-feature-atlas-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        for size in BADGE_SIZES {
+            write_badge(&dir, size).unwrap();
+            let image = image::open(dir.join(BADGE)).unwrap().to_rgba8();
+            assert_eq!(image.dimensions(), (size, size));
+            assert_eq!(image.into_raw(), badge_pixels(size));
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! Feature: rule-based autotiling.
+//!
+//! `terrain.png` holds the 47 blob tiles of a `rock` terrain and the 16 edge
+//! tiles of a `water` terrain; `terrain.autotile.json`, named by the sprite
+//! sheet's `autotile` entry in `autotile.assets.json`, lays them out in mask
+//! order. The cave is generated from a seed with a cellular automaton that
+//! only decides *which terrain* each cell holds — [`Autotiler::apply`] picks
+//! every tile at load time. Painting with the mouse goes through
+//! [`Autotiler::set_terrain`], which re-tiles the painted cell and its eight
+//! neighbours. Rock and water connect to each other, so a pool only shows a
+//! shoreline at its surface.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use rengine::*;
+
+const COLS: usize = 50;
+const ROWS: usize = 36;
+const TILE: f32 = 16.0;
+/// Open cells below this row fill with water.
+const WATER_LINE: usize = 7;
+const SMOOTHING_PASSES: usize = 5;
+
+struct Cave {
+    map: TileMap,
+    tiler: Autotiler,
+    seed: u64,
+}
+
+impl Cave {
+    fn new(rules: Arc<AutotileRules>, sheet: &SpriteSheet, seed: u64) -> Self {
+        let origin = -Vec2::new(COLS as f32, ROWS as f32) * TILE / 2.0;
+        let mut map = TileMap::new(COLS, ROWS, TILE).with_origin(origin);
+        let mut tiler = Autotiler::new(rules, sheet, COLS, ROWS, |def| map.add_tile(def));
+        for (index, terrain) in generate(seed).into_iter().enumerate() {
+            tiler.paint(index % COLS, index / COLS, terrain);
+        }
+        tiler.apply(&mut map);
+        Self { map, tiler, seed }
+    }
+
+    fn cell_at(&self, point: Vec2) -> Option<(usize, usize)> {
+        let local = (point - self.map.origin) / TILE;
+        let (col, row) = (local.x.floor(), local.y.floor());
+        (col >= 0.0 && row >= 0.0 && (col as usize) < COLS && (row as usize) < ROWS)
+            .then_some((col as usize, row as usize))
+    }
+
+    fn paint(&mut self, col: usize, row: usize, terrain: Option<&str>) {
+        if self.tiler.terrain_at(col, row) != terrain {
+            self.tiler.set_terrain(&mut self.map, col, row, terrain);
+        }
+    }
+}
+
+/// Which terrain each cell holds, row by row from the bottom.
+fn generate(seed: u64) -> Vec<Option<&'static str>> {
+    let mut rng = Rng::new(seed);
+    let border =
+        |col: usize, row: usize| col == 0 || row == 0 || col == COLS - 1 || row == ROWS - 1;
+    let mut rock: Vec<bool> = (0..COLS * ROWS)
+        .map(|index| border(index % COLS, index / COLS) || rng.chance(0.45))
+        .collect();
+
+    for _ in 0..SMOOTHING_PASSES {
+        let before = rock.clone();
+        for row in 0..ROWS {
+            for col in 0..COLS {
+                let mut walls = 0;
+                for (dx, dy) in [
+                    (-1, -1),
+                    (0, -1),
+                    (1, -1),
+                    (-1, 0),
+                    (1, 0),
+                    (-1, 1),
+                    (0, 1),
+                    (1, 1),
+                ] {
+                    let (c, r) = (col as i32 + dx, row as i32 + dy);
+                    let outside = c < 0 || r < 0 || c >= COLS as i32 || r >= ROWS as i32;
+                    if outside || before[r as usize * COLS + c as usize] {
+                        walls += 1;
+                    }
+                }
+                let index = row * COLS + col;
+                rock[index] = border(col, row) || walls > 4 || (walls == 4 && before[index]);
+            }
+        }
+    }
+
+    rock.into_iter()
+        .enumerate()
+        .map(|(index, rock)| match (rock, index / COLS < WATER_LINE) {
+            (true, _) => Some("rock"),
+            (false, true) => Some("water"),
+            (false, false) => None,
+        })
+        .collect()
+}
+
+struct AutotileDemo {
+    _assets: AssetBundle,
+    rules: Arc<AutotileRules>,
+    sheet: SpriteSheet,
+    cave: Cave,
+    brush: &'static str,
+    hover: Option<(usize, usize)>,
+}
+
+impl Game for AutotileDemo {
+    fn new(engine: &mut Engine) -> Self {
+        engine.set_asset_root(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets"));
+        let assets = engine
+            .load_asset_bundle("autotile.assets.json")
+            .expect("failed to load the terrain sheet");
+        let sheet = assets
+            .sprite_sheet("terrain")
+            .expect("terrain sheet")
+            .clone();
+        let rules = assets
+            .autotile_rules("terrain")
+            .expect("terrain autotile rules")
+            .clone();
+        Self {
+            cave: Cave::new(rules.clone(), &sheet, 1),
+            rules,
+            sheet,
+            _assets: assets,
+            brush: "rock",
+            hover: None,
+        }
+    }
+
+    fn update(&mut self, engine: &Engine, frame: &mut Frame) {
+        let input = engine.input();
+        if input.is_key_pressed(KeyCode::Digit1) {
+            self.brush = "rock";
+        }
+        if input.is_key_pressed(KeyCode::Digit2) {
+            self.brush = "water";
+        }
+        if input.is_key_pressed(KeyCode::KeyG) {
+            self.cave = Cave::new(self.rules.clone(), &self.sheet, self.cave.seed + 1);
+        }
+
+        let (mx, my) = input.mouse_position();
+        self.hover = self
+            .cave
+            .cell_at(frame.camera.screen_to_world(Vec2::new(mx, my)));
+        if let Some((col, row)) = self.hover {
+            if input.is_mouse_down(0) {
+                self.cave.paint(col, row, Some(self.brush));
+            } else if input.is_mouse_down(1) {
+                self.cave.paint(col, row, None);
+            }
+        }
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(24, 20, 28, 255);
+        self.cave.map.draw(frame);
+
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+
+        let mut status = String::from("-");
+        if let Some((col, row)) = self.hover {
+            let r = self.cave.map.cell_rect(col, row);
+            let edge = Color::from_rgba8(255, 230, 120, 255);
+            let (x0, y0, x1, y1) = (r.left(), r.bottom(), r.right(), r.top());
+            for (ax, ay, bx, by) in [
+                (x0, y0, x1, y0),
+                (x1, y0, x1, y1),
+                (x1, y1, x0, y1),
+                (x0, y1, x0, y0),
+            ] {
+                canvas.line(ax, ay, bx, by, 1.0, edge);
+            }
+            let terrain = self.cave.tiler.terrain_at(col, row).unwrap_or("empty");
+            status = match self.cave.tiler.mask_at(col, row) {
+                Some(mask) => format!("({col}, {row}) {terrain}, mask {mask}"),
+                None => format!("({col}, {row}) {terrain}"),
+            };
+        }
+
+        canvas.text(
+            -hw + 12.0,
+            hh - 24.0,
+            &format!(
+                "LMB paint {}, RMB erase, 1 rock, 2 water, G new cave  |  Seed: {}",
+                self.brush, self.cave.seed
+            ),
+            14.0,
+            Color::WHITE,
+        );
+        canvas.text(
+            -hw + 12.0,
+            hh - 44.0,
+            &status,
+            14.0,
+            Color::from_rgba8(180, 200, 230, 255),
+        );
+    }
+}
+
+fn main() {
+    rengine::run::<AutotileDemo>(EngineConfig {
+        title: "Feature: Autotiling".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn caves_are_seeded_walled_in_and_flooded_below_the_water_line() {
+        let cave = generate(7);
+        assert_eq!(cave, generate(7));
+        assert_ne!(cave, generate(8));
+        for (index, terrain) in cave.iter().enumerate() {
+            let (col, row) = (index % COLS, index / COLS);
+            if col == 0 || row == 0 || col == COLS - 1 || row == ROWS - 1 {
+                assert_eq!(*terrain, Some("rock"));
+            } else if row >= WATER_LINE {
+                assert_ne!(*terrain, Some("water"));
+            } else {
+                assert!(terrain.is_some(), "({col}, {row}) should be rock or water");
+            }
+        }
+        assert!(cave.iter().any(|terrain| terrain.is_none()));
+    }
+
+    #[test]
+    fn terrain_rules_cover_every_mask_inside_the_sheet() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/terrain.autotile.json");
+        let text = std::fs::read_to_string(&path).unwrap();
+        let rules = AutotileRules::parse(&path, &text).unwrap();
+        // terrain.png is 8 x 8 cells.
+        for name in ["rock", "water"] {
+            let rule = &rules.terrains[rules.terrain(name).unwrap()];
+            for mask in rule.mode.masks() {
+                let cell = rule.variants[&mask][0];
+                assert!(cell[0] < 8 && cell[1] < 8, "{name} mask {mask} at {cell:?}");
+            }
+        }
+        assert!(!rules.terrains[rules.terrain("water").unwrap()].collides);
+    }
+}
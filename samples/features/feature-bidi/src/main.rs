@@ -0,0 +1,134 @@
+//! Feature: right-to-left and mixed-direction text.
+//!
+//! The built-in pixel face only covers Latin, so a cut-down DejaVu Sans with
+//! Hebrew and Arabic is loaded as its fallback. Every line below goes through
+//! the ordinary [`Canvas::text_aligned`] call. Arabic letters join and lam
+//! and alef fuse into one ligature. Hebrew and Arabic runs read right to
+//! left, while the Latin words and numbers inside them still read left to
+//! right. [`TextAlign::Start`] puts each line against the edge its first
+//! strong character reads from.
+//!
+//! Coordinates are centered and y-up.
+
+use std::path::PathBuf;
+
+use rengine::*;
+
+/// Each line, and what to look for in it.
+const LINES: [(&str, &str); 6] = [
+    ("Hello, world!", "Latin: left to right, on the left"),
+    (
+        "\u{5e9}\u{5dc}\u{5d5}\u{5dd} \u{5e2}\u{5d5}\u{5dc}\u{5dd}",
+        "Hebrew: on the right",
+    ),
+    (
+        "\u{645}\u{631}\u{62d}\u{628}\u{627} \u{628}\u{627}\u{644}\u{639}\u{627}\u{644}\u{645}",
+        "Arabic: joined letters and a lam-alef",
+    ),
+    (
+        "Score 42 \u{2014} \u{646}\u{642}\u{627}\u{637}",
+        "Latin first: the Arabic word ends the line",
+    ),
+    (
+        "\u{5d7}\u{5d9}\u{5d9}\u{5dd}: HP 100",
+        "Hebrew first: the Latin sits on its left",
+    ),
+    (
+        "\u{633}\u{644}\u{627}\u{645} (3 \u{645}\u{646} 5)",
+        "Arabic with digits: numbers still read 3 then 5",
+    ),
+];
+
+/// Which side of the column `text` starts on.
+fn starts_on_the_right(text: &str) -> bool {
+    TextAlign::Start.resolve(text) == TextAlign::Right
+}
+
+struct BidiDemo;
+
+impl Game for BidiDemo {
+    fn new(engine: &mut Engine) -> Self {
+        engine.set_asset_root(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets"));
+        let rtl = engine
+            .load_font("dejavu-sans-rtl.ttf")
+            .expect("failed to load the Hebrew and Arabic font");
+        engine.set_font_fallbacks(FontId::DEFAULT, &[rtl]);
+        Self
+    }
+
+    fn update(&mut self, _engine: &Engine, _frame: &mut Frame) {}
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(28, 30, 42, 255);
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+
+        let left = -hw + 40.0;
+        let right = hw - 40.0;
+        let mut y = hh - 60.0;
+        for (text, note) in LINES {
+            canvas.rect(
+                left,
+                y - 34.0,
+                right - left,
+                1.0,
+                Color::from_rgba8(60, 64, 84, 255),
+            );
+            let x = if starts_on_the_right(text) {
+                right
+            } else {
+                left
+            };
+            canvas.text_aligned(x, y, text, 24.0, Color::WHITE, TextAlign::Start);
+            canvas.text(
+                left,
+                y - 28.0,
+                note,
+                12.0,
+                Color::from_rgba8(140, 150, 180, 255),
+            );
+            y -= 76.0;
+        }
+
+        canvas.text(
+            -hw + 12.0,
+            hh - 24.0,
+            "Built-in pixel face, with DejaVu Sans as its Hebrew and Arabic fallback",
+            14.0,
+            Color::from_rgba8(180, 200, 230, 255),
+        );
+    }
+}
+
+fn main() {
+    rengine::run::<BidiDemo>(EngineConfig {
+        title: "Feature: Bidirectional Text".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_line_starts_on_the_side_its_first_letter_reads_from() {
+        let sides: Vec<bool> = LINES
+            .iter()
+            .map(|(text, _)| starts_on_the_right(text))
+            .collect();
+        assert_eq!(sides, [false, true, true, false, true, true]);
+    }
+
+    #[test]
+    fn numbers_alone_read_left_to_right() {
+        assert!(!starts_on_the_right("3 - 5"));
+        assert!(starts_on_the_right("5 - \u{5d0}"));
+    }
+}
@@ -0,0 +1,253 @@
+//! Feature: spatial broadphase.
+//!
+//! A maze of about a thousand one-tile [`Solid2D`]s lives in a [`SolidIndex2D`]
+//! and over a hundred gem zones in a [`TriggerSystem`] with its grid
+//! broadphase enabled. The player moves through
+//! [`SolidIndex2D::move_and_collide`], so each step only visits the solids
+//! near it; those candidates are tinted. A sliding door is moved with
+//! [`SolidIndex2D::set_rect`] and collected gems are shrunk away with
+//! [`TriggerSystem::set_zone_rect`]; both only touch the grid cells involved.
+//!
+//! Coordinates are centered and y-up, so the canvas draws world space directly.
+
+use rengine::*;
+
+const PLAYER_SPEED: f32 = 220.0;
+const PLAYER_SIZE: f32 = 12.0;
+const PLAYER_BODY_ID: BodyId = 0;
+const TILE: f32 = 8.0;
+const COLS: usize = 96;
+const ROWS: usize = 70;
+const ORIGIN: Vec2 = Vec2::new(-384.0, -280.0);
+const CELL_SIZE: f32 = 32.0;
+const GEM_SIZE: f32 = 6.0;
+const DOOR_TRAVEL: f32 = 48.0;
+
+/// Border walls plus a wall every twelfth column and tenth row, each broken
+/// by a doorway, one solid per tile.
+fn maze_solids() -> Vec<Solid2D> {
+    let mut solids = Vec::new();
+    for row in 0..ROWS {
+        for col in 0..COLS {
+            let border = col == 0 || row == 0 || col == COLS - 1 || row == ROWS - 1;
+            let wall = (col % 12 == 0 && row % 10 > 3) || (row % 10 == 0 && col % 12 > 5);
+            if border || wall {
+                solids.push(Solid2D::solid(Rect::new(
+                    ORIGIN.x + col as f32 * TILE,
+                    ORIGIN.y + row as f32 * TILE,
+                    TILE,
+                    TILE,
+                )));
+            }
+        }
+    }
+    solids
+}
+
+/// A gem in the middle of every open room.
+fn gem_rects() -> Vec<Rect> {
+    (0..ROWS / 10)
+        .flat_map(|room_row| (0..COLS / 12).map(move |room_col| (room_col, room_row)))
+        .flat_map(|(room_col, room_row)| {
+            (0..3).map(move |i| {
+                Rect::new(
+                    ORIGIN.x + (room_col * 12 + 3 + i * 3) as f32 * TILE,
+                    ORIGIN.y + (room_row * 10 + 5) as f32 * TILE,
+                    GEM_SIZE,
+                    GEM_SIZE,
+                )
+            })
+        })
+        .collect()
+}
+
+fn door_rect(offset: f32) -> Rect {
+    Rect::new(
+        ORIGIN.x + 48.0 * TILE,
+        ORIGIN.y + 30.0 * TILE + 8.0 + offset,
+        TILE,
+        5.0 * TILE,
+    )
+}
+
+struct BroadphaseDemo {
+    player: Rect,
+    solids: SolidIndex2D,
+    door: SolidId,
+    triggers: TriggerSystem,
+    gems: Vec<TriggerZoneId>,
+    collected: usize,
+    candidates: Vec<SolidId>,
+    time: f32,
+}
+
+impl Game for BroadphaseDemo {
+    fn new(_engine: &mut Engine) -> Self {
+        let mut solids = SolidIndex2D::from_solids(CELL_SIZE, &maze_solids());
+        let door = solids.insert(Solid2D::solid(door_rect(0.0)));
+        let mut triggers = TriggerSystem::with_broadphase(CELL_SIZE);
+        let gems = gem_rects()
+            .into_iter()
+            .map(|rect| triggers.add_zone(TriggerZone::new(rect).with_once(true)))
+            .collect();
+        Self {
+            player: Rect::new(
+                ORIGIN.x + 3.0 * TILE,
+                ORIGIN.y + 2.0 * TILE,
+                PLAYER_SIZE,
+                PLAYER_SIZE,
+            ),
+            solids,
+            door,
+            triggers,
+            gems,
+            collected: 0,
+            candidates: Vec::new(),
+            time: 0.0,
+        }
+    }
+
+    fn update(&mut self, engine: &Engine, _frame: &mut Frame) {
+        let input = engine.input();
+        let dt = engine.dt().min(1.0 / 30.0);
+        self.time += dt;
+
+        let door = door_rect(DOOR_TRAVEL * (0.5 - 0.5 * (self.time * 1.5).cos()));
+        self.solids.set_rect(self.door, door);
+
+        let mut dir = Vec2::ZERO;
+        if input.is_key_down(KeyCode::KeyW) {
+            dir.y += 1.0;
+        }
+        if input.is_key_down(KeyCode::KeyS) {
+            dir.y -= 1.0;
+        }
+        if input.is_key_down(KeyCode::KeyA) {
+            dir.x -= 1.0;
+        }
+        if input.is_key_down(KeyCode::KeyD) {
+            dir.x += 1.0;
+        }
+        let motion = dir.normalize_or_zero() * PLAYER_SPEED * dt;
+        let swept = Rect::new(
+            self.player.x + motion.x.min(0.0),
+            self.player.y + motion.y.min(0.0),
+            self.player.width + motion.x.abs(),
+            self.player.height + motion.y.abs(),
+        );
+        self.candidates = self.solids.query(&swept).map(|(id, _)| id).collect();
+        let moved = self.solids.move_and_collide(self.player, motion);
+        self.player.x = moved.position.x;
+        self.player.y = moved.position.y;
+
+        self.triggers.advance(dt);
+        self.triggers
+            .tick(&[(PLAYER_BODY_ID, self.player, CollisionLayer::default())]);
+        let fired: Vec<_> = self.triggers.fired().map(|(zone, _)| zone).collect();
+        for zone in fired {
+            let rect = self.triggers.zone(zone).rect;
+            self.triggers
+                .set_zone_rect(zone, Rect::new(rect.x, rect.y, 0.0, 0.0));
+            self.collected += 1;
+        }
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(16, 18, 24, 255);
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+
+        for (id, solid) in self.solids.solids() {
+            let r = solid.rect;
+            let color = if self.candidates.contains(&id) {
+                Color::from_rgba8(255, 170, 70, 255)
+            } else if id == self.door {
+                Color::from_rgba8(120, 150, 220, 255)
+            } else {
+                Color::from_rgba8(60, 66, 84, 255)
+            };
+            canvas.rect(r.x, r.y, r.width, r.height, color);
+        }
+        for &gem in &self.gems {
+            let r = self.triggers.zone(gem).rect;
+            if r.width > 0.0 {
+                canvas.rect(
+                    r.x,
+                    r.y,
+                    r.width,
+                    r.height,
+                    Color::from_rgba8(120, 240, 170, 255),
+                );
+            }
+        }
+
+        let p = self.player;
+        canvas.rect(
+            p.x,
+            p.y,
+            p.width,
+            p.height,
+            Color::from_rgba8(80, 200, 255, 255),
+        );
+
+        canvas.text(
+            -hw + 12.0,
+            hh - 16.0,
+            &format!(
+                "WASD move. {} solids, {} gem zones in {CELL_SIZE}px grids; \
+                 {} solid(s) checked this step. Gems: {}/{}",
+                self.solids.len(),
+                self.triggers.zone_count(),
+                self.candidates.len(),
+                self.collected,
+                self.gems.len()
+            ),
+            12.0,
+            Color::WHITE,
+        );
+    }
+}
+
+fn main() {
+    rengine::run::<BroadphaseDemo>(EngineConfig {
+        title: "Feature: Broadphase".into(),
+        width: 800,
+        height: 600,
+        show_fps: true,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexed_moves_match_the_brute_force_scan() {
+        let solids = maze_solids();
+        let index = SolidIndex2D::from_solids(CELL_SIZE, &solids);
+        let body = Rect::new(ORIGIN.x + 20.0, ORIGIN.y + 20.0, PLAYER_SIZE, PLAYER_SIZE);
+        for motion in [
+            Vec2::new(-40.0, 0.0),
+            Vec2::new(0.0, -40.0),
+            Vec2::new(300.0, 37.0),
+            Vec2::new(90.0, 400.0),
+        ] {
+            assert_eq!(
+                index.move_and_collide(body, motion),
+                move_and_collide_solids(body, motion, &solids)
+            );
+        }
+    }
+
+    #[test]
+    fn a_step_only_visits_nearby_solids() {
+        let index = SolidIndex2D::from_solids(CELL_SIZE, &maze_solids());
+        let body = Rect::new(ORIGIN.x + 20.0, ORIGIN.y + 20.0, PLAYER_SIZE, PLAYER_SIZE);
+        let near = index.query(&body).count();
+        assert!(near > 0 && near * 20 < index.len());
+    }
+}
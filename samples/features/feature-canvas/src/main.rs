@@ -101,16 +101,19 @@ impl Game for CanvasDemo {
                     position: p0,
                     color: c,
                     uv,
+                    ..CanvasVertex::PLAIN
                 },
                 CanvasVertex {
                     position: p1,
                     color: c,
                     uv,
+                    ..CanvasVertex::PLAIN
                 },
                 CanvasVertex {
                     position: p2,
                     color: c,
                     uv,
+                    ..CanvasVertex::PLAIN
                 },
             ]);
         }
@@ -155,6 +158,7 @@ impl Game for CanvasDemo {
                     position: p,
                     color: quad_color,
                     uv,
+                    ..CanvasVertex::PLAIN
                 }
             })
             .collect();
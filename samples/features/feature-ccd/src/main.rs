@@ -0,0 +1,269 @@
+//! Feature: continuous collision detection.
+//!
+//! A turret fires bullets far faster than their own size per frame at a row of
+//! 2px walls. With [`CcdMode2D::Off`] most of them tunnel straight through;
+//! [`CcdMode2D::Swept`] and [`CcdMode2D::Substep`] stop them on the first wall
+//! and read [`KinematicBody2D::impact`] to leave a scorch decal exactly where
+//! each bullet struck, oriented by the impact normal.
+//!
+//! Coordinates are centered and y-up, so the canvas draws world space directly.
+
+use rengine::*;
+
+const BULLET_SPEED: f32 = 4200.0;
+const BULLET_SIZE: f32 = 6.0;
+const MAX_DECALS: usize = 96;
+const TURRET: Vec2 = Vec2::new(-330.0, 0.0);
+
+fn level_solids() -> Vec<Solid2D> {
+    vec![
+        Solid2D::solid(Rect::new(-120.0, -160.0, 2.0, 120.0)),
+        Solid2D::solid(Rect::new(0.0, -60.0, 2.0, 200.0)),
+        Solid2D::solid(Rect::new(-200.0, 150.0, 400.0, 2.0)),
+        Solid2D::shaped(
+            Shape2D::polygon(&[
+                Vec2::new(120.0, -200.0),
+                Vec2::new(124.0, -200.0),
+                Vec2::new(244.0, 120.0),
+                Vec2::new(240.0, 120.0),
+            ])
+            .unwrap(),
+        ),
+        Solid2D::solid(Rect::new(330.0, -220.0, 4.0, 440.0)),
+    ]
+}
+
+/// Where a bullet struck, drawn as a short scorch mark along the surface.
+#[derive(Clone, Copy)]
+struct Decal {
+    point: Vec2,
+    normal: Vec2,
+}
+
+struct CcdDemo {
+    mode: CcdMode2D,
+    solids: Vec<Solid2D>,
+    bullets: Vec<KinematicBody2D>,
+    decals: Vec<Decal>,
+    tunneled: u32,
+}
+
+impl CcdDemo {
+    fn fire(&mut self, target: Vec2) {
+        let dir = (target - TURRET).normalize_or(Vec2::X);
+        let half = BULLET_SIZE / 2.0;
+        let bullet = KinematicBody2D::new(Rect::new(
+            TURRET.x - half,
+            TURRET.y - half,
+            BULLET_SIZE,
+            BULLET_SIZE,
+        ))
+        .with_gravity(Vec2::ZERO)
+        .with_velocity(dir * BULLET_SPEED)
+        .with_ccd(self.mode);
+        self.bullets.push(bullet);
+    }
+
+    fn tick(&mut self, dt: f32) {
+        let solids = &self.solids;
+        let decals = &mut self.decals;
+        let tunneled = &mut self.tunneled;
+        self.bullets.retain_mut(|bullet| {
+            bullet.step_solids(dt, solids);
+            if let Some(impact) = bullet.impact {
+                decals.push(Decal {
+                    point: impact.point,
+                    normal: impact.normal,
+                });
+                return false;
+            }
+            // Without CCD a bullet that does get blocked knows nothing
+            // about where it struck; it just fizzles.
+            let c = bullet.contacts;
+            if c.left || c.right || c.top || c.bottom {
+                return false;
+            }
+            let c = bullet.bounds.center();
+            if c.x.abs() > 420.0 || c.y.abs() > 320.0 {
+                *tunneled += 1;
+                return false;
+            }
+            true
+        });
+        if self.decals.len() > MAX_DECALS {
+            let excess = self.decals.len() - MAX_DECALS;
+            self.decals.drain(..excess);
+        }
+    }
+}
+
+impl Game for CcdDemo {
+    fn new(_engine: &mut Engine) -> Self {
+        Self {
+            mode: CcdMode2D::Swept,
+            solids: level_solids(),
+            bullets: Vec::new(),
+            decals: Vec::new(),
+            tunneled: 0,
+        }
+    }
+
+    fn update(&mut self, engine: &Engine, _frame: &mut Frame) {
+        let input = engine.input();
+        let dt = engine.dt().min(1.0 / 30.0);
+
+        for (key, mode) in [
+            (KeyCode::Digit1, CcdMode2D::Off),
+            (KeyCode::Digit2, CcdMode2D::Swept),
+            (KeyCode::Digit3, CcdMode2D::Substep),
+        ] {
+            if input.is_key_pressed(key) {
+                self.mode = mode;
+            }
+        }
+        if input.is_key_pressed(KeyCode::KeyC) {
+            self.decals.clear();
+            self.tunneled = 0;
+        }
+        if input.is_mouse_pressed(0) || input.is_key_down(KeyCode::Space) {
+            let (mx, my) = input.mouse_position();
+            self.fire(Vec2::new(mx, my));
+        }
+
+        self.tick(dt);
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(18, 20, 28, 255);
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+        let wall = Color::from_rgba8(150, 160, 190, 255);
+
+        for solid in &self.solids {
+            match solid.shape {
+                Some(Shape2D::Polygon(ref polygon)) => {
+                    let points: Vec<(f32, f32)> =
+                        polygon.points().iter().map(|p| (p.x, p.y)).collect();
+                    canvas.polygon(&points, wall);
+                }
+                _ => {
+                    let r = solid.rect;
+                    canvas.rect(r.x, r.y, r.width, r.height, wall);
+                }
+            }
+        }
+
+        let scorch = Color::from_rgba8(255, 150, 60, 255);
+        for decal in &self.decals {
+            let along = decal.normal.perp() * 5.0;
+            let (a, b) = (decal.point - along, decal.point + along);
+            canvas.line(a.x, a.y, b.x, b.y, 2.0, scorch);
+            let tip = decal.point + decal.normal * 6.0;
+            canvas.line(
+                decal.point.x,
+                decal.point.y,
+                tip.x,
+                tip.y,
+                1.0,
+                Color::WHITE,
+            );
+        }
+
+        for bullet in &self.bullets {
+            let b = bullet.bounds;
+            canvas.rect(
+                b.x,
+                b.y,
+                b.width,
+                b.height,
+                Color::from_rgba8(255, 230, 120, 255),
+            );
+        }
+        canvas.circle_filled(
+            TURRET.x,
+            TURRET.y,
+            12.0,
+            20,
+            Color::from_rgba8(120, 200, 140, 255),
+        );
+
+        let mode = match self.mode {
+            CcdMode2D::Off => "off",
+            CcdMode2D::Swept => "swept",
+            CcdMode2D::Substep => "substep",
+        };
+        canvas.text(
+            -hw + 12.0,
+            hh - 24.0,
+            &format!(
+                "Click or hold Space to fire, 1/2/3 CCD off/swept/substep, C clears. \
+                 CCD: {mode}. Tunneled: {}",
+                self.tunneled
+            ),
+            14.0,
+            Color::WHITE,
+        );
+    }
+}
+
+fn main() {
+    rengine::run::<CcdDemo>(EngineConfig {
+        title: "Feature: Continuous Collision".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: f32 = 1.0 / 60.0;
+
+    fn demo(mode: CcdMode2D) -> CcdDemo {
+        CcdDemo {
+            mode,
+            solids: level_solids(),
+            bullets: Vec::new(),
+            decals: Vec::new(),
+            tunneled: 0,
+        }
+    }
+
+    fn volley(demo: &mut CcdDemo) {
+        for i in 0..20 {
+            demo.fire(Vec2::new(0.0, -40.0 + i as f32 * 8.0));
+        }
+        for _ in 0..30 {
+            demo.tick(STEP);
+        }
+    }
+
+    #[test]
+    fn without_ccd_bullets_tunnel_through_thin_walls() {
+        let mut demo = demo(CcdMode2D::Off);
+        volley(&mut demo);
+        assert!(demo.bullets.is_empty());
+        assert!(demo.tunneled > 0);
+    }
+
+    #[test]
+    fn ccd_bullets_leave_decals_on_the_wall_they_hit() {
+        for mode in [CcdMode2D::Swept, CcdMode2D::Substep] {
+            let mut demo = demo(mode);
+            volley(&mut demo);
+            assert_eq!(demo.tunneled, 0);
+            assert_eq!(demo.decals.len(), 20);
+            for decal in &demo.decals {
+                // Aimed at the middle wall, whose face sits at x = 0.
+                assert!(decal.point.x.abs() < 1e-2, "{mode:?}");
+                assert!((decal.normal - Vec2::new(-1.0, 0.0)).length() < 1e-3);
+            }
+        }
+    }
+}
@@ -0,0 +1,292 @@
+//! Feature: deterministic fixed-point simulation.
+//!
+//! Crates drop at [`Rng::fixed_range`] positions onto a stepped floor and
+//! stack up, moved by [`move_aabb`] on [`FixedRect`]s with [`Fixed`]
+//! velocities; each landing starts a `Tween<Fixed>` squash. Two peers run the
+//! same simulation. The second one rolls back a few ticks every so often,
+//! restoring an old snapshot and simulating forward again, as a rollback
+//! session does after a late input. Because nothing in the simulation touches
+//! `f32`, the two checksums always agree.
+//!
+//! Coordinates are centered and y-up; rendering converts with `to_f32`.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+use rengine::*;
+
+const FIXED_DT: Fixed = Fixed::from_ratio(1, 60);
+const GRAVITY: Fixed = Fixed::from_int(900);
+const CRATE: Fixed = Fixed::from_int(18);
+const SPAWN_EVERY: u32 = 20;
+const MAX_CRATES: usize = 60;
+/// The second peer rewinds this many ticks...
+const ROLLBACK_TICKS: usize = 8;
+/// ...once every this many.
+const ROLLBACK_EVERY: u32 = 45;
+const SEED: u64 = 0x5eed;
+
+#[derive(Debug, Clone)]
+struct Crate {
+    body: FixedRect,
+    velocity: FixedVec2,
+    squash: Tween<Fixed>,
+}
+
+/// Everything a rollback peer would save and restore.
+#[derive(Debug, Clone)]
+struct Sim {
+    tick: u32,
+    /// [`Rng::state`], so snapshots restore the random sequence too.
+    rng: [u64; 4],
+    crates: Vec<Crate>,
+}
+
+impl Sim {
+    fn new() -> Self {
+        Self {
+            tick: 0,
+            rng: Rng::new(SEED).state(),
+            crates: Vec::new(),
+        }
+    }
+
+    fn advance(&mut self, floor: &[FixedRect]) {
+        self.tick += 1;
+        if self.tick.is_multiple_of(SPAWN_EVERY) {
+            if self.crates.len() == MAX_CRATES {
+                self.crates.clear();
+            }
+            let mut rng = Rng::from_state(self.rng);
+            let x = rng.fixed_range(Fixed::from_int(-300), Fixed::from_int(280));
+            self.rng = rng.state();
+            self.crates.push(Crate {
+                body: FixedRect::new(x, Fixed::from_int(280), CRATE, CRATE),
+                velocity: FixedVec2::new(Fixed::ZERO, Fixed::ZERO),
+                squash: Tween::between(Fixed::ONE, Fixed::ONE, Fixed::ONE, Easing::Linear),
+            });
+        }
+
+        for i in 0..self.crates.len() {
+            let mut solids = floor.to_vec();
+            solids.extend(
+                self.crates
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .map(|(_, other)| other.body),
+            );
+            let crate_ = &mut self.crates[i];
+            crate_.velocity.y -= GRAVITY * FIXED_DT;
+            let (body, contacts) = move_aabb(crate_.body, crate_.velocity * FIXED_DT, &solids);
+            crate_.body = body;
+            if contacts.bottom {
+                if crate_.velocity.y < -Fixed::from_int(120) {
+                    crate_.squash = Tween::between(
+                        Fixed::from_ratio(3, 5),
+                        Fixed::ONE,
+                        Fixed::from_ratio(1, 4),
+                        Easing::OutBack,
+                    );
+                }
+                crate_.velocity.y = Fixed::ZERO;
+            }
+            crate_.squash.update(FIXED_DT);
+        }
+    }
+
+    /// A hash of the raw bits of the simulation, as rollback sessions
+    /// compare between peers.
+    fn checksum(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.tick.hash(&mut hasher);
+        self.rng.hash(&mut hasher);
+        for crate_ in &self.crates {
+            crate_.body.hash(&mut hasher);
+            crate_.velocity.hash(&mut hasher);
+            crate_.squash.value().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+/// A simulation that rewinds and replays itself now and then.
+struct RollbackPeer {
+    sim: Sim,
+    snapshots: VecDeque<Sim>,
+    rollbacks: u32,
+}
+
+impl RollbackPeer {
+    fn new() -> Self {
+        Self {
+            sim: Sim::new(),
+            snapshots: VecDeque::new(),
+            rollbacks: 0,
+        }
+    }
+
+    fn advance(&mut self, floor: &[FixedRect]) {
+        self.snapshots.push_back(self.sim.clone());
+        if self.snapshots.len() > ROLLBACK_TICKS {
+            self.snapshots.pop_front();
+        }
+        self.sim.advance(floor);
+
+        if self.sim.tick.is_multiple_of(ROLLBACK_EVERY) && self.snapshots.len() == ROLLBACK_TICKS {
+            let target = self.sim.tick;
+            self.sim = self.snapshots[0].clone();
+            while self.sim.tick < target {
+                self.sim.advance(floor);
+            }
+            self.rollbacks += 1;
+        }
+    }
+}
+
+/// A stepped floor with a wall at each end, authored in `f32` and snapped to
+/// [`Fixed`] once, as levels are at load.
+fn floor() -> Vec<FixedRect> {
+    let mut rects = vec![
+        Rect::new(-380.0, -260.0, 20.0, 560.0),
+        Rect::new(360.0, -260.0, 20.0, 560.0),
+    ];
+    for step in 0..6 {
+        let x = -360.0 + step as f32 * 120.0;
+        let height = 40.0 + (step as f32 - 2.5).abs() * 30.0;
+        rects.push(Rect::new(x, -260.0, 120.0, height));
+    }
+    rects.iter().map(FixedRect::from_rect).collect()
+}
+
+struct FixedPointDemo {
+    floor: Vec<FixedRect>,
+    peer: Sim,
+    rollback_peer: RollbackPeer,
+    desynced: bool,
+}
+
+impl Game for FixedPointDemo {
+    fn new(_engine: &mut Engine) -> Self {
+        Self {
+            floor: floor(),
+            peer: Sim::new(),
+            rollback_peer: RollbackPeer::new(),
+            desynced: false,
+        }
+    }
+
+    fn update(&mut self, _engine: &Engine, _frame: &mut Frame) {}
+
+    fn fixed_update(&mut self, _engine: &Engine) {
+        self.peer.advance(&self.floor);
+        self.rollback_peer.advance(&self.floor);
+        self.desynced |= self.peer.checksum() != self.rollback_peer.sim.checksum();
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(20, 22, 30, 255);
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+
+        for rect in &self.floor {
+            let r = rect.to_rect();
+            canvas.rect(
+                r.x,
+                r.y,
+                r.width,
+                r.height,
+                Color::from_rgba8(70, 76, 96, 255),
+            );
+        }
+        for crate_ in &self.peer.crates {
+            let r = crate_.body.to_rect();
+            // Squash about the crate's base, widening as it flattens.
+            let squash = crate_.squash.value().to_f32();
+            let height = r.height * squash;
+            let width = r.width / squash.sqrt();
+            canvas.rect(
+                r.x + (r.width - width) / 2.0,
+                r.y,
+                width,
+                height,
+                Color::from_rgba8(214, 160, 90, 255),
+            );
+        }
+
+        canvas.text(
+            -hw + 12.0,
+            hh - 24.0,
+            &format!(
+                "Tick {}  |  {} crates  |  peer {:016x}",
+                self.peer.tick,
+                self.peer.crates.len(),
+                self.peer.checksum()
+            ),
+            14.0,
+            Color::WHITE,
+        );
+        let (status, color) = if self.desynced {
+            ("DESYNC", Color::from_rgba8(240, 90, 80, 255))
+        } else {
+            ("in sync", Color::from_rgba8(120, 220, 150, 255))
+        };
+        canvas.text(
+            -hw + 12.0,
+            hh - 44.0,
+            &format!(
+                "Rollback peer {:016x} after {} rewinds of {ROLLBACK_TICKS} ticks: {status}",
+                self.rollback_peer.sim.checksum(),
+                self.rollback_peer.rollbacks,
+            ),
+            14.0,
+            color,
+        );
+    }
+}
+
+fn main() {
+    rengine::run::<FixedPointDemo>(EngineConfig {
+        title: "Feature: Fixed-Point Simulation".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_peer_that_rolls_back_stays_bit_identical() {
+        let floor = floor();
+        let mut peer = Sim::new();
+        let mut rollback_peer = RollbackPeer::new();
+        for _ in 0..600 {
+            peer.advance(&floor);
+            rollback_peer.advance(&floor);
+            assert_eq!(peer.checksum(), rollback_peer.sim.checksum());
+        }
+        assert!(rollback_peer.rollbacks > 10);
+    }
+
+    #[test]
+    fn crates_come_to_rest_on_the_floor() {
+        let floor = floor();
+        let mut sim = Sim::new();
+        for _ in 0..SPAWN_EVERY * 4 {
+            sim.advance(&floor);
+        }
+        let first = &sim.crates[0];
+        assert_eq!(first.velocity.y, Fixed::ZERO);
+        assert!(floor
+            .iter()
+            .any(|step| step.y + step.height == first.body.y));
+    }
+}
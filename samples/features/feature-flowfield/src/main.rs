@@ -0,0 +1,302 @@
+//! Feature: flow-field navigation for crowds.
+//!
+//! Hundreds of agents head for the goal cells through a walled level. A
+//! single [`FlowField`] is integrated outwards from the goals and every agent
+//! samples it, so the cost no longer grows with the number of agents.
+//! [`FlowCrowd`] turns the flow into velocities and pushes close neighbours
+//! apart, so the swarm spreads through the doors instead of collapsing onto
+//! one line. Toggling the doors edits the [`NavGrid`] and
+//! [`FlowField::update`] repairs only the cells whose routes they touched.
+
+use rengine::*;
+
+const COLS: usize = 50;
+const ROWS: usize = 34;
+const CELL: f32 = 16.0;
+const AGENT_RADIUS: f32 = 4.0;
+const SPEED: f32 = 70.0;
+const START_AGENTS: usize = 300;
+
+/// The two doors, each a run of cells in a wall.
+const DOORS: [[(usize, usize); 3]; 2] = [
+    [(16, 10), (16, 11), (16, 12)],
+    [(33, 20), (33, 21), (33, 22)],
+];
+
+struct Level {
+    grid: NavGrid,
+    field: FlowField,
+    doors_open: bool,
+    /// Cells re-integrated by the last update, against the full grid.
+    last_update: usize,
+}
+
+impl Level {
+    fn new() -> Self {
+        let origin = -Vec2::new(COLS as f32, ROWS as f32) * CELL / 2.0;
+        let mut grid = NavGrid::new(COLS, ROWS, CELL).with_origin(origin);
+        for row in 0..ROWS {
+            for col in 0..COLS {
+                let border = col == 0 || row == 0 || col == COLS - 1 || row == ROWS - 1;
+                let wall = border
+                    || (col == 16 && row != 26 && row != 27)
+                    || (col == 33 && row != 6 && row != 7)
+                    || (row == 17 && (4..12).contains(&col));
+                if wall {
+                    grid.set_cost(col, row, None);
+                }
+            }
+        }
+        for (col, row) in DOORS.iter().flatten() {
+            grid.set_cost(*col, *row, Some(1.0));
+        }
+        let field = FlowField::new(&grid, &NavAgent::new(AGENT_RADIUS), &[(45, 16)]);
+        Self {
+            grid,
+            field,
+            doors_open: true,
+            last_update: 0,
+        }
+    }
+
+    fn toggle_doors(&mut self) {
+        self.doors_open = !self.doors_open;
+        let cost = self.doors_open.then_some(1.0);
+        for (col, row) in DOORS.iter().flatten() {
+            self.grid.set_cost(*col, *row, cost);
+        }
+        self.last_update = self.field.update(&self.grid);
+    }
+
+    /// Move `position` by `delta`, one axis at a time, stopping at blocked
+    /// cells.
+    fn slide(&self, position: Vec2, delta: Vec2) -> Vec2 {
+        let open = |p: Vec2| {
+            [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)]
+                .iter()
+                .all(|&(sx, sy)| {
+                    let corner = p + Vec2::new(sx, sy) * AGENT_RADIUS;
+                    self.grid
+                        .cell_at(corner)
+                        .is_some_and(|(col, row)| self.grid.cost(col, row).is_some())
+                })
+        };
+        let mut p = position;
+        if open(p + Vec2::new(delta.x, 0.0)) {
+            p.x += delta.x;
+        }
+        if open(p + Vec2::new(0.0, delta.y)) {
+            p.y += delta.y;
+        }
+        p
+    }
+}
+
+fn spawn(level: &Level, rng: &mut Rng, count: usize) -> Vec<CrowdAgent> {
+    let mut agents = Vec::with_capacity(count);
+    while agents.len() < count {
+        let col = 1 + rng.usize(14);
+        let row = 1 + rng.usize(ROWS - 2);
+        if level.grid.cost(col, row).is_some() {
+            let jitter = Vec2::new(rng.f32(), rng.f32()) - 0.5;
+            agents.push(CrowdAgent::new(
+                level.grid.cell_center(col, row) + jitter * CELL * 0.5,
+            ));
+        }
+    }
+    agents
+}
+
+/// Steer and move every agent for one frame.
+fn step(level: &Level, crowd: &mut FlowCrowd, agents: &mut [CrowdAgent], dt: f32) {
+    crowd.steer(&level.field, agents, dt);
+    for agent in agents.iter_mut() {
+        let moved = level.slide(agent.position, agent.velocity * dt);
+        // Blocked axes stop dead instead of pushing into the wall.
+        agent.velocity = (moved - agent.position) / dt;
+        agent.position = moved;
+    }
+}
+
+struct FlowDemo {
+    level: Level,
+    crowd: FlowCrowd,
+    agents: Vec<CrowdAgent>,
+    rng: Rng,
+    show_flow: bool,
+}
+
+impl Game for FlowDemo {
+    fn new(_engine: &mut Engine) -> Self {
+        let level = Level::new();
+        let mut rng = Rng::new(3);
+        let agents = spawn(&level, &mut rng, START_AGENTS);
+        Self {
+            level,
+            crowd: FlowCrowd::new(SPEED).with_separation(AGENT_RADIUS * 2.5, 1.5),
+            agents,
+            rng,
+            show_flow: false,
+        }
+    }
+
+    fn update(&mut self, engine: &Engine, frame: &mut Frame) {
+        let input = engine.input();
+        let (mx, my) = input.mouse_position();
+        let mouse = frame.camera.screen_to_world(Vec2::new(mx, my));
+        if let Some(cell) = self.level.grid.cell_at(mouse) {
+            if input.is_mouse_pressed(0) {
+                self.level.field.set_goals(&self.level.grid, &[cell]);
+                self.level.last_update = COLS * ROWS;
+            } else if input.is_mouse_pressed(1) {
+                self.level.field.add_goal(&self.level.grid, cell);
+            }
+        }
+        if input.is_key_pressed(KeyCode::KeyD) {
+            self.level.toggle_doors();
+        }
+        if input.is_key_pressed(KeyCode::KeyF) {
+            self.show_flow = !self.show_flow;
+        }
+        if input.is_key_pressed(KeyCode::Space) {
+            let more = spawn(&self.level, &mut self.rng, 100);
+            self.agents.extend(more);
+        }
+        step(
+            &self.level,
+            &mut self.crowd,
+            &mut self.agents,
+            engine.dt().min(1.0 / 30.0),
+        );
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(14, 14, 20, 255);
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+
+        let level = &self.level;
+        let far = (COLS + ROWS) as f32;
+        for row in 0..ROWS {
+            for col in 0..COLS {
+                let color = match (level.grid.cost(col, row), level.field.distance(col, row)) {
+                    (None, _) => Color::from_rgba8(70, 74, 86, 255),
+                    (Some(_), None) => Color::from_rgba8(40, 20, 24, 255),
+                    (Some(_), Some(distance)) => {
+                        let t = (1.0 - distance / far).clamp(0.0, 1.0);
+                        Color::new(0.08 + 0.1 * t, 0.1 + 0.25 * t, 0.16 + 0.2 * t, 1.0)
+                    }
+                };
+                let p = level.grid.origin() + Vec2::new(col as f32, row as f32) * CELL;
+                canvas.rect(p.x, p.y, CELL - 1.0, CELL - 1.0, color);
+                if self.show_flow {
+                    let dir = level.field.direction(col, row);
+                    if dir != Vec2::ZERO {
+                        let c = level.grid.cell_center(col, row);
+                        let tip = c + dir * CELL * 0.4;
+                        canvas.line(
+                            c.x,
+                            c.y,
+                            tip.x,
+                            tip.y,
+                            1.0,
+                            Color::from_rgba8(140, 170, 210, 255),
+                        );
+                    }
+                }
+            }
+        }
+        for &(col, row) in level.field.goals() {
+            let c = level.grid.cell_center(col, row);
+            canvas.circle(
+                c.x,
+                c.y,
+                CELL * 0.6,
+                2.0,
+                20,
+                Color::from_rgba8(255, 220, 90, 255),
+            );
+        }
+        for agent in &self.agents {
+            canvas.circle_filled(
+                agent.position.x,
+                agent.position.y,
+                AGENT_RADIUS,
+                8,
+                Color::from_rgba8(240, 120, 90, 255),
+            );
+        }
+
+        canvas.text(
+            -hw + 12.0,
+            hh - 24.0,
+            "LMB set goal, RMB add goal, D toggle doors, F show flow, Space more agents",
+            14.0,
+            Color::WHITE,
+        );
+        canvas.text(
+            -hw + 12.0,
+            hh - 44.0,
+            &format!(
+                "Agents: {}  |  Doors {}  |  Last update re-integrated {} of {} cells",
+                self.agents.len(),
+                if level.doors_open { "open" } else { "shut" },
+                level.last_update,
+                COLS * ROWS,
+            ),
+            14.0,
+            Color::from_rgba8(180, 200, 230, 255),
+        );
+    }
+}
+
+fn main() {
+    rengine::run::<FlowDemo>(EngineConfig {
+        title: "Feature: Flow Fields".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doors_reroute_the_field_without_a_full_rebuild() {
+        let mut level = Level::new();
+        let start = level.field.distance(3, 5).unwrap();
+        level.toggle_doors();
+        let detour = level.field.distance(3, 5).unwrap();
+        assert!(detour > start);
+        assert!(level.last_update < COLS * ROWS / 2);
+        level.toggle_doors();
+        assert_eq!(level.field.distance(3, 5), Some(start));
+    }
+
+    #[test]
+    fn the_crowd_reaches_the_goal_without_passing_through_walls() {
+        let level = Level::new();
+        let mut rng = Rng::new(1);
+        let mut agents = spawn(&level, &mut rng, 60);
+        let mut crowd = FlowCrowd::new(SPEED).with_separation(AGENT_RADIUS * 2.5, 1.5);
+        for _ in 0..60 * 20 {
+            step(&level, &mut crowd, &mut agents, 1.0 / 60.0);
+        }
+        let goal = level.grid.cell_center(45, 16);
+        let arrived = agents
+            .iter()
+            .filter(|agent| agent.position.distance(goal) < CELL * 4.0)
+            .count();
+        assert!(arrived >= 50, "{arrived} of 60 arrived");
+        for agent in &agents {
+            let (col, row) = level.grid.cell_at(agent.position).unwrap();
+            assert!(level.grid.cost(col, row).is_some());
+        }
+    }
+}
@@ -13,6 +13,13 @@ impl Game for FontsDemo {
         let assets = engine
             .load_asset_bundle("fonts.assets.json")
             .expect("failed to load font asset bundle");
+        // The pixel face has no box-drawing glyphs; borrow them from mono.
+        // Mono itself draws from a distance field, so every size below comes
+        // out of one atlas and the effects row can outline and glow.
+        if let Some(mono) = assets.font_id("mono") {
+            engine.set_font_fallbacks(FontId::DEFAULT, &[mono]);
+            engine.set_font_mode(mono, FontMode::Sdf);
+        }
         Self {
             assets,
             quit: false,
@@ -130,6 +137,54 @@ impl Game for FontsDemo {
         );
         y -= 40.0;
 
+        canvas.text(
+            col_x,
+            y,
+            "Unicode (glyph cache + fallback):",
+            heading,
+            label_color,
+        );
+        y -= 30.0;
+        canvas.text(
+            col_x + 10.0,
+            y,
+            "Cr\u{e8}me br\u{fb}l\u{e9}e \u{2014} \u{c5}ngstr\u{f6}m \u{2014} \u{2192} \u{221e}",
+            body,
+            text_color,
+        );
+        y -= 24.0;
+        canvas.text(
+            col_x + 10.0,
+            y,
+            "\u{250c}\u{2500}\u{2500}\u{2510} box drawing from the mono fallback",
+            body,
+            text_color,
+        );
+        y -= 40.0;
+
+        canvas.text(col_x, y, "SDF effects (mono):", heading, label_color);
+        y -= 40.0;
+        let effects = [
+            (
+                "Outline",
+                TextEffects::NONE.with_outline(2.0, Color::from_rgba8(200, 40, 40, 255)),
+            ),
+            (
+                "Shadow",
+                TextEffects::NONE.with_shadow(3.0, -3.0, 2.0, Color::from_rgba8(0, 0, 0, 200)),
+            ),
+            (
+                "Glow",
+                TextEffects::NONE.with_glow(5.0, Color::from_rgba8(90, 200, 255, 255)),
+            ),
+        ];
+        let mut ex = col_x + 10.0;
+        for (label, effect) in &effects {
+            canvas.text_with_effects(ex, y, label, 32.0, text_color, mono, effect);
+            ex += mono.measure_text(label, 32.0).0 + 40.0;
+        }
+        y -= 56.0;
+
         canvas.text(col_x, y, "Size comparison:", heading, label_color);
         y -= 30.0;
         let sizes = [12.0, 16.0, 24.0, 32.0];
@@ -193,7 +248,7 @@ fn main() {
     let config = EngineConfig {
         title: "Feature: Multiple Fonts".into(),
         width: 960,
-        height: 700,
+        height: 880,
         show_fps: false,
         ..Default::default()
     };
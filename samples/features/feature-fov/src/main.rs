@@ -0,0 +1,353 @@
+//! Feature: field of view and line of sight.
+//!
+//! A generated dungeon of rooms and corridors, explored one step at a time.
+//! Every move recomputes the player's [`FieldOfView`] by symmetric
+//! shadowcasting and folds it into an explored [`VisibilitySet`], which
+//! [`TileMap::draw_fog`] turns into fog of war: lit cells in full, remembered
+//! ones dimmed, the rest unknown. Guards run the same field of view for
+//! perception and raise the alarm when the player steps into it. Holding the
+//! mouse button traces a [`line_of_sight_with`] check to the cursor.
+
+use rengine::pixelart::PixelCanvas;
+use rengine::*;
+
+const COLS: usize = 40;
+const ROWS: usize = 30;
+const CELL: f32 = 20.0;
+const SIGHT: f32 = 8.0;
+const GUARD_SIGHT: f32 = 5.0;
+
+/// The level's layout: which cells are walls, and where rooms are.
+struct Dungeon {
+    walls: Vec<bool>,
+    /// The centre cell of every room, in the order they were carved.
+    rooms: Vec<(usize, usize)>,
+}
+
+impl Dungeon {
+    /// Carve rooms into solid rock and join each to the previous one with an
+    /// L-shaped corridor. Some rooms get a pillar to hide behind.
+    fn generate(seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut walls = vec![true; COLS * ROWS];
+        let mut rooms: Vec<(usize, usize)> = Vec::new();
+        let mut carve = |col: usize, row: usize| walls[row * COLS + col] = false;
+        for _ in 0..9 {
+            let (w, h) = (4 + rng.usize(6), 4 + rng.usize(4));
+            let (col, row) = (1 + rng.usize(COLS - w - 2), 1 + rng.usize(ROWS - h - 2));
+            for r in row..row + h {
+                for c in col..col + w {
+                    carve(c, r);
+                }
+            }
+            let centre = (col + w / 2, row + h / 2);
+            if let Some(&(pc, pr)) = rooms.last() {
+                for c in pc.min(centre.0)..=pc.max(centre.0) {
+                    carve(c, pr);
+                }
+                for r in pr.min(centre.1)..=pr.max(centre.1) {
+                    carve(centre.0, r);
+                }
+            }
+            rooms.push(centre);
+        }
+        let mut dungeon = Self { walls, rooms };
+        for i in 1..dungeon.rooms.len() {
+            let (col, row) = dungeon.rooms[i];
+            if rng.chance(0.5) && !dungeon.is_wall(col + 2, row + 1) {
+                dungeon.walls[(row + 1) * COLS + col + 2] = true;
+            }
+        }
+        dungeon
+    }
+
+    /// Whether a cell blocks movement and sight; off the map counts as wall.
+    fn is_wall(&self, col: usize, row: usize) -> bool {
+        col >= COLS || row >= ROWS || self.walls[row * COLS + col]
+    }
+
+    fn tilemap(&self, wall: TileDef, floor: TileDef) -> TileMap {
+        let origin = -Vec2::new(COLS as f32, ROWS as f32) * CELL / 2.0;
+        let mut map = TileMap::new(COLS, ROWS, CELL).with_origin(origin);
+        let wall = map.add_tile(wall.with_opaque(true));
+        let floor = map.add_tile(floor.with_collision(false));
+        for row in 0..ROWS {
+            for col in 0..COLS {
+                map.set(
+                    col,
+                    row,
+                    Some(if self.is_wall(col, row) { wall } else { floor }),
+                );
+            }
+        }
+        map
+    }
+}
+
+/// The player's and guards' view of the dungeon.
+struct Explorer {
+    player: (usize, usize),
+    guards: Vec<(usize, usize)>,
+    visible: VisibilitySet,
+    explored: VisibilitySet,
+}
+
+impl Explorer {
+    fn new(dungeon: &Dungeon) -> Self {
+        let mut explorer = Self {
+            player: dungeon.rooms[0],
+            guards: dungeon.rooms[2..].iter().step_by(2).copied().collect(),
+            visible: VisibilitySet::new(COLS, ROWS),
+            explored: VisibilitySet::new(COLS, ROWS),
+        };
+        explorer.look(dungeon);
+        explorer
+    }
+
+    /// Step one cell unless a wall is in the way, then look around.
+    fn step(&mut self, dungeon: &Dungeon, (dx, dy): (isize, isize)) {
+        let col = self.player.0.wrapping_add_signed(dx);
+        let row = self.player.1.wrapping_add_signed(dy);
+        if !dungeon.is_wall(col, row) {
+            self.player = (col, row);
+            self.look(dungeon);
+        }
+    }
+
+    fn look(&mut self, dungeon: &Dungeon) {
+        FieldOfView::new(self.player)
+            .with_radius(SIGHT)
+            .compute_with(
+                COLS,
+                ROWS,
+                |col, row| dungeon.is_wall(col, row),
+                &mut self.visible,
+            );
+        self.explored.union_with(&self.visible);
+    }
+
+    /// Whether a guard's own field of view takes in the player.
+    fn spotted_by(&self, dungeon: &Dungeon, guard: (usize, usize)) -> bool {
+        let mut view = VisibilitySet::new(COLS, ROWS);
+        FieldOfView::new(guard)
+            .with_radius(GUARD_SIGHT)
+            .compute_with(COLS, ROWS, |col, row| dungeon.is_wall(col, row), &mut view);
+        view.contains(self.player.0, self.player.1)
+    }
+}
+
+fn make_texture(engine: &mut Engine, base: Color, speck: Color) -> TextureId {
+    let mut pc = PixelCanvas::new(8, 8);
+    pc.fill(base);
+    for (x, y) in [(1, 2), (5, 1), (3, 5), (6, 6)] {
+        pc.set(x, y, speck);
+    }
+    let bytes = pc.into_bytes();
+    engine.create_texture(8, 8, &bytes)
+}
+
+struct FovDemo {
+    dungeon: Dungeon,
+    map: TileMap,
+    explorer: Explorer,
+    reveal: bool,
+}
+
+impl Game for FovDemo {
+    fn new(engine: &mut Engine) -> Self {
+        let wall = make_texture(
+            engine,
+            Color::from_rgba8(96, 84, 76, 255),
+            Color::from_rgba8(70, 60, 54, 255),
+        );
+        let floor = make_texture(
+            engine,
+            Color::from_rgba8(52, 58, 70, 255),
+            Color::from_rgba8(62, 70, 84, 255),
+        );
+        let dungeon = Dungeon::generate(11);
+        let map = dungeon.tilemap(TileDef::solid(wall), TileDef::solid(floor));
+        let explorer = Explorer::new(&dungeon);
+        Self {
+            dungeon,
+            map,
+            explorer,
+            reveal: false,
+        }
+    }
+
+    fn update(&mut self, engine: &Engine, _frame: &mut Frame) {
+        let input = engine.input();
+        for (keys, step) in [
+            ([KeyCode::ArrowUp, KeyCode::KeyW], (0, 1)),
+            ([KeyCode::ArrowDown, KeyCode::KeyS], (0, -1)),
+            ([KeyCode::ArrowLeft, KeyCode::KeyA], (-1, 0)),
+            ([KeyCode::ArrowRight, KeyCode::KeyD], (1, 0)),
+        ] {
+            if keys.iter().any(|&key| input.is_key_pressed(key)) {
+                self.explorer.step(&self.dungeon, step);
+            }
+        }
+        if input.is_key_pressed(KeyCode::KeyR) {
+            self.reveal = !self.reveal;
+        }
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(6, 6, 10, 255);
+        let input = engine.input();
+        let (mx, my) = input.mouse_position();
+        let mouse = frame.camera.screen_to_world(Vec2::new(mx, my));
+        let explorer = &self.explorer;
+        if self.reveal {
+            self.map.draw(frame);
+        } else {
+            self.map.draw_fog(
+                frame,
+                &explorer.visible,
+                &explorer.explored,
+                Color::new(0.35, 0.35, 0.45, 1.0),
+            );
+        }
+
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let map = &self.map;
+        let canvas = frame.canvas(0);
+        let centre = |(col, row): (usize, usize)| map.cell_rect(col, row).center();
+
+        if input.is_mouse_down(0) {
+            if let Some(target) = map.cell_at(mouse) {
+                let clear = line_of_sight_with(explorer.player, target, |col, row| {
+                    self.dungeon.is_wall(col, row)
+                });
+                let color = if clear {
+                    Color::from_rgba8(120, 230, 140, 120)
+                } else {
+                    Color::from_rgba8(240, 90, 80, 120)
+                };
+                for cell in GridLine::new(explorer.player, target) {
+                    let r = map.cell_rect(cell.0, cell.1);
+                    canvas.rect(r.x, r.y, r.width, r.height, color);
+                }
+            }
+        }
+
+        let mut alarms = 0;
+        for &guard in &explorer.guards {
+            let spotted = explorer.spotted_by(&self.dungeon, guard);
+            alarms += usize::from(spotted);
+            if self.reveal || explorer.visible.contains(guard.0, guard.1) {
+                let p = centre(guard);
+                let color = if spotted {
+                    Color::from_rgba8(255, 80, 70, 255)
+                } else {
+                    Color::from_rgba8(230, 190, 90, 255)
+                };
+                canvas.circle_filled(p.x, p.y, CELL * 0.35, 12, color);
+            }
+        }
+        let p = centre(explorer.player);
+        canvas.circle_filled(
+            p.x,
+            p.y,
+            CELL * 0.35,
+            12,
+            Color::from_rgba8(110, 180, 255, 255),
+        );
+
+        canvas.text(
+            -hw + 12.0,
+            hh - 24.0,
+            "WASD / arrows step, hold LMB to test line of sight, R reveal map",
+            14.0,
+            Color::WHITE,
+        );
+        canvas.text(
+            -hw + 12.0,
+            hh - 44.0,
+            &format!(
+                "In view: {} cells  |  Explored: {}  |  Guards alerted: {alarms}",
+                explorer.visible.len(),
+                explorer.explored.len(),
+            ),
+            14.0,
+            Color::from_rgba8(180, 200, 230, 255),
+        );
+    }
+}
+
+fn main() {
+    rengine::run::<FovDemo>(EngineConfig {
+        title: "Feature: Field of View".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exploring_remembers_what_was_seen_and_walls_block_sight() {
+        let dungeon = Dungeon::generate(11);
+        let mut explorer = Explorer::new(&dungeon);
+        let first = explorer.visible.clone();
+        for cell in first.iter() {
+            let distance = Vec2::new(
+                cell.0 as f32 - explorer.player.0 as f32,
+                cell.1 as f32 - explorer.player.1 as f32,
+            )
+            .length();
+            assert!(distance <= SIGHT + 0.5);
+        }
+        // Nothing seen lies behind a wall from the player's point of view.
+        for (col, row) in first.iter().filter(|&(c, r)| !dungeon.is_wall(c, r)) {
+            assert!(line_of_sight_with(explorer.player, (col, row), |c, r| {
+                dungeon.is_wall(c, r)
+            }));
+        }
+
+        for step in [(1, 0), (1, 0), (0, 1), (0, 1), (-1, 0), (0, -1)] {
+            explorer.step(&dungeon, step);
+            let mut union = explorer.visible.clone();
+            union.union_with(&explorer.explored);
+            assert_eq!(union, explorer.explored);
+        }
+        let mut before = first;
+        before.union_with(&explorer.explored);
+        assert_eq!(before, explorer.explored);
+        assert!(!dungeon.is_wall(explorer.player.0, explorer.player.1));
+    }
+
+    #[test]
+    fn guards_spot_the_player_exactly_when_the_player_could_see_them() {
+        let dungeon = Dungeon::generate(11);
+        let mut explorer = Explorer::new(&dungeon);
+        let guard = explorer.guards[0];
+        explorer.player = guard;
+        explorer.player.0 -= 1;
+        let mut alerted = 0;
+        // Walk around the guard's room; sight is symmetric, so the guard
+        // notices the player exactly when the player's view holds the guard.
+        for step in [(-1, 0), (-1, 0), (0, 1), (0, 1), (1, 0), (1, 0), (1, 0)] {
+            explorer.step(&dungeon, step);
+            // Cells count as in range when their centres are, rounded out.
+            let near = Vec2::new(
+                explorer.player.0 as f32 - guard.0 as f32,
+                explorer.player.1 as f32 - guard.1 as f32,
+            )
+            .length()
+                <= GUARD_SIGHT + 0.5;
+            let spotted = explorer.spotted_by(&dungeon, guard);
+            assert_eq!(spotted, near && explorer.visible.contains(guard.0, guard.1));
+            alerted += usize::from(spotted);
+        }
+        assert!(alerted > 0);
+    }
+}
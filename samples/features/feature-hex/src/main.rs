@@ -0,0 +1,407 @@
+//! Feature: hexagonal and staggered grids.
+//!
+//! The same [`TileMap`] laid out three ways: pointy-topped hexes, flat-topped
+//! hexes and a staggered isometric grid. Walls are ordinary colliding tiles,
+//! so the walker slides along hex sides and diamond edges through
+//! [`TileMap::collide_rect`] exactly as it would against square cells, and
+//! [`TileMap::draw`] puts each wall sprite on its cell. The hover overlay
+//! shows each grid's own coordinate maths: the cells within reach of the
+//! walker, the ring at the edge of that reach, and the line of cells from the
+//! walker to the cursor.
+
+use rengine::pixelart::PixelCanvas;
+use rengine::*;
+
+const COLS: usize = 14;
+const ROWS: usize = 11;
+const WALL: usize = 0;
+const REACH: u32 = 3;
+const WALKER: f32 = 12.0;
+const SPEED: f32 = 140.0;
+
+/// One of the demo's grids, with the coordinate maths that goes with it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Grid {
+    Hex(HexLayout),
+    Staggered(StaggeredIso),
+}
+
+impl Grid {
+    fn all() -> [Grid; 3] {
+        [
+            Grid::Hex(HexLayout::new(HexOrientation::Pointy, 22.0)),
+            Grid::Hex(HexLayout::new(HexOrientation::Flat, 22.0).with_stagger(Stagger::Even)),
+            Grid::Staggered(StaggeredIso::new(56.0, 28.0)),
+        ]
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Grid::Hex(layout) if layout.orientation == HexOrientation::Pointy => "pointy hexes",
+            Grid::Hex(_) => "flat hexes",
+            Grid::Staggered(_) => "staggered isometric",
+        }
+    }
+
+    fn layout(self) -> GridLayout {
+        match self {
+            Grid::Hex(layout) => GridLayout::Hex(layout),
+            Grid::Staggered(grid) => GridLayout::Staggered(grid),
+        }
+    }
+
+    fn hex(layout: HexLayout, (col, row): (i32, i32)) -> Hex {
+        Hex::from_offset(col, row, layout.orientation, layout.stagger)
+    }
+
+    fn offsets(layout: HexLayout, hexes: Vec<Hex>) -> Vec<(i32, i32)> {
+        hexes
+            .into_iter()
+            .map(|hex| hex.to_offset(layout.orientation, layout.stagger))
+            .collect()
+    }
+
+    fn distance(self, a: (i32, i32), b: (i32, i32)) -> i32 {
+        match self {
+            Grid::Hex(layout) => Self::hex(layout, a).distance(Self::hex(layout, b)),
+            Grid::Staggered(grid) => grid.distance(a, b),
+        }
+    }
+
+    fn range(self, center: (i32, i32), radius: u32) -> Vec<(i32, i32)> {
+        match self {
+            Grid::Hex(layout) => Self::offsets(layout, Self::hex(layout, center).range(radius)),
+            Grid::Staggered(grid) => grid.range(center, radius),
+        }
+    }
+
+    fn ring(self, center: (i32, i32), radius: u32) -> Vec<(i32, i32)> {
+        match self {
+            Grid::Hex(layout) => Self::offsets(layout, Self::hex(layout, center).ring(radius)),
+            Grid::Staggered(grid) => grid.ring(center, radius),
+        }
+    }
+
+    fn line(self, from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+        match self {
+            Grid::Hex(layout) => Self::offsets(
+                layout,
+                Self::hex(layout, from).line_to(Self::hex(layout, to)),
+            ),
+            Grid::Staggered(grid) => grid.line(from, to),
+        }
+    }
+}
+
+fn signed((col, row): (usize, usize)) -> (i32, i32) {
+    (col as i32, row as i32)
+}
+
+struct Board {
+    grid: Grid,
+    map: TileMap,
+    walker: Rect,
+}
+
+impl Board {
+    /// A map of `grid` centred on the origin, scattered with walls that keep
+    /// clear of the walker's starting cell. Cells hold [`WALL`] before any
+    /// tile is defined, which still collides; the caller adds the sprite.
+    fn new(grid: Grid, seed: u64) -> Self {
+        let mut map = TileMap::new(COLS, ROWS, 1.0).with_layout(grid.layout());
+        map.origin = -map.bounds().center();
+        let start = (COLS / 2, ROWS / 2);
+        let mut rng = Rng::new(seed);
+        for row in 0..ROWS {
+            for col in 0..COLS {
+                if grid.distance(signed((col, row)), signed(start)) > 1 && rng.chance(0.22) {
+                    map.set(col, row, Some(WALL));
+                }
+            }
+        }
+        let size = Vec2::splat(WALKER);
+        let walker = Rect::from_pos_size(map.cell_center(start.0, start.1) - size / 2.0, size);
+        Self { grid, map, walker }
+    }
+
+    fn is_wall(&self, (col, row): (i32, i32)) -> bool {
+        col >= 0 && row >= 0 && self.map.get(col as usize, row as usize) == Some(WALL)
+    }
+
+    fn on_map(&self, (col, row): (i32, i32)) -> bool {
+        (0..COLS as i32).contains(&col) && (0..ROWS as i32).contains(&row)
+    }
+
+    fn walker_cell(&self) -> (usize, usize) {
+        self.map
+            .cell_at(self.walker.center())
+            .unwrap_or((COLS / 2, ROWS / 2))
+    }
+
+    /// Move the walker, pushed back out of any wall and kept on the map.
+    fn walk(&mut self, delta: Vec2) {
+        self.walker.x += delta.x;
+        self.walker.y += delta.y;
+        if let Some(push) = self.map.collide_rect(&self.walker) {
+            self.walker.x += push.x;
+            self.walker.y += push.y;
+        }
+        let bounds = self.map.bounds();
+        self.walker.x = self.walker.x.clamp(bounds.left(), bounds.right() - WALKER);
+        self.walker.y = self.walker.y.clamp(bounds.bottom(), bounds.top() - WALKER);
+    }
+
+    /// The cells from the walker to `target` and whether none of the cells
+    /// between them is a wall.
+    fn sight(&self, target: (usize, usize)) -> (Vec<(i32, i32)>, bool) {
+        let line = self.grid.line(signed(self.walker_cell()), signed(target));
+        let inner = &line[1..line.len().saturating_sub(1).max(1)];
+        let clear = !inner.iter().any(|&cell| self.is_wall(cell));
+        (line, clear)
+    }
+}
+
+/// A texture of one cell's outline, for wall sprites stretched over the
+/// cell's bounding box.
+fn cell_texture(engine: &mut Engine, map: &TileMap, base: Color, edge: Color) -> TextureId {
+    let rect = map.cell_rect(0, 0);
+    let outline = map.cell_outline(0, 0);
+    let width = 48;
+    let height = (width as f32 * rect.height / rect.width).round() as u32;
+    // How far inside every side a point is, in world units.
+    let depth = |p: Vec2| {
+        outline
+            .iter()
+            .zip(outline.iter().cycle().skip(1))
+            .map(|(&a, &b)| (b - a).perp().normalize().dot(p - a))
+            .fold(f32::INFINITY, f32::min)
+    };
+    let mut pc = PixelCanvas::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let p = Vec2::new(
+                rect.x + (x as f32 + 0.5) / width as f32 * rect.width,
+                rect.top() - (y as f32 + 0.5) / height as f32 * rect.height,
+            );
+            match depth(p) {
+                d if d > 3.0 => pc.set(x as i32, y as i32, base),
+                d if d >= 0.0 => pc.set(x as i32, y as i32, edge),
+                _ => {}
+            }
+        }
+    }
+    let bytes = pc.into_bytes();
+    engine.create_texture(width, height, &bytes)
+}
+
+struct HexDemo {
+    grids: [Grid; 3],
+    walls: Vec<TextureId>,
+    current: usize,
+    board: Board,
+    hover: Option<(usize, usize)>,
+}
+
+impl HexDemo {
+    fn board(&self, index: usize) -> Board {
+        let mut board = Board::new(self.grids[index], 4);
+        board.map.add_tile(TileDef::solid(self.walls[index]));
+        board
+    }
+}
+
+impl Game for HexDemo {
+    fn new(engine: &mut Engine) -> Self {
+        let grids = Grid::all();
+        let walls = grids
+            .iter()
+            .map(|&grid| {
+                let map = TileMap::new(1, 1, 1.0).with_layout(grid.layout());
+                cell_texture(
+                    engine,
+                    &map,
+                    Color::from_rgba8(92, 84, 110, 255),
+                    Color::from_rgba8(140, 130, 170, 255),
+                )
+            })
+            .collect();
+        let mut demo = Self {
+            grids,
+            walls,
+            current: 0,
+            board: Board::new(grids[0], 4),
+            hover: None,
+        };
+        demo.board = demo.board(0);
+        demo
+    }
+
+    fn update(&mut self, engine: &Engine, frame: &mut Frame) {
+        let input = engine.input();
+        if input.is_key_pressed(KeyCode::Tab) {
+            self.current = (self.current + 1) % self.grids.len();
+            self.board = self.board(self.current);
+        }
+        let mut direction = Vec2::ZERO;
+        for (keys, step) in [
+            ([KeyCode::ArrowUp, KeyCode::KeyW], Vec2::Y),
+            ([KeyCode::ArrowDown, KeyCode::KeyS], Vec2::NEG_Y),
+            ([KeyCode::ArrowLeft, KeyCode::KeyA], Vec2::NEG_X),
+            ([KeyCode::ArrowRight, KeyCode::KeyD], Vec2::X),
+        ] {
+            if keys.iter().any(|&key| input.is_key_down(key)) {
+                direction += step;
+            }
+        }
+        let dt = engine.dt().min(1.0 / 30.0);
+        self.board.walk(direction.normalize_or_zero() * SPEED * dt);
+        let (mx, my) = input.mouse_position();
+        self.hover = self
+            .board
+            .map
+            .cell_at(frame.camera.screen_to_world(Vec2::new(mx, my)));
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(14, 16, 22, 255);
+        let board = &self.board;
+        let map = &board.map;
+        map.draw(frame);
+
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+        let outline = |(col, row): (i32, i32)| -> Vec<(f32, f32)> {
+            let mut points: Vec<(f32, f32)> = map
+                .cell_outline(col as usize, row as usize)
+                .iter()
+                .map(|p| (p.x, p.y))
+                .collect();
+            points.push(points[0]);
+            points
+        };
+
+        let home = signed(board.walker_cell());
+        for cell in board.grid.range(home, REACH) {
+            if board.on_map(cell) && !board.is_wall(cell) {
+                canvas.polygon(&outline(cell), Color::from_rgba8(50, 90, 70, 255));
+            }
+        }
+        for row in 0..ROWS as i32 {
+            for col in 0..COLS as i32 {
+                if !board.is_wall((col, row)) {
+                    canvas.polyline(
+                        &outline((col, row)),
+                        1.0,
+                        Color::from_rgba8(60, 66, 84, 255),
+                    );
+                }
+            }
+        }
+        for cell in board.grid.ring(home, REACH) {
+            if board.on_map(cell) {
+                canvas.polyline(&outline(cell), 2.0, Color::from_rgba8(120, 200, 150, 255));
+            }
+        }
+
+        let mut status = String::from("Hover a cell");
+        if let Some(target) = self.hover {
+            let (line, clear) = board.sight(target);
+            let color = if clear {
+                Color::from_rgba8(110, 180, 255, 110)
+            } else {
+                Color::from_rgba8(240, 90, 80, 110)
+            };
+            for &cell in &line {
+                canvas.polygon(&outline(cell), color);
+            }
+            status = format!(
+                "Cell {:?}: {} steps away, {}",
+                target,
+                board.grid.distance(home, signed(target)),
+                if clear { "in sight" } else { "blocked" },
+            );
+        }
+
+        let p = board.walker;
+        canvas.rect(
+            p.x,
+            p.y,
+            p.width,
+            p.height,
+            Color::from_rgba8(255, 200, 90, 255),
+        );
+
+        canvas.text(
+            -hw + 12.0,
+            hh - 24.0,
+            "WASD walk, Tab switch grid, hover to measure",
+            14.0,
+            Color::WHITE,
+        );
+        canvas.text(
+            -hw + 12.0,
+            hh - 44.0,
+            &format!("Grid: {}  |  {}", board.grid.name(), status),
+            14.0,
+            Color::from_rgba8(180, 200, 230, 255),
+        );
+    }
+}
+
+fn main() {
+    rengine::run::<HexDemo>(EngineConfig {
+        title: "Feature: Hex Grids".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_grid_finds_its_cells_and_measures_consistently() {
+        for grid in Grid::all() {
+            let board = Board::new(grid, 4);
+            for row in 0..ROWS {
+                for col in 0..COLS {
+                    let center = board.map.cell_center(col, row);
+                    assert_eq!(board.map.cell_at(center), Some((col, row)), "{grid:?}");
+                }
+            }
+            let home = signed(board.walker_cell());
+            assert_eq!(home, (COLS as i32 / 2, ROWS as i32 / 2));
+            for cell in board.grid.ring(home, REACH) {
+                assert_eq!(board.grid.distance(home, cell), REACH as i32);
+            }
+            let (line, _) = board.sight((0, 0));
+            assert_eq!(line.len() as i32, board.grid.distance(home, (0, 0)) + 1);
+            assert_eq!((line[0], line[line.len() - 1]), (home, (0, 0)));
+        }
+    }
+
+    #[test]
+    fn the_walker_slides_around_walls_on_every_grid() {
+        for grid in Grid::all() {
+            let mut board = Board::new(grid, 4);
+            let mut visited = std::collections::HashSet::new();
+            for step in 0..1200 {
+                let angle = (step / 90) as f32 * 2.4;
+                board.walk(Vec2::from_angle(angle) * SPEED / 60.0);
+                let cell = board.walker_cell();
+                assert!(
+                    !board.is_wall(signed(cell)),
+                    "{grid:?} in a wall at {cell:?}"
+                );
+                visited.insert(cell);
+            }
+            assert!(visited.len() > 10, "{grid:?} only reached {visited:?}");
+        }
+    }
+}
@@ -0,0 +1,385 @@
+//! Feature: physics joints.
+//!
+//! [`Joint2D`]s inside a [`PhysicsWorld2D`]: a hinged chain, a plank hung on
+//! two ropes that the player can stand on, a sign dangling from a hinge and a
+//! crate bobbing on a spring. The kinematic player fires a grappling hook at
+//! the ceiling and swings from it through [`constrain_kinematic`]. Tab
+//! toggles [`PhysicsWorld2D::debug_draw`], which outlines bodies, contacts
+//! and every joint.
+//!
+//! Coordinates are centered and y-up, so the canvas draws world space directly.
+
+use rengine::*;
+
+const MOVE_SPEED: f32 = 200.0;
+const SWING_ACCEL: f32 = 500.0;
+const JUMP_SPEED: f32 = 420.0;
+const HOOK_RANGE: f32 = 420.0;
+const REEL_SPEED: f32 = 150.0;
+const CHAIN_LINKS: usize = 8;
+const LINK_LENGTH: f32 = 18.0;
+
+fn level_solids() -> Vec<Solid2D> {
+    vec![
+        Solid2D::solid(Rect::new(-380.0, -240.0, 760.0, 30.0)),
+        Solid2D::solid(Rect::new(-380.0, -240.0, 24.0, 480.0)),
+        Solid2D::solid(Rect::new(356.0, -240.0, 24.0, 480.0)),
+        Solid2D::solid(Rect::new(-380.0, 210.0, 760.0, 30.0)),
+    ]
+}
+
+/// The rigid bodies, their joints, and the id of every chain joint from the
+/// ceiling down.
+fn initial_world() -> (PhysicsWorld2D, Vec<JointId>) {
+    let mut world = PhysicsWorld2D::default();
+
+    let mut chain = Vec::new();
+    let mut above = JointAnchor2D::World(Vec2::new(-260.0, 210.0));
+    for i in 0..CHAIN_LINKS {
+        let top = 210.0 - i as f32 * LINK_LENGTH;
+        let center = Vec2::new(-260.0, top - LINK_LENGTH / 2.0);
+        let link = world.insert(
+            RigidBody2D::dynamic(
+                Shape2D::capsule(
+                    center + Vec2::new(0.0, LINK_LENGTH / 2.0 - 3.0),
+                    center - Vec2::new(0.0, LINK_LENGTH / 2.0 - 3.0),
+                    3.0,
+                ),
+                1.0,
+            )
+            .with_damping(0.1, 0.5),
+        );
+        let pin = world.body_anchor(link, Vec2::new(-260.0, top)).unwrap();
+        chain.push(world.insert_joint(Joint2D::hinge(above, pin)));
+        above = world
+            .body_anchor(link, Vec2::new(-260.0, top - LINK_LENGTH))
+            .unwrap();
+    }
+
+    let plank = world.insert(
+        RigidBody2D::dynamic(
+            Shape2D::oriented_box(Vec2::new(-60.0, -40.0), Vec2::new(60.0, 6.0), 0.0),
+            1.0,
+        )
+        .with_damping(0.2, 0.5),
+    );
+    for x in [-110.0, -10.0] {
+        let end = world.body_anchor(plank, Vec2::new(x, -34.0)).unwrap();
+        world.insert_joint(Joint2D::rope(
+            JointAnchor2D::World(Vec2::new(x, 210.0)),
+            end,
+            244.0,
+        ));
+    }
+
+    let sign = world.insert(RigidBody2D::dynamic(
+        Shape2D::oriented_box(Vec2::new(120.0, 120.0), Vec2::new(36.0, 18.0), 0.0),
+        0.5,
+    ));
+    let pivot = world.body_anchor(sign, Vec2::new(120.0, 150.0)).unwrap();
+    world.insert_joint(Joint2D::hinge(
+        JointAnchor2D::World(Vec2::new(120.0, 150.0)),
+        pivot,
+    ));
+
+    let bob = world.insert(RigidBody2D::dynamic(
+        Shape2D::oriented_box(Vec2::new(280.0, 40.0), Vec2::splat(16.0), 0.0),
+        1.0,
+    ));
+    let top = world.body_anchor(bob, Vec2::new(280.0, 56.0)).unwrap();
+    world.insert_joint(Joint2D::spring(
+        JointAnchor2D::World(Vec2::new(280.0, 210.0)),
+        top,
+        120.0,
+        1.2,
+        0.1,
+    ));
+
+    (world, chain)
+}
+
+fn initial_player() -> KinematicBody2D {
+    KinematicBody2D::new(Rect::new(-20.0, -210.0, 20.0, 28.0)).with_ground_snap(4.0)
+}
+
+fn draw_shape(canvas: &mut Canvas, shape: &Shape2D, color: Color) {
+    match *shape {
+        Shape2D::Aabb(r) => canvas.rect(r.x, r.y, r.width, r.height, color),
+        Shape2D::Circle { center, radius } => {
+            canvas.circle_filled(center.x, center.y, radius, 24, color)
+        }
+        Shape2D::Capsule { a, b, radius } => {
+            canvas.line(a.x, a.y, b.x, b.y, radius * 2.0, color);
+            canvas.circle_filled(a.x, a.y, radius, 16, color);
+            canvas.circle_filled(b.x, b.y, radius, 16, color);
+        }
+        Shape2D::Polygon(ref polygon) => {
+            let points: Vec<(f32, f32)> = polygon.points().iter().map(|p| (p.x, p.y)).collect();
+            canvas.polygon(&points, color);
+        }
+    }
+}
+
+/// A grappling hook stuck in the level.
+#[derive(Clone, Copy)]
+struct Hook {
+    anchor: Vec2,
+    length: f32,
+}
+
+struct JointsDemo {
+    world: PhysicsWorld2D,
+    chain: Vec<JointId>,
+    solids: Vec<Solid2D>,
+    player: KinematicBody2D,
+    hook: Option<Hook>,
+    steer: f32,
+    reel: f32,
+    jump: bool,
+    debug: bool,
+}
+
+impl JointsDemo {
+    fn fresh() -> Self {
+        let (world, chain) = initial_world();
+        Self {
+            world,
+            chain,
+            solids: level_solids(),
+            player: initial_player(),
+            hook: None,
+            steer: 0.0,
+            reel: 0.0,
+            jump: false,
+            debug: false,
+        }
+    }
+
+    /// Fire the hook from the player towards `target`; it sticks to the first
+    /// level solid within range.
+    fn fire_hook(&mut self, target: Vec2) {
+        let origin = self.player.bounds.center();
+        let hit = Ray2D::new(origin, target - origin)
+            .with_max_distance(HOOK_RANGE)
+            .cast_solids(&self.solids);
+        self.hook = hit.map(|hit| Hook {
+            anchor: hit.point,
+            length: hit.distance,
+        });
+    }
+
+    /// Cut the chain at its `index`th joint, dropping every link below.
+    fn cut_chain(&mut self, index: usize) {
+        if let Some(&joint) = self.chain.get(index) {
+            self.world.remove_joint(joint);
+            self.chain.truncate(index);
+        }
+    }
+
+    fn tick(&mut self, dt: f32) {
+        // The jointed bodies are solid ground for the player this step.
+        let mut solids = self.solids.clone();
+        solids.extend(
+            self.world
+                .bodies()
+                .map(|(_, body)| Solid2D::shaped(body.collider())),
+        );
+
+        match self.hook {
+            Some(ref mut hook) => {
+                hook.length = (hook.length + self.reel * REEL_SPEED * dt).clamp(20.0, HOOK_RANGE);
+                self.player.velocity.x += self.steer * SWING_ACCEL * dt;
+                let rope = JointKind2D::Rope {
+                    max_length: hook.length,
+                };
+                constrain_kinematic(dt, &mut self.player, hook.anchor, rope);
+            }
+            None => self.player.velocity.x = self.steer * MOVE_SPEED,
+        }
+        if self.jump && self.player.on_ground() {
+            self.player.velocity.y = JUMP_SPEED;
+        }
+        self.jump = false;
+        self.player.step_solids(dt, &solids);
+        if self.player.on_ground() && self.hook.is_none() {
+            self.player.velocity.x = 0.0;
+        }
+
+        self.world.step(dt, &self.solids);
+    }
+}
+
+impl Game for JointsDemo {
+    fn new(_engine: &mut Engine) -> Self {
+        Self::fresh()
+    }
+
+    fn fixed_update(&mut self, engine: &Engine) {
+        self.tick(engine.time().fixed_dt());
+    }
+
+    fn update(&mut self, engine: &Engine, _frame: &mut Frame) {
+        let input = engine.input();
+        let (mx, my) = input.mouse_position();
+        let axis = |neg: bool, pos: bool| (pos as i32 - neg as i32) as f32;
+
+        self.steer = axis(
+            input.is_key_down(KeyCode::KeyA),
+            input.is_key_down(KeyCode::KeyD),
+        );
+        self.reel = axis(
+            input.is_key_down(KeyCode::KeyW),
+            input.is_key_down(KeyCode::KeyS),
+        );
+        self.jump |= input.is_key_pressed(KeyCode::Space);
+
+        if input.is_mouse_pressed(0) {
+            self.fire_hook(Vec2::new(mx, my));
+        }
+        if input.is_mouse_released(0) {
+            self.hook = None;
+        }
+        if input.is_key_pressed(KeyCode::KeyX) {
+            self.cut_chain(CHAIN_LINKS / 2);
+        }
+        if input.is_key_pressed(KeyCode::Tab) {
+            self.debug = !self.debug;
+        }
+        if input.is_key_pressed(KeyCode::KeyR) {
+            *self = Self {
+                debug: self.debug,
+                ..Self::fresh()
+            };
+        }
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(20, 22, 30, 255);
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+
+        for solid in &self.solids {
+            draw_shape(
+                canvas,
+                &solid.collider(),
+                Color::from_rgba8(70, 80, 100, 255),
+            );
+        }
+
+        if self.debug {
+            self.world.debug_draw(canvas);
+        } else {
+            let rope = Color::from_rgba8(200, 180, 140, 255);
+            for (_, joint) in self.world.joints() {
+                if joint.kind == JointKind2D::Hinge {
+                    continue;
+                }
+                let a = self.world.anchor_position(&joint.a).unwrap();
+                let b = self.world.anchor_position(&joint.b).unwrap();
+                canvas.line(a.x, a.y, b.x, b.y, 2.0, rope);
+            }
+            for (_, body) in self.world.bodies() {
+                draw_shape(
+                    canvas,
+                    &body.collider(),
+                    Color::from_rgba8(220, 160, 90, 255),
+                );
+            }
+        }
+
+        let p = self.player.bounds;
+        if let Some(hook) = self.hook {
+            let c = p.center();
+            canvas.line(
+                c.x,
+                c.y,
+                hook.anchor.x,
+                hook.anchor.y,
+                2.0,
+                Color::from_rgba8(160, 220, 255, 255),
+            );
+        }
+        canvas.rect(
+            p.x,
+            p.y,
+            p.width,
+            p.height,
+            Color::from_rgba8(120, 200, 140, 255),
+        );
+
+        canvas.text(
+            -hw + 12.0,
+            hh - 24.0,
+            "A/D move, Space jump, hold left click to grapple (W/S reel), X cuts the chain, \
+             Tab debug draw, R resets.",
+            14.0,
+            Color::WHITE,
+        );
+    }
+}
+
+fn main() {
+    rengine::run::<JointsDemo>(EngineConfig {
+        title: "Feature: Joints".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn grappling_hook_swings_the_player_on_a_fixed_rope() {
+        let mut demo = JointsDemo::fresh();
+        demo.player.bounds.x = 200.0;
+        demo.player.bounds.y = 0.0;
+        demo.fire_hook(Vec2::new(40.0, 300.0));
+        let hook = demo.hook.expect("hook should reach the ceiling");
+        assert!((hook.anchor.y - 210.0).abs() < 1e-3);
+
+        let mut leftmost = f32::INFINITY;
+        for _ in 0..90 {
+            demo.tick(STEP);
+            let c = demo.player.bounds.center();
+            assert!(c.distance(hook.anchor) <= hook.length + 1.0);
+            leftmost = leftmost.min(c.x);
+        }
+        // Swung under the anchor and out the other side.
+        assert!(leftmost < hook.anchor.x - 20.0);
+    }
+
+    #[test]
+    fn chain_hangs_from_the_ceiling_until_cut() {
+        let mut demo = JointsDemo::fresh();
+        for _ in 0..180 {
+            demo.tick(STEP);
+        }
+        let bottom = |demo: &JointsDemo| {
+            demo.world
+                .bodies()
+                .take(CHAIN_LINKS)
+                .map(|(_, link)| link.position.y)
+                .fold(f32::INFINITY, f32::min)
+        };
+        let hanging = bottom(&demo);
+        assert!((hanging - (210.0 - (CHAIN_LINKS as f32 - 0.5) * LINK_LENGTH)).abs() < 4.0);
+
+        demo.cut_chain(CHAIN_LINKS / 2);
+        for _ in 0..30 {
+            demo.tick(STEP);
+        }
+        assert!(bottom(&demo) < hanging - 50.0);
+        // The plank still hangs on its ropes.
+        let plank = demo.world.get(CHAIN_LINKS).unwrap();
+        assert!((plank.position.y + 40.0).abs() < 4.0);
+    }
+}
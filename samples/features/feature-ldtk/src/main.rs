@@ -0,0 +1,445 @@
+//! Feature: LDtk project import and level streaming.
+//!
+//! `assets/world.ldtk` is a three-level GridVania world made in LDtk and
+//! loaded through `ldtk.assets.json`. Each level has a `Collision` IntGrid
+//! layer (walls, plus one-way `platform` cells) whose auto-layer tiles draw
+//! the terrain, a painted `Decor` tile layer and an `Entities` layer.
+//! [`LdtkProject::build_level`] turns a level into [`TileMap`]s, [`Solid2D`]s
+//! and a [`SceneWorld2D`] with one node per entity, its LDtk fields as node
+//! properties.
+//!
+//! Only the levels overlapping the camera view (plus a margin) are built;
+//! the rest are dropped, so walking right streams in the Bridge and falling
+//! through its gap streams in the Cellar. The door's `target` field is an
+//! entity reference into the Cellar. Coins are collected once, even if their
+//! level is streamed out and back in.
+//!
+//! Save the project in LDtk while this runs and the loaded levels rebuild.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use rengine::*;
+
+const VIEW: Vec2 = Vec2::new(800.0, 600.0);
+/// How far past the view levels are built ahead of time.
+const STREAM_MARGIN: f32 = 96.0;
+const MOVE_SPEED: f32 = 220.0;
+const JUMP_SPEED: f32 = 560.0;
+
+struct World {
+    project: Arc<LdtkProject>,
+    sheets: Vec<SpriteSheet>,
+    /// Built levels near the player.
+    loaded: Vec<LdtkLevelScene>,
+    /// Every loaded level's solids, rebuilt when the loaded set changes.
+    solids: Vec<Solid2D>,
+    /// Iids of coins already picked up.
+    collected: HashSet<String>,
+    player: KinematicBody2D,
+    score: i64,
+    message: Option<String>,
+    steer: f32,
+    jump: bool,
+    enter: bool,
+}
+
+impl World {
+    fn new(project: Arc<LdtkProject>, sheets: Vec<SpriteSheet>) -> Self {
+        let spawn = project
+            .levels
+            .first()
+            .map(|level| project.build_level(level, &[]))
+            .and_then(|level| {
+                let player = level.scene.by_prefab("Player").into_iter().next()?;
+                level.scene.node_bounds(player)
+            })
+            .unwrap_or(Rect::new(64.0, -320.0, 20.0, 28.0));
+        let mut world = Self {
+            project,
+            sheets,
+            loaded: Vec::new(),
+            solids: Vec::new(),
+            collected: HashSet::new(),
+            player: KinematicBody2D::new(spawn).with_ground_snap(6.0),
+            score: 0,
+            message: None,
+            steer: 0.0,
+            jump: false,
+            enter: false,
+        };
+        world.stream();
+        world
+    }
+
+    /// Swap in a reloaded project, rebuilding every loaded level from it.
+    fn replace_project(&mut self, project: Arc<LdtkProject>, sheets: Vec<SpriteSheet>) {
+        self.project = project;
+        self.sheets = sheets;
+        self.loaded.clear();
+        self.stream();
+    }
+
+    fn view(&self) -> Rect {
+        let size = VIEW + Vec2::splat(STREAM_MARGIN * 2.0);
+        Rect::from_pos_size(self.player.bounds.center() - size / 2.0, size)
+    }
+
+    /// Build the levels the view reaches and drop the ones it left.
+    fn stream(&mut self) {
+        let view = self.view();
+        let wanted: Vec<&LdtkLevel> = self.project.levels_in(view).collect();
+        let before = self.loaded.len();
+        self.loaded
+            .retain(|loaded| wanted.iter().any(|level| level.iid == loaded.iid));
+        let mut changed = self.loaded.len() != before;
+
+        for level in wanted {
+            if self.loaded.iter().any(|loaded| loaded.iid == level.iid) {
+                continue;
+            }
+            let mut built = self.project.build_level(level, &self.sheets);
+            for coin in built.scene.by_prefab("Coin") {
+                let taken = built
+                    .scene
+                    .get(coin)
+                    .and_then(|node| node.name())
+                    .is_some_and(|iid| self.collected.contains(iid));
+                if taken {
+                    built.scene.despawn(coin);
+                }
+            }
+            self.loaded.push(built);
+            changed = true;
+        }
+
+        if changed {
+            self.solids = self
+                .loaded
+                .iter()
+                .flat_map(|level| level.solids.iter().cloned())
+                .collect();
+        }
+    }
+
+    /// The world position of an entity's pivot, looked up in the project so
+    /// its level need not be loaded.
+    fn entity_pivot(&self, iid: &str) -> Option<Vec2> {
+        self.project.levels.iter().find_map(|level| {
+            level.layers.iter().find_map(|layer| {
+                let entity = layer.entities.iter().find(|entity| entity.iid == iid)?;
+                let px = Vec2::new(
+                    (layer.offset[0] + entity.px[0]) as f32,
+                    (layer.offset[1] + entity.px[1]) as f32,
+                );
+                Some(level.to_world(px))
+            })
+        })
+    }
+
+    fn current_level(&self) -> Option<&LdtkLevel> {
+        self.project.level_at(self.player.bounds.center())
+    }
+
+    fn tick(&mut self, dt: f32) {
+        self.player.velocity.x = self.steer * MOVE_SPEED;
+        if self.jump && self.player.on_ground() {
+            self.player.velocity.y = JUMP_SPEED;
+        }
+        self.jump = false;
+        self.player.step_solids(dt, &self.solids);
+
+        let bounds = self.player.bounds;
+        let mut door_target = None;
+        for level in &mut self.loaded {
+            let scene = &mut level.scene;
+            let touching: Vec<NodeHandle2D> = scene
+                .handles()
+                .filter(|&handle| {
+                    scene
+                        .node_bounds(handle)
+                        .is_some_and(|node| node.overlaps(&bounds))
+                })
+                .collect();
+            for handle in touching {
+                let Some(node) = scene.get(handle) else {
+                    continue;
+                };
+                match node.prefab() {
+                    "Coin" => {
+                        self.score += node.property_i64("value").unwrap_or(1);
+                        self.collected.extend(node.name().map(str::to_string));
+                        scene.despawn(handle);
+                    }
+                    "Sign" => self.message = node.property("text").map(str::to_string),
+                    "Exit" => self.message = node.property("message").map(str::to_string),
+                    "Door" if self.enter => {
+                        door_target = node.property("target").map(str::to_string)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        self.enter = false;
+
+        if let Some(pivot) = door_target.and_then(|iid| self.entity_pivot(&iid)) {
+            let size = Vec2::new(self.player.bounds.width, self.player.bounds.height);
+            self.player.bounds = Rect::from_pos_size(pivot - Vec2::new(size.x / 2.0, 0.0), size);
+            self.player.velocity = Vec2::ZERO;
+        }
+        self.stream();
+    }
+}
+
+struct LdtkDemo {
+    _assets: AssetBundle,
+    project: LdtkProjectAsset,
+    world: World,
+    reloads: u32,
+}
+
+impl Game for LdtkDemo {
+    fn new(engine: &mut Engine) -> Self {
+        engine.set_asset_root(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets"));
+        let assets = engine
+            .load_asset_bundle("ldtk.assets.json")
+            .expect("failed to load the LDtk project");
+        let project = assets.ldtk_project("world").expect("world project").clone();
+        Self {
+            world: World::new(project.project.clone(), project.sheets.clone()),
+            project,
+            _assets: assets,
+            reloads: 0,
+        }
+    }
+
+    fn fixed_update(&mut self, engine: &Engine) {
+        self.world.tick(engine.time().fixed_dt());
+    }
+
+    fn update(&mut self, engine: &Engine, _frame: &mut Frame) {
+        if let Some(project) = engine.reloaded_ldtk_project(&self.project) {
+            self.world
+                .replace_project(project.project.clone(), project.sheets.clone());
+            self.project = project;
+            self.reloads += 1;
+        }
+
+        let input = engine.input();
+        let left = input.is_key_down(KeyCode::KeyA) || input.is_key_down(KeyCode::ArrowLeft);
+        let right = input.is_key_down(KeyCode::KeyD) || input.is_key_down(KeyCode::ArrowRight);
+        self.world.steer = (right as i32 - left as i32) as f32;
+        self.world.jump |= input.is_key_pressed(KeyCode::Space);
+        self.world.enter |=
+            input.is_key_pressed(KeyCode::KeyW) || input.is_key_pressed(KeyCode::ArrowUp);
+        if input.is_key_pressed(KeyCode::KeyR) {
+            self.world = World::new(self.project.project.clone(), self.project.sheets.clone());
+        }
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(28, 32, 48, 255);
+        let world = &self.world;
+        let camera = world.player.bounds.center();
+        frame.camera.position = camera;
+        for level in &world.loaded {
+            level.draw(frame);
+        }
+
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+
+        for level in &world.loaded {
+            let b = level.bounds;
+            let (x0, y0) = (b.x - camera.x, b.y - camera.y);
+            let (x1, y1) = (x0 + b.width, y0 + b.height);
+            let edge = Color::from_rgba8(90, 110, 150, 255);
+            for (ax, ay, bx, by) in [
+                (x0, y0, x1, y0),
+                (x1, y0, x1, y1),
+                (x1, y1, x0, y1),
+                (x0, y1, x0, y0),
+            ] {
+                canvas.line(ax, ay, bx, by, 1.0, edge);
+            }
+            for handle in level.scene.handles() {
+                let (Some(node), Some(r)) =
+                    (level.scene.get(handle), level.scene.node_bounds(handle))
+                else {
+                    continue;
+                };
+                let (x, y) = (r.x - camera.x, r.y - camera.y);
+                match node.prefab() {
+                    "Coin" => {
+                        let c = Vec2::new(x, y) + Vec2::new(r.width, r.height) / 2.0;
+                        canvas.circle_filled(
+                            c.x,
+                            c.y,
+                            r.width / 2.0,
+                            16,
+                            Color::from_rgba8(255, 215, 80, 255),
+                        );
+                    }
+                    "Door" | "Exit" => {
+                        canvas.rect(
+                            x,
+                            y,
+                            r.width,
+                            r.height,
+                            Color::from_rgba8(150, 100, 60, 255),
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let b = world.player.bounds;
+        canvas.rect(
+            b.x - camera.x,
+            b.y - camera.y,
+            b.width,
+            b.height,
+            Color::from_rgba8(250, 120, 110, 255),
+        );
+
+        let title = world
+            .current_level()
+            .and_then(|level| match level.field("title") {
+                Some(LdtkValue::String(title)) => Some(title.as_str()),
+                _ => None,
+            })
+            .unwrap_or("-");
+        let loaded: Vec<&str> = world
+            .loaded
+            .iter()
+            .map(|level| level.identifier.as_str())
+            .collect();
+        canvas.text(
+            -hw + 12.0,
+            hh - 24.0,
+            &format!(
+                "{title}  |  A/D move, Space jump, W enter door, R restart  |  Score: {}  Reloads: {}",
+                world.score, self.reloads
+            ),
+            14.0,
+            Color::WHITE,
+        );
+        canvas.text(
+            -hw + 12.0,
+            hh - 44.0,
+            &format!("Loaded levels: {}", loaded.join(", ")),
+            14.0,
+            Color::from_rgba8(180, 200, 230, 255),
+        );
+        if let Some(message) = &world.message {
+            canvas.text(-hw + 12.0, hh - 64.0, message, 14.0, Color::YELLOW);
+        }
+    }
+}
+
+fn main() {
+    rengine::run::<LdtkDemo>(EngineConfig {
+        title: "Feature: LDtk Levels".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    const STEP: f32 = 1.0 / 60.0;
+
+    /// The project without a renderer: with no sprite sheets, levels build
+    /// their collision and entities but no tile layers.
+    fn world() -> World {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/world.ldtk");
+        let text = std::fs::read_to_string(&path).unwrap();
+        World::new(
+            Arc::new(LdtkProject::parse(&path, &text).unwrap()),
+            Vec::new(),
+        )
+    }
+
+    fn loaded(world: &World) -> Vec<&str> {
+        let mut names: Vec<&str> = world.loaded.iter().map(|l| l.identifier.as_str()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn levels_stream_in_as_the_player_walks_right() {
+        let mut world = world();
+        assert_eq!(loaded(&world), vec!["Meadow"]);
+        assert_eq!(world.current_level().unwrap().identifier, "Meadow");
+        for _ in 0..30 {
+            world.tick(STEP);
+        }
+        assert!(world.player.on_ground());
+
+        // The Bridge and the Cellar below it come into view at the edge.
+        let start = world.player.bounds.x;
+        world.player.bounds.x = 560.0;
+        world.tick(STEP);
+        assert_eq!(loaded(&world), vec!["Bridge", "Cellar", "Meadow"]);
+        world.player.bounds.x = start;
+        world.tick(STEP);
+        assert_eq!(loaded(&world), vec!["Meadow"]);
+
+        let bridge = world.project.level("Bridge").unwrap();
+        let neighbours: Vec<_> = world
+            .project
+            .neighbours(bridge)
+            .map(|(level, dir)| (level.identifier.as_str(), dir))
+            .collect();
+        assert!(neighbours.contains(&("Cellar", LdtkDirection::South)));
+    }
+
+    #[test]
+    fn door_reference_leads_to_the_cellar_and_coins_stay_collected() {
+        let mut world = world();
+        let meadow = world.loaded[0].scene.by_prefab("Coin");
+        assert_eq!(meadow.len(), 2);
+        let coin = world.loaded[0].scene.node_bounds(meadow[0]).unwrap();
+        world.player.bounds.x = coin.x;
+        world.player.bounds.y = coin.y;
+        world.tick(STEP);
+        assert_eq!(world.score, 1);
+
+        let door = world.loaded[0].scene.by_prefab("Door")[0];
+        let door = world.loaded[0].scene.node_bounds(door).unwrap();
+        world.player.bounds.x = door.x;
+        world.player.bounds.y = door.y;
+        world.enter = true;
+        world.tick(STEP);
+        assert_eq!(world.current_level().unwrap().identifier, "Cellar");
+        assert!(loaded(&world).contains(&"Cellar"));
+
+        for _ in 0..10 {
+            world.tick(STEP);
+        }
+        assert_eq!(
+            world.message.as_deref(),
+            Some("You reached the cellar exit!")
+        );
+
+        // Back at the start the collected coin is not rebuilt.
+        world.player.bounds = Rect::from_pos_size(Vec2::new(70.0, -320.0), Vec2::new(20.0, 28.0));
+        world.tick(STEP);
+        let meadow = world
+            .loaded
+            .iter()
+            .find(|l| l.identifier == "Meadow")
+            .unwrap();
+        assert_eq!(meadow.scene.by_prefab("Coin").len(), 1);
+    }
+}
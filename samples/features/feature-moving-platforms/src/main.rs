@@ -0,0 +1,206 @@
+//! Feature: moving platforms.
+//!
+//! Kinematic [`Solid2D`]s (solids with a velocity) advanced by
+//! [`move_platforms`]: an elevator and a shuttle that carry the player, a
+//! drop-through lift, and a piston that shoves the player and squashes them
+//! against the wall if they stay in its way. The HUD shows which platform the
+//! player is riding, read from [`KinematicBody2D::platform`].
+//!
+//! Coordinates are centered and y-up, so the canvas draws world space directly.
+
+use rengine::*;
+
+const MOVE_SPEED: f32 = 220.0;
+const JUMP_SPEED: f32 = 430.0;
+const ELEVATOR: usize = 3;
+const SHUTTLE: usize = 4;
+const LIFT: usize = 5;
+const PISTON: usize = 6;
+
+fn initial_player() -> KinematicBody2D {
+    KinematicBody2D::new(Rect::new(-300.0, -170.0, 24.0, 24.0)).with_ground_snap(4.0)
+}
+
+fn level_solids() -> Vec<Solid2D> {
+    vec![
+        Solid2D::solid(Rect::new(-360.0, -200.0, 720.0, 30.0)),
+        Solid2D::solid(Rect::new(-360.0, -200.0, 24.0, 420.0)),
+        Solid2D::solid(Rect::new(336.0, -200.0, 24.0, 420.0)),
+        Solid2D::solid(Rect::new(-240.0, -170.0, 80.0, 12.0)).with_velocity(Vec2::new(0.0, 70.0)),
+        Solid2D::solid(Rect::new(-120.0, 40.0, 90.0, 12.0)).with_velocity(Vec2::new(90.0, 0.0)),
+        Solid2D::one_way(Rect::new(180.0, -150.0, 70.0, 8.0)).with_velocity(Vec2::new(0.0, 50.0)),
+        Solid2D::solid(Rect::new(-20.0, -170.0, 30.0, 50.0)).with_velocity(Vec2::new(80.0, 0.0)),
+    ]
+}
+
+/// Ping-pong each kinematic solid between its travel limits.
+fn steer_platforms(solids: &mut [Solid2D]) {
+    let bounce = |value: f32, min: f32, max: f32, speed: f32| {
+        if value <= min {
+            speed.abs()
+        } else if value >= max {
+            -speed.abs()
+        } else {
+            speed
+        }
+    };
+    let v = solids[ELEVATOR].velocity.y;
+    solids[ELEVATOR].velocity.y = bounce(solids[ELEVATOR].rect.y, -170.0, 60.0, v);
+    let v = solids[SHUTTLE].velocity.x;
+    solids[SHUTTLE].velocity.x = bounce(solids[SHUTTLE].rect.x, -120.0, 120.0, v);
+    let v = solids[LIFT].velocity.y;
+    solids[LIFT].velocity.y = bounce(solids[LIFT].rect.y, -150.0, 20.0, v);
+    let v = solids[PISTON].velocity.x;
+    solids[PISTON].velocity.x = bounce(solids[PISTON].rect.x, -20.0, 306.0, v);
+}
+
+struct PlatformsDemo {
+    player: KinematicBody2D,
+    solids: Vec<Solid2D>,
+    squashes: u32,
+}
+
+impl PlatformsDemo {
+    fn tick(&mut self, dt: f32) {
+        steer_platforms(&mut self.solids);
+        let events = move_platforms(dt, &mut self.solids, std::slice::from_mut(&mut self.player));
+        if events
+            .iter()
+            .any(|event| matches!(event, PlatformEvent2D::Squashed { .. }))
+        {
+            self.squashes += 1;
+            self.player = initial_player();
+        }
+        self.player.step_solids(dt, &self.solids);
+    }
+}
+
+impl Game for PlatformsDemo {
+    fn new(_engine: &mut Engine) -> Self {
+        Self {
+            player: initial_player(),
+            solids: level_solids(),
+            squashes: 0,
+        }
+    }
+
+    fn update(&mut self, engine: &Engine, _frame: &mut Frame) {
+        let input = engine.input();
+        let dt = engine.dt().min(1.0 / 30.0);
+
+        let mut vx = 0.0;
+        if input.is_key_down(KeyCode::ArrowLeft) || input.is_key_down(KeyCode::KeyA) {
+            vx -= MOVE_SPEED;
+        }
+        if input.is_key_down(KeyCode::ArrowRight) || input.is_key_down(KeyCode::KeyD) {
+            vx += MOVE_SPEED;
+        }
+        self.player.velocity.x = vx;
+        let jump = input.is_key_pressed(KeyCode::Space) || input.is_key_pressed(KeyCode::ArrowUp);
+        if jump && self.player.on_ground() {
+            self.player.velocity.y = JUMP_SPEED;
+        }
+
+        self.tick(dt);
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(20, 22, 30, 255);
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+
+        for (id, solid) in self.solids.iter().enumerate() {
+            let r = solid.rect;
+            let color = if Some(id) == self.player.platform {
+                Color::from_rgba8(120, 170, 230, 255)
+            } else if solid.velocity != Vec2::ZERO {
+                Color::from_rgba8(170, 120, 80, 255)
+            } else {
+                Color::from_rgba8(70, 80, 100, 255)
+            };
+            canvas.rect(r.x, r.y, r.width, r.height, color);
+        }
+
+        let p = self.player.bounds;
+        canvas.rect(
+            p.x,
+            p.y,
+            p.width,
+            p.height,
+            Color::from_rgba8(120, 200, 140, 255),
+        );
+
+        let riding = match self.player.platform {
+            Some(ELEVATOR) => "elevator",
+            Some(SHUTTLE) => "shuttle",
+            Some(LIFT) => "drop-through lift",
+            Some(PISTON) => "piston",
+            Some(_) => "static ground",
+            None => "nothing",
+        };
+        canvas.text(
+            -hw + 12.0,
+            hh - 24.0,
+            &format!(
+                "A/D move, Space jump. Riding: {riding}. Squashed by the piston: {}",
+                self.squashes
+            ),
+            14.0,
+            Color::WHITE,
+        );
+    }
+}
+
+fn main() {
+    rengine::run::<PlatformsDemo>(EngineConfig {
+        title: "Feature: Moving Platforms".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: f32 = 1.0 / 60.0;
+
+    fn demo() -> PlatformsDemo {
+        PlatformsDemo {
+            player: initial_player(),
+            solids: level_solids(),
+            squashes: 0,
+        }
+    }
+
+    #[test]
+    fn elevator_lifts_a_rider() {
+        let mut demo = demo();
+        demo.player.bounds.x = -210.0;
+        demo.player.bounds.y = -158.0;
+        for _ in 0..60 {
+            demo.tick(STEP);
+        }
+        assert_eq!(demo.player.platform, Some(ELEVATOR));
+        assert!((demo.player.bounds.y - demo.solids[ELEVATOR].rect.top()).abs() < 1e-3);
+        assert!(demo.player.bounds.y > -100.0);
+    }
+
+    #[test]
+    fn standing_in_the_piston_path_gets_squashed_against_the_wall() {
+        let mut demo = demo();
+        demo.player.bounds.x = 300.0;
+        for _ in 0..600 {
+            demo.tick(STEP);
+            if demo.squashes > 0 {
+                break;
+            }
+        }
+        assert_eq!(demo.squashes, 1);
+    }
+}
@@ -0,0 +1,387 @@
+//! Feature: grid and navmesh pathfinding.
+//!
+//! The grid view searches a [`NavGrid`] of walls and costly mud from the
+//! start marker to the mouse through a [`GridPathCache`]: the search counter
+//! only climbs when the goal moves to a new cell or an edit blocks the
+//! cached path. Jump point search only takes over once the mud is cleared
+//! away, since it cannot weigh costly cells; until then it falls back to A*.
+//!
+//! The mesh view searches a [`NavMesh`] built from one room with obstacles
+//! cut out of it, plus an alcove joined through a narrow doorway. The funnel
+//! pass pulls the path tight around corners, kept `radius` away from them,
+//! and the widest agent cannot fit into the alcove at all.
+
+use rengine::*;
+
+const COLS: usize = 44;
+const ROWS: usize = 30;
+const CELL: f32 = 16.0;
+const MUD_COST: f32 = 5.0;
+const RADII: [f32; 3] = [0.0, 10.0, 24.0];
+
+#[derive(Clone, Copy, PartialEq)]
+enum View {
+    Grid,
+    Mesh,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Brush {
+    Wall,
+    Mud,
+}
+
+fn grid_origin() -> Vec2 {
+    -Vec2::new(COLS as f32, ROWS as f32) * CELL / 2.0
+}
+
+/// Three walls with gaps of different widths and a mud field, walled in.
+fn build_grid() -> NavGrid {
+    let mut grid = NavGrid::new(COLS, ROWS, CELL).with_origin(grid_origin());
+    for row in 0..ROWS {
+        for col in 0..COLS {
+            let border = col == 0 || row == 0 || col == COLS - 1 || row == ROWS - 1;
+            let wall = border
+                || (col == 11 && row < 22)
+                || (col == 22 && row > 7)
+                || (col == 33 && !(12..16).contains(&row));
+            if wall {
+                grid.set_cost(col, row, None);
+            } else if (24..32).contains(&col) && (3..13).contains(&row) {
+                grid.set_cost(col, row, Some(MUD_COST));
+            }
+        }
+    }
+    grid
+}
+
+fn rect(x: f32, y: f32, w: f32, h: f32) -> Vec<Vec2> {
+    vec![
+        Vec2::new(x, y),
+        Vec2::new(x + w, y),
+        Vec2::new(x + w, y + h),
+        Vec2::new(x, y + h),
+    ]
+}
+
+fn obstacles() -> Vec<Vec<Vec2>> {
+    vec![
+        rect(-200.0, -180.0, 60.0, 330.0),
+        rect(-40.0, -120.0, 120.0, 60.0),
+        rect(-20.0, 40.0, 80.0, 120.0),
+        rect(160.0, -200.0, 50.0, 300.0),
+        vec![
+            Vec2::new(220.0, 150.0),
+            Vec2::new(290.0, 120.0),
+            Vec2::new(260.0, 190.0),
+        ],
+    ]
+}
+
+/// The room outline carries the doorway's ends as corners, so the alcove
+/// added after it joins through that edge.
+fn build_mesh() -> NavMesh {
+    let room = vec![
+        Vec2::new(-340.0, -230.0),
+        Vec2::new(340.0, -230.0),
+        Vec2::new(340.0, -20.0),
+        Vec2::new(340.0, 20.0),
+        Vec2::new(340.0, 210.0),
+        Vec2::new(-340.0, 210.0),
+    ];
+    let alcove = vec![
+        Vec2::new(340.0, -20.0),
+        Vec2::new(390.0, -60.0),
+        Vec2::new(390.0, 60.0),
+        Vec2::new(340.0, 20.0),
+    ];
+    let mut mesh = NavMesh::new();
+    mesh.add_polygon(&room, &obstacles());
+    mesh.add_polygon(&alcove, &[]);
+    mesh
+}
+
+struct NavDemo {
+    view: View,
+    grid: NavGrid,
+    cache: GridPathCache,
+    mesh: NavMesh,
+    brush: Brush,
+    radius: usize,
+    agent: NavAgent,
+    grid_start: Vec2,
+    mesh_start: Vec2,
+    mouse: Vec2,
+    path: Option<Vec<Vec2>>,
+    cost: f32,
+}
+
+impl NavDemo {
+    fn replan(&mut self) {
+        self.path = None;
+        self.cost = 0.0;
+        match self.view {
+            View::Grid => {
+                let (Some(from), Some(to)) = (
+                    self.grid.cell_at(self.grid_start),
+                    self.grid.cell_at(self.mouse),
+                ) else {
+                    return;
+                };
+                if let Some(path) = self.cache.find_path(&self.grid, from, to, &self.agent) {
+                    self.cost = path.cost;
+                    self.path = Some(path.points(&self.grid));
+                }
+            }
+            View::Mesh => {
+                self.path = self
+                    .mesh
+                    .find_path(self.mesh_start, self.mouse, &self.agent)
+                    .map(|mut points| {
+                        points.insert(0, self.mesh_start);
+                        points
+                    });
+            }
+        }
+    }
+
+    fn paint(&mut self, cost: Option<f32>) {
+        if let Some((col, row)) = self.grid.cell_at(self.mouse) {
+            let border = col == 0 || row == 0 || col == COLS - 1 || row == ROWS - 1;
+            if !border && self.grid.cost(col, row) != cost {
+                self.grid.set_cost(col, row, cost);
+            }
+        }
+    }
+
+    fn draw_grid(&self, canvas: &mut Canvas) {
+        for row in 0..ROWS {
+            for col in 0..COLS {
+                let color = match self.grid.cost(col, row) {
+                    None => Color::from_rgba8(70, 74, 86, 255),
+                    Some(cost) if cost > 1.0 => Color::from_rgba8(92, 70, 44, 255),
+                    Some(_) if !self.grid.is_walkable(col, row, &self.agent) => {
+                        Color::from_rgba8(40, 40, 50, 255)
+                    }
+                    Some(_) => Color::from_rgba8(30, 30, 38, 255),
+                };
+                let p = self.grid.origin() + Vec2::new(col as f32, row as f32) * CELL;
+                canvas.rect(p.x + 1.0, p.y + 1.0, CELL - 2.0, CELL - 2.0, color);
+            }
+        }
+    }
+
+    fn draw_mesh(&self, canvas: &mut Canvas) {
+        for [a, b, c] in self.mesh.triangles() {
+            canvas.polygon(
+                &[(a.x, a.y), (b.x, b.y), (c.x, c.y)],
+                Color::from_rgba8(30, 34, 44, 255),
+            );
+            canvas.polyline(
+                &[(a.x, a.y), (b.x, b.y), (c.x, c.y), (a.x, a.y)],
+                1.0,
+                Color::from_rgba8(60, 70, 90, 255),
+            );
+        }
+    }
+}
+
+impl Game for NavDemo {
+    fn new(_engine: &mut Engine) -> Self {
+        let mut demo = Self {
+            view: View::Grid,
+            grid: build_grid(),
+            cache: GridPathCache::new(64),
+            mesh: build_mesh(),
+            brush: Brush::Wall,
+            radius: 0,
+            agent: NavAgent::new(RADII[0]),
+            grid_start: grid_origin() + Vec2::splat(CELL * 3.5),
+            mesh_start: Vec2::new(-300.0, -200.0),
+            mouse: Vec2::ZERO,
+            path: None,
+            cost: 0.0,
+        };
+        demo.replan();
+        demo
+    }
+
+    fn update(&mut self, engine: &Engine, frame: &mut Frame) {
+        let input = engine.input();
+        if input.is_key_pressed(KeyCode::Tab) {
+            self.view = match self.view {
+                View::Grid => View::Mesh,
+                View::Mesh => View::Grid,
+            };
+        }
+        if input.is_key_pressed(KeyCode::KeyR) {
+            self.radius = (self.radius + 1) % RADII.len();
+            self.agent.radius = RADII[self.radius];
+        }
+        if input.is_key_pressed(KeyCode::KeyJ) {
+            self.agent.search = match self.agent.search {
+                PathSearch::AStar => PathSearch::Jps,
+                PathSearch::Jps => PathSearch::AStar,
+            };
+        }
+        if input.is_key_pressed(KeyCode::KeyD) {
+            self.agent.diagonals = !self.agent.diagonals;
+        }
+        if input.is_key_pressed(KeyCode::Digit1) {
+            self.brush = Brush::Wall;
+        }
+        if input.is_key_pressed(KeyCode::Digit2) {
+            self.brush = Brush::Mud;
+        }
+
+        let (mx, my) = input.mouse_position();
+        self.mouse = frame.camera.screen_to_world(Vec2::new(mx, my));
+        if input.is_key_pressed(KeyCode::KeyS) {
+            match self.view {
+                View::Grid => self.grid_start = self.mouse,
+                View::Mesh => self.mesh_start = self.mouse,
+            }
+        }
+        if self.view == View::Grid {
+            if input.is_mouse_down(0) {
+                let cost = match self.brush {
+                    Brush::Wall => None,
+                    Brush::Mud => Some(MUD_COST),
+                };
+                self.paint(cost);
+            } else if input.is_mouse_down(1) {
+                self.paint(Some(1.0));
+            }
+        }
+        self.replan();
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(16, 16, 22, 255);
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+
+        let start = match self.view {
+            View::Grid => {
+                self.draw_grid(canvas);
+                self.grid_start
+            }
+            View::Mesh => {
+                self.draw_mesh(canvas);
+                self.mesh_start
+            }
+        };
+
+        let path_color = Color::from_rgba8(120, 220, 160, 255);
+        if let Some(path) = &self.path {
+            let points: Vec<(f32, f32)> = path.iter().map(|p| (p.x, p.y)).collect();
+            canvas.polyline(&points, 2.0, path_color);
+            for p in path {
+                canvas.circle(p.x, p.y, self.agent.radius.max(3.0), 1.0, 20, path_color);
+            }
+        }
+        canvas.circle_filled(
+            start.x,
+            start.y,
+            5.0,
+            16,
+            Color::from_rgba8(255, 220, 90, 255),
+        );
+
+        let search = match self.agent.search {
+            PathSearch::AStar => "A*",
+            PathSearch::Jps => "JPS",
+        };
+        let controls = match self.view {
+            View::Grid => format!(
+                "LMB paint {}, RMB clear, 1 wall, 2 mud, S start, J search, D diagonals, R radius, Tab mesh",
+                if self.brush == Brush::Wall { "wall" } else { "mud" }
+            ),
+            View::Mesh => "S start, R radius, Tab grid".to_string(),
+        };
+        let status = match (self.view, &self.path) {
+            (_, None) => format!("Radius {}  |  no path", self.agent.radius),
+            (View::Grid, Some(_)) => format!(
+                "{search}{}  |  Radius {}  |  Cost {:.1}  |  Searches {} ({} cached)",
+                if self.agent.diagonals {
+                    ""
+                } else {
+                    ", no diagonals"
+                },
+                self.agent.radius,
+                self.cost,
+                self.cache.searches(),
+                self.cache.len(),
+            ),
+            (View::Mesh, Some(path)) => format!(
+                "Radius {}  |  {} triangles  |  {} waypoints",
+                self.agent.radius,
+                self.mesh.triangle_count(),
+                path.len()
+            ),
+        };
+        canvas.text(-hw + 12.0, hh - 24.0, &controls, 14.0, Color::WHITE);
+        canvas.text(
+            -hw + 12.0,
+            hh - 44.0,
+            &status,
+            14.0,
+            Color::from_rgba8(180, 200, 230, 255),
+        );
+    }
+}
+
+fn main() {
+    rengine::run::<NavDemo>(EngineConfig {
+        title: "Feature: Pathfinding".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_gaps_let_small_agents_through_and_mud_is_avoided() {
+        let grid = build_grid();
+        let (from, to) = ((5, 5), (COLS - 6, 5));
+        let path = grid.find_path(from, to, &NavAgent::new(0.0)).unwrap();
+        assert!(path
+            .cells
+            .iter()
+            .all(|&(col, row)| grid.cost(col, row) == Some(1.0)));
+        // The gap in the last wall is four cells wide.
+        assert!(grid.find_path(from, to, &NavAgent::new(RADII[1])).is_some());
+        assert!(grid.find_path(from, to, &NavAgent::new(40.0)).is_none());
+    }
+
+    #[test]
+    fn mesh_paths_go_around_obstacles_and_only_small_agents_reach_the_alcove() {
+        let mesh = build_mesh();
+        let (from, to) = (Vec2::new(-300.0, -200.0), Vec2::new(300.0, 0.0));
+        let path = mesh.find_path(from, to, &NavAgent::new(RADII[1])).unwrap();
+        let mut walked = vec![from];
+        walked.extend(&path);
+        for pair in walked.windows(2) {
+            for k in 0..=20 {
+                let p = pair[0].lerp(pair[1], k as f32 / 20.0);
+                assert!(mesh.triangle_at(p).is_some(), "{p} is off the mesh");
+            }
+        }
+
+        let alcove = Vec2::new(375.0, 0.0);
+        assert!(mesh
+            .find_path(from, alcove, &NavAgent::new(RADII[1]))
+            .is_some());
+        assert!(mesh
+            .find_path(from, alcove, &NavAgent::new(RADII[2]))
+            .is_none());
+    }
+}
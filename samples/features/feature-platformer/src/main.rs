@@ -1,7 +1,9 @@
 //! Feature: 2D platformer physics.
 //!
 //! A controllable character driven by [`KinematicBody2D`] against a set of
-//! static solids: gravity, run, jump, and flush wall/floor/ceiling collisions.
+//! static solids: gravity, run, jump, and flush wall/floor/ceiling collisions,
+//! plus [`Solid2D::slope`] ramps — a 45° climb, a shallow half-slope bump, and
+//! a drop-through slope — that the body walks over with ground snapping.
 //! This is the worked example for the engine's 2D character-controller physics
 //! (`move_and_collide` + `KinematicBody2D`).
 //!
@@ -13,15 +15,17 @@ use rengine::*;
 const MOVE_SPEED: f32 = 240.0;
 const JUMP_SPEED: f32 = 430.0;
 const RESPAWN_BELOW_Y: f32 = -400.0;
+/// One frame of running down a 45° slope drops `MOVE_SPEED / 60` pixels.
+const GROUND_SNAP: f32 = 6.0;
 
 /// The player's starting body — placed above the left platform so it visibly
 /// falls and lands when the sample boots.
 fn initial_player() -> KinematicBody2D {
-    KinematicBody2D::new(Rect::new(-120.0, 140.0, 28.0, 28.0))
+    KinematicBody2D::new(Rect::new(-120.0, 140.0, 28.0, 28.0)).with_ground_snap(GROUND_SNAP)
 }
 
 /// The static level geometry: ground, a solid platform, a one-way (drop-through)
-/// platform, and side walls.
+/// platform, slopes, and side walls.
 fn level_solids() -> Vec<Solid2D> {
     vec![
         Solid2D::solid(Rect::new(-360.0, -200.0, 720.0, 30.0)), // ground
@@ -29,6 +33,14 @@ fn level_solids() -> Vec<Solid2D> {
         Solid2D::one_way(Rect::new(60.0, -40.0, 160.0, 12.0)),  // right: drop-through
         Solid2D::solid(Rect::new(-360.0, -200.0, 24.0, 380.0)), // left wall
         Solid2D::solid(Rect::new(336.0, -200.0, 24.0, 380.0)),  // right wall
+        // A shallow bump made of two half slopes.
+        Solid2D::slope(Rect::new(-20.0, -170.0, 100.0, 20.0), 0.0, 20.0),
+        Solid2D::slope(Rect::new(80.0, -170.0, 100.0, 20.0), 20.0, 0.0),
+        // A 45° ramp up to a ledge against the right wall.
+        Solid2D::slope(Rect::new(220.0, -170.0, 60.0, 60.0), 0.0, 60.0),
+        Solid2D::solid(Rect::new(280.0, -170.0, 56.0, 60.0)),
+        // A drop-through slope above the left side of the ground.
+        Solid2D::slope(Rect::new(-330.0, -120.0, 120.0, 40.0), 40.0, 0.0).with_one_way(true),
     ]
 }
 
@@ -93,19 +105,28 @@ impl Scene for PlatformerScene {
         canvas.text(
             -hw + 20.0,
             hh - 28.0,
-            "Platformer physics: A/D or arrows to move, Space/Up to jump (drop-through the thin platform), Esc to quit",
+            "Platformer physics: A/D or arrows to move, Space/Up to jump (slopes snap you to the ground; green platforms are drop-through), Esc to quit",
             15.0,
             Color::WHITE,
         );
 
         for solid in &self.solids {
-            let r = solid.rect;
             let color = if solid.one_way {
                 Color::from_rgba8(110, 130, 90, 255)
             } else {
                 Color::from_rgba8(70, 80, 100, 255)
             };
-            canvas.rect(r.x, r.y, r.width, r.height, color);
+            match solid.collider() {
+                Shape2D::Polygon(polygon) => {
+                    let points: Vec<(f32, f32)> =
+                        polygon.points().iter().map(|p| (p.x, p.y)).collect();
+                    canvas.polygon(&points, color);
+                }
+                _ => {
+                    let r = solid.rect;
+                    canvas.rect(r.x, r.y, r.width, r.height, color);
+                }
+            }
         }
 
         let body = &self.player.bounds;
@@ -202,5 +223,27 @@ mod tests {
         // The right wall's left edge is x = 336; the body cannot pass it.
         assert!(player.bounds.x + player.bounds.width <= 336.0 + 1e-2);
         assert!(player.contacts.right);
+        // It climbed the 45° ramp onto the ledge (top y = -110) on the way.
+        assert!((player.bounds.y - (-110.0)).abs() < 1e-2);
+    }
+
+    #[test]
+    fn running_over_the_half_slope_bump_never_leaves_the_ground() {
+        let solids = level_solids();
+        let mut player = initial_player();
+        player.bounds.x = -80.0;
+        player.bounds.y = -170.0;
+        settle(&mut player, &solids, 10);
+
+        let mut peak = player.bounds.y;
+        for _ in 0..60 {
+            player.velocity.x = MOVE_SPEED;
+            player.step_solids(STEP, &solids);
+            assert!(player.on_ground());
+            peak = peak.max(player.bounds.y);
+        }
+        assert!((peak - (-150.0)).abs() < 1.0);
+        // Still on the far (downhill) half, whose normal leans right.
+        assert!(player.contacts.ground_normal.x > 0.0);
     }
 }
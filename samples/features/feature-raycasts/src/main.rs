@@ -0,0 +1,263 @@
+//! Feature: raycasts and shape casts.
+//!
+//! A player square aims at the mouse cursor. A [`Ray2D`] hitscan is traced
+//! against both a set of [`Solid2D`]s and a small [`TileMap`], and the nearest
+//! hit is drawn with its face normal. A [`BoxCast2D`] previews where the
+//! player's own box would stop if it dashed toward the cursor.
+//!
+//! Coordinates are centered and y-up, so the canvas draws world space directly.
+
+use rengine::*;
+
+const PLAYER_SPEED: f32 = 220.0;
+const PLAYER_SIZE: f32 = 20.0;
+const TILE_SIZE: f32 = 24.0;
+const MAP_ORIGIN: Vec2 = Vec2::new(120.0, -180.0);
+const DASH_LENGTH: f32 = 260.0;
+
+fn level_solids() -> Vec<Solid2D> {
+    vec![
+        Solid2D::solid(Rect::new(-360.0, -260.0, 720.0, 20.0)),
+        Solid2D::solid(Rect::new(-360.0, 200.0, 720.0, 20.0)),
+        Solid2D::solid(Rect::new(-200.0, -120.0, 30.0, 200.0)),
+        Solid2D::solid(Rect::new(-60.0, 80.0, 140.0, 24.0)),
+        Solid2D::one_way(Rect::new(-150.0, -40.0, 120.0, 10.0)),
+    ]
+}
+
+fn level_tiles(texture: TextureId) -> TileMap {
+    let mut map = TileMap::new(8, 10, TILE_SIZE);
+    let tile = map.add_tile(TileDef::solid(texture));
+    for row in 0..10 {
+        map.set(0, row, Some(tile));
+    }
+    for col in 3..7 {
+        map.set(col, 6, Some(tile));
+    }
+    map.set(5, 2, Some(tile));
+    map
+}
+
+#[derive(Debug, Clone, Copy)]
+struct AimHit {
+    point: Vec2,
+    normal: Vec2,
+    on_tiles: bool,
+}
+
+fn trace(origin: Vec2, target: Vec2, solids: &[Solid2D], tiles: &TileMap) -> Option<AimHit> {
+    let ray = Ray2D::new(origin, target - origin).with_max_distance(1000.0);
+    let solid_hit = ray.cast_solids(solids);
+    let tile_ray = Ray2D {
+        origin: origin - MAP_ORIGIN,
+        ..ray
+    };
+    let tile_hit = tile_ray.cast_tilemap(tiles);
+
+    match (solid_hit, tile_hit) {
+        (Some(s), Some(t)) if t.distance < s.distance => Some(AimHit {
+            point: t.point + MAP_ORIGIN,
+            normal: t.normal,
+            on_tiles: true,
+        }),
+        (Some(s), _) => Some(AimHit {
+            point: s.point,
+            normal: s.normal,
+            on_tiles: false,
+        }),
+        (None, Some(t)) => Some(AimHit {
+            point: t.point + MAP_ORIGIN,
+            normal: t.normal,
+            on_tiles: true,
+        }),
+        (None, None) => None,
+    }
+}
+
+struct RaycastDemo {
+    player: Rect,
+    solids: Vec<Solid2D>,
+    tiles: TileMap,
+    aim: Vec2,
+    hit: Option<AimHit>,
+    dash: Option<SweepHit2D>,
+}
+
+impl Game for RaycastDemo {
+    fn new(engine: &mut Engine) -> Self {
+        let white = engine.create_color_texture(1, 1, Color::WHITE);
+        Self {
+            player: Rect::new(-300.0, -10.0, PLAYER_SIZE, PLAYER_SIZE),
+            solids: level_solids(),
+            tiles: level_tiles(white),
+            aim: Vec2::X,
+            hit: None,
+            dash: None,
+        }
+    }
+
+    fn update(&mut self, engine: &Engine, _frame: &mut Frame) {
+        let input = engine.input();
+        let dt = engine.dt().min(1.0 / 30.0);
+
+        let mut dir = Vec2::ZERO;
+        if input.is_key_down(KeyCode::KeyW) {
+            dir.y += 1.0;
+        }
+        if input.is_key_down(KeyCode::KeyS) {
+            dir.y -= 1.0;
+        }
+        if input.is_key_down(KeyCode::KeyA) {
+            dir.x -= 1.0;
+        }
+        if input.is_key_down(KeyCode::KeyD) {
+            dir.x += 1.0;
+        }
+        let motion = dir.normalize_or_zero() * PLAYER_SPEED * dt;
+        let moved = move_and_collide_solids(self.player, motion, &self.solids);
+        self.player.x = moved.position.x;
+        self.player.y = moved.position.y;
+
+        let (mx, my) = input.mouse_position();
+        self.aim = Vec2::new(mx, my);
+        let origin = self.player.center();
+        self.hit = trace(origin, self.aim, &self.solids, &self.tiles);
+
+        let dash = (self.aim - origin).normalize_or_zero() * DASH_LENGTH;
+        self.dash = BoxCast2D::new(self.player, dash).cast_solids(&self.solids);
+        if input.is_mouse_pressed(0) {
+            let stop = self
+                .dash
+                .map_or(Vec2::new(self.player.x, self.player.y) + dash, |hit| {
+                    hit.position
+                });
+            self.player.x = stop.x;
+            self.player.y = stop.y;
+        }
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(18, 20, 28, 255);
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+
+        for solid in &self.solids {
+            let r = solid.rect;
+            let color = if solid.one_way {
+                Color::from_rgba8(110, 130, 90, 255)
+            } else {
+                Color::from_rgba8(70, 80, 100, 255)
+            };
+            canvas.rect(r.x, r.y, r.width, r.height, color);
+        }
+        for row in 0..self.tiles.height {
+            for col in 0..self.tiles.width {
+                if self.tiles.get(col, row).is_some() {
+                    let p = self.tiles.cell_position(col, row) + MAP_ORIGIN;
+                    canvas.rect(
+                        p.x + 1.0,
+                        p.y + 1.0,
+                        TILE_SIZE - 2.0,
+                        TILE_SIZE - 2.0,
+                        Color::from_rgba8(90, 70, 110, 255),
+                    );
+                }
+            }
+        }
+
+        let origin = self.player.center();
+        match self.hit {
+            Some(hit) => {
+                let color = if hit.on_tiles {
+                    Color::from_rgba8(220, 140, 255, 255)
+                } else {
+                    Color::from_rgba8(255, 200, 90, 255)
+                };
+                canvas.line(origin.x, origin.y, hit.point.x, hit.point.y, 2.0, color);
+                canvas.circle_filled(hit.point.x, hit.point.y, 4.0, 12, color);
+                let tip = hit.point + hit.normal * 18.0;
+                canvas.line(hit.point.x, hit.point.y, tip.x, tip.y, 2.0, Color::WHITE);
+            }
+            None => canvas.line(
+                origin.x,
+                origin.y,
+                self.aim.x,
+                self.aim.y,
+                1.0,
+                Color::from_rgba8(120, 120, 120, 255),
+            ),
+        }
+
+        if let Some(dash) = self.dash {
+            canvas.rect(
+                dash.position.x,
+                dash.position.y,
+                PLAYER_SIZE,
+                PLAYER_SIZE,
+                Color::new(0.3, 0.8, 1.0, 0.3),
+            );
+        }
+
+        let p = self.player;
+        canvas.rect(
+            p.x,
+            p.y,
+            p.width,
+            p.height,
+            Color::from_rgba8(80, 200, 255, 255),
+        );
+
+        canvas.text(
+            -hw + 12.0,
+            hh - 24.0,
+            "WASD move, mouse aims the hitscan ray. Click to dash to the box-cast stop point.",
+            13.0,
+            Color::WHITE,
+        );
+    }
+}
+
+fn main() {
+    rengine::run::<RaycastDemo>(EngineConfig {
+        title: "Feature: Raycasts".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ray_toward_the_pillar_hits_its_left_face() {
+        let hit = trace(
+            Vec2::new(-300.0, 0.0),
+            Vec2::new(0.0, 0.0),
+            &level_solids(),
+            &TileMap::new(8, 10, TILE_SIZE),
+        )
+        .unwrap();
+        assert!(!hit.on_tiles);
+        assert_eq!(hit.normal, Vec2::new(-1.0, 0.0));
+        assert!((hit.point.x - -200.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn ray_up_through_the_one_way_platform_reaches_the_ceiling() {
+        let hit = trace(
+            Vec2::new(-100.0, -100.0),
+            Vec2::new(-100.0, 0.0),
+            &level_solids(),
+            &TileMap::new(8, 10, TILE_SIZE),
+        )
+        .unwrap();
+        assert_eq!(hit.normal, Vec2::new(0.0, -1.0));
+        assert!((hit.point.y - 200.0).abs() < 1e-3);
+    }
+}
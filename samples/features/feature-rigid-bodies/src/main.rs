@@ -0,0 +1,241 @@
+//! Feature: rigid-body dynamics.
+//!
+//! A [`PhysicsWorld2D`] stepped from `fixed_update`: a pyramid of crates, a
+//! bouncy ball and a capsule on a fixed ramp, all resting on plain
+//! [`Solid2D`] level geometry. Left click drops a crate or ball at the cursor,
+//! right click sets off an explosion impulse. Sleeping bodies are drawn dimmed.
+//!
+//! Coordinates are centered and y-up, so the canvas draws world space directly.
+
+use rengine::*;
+
+const CRATE_HALF: f32 = 14.0;
+const BLAST_RADIUS: f32 = 160.0;
+const BLAST_STRENGTH: f32 = 400.0;
+
+fn level_solids() -> Vec<Solid2D> {
+    vec![
+        Solid2D::solid(Rect::new(-380.0, -240.0, 760.0, 30.0)),
+        Solid2D::solid(Rect::new(-380.0, -240.0, 24.0, 480.0)),
+        Solid2D::solid(Rect::new(356.0, -240.0, 24.0, 480.0)),
+        Solid2D::one_way(Rect::new(140.0, 0.0, 160.0, 8.0)),
+    ]
+}
+
+fn crate_body(center: Vec2) -> RigidBody2D {
+    RigidBody2D::dynamic(
+        Shape2D::oriented_box(center, Vec2::splat(CRATE_HALF), 0.0),
+        1.0,
+    )
+    .with_friction(0.6)
+}
+
+fn ball_body(center: Vec2) -> RigidBody2D {
+    RigidBody2D::dynamic(Shape2D::circle(center, 12.0), 1.0).with_restitution(0.7)
+}
+
+fn initial_world() -> PhysicsWorld2D {
+    let mut world = PhysicsWorld2D::default();
+    let floor = -210.0 + CRATE_HALF;
+    for row in 0..4 {
+        for col in 0..(4 - row) {
+            let x = -200.0 + (col as f32 + row as f32 * 0.5) * CRATE_HALF * 2.0;
+            world.insert(crate_body(Vec2::new(
+                x,
+                floor + row as f32 * CRATE_HALF * 2.0,
+            )));
+        }
+    }
+    world.insert(RigidBody2D::fixed(Shape2D::oriented_box(
+        Vec2::new(60.0, -150.0),
+        Vec2::new(110.0, 8.0),
+        0.3,
+    )));
+    world.insert(
+        RigidBody2D::dynamic(
+            Shape2D::capsule(Vec2::new(80.0, -60.0), Vec2::new(120.0, -60.0), 10.0),
+            1.0,
+        )
+        .with_friction(0.3),
+    );
+    world.insert(ball_body(Vec2::new(220.0, 120.0)));
+    world
+}
+
+/// Push every body within [`BLAST_RADIUS`] of `center` away from it, harder
+/// the closer it is.
+fn explode(world: &mut PhysicsWorld2D, center: Vec2) {
+    let ids: Vec<RigidBodyId> = world.bodies().map(|(id, _)| id).collect();
+    for id in ids {
+        let Some(body) = world.get_mut(id) else {
+            continue;
+        };
+        let offset = body.position - center;
+        let distance = offset.length();
+        if !body.is_dynamic() || distance >= BLAST_RADIUS {
+            continue;
+        }
+        let falloff = 1.0 - distance / BLAST_RADIUS;
+        let impulse = offset.normalize_or(Vec2::Y) * BLAST_STRENGTH * falloff * body.mass();
+        let point = body.position + Vec2::new(0.0, 4.0);
+        body.apply_impulse(impulse, point);
+    }
+}
+
+fn draw_shape(canvas: &mut Canvas, shape: &Shape2D, color: Color) {
+    match *shape {
+        Shape2D::Aabb(r) => canvas.rect(r.x, r.y, r.width, r.height, color),
+        Shape2D::Circle { center, radius } => {
+            canvas.circle_filled(center.x, center.y, radius, 24, color)
+        }
+        Shape2D::Capsule { a, b, radius } => {
+            canvas.line(a.x, a.y, b.x, b.y, radius * 2.0, color);
+            canvas.circle_filled(a.x, a.y, radius, 16, color);
+            canvas.circle_filled(b.x, b.y, radius, 16, color);
+        }
+        Shape2D::Polygon(ref polygon) => {
+            let points: Vec<(f32, f32)> = polygon.points().iter().map(|p| (p.x, p.y)).collect();
+            canvas.polygon(&points, color);
+        }
+    }
+}
+
+struct RigidBodiesDemo {
+    world: PhysicsWorld2D,
+    solids: Vec<Solid2D>,
+    spawn_ball: bool,
+}
+
+impl Game for RigidBodiesDemo {
+    fn new(_engine: &mut Engine) -> Self {
+        Self {
+            world: initial_world(),
+            solids: level_solids(),
+            spawn_ball: false,
+        }
+    }
+
+    fn fixed_update(&mut self, engine: &Engine) {
+        self.world.step(engine.time().fixed_dt(), &self.solids);
+    }
+
+    fn update(&mut self, engine: &Engine, _frame: &mut Frame) {
+        let input = engine.input();
+        let (mx, my) = input.mouse_position();
+        let cursor = Vec2::new(mx, my);
+
+        if input.is_mouse_pressed(0) {
+            let body = if self.spawn_ball {
+                ball_body(cursor)
+            } else {
+                crate_body(cursor).with_rotation(0.4)
+            };
+            self.world.insert(body);
+            self.spawn_ball = !self.spawn_ball;
+        }
+        if input.is_mouse_pressed(1) {
+            explode(&mut self.world, cursor);
+        }
+        if input.is_key_pressed(KeyCode::KeyR) {
+            self.world = initial_world();
+        }
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(20, 22, 30, 255);
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+
+        for solid in &self.solids {
+            draw_shape(
+                canvas,
+                &solid.collider(),
+                Color::from_rgba8(70, 80, 100, 255),
+            );
+        }
+
+        let mut sleeping = 0;
+        for (_, body) in self.world.bodies() {
+            let color = if !body.is_dynamic() {
+                Color::from_rgba8(90, 100, 120, 255)
+            } else if body.is_sleeping() {
+                sleeping += 1;
+                Color::from_rgba8(110, 90, 70, 255)
+            } else {
+                Color::from_rgba8(220, 160, 90, 255)
+            };
+            draw_shape(canvas, &body.collider(), color);
+        }
+        for contact in self.world.contacts() {
+            canvas.circle_filled(
+                contact.point.x,
+                contact.point.y,
+                2.0,
+                6,
+                Color::from_rgba8(255, 80, 80, 255),
+            );
+        }
+
+        canvas.text(
+            -hw + 12.0,
+            hh - 24.0,
+            &format!(
+                "Left click spawns, right click explodes, R resets. Bodies: {}, sleeping: {sleeping}",
+                self.world.len()
+            ),
+            14.0,
+            Color::WHITE,
+        );
+    }
+}
+
+fn main() {
+    rengine::run::<RigidBodiesDemo>(EngineConfig {
+        title: "Feature: Rigid Bodies".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn the_pyramid_settles_and_falls_asleep() {
+        let solids = level_solids();
+        let mut world = initial_world();
+        let start: Vec<Vec2> = world.bodies().map(|(_, body)| body.position).collect();
+        for _ in 0..300 {
+            world.step(STEP, &solids);
+        }
+        for (id, body) in world.bodies().take(10) {
+            assert!(body.is_sleeping(), "crate {id} still awake");
+            assert!(body.position.distance(start[id]) < 2.0, "crate {id} slid");
+        }
+    }
+
+    #[test]
+    fn an_explosion_wakes_and_scatters_the_pyramid() {
+        let solids = level_solids();
+        let mut world = initial_world();
+        for _ in 0..300 {
+            world.step(STEP, &solids);
+        }
+        explode(&mut world, Vec2::new(-160.0, -220.0));
+        let mut peak = f32::NEG_INFINITY;
+        for _ in 0..30 {
+            world.step(STEP, &solids);
+            peak = peak.max(world.bodies().nth(9).unwrap().1.position.y);
+        }
+        assert!(world.bodies().take(10).all(|(_, body)| !body.is_sleeping()));
+        assert!(peak > -100.0, "top crate only reached {peak}");
+    }
+}
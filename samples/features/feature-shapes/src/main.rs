@@ -0,0 +1,210 @@
+//! Feature: non-rectangular collision shapes.
+//!
+//! A capsule-shaped [`KinematicBody2D`] runs over a level built from
+//! [`Shape2D`] solids: triangular ramps, a round bumper, and a tilted plank,
+//! alongside ordinary rectangles. A circular [`TriggerZone`] coin only fires
+//! when the capsule actually touches the circle, not its bounding box.
+//!
+//! Coordinates are centered and y-up, so the canvas draws world space directly.
+
+use rengine::*;
+
+const MOVE_SPEED: f32 = 240.0;
+const JUMP_SPEED: f32 = 430.0;
+const RESPAWN_BELOW_Y: f32 = -400.0;
+const PLAYER_ID: BodyId = 1;
+
+fn initial_player() -> KinematicBody2D {
+    KinematicBody2D::new(Rect::new(0.0, 0.0, 0.0, 0.0)).with_shape(Shape2D::vertical_capsule(
+        Vec2::new(-290.0, 100.0),
+        10.0,
+        12.0,
+    ))
+}
+
+fn level_solids() -> Vec<Solid2D> {
+    let ramp = |points: &[Vec2]| Solid2D::shaped(Shape2D::polygon(points).unwrap());
+    vec![
+        Solid2D::solid(Rect::new(-360.0, -200.0, 720.0, 30.0)),
+        ramp(&[
+            Vec2::new(-200.0, -170.0),
+            Vec2::new(-40.0, -170.0),
+            Vec2::new(-40.0, -90.0),
+        ]),
+        ramp(&[
+            Vec2::new(-40.0, -170.0),
+            Vec2::new(-40.0, -90.0),
+            Vec2::new(120.0, -170.0),
+        ]),
+        Solid2D::shaped(Shape2D::circle(Vec2::new(220.0, -170.0), 40.0)),
+        Solid2D::shaped(Shape2D::oriented_box(
+            Vec2::new(130.0, 10.0),
+            Vec2::new(90.0, 8.0),
+            -0.25,
+        )),
+        Solid2D::solid(Rect::new(-360.0, -200.0, 24.0, 380.0)),
+        Solid2D::solid(Rect::new(336.0, -200.0, 24.0, 380.0)),
+    ]
+}
+
+fn draw_shape(canvas: &mut Canvas, shape: &Shape2D, color: Color) {
+    match *shape {
+        Shape2D::Aabb(r) => canvas.rect(r.x, r.y, r.width, r.height, color),
+        Shape2D::Circle { center, radius } => {
+            canvas.circle_filled(center.x, center.y, radius, 32, color)
+        }
+        Shape2D::Capsule { a, b, radius } => {
+            canvas.line(a.x, a.y, b.x, b.y, radius * 2.0, color);
+            canvas.circle_filled(a.x, a.y, radius, 16, color);
+            canvas.circle_filled(b.x, b.y, radius, 16, color);
+        }
+        Shape2D::Polygon(ref polygon) => {
+            let points: Vec<(f32, f32)> = polygon.points().iter().map(|p| (p.x, p.y)).collect();
+            canvas.polygon(&points, color);
+        }
+    }
+}
+
+struct ShapesDemo {
+    player: KinematicBody2D,
+    solids: Vec<Solid2D>,
+    triggers: TriggerSystem,
+    coin: TriggerZoneId,
+    coins: u32,
+}
+
+impl Game for ShapesDemo {
+    fn new(_engine: &mut Engine) -> Self {
+        let mut triggers = TriggerSystem::new();
+        let coin = triggers.add_zone(TriggerZone::shaped(Shape2D::circle(
+            Vec2::new(-40.0, -40.0),
+            14.0,
+        )));
+        Self {
+            player: initial_player(),
+            solids: level_solids(),
+            triggers,
+            coin,
+            coins: 0,
+        }
+    }
+
+    fn update(&mut self, engine: &Engine, _frame: &mut Frame) {
+        let input = engine.input();
+        let dt = engine.dt().min(1.0 / 30.0);
+
+        let mut vx = 0.0;
+        if input.is_key_down(KeyCode::ArrowLeft) || input.is_key_down(KeyCode::KeyA) {
+            vx -= MOVE_SPEED;
+        }
+        if input.is_key_down(KeyCode::ArrowRight) || input.is_key_down(KeyCode::KeyD) {
+            vx += MOVE_SPEED;
+        }
+        self.player.velocity.x = vx;
+
+        let jump = input.is_key_pressed(KeyCode::Space) || input.is_key_pressed(KeyCode::ArrowUp);
+        if jump && self.player.on_ground() {
+            self.player.velocity.y = JUMP_SPEED;
+        }
+
+        self.player.step_solids(dt, &self.solids);
+        if self.player.bounds.y < RESPAWN_BELOW_Y {
+            self.player = initial_player();
+        }
+
+        self.triggers.tick_shapes(&[(
+            PLAYER_ID,
+            self.player.collider(),
+            CollisionLayer::default(),
+        )]);
+        if self.triggers.event_for(self.coin, PLAYER_ID) == Some(OverlapEvent::Enter) {
+            self.coins += 1;
+        }
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(20, 22, 30, 255);
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+
+        for solid in &self.solids {
+            draw_shape(
+                canvas,
+                &solid.collider(),
+                Color::from_rgba8(70, 80, 100, 255),
+            );
+        }
+
+        let coin_color = if self.triggers.overlapping(self.coin, PLAYER_ID) {
+            Color::from_rgba8(255, 240, 140, 255)
+        } else {
+            Color::from_rgba8(230, 190, 60, 255)
+        };
+        draw_shape(
+            canvas,
+            &self.triggers.zone(self.coin).collider(),
+            coin_color,
+        );
+
+        let color = if self.player.on_ground() {
+            Color::from_rgba8(120, 200, 140, 255)
+        } else {
+            Color::from_rgba8(230, 180, 90, 255)
+        };
+        draw_shape(canvas, &self.player.collider(), color);
+
+        canvas.text(
+            -hw + 20.0,
+            hh - 28.0,
+            &format!(
+                "Shapes: A/D to move, Space to jump over ramps and the bumper. Coin touches: {}",
+                self.coins
+            ),
+            15.0,
+            Color::WHITE,
+        );
+    }
+}
+
+fn main() {
+    rengine::run::<ShapesDemo>(EngineConfig {
+        title: "Feature: Collision Shapes".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capsule_settles_on_the_ground() {
+        let solids = level_solids();
+        let mut player = initial_player();
+        for _ in 0..240 {
+            player.step_solids(1.0 / 60.0, &solids);
+        }
+        assert!(player.on_ground());
+        assert!((player.bounds.bottom() - -170.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn capsule_rests_on_the_ramp_surface_not_its_bounding_box() {
+        let solids = level_solids();
+        let mut player = KinematicBody2D::new(Rect::new(0.0, 0.0, 0.0, 0.0)).with_shape(
+            Shape2D::vertical_capsule(Vec2::new(-100.0, 0.0), 10.0, 12.0),
+        );
+        for _ in 0..60 {
+            player.step_solids(1.0 / 60.0, &solids);
+        }
+        assert!(player.on_ground());
+        // The ramp's box top is -90; at x = -100 the slope is far lower.
+        assert!(player.bounds.bottom() < -100.0);
+    }
+}
@@ -0,0 +1,311 @@
+//! Feature: steering behaviours.
+//!
+//! A dozen agents pick one of the classic behaviours at a time: seeking,
+//! fleeing or arriving at the cursor, pursuing a wandering quarry, wandering
+//! on their own, following a looped path, or flocking. Each frame builds a
+//! [`Steering`] blend for every agent, with obstacle avoidance and separation
+//! mixed in by weight, and stores the resulting velocity straight into the
+//! agent, exactly as a [`KinematicBody2D`] would take it.
+
+use rengine::*;
+
+const ARENA: Vec2 = Vec2::new(380.0, 250.0);
+const RADIUS: f32 = 7.0;
+const SPEED: f32 = 110.0;
+const BOIDS: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Seek,
+    Flee,
+    Arrive,
+    Pursue,
+    Wander,
+    Path,
+    Flock,
+}
+
+impl Mode {
+    const ALL: [(KeyCode, Mode); 7] = [
+        (KeyCode::Digit1, Mode::Seek),
+        (KeyCode::Digit2, Mode::Flee),
+        (KeyCode::Digit3, Mode::Arrive),
+        (KeyCode::Digit4, Mode::Pursue),
+        (KeyCode::Digit5, Mode::Wander),
+        (KeyCode::Digit6, Mode::Path),
+        (KeyCode::Digit7, Mode::Flock),
+    ];
+}
+
+struct Boid {
+    agent: SteeringAgent,
+    wander: Wander,
+    path: PathFollower,
+}
+
+fn level_solids() -> Vec<Solid2D> {
+    let mut solids = vec![
+        Solid2D::solid(Rect::new(-170.0, -90.0, 60.0, 180.0)),
+        Solid2D::solid(Rect::new(60.0, 60.0, 140.0, 40.0)),
+        Solid2D::solid(Rect::new(110.0, -150.0, 50.0, 90.0)),
+    ];
+    // The arena's walls.
+    let (w, h) = (ARENA.x, ARENA.y);
+    solids.push(Solid2D::solid(Rect::new(
+        -w - 20.0,
+        -h - 20.0,
+        w * 2.0 + 40.0,
+        20.0,
+    )));
+    solids.push(Solid2D::solid(Rect::new(
+        -w - 20.0,
+        h,
+        w * 2.0 + 40.0,
+        20.0,
+    )));
+    solids.push(Solid2D::solid(Rect::new(-w - 20.0, -h, 20.0, h * 2.0)));
+    solids.push(Solid2D::solid(Rect::new(w, -h, 20.0, h * 2.0)));
+    solids
+}
+
+/// Move a square of `RADIUS` by `delta`, pushed back out of the solids it
+/// would overlap.
+fn slide(solids: &[Solid2D], position: Vec2, delta: Vec2) -> Vec2 {
+    let size = Vec2::splat(RADIUS * 2.0);
+    let mut rect = Rect::from_pos_size(position + delta - size / 2.0, size);
+    for solid in solids {
+        if let Some(mtv) = aabb_overlap(&rect, &solid.rect) {
+            rect.x += mtv.x;
+            rect.y += mtv.y;
+        }
+    }
+    rect.center()
+}
+
+fn patrol_route() -> Vec<Vec2> {
+    vec![
+        Vec2::new(-280.0, -180.0),
+        Vec2::new(-40.0, -190.0),
+        Vec2::new(40.0, 0.0),
+        Vec2::new(280.0, 10.0),
+        Vec2::new(260.0, 190.0),
+        Vec2::new(-60.0, 170.0),
+        Vec2::new(-280.0, 150.0),
+    ]
+}
+
+struct SteeringDemo {
+    mode: Mode,
+    solids: Vec<Solid2D>,
+    boids: Vec<Boid>,
+    quarry: SteeringAgent,
+    quarry_wander: Wander,
+    rng: Rng,
+    cursor: Vec2,
+}
+
+impl SteeringDemo {
+    fn with_seed(seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let boids = (0..BOIDS)
+            .map(|i| {
+                let position =
+                    Vec2::new(rng.f32_range(-300.0, -220.0), rng.f32_range(-200.0, 200.0));
+                let heading = Vec2::from_angle(rng.f32_range(0.0, std::f32::consts::TAU));
+                // Everyone walks the same loop, starting at different points.
+                let mut route = patrol_route();
+                let len = route.len();
+                route.rotate_left(i % len);
+                Boid {
+                    agent: SteeringAgent::new(position, SPEED)
+                        .with_velocity(heading * SPEED * 0.5)
+                        .with_radius(RADIUS),
+                    wander: Wander::new(40.0, 24.0, 5.0),
+                    path: PathFollower::new(route, 16.0).with_looped(true),
+                }
+            })
+            .collect();
+        Self {
+            mode: Mode::Seek,
+            solids: level_solids(),
+            boids,
+            quarry: SteeringAgent::new(Vec2::new(250.0, -200.0), SPEED * 0.7).with_radius(RADIUS),
+            quarry_wander: Wander::new(50.0, 30.0, 3.0),
+            rng,
+            cursor: Vec2::new(250.0, -100.0),
+        }
+    }
+
+    /// Steer and move every agent for one frame.
+    fn step(&mut self, dt: f32) {
+        let quarry = Steering::new(&self.quarry, dt)
+            .wander(&mut self.quarry_wander, &mut self.rng, 1.0)
+            .avoid_solids(&self.solids, 0.6, 4.0)
+            .velocity();
+        self.quarry.velocity = quarry;
+        self.quarry.position = slide(&self.solids, self.quarry.position, quarry * dt);
+
+        let flock: Vec<SteeringAgent> = self.boids.iter().map(|boid| boid.agent).collect();
+        for (boid, me) in self.boids.iter_mut().zip(&flock) {
+            let steering = Steering::new(me, dt);
+            let steering = match self.mode {
+                Mode::Seek => steering.seek(self.cursor, 1.0),
+                Mode::Flee => steering.flee(self.cursor, 150.0, 2.0).wander(
+                    &mut boid.wander,
+                    &mut self.rng,
+                    0.5,
+                ),
+                Mode::Arrive => steering.arrive(self.cursor, 90.0, 1.0),
+                Mode::Pursue => steering.pursue(self.quarry.position, self.quarry.velocity, 1.0),
+                Mode::Wander => steering.wander(&mut boid.wander, &mut self.rng, 1.0),
+                Mode::Path => steering.follow_path(&mut boid.path, 1.0),
+                Mode::Flock => steering
+                    .wander(&mut boid.wander, &mut self.rng, 0.3)
+                    .alignment(&flock, 70.0, 1.0)
+                    .cohesion(&flock, 90.0, 0.6),
+            };
+            let velocity = steering
+                .separation(&flock, RADIUS * 3.5, 1.5)
+                .avoid_solids(&self.solids, 0.5, 4.0)
+                .velocity();
+            boid.agent.position = slide(&self.solids, me.position, velocity * dt);
+            boid.agent.velocity = velocity;
+        }
+    }
+}
+
+impl Game for SteeringDemo {
+    fn new(_engine: &mut Engine) -> Self {
+        Self::with_seed(5)
+    }
+
+    fn update(&mut self, engine: &Engine, frame: &mut Frame) {
+        let input = engine.input();
+        for (key, mode) in Mode::ALL {
+            if input.is_key_pressed(key) {
+                self.mode = mode;
+            }
+        }
+        let (mx, my) = input.mouse_position();
+        self.cursor = frame.camera.screen_to_world(Vec2::new(mx, my));
+        self.step(engine.dt().min(1.0 / 30.0));
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(16, 18, 26, 255);
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+
+        for solid in &self.solids {
+            let b = solid.rect;
+            canvas.rect(
+                b.x,
+                b.y,
+                b.width,
+                b.height,
+                Color::from_rgba8(70, 76, 92, 255),
+            );
+        }
+        match self.mode {
+            Mode::Seek | Mode::Flee | Mode::Arrive => {
+                let radius = if self.mode == Mode::Flee { 150.0 } else { 10.0 };
+                canvas.circle(
+                    self.cursor.x,
+                    self.cursor.y,
+                    radius,
+                    1.5,
+                    32,
+                    Color::from_rgba8(255, 220, 90, 255),
+                );
+            }
+            Mode::Pursue => canvas.circle_filled(
+                self.quarry.position.x,
+                self.quarry.position.y,
+                RADIUS + 2.0,
+                12,
+                Color::from_rgba8(120, 220, 140, 255),
+            ),
+            Mode::Path => {
+                let mut route: Vec<(f32, f32)> =
+                    patrol_route().iter().map(|p| (p.x, p.y)).collect();
+                route.push(route[0]);
+                canvas.polyline(&route, 1.5, Color::from_rgba8(110, 130, 170, 255));
+            }
+            Mode::Wander | Mode::Flock => {}
+        }
+        for boid in &self.boids {
+            let p = boid.agent.position;
+            let tip = p + boid.agent.heading() * RADIUS * 2.0;
+            canvas.circle_filled(p.x, p.y, RADIUS, 10, Color::from_rgba8(240, 130, 90, 255));
+            canvas.line(p.x, p.y, tip.x, tip.y, 2.0, Color::WHITE);
+        }
+
+        canvas.text(
+            -hw + 12.0,
+            hh - 24.0,
+            "1 seek  2 flee  3 arrive  4 pursue  5 wander  6 path  7 flock",
+            14.0,
+            Color::WHITE,
+        );
+        canvas.text(
+            -hw + 12.0,
+            hh - 44.0,
+            &format!(
+                "Behaviour: {:?}, with separation and obstacle avoidance",
+                self.mode
+            ),
+            14.0,
+            Color::from_rgba8(180, 200, 230, 255),
+        );
+    }
+}
+
+fn main() {
+    rengine::run::<SteeringDemo>(EngineConfig {
+        title: "Feature: Steering".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(mode: Mode, cursor: Vec2, seconds: f32) -> SteeringDemo {
+        let mut demo = SteeringDemo::with_seed(2);
+        demo.mode = mode;
+        demo.cursor = cursor;
+        for _ in 0..(seconds * 60.0) as usize {
+            demo.step(1.0 / 60.0);
+        }
+        demo
+    }
+
+    #[test]
+    fn arriving_boids_gather_at_the_cursor_and_settle() {
+        let demo = run(Mode::Arrive, Vec2::new(0.0, -200.0), 10.0);
+        for boid in &demo.boids {
+            assert!(boid.agent.position.distance(demo.cursor) < 80.0);
+            assert!(boid.agent.velocity.length() < SPEED * 0.5);
+        }
+    }
+
+    #[test]
+    fn every_behaviour_stays_in_the_arena_and_repeats_exactly() {
+        for (_, mode) in Mode::ALL {
+            let demo = run(mode, Vec2::ZERO, 6.0);
+            let again = run(mode, Vec2::ZERO, 6.0);
+            for (boid, twin) in demo.boids.iter().zip(&again.boids) {
+                assert_eq!(boid.agent, twin.agent);
+                let p = boid.agent.position;
+                assert!(p.x.abs() <= ARENA.x && p.y.abs() <= ARENA.y, "{mode:?} {p}");
+            }
+        }
+    }
+}
@@ -54,13 +54,16 @@ impl Game for TextDemo {
             (TextAlign::Left, "Left-aligned text"),
             (TextAlign::Center, "Center-aligned text"),
             (TextAlign::Right, "Right-aligned text"),
+            (TextAlign::End, "End: right for left-to-right text"),
         ];
         for (align, txt) in &aligns {
             canvas.rect(align_x - guide_w / 2.0, y - 2.0, guide_w, 1.0, dim_color);
-            let ax = match align {
-                TextAlign::Left => align_x - guide_w / 2.0,
+            // `Start` and `End` anchor on whichever edge the text's
+            // direction makes them.
+            let ax = match align.resolve(txt) {
                 TextAlign::Center => align_x,
-                TextAlign::Right => align_x + guide_w / 2.0,
+                TextAlign::Right | TextAlign::End => align_x + guide_w / 2.0,
+                TextAlign::Left | TextAlign::Start => align_x - guide_w / 2.0,
             };
             canvas.text_aligned(ax, y, txt, body, text_color, *align);
             y -= 30.0;
@@ -135,9 +138,9 @@ impl Game for TextDemo {
             );
 
             let ax = match align {
-                TextAlign::Left => bx,
+                TextAlign::Left | TextAlign::Start => bx,
                 TextAlign::Center => bx + block_w / 2.0,
-                TextAlign::Right => bx + block_w,
+                TextAlign::Right | TextAlign::End => bx + block_w,
             };
             canvas.text_block(ax, by, short_text, body, text_color, block_w, *align);
         }
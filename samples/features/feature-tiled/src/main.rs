@@ -0,0 +1,309 @@
+//! Feature: Tiled map import.
+//!
+//! `assets/level.tmx` was laid out in the Tiled editor against an external
+//! `terrain.tsx` tileset, and loads through `tiled.assets.json` like any other
+//! asset. [`TiledMapAsset::level`] turns it into a [`TileMap`] per tile layer
+//! (flipped and rotated tiles included), [`Solid2D`]s for the one-way ledge,
+//! tilted plank and L-shaped steps, [`TriggerZone`]s for the coins and exit,
+//! and spawn markers carrying their custom properties. Half-height slabs and
+//! ramps collide through the per-tile shapes drawn in the tileset.
+//!
+//! Save the map in Tiled while this runs and the level rebuilds in place.
+//!
+//! The camera centres the map; canvas overlays are drawn relative to it.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use rengine::*;
+
+const PLAYER_SIZE: Vec2 = Vec2::new(20.0, 28.0);
+const MOVE_SPEED: f32 = 220.0;
+const JUMP_SPEED: f32 = 560.0;
+const PLAYER_BODY: BodyId = 1;
+
+/// Everything the simulation needs from a built [`TiledLevel`].
+struct World {
+    level: TiledLevel,
+    solids: Vec<Solid2D>,
+    triggers: TriggerSystem,
+    /// Zone ids, parallel to `level.triggers`.
+    zones: Vec<TriggerZoneId>,
+    /// Object ids of coins already picked up; kept across hot reloads.
+    collected: HashSet<u32>,
+    player: KinematicBody2D,
+    message: Option<String>,
+    steer: f32,
+    jump: bool,
+}
+
+impl World {
+    fn new(level: TiledLevel) -> Self {
+        let spawn = level
+            .spawn("player")
+            .map_or(Vec2::new(64.0, 96.0), |spawn| spawn.position);
+        let player =
+            KinematicBody2D::new(Rect::from_pos_size(spawn - PLAYER_SIZE / 2.0, PLAYER_SIZE))
+                .with_ground_snap(6.0);
+        let mut world = Self {
+            level: TiledLevel::default(),
+            solids: Vec::new(),
+            triggers: TriggerSystem::new(),
+            zones: Vec::new(),
+            collected: HashSet::new(),
+            player,
+            message: None,
+            steer: 0.0,
+            jump: false,
+        };
+        world.replace_level(level);
+        world
+    }
+
+    /// Swap in a rebuilt level, keeping the player and collected coins.
+    fn replace_level(&mut self, level: TiledLevel) {
+        self.solids = level.all_solids();
+        self.triggers = TriggerSystem::new();
+        self.zones = level.add_triggers(&mut self.triggers);
+        self.level = level;
+    }
+
+    fn coins_left(&self) -> usize {
+        self.level
+            .triggers
+            .iter()
+            .filter(|t| t.layer == "coins" && !self.collected.contains(&t.id))
+            .count()
+    }
+
+    fn tick(&mut self, dt: f32) {
+        self.player.velocity.x = self.steer * MOVE_SPEED;
+        if self.jump && self.player.on_ground() {
+            self.player.velocity.y = JUMP_SPEED;
+        }
+        self.jump = false;
+        self.player.step_solids(dt, &self.solids);
+
+        let body = (PLAYER_BODY, self.player.bounds, CollisionLayer::default());
+        self.triggers.tick(&[body]);
+        for (zone, _) in self.triggers.entered() {
+            let Some(index) = self.zones.iter().position(|&id| id == zone) else {
+                continue;
+            };
+            let trigger = &self.level.triggers[index];
+            if trigger.layer == "coins" {
+                self.collected.insert(trigger.id);
+            } else if let Some(message) = trigger.properties.get_str("message") {
+                self.message = Some(message.to_string());
+            }
+        }
+    }
+}
+
+fn draw_shape(canvas: &mut Canvas, shape: &Shape2D, color: Color) {
+    match *shape {
+        Shape2D::Aabb(r) => canvas.rect(r.x, r.y, r.width, r.height, color),
+        Shape2D::Circle { center, radius } => {
+            canvas.circle_filled(center.x, center.y, radius, 24, color)
+        }
+        Shape2D::Capsule { a, b, radius } => {
+            canvas.line(a.x, a.y, b.x, b.y, radius * 2.0, color);
+            canvas.circle_filled(a.x, a.y, radius, 16, color);
+            canvas.circle_filled(b.x, b.y, radius, 16, color);
+        }
+        Shape2D::Polygon(ref polygon) => {
+            let points: Vec<(f32, f32)> = polygon.points().iter().map(|p| (p.x, p.y)).collect();
+            canvas.polygon(&points, color);
+        }
+    }
+}
+
+struct TiledDemo {
+    _assets: AssetBundle,
+    map: TiledMapAsset,
+    world: World,
+    show_colliders: bool,
+    reloads: u32,
+}
+
+impl Game for TiledDemo {
+    fn new(engine: &mut Engine) -> Self {
+        engine.set_asset_root(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets"));
+        let assets = engine
+            .load_asset_bundle("tiled.assets.json")
+            .expect("failed to load the Tiled level");
+        let map = assets.tiled_map("level").expect("level map").clone();
+        Self {
+            world: World::new(map.level()),
+            map,
+            _assets: assets,
+            show_colliders: false,
+            reloads: 0,
+        }
+    }
+
+    fn fixed_update(&mut self, engine: &Engine) {
+        self.world.tick(engine.time().fixed_dt());
+    }
+
+    fn update(&mut self, engine: &Engine, _frame: &mut Frame) {
+        if let Some(map) = engine.reloaded_tiled_map(&self.map) {
+            self.world.replace_level(map.level());
+            self.map = map;
+            self.reloads += 1;
+        }
+
+        let input = engine.input();
+        let left = input.is_key_down(KeyCode::KeyA) || input.is_key_down(KeyCode::ArrowLeft);
+        let right = input.is_key_down(KeyCode::KeyD) || input.is_key_down(KeyCode::ArrowRight);
+        self.world.steer = (right as i32 - left as i32) as f32;
+        self.world.jump |= input.is_key_pressed(KeyCode::Space);
+        if input.is_key_pressed(KeyCode::Tab) {
+            self.show_colliders = !self.show_colliders;
+        }
+        if input.is_key_pressed(KeyCode::KeyR) {
+            self.world = World::new(self.map.level());
+        }
+    }
+
+    fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+        frame.clear_color = Color::from_rgba8(24, 28, 44, 255);
+        let map = self.map.map();
+        let center = Vec2::new(map.width as f32 * map.tile_width as f32, map.pixel_height()) / 2.0;
+        frame.camera.position = center;
+        self.world.level.draw(frame);
+
+        let (w, h) = engine.window_size();
+        let hw = w as f32 / 2.0;
+        let hh = h as f32 / 2.0;
+        let canvas = frame.canvas(0);
+        let world = &self.world;
+
+        for solid in &world.solids {
+            if solid.one_way {
+                let r = solid.rect;
+                let color = Color::from_rgba8(140, 200, 255, 255);
+                canvas.rect(r.x - center.x, r.y - center.y, r.width, r.height, color);
+            } else if self.show_colliders || solid.shape.is_some() {
+                let color = Color::from_rgba8(120, 140, 170, 200);
+                draw_shape(canvas, &solid.collider().translated(-center), color);
+            }
+        }
+        for trigger in &world.level.triggers {
+            let shape = trigger.zone.collider().translated(-center);
+            if trigger.layer == "coins" {
+                if !world.collected.contains(&trigger.id) {
+                    draw_shape(canvas, &shape, Color::from_rgba8(255, 215, 80, 255));
+                }
+            } else {
+                draw_shape(canvas, &shape, Color::from_rgba8(120, 255, 160, 90));
+            }
+        }
+        for spawn in &world.level.spawns {
+            let p = spawn.position - center;
+            canvas.text(p.x - 30.0, p.y + 8.0, &spawn.name, 12.0, Color::WHITE);
+        }
+
+        let b = world.player.bounds;
+        canvas.rect(
+            b.x - center.x,
+            b.y - center.y,
+            b.width,
+            b.height,
+            Color::from_rgba8(250, 120, 110, 255),
+        );
+
+        let title = map.properties.get_str("title").unwrap_or("Tiled map");
+        canvas.text(
+            -hw + 12.0,
+            hh - 24.0,
+            &format!(
+                "{title}: A/D move, Space jump, Tab colliders, R restart. \
+                 Coins left: {}. Reloads: {}",
+                world.coins_left(),
+                self.reloads
+            ),
+            14.0,
+            Color::WHITE,
+        );
+        if let Some(message) = &world.message {
+            canvas.text(-hw + 12.0, hh - 44.0, message, 14.0, Color::YELLOW);
+        }
+    }
+}
+
+fn main() {
+    rengine::run::<TiledDemo>(EngineConfig {
+        title: "Feature: Tiled Maps".into(),
+        width: 800,
+        height: 600,
+        show_fps: false,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    const STEP: f32 = 1.0 / 60.0;
+
+    /// The level without a renderer: tile layers have no sprite sheets, so
+    /// only the map's objects turn into colliders and triggers.
+    fn level() -> TiledLevel {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+        let mut map = TiledMap::parse(Path::new("level.tmx"), &read("level.tmx")).unwrap();
+        let tileset = TiledTileset::parse(Path::new("terrain.tsx"), &read("terrain.tsx"));
+        map.tilesets[0] = TiledTileset {
+            first_gid: map.tilesets[0].first_gid,
+            ..tileset.unwrap()
+        };
+        map.build(&[])
+    }
+
+    #[test]
+    fn level_objects_become_spawns_triggers_and_solids() {
+        let level = level();
+
+        assert!(level.spawn("player").is_some());
+        let checkpoint = level.spawn("checkpoint").unwrap();
+        assert_eq!(checkpoint.class, "checkpoint");
+        assert_eq!(checkpoint.properties.get_int("order"), Some(1));
+
+        let world = World::new(level);
+        assert_eq!(world.coins_left(), 7);
+        let exit = world.level.triggers.iter().find(|t| t.name == "exit");
+        assert_eq!(
+            exit.and_then(|t| t.properties.get_str("message")),
+            Some("You found the exit!")
+        );
+        assert!(world.solids.iter().any(|s| s.one_way));
+        // The L-shaped steps are concave, so they arrive in pieces.
+        assert!(world.solids.iter().filter(|s| s.shape.is_some()).count() >= 3);
+    }
+
+    #[test]
+    fn player_lands_on_the_ledge_and_collects_its_coins() {
+        let mut world = World::new(level());
+        let ledge = world.solids.iter().find(|s| s.one_way).unwrap().rect;
+        // Drop the player onto the one-way ledge, between its two coins.
+        world.player.bounds = Rect::from_pos_size(
+            Vec2::new(ledge.center().x - PLAYER_SIZE.x / 2.0, ledge.top() + 40.0),
+            PLAYER_SIZE,
+        );
+        for _ in 0..60 {
+            world.tick(STEP);
+        }
+        assert!(world.player.on_ground());
+        assert!((world.player.bounds.y - ledge.top()).abs() < 1.0);
+
+        world.steer = 1.0;
+        for _ in 0..20 {
+            world.tick(STEP);
+        }
+        assert_eq!(world.coins_left(), 6);
+    }
+}
@@ -1,3 +1,10 @@
+//! Feature: layered, chunked tile maps.
+//!
+//! A [`LayeredTileMap`] holds the floor, the walls the player collides with
+//! and a leafy canopy drawn above everything with a little parallax. Pillars
+//! use a [`TileShape`] smaller than their cell, so the player can squeeze
+//! round them. Only the chunks the camera can see are drawn.
+
 use rengine::pixelart::PixelCanvas;
 use rengine::*;
 
@@ -8,8 +15,7 @@ const PLAYER_SIZE: f32 = 24.0;
 const PLAYER_SPEED: f32 = 160.0;
 
 struct TileMapDemo {
-    map: TileMap,
-    floor_map: TileMap,
+    map: LayeredTileMap,
     player_tex: TextureId,
     player_pos: Vec2,
 }
@@ -40,6 +46,22 @@ fn make_floor_texture(engine: &mut Engine) -> TextureId {
     engine.create_texture(16, 16, &bytes)
 }
 
+fn make_pillar_texture(engine: &mut Engine) -> TextureId {
+    let mut pc = PixelCanvas::new(16, 16);
+    pc.fill_circle(8, 8, 4, Color::new(0.5, 0.45, 0.42, 1.0));
+    pc.fill_circle(7, 7, 2, Color::new(0.62, 0.58, 0.55, 1.0));
+    let bytes = pc.into_bytes();
+    engine.create_texture(16, 16, &bytes)
+}
+
+fn make_leaves_texture(engine: &mut Engine) -> TextureId {
+    let mut pc = PixelCanvas::new(16, 16);
+    pc.fill_circle(8, 8, 7, Color::new(0.2, 0.45, 0.2, 0.8));
+    pc.fill_circle(5, 6, 3, Color::new(0.3, 0.55, 0.25, 0.8));
+    let bytes = pc.into_bytes();
+    engine.create_texture(16, 16, &bytes)
+}
+
 fn make_player_texture(engine: &mut Engine) -> TextureId {
     let mut pc = PixelCanvas::new(12, 12);
     pc.fill_circle(6, 6, 5, Color::new(0.3, 0.6, 1.0, 1.0));
@@ -57,9 +79,9 @@ const LEVEL: [u8; MAP_W * MAP_H] = [
     1,0,0,1,1,1,0,0,0,0,0,0,0,1,1,1,0,0,0,1,
     1,0,0,1,0,0,0,0,0,0,0,0,0,0,0,1,0,0,0,1,
     1,0,0,1,0,0,0,0,0,0,0,0,0,0,0,1,0,0,0,1,
+    1,0,0,0,0,0,2,0,1,1,0,2,0,0,0,0,0,0,0,1,
     1,0,0,0,0,0,0,0,1,1,0,0,0,0,0,0,0,0,0,1,
-    1,0,0,0,0,0,0,0,1,1,0,0,0,0,0,0,0,0,0,1,
-    1,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,1,
+    1,0,0,0,0,0,2,0,0,0,0,2,0,0,0,0,0,0,0,1,
     1,0,0,1,0,0,0,0,0,0,0,0,0,0,0,1,0,0,0,1,
     1,0,0,1,0,0,0,0,0,0,0,0,0,0,0,1,0,0,0,1,
     1,0,0,1,1,1,0,0,0,0,0,0,0,1,1,1,0,0,0,1,
@@ -74,17 +96,33 @@ impl Game for TileMapDemo {
         let floor_tex = make_floor_texture(engine);
         let player_tex = make_player_texture(engine);
 
-        let mut map = TileMap::new(MAP_W, MAP_H, TILE_SIZE);
+        let pillar_tex = make_pillar_texture(engine);
+        let leaves_tex = make_leaves_texture(engine);
+
+        let mut map = LayeredTileMap::new(MAP_W, MAP_H, TILE_SIZE);
+        let floor_id = map.add_tile(TileDef::solid(floor_tex).with_collision(false));
         let wall_id = map.add_tile(TileDef::solid(wall_tex));
+        let pillar_id = map.add_tile(
+            TileDef::solid(pillar_tex).with_shape(TileShape::Rect([0.25, 0.25, 0.5, 0.5])),
+        );
+        let leaves_id = map.add_tile(TileDef::solid(leaves_tex).with_collision(false));
 
-        let mut floor_map = TileMap::new(MAP_W, MAP_H, TILE_SIZE);
-        let floor_id = floor_map.add_tile(TileDef::solid(floor_tex));
+        map.add_layer("floor", -10);
+        map.add_layer("walls", 0);
+        let canopy = map.add_layer("canopy", 10);
+        canopy.collides = false;
+        canopy.map.parallax = Vec2::splat(1.15);
 
         for row in 0..MAP_H {
             for col in 0..MAP_W {
-                floor_map.set(col, row, Some(floor_id));
-                if LEVEL[row * MAP_W + col] == 1 {
-                    map.set(col, row, Some(wall_id));
+                map.set("floor", col, row, Some(floor_id));
+                match LEVEL[row * MAP_W + col] {
+                    1 => map.set("walls", col, row, Some(wall_id)),
+                    2 => map.set("walls", col, row, Some(pillar_id)),
+                    _ => {}
+                }
+                if (col * 7 + row * 3) % 11 == 0 {
+                    map.set("canopy", col, row, Some(leaves_id));
                 }
             }
         }
@@ -96,7 +134,6 @@ impl Game for TileMapDemo {
 
         Self {
             map,
-            floor_map,
             player_tex,
             player_pos,
         }
@@ -126,6 +163,12 @@ impl Game for TileMapDemo {
 
         self.player_pos += dir * PLAYER_SPEED * dt;
 
+        if input.is_key_pressed(KeyCode::KeyC) {
+            if let Some(canopy) = self.map.layer_mut("canopy") {
+                canopy.visible = !canopy.visible;
+            }
+        }
+
         let half = PLAYER_SIZE * 0.5;
         let player_rect = Rect::new(
             self.player_pos.x - half,
@@ -141,7 +184,6 @@ impl Game for TileMapDemo {
     fn render(&mut self, engine: &Engine, frame: &mut Frame) {
         frame.camera.position = self.player_pos;
 
-        self.floor_map.draw(frame);
         self.map.draw(frame);
 
         let half = PLAYER_SIZE * 0.5;
@@ -160,7 +202,7 @@ impl Game for TileMapDemo {
         hud.text(
             -hw + 12.0,
             hh - 8.0,
-            "TileMap Demo - WASD/Arrows to move",
+            "TileMap Demo - WASD/Arrows to move, C toggles the canopy",
             16.0,
             Color::WHITE,
         );
@@ -168,7 +210,8 @@ impl Game for TileMapDemo {
         let pos_text = format!("pos: ({:.0}, {:.0})", self.player_pos.x, self.player_pos.y);
         let col = (self.player_pos.x / TILE_SIZE) as usize;
         let row = (self.player_pos.y / TILE_SIZE) as usize;
-        let cell_text = format!("cell: ({}, {})", col, row);
+        let chunks: usize = self.map.layers().iter().map(|l| l.map.chunk_count()).sum();
+        let cell_text = format!("cell: ({}, {})  chunks: {}", col, row, chunks);
         hud.text(-hw + 12.0, -hh + 40.0, &pos_text, 13.0, Color::YELLOW);
         hud.text(
             -hw + 12.0,
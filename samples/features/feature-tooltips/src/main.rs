@@ -54,7 +54,8 @@ impl TooltipDemo {
         };
 
         self.ui.begin(engine, -230.0, 56.0, 460.0);
-        self.ui.label_centered("Tooltip Demo", 28.0, Color::WHITE);
+        self.ui
+            .label_centered("[wave]Tooltip[/wave] Demo", 28.0, Color::WHITE);
         self.ui.tooltip_with(
             "Text-only widgets can explain themselves without becoming buttons or sliders. This header uses the live delay and animation controls below.",
             TooltipOptions::new().with_max_width(260.0),
@@ -71,7 +72,7 @@ impl TooltipDemo {
                 .with_offset(Vec2::new(18.0, 8.0)),
         );
         self.ui.label_centered(
-            "Project Briefing",
+            "[img=badge] Project [color=#fbc75c]Briefing[/color]",
             18.0,
             Color::from_rgba8(220, 220, 240, 255),
         );
@@ -82,7 +83,7 @@ impl TooltipDemo {
             TooltipOptions::new()
                 .with_max_width(250.0)
                 .with_advanced_text(
-                    "Advanced stats: momentum is currently +6 from recent progress, -2 from interruptions, and +4 from review confidence. Hold Shift to reveal this block.",
+                    "Advanced stats: momentum is currently [color=#7be07b]+6[/color] from recent progress, [color=#f06a5a]-2[/color] from interruptions, and [color=#7be07b]+4[/color] from review confidence. Hold [b]Shift[/b] to reveal this block.",
                 ),
         );
         self.ui
@@ -122,9 +123,14 @@ impl TooltipDemo {
 
 impl Game for TooltipDemo {
     fn new(engine: &mut Engine) -> Self {
+        let badge = Self::create_badge(engine);
+        // `[img=badge]` in any label or tooltip draws it inline.
+        engine
+            .markup_assets_mut()
+            .insert_image("badge", MarkupImage::new(badge));
         Self {
             ui: Ui::default(),
-            badge: Self::create_badge(engine),
+            badge,
             morale: 0.78,
             tire_wear: 0.34,
             aggressive_calls: false,
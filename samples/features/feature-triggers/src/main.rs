@@ -3,6 +3,7 @@ use rengine::*;
 const PLAYER_SPEED: f32 = 200.0;
 const PLAYER_SIZE: f32 = 20.0;
 const PLAYER_BODY_ID: BodyId = 0;
+const SENTRY_RADIUS: f32 = 50.0;
 
 struct TriggerDemo {
     white: TextureId,
@@ -12,9 +13,17 @@ struct TriggerDemo {
     zone_pickup: TriggerZoneId,
     zone_damage: TriggerZoneId,
     zone_layered: TriggerZoneId,
+    zone_plate: TriggerZoneId,
+    zone_sentry: TriggerZoneId,
+    scene: SceneWorld2D,
+    scene_triggers: SceneTriggers2D,
+    sentry: NodeHandle2D,
+    time: f32,
     score: u32,
+    plate_presses: u32,
     checkpoint_flash: f32,
     damage_flash: f32,
+    spotted_flash: f32,
     pickup_collected: bool,
     layered_overlap: bool,
 }
@@ -23,12 +32,13 @@ impl Game for TriggerDemo {
     fn new(engine: &mut Engine) -> Self {
         let white = engine.create_color_texture(1, 1, Color::WHITE);
 
-        let mut triggers = TriggerSystem::new();
+        let mut triggers = TriggerSystem::with_broadphase(64.0);
 
         let zone_checkpoint =
             triggers.add_zone(TriggerZone::new(Rect::new(-300.0, -50.0, 80.0, 100.0)));
 
-        let zone_pickup = triggers.add_zone(TriggerZone::new(Rect::new(100.0, 100.0, 40.0, 40.0)));
+        let zone_pickup = triggers
+            .add_zone(TriggerZone::new(Rect::new(100.0, 100.0, 40.0, 40.0)).with_once(true));
 
         let zone_damage =
             triggers.add_zone(TriggerZone::new(Rect::new(200.0, -150.0, 120.0, 60.0)));
@@ -39,6 +49,22 @@ impl Game for TriggerDemo {
             ),
         );
 
+        // Stand on the plate for 1.5s to press it; it then needs 3s to reset.
+        let zone_plate = triggers.add_zone(
+            TriggerZone::new(Rect::new(-300.0, 120.0, 60.0, 60.0))
+                .with_dwell(1.5)
+                .with_cooldown(3.0),
+        );
+
+        // A sentry's circle of view rides on a scene node patrolling the top.
+        let mut scene = SceneWorld2D::new();
+        let sentry = scene.spawn(SceneNode2D::new("sentry").with_position(Vec2::new(0.0, 180.0)));
+        let zone_sentry = triggers.add_zone(
+            TriggerZone::shaped(Shape2D::circle(Vec2::ZERO, SENTRY_RADIUS)).with_cooldown(1.0),
+        );
+        let mut scene_triggers = SceneTriggers2D::new();
+        scene_triggers.attach(&triggers, zone_sentry, sentry);
+
         Self {
             white,
             player_pos: Vec2::ZERO,
@@ -47,9 +73,17 @@ impl Game for TriggerDemo {
             zone_pickup,
             zone_damage,
             zone_layered,
+            zone_plate,
+            zone_sentry,
+            scene,
+            scene_triggers,
+            sentry,
+            time: 0.0,
             score: 0,
+            plate_presses: 0,
             checkpoint_flash: 0.0,
             damage_flash: 0.0,
+            spotted_flash: 0.0,
             pickup_collected: false,
             layered_overlap: false,
         }
@@ -88,9 +122,32 @@ impl Game for TriggerDemo {
             CollisionLayer::PLAYER | CollisionLayer::TRIGGER,
         );
 
+        self.time += dt;
+        if let Some(sentry) = self.scene.get_mut(self.sentry) {
+            sentry.set_position(Vec2::new((self.time * 0.8).sin() * 250.0, 180.0));
+        }
+        self.scene_triggers
+            .follow_nodes(&mut self.triggers, &self.scene);
+        self.triggers.advance(dt);
         self.triggers
             .tick(&[(PLAYER_BODY_ID, player_rect, player_layer)]);
 
+        let fired: Vec<_> = self.triggers.fired().collect();
+        for (zone_id, _body_id) in fired {
+            if zone_id == self.zone_pickup {
+                self.pickup_collected = true;
+                self.score += 50;
+            }
+            if zone_id == self.zone_plate {
+                self.plate_presses += 1;
+                self.score += 25;
+            }
+            if zone_id == self.zone_sentry {
+                self.spotted_flash = 1.0;
+                self.score = self.score.saturating_sub(20);
+            }
+        }
+
         let events: Vec<_> = self.triggers.events().collect();
         for (zone_id, _body_id, event) in events {
             if zone_id == self.zone_checkpoint && event == OverlapEvent::Enter {
@@ -98,13 +155,6 @@ impl Game for TriggerDemo {
                 self.score += 10;
             }
 
-            if zone_id == self.zone_pickup && event == OverlapEvent::Enter && !self.pickup_collected
-            {
-                self.pickup_collected = true;
-                self.score += 50;
-                self.triggers.zone_mut(self.zone_pickup).enabled = false;
-            }
-
             if zone_id == self.zone_damage && event == OverlapEvent::Stay {
                 self.damage_flash = 0.5;
             }
@@ -116,6 +166,7 @@ impl Game for TriggerDemo {
 
         self.checkpoint_flash = (self.checkpoint_flash - dt * 2.0).max(0.0);
         self.damage_flash = (self.damage_flash - dt * 2.0).max(0.0);
+        self.spotted_flash = (self.spotted_flash - dt * 2.0).max(0.0);
     }
 
     fn render(&mut self, engine: &Engine, frame: &mut Frame) {
@@ -127,6 +178,7 @@ impl Game for TriggerDemo {
             (self.zone_pickup, Color::new(0.8, 0.7, 0.1, 0.4)),
             (self.zone_damage, Color::new(0.7, 0.1, 0.1, 0.4)),
             (self.zone_layered, Color::new(0.5, 0.2, 0.7, 0.4)),
+            (self.zone_plate, Color::new(0.2, 0.4, 0.8, 0.4)),
         ];
 
         for &(zone_id, base_color) in zones {
@@ -154,7 +206,7 @@ impl Game for TriggerDemo {
         }
 
         let hs = PLAYER_SIZE / 2.0;
-        let player_color = if self.damage_flash > 0.0 {
+        let player_color = if self.damage_flash > 0.0 || self.spotted_flash > 0.0 {
             Color::new(1.0, 0.3, 0.3, 1.0)
         } else if self.checkpoint_flash > 0.0 {
             Color::new(0.3, 1.0, 0.5, 1.0)
@@ -175,7 +227,19 @@ impl Game for TriggerDemo {
         let (sw, sh) = engine.window_size();
         let hw = sw as f32 / 2.0;
         let hh = sh as f32 / 2.0;
+        let sentry = self.triggers.zone(self.zone_sentry).rect.center();
+        let plate_progress = self
+            .triggers
+            .dwell_time(self.zone_plate, PLAYER_BODY_ID)
+            .map_or(0.0, |dwell| (dwell / 1.5).min(1.0));
         let canvas = frame.canvas(0);
+        canvas.circle_filled(
+            sentry.x,
+            sentry.y,
+            SENTRY_RADIUS,
+            32,
+            Color::new(0.9, 0.5, 0.1, 0.3),
+        );
         canvas.rect(
             -hw,
             hh - 42.0,
@@ -187,7 +251,7 @@ impl Game for TriggerDemo {
             -hw + 8.0,
             hh - 12.0,
             &format!(
-                "Score: {}   {}   {}",
+                "Score: {}   {}   {}   Plate: {} presses ({:.0}%)",
                 self.score,
                 if self.pickup_collected {
                     "Pickup: collected"
@@ -198,7 +262,9 @@ impl Game for TriggerDemo {
                     "Layered: inside"
                 } else {
                     "Layered: outside"
-                }
+                },
+                self.plate_presses,
+                plate_progress * 100.0
             ),
             12.0,
             Color::WHITE,
@@ -207,7 +273,7 @@ impl Game for TriggerDemo {
         canvas.text_block(
             -hw + 8.0,
             hh - 28.0,
-            "WASD / Arrows move. Green checkpoint, yellow pickup, red damage, purple layered zone (PLAYER only).",
+            "WASD / Arrows move. Green checkpoint, yellow one-shot pickup, red damage, purple layered zone (PLAYER only), blue plate (stand 1.5s), orange patrolling sentry.",
             10.0,
             Color::new(0.7, 0.7, 0.7, 1.0),
             560.0,
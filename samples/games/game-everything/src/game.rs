@@ -1,7 +1,64 @@
+use std::sync::Arc;
+
 use crate::pause::PauseOverlay;
 use crate::state::*;
 use rengine::*;
 
+const CRITTER_SPEED: f32 = 70.0;
+const CRITTER_LIFT: usize = 3;
+const CRITTER_LIFT_SPEED: f32 = 20.0;
+/// A spark falling ~50px per fixed step would skip clean through the 32px
+/// arena floor without CCD.
+const SPARK_SPEED: f32 = 3000.0;
+/// "Hello" in English, Hebrew and Arabic, shaped and reordered on one line.
+const GREETING: &str =
+    "Hello \u{b7} \u{5e9}\u{5dc}\u{5d5}\u{5dd} \u{b7} \u{645}\u{631}\u{62d}\u{628}\u{627}";
+/// Arena markers as Tiled saves them: y runs down from the top of the 640px
+/// tall arena.
+const ARENA_MARKERS: &str = r#"{
+  "orientation": "orthogonal", "width": 50, "height": 20,
+  "tilewidth": 32, "tileheight": 32, "infinite": false,
+  "tilesets": [],
+  "layers": [{
+    "type": "objectgroup", "name": "markers", "visible": true,
+    "objects": [
+      {"id": 1, "name": "player", "type": "spawn", "point": true,
+       "x": 100, "y": 540, "width": 0, "height": 0, "rotation": 0, "visible": true}
+    ]
+  }]
+}"#;
+
+/// Bonus coins placed in LDtk. The level sits at `worldY` -640 so its
+/// y-down pixels land on the 640px tall arena.
+const ARENA_BONUS: &str = r#"{
+  "worldLayout": "Free", "externalLevels": false,
+  "defs": {"tilesets": [], "layers": []},
+  "levels": [{
+    "identifier": "Arena", "iid": "arena", "uid": 0, "worldX": 0, "worldY": -640,
+    "worldDepth": 0, "pxWid": 1600, "pxHei": 640, "__neighbours": [], "fieldInstances": [],
+    "layerInstances": [{
+      "__identifier": "Bonus", "__type": "Entities", "__cWid": 50, "__cHei": 20,
+      "__gridSize": 32, "__pxTotalOffsetX": 0, "__pxTotalOffsetY": 0,
+      "__tilesetDefUid": null, "layerDefUid": 1, "visible": true, "__opacity": 1,
+      "intGridCsv": [], "autoLayerTiles": [], "gridTiles": [],
+      "entityInstances": [
+        {"__identifier": "Coin", "iid": "bonus-1", "px": [660, 376], "__pivot": [0.5, 0.5],
+         "width": 16, "height": 16, "__tags": [], "fieldInstances": []},
+        {"__identifier": "Coin", "iid": "bonus-2", "px": [850, 376], "__pivot": [0.5, 0.5],
+         "width": 16, "height": 16, "__tags": [], "fieldInstances": []}
+      ]
+    }]
+  }]
+}"#;
+
+/// A fresh spark high above the flat stretch of the critter's arena.
+fn spark() -> KinematicBody2D {
+    KinematicBody2D::new(Rect::new(1260.0, 400.0, 4.0, 4.0))
+        .with_gravity(Vec2::ZERO)
+        .with_velocity(Vec2::new(0.0, -SPARK_SPEED))
+        .with_ccd(CcdMode2D::Swept)
+}
+
 pub struct GameScene {
     config: Option<GameConfig>,
 
@@ -14,6 +71,8 @@ pub struct GameScene {
     coin_anim: Animation,
 
     tilemap: Option<TileMap>,
+    /// `mono.ttf` in [`FontMode::Sdf`], for the title's outline and glow.
+    title_font: Option<FontId>,
 
     player_pos: Vec2,
     player_vel: Vec2,
@@ -25,6 +84,13 @@ pub struct GameScene {
     coins: Vec<Vec2>,
     score: u32,
 
+    critter: KinematicBody2D,
+    critter_solids: Vec<Solid2D>,
+    crates: PhysicsWorld2D,
+    lantern: RigidBodyId,
+    spark: KinematicBody2D,
+    spark_mark: Option<Vec2>,
+
     triggers: TriggerSystem,
     zone_checkpoint: TriggerZoneId,
     zone_damage: TriggerZoneId,
@@ -55,7 +121,7 @@ pub struct GameScene {
 
 impl Default for GameScene {
     fn default() -> Self {
-        let mut triggers = TriggerSystem::new();
+        let mut triggers = TriggerSystem::with_broadphase(64.0);
 
         let zone_checkpoint =
             triggers.add_zone(TriggerZone::new(Rect::new(300.0, 160.0, 64.0, 96.0)));
@@ -67,6 +133,32 @@ impl Default for GameScene {
             )),
         );
 
+        let mut crates = PhysicsWorld2D::default();
+        for i in 0..3 {
+            let center = Vec2::new(1522.0 + i as f32 * 3.0, 76.0 + i as f32 * 26.0);
+            crates.insert(RigidBody2D::dynamic(
+                Shape2D::oriented_box(center, Vec2::splat(12.0), 0.0),
+                1.0,
+            ));
+        }
+        // A lantern hinged above the critter's route, released at an angle so
+        // it swings.
+        let lantern = crates.insert(
+            RigidBody2D::dynamic(
+                Shape2D::oriented_box(Vec2::new(1270.0, 150.0), Vec2::new(5.0, 8.0), 0.0),
+                1.0,
+            )
+            .with_damping(0.0, 0.2),
+        );
+        let pivot = crates
+            .body_anchor(lantern, Vec2::new(1270.0, 158.0))
+            .unwrap();
+        crates.insert_joint(Joint2D::rope(
+            JointAnchor2D::World(Vec2::new(1240.0, 188.0)),
+            pivot,
+            30.0_f32.hypot(30.0),
+        ));
+
         Self {
             config: None,
             player_tex: None,
@@ -76,6 +168,7 @@ impl Default for GameScene {
             coin_sheet: None,
             coin_anim: Animation::new(vec![(0, 0), (1, 0), (2, 0), (3, 0)], 8.0),
             tilemap: None,
+            title_font: None,
             player_pos: Vec2::new(100.0, 100.0),
             player_vel: Vec2::ZERO,
             player_on_ground: false,
@@ -87,6 +180,22 @@ impl Default for GameScene {
             ),
             coins: Vec::new(),
             score: 0,
+            critter: KinematicBody2D::new(Rect::new(1220.0, 64.0, 16.0, 16.0))
+                .with_velocity(Vec2::new(CRITTER_SPEED, 0.0))
+                .with_ground_snap(4.0),
+            // The arena floor, a slope bump the critter patrols over and a
+            // drop-through lift at the end of its route.
+            critter_solids: vec![
+                Solid2D::solid(Rect::new(32.0, 32.0, 1536.0, 32.0)),
+                Solid2D::slope(Rect::new(1300.0, 64.0, 64.0, 32.0), 0.0, 32.0),
+                Solid2D::slope(Rect::new(1364.0, 64.0, 64.0, 32.0), 32.0, 0.0),
+                Solid2D::one_way(Rect::new(1436.0, 60.0, 60.0, 4.0))
+                    .with_velocity(Vec2::new(0.0, CRITTER_LIFT_SPEED)),
+            ],
+            crates,
+            lantern,
+            spark: spark(),
+            spark_mark: None,
             triggers,
             zone_checkpoint,
             zone_damage,
@@ -176,7 +285,9 @@ impl Scene for GameScene {
         pc.fill_rect(6, 2, 4, 4, Color::new(1.0, 0.85, 0.7, 1.0));
         pc.set(7, 3, Color::BLACK);
         pc.set(9, 3, Color::BLACK);
-        self.player_tex = Some(engine.create_texture(16, 16, &pc.into_bytes()));
+        // The player and the coin sheet are drawn every frame; packing both
+        // into one atlas page lets them share a batch.
+        self.player_tex = Some(engine.create_packed_texture(16, 16, &pc.into_bytes()));
         println!("[FEATURE OK] PixelCanvas — player texture (fill, fill_rect, set)");
 
         let mut cc = pixelart::PixelCanvas::new(64, 16);
@@ -196,21 +307,38 @@ impl Scene for GameScene {
             );
         }
         let coin_pixels = cc.into_bytes();
-        let coin_tex_id = engine.create_texture(64, 16, &coin_pixels);
+        let coin_tex_id = engine.create_packed_texture(64, 16, &coin_pixels);
         self.coin_tex = Some(coin_tex_id);
         self.coin_sheet = Some(SpriteSheet::new(coin_tex_id, 64, 16, 16, 16));
         println!("[FEATURE OK] SpriteSheet — 4-frame coin sheet (lighten)");
+        let pages = self
+            .player_tex
+            .and_then(|player| engine.atlas_region(player))
+            .zip(engine.atlas_region(coin_tex_id))
+            .map(|(player, coin)| (player.page, coin.page));
+        println!(
+            "[FEATURE OK] Engine::create_packed_texture — player and coin sheet share an atlas page: {}, {} page(s)",
+            pages.is_some_and(|(player, coin)| player == coin),
+            engine.atlas_page_count()
+        );
 
-        let mut gc = pixelart::PixelCanvas::new(16, 16);
+        // Two cells: grass-topped ground, then the plain dirt buried under it.
+        let mut gc = pixelart::PixelCanvas::new(32, 16);
         gc.fill(Color::new(0.4, 0.25, 0.1, 1.0));
         gc.fill_rect(0, 0, 16, 3, Color::new(0.2, 0.7, 0.2, 1.0));
-        gc.set(3, 5, pixelart::darken(Color::new(0.4, 0.25, 0.1, 1.0), 0.7));
-        gc.set(
-            10,
-            8,
-            pixelart::darken(Color::new(0.4, 0.25, 0.1, 1.0), 0.7),
-        );
-        self.ground_tex = Some(engine.create_texture(16, 16, &gc.into_bytes()));
+        for ox in [0, 16] {
+            gc.set(
+                ox + 3,
+                5,
+                pixelart::darken(Color::new(0.4, 0.25, 0.1, 1.0), 0.7),
+            );
+            gc.set(
+                ox + 10,
+                8,
+                pixelart::darken(Color::new(0.4, 0.25, 0.1, 1.0), 0.7),
+            );
+        }
+        self.ground_tex = Some(engine.create_texture(32, 16, &gc.into_bytes()));
         println!("[FEATURE OK] PixelCanvas::darken — ground tile");
 
         let mut bg = pixelart::PixelCanvas::new(1, 64);
@@ -231,33 +359,46 @@ impl Scene for GameScene {
         println!("[FEATURE OK] Color::rgb — gradient background");
 
         let ground = self.ground_tex.unwrap();
+        let ground_sheet = SpriteSheet::new(ground, 32, 16, 16, 16);
         let mut tilemap = TileMap::new(50, 20, 32.0);
-        let ground_tile = tilemap.add_tile(TileDef::solid(ground));
+        // Ground with more ground above it (Edges4 north bit) shows as dirt.
+        let terrain = (0..16).filter(|mask| mask & 1 != 0).fold(
+            TerrainRule::new("ground", AutotileMode::Edges4).with_fallback([0, 0]),
+            |rule, mask| rule.with_variant(mask, [1, 0]),
+        );
+        let mut tiler = Autotiler::new(
+            Arc::new(AutotileRules::new().with_terrain(terrain)),
+            &ground_sheet,
+            50,
+            20,
+            |def| tilemap.add_tile(def),
+        )
+        .with_edges_connect(false);
+        let mut ground_at = |col: usize, row: usize| tiler.paint(col, row, Some("ground"));
         for col in 0..50 {
-            tilemap.set(col, 0, Some(ground_tile));
-            tilemap.set(col, 1, Some(ground_tile));
+            ground_at(col, 0);
+            ground_at(col, 1);
         }
         for row in 2..20 {
-            tilemap.set(0, row, Some(ground_tile));
-            tilemap.set(49, row, Some(ground_tile));
-        }
-        for col in 5..10 {
-            tilemap.set(col, 4, Some(ground_tile));
+            ground_at(0, row);
+            ground_at(49, row);
         }
-        for col in 14..20 {
-            tilemap.set(col, 4, Some(ground_tile));
+        for col in (5..10).chain(14..20).chain(28..34).chain(35..43) {
+            ground_at(col, 4);
         }
+        tiler.apply(&mut tilemap);
+        let ledge_tile = tilemap.add_tile(
+            TileDef::solid(ground)
+                .with_uv(ground_sheet.uv_rect(0, 0))
+                .with_shape(TileShape::OneWay),
+        );
         for col in 20..27 {
-            tilemap.set(col, 7, Some(ground_tile));
-        }
-        for col in 28..34 {
-            tilemap.set(col, 4, Some(ground_tile));
-        }
-        for col in 35..43 {
-            tilemap.set(col, 4, Some(ground_tile));
+            tilemap.set(col, 7, Some(ledge_tile));
         }
         self.tilemap = Some(tilemap);
         println!("[FEATURE OK] TileMap — 50x20 platformer arena with platforms");
+        println!("[FEATURE OK] TileShape::OneWay — high ledge can be jumped through from below");
+        println!("[FEATURE OK] Autotiler::apply — buried ground cells tiled as dirt");
 
         self.coins = vec![
             Vec2::new(70.0, 72.0),
@@ -269,8 +410,137 @@ impl Scene for GameScene {
             Vec2::new(1250.0, 168.0),
             Vec2::new(1420.0, 72.0),
         ];
+        let bonus = LdtkProject::parse(std::path::Path::new("arena.ldtk"), ARENA_BONUS)
+            .expect("arena bonus coins");
+        if let Some(level) = bonus.level("Arena") {
+            let scene = bonus.build_level(level, &[]).scene;
+            for coin in scene.by_prefab("Coin") {
+                self.coins
+                    .extend(scene.node_bounds(coin).map(|r| r.center()));
+            }
+        }
+        println!("[FEATURE OK] LdtkProject::build_level — bonus coins from an LDtk entity layer");
+        if let (Some(tilemap), Some(&last_coin)) = (&self.tilemap, self.coins.last()) {
+            let nav = NavGrid::from_tilemap(tilemap);
+            let route = nav.find_points(Vec2::new(100.0, 100.0), last_coin, &NavAgent::new(12.0));
+            println!(
+                "[FEATURE OK] NavGrid::find_points — {} waypoints flying to the last coin",
+                route.map_or(0, |points| points.len())
+            );
+            if let Some(goal) = nav.cell_at(last_coin) {
+                let field = FlowField::new(&nav, &NavAgent::new(12.0), &[goal]);
+                let reachable = (0..nav.height())
+                    .flat_map(|row| (0..nav.width()).map(move |col| (col, row)))
+                    .filter(|&(col, row)| field.distance(col, row).is_some())
+                    .count();
+                println!("[FEATURE OK] FlowField::new — {reachable} cells flow to the last coin");
+            }
+            let flyer = SteeringAgent::new(Vec2::new(100.0, 100.0), 120.0).with_radius(12.0);
+            let velocity = Steering::new(&flyer, 1.0 / 60.0)
+                .arrive(last_coin, 48.0, 1.0)
+                .avoid_tiles(tilemap, 0.5, 2.0)
+                .velocity();
+            println!(
+                "[FEATURE OK] Steering::arrive — setting off at ({:.1}, {:.1}) for the last coin",
+                velocity.x, velocity.y
+            );
+            if let Some(start) = tilemap.cell_at(Vec2::new(100.0, 100.0)) {
+                let seen = FieldOfView::new(start).with_radius(8.0).compute(tilemap);
+                println!(
+                    "[FEATURE OK] FieldOfView::compute — {} cells in sight of the start",
+                    seen.len()
+                );
+            }
+            let hexes = HexLayout::new(HexOrientation::Pointy, tilemap.tile_size / 2.0);
+            let coin_hex = hexes.world_to_hex(last_coin - tilemap.origin);
+            let start_hex = hexes.world_to_hex(Vec2::new(100.0, 100.0) - tilemap.origin);
+            println!(
+                "[FEATURE OK] HexLayout::world_to_hex — the last coin is {} hexes from the start",
+                coin_hex.distance(start_hex)
+            );
+            let mut iso = IsoRenderer::new(64.0, 32.0);
+            iso.add_tilemap(tilemap, Rect::new(-400.0, -300.0, 800.0, 600.0));
+            let wall = IsoBox::cell(2, 2, 0.0, 1.0);
+            let hero = IsoBox::standing(Vec2::new(2.0, 2.8), Vec2::splat(0.4), 0.0, 1.5);
+            println!(
+                "[FEATURE OK] IsoRenderer — {} tiles queued, a hero beside a wall sorts {:?}",
+                iso.len(),
+                hero.draw_order(&wall)
+            );
+            let mut plates = TriggerSystem::new();
+            let plate = plates.add_zone(
+                TriggerZone::shaped(Shape2D::circle(last_coin, 24.0))
+                    .with_dwell(1.0)
+                    .with_once(true),
+            );
+            let feet = Rect::new(last_coin.x - 4.0, last_coin.y - 4.0, 8.0, 8.0);
+            let standing = [(1, feet, CollisionLayer::default())];
+            plates.tick(&standing);
+            plates.advance(1.0);
+            plates.tick(&standing);
+            println!(
+                "[FEATURE OK] TriggerZone::with_dwell — the plate on the last coin fired {} time(s) after {:?}s",
+                plates.fired().count(),
+                plates.dwell_time(plate, 1)
+            );
+            if let Some(tile) = tilemap.solid_rects().first() {
+                let floor = FixedRect::from_rect(tile);
+                let size = Fixed::from_int(8);
+                let crate_box = FixedRect::new(floor.x, floor.y + floor.height + size, size, size);
+                let fall = FixedVec2::new(Fixed::ZERO, -Fixed::from_int(12));
+                let (landed, contacts) = move_aabb(crate_box, fall, &[floor]);
+                println!(
+                    "[FEATURE OK] Fixed + move_aabb — a fixed-point crate lands on a tile: {}, flush: {}",
+                    contacts.bottom,
+                    landed.y == floor.y + floor.height
+                );
+            }
+        }
+        let (name_w, _) = engine
+            .font_atlas()
+            .measure_text("Zo\u{eb} \u{2014} \u{c5}sa", 16.0);
+        println!(
+            "[FEATURE OK] FontAtlas glyph cache — an accented name measures {:.0}px across {} page(s)",
+            name_w,
+            engine.font_atlas().page_count()
+        );
+        match engine.load_font("mono.ttf") {
+            Ok(font) => {
+                engine.set_font_mode(font, FontMode::Sdf);
+                self.title_font = Some(font);
+                println!(
+                    "[FEATURE OK] FontMode::Sdf — title font loaded for outline, shadow and glow"
+                );
+            }
+            Err(e) => eprintln!("Warning: could not load mono.ttf: {e}"),
+        }
+        match engine.load_font("dejavu-sans-rtl.ttf") {
+            Ok(rtl) => {
+                engine.set_font_fallbacks(FontId::DEFAULT, &[rtl]);
+                let (width, _) = engine.font_atlas().measure_text(GREETING, 12.0);
+                println!(
+                    "[FEATURE OK] shaping + bidi — a Hebrew and Arabic greeting measures {:.0}px \
+                     through the fallback, an Arabic line aligns {:?}",
+                    width,
+                    TextAlign::Start.resolve("\u{645}\u{631}\u{62d}\u{628}\u{627}")
+                );
+            }
+            Err(e) => eprintln!("Warning: could not load dejavu-sans-rtl.ttf: {e}"),
+        }
+        let briefing = RichText::parse("[b]Collect[/b] the [color=#fbc75c]coins[/color]");
+        println!(
+            "[FEATURE OK] RichText::parse — {} styled spans read \"{}\"",
+            briefing.spans().len(),
+            briefing.plain_text()
+        );
 
-        self.player_pos = Vec2::new(100.0, 100.0);
+        let markers = TiledMap::parse(std::path::Path::new("arena.tmj"), ARENA_MARKERS)
+            .expect("arena markers")
+            .build(&[]);
+        self.player_pos = markers
+            .spawn("player")
+            .map_or(Vec2::new(100.0, 100.0), |spawn| spawn.position);
+        println!("[FEATURE OK] TiledMap::parse — player start from a Tiled object layer");
         self.player_vel = Vec2::ZERO;
         self.score = 0;
         self.play_time = 0.0;
@@ -285,7 +555,7 @@ impl Scene for GameScene {
         println!("[FEATURE OK] Globals::contains — checked PlayerStats existence");
         println!(
             "[FEATURE OK] TriggerSystem — checkpoint zone (300,160) 64x96, \
-             damage zone (750,64) 120x60"
+             damage zone (750,64) 120x60, 64px broadphase grid"
         );
         println!(
             "[FEATURE OK] CollisionLayer — player mask PLAYER|TRIGGER, \
@@ -419,11 +689,81 @@ impl Scene for GameScene {
                     demo.log_feature("TileMap::collide_rect");
                 }
             }
+
+            let probe_origin = Vec2::new(self.player_pos.x + 14.0, self.player_pos.y);
+            let probe = Ray2D::new(probe_origin, Vec2::NEG_Y)
+                .with_max_distance(256.0)
+                .cast_tilemap(tilemap);
+            if probe.is_some() {
+                if let Some(demo) = globals.get_mut::<DemoConfig>() {
+                    demo.log_feature("Ray2D::cast_tilemap ground probe");
+                }
+            }
         }
 
-        let player_rect = Rect::new(self.player_pos.x, self.player_pos.y, 28.0, 44.0);
+        let heading = if self.critter.bounds.x > 1480.0 {
+            -CRITTER_SPEED
+        } else if self.critter.bounds.x < 1200.0 {
+            CRITTER_SPEED
+        } else {
+            self.critter.velocity.x.signum() * CRITTER_SPEED
+        };
+        self.critter.velocity.x = heading;
+        let lift = &mut self.critter_solids[CRITTER_LIFT];
+        if lift.rect.y >= 100.0 {
+            lift.velocity.y = -CRITTER_LIFT_SPEED;
+        } else if lift.rect.y <= 60.0 {
+            lift.velocity.y = CRITTER_LIFT_SPEED;
+        }
+        move_platforms(
+            fixed_dt,
+            &mut self.critter_solids,
+            std::slice::from_mut(&mut self.critter),
+        );
+        self.critter.step_solids(fixed_dt, &self.critter_solids);
+        if self.critter.platform == Some(CRITTER_LIFT) {
+            if let Some(demo) = globals.get_mut::<DemoConfig>() {
+                demo.log_feature("move_platforms + KinematicBody2D::platform");
+            }
+        }
+        self.crates.step(fixed_dt, &self.critter_solids);
+        if self.crates.bodies().any(|(_, body)| body.is_sleeping()) {
+            if let Some(demo) = globals.get_mut::<DemoConfig>() {
+                demo.log_feature("PhysicsWorld2D rigid-body crate stack");
+            }
+        }
+        if self
+            .crates
+            .joints()
+            .any(|(_, joint)| joint.impulse() != Vec2::ZERO)
+        {
+            if let Some(demo) = globals.get_mut::<DemoConfig>() {
+                demo.log_feature("Joint2D rope lantern");
+            }
+        }
+        self.spark.step_solids(fixed_dt, &self.critter_solids);
+        if let Some(impact) = self.spark.impact {
+            self.spark_mark = Some(impact.point);
+            self.spark = spark();
+            if let Some(demo) = globals.get_mut::<DemoConfig>() {
+                demo.log_feature("KinematicBody2D CCD impact");
+            }
+        }
+        if self
+            .critter
+            .contacts
+            .ground_angle()
+            .is_some_and(|a| a > 0.1)
+        {
+            if let Some(demo) = globals.get_mut::<DemoConfig>() {
+                demo.log_feature("KinematicBody2D slopes + ground_snap");
+            }
+        }
+
+        let player_capsule =
+            Shape2D::vertical_capsule(self.player_pos + Vec2::new(14.0, 22.0), 8.0, 14.0);
         self.triggers
-            .tick(&[(PLAYER_BODY_ID, player_rect, self.player_layer)]);
+            .tick_shapes(&[(PLAYER_BODY_ID, player_capsule, self.player_layer)]);
 
         let events: Vec<_> = self.triggers.events().collect();
         for (zone_id, _body_id, event) in &events {
@@ -458,6 +798,7 @@ impl Scene for GameScene {
         if !events.is_empty() {
             if let Some(demo) = globals.get_mut::<DemoConfig>() {
                 demo.log_feature("TriggerSystem::tick + OverlapEvent");
+                demo.log_feature("Shape2D capsule trigger body");
             }
         }
 
@@ -739,6 +1080,99 @@ impl Scene for GameScene {
             .with_z_order(1),
         );
 
+        // Sprites are axis-aligned, so draw the ramps as thin columns that
+        // follow each slope's surface.
+        for solid in &self.critter_solids[1..CRITTER_LIFT] {
+            let r = solid.rect;
+            let mut x = r.left();
+            while x < r.right() {
+                let probe = Ray2D::new(Vec2::new(x + 2.0, r.top() + 1.0), Vec2::NEG_Y)
+                    .cast_solids(std::slice::from_ref(solid));
+                if let Some(hit) = probe {
+                    frame.draw_sprite(
+                        DrawParams::new(
+                            white,
+                            Vec2::new(x, r.bottom()),
+                            Vec2::new(4.0, hit.point.y - r.bottom()),
+                        )
+                        .with_color(Color::from_rgba8(110, 90, 60, 255))
+                        .with_z_order(2),
+                    );
+                }
+                x += 4.0;
+            }
+        }
+        let lift = self.critter_solids[CRITTER_LIFT].rect;
+        frame.draw_sprite(
+            DrawParams::new(
+                white,
+                Vec2::new(lift.x, lift.y),
+                Vec2::new(lift.width, lift.height),
+            )
+            .with_color(Color::from_rgba8(120, 170, 230, 255))
+            .with_z_order(2),
+        );
+        for (_, joint) in self.crates.joints() {
+            let top = self.crates.anchor_position(&joint.a).unwrap();
+            let end = self.crates.anchor_position(&joint.b).unwrap();
+            let span = end - top;
+            frame.draw_sprite(
+                DrawParams::new(white, (top + end) * 0.5, Vec2::new(span.length(), 1.0))
+                    .with_centered_origin()
+                    .with_rotation(span.to_angle())
+                    .with_color(Color::from_rgba8(200, 180, 140, 255))
+                    .with_z_order(3),
+            );
+        }
+        let lantern = self.crates.get(self.lantern).unwrap();
+        frame.draw_sprite(
+            DrawParams::new(white, lantern.position, Vec2::new(10.0, 16.0))
+                .with_centered_origin()
+                .with_rotation(lantern.rotation)
+                .with_color(Color::from_rgba8(255, 210, 110, 255))
+                .with_z_order(3),
+        );
+        for (id, body) in self.crates.bodies() {
+            if id == self.lantern {
+                continue;
+            }
+            frame.draw_sprite(
+                DrawParams::new(white, body.position, Vec2::splat(24.0))
+                    .with_centered_origin()
+                    .with_rotation(body.rotation)
+                    .with_color(Color::from_rgba8(170, 120, 70, 255))
+                    .with_z_order(3),
+            );
+        }
+        let spark = self.spark.bounds;
+        frame.draw_sprite(
+            DrawParams::new(
+                white,
+                Vec2::new(spark.x, spark.y),
+                Vec2::new(spark.width, spark.height),
+            )
+            .with_color(Color::from_rgba8(255, 220, 120, 255))
+            .with_z_order(9),
+        );
+        if let Some(mark) = self.spark_mark {
+            frame.draw_sprite(
+                DrawParams::new(white, mark, Vec2::new(8.0, 2.0))
+                    .with_centered_origin()
+                    .with_color(Color::from_rgba8(255, 150, 60, 255))
+                    .with_z_order(3),
+            );
+        }
+        let critter = self.critter.bounds;
+        frame.draw_sprite(
+            DrawParams::new(
+                white,
+                Vec2::new(critter.x, critter.y),
+                Vec2::new(critter.width, critter.height),
+            )
+            .with_color(Color::from_rgba8(200, 120, 220, 255))
+            .with_z_order(9),
+        );
+
         if let Some(player_tex) = self.player_tex {
             frame.draw_sprite(
                 DrawParams::new(player_tex, self.player_pos, Vec2::new(28.0, 44.0))
@@ -807,12 +1241,45 @@ impl Scene for GameScene {
             Color::new(1.0, 1.0, 1.0, 0.6),
         );
 
+        match self.title_font {
+            Some(font) => {
+                let atlas = engine.font(font);
+                let title = "Kitchen Sink Demo";
+                let (title_w, _) = atlas.measure_text(title, 20.0);
+                let mut effects = TextEffects::NONE
+                    .with_outline(1.5, Color::new(0.05, 0.08, 0.2, 1.0))
+                    .with_shadow(2.0, -2.0, 1.0, Color::new(0.0, 0.0, 0.0, 0.6));
+                // The title lights up while a checkpoint message shows.
+                if self.checkpoint_flash > 0.0 {
+                    let alpha = self.checkpoint_flash / 0.5;
+                    effects = effects.with_glow(6.0, Color::new(0.3, 1.0, 0.4, alpha));
+                }
+                hud.text_with_effects(
+                    -title_w / 2.0,
+                    -hh + 50.0,
+                    title,
+                    20.0,
+                    Color::new(0.7, 0.8, 1.0, 1.0),
+                    atlas,
+                    &effects,
+                );
+            }
+            None => hud.text_aligned(
+                0.0,
+                -hh + 50.0,
+                "Kitchen Sink Demo",
+                12.0,
+                Color::new(0.7, 0.8, 1.0, 0.8),
+                TextAlign::Center,
+            ),
+        }
+
         hud.text_aligned(
             0.0,
-            -hh + 50.0,
-            "Kitchen Sink Demo",
+            -hh + 30.0,
+            GREETING,
             12.0,
-            Color::new(0.7, 0.8, 1.0, 0.8),
+            Color::new(1.0, 1.0, 1.0, 0.6),
             TextAlign::Center,
         );
 
@@ -1,5 +1,6 @@
 use crate::state::{FightInput, FighterData, FighterState};
 use crate::{KICK_RANGE, PUNCH_RANGE};
+use rengine::Fixed;
 
 pub fn bot_input(me: &FighterData, opponent: &FighterData, frame: u32, player: u32) -> FightInput {
     let dx = opponent.x - me.x;
@@ -10,15 +11,15 @@ pub fn bot_input(me: &FighterData, opponent: &FighterData, frame: u32, player: u
         .wrapping_mul(2654435761)
         .wrapping_add(player.wrapping_mul(1013904223));
 
-    if dist > PUNCH_RANGE + 20.0 {
-        if dx > 0.0 {
+    if dist > PUNCH_RANGE + Fixed::from_int(20) {
+        if dx > Fixed::ZERO {
             flags |= FightInput::RIGHT;
         } else {
             flags |= FightInput::LEFT;
         }
     }
 
-    if dist < KICK_RANGE + 10.0 && me.can_act() {
+    if dist < KICK_RANGE + Fixed::from_int(10) && me.can_act() {
         if rng % 3 == 0 {
             flags |= FightInput::PUNCH;
         } else if rng % 3 == 1 {
@@ -30,7 +31,7 @@ pub fn bot_input(me: &FighterData, opponent: &FighterData, frame: u32, player: u
         flags |= FightInput::JUMP;
     }
 
-    if dist < KICK_RANGE + 30.0
+    if dist < KICK_RANGE + Fixed::from_int(30)
         && matches!(
             opponent.state,
             FighterState::Punching | FighterState::Kicking
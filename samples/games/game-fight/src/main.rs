@@ -7,42 +7,44 @@ pub mod state;
 use std::path::PathBuf;
 
 use rengine::{
-    AudioBus, AxisMapping, Binding, Engine, EngineConfig, Frame, Game, GamepadAxis, GamepadButton,
-    KeyCode, OnlineConfig, RollbackConfig, RollbackSession, SessionMode,
+    AudioBus, AxisMapping, Binding, Engine, EngineConfig, Fixed, Frame, Game, GamepadAxis,
+    GamepadButton, KeyCode, OnlineConfig, RollbackConfig, RollbackSession, SessionMode,
 };
 use state::{FightGame, FightSim, FighterTextures};
 
 pub const SCREEN_W: u32 = 800;
 pub const SCREEN_H: u32 = 480;
 
-pub const GROUND_Y: f32 = 96.0;
-pub const STAGE_LEFT: f32 = 40.0;
-pub const STAGE_RIGHT: f32 = 760.0;
+// The simulation runs in fixed point so every rollback peer computes
+// bit-identical states; only rendering converts back to f32.
+pub const GROUND_Y: Fixed = Fixed::from_int(96);
+pub const STAGE_LEFT: Fixed = Fixed::from_int(40);
+pub const STAGE_RIGHT: Fixed = Fixed::from_int(760);
 
-pub const FIGHTER_W: f32 = 96.0;
-pub const FIGHTER_H: f32 = 144.0;
+pub const FIGHTER_W: Fixed = Fixed::from_int(96);
+pub const FIGHTER_H: Fixed = Fixed::from_int(144);
 
-pub const FIGHTER_FOOT_OFFSET: f32 = 24.0;
+pub const FIGHTER_FOOT_OFFSET: Fixed = Fixed::from_int(24);
 
-pub const WALK_SPEED: f32 = 250.0;
-pub const JUMP_VEL: f32 = 600.0;
-pub const GRAVITY: f32 = -1500.0;
+pub const WALK_SPEED: Fixed = Fixed::from_int(250);
+pub const JUMP_VEL: Fixed = Fixed::from_int(600);
+pub const GRAVITY: Fixed = Fixed::from_int(-1500);
 
-pub const PUNCH_RANGE: f32 = 90.0;
-pub const KICK_RANGE: f32 = 115.0;
+pub const PUNCH_RANGE: Fixed = Fixed::from_int(90);
+pub const KICK_RANGE: Fixed = Fixed::from_int(115);
 pub const PUNCH_DAMAGE: i32 = 8;
 pub const KICK_DAMAGE: i32 = 12;
-pub const BLOCK_REDUCTION: f32 = 0.25;
+pub const BLOCK_REDUCTION: Fixed = Fixed::from_ratio(1, 4);
 
-pub const ATTACK_DURATION: f32 = 0.25;
-pub const HIT_STUN: f32 = 0.30;
-pub const KNOCKBACK_SPEED: f32 = 300.0;
+pub const ATTACK_DURATION: Fixed = Fixed::from_ratio(1, 4);
+pub const HIT_STUN: Fixed = Fixed::from_ratio(3, 10);
+pub const KNOCKBACK_SPEED: Fixed = Fixed::from_int(300);
 
 pub const MAX_HP: i32 = 100;
 
-pub const ROUND_WIN_PAUSE: f32 = 2.0;
+pub const ROUND_WIN_PAUSE: Fixed = Fixed::from_int(2);
 
-pub const FIXED_DT: f32 = 1.0 / 60.0;
+pub const FIXED_DT: Fixed = Fixed::from_ratio(1, 60);
 
 impl Game for FightGame {
     fn new(engine: &mut Engine) -> Self {
@@ -232,11 +234,11 @@ impl Game for FightGame {
                 let _ = engine.play_sound_on_bus(AudioBus::Effects, &self.hit_sfx, 1.0);
             }
 
-            if prev_round_pause <= 0.0 && self.sim.round_pause > 0.0 {
+            if prev_round_pause <= Fixed::ZERO && self.sim.round_pause > Fixed::ZERO {
                 engine.pause_music();
                 let _ = engine.play_sound_on_bus(AudioBus::Ui, &self.hit_sfx, 0.55);
-            } else if prev_round_pause > 0.0
-                && self.sim.round_pause <= 0.0
+            } else if prev_round_pause > Fixed::ZERO
+                && self.sim.round_pause <= Fixed::ZERO
                 && self.sim.winner().is_none()
             {
                 engine.resume_music();
@@ -262,7 +264,7 @@ impl Game for FightGame {
     }
 
     fn should_exit(&self) -> bool {
-        self.printed_result || (self.sim.winner().is_some() && self.sim.round_pause <= 0.0)
+        self.printed_result || (self.sim.winner().is_some() && self.sim.round_pause <= Fixed::ZERO)
     }
 }
 
@@ -1,10 +1,12 @@
-use rengine::{Canvas, Color, DrawParams, Engine, Frame, Vec2};
+use rengine::{Canvas, Color, DrawParams, Engine, Fixed, Frame, Vec2};
 
 use crate::state::{Facing, FightGame, FighterData, FighterState, FighterTextures};
 use crate::{FIGHTER_H, FIGHTER_W, GROUND_Y, MAX_HP, SCREEN_H, SCREEN_W};
 
 pub fn draw(game: &FightGame, _engine: &Engine, frame: &mut Frame) {
     frame.clear_color = Color::from_rgba8(10, 8, 20, 255);
+    let ground_y = GROUND_Y.to_f32();
+    let fighter_w = FIGHTER_W.to_f32();
 
     frame.camera.position = Vec2::new(SCREEN_W as f32 / 2.0, SCREEN_H as f32 / 2.0);
     frame.camera.zoom = 1.0;
@@ -63,7 +65,7 @@ pub fn draw(game: &FightGame, _engine: &Engine, frame: &mut Frame) {
     let wall_bands: &[(f32, f32, [u8; 4])] = &[
         (170.0, 30.0, [55, 42, 35, 255]),
         (140.0, 30.0, [65, 50, 40, 255]),
-        (GROUND_Y, 140.0 - GROUND_Y, [75, 58, 45, 255]),
+        (ground_y, 140.0 - ground_y, [75, 58, 45, 255]),
     ];
     for &(y, h, rgba) in wall_bands {
         frame.draw_colored(
@@ -79,7 +81,7 @@ pub fn draw(game: &FightGame, _engine: &Engine, frame: &mut Frame) {
         let px = 30.0 + i as f32 * 155.0;
         frame.draw_colored(
             wt,
-            Vec2::new(px, GROUND_Y),
+            Vec2::new(px, ground_y),
             Vec2::new(12.0, 100.0),
             pillar_color,
         );
@@ -93,7 +95,7 @@ pub fn draw(game: &FightGame, _engine: &Engine, frame: &mut Frame) {
 
     frame.draw_colored(
         wt,
-        Vec2::new(0.0, GROUND_Y - 1.0),
+        Vec2::new(0.0, ground_y - 1.0),
         Vec2::new(w, 3.0),
         Color::from_rgba8(120, 100, 70, 200),
     );
@@ -104,22 +106,25 @@ pub fn draw(game: &FightGame, _engine: &Engine, frame: &mut Frame) {
         let tx = i as f32 * tile_size;
         frame.draw(
             game.floor_tex,
-            Vec2::new(tx, GROUND_Y - tile_size),
+            Vec2::new(tx, ground_y - tile_size),
             Vec2::new(tile_size, tile_size),
         );
         frame.draw(
             game.floor_tex,
-            Vec2::new(tx, GROUND_Y - tile_size * 2.0),
+            Vec2::new(tx, ground_y - tile_size * 2.0),
             Vec2::new(tile_size, tile_size),
         );
     }
 
     for fighter in [&game.sim.p1, &game.sim.p2] {
-        let shadow_w = FIGHTER_W * 0.7;
+        let shadow_w = fighter_w * 0.7;
         let shadow_h = 6.0;
         frame.draw_colored(
             wt,
-            Vec2::new(fighter.x - shadow_w / 2.0, GROUND_Y - shadow_h / 2.0),
+            Vec2::new(
+                fighter.x.to_f32() - shadow_w / 2.0,
+                ground_y - shadow_h / 2.0,
+            ),
             Vec2::new(shadow_w, shadow_h),
             Color::from_rgba8(0, 0, 0, 90),
         );
@@ -129,11 +134,11 @@ pub fn draw(game: &FightGame, _engine: &Engine, frame: &mut Frame) {
     draw_fighter(game, &game.sim.p2, &game.p2_tex, frame);
 
     for spark in &game.sim.sparks {
-        let alpha = (spark.life * 4.0).min(1.0);
+        let alpha = (spark.life.to_f32() * 4.0).min(1.0);
         let color = Color::new(1.0, 1.0, 0.3, alpha);
         frame.draw_colored(
             game.white_tex,
-            Vec2::new(spark.x - 3.0, spark.y - 3.0),
+            Vec2::new(spark.x.to_f32() - 3.0, spark.y.to_f32() - 3.0),
             Vec2::new(6.0, 6.0),
             color,
         );
@@ -147,6 +152,8 @@ pub fn draw(game: &FightGame, _engine: &Engine, frame: &mut Frame) {
 }
 
 fn draw_fighter(game: &FightGame, fighter: &FighterData, tex: &FighterTextures, frame: &mut Frame) {
+    let ground_y = GROUND_Y.to_f32();
+    let (fighter_w, fighter_h) = (FIGHTER_W.to_f32(), FIGHTER_H.to_f32());
     let uv = match fighter.state {
         FighterState::Punching => tex.punch,
         FighterState::Kicking => tex.kick,
@@ -160,20 +167,23 @@ fn draw_fighter(game: &FightGame, fighter: &FighterData, tex: &FighterTextures,
     frame.draw_sprite(
         DrawParams::new(
             tex.texture,
-            Vec2::new(fighter.rect_x(), fighter.rect_y()),
-            Vec2::new(FIGHTER_W, FIGHTER_H),
+            Vec2::new(fighter.rect_x().to_f32(), fighter.rect_y().to_f32()),
+            Vec2::new(fighter_w, fighter_h),
         )
         .with_uv_rect(uv)
         .with_flip_x(flip),
     );
 
     if !fighter.is_on_ground() {
-        let shadow_w = FIGHTER_W * 0.6;
+        let shadow_w = fighter_w * 0.6;
         let shadow_h = 8.0;
-        let shadow_alpha = ((fighter.y - GROUND_Y) / 200.0).clamp(0.1, 0.5);
+        let shadow_alpha = ((fighter.y.to_f32() - ground_y) / 200.0).clamp(0.1, 0.5);
         frame.draw_colored(
             game.white_tex,
-            Vec2::new(fighter.x - shadow_w / 2.0, GROUND_Y - shadow_h / 2.0),
+            Vec2::new(
+                fighter.x.to_f32() - shadow_w / 2.0,
+                ground_y - shadow_h / 2.0,
+            ),
             Vec2::new(shadow_w, shadow_h),
             Color::new(0.0, 0.0, 0.0, shadow_alpha),
         );
@@ -271,7 +281,7 @@ fn draw_hud(game: &FightGame, hud: &mut Canvas) {
         Color::WHITE,
     );
 
-    if game.sim.round_pause > 0.0 {
+    if game.sim.round_pause > Fixed::ZERO {
         let winner = if game.sim.p1.hp <= 0 { 2u32 } else { 1 };
         let ko_x = -30.0;
         let ko_y = -30.0;
@@ -1,3 +1,5 @@
+use rengine::Fixed;
+
 use crate::state::{Facing, FightInput, FightSim, FightSnapshot, FighterState, Spark};
 use crate::{
     ATTACK_DURATION, BLOCK_REDUCTION, FIGHTER_H, FIGHTER_W, FIXED_DT, GRAVITY, GROUND_Y, HIT_STUN,
@@ -17,7 +19,7 @@ impl FightSim {
     }
 
     pub fn advance(&mut self, inputs: &[FightInput]) {
-        if self.winner().is_some() && self.round_pause <= 0.0 {
+        if self.winner().is_some() && self.round_pause <= Fixed::ZERO {
             return;
         }
 
@@ -26,14 +28,14 @@ impl FightSim {
         for spark in &mut self.sparks {
             spark.x += spark.vx * dt;
             spark.y += spark.vy * dt;
-            spark.vy -= 800.0 * dt;
+            spark.vy -= Fixed::from_int(800) * dt;
             spark.life -= dt;
         }
-        self.sparks.retain(|s| s.life > 0.0);
+        self.sparks.retain(|s| s.life > Fixed::ZERO);
 
-        if self.round_pause > 0.0 {
+        if self.round_pause > Fixed::ZERO {
             self.round_pause -= dt;
-            if self.round_pause <= 0.0 && self.winner().is_none() {
+            if self.round_pause <= Fixed::ZERO && self.winner().is_none() {
                 self.reset_round();
             }
             return;
@@ -99,16 +101,16 @@ impl FightSim {
         self.sparks = snapshot.sparks;
     }
 
-    fn update_fighter_input(&mut self, is_p1: bool, input: FightInput, dt: f32) {
+    fn update_fighter_input(&mut self, is_p1: bool, input: FightInput, dt: Fixed) {
         let fighter = if is_p1 { &mut self.p1 } else { &mut self.p2 };
 
-        if fighter.state_timer > 0.0 {
+        if fighter.state_timer > Fixed::ZERO {
             fighter.state_timer -= dt;
-            if fighter.state_timer <= 0.0 {
+            if fighter.state_timer <= Fixed::ZERO {
                 match fighter.state {
                     FighterState::Punching | FighterState::Kicking | FighterState::HitStun => {
                         fighter.state = FighterState::Idle;
-                        fighter.vel_x = 0.0;
+                        fighter.vel_x = Fixed::ZERO;
                     }
                     _ => {}
                 }
@@ -122,29 +124,29 @@ impl FightSim {
                 fighter.state = FighterState::Punching;
                 fighter.state_timer = ATTACK_DURATION;
                 fighter.attack_hit_connected = false;
-                fighter.vel_x = 0.0;
+                fighter.vel_x = Fixed::ZERO;
                 return;
             }
             if input.kick() && fighter.can_act() {
                 fighter.state = FighterState::Kicking;
                 fighter.state_timer = ATTACK_DURATION;
                 fighter.attack_hit_connected = false;
-                fighter.vel_x = 0.0;
+                fighter.vel_x = Fixed::ZERO;
                 return;
             }
             if input.jump() && fighter.can_act() {
                 fighter.vel_y = JUMP_VEL;
                 fighter.state = FighterState::Jumping;
-                fighter.y += 1.0;
+                fighter.y += Fixed::ONE;
                 return;
             }
             if input.crouch() {
                 fighter.state = FighterState::Blocking;
-                fighter.vel_x = 0.0;
+                fighter.vel_x = Fixed::ZERO;
                 return;
             }
 
-            fighter.vel_x = 0.0;
+            fighter.vel_x = Fixed::ZERO;
             if input.left() {
                 fighter.vel_x = -WALK_SPEED;
                 fighter.state = FighterState::Walking;
@@ -155,16 +157,17 @@ impl FightSim {
                 fighter.state = FighterState::Idle;
             }
         } else {
-            fighter.vel_x = 0.0;
+            fighter.vel_x = Fixed::ZERO;
+            let air_speed = WALK_SPEED * Fixed::from_ratio(3, 5);
             if input.left() {
-                fighter.vel_x = -WALK_SPEED * 0.6;
+                fighter.vel_x = -air_speed;
             } else if input.right() {
-                fighter.vel_x = WALK_SPEED * 0.6;
+                fighter.vel_x = air_speed;
             }
         }
     }
 
-    fn apply_physics(&mut self, is_p1: bool, dt: f32) {
+    fn apply_physics(&mut self, is_p1: bool, dt: Fixed) {
         let fighter = if is_p1 { &mut self.p1 } else { &mut self.p2 };
 
         fighter.vel_y += GRAVITY * dt;
@@ -173,15 +176,13 @@ impl FightSim {
 
         if fighter.y <= GROUND_Y {
             fighter.y = GROUND_Y;
-            fighter.vel_y = 0.0;
+            fighter.vel_y = Fixed::ZERO;
             if fighter.state == FighterState::Jumping {
                 fighter.state = FighterState::Idle;
             }
         }
 
-        fighter.x = fighter
-            .x
-            .clamp(STAGE_LEFT + FIGHTER_W / 2.0, STAGE_RIGHT - FIGHTER_W / 2.0);
+        fighter.x = clamp_to_stage(fighter.x);
     }
 
     fn check_attack_hit(&mut self, attacker_is_p1: bool) {
@@ -209,10 +210,10 @@ impl FightSim {
             let d = if attacker_is_p1 { &self.p2 } else { &self.p1 };
             (d.x, d.y, d.state)
         };
-        let def_left = def_x - FIGHTER_W / 2.0;
-        let def_right = def_x + FIGHTER_W / 2.0;
+        let def_left = def_x - FIGHTER_W / Fixed::from_int(2);
+        let def_right = def_x + FIGHTER_W / Fixed::from_int(2);
         let def_top = def_y + FIGHTER_H;
-        let atk_hit_y = atk_y + FIGHTER_H * 0.5;
+        let atk_hit_y = atk_y + FIGHTER_H * Fixed::HALF;
 
         let hit_x_in = match atk_facing {
             Facing::Right => attack_x >= def_left && atk_x < def_right,
@@ -221,15 +222,15 @@ impl FightSim {
 
         if hit_x_in && atk_hit_y <= def_top && atk_hit_y >= def_y {
             let actual_damage = if def_state == FighterState::Blocking {
-                (damage as f32 * BLOCK_REDUCTION) as i32
+                (Fixed::from_int(damage) * BLOCK_REDUCTION).to_int()
             } else {
                 damage
             };
 
             let kb_dir = if atk_facing == Facing::Right {
-                1.0
+                Fixed::ONE
             } else {
-                -1.0
+                -Fixed::ONE
             };
 
             {
@@ -243,9 +244,9 @@ impl FightSim {
                     defender.state = FighterState::HitStun;
                     defender.state_timer = HIT_STUN;
                     defender.vel_x = kb_dir * KNOCKBACK_SPEED;
-                    defender.vel_y = 150.0;
+                    defender.vel_y = Fixed::from_int(150);
                 } else {
-                    defender.vel_x = kb_dir * KNOCKBACK_SPEED * 0.3;
+                    defender.vel_x = kb_dir * KNOCKBACK_SPEED * Fixed::from_ratio(3, 10);
                 }
             }
 
@@ -258,67 +259,66 @@ impl FightSim {
                 attacker.attack_hit_connected = true;
             }
 
-            let spark_x = (atk_x + def_x) / 2.0;
+            let spark_x = (atk_x + def_x) / Fixed::from_int(2);
             let spark_y = atk_hit_y;
             for i in 0..8 {
-                let angle = (i as f32 / 8.0) * std::f32::consts::TAU;
-                let speed = 200.0 + (i as f32 * 30.0);
+                let angle = Fixed::from_ratio(i, 8) * Fixed::TAU;
+                let speed = Fixed::from_int(200 + i * 30);
                 self.sparks.push(Spark {
                     x: spark_x,
                     y: spark_y,
                     vx: angle.cos() * speed,
-                    vy: angle.sin() * speed - 100.0,
-                    life: 0.3,
+                    vy: angle.sin() * speed - Fixed::from_int(100),
+                    life: Fixed::from_ratio(3, 10),
                 });
             }
         }
     }
 
     fn push_apart(&mut self) {
-        let min_dist = FIGHTER_W * 0.6;
+        let min_dist = FIGHTER_W * Fixed::from_ratio(3, 5);
         let dx = self.p2.x - self.p1.x;
         let dist = dx.abs();
         if dist < min_dist {
-            let push = (min_dist - dist) / 2.0;
-            if dx > 0.0 {
+            let push = (min_dist - dist) / Fixed::from_int(2);
+            if dx > Fixed::ZERO {
                 self.p1.x -= push;
                 self.p2.x += push;
             } else {
                 self.p1.x += push;
                 self.p2.x -= push;
             }
-            self.p1.x = self
-                .p1
-                .x
-                .clamp(STAGE_LEFT + FIGHTER_W / 2.0, STAGE_RIGHT - FIGHTER_W / 2.0);
-            self.p2.x = self
-                .p2
-                .x
-                .clamp(STAGE_LEFT + FIGHTER_W / 2.0, STAGE_RIGHT - FIGHTER_W / 2.0);
+            self.p1.x = clamp_to_stage(self.p1.x);
+            self.p2.x = clamp_to_stage(self.p2.x);
         }
     }
 
     pub fn reset_round(&mut self) {
-        self.p1.x = 250.0;
+        self.p1.x = Fixed::from_int(250);
         self.p1.y = GROUND_Y;
-        self.p1.vel_x = 0.0;
-        self.p1.vel_y = 0.0;
+        self.p1.vel_x = Fixed::ZERO;
+        self.p1.vel_y = Fixed::ZERO;
         self.p1.hp = MAX_HP;
         self.p1.state = FighterState::Idle;
-        self.p1.state_timer = 0.0;
+        self.p1.state_timer = Fixed::ZERO;
 
-        self.p2.x = 550.0;
+        self.p2.x = Fixed::from_int(550);
         self.p2.y = GROUND_Y;
-        self.p2.vel_x = 0.0;
-        self.p2.vel_y = 0.0;
+        self.p2.vel_x = Fixed::ZERO;
+        self.p2.vel_y = Fixed::ZERO;
         self.p2.hp = MAX_HP;
         self.p2.state = FighterState::Idle;
-        self.p2.state_timer = 0.0;
+        self.p2.state_timer = Fixed::ZERO;
 
         self.sparks.clear();
     }
 }
 
+fn clamp_to_stage(x: Fixed) -> Fixed {
+    let half_w = FIGHTER_W / Fixed::from_int(2);
+    x.clamp(STAGE_LEFT + half_w, STAGE_RIGHT - half_w)
+}
+
 impl rengine::Rollbackable for FightSim {
     type Input = FightInput;
 
@@ -334,3 +334,41 @@ impl rengine::Rollbackable for FightSim {
         self.load(data);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::bot_input;
+
+    fn run_match(frames: u32) -> FightSim {
+        let mut sim = FightSim::new();
+        for f in 0..frames {
+            let inputs = [
+                bot_input(&sim.p1, &sim.p2, f, 0),
+                bot_input(&sim.p2, &sim.p1, f, 1),
+            ];
+            sim.advance(&inputs);
+        }
+        sim
+    }
+
+    #[test]
+    fn fixed_point_bouts_replay_bit_identically() {
+        let a = run_match(1800);
+        let b = run_match(1800);
+        assert_eq!(a.save(), b.save());
+        assert!(a.p1.hp < MAX_HP || a.p2.hp < MAX_HP || a.round_number > 1);
+
+        let mut resumed = run_match(900);
+        let snapshot = resumed.save();
+        resumed.load(&snapshot);
+        for f in 900..1800 {
+            let inputs = [
+                bot_input(&resumed.p1, &resumed.p2, f, 0),
+                bot_input(&resumed.p2, &resumed.p1, f, 1),
+            ];
+            resumed.advance(&inputs);
+        }
+        assert_eq!(resumed.save(), a.save());
+    }
+}
@@ -1,4 +1,4 @@
-use rengine::{AudioClip, RollbackSession, TextureId};
+use rengine::{AudioClip, Fixed, RollbackSession, TextureId};
 use serde::{Deserialize, Serialize};
 
 use crate::{FIGHTER_FOOT_OFFSET, FIGHTER_W, GROUND_Y, MAX_HP};
@@ -59,28 +59,28 @@ pub enum Facing {
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct FighterData {
-    pub x: f32,
-    pub y: f32,
-    pub vel_x: f32,
-    pub vel_y: f32,
+    pub x: Fixed,
+    pub y: Fixed,
+    pub vel_x: Fixed,
+    pub vel_y: Fixed,
     pub hp: i32,
     pub facing: Facing,
     pub state: FighterState,
-    pub state_timer: f32,
+    pub state_timer: Fixed,
     pub attack_hit_connected: bool,
     pub wins: u32,
 }
 
 impl FighterData {
-    pub fn rect_x(&self) -> f32 {
-        self.x - FIGHTER_W / 2.0
+    pub fn rect_x(&self) -> Fixed {
+        self.x - FIGHTER_W / Fixed::from_int(2)
     }
-    pub fn rect_y(&self) -> f32 {
+    pub fn rect_y(&self) -> Fixed {
         self.y - FIGHTER_FOOT_OFFSET
     }
 
     pub fn is_on_ground(&self) -> bool {
-        self.y <= GROUND_Y + 1.0
+        self.y <= GROUND_Y + Fixed::ONE
     }
 
     pub fn can_act(&self) -> bool {
@@ -90,18 +90,18 @@ impl FighterData {
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Spark {
-    pub x: f32,
-    pub y: f32,
-    pub vx: f32,
-    pub vy: f32,
-    pub life: f32,
+    pub x: Fixed,
+    pub y: Fixed,
+    pub vx: Fixed,
+    pub vy: Fixed,
+    pub life: Fixed,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct FightSnapshot {
     pub p1: FighterData,
     pub p2: FighterData,
-    pub round_pause: f32,
+    pub round_pause: Fixed,
     pub round_number: u32,
     pub sparks: Vec<Spark>,
 }
@@ -109,7 +109,7 @@ pub struct FightSnapshot {
 pub struct FightSim {
     pub p1: FighterData,
     pub p2: FighterData,
-    pub round_pause: f32,
+    pub round_pause: Fixed,
     pub round_number: u32,
     pub sparks: Vec<Spark>,
 }
@@ -118,30 +118,30 @@ impl FightSim {
     pub fn new() -> Self {
         Self {
             p1: FighterData {
-                x: 250.0,
+                x: Fixed::from_int(250),
                 y: GROUND_Y,
-                vel_x: 0.0,
-                vel_y: 0.0,
+                vel_x: Fixed::ZERO,
+                vel_y: Fixed::ZERO,
                 hp: MAX_HP,
                 facing: Facing::Right,
                 state: FighterState::Idle,
-                state_timer: 0.0,
+                state_timer: Fixed::ZERO,
                 attack_hit_connected: false,
                 wins: 0,
             },
             p2: FighterData {
-                x: 550.0,
+                x: Fixed::from_int(550),
                 y: GROUND_Y,
-                vel_x: 0.0,
-                vel_y: 0.0,
+                vel_x: Fixed::ZERO,
+                vel_y: Fixed::ZERO,
                 hp: MAX_HP,
                 facing: Facing::Left,
                 state: FighterState::Idle,
-                state_timer: 0.0,
+                state_timer: Fixed::ZERO,
                 attack_hit_connected: false,
                 wins: 0,
             },
-            round_pause: 0.0,
+            round_pause: Fixed::ZERO,
             round_number: 1,
             sparks: Vec::new(),
         }
@@ -9,22 +9,24 @@ pub fn bot_input(sim: &FpsSim, frame: u32, player: u32) -> FpsInput {
         return FpsInput::default();
     }
 
-    let dx = opp.x - me.x;
-    let dz = opp.z - me.z;
-    let dy = opp.y - me.y;
+    // Bots only produce inputs, which are what the peers exchange, so they can
+    // steer in f32 without risking a desync.
+    let dx = (opp.x - me.x).to_f32();
+    let dz = (opp.z - me.z).to_f32();
+    let dy = (opp.y - me.y).to_f32();
     let dist_xz = (dx * dx + dz * dz).sqrt().max(0.01);
 
     let target_yaw = dx.atan2(-dz);
     let target_pitch = (dy / dist_xz).atan();
 
-    let mut yaw_err = target_yaw - me.yaw;
+    let mut yaw_err = target_yaw - me.yaw.to_f32();
     while yaw_err > std::f32::consts::PI {
         yaw_err -= 2.0 * std::f32::consts::PI;
     }
     while yaw_err < -std::f32::consts::PI {
         yaw_err += 2.0 * std::f32::consts::PI;
     }
-    let pitch_err = target_pitch - me.pitch;
+    let pitch_err = target_pitch - me.pitch.to_f32();
 
     let smoothing = 0.15_f32;
     let dyaw = yaw_err * smoothing;
@@ -89,7 +91,8 @@ mod tests {
             CollisionWall::new(28.0, 0.0, 28.0, 12.0),
         ];
         let door_defs = Vec::new();
-        let spawn_points = [[4.0, PLAYER_HEIGHT, 4.0], [24.0, PLAYER_HEIGHT, 8.0]];
+        let height = PLAYER_HEIGHT.to_f32();
+        let spawn_points = [[4.0, height, 4.0], [24.0, height, 8.0]];
         FpsSim::new(walls, door_defs, spawn_points)
     }
 
@@ -86,6 +86,7 @@ pub fn build(engine: &mut Engine3D) -> BuildResult {
     engine.set_asset_root(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets"));
 
     let mut builder = LevelBuilder::new();
+    let wall_height = WALL_HEIGHT.to_f32();
 
     let floor_col = Color::from_rgba8(100, 100, 100, 255);
     let wall_col = Color::from_rgba8(160, 150, 140, 255);
@@ -95,37 +96,37 @@ pub fn build(engine: &mut Engine3D) -> BuildResult {
     let floor_col2 = Color::from_rgba8(120, 110, 100, 255);
 
     builder.floor_rect(0.0, 0.0, 8.0, 8.0, 0.0, floor_col);
-    builder.ceiling_rect(0.0, 0.0, 8.0, 8.0, WALL_HEIGHT, ceil_col);
-    builder.wall(0.0, 0.0, 8.0, 0.0, 0.0, WALL_HEIGHT, wall_col);
-    builder.wall(8.0, 8.0, 0.0, 8.0, 0.0, WALL_HEIGHT, wall_col);
-    builder.wall(0.0, 8.0, 0.0, 0.0, 0.0, WALL_HEIGHT, wall_col);
-    builder.wall(8.0, 0.0, 8.0, 3.0, 0.0, WALL_HEIGHT, wall_col);
-    builder.wall(8.0, 5.0, 8.0, 8.0, 0.0, WALL_HEIGHT, wall_col);
-    builder.wall(8.0, 3.0, 8.0, 5.0, 2.2, WALL_HEIGHT - 2.2, wall_col);
+    builder.ceiling_rect(0.0, 0.0, 8.0, 8.0, wall_height, ceil_col);
+    builder.wall(0.0, 0.0, 8.0, 0.0, 0.0, wall_height, wall_col);
+    builder.wall(8.0, 8.0, 0.0, 8.0, 0.0, wall_height, wall_col);
+    builder.wall(0.0, 8.0, 0.0, 0.0, 0.0, wall_height, wall_col);
+    builder.wall(8.0, 0.0, 8.0, 3.0, 0.0, wall_height, wall_col);
+    builder.wall(8.0, 5.0, 8.0, 8.0, 0.0, wall_height, wall_col);
+    builder.wall(8.0, 3.0, 8.0, 5.0, 2.2, wall_height - 2.2, wall_col);
 
     builder.floor_rect(8.0, 2.0, 16.0, 6.0, 0.0, floor_col2);
-    builder.ceiling_rect(8.0, 2.0, 16.0, 6.0, WALL_HEIGHT, ceil_col);
-    builder.wall(8.0, 2.0, 16.0, 2.0, 0.0, WALL_HEIGHT, wall_col2);
-    builder.wall(16.0, 6.0, 8.0, 6.0, 0.0, WALL_HEIGHT, wall_col2);
-    builder.wall(16.0, 2.0, 16.0, 3.0, 0.0, WALL_HEIGHT, wall_col2);
-    builder.wall(16.0, 5.0, 16.0, 6.0, 0.0, WALL_HEIGHT, wall_col2);
-    builder.wall(16.0, 3.0, 16.0, 5.0, 2.2, WALL_HEIGHT - 2.2, wall_col2);
+    builder.ceiling_rect(8.0, 2.0, 16.0, 6.0, wall_height, ceil_col);
+    builder.wall(8.0, 2.0, 16.0, 2.0, 0.0, wall_height, wall_col2);
+    builder.wall(16.0, 6.0, 8.0, 6.0, 0.0, wall_height, wall_col2);
+    builder.wall(16.0, 2.0, 16.0, 3.0, 0.0, wall_height, wall_col2);
+    builder.wall(16.0, 5.0, 16.0, 6.0, 0.0, wall_height, wall_col2);
+    builder.wall(16.0, 3.0, 16.0, 5.0, 2.2, wall_height - 2.2, wall_col2);
 
     builder.floor_rect(16.0, 0.0, 28.0, 12.0, 0.0, floor_col);
-    builder.ceiling_rect(16.0, 0.0, 28.0, 12.0, WALL_HEIGHT, ceil_col);
-    builder.wall(16.0, 0.0, 28.0, 0.0, 0.0, WALL_HEIGHT, accent);
-    builder.wall(28.0, 12.0, 16.0, 12.0, 0.0, WALL_HEIGHT, accent);
-    builder.wall(28.0, 0.0, 28.0, 12.0, 0.0, WALL_HEIGHT, accent);
-    builder.wall(16.0, 12.0, 16.0, 6.0, 0.0, WALL_HEIGHT, wall_col2);
-    builder.wall(16.0, 2.0, 16.0, 0.0, 0.0, WALL_HEIGHT, wall_col2);
+    builder.ceiling_rect(16.0, 0.0, 28.0, 12.0, wall_height, ceil_col);
+    builder.wall(16.0, 0.0, 28.0, 0.0, 0.0, wall_height, accent);
+    builder.wall(28.0, 12.0, 16.0, 12.0, 0.0, wall_height, accent);
+    builder.wall(28.0, 0.0, 28.0, 12.0, 0.0, wall_height, accent);
+    builder.wall(16.0, 12.0, 16.0, 6.0, 0.0, wall_height, wall_col2);
+    builder.wall(16.0, 2.0, 16.0, 0.0, 0.0, wall_height, wall_col2);
 
     let pillar_col = Color::from_rgba8(90, 80, 70, 255);
     for &(px, pz) in &[(20.0, 3.0), (20.0, 9.0), (24.0, 3.0), (24.0, 9.0)] {
-        let (v, i) = cube_mesh(0.6, WALL_HEIGHT, 0.6, pillar_col);
+        let (v, i) = cube_mesh(0.6, wall_height, 0.6, pillar_col);
         let base = builder.verts.len() as u32;
         for mut vert in v {
             vert.position[0] += px;
-            vert.position[1] += WALL_HEIGHT / 2.0;
+            vert.position[1] += wall_height / 2.0;
             vert.position[2] += pz;
             builder.verts.push(vert);
         }
@@ -184,17 +185,17 @@ pub fn build(engine: &mut Engine3D) -> BuildResult {
 
     let door_defs = vec![
         DoorDef {
-            x: 8.0,
-            z: 4.0,
+            x: Fixed::from_int(8),
+            z: Fixed::from_int(4),
             slides_x: false,
-            trigger_radius: 2.0,
+            trigger_radius: Fixed::from_int(2),
             wall: CollisionWall::new(8.0, 3.0, 8.0, 5.0),
         },
         DoorDef {
-            x: 16.0,
-            z: 4.0,
+            x: Fixed::from_int(16),
+            z: Fixed::from_int(4),
             slides_x: false,
-            trigger_radius: 2.0,
+            trigger_radius: Fixed::from_int(2),
             wall: CollisionWall::new(16.0, 3.0, 16.0, 5.0),
         },
     ];
@@ -210,8 +211,8 @@ pub fn build(engine: &mut Engine3D) -> BuildResult {
         .mesh();
 
     let spawn_points = [
-        [4.0, crate::PLAYER_HEIGHT, 4.0],
-        [24.0, crate::PLAYER_HEIGHT, 6.0],
+        [4.0, crate::PLAYER_HEIGHT.to_f32(), 4.0],
+        [24.0, crate::PLAYER_HEIGHT.to_f32(), 6.0],
     ];
 
     BuildResult {
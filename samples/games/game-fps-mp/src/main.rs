@@ -6,26 +6,29 @@ pub mod sim;
 pub mod state;
 
 use rengine::{
-    Engine3D, EngineConfig, Frame3D, Game3D, OnlineConfig, RollbackConfig, RollbackSession,
+    Engine3D, EngineConfig, Fixed, Frame3D, Game3D, OnlineConfig, RollbackConfig, RollbackSession,
     SessionMode,
 };
 use state::{FpsInput, FpsMpGame, FpsSim};
 
-pub const MOVE_SPEED: f32 = 6.0;
 pub const MOUSE_SENSITIVITY: f32 = 0.002;
-pub const WALL_HEIGHT: f32 = 3.0;
-pub const PLAYER_HEIGHT: f32 = 1.7;
-pub const PLAYER_RADIUS: f32 = 0.3;
-pub const GRAVITY: f32 = 15.0;
-pub const JUMP_VEL: f32 = 6.0;
-pub const PROJECTILE_SPEED: f32 = 30.0;
-pub const PROJECTILE_LIFETIME: f32 = 3.0;
-pub const DOOR_OPEN_SPEED: f32 = 3.0;
+
+// The rollback simulation runs in fixed point so every peer computes
+// bit-identical states; level meshes and rendering convert back to f32.
+pub const MOVE_SPEED: Fixed = Fixed::from_int(6);
+pub const WALL_HEIGHT: Fixed = Fixed::from_int(3);
+pub const PLAYER_HEIGHT: Fixed = Fixed::from_ratio(17, 10);
+pub const PLAYER_RADIUS: Fixed = Fixed::from_ratio(3, 10);
+pub const GRAVITY: Fixed = Fixed::from_int(15);
+pub const JUMP_VEL: Fixed = Fixed::from_int(6);
+pub const PROJECTILE_SPEED: Fixed = Fixed::from_int(30);
+pub const PROJECTILE_LIFETIME: Fixed = Fixed::from_int(3);
+pub const DOOR_OPEN_SPEED: Fixed = Fixed::from_int(3);
 pub const MAX_HP: i32 = 100;
-pub const RESPAWN_TIME: f32 = 3.0;
-pub const SHOOT_COOLDOWN: f32 = 0.3;
-pub const HIT_RADIUS: f32 = 0.5;
-pub const FIXED_DT: f32 = 1.0 / 60.0;
+pub const RESPAWN_TIME: Fixed = Fixed::from_int(3);
+pub const SHOOT_COOLDOWN: Fixed = Fixed::from_ratio(3, 10);
+pub const HIT_RADIUS: Fixed = Fixed::HALF;
+pub const FIXED_DT: Fixed = Fixed::from_ratio(1, 60);
 
 impl Game3D for FpsMpGame {
     fn new(engine: &mut Engine3D) -> Self {
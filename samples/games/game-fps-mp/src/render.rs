@@ -11,20 +11,21 @@ pub fn draw(game: &FpsMpGame, engine: &Engine3D, frame: &mut Frame3D) {
     frame.light_intensity = 0.7;
     frame.ambient_intensity = 0.4;
 
-    frame.camera.position = Vec3::new(player.x, player.y, player.z);
-    frame.camera.yaw = player.yaw;
-    frame.camera.pitch = player.pitch;
+    frame.camera.position = Vec3::new(player.x.to_f32(), player.y.to_f32(), player.z.to_f32());
+    frame.camera.yaw = player.yaw.to_f32();
+    frame.camera.pitch = player.pitch.to_f32();
 
     frame.draw_raw(&game.level_verts, &game.level_idxs);
 
     for (i, door_def) in game.sim.door_defs.iter().enumerate() {
         let door_state = &game.sim.door_states[i];
+        let offset = door_state.offset.to_f32();
         let slide = if door_def.slides_x {
-            Vec3::new(door_state.offset, 0.0, 0.0)
+            Vec3::new(offset, 0.0, 0.0)
         } else {
-            Vec3::new(0.0, 0.0, door_state.offset)
+            Vec3::new(0.0, 0.0, offset)
         };
-        let pos = Vec3::new(door_def.x, 1.1, door_def.z) + slide;
+        let pos = Vec3::new(door_def.x.to_f32(), 1.1, door_def.z.to_f32()) + slide;
         frame.draw_mesh(game.door_meshes[i], pos);
     }
 
@@ -32,11 +33,13 @@ pub fn draw(game: &FpsMpGame, engine: &Engine3D, frame: &mut Frame3D) {
         if i == local || !p.alive() {
             continue;
         }
-        frame.draw_mesh(game.player_mesh, Vec3::new(p.x, p.y - 0.85, p.z));
+        let pos = Vec3::new(p.x.to_f32(), p.y.to_f32() - 0.85, p.z.to_f32());
+        frame.draw_mesh(game.player_mesh, pos);
     }
 
     for proj in &game.sim.projectiles {
-        frame.draw_mesh(game.projectile_mesh, Vec3::new(proj.x, proj.y, proj.z));
+        let pos = Vec3::new(proj.x.to_f32(), proj.y.to_f32(), proj.z.to_f32());
+        frame.draw_mesh(game.projectile_mesh, pos);
     }
 
     let screen_size = engine.window_size();
@@ -1,4 +1,4 @@
-use rengine::Vec3;
+use rengine::{Fixed, FixedVec2};
 
 use crate::state::{FpsInput, FpsSim, FpsSnapshot, ProjectileData};
 use crate::{
@@ -16,16 +16,16 @@ impl FpsSim {
 
             if !self.players[i].alive() {
                 self.players[i].respawn_timer -= dt;
-                if self.players[i].respawn_timer <= 0.0 {
+                if self.players[i].respawn_timer <= Fixed::ZERO {
                     self.players[i].x = sp[0];
                     self.players[i].y = sp[1];
                     self.players[i].z = sp[2];
-                    self.players[i].vel_y = 0.0;
+                    self.players[i].vel_y = Fixed::ZERO;
                     self.players[i].hp = MAX_HP;
                     self.players[i].on_ground = true;
-                    self.players[i].shoot_cooldown = 0.0;
-                    self.players[i].yaw = if i == 0 { 0.0 } else { std::f32::consts::PI };
-                    self.players[i].pitch = 0.0;
+                    self.players[i].shoot_cooldown = Fixed::ZERO;
+                    self.players[i].yaw = if i == 0 { Fixed::ZERO } else { Fixed::PI };
+                    self.players[i].pitch = Fixed::ZERO;
                 }
                 continue;
             }
@@ -33,14 +33,15 @@ impl FpsSim {
             let input = inputs[i];
 
             self.players[i].yaw += input.decode_look_dx();
-            let max_pitch = 89.0_f32.to_radians();
+            let max_pitch = Fixed::PI * Fixed::from_ratio(89, 180);
             self.players[i].pitch =
                 (self.players[i].pitch + input.decode_look_dy()).clamp(-max_pitch, max_pitch);
 
+            // Horizontal movement lives on the XZ plane: `FixedVec2::y` is world z.
             let yaw = self.players[i].yaw;
-            let forward = Vec3::new(yaw.sin(), 0.0, -yaw.cos());
-            let right = Vec3::new(yaw.cos(), 0.0, yaw.sin());
-            let mut dir = Vec3::ZERO;
+            let forward = FixedVec2::new(yaw.sin(), -yaw.cos());
+            let right = FixedVec2::new(yaw.cos(), yaw.sin());
+            let mut dir = FixedVec2::ZERO;
             if input.forward() {
                 dir += forward;
             }
@@ -53,12 +54,10 @@ impl FpsSim {
             if input.left() {
                 dir -= right;
             }
-            if dir.length_squared() > 0.0 {
-                dir = dir.normalize();
-            }
+            let dir = dir.normalize_or_zero();
 
             let mut new_x = self.players[i].x + dir.x * MOVE_SPEED * dt;
-            let mut new_z = self.players[i].z + dir.z * MOVE_SPEED * dt;
+            let mut new_z = self.players[i].z + dir.y * MOVE_SPEED * dt;
 
             for wall in &self.walls {
                 let (px, pz) = wall.push_out(new_x, new_z, PLAYER_RADIUS);
@@ -66,7 +65,7 @@ impl FpsSim {
                 new_z = pz;
             }
             for (di, door_def) in self.door_defs.iter().enumerate() {
-                if self.door_states[di].offset < 1.5 {
+                if self.door_states[di].offset < Fixed::from_ratio(3, 2) {
                     let (px, pz) = door_def.wall.push_out(new_x, new_z, PLAYER_RADIUS);
                     new_x = px;
                     new_z = pz;
@@ -80,35 +79,36 @@ impl FpsSim {
                 self.players[i].on_ground = false;
             }
             self.players[i].vel_y -= GRAVITY * dt;
-            self.players[i].y += self.players[i].vel_y * dt;
+            let vel_y = self.players[i].vel_y;
+            self.players[i].y += vel_y * dt;
             if self.players[i].y <= PLAYER_HEIGHT {
                 self.players[i].y = PLAYER_HEIGHT;
-                self.players[i].vel_y = 0.0;
+                self.players[i].vel_y = Fixed::ZERO;
                 self.players[i].on_ground = true;
             }
 
             self.players[i].shoot_cooldown -= dt;
-            if self.players[i].shoot_cooldown < 0.0 {
-                self.players[i].shoot_cooldown = 0.0;
+            if self.players[i].shoot_cooldown < Fixed::ZERO {
+                self.players[i].shoot_cooldown = Fixed::ZERO;
             }
 
-            if input.shoot() && self.players[i].shoot_cooldown <= 0.0 {
+            if input.shoot() && self.players[i].shoot_cooldown <= Fixed::ZERO {
                 let p_yaw = self.players[i].yaw;
                 let p_pitch = self.players[i].pitch;
-                let cam_fwd = Vec3::new(
+                // Already unit length: a horizontal unit vector scaled by cos(pitch).
+                let (fx, fy, fz) = (
                     p_yaw.sin() * p_pitch.cos(),
                     p_pitch.sin(),
                     -p_yaw.cos() * p_pitch.cos(),
-                )
-                .normalize();
+                );
 
                 self.projectiles.push(ProjectileData {
-                    x: self.players[i].x + cam_fwd.x * 0.5,
-                    y: self.players[i].y + cam_fwd.y * 0.5,
-                    z: self.players[i].z + cam_fwd.z * 0.5,
-                    vx: cam_fwd.x * PROJECTILE_SPEED,
-                    vy: cam_fwd.y * PROJECTILE_SPEED,
-                    vz: cam_fwd.z * PROJECTILE_SPEED,
+                    x: self.players[i].x + fx * Fixed::HALF,
+                    y: self.players[i].y + fy * Fixed::HALF,
+                    z: self.players[i].z + fz * Fixed::HALF,
+                    vx: fx * PROJECTILE_SPEED,
+                    vy: fy * PROJECTILE_SPEED,
+                    vz: fz * PROJECTILE_SPEED,
                     life: PROJECTILE_LIFETIME,
                     owner: i as u8,
                     alive: true,
@@ -125,12 +125,12 @@ impl FpsSim {
             proj.y += proj.vy * dt;
             proj.z += proj.vz * dt;
             proj.life -= dt;
-            if proj.life <= 0.0
-                || proj.x < -0.5
-                || proj.x > 28.5
-                || proj.z < -0.5
-                || proj.z > 12.5
-                || proj.y < 0.0
+            if proj.life <= Fixed::ZERO
+                || proj.x < -Fixed::HALF
+                || proj.x > Fixed::from_ratio(57, 2)
+                || proj.z < -Fixed::HALF
+                || proj.z > Fixed::from_ratio(25, 2)
+                || proj.y < Fixed::ZERO
                 || proj.y > WALL_HEIGHT
             {
                 proj.alive = false;
@@ -182,10 +182,11 @@ impl FpsSim {
                     door_state.open = true;
                 }
             }
-            if door_state.open && door_state.offset < 2.2 {
+            let fully_open = Fixed::from_ratio(11, 5);
+            if door_state.open && door_state.offset < fully_open {
                 door_state.offset += DOOR_OPEN_SPEED * dt;
-                if door_state.offset > 2.2 {
-                    door_state.offset = 2.2;
+                if door_state.offset > fully_open {
+                    door_state.offset = fully_open;
                 }
             }
         }
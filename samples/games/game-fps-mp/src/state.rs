@@ -1,4 +1,4 @@
-use rengine::{MeshId, RollbackSession, Vertex3D};
+use rengine::{Fixed, MeshId, RollbackSession, Vertex3D};
 use serde::{Deserialize, Serialize};
 
 use crate::{MAX_HP, PLAYER_HEIGHT};
@@ -51,27 +51,27 @@ impl FpsInput {
         )
     }
 
-    pub fn decode_look_dx(self) -> f32 {
-        self.look_dx as f32 / LOOK_SCALE
+    pub fn decode_look_dx(self) -> Fixed {
+        Fixed::from_ratio(self.look_dx.into(), LOOK_SCALE as i32)
     }
-    pub fn decode_look_dy(self) -> f32 {
-        self.look_dy as f32 / LOOK_SCALE
+    pub fn decode_look_dy(self) -> Fixed {
+        Fixed::from_ratio(self.look_dy.into(), LOOK_SCALE as i32)
     }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct PlayerData {
-    pub x: f32,
-    pub y: f32,
-    pub z: f32,
-    pub vel_y: f32,
-    pub yaw: f32,
-    pub pitch: f32,
+    pub x: Fixed,
+    pub y: Fixed,
+    pub z: Fixed,
+    pub vel_y: Fixed,
+    pub yaw: Fixed,
+    pub pitch: Fixed,
     pub on_ground: bool,
     pub hp: i32,
     pub score: u32,
-    pub respawn_timer: f32,
-    pub shoot_cooldown: f32,
+    pub respawn_timer: Fixed,
+    pub shoot_cooldown: Fixed,
 }
 
 impl PlayerData {
@@ -79,65 +79,71 @@ impl PlayerData {
         self.hp > 0
     }
 
-    pub fn new(x: f32, z: f32, yaw: f32) -> Self {
+    pub fn new(x: Fixed, z: Fixed, yaw: Fixed) -> Self {
         Self {
             x,
             y: PLAYER_HEIGHT,
             z,
-            vel_y: 0.0,
+            vel_y: Fixed::ZERO,
             yaw,
-            pitch: 0.0,
+            pitch: Fixed::ZERO,
             on_ground: true,
             hp: MAX_HP,
             score: 0,
-            respawn_timer: 0.0,
-            shoot_cooldown: 0.0,
+            respawn_timer: Fixed::ZERO,
+            shoot_cooldown: Fixed::ZERO,
         }
     }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ProjectileData {
-    pub x: f32,
-    pub y: f32,
-    pub z: f32,
-    pub vx: f32,
-    pub vy: f32,
-    pub vz: f32,
-    pub life: f32,
+    pub x: Fixed,
+    pub y: Fixed,
+    pub z: Fixed,
+    pub vx: Fixed,
+    pub vy: Fixed,
+    pub vz: Fixed,
+    pub life: Fixed,
     pub owner: u8,
     pub alive: bool,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct DoorState {
-    pub offset: f32,
+    pub offset: Fixed,
     pub open: bool,
 }
 
 #[derive(Clone)]
 pub struct DoorDef {
-    pub x: f32,
-    pub z: f32,
+    pub x: Fixed,
+    pub z: Fixed,
     pub slides_x: bool,
-    pub trigger_radius: f32,
+    pub trigger_radius: Fixed,
     pub wall: CollisionWall,
 }
 
 #[derive(Clone)]
 pub struct CollisionWall {
-    pub x0: f32,
-    pub z0: f32,
-    pub x1: f32,
-    pub z1: f32,
+    pub x0: Fixed,
+    pub z0: Fixed,
+    pub x1: Fixed,
+    pub z1: Fixed,
 }
 
 impl CollisionWall {
+    /// Walls are authored in level units and snapped to fixed point once here.
     pub fn new(x0: f32, z0: f32, x1: f32, z1: f32) -> Self {
-        Self { x0, z0, x1, z1 }
+        Self {
+            x0: Fixed::from_f32(x0),
+            z0: Fixed::from_f32(z0),
+            x1: Fixed::from_f32(x1),
+            z1: Fixed::from_f32(z1),
+        }
     }
 
-    pub fn push_out(&self, px: f32, pz: f32, radius: f32) -> (f32, f32) {
+    pub fn push_out(&self, px: Fixed, pz: Fixed, radius: Fixed) -> (Fixed, Fixed) {
         let dx = (self.x1 - self.x0).abs();
         let dz = (self.z1 - self.z0).abs();
 
@@ -148,7 +154,11 @@ impl CollisionWall {
             if px >= x_min - radius && px <= x_max + radius {
                 let dist = pz - z_wall;
                 if dist.abs() < radius {
-                    let sign = if dist >= 0.0 { 1.0 } else { -1.0 };
+                    let sign = if dist >= Fixed::ZERO {
+                        Fixed::ONE
+                    } else {
+                        -Fixed::ONE
+                    };
                     return (px, z_wall + sign * radius);
                 }
             }
@@ -159,7 +169,11 @@ impl CollisionWall {
             if pz >= z_min - radius && pz <= z_max + radius {
                 let dist = px - x_wall;
                 if dist.abs() < radius {
-                    let sign = if dist >= 0.0 { 1.0 } else { -1.0 };
+                    let sign = if dist >= Fixed::ZERO {
+                        Fixed::ONE
+                    } else {
+                        -Fixed::ONE
+                    };
                     return (x_wall + sign * radius, pz);
                 }
             }
@@ -182,7 +196,7 @@ pub struct FpsSim {
 
     pub walls: Vec<CollisionWall>,
     pub door_defs: Vec<DoorDef>,
-    pub spawn_points: [[f32; 3]; 2],
+    pub spawn_points: [[Fixed; 3]; 2],
 }
 
 impl FpsSim {
@@ -194,13 +208,14 @@ impl FpsSim {
         let door_states: Vec<DoorState> = door_defs
             .iter()
             .map(|_| DoorState {
-                offset: 0.0,
+                offset: Fixed::ZERO,
                 open: false,
             })
             .collect();
 
-        let p0_yaw = 0.0_f32;
-        let p1_yaw = std::f32::consts::PI;
+        let spawn_points = spawn_points.map(|point| point.map(Fixed::from_f32));
+        let p0_yaw = Fixed::ZERO;
+        let p1_yaw = Fixed::PI;
 
         Self {
             players: vec![
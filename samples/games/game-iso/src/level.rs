@@ -1,9 +1,10 @@
 use std::path::PathBuf;
 
-use rengine::Engine;
+use rengine::pixelart::{darken, PixelCanvas};
+use rengine::{Color, Engine, IsoBox, IsoRenderer, TextureId, TileDef, TileMap, Vec2, Vec3};
 
 use crate::state::{IsoGame, Tile};
-use crate::MAP_SIZE;
+use crate::{MAP_SIZE, TILE_H, TILE_W};
 
 pub fn build(engine: &mut Engine) -> IsoGame {
     engine.set_asset_root(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets"));
@@ -11,10 +12,7 @@ pub fn build(engine: &mut Engine) -> IsoGame {
     let tile_sheet = engine
         .load_sprite_sheet("tiles.png", 64, 32)
         .expect("failed to load iso tile sheet");
-    let grass_tex = tile_sheet.texture;
-    let dirt_tex = tile_sheet.texture;
-    let water_tex = tile_sheet.texture;
-    let stone_tex = tile_sheet.texture;
+    let renderer = IsoRenderer::new(TILE_W, TILE_H);
     let tree_tex = engine
         .load_texture("tree.png")
         .expect("failed to load iso tree texture")
@@ -68,20 +66,99 @@ pub fn build(engine: &mut Engine) -> IsoGame {
         (4, 6),
     ];
 
+    // Stone stands a level high; the log lies across three cells of grass.
+    let stone_block = block_texture(
+        engine,
+        &renderer,
+        &IsoBox::cell(0, 0, 0.0, 1.0),
+        Color::from_rgba8(140, 140, 150, 255),
+    );
+    let log = IsoBox::new(Vec3::new(8.5, 9.6, 0.0), Vec3::new(11.5, 10.4, 0.4));
+    let log_tex = block_texture(engine, &renderer, &log, Color::from_rgba8(120, 80, 40, 255));
+
+    // Tile ids follow the order of `Tile`'s variants.
+    let mut tiles = TileMap::new(MAP_SIZE as usize, MAP_SIZE as usize, 1.0);
+    tiles.add_tile(TileDef::solid(tile_sheet.texture).with_uv(tile_sheet.uv_rect(0, 0)));
+    tiles.add_tile(TileDef::solid(tile_sheet.texture).with_uv(tile_sheet.uv_rect(1, 0)));
+    tiles.add_tile(TileDef::solid(tile_sheet.texture).with_uv(tile_sheet.uv_rect(2, 0)));
+    tiles.add_tile(TileDef::solid(stone_block).with_height(1.0));
+    for (row, cells) in map.iter().enumerate() {
+        for (col, &tile) in cells.iter().enumerate() {
+            tiles.set(col, row, Some(tile as usize));
+        }
+    }
+
     IsoGame {
         map,
-        grass_tex,
-        dirt_tex,
-        water_tex,
-        stone_tex,
-        grass_uv: tile_sheet.uv_rect(0, 0),
-        dirt_uv: tile_sheet.uv_rect(1, 0),
-        water_uv: tile_sheet.uv_rect(2, 0),
-        stone_uv: tile_sheet.uv_rect(3, 0),
+        tiles,
+        renderer,
         tree_tex,
         player_tex,
+        log_tex,
         trees,
+        log,
         player_col: 7.0,
         player_row: 7.0,
     }
 }
+
+/// A texture of a box as the renderer projects it: its top in `color` and
+/// the two faces towards the viewer in darker shades, stretched over
+/// [`IsoRenderer::silhouette`].
+fn block_texture(
+    engine: &mut Engine,
+    renderer: &IsoRenderer,
+    bounds: &IsoBox,
+    color: Color,
+) -> TextureId {
+    let (min, max) = (bounds.min, bounds.max);
+    let corner = |x: f32, y: f32, z: f32| renderer.to_screen(Vec3::new(x, y, z));
+    let faces = [
+        (
+            [
+                corner(min.x, min.y, max.z),
+                corner(min.x, max.y, max.z),
+                corner(max.x, max.y, max.z),
+                corner(max.x, min.y, max.z),
+            ],
+            color,
+        ),
+        (
+            [
+                corner(min.x, max.y, min.z),
+                corner(max.x, max.y, min.z),
+                corner(max.x, max.y, max.z),
+                corner(min.x, max.y, max.z),
+            ],
+            darken(color, 0.8),
+        ),
+        (
+            [
+                corner(max.x, max.y, min.z),
+                corner(max.x, min.y, min.z),
+                corner(max.x, min.y, max.z),
+                corner(max.x, max.y, max.z),
+            ],
+            darken(color, 0.65),
+        ),
+    ];
+    let inside = |quad: &[Vec2; 4], p: Vec2| {
+        let sides = (0..4).map(|i| (quad[(i + 1) % 4] - quad[i]).perp_dot(p - quad[i]));
+        let sides: Vec<f32> = sides.collect();
+        sides.iter().all(|&s| s >= 0.0) || sides.iter().all(|&s| s <= 0.0)
+    };
+
+    let rect = renderer.silhouette(bounds);
+    let (width, height) = (rect.width.round() as u32, rect.height.round() as u32);
+    let mut pc = PixelCanvas::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let p = Vec2::new(rect.x + x as f32 + 0.5, rect.top() - y as f32 - 0.5);
+            if let Some(&(_, shade)) = faces.iter().find(|(quad, _)| inside(quad, p)) {
+                pc.set(x as i32, y as i32, shade);
+            }
+        }
+    }
+    let bytes = pc.into_bytes();
+    engine.create_texture(width, height, &bytes)
+}
@@ -1,3 +1,5 @@
+use rengine::{IsoBox, Vec2};
+
 use crate::state::{IsoGame, Tile};
 use crate::{MAP_SIZE, PLAYER_SPEED, TILE_W};
 
@@ -5,17 +7,17 @@ pub fn move_player(game: &mut IsoGame, dc: f32, dr: f32, dt: f32) {
     let speed_tiles = PLAYER_SPEED / TILE_W;
 
     let new_col = game.player_col + dc * speed_tiles * dt;
-    if can_walk(&game.map, new_col, game.player_row) {
+    if can_walk(&game.map, &game.log, new_col, game.player_row) {
         game.player_col = new_col;
     }
 
     let new_row = game.player_row + dr * speed_tiles * dt;
-    if can_walk(&game.map, game.player_col, new_row) {
+    if can_walk(&game.map, &game.log, game.player_col, new_row) {
         game.player_row = new_row;
     }
 }
 
-pub fn can_walk(map: &[Vec<Tile>], col: f32, row: f32) -> bool {
+pub fn can_walk(map: &[Vec<Tile>], log: &IsoBox, col: f32, row: f32) -> bool {
     let margin = 0.3;
     for &(dc, dr) in &[
         (-margin, -margin),
@@ -32,6 +34,10 @@ pub fn can_walk(map: &[Vec<Tile>], col: f32, row: f32) -> bool {
         if tile == Tile::Water || tile == Tile::Stone {
             return false;
         }
+        let corner = Vec2::new(col + dc, row + dr);
+        if corner.cmpgt(log.min.truncate()).all() && corner.cmplt(log.max.truncate()).all() {
+            return false;
+        }
     }
     true
 }
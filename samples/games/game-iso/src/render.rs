@@ -1,56 +1,47 @@
-use rengine::{Color, DrawParams, Frame, Vec2};
+use rengine::{Color, DrawParams, Frame, IsoBox, Vec2};
 
-use crate::state::{IsoGame, Tile};
-use crate::{MAP_SIZE, TILE_H, TILE_W};
+use crate::state::IsoGame;
 
-pub fn draw(game: &IsoGame, frame: &mut Frame) {
+pub fn draw(game: &mut IsoGame, frame: &mut Frame) {
     frame.clear_color = Color::from_rgba8(30, 30, 50, 255);
 
-    let player_screen = iso_to_screen_frac(game.player_col, game.player_row, TILE_W, TILE_H);
-    frame.camera.position = player_screen;
-
-    for row in 0..MAP_SIZE {
-        for col in 0..MAP_SIZE {
-            let tile = game.map[row as usize][col as usize];
-            let tex = match tile {
-                Tile::Grass => game.grass_tex,
-                Tile::Dirt => game.dirt_tex,
-                Tile::Water => game.water_tex,
-                Tile::Stone => game.stone_tex,
-            };
-            let uv = match tile {
-                Tile::Grass => game.grass_uv,
-                Tile::Dirt => game.dirt_uv,
-                Tile::Water => game.water_uv,
-                Tile::Stone => game.stone_uv,
-            };
-            let screen = iso_to_screen(col, row, TILE_W, TILE_H);
-            let draw_pos = Vec2::new(screen.x - TILE_W / 2.0, screen.y - TILE_H / 2.0);
-            frame.draw_sprite(
-                DrawParams::new(tex, draw_pos, Vec2::new(TILE_W, TILE_H)).with_uv_rect(uv),
-            );
-        }
+    let player = Vec2::new(game.player_col, game.player_row);
+    frame.camera.position = game.renderer.to_screen(player.extend(0.0));
+
+    // The renderer sorts tiles, props and the player against each other by
+    // their footprints, so walls hide whoever is behind them.
+    let renderer = &mut game.renderer;
+    renderer.add_tilemap(&game.tiles, frame.view_rect());
+
+    for &(col, row) in &game.trees {
+        let bounds = IsoBox::standing(
+            Vec2::new(col as f32, row as f32),
+            Vec2::splat(0.4),
+            0.0,
+            1.5,
+        );
+        let params = DrawParams::new(
+            game.tree_tex,
+            renderer.anchor(&bounds),
+            Vec2::new(32.0, 48.0),
+        )
+        .with_origin(Vec2::new(16.0, 8.0));
+        renderer.add(bounds, params);
     }
 
-    let mut sorted_trees = game.trees.clone();
-    sorted_trees.sort_by_key(|&(_, r)| r);
-    for &(col, row) in &sorted_trees {
-        let screen = iso_to_screen(col, row, TILE_W, TILE_H);
-        let draw_pos = Vec2::new(screen.x - 16.0, screen.y - 8.0);
-        frame.draw(game.tree_tex, draw_pos, Vec2::new(32.0, 48.0));
-    }
-
-    let player_screen = iso_to_screen_frac(game.player_col, game.player_row, TILE_W, TILE_H);
-    let draw_pos = Vec2::new(player_screen.x - 8.0, player_screen.y - 8.0);
-    frame.draw(game.player_tex, draw_pos, Vec2::new(16.0, 24.0));
-}
-
-fn iso_to_screen(col: i32, row: i32, tile_width: f32, tile_height: f32) -> Vec2 {
-    iso_to_screen_frac(col as f32, row as f32, tile_width, tile_height)
-}
-
-fn iso_to_screen_frac(col: f32, row: f32, tile_width: f32, tile_height: f32) -> Vec2 {
-    let x = (col - row) * (tile_width / 2.0);
-    let y = (col + row) * (tile_height / 2.0);
-    Vec2::new(x, -y)
+    let log = renderer.silhouette(&game.log);
+    let params = DrawParams::new(game.log_tex, log.center(), Vec2::new(log.width, log.height))
+        .with_centered_origin();
+    renderer.add(game.log, params);
+
+    let bounds = IsoBox::standing(player, Vec2::splat(0.6), 0.0, 0.75);
+    let params = DrawParams::new(
+        game.player_tex,
+        renderer.anchor(&bounds),
+        Vec2::new(16.0, 24.0),
+    )
+    .with_origin(Vec2::new(8.0, 8.0));
+    renderer.add(bounds, params);
+
+    renderer.flush(frame);
 }
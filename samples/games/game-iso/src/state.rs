@@ -1,4 +1,4 @@
-use rengine::TextureId;
+use rengine::{IsoBox, IsoRenderer, TextureId, TileMap};
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum Tile {
@@ -10,17 +10,15 @@ pub enum Tile {
 
 pub struct IsoGame {
     pub map: Vec<Vec<Tile>>,
-    pub grass_tex: TextureId,
-    pub dirt_tex: TextureId,
-    pub water_tex: TextureId,
-    pub stone_tex: TextureId,
-    pub grass_uv: [f32; 4],
-    pub dirt_uv: [f32; 4],
-    pub water_uv: [f32; 4],
-    pub stone_uv: [f32; 4],
+    /// `map` as tiles for the renderer, with stone raised into walls.
+    pub tiles: TileMap,
+    pub renderer: IsoRenderer,
     pub tree_tex: TextureId,
     pub player_tex: TextureId,
+    pub log_tex: TextureId,
     pub trees: Vec<(i32, i32)>,
+    /// A fallen log lying across several cells.
+    pub log: IsoBox,
 
     pub player_col: f32,
     pub player_row: f32,
@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use rengine::tilemap::{TileDef, TileMap};
-use rengine::{Engine, Vec2};
+use rengine::{Engine, GridPathCache, NavGrid, PathFollower, Rng, Vec2, Wander};
 
 use crate::state::{Enemy, Gem, Player, TopDown};
 use crate::{ENEMY_SPEED, MAP_H, MAP_W, TILE_SIZE};
@@ -35,10 +35,16 @@ pub fn build(engine: &mut Engine) -> TopDown {
         .expect("failed to load topdown scene");
 
     let mut tilemap = TileMap::new(MAP_W, MAP_H, TILE_SIZE);
-    let grass_id = tilemap.add_tile(TileDef::solid(world_sheet.texture).with_uv(grass_uv));
-    let dirt_id = tilemap.add_tile(TileDef::solid(world_sheet.texture).with_uv(dirt_uv));
+    // Only stone blocks, so enemies' obstacle avoidance sees the walls alone.
+    let ground = |uv| {
+        TileDef::solid(world_sheet.texture)
+            .with_uv(uv)
+            .with_collision(false)
+    };
+    let grass_id = tilemap.add_tile(ground(grass_uv));
+    let dirt_id = tilemap.add_tile(ground(dirt_uv));
     let stone_id = tilemap.add_tile(TileDef::solid(world_sheet.texture).with_uv(stone_uv));
-    let water_id = tilemap.add_tile(TileDef::solid(world_sheet.texture).with_uv(water_uv));
+    let water_id = tilemap.add_tile(ground(water_uv));
 
     for row in 0..MAP_H {
         for col in 0..MAP_W {
@@ -101,6 +107,9 @@ pub fn build(engine: &mut Engine) -> TopDown {
             pos: instance.position,
             vel: enemy_directions[index % enemy_directions.len()],
             tex: enemy_tex,
+            wander: Wander::new(ENEMY_SPEED, ENEMY_SPEED * 0.5, 3.0),
+            path: PathFollower::new(Vec::new(), TILE_SIZE / 4.0),
+            replan_in: 0.0,
         })
         .collect();
 
@@ -122,11 +131,23 @@ pub fn build(engine: &mut Engine) -> TopDown {
         tex: player_tex,
     };
 
+    // Enemies keep to the dirt paths and wade through water only when it
+    // saves a long detour.
+    let nav = NavGrid::from_tilemap_with(&tilemap, |tile| match tile {
+        Some(id) if id == stone_id => None,
+        Some(id) if id == water_id => Some(4.0),
+        Some(id) if id == grass_id => Some(1.5),
+        _ => Some(1.0),
+    });
+
     TopDown {
         player,
         enemies,
         gems,
         tilemap,
+        nav,
+        paths: GridPathCache::new(32),
+        rng: Rng::new(7),
         score: 0,
         scene,
     }
@@ -13,6 +13,9 @@ pub const MAP_H: usize = 20;
 pub const PLAYER_SPEED: f32 = 150.0;
 pub const PLAYER_SIZE: f32 = 28.0;
 pub const ENEMY_SPEED: f32 = 60.0;
+/// Enemies closer than this to the player give up patrolling and give chase.
+pub const CHASE_RANGE: f32 = 10.0 * TILE_SIZE;
+pub const REPLAN_INTERVAL: f32 = 0.5;
 
 impl Game for TopDown {
     fn new(engine: &mut Engine) -> Self {
@@ -52,7 +55,7 @@ impl Game for TopDown {
 
         physics::move_player(self, dir, dt);
         physics::collect_gems(self);
-        physics::update_enemies(&mut self.enemies, &self.tilemap, dt);
+        physics::update_enemies(self, dt);
     }
 
     fn render(&mut self, _engine: &Engine, frame: &mut Frame) {
@@ -1,8 +1,10 @@
 use rengine::tilemap::TileMap;
-use rengine::{aabb_overlap, Rect, Vec2};
+use rengine::{aabb_overlap, NavAgent, Rect, Steering, SteeringAgent, Vec2};
 
-use crate::state::{Enemy, TopDown};
-use crate::{MAP_H, MAP_W, PLAYER_SIZE, PLAYER_SPEED, TILE_SIZE};
+use crate::state::TopDown;
+use crate::{
+    CHASE_RANGE, ENEMY_SPEED, MAP_H, MAP_W, PLAYER_SIZE, PLAYER_SPEED, REPLAN_INTERVAL, TILE_SIZE,
+};
 
 pub fn move_player(game: &mut TopDown, dir: Vec2, dt: f32) {
     game.player.pos.x += dir.x * PLAYER_SPEED * dt;
@@ -32,18 +34,61 @@ pub fn collect_gems(game: &mut TopDown) {
     }
 }
 
-pub fn update_enemies(enemies: &mut [Enemy], tilemap: &TileMap, dt: f32) {
-    for enemy in enemies.iter_mut() {
+pub fn update_enemies(game: &mut TopDown, dt: f32) {
+    let TopDown {
+        player,
+        enemies,
+        tilemap,
+        nav,
+        paths,
+        rng,
+        ..
+    } = game;
+    let half = Vec2::splat(PLAYER_SIZE / 2.0);
+    let target = player.pos + half;
+    let agent = NavAgent::new(PLAYER_SIZE / 2.0);
+    let pack: Vec<SteeringAgent> = enemies
+        .iter()
+        .map(|enemy| {
+            SteeringAgent::new(enemy.pos + half, ENEMY_SPEED)
+                .with_velocity(enemy.vel)
+                .with_radius(PLAYER_SIZE / 2.0)
+        })
+        .collect();
+
+    for (enemy, me) in enemies.iter_mut().zip(&pack) {
+        // Enemies keep out of each other's way whatever they are doing.
+        let steering = Steering::new(me, dt).separation(&pack, PLAYER_SIZE * 1.5, 1.0);
+        let steering = if me.position.distance(target) > CHASE_RANGE {
+            enemy.path.set_points(Vec::new());
+            enemy.replan_in = 0.0;
+            steering
+                .wander(&mut enemy.wander, rng, 1.0)
+                .avoid_tiles(tilemap, 0.75, 3.0)
+        } else {
+            enemy.replan_in -= dt;
+            if enemy.replan_in <= 0.0 {
+                enemy.replan_in = REPLAN_INTERVAL;
+                // Cells are shared between enemies, so the cache answers most
+                // of these without a search.
+                let mut points = match (nav.cell_at(me.position), nav.cell_at(target)) {
+                    (Some(from), Some(to)) => paths
+                        .find_path(nav, from, to, &agent)
+                        .map(|path| path.points(nav).split_off(1))
+                        .unwrap_or_default(),
+                    _ => Vec::new(),
+                };
+                points.push(target);
+                enemy.path.set_points(points);
+            }
+            steering.follow_path(&mut enemy.path, 2.0)
+        };
+
+        enemy.vel = steering.velocity();
         enemy.pos += enemy.vel * dt;
         let r = Rect::from_pos_size(enemy.pos, Vec2::splat(PLAYER_SIZE));
         if let Some(mtv) = collide_stone(tilemap, &r) {
             enemy.pos += mtv;
-            if mtv.x.abs() > 0.001 {
-                enemy.vel.x = -enemy.vel.x;
-            }
-            if mtv.y.abs() > 0.001 {
-                enemy.vel.y = -enemy.vel.y;
-            }
         }
     }
 }
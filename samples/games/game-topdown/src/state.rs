@@ -1,5 +1,5 @@
 use rengine::tilemap::TileMap;
-use rengine::{Scene2D, TextureId, Vec2};
+use rengine::{GridPathCache, NavGrid, PathFollower, Rng, Scene2D, TextureId, Vec2, Wander};
 
 pub struct Player {
     pub pos: Vec2,
@@ -8,8 +8,13 @@ pub struct Player {
 
 pub struct Enemy {
     pub pos: Vec2,
+    /// Steered every frame, wandering while the player is out of sight.
     pub vel: Vec2,
     pub tex: TextureId,
+    pub wander: Wander,
+    /// Waypoints towards the player, while giving chase.
+    pub path: PathFollower,
+    pub replan_in: f32,
 }
 
 pub struct Gem {
@@ -23,6 +28,10 @@ pub struct TopDown {
     pub enemies: Vec<Enemy>,
     pub gems: Vec<Gem>,
     pub tilemap: TileMap,
+    pub nav: NavGrid,
+    pub paths: GridPathCache,
+    /// Drives the enemies' wandering.
+    pub rng: Rng,
     pub score: u32,
     pub scene: Scene2D,
 }